@@ -209,6 +209,30 @@ impl EmailExtractor {
         )))
     }
 
+    /// Extract the `index`th named attachment, counting as
+    /// [`parse_email`](Self::parse_email) lists them.
+    pub fn extract_attachment_at(
+        email_path: &Path,
+        index: usize,
+    ) -> Result<ExtractedAttachment, EmailError> {
+        let raw_email = read_and_parse_email(email_path)?;
+        let message = MessageParser::default()
+            .parse(&raw_email)
+            .ok_or_else(|| EmailError::ParseFailed("Failed to parse email".to_string()))?;
+
+        let attachment = message
+            .attachments()
+            .filter_map(|part| part.attachment_name().map(|name| (name, part)))
+            .nth(index);
+        match attachment {
+            Some((filename, part)) => Self::extract_attachment_to_temp(filename, part),
+            None => Err(EmailError::ExtractFailed(format!(
+                "Attachment {} not found in email",
+                index
+            ))),
+        }
+    }
+
     /// Extract an attachment part to a temporary file.
     fn extract_attachment_to_temp(
         filename: &str,
@@ -81,6 +81,7 @@ pub fn extract_document_text_per_page(
         page.final_text = Some(result.text);
         page.ocr_status = PageOcrStatus::OcrComplete;
         handle.block_on(doc_repo.save_page(&page))?;
+        handle.block_on(doc_repo.stamp_bates_pages(&doc.id))?;
 
         // Cache page count (1 for non-PDFs)
         handle.block_on(doc_repo.set_version_page_count(version.id, 1))?;
@@ -162,6 +163,9 @@ pub fn extract_document_text_per_page(
             doc.id
         );
         handle.block_on(doc_repo.save_pages_batch(&pages))?;
+
+        // Productions carry Bates ranges; stamp each page with its number
+        handle.block_on(doc_repo.stamp_bates_pages(&doc.id))?;
     }
 
     Ok(pages.len())
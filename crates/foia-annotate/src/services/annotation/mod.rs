@@ -1,14 +1,15 @@
 //! Annotation pipeline — trait-based abstraction for document annotation backends.
 //!
-//! Each backend (LLM summarization, date detection, URL extraction) implements
-//! the `Annotator` trait. The `AnnotationManager` provides a single batch loop
-//! that works with any annotator.
+//! Each backend (LLM summarization, date detection, URL extraction, PII
+//! detection) implements the `Annotator` trait. The `AnnotationManager`
+//! provides a single batch loop that works with any annotator.
 
 mod annotator;
 mod date_annotator;
 mod llm_annotator;
 mod manager;
 mod ner_annotator;
+mod pii_annotator;
 pub mod stage;
 mod types;
 mod url_annotator;
@@ -18,6 +19,7 @@ pub use date_annotator::DateAnnotator;
 pub use llm_annotator::LlmAnnotator;
 pub use manager::AnnotationManager;
 pub use ner_annotator::NerAnnotator;
+pub use pii_annotator::PiiAnnotator;
 pub use types::{AnnotationError, AnnotationEvent, AnnotationOutput, BatchAnnotationResult};
 pub use stage::AnnotationStage;
 pub use url_annotator::UrlAnnotator;
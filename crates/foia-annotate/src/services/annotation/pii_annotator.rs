@@ -0,0 +1,128 @@
+//! PII detection annotator — stores detected spans per page and for the
+//! document's extracted text.
+
+use std::collections::{BTreeMap, HashSet};
+
+use async_trait::async_trait;
+
+use crate::services::pii::detect_pii;
+use foia::models::{Document, PII_ANNOTATION_TYPE};
+use foia::repository::models::NewDocumentPiiSpan;
+use foia::repository::DieselDocumentRepository;
+
+use super::annotator::Annotator;
+use super::types::{AnnotationError, AnnotationOutput};
+
+/// Annotator that detects personal data in each page of a document and in
+/// its document-level extracted text, which can be served without pages
+/// (e.g. imported emails) or alongside them.
+///
+/// Spans are written to `document_pii_spans` directly; the recorded
+/// annotation only holds the scanned version ID and per-type counts so the
+/// PII itself never ends up in document metadata.
+pub struct PiiAnnotator;
+
+impl PiiAnnotator {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for PiiAnnotator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Annotator for PiiAnnotator {
+    fn annotation_type(&self) -> &str {
+        PII_ANNOTATION_TYPE
+    }
+
+    fn display_name(&self) -> &str {
+        "PII Detection"
+    }
+
+    /// Version 2 records the scanned document version, which public mode
+    /// requires before publishing text.
+    fn version(&self) -> i32 {
+        2
+    }
+
+    async fn annotate(
+        &self,
+        doc: &Document,
+        doc_repo: &DieselDocumentRepository,
+    ) -> Result<AnnotationOutput, AnnotationError> {
+        let version_id = match doc.current_version() {
+            Some(v) => v.id,
+            None => return Ok(AnnotationOutput::Skipped),
+        };
+
+        let pages = doc_repo
+            .get_pages(&doc.id, version_id as i32)
+            .await
+            .map_err(|e| AnnotationError::Database(e.to_string()))?;
+
+        // Spans index into the same text the content API serves per page.
+        // The document text usually repeats the pages, so only values not
+        // already found there are stored from it, with page 0.
+        let mut detected: Vec<_> = pages
+            .iter()
+            .filter_map(|p| {
+                let text = p
+                    .final_text
+                    .as_deref()
+                    .or(p.ocr_text.as_deref())
+                    .or(p.pdf_text.as_deref())?;
+                Some(((p.id as i32, p.page_number as i32), detect_pii(text)))
+            })
+            .collect();
+        if let Some(text) = doc.extracted_text.as_deref() {
+            let seen: HashSet<&str> = detected
+                .iter()
+                .flat_map(|(_, spans)| spans.iter().map(|s| s.text.as_str()))
+                .collect();
+            let extra: Vec<_> = detect_pii(text)
+                .into_iter()
+                .filter(|s| !seen.contains(s.text.as_str()))
+                .collect();
+            detected.push(((0, 0), extra));
+        }
+
+        if detected.is_empty() {
+            return Ok(AnnotationOutput::Skipped);
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut rows = Vec::new();
+        for ((page_id, page_number), spans) in &detected {
+            for span in spans {
+                *counts.entry(span.pii_type.as_str()).or_insert(0) += 1;
+                rows.push(NewDocumentPiiSpan {
+                    document_id: &doc.id,
+                    page_id: *page_id,
+                    page_number: *page_number,
+                    pii_type: span.pii_type.as_str(),
+                    start_offset: span.start as i32,
+                    end_offset: span.end as i32,
+                    matched_text: &span.text,
+                    created_at: &now,
+                });
+            }
+        }
+
+        // Always replace, so a re-scan that finds nothing clears stale spans
+        doc_repo
+            .replace_pii_spans(&doc.id, &rows)
+            .await
+            .map_err(|e| AnnotationError::Database(e.to_string()))?;
+
+        // Recorded even when nothing is found, so a clean scan is tied to the
+        // version it covered
+        let data = serde_json::json!({ "version_id": version_id, "counts": counts });
+        Ok(AnnotationOutput::Data(data.to_string()))
+    }
+}
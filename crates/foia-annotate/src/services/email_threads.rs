@@ -0,0 +1,509 @@
+//! Email thread reconstruction and correspondent edges.
+//!
+//! Threads are rebuilt from the header metadata recorded at import time.
+//! Message-ID, In-Reply-To and References link messages directly; any two
+//! messages citing the same ID share a thread even when that message was
+//! never released. Replies whose headers were stripped ("Re: budget" with no
+//! In-Reply-To) fall back to matching the normalized subject against earlier
+//! messages.
+//!
+//! Threading is a whole-corpus operation, so it runs as a rebuild rather than
+//! as a per-document annotator.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use foia::repository::models::{EmailMessageRecord, NewEmailCorrespondence};
+use foia::repository::{DieselDocumentRepository, DieselError};
+
+/// Reply/forward markers and list tags at the start of a subject.
+static SUBJECT_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*(?:(?:re|fw|fwd|aw|sv|wg)(?:\[\d+\])?\s*:|\[[^\]]*\])\s*")
+        .expect("subject prefix pattern should compile")
+});
+
+static REPLY_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*(?:\[[^\]]*\]\s*)*(?:re|fw|fwd|aw|sv|wg)(?:\[\d+\])?\s*:")
+        .expect("reply prefix pattern should compile")
+});
+
+/// Header fields needed for threading, read from document metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmailHeaders {
+    pub document_id: String,
+    pub message_id: Option<String>,
+    pub in_reply_to: Vec<String>,
+    pub references: Vec<String>,
+    pub subject: Option<String>,
+    pub from: Vec<String>,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub date: Option<DateTime<Utc>>,
+}
+
+/// Read a metadata field that may be a string or an array of strings.
+fn string_list(value: Option<&serde_json::Value>) -> Vec<String> {
+    match value {
+        Some(serde_json::Value::String(s)) if !s.is_empty() => vec![s.clone()],
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl EmailHeaders {
+    /// Read headers from a document's metadata.
+    ///
+    /// Returns `None` for documents without email headers.
+    pub fn from_metadata(document_id: &str, metadata: &serde_json::Value) -> Option<Self> {
+        let message_id = metadata
+            .get("message_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim_matches(|c| c == '<' || c == '>').to_string())
+            .filter(|s| !s.is_empty());
+        let from = string_list(metadata.get("from"));
+        if message_id.is_none() && from.is_empty() {
+            return None;
+        }
+
+        let strip = |ids: Vec<String>| -> Vec<String> {
+            ids.into_iter()
+                .map(|s| s.trim_matches(|c| c == '<' || c == '>').to_string())
+                .collect()
+        };
+
+        Some(Self {
+            document_id: document_id.to_string(),
+            message_id,
+            in_reply_to: strip(string_list(metadata.get("in_reply_to"))),
+            references: strip(string_list(metadata.get("references"))),
+            subject: metadata
+                .get("subject")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            from,
+            to: string_list(metadata.get("to")),
+            cc: string_list(metadata.get("cc")),
+            date: metadata
+                .get("date")
+                .and_then(|v| v.as_str())
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|d| d.with_timezone(&Utc)),
+        })
+    }
+
+    /// The sending address, lowercased.
+    pub fn sender(&self) -> Option<String> {
+        self.from.first().map(|s| s.to_lowercase())
+    }
+
+    fn has_header_links(&self) -> bool {
+        !self.in_reply_to.is_empty() || !self.references.is_empty()
+    }
+}
+
+/// Strip reply/forward prefixes and list tags, then lowercase and collapse
+/// whitespace, so "RE: Fwd: [foia] Budget  memo" matches "Budget memo".
+pub fn normalize_subject(subject: &str) -> String {
+    let mut s = subject;
+    while let Some(m) = SUBJECT_PREFIX.find(s) {
+        if m.end() == 0 {
+            break;
+        }
+        s = &s[m.end()..];
+    }
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Whether a subject marks the message as a reply or forward.
+pub fn is_reply_subject(subject: &str) -> bool {
+    REPLY_PREFIX.is_match(subject)
+}
+
+/// A message's position within its reconstructed thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadedMessage {
+    pub document_id: String,
+    /// Document ID of the thread's first message.
+    pub thread_id: String,
+    pub parent_document_id: Option<String>,
+    /// Distance from the thread root.
+    pub depth: usize,
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new() -> Self {
+        Self { parent: Vec::new() }
+    }
+
+    fn add(&mut self) -> usize {
+        self.parent.push(self.parent.len());
+        self.parent.len() - 1
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a.max(b)] = a.min(b);
+        }
+    }
+}
+
+/// Sort key placing undated messages last, then ordering by document ID.
+fn chronological(m: &EmailHeaders) -> (bool, Option<DateTime<Utc>>, &str) {
+    (m.date.is_none(), m.date, &m.document_id)
+}
+
+/// Group messages into threads.
+pub fn build_threads(messages: &[EmailHeaders]) -> Vec<ThreadedMessage> {
+    let n = messages.len();
+    let mut uf = UnionFind::new();
+    for _ in 0..n {
+        uf.add();
+    }
+
+    // Message-IDs map to their message; cited IDs we don't hold become
+    // placeholder nodes so their replies still group together.
+    let mut by_id: HashMap<&str, usize> = HashMap::new();
+    for (i, m) in messages.iter().enumerate() {
+        if let Some(id) = m.message_id.as_deref() {
+            match by_id.get(id) {
+                Some(&j) => uf.union(i, j),
+                None => {
+                    by_id.insert(id, i);
+                }
+            }
+        }
+    }
+    for (i, m) in messages.iter().enumerate() {
+        for id in m.references.iter().chain(&m.in_reply_to) {
+            let node = match by_id.get(id.as_str()) {
+                Some(&j) => j,
+                None => {
+                    let node = uf.add();
+                    by_id.insert(id, node);
+                    node
+                }
+            };
+            uf.union(i, node);
+        }
+    }
+
+    // Direct parent: In-Reply-To, else the nearest cited ancestor we hold
+    let mut parent: Vec<Option<usize>> = messages
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            m.in_reply_to
+                .iter()
+                .rev()
+                .chain(m.references.iter().rev())
+                .filter_map(|id| by_id.get(id.as_str()).copied())
+                .find(|&j| j < n && j != i)
+        })
+        .collect();
+
+    // Subject fallback for replies that lost their headers
+    let mut by_subject: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, m) in messages.iter().enumerate() {
+        if let Some(subject) = m.subject.as_deref() {
+            let key = normalize_subject(subject);
+            if !key.is_empty() {
+                by_subject.entry(key).or_default().push(i);
+            }
+        }
+    }
+    for group in by_subject.values_mut() {
+        group.sort_by(|&a, &b| chronological(&messages[a]).cmp(&chronological(&messages[b])));
+        for (pos, &i) in group.iter().enumerate() {
+            let m = &messages[i];
+            let orphaned_reply = parent[i].is_none()
+                && !m.has_header_links()
+                && m.subject.as_deref().is_some_and(is_reply_subject);
+            if orphaned_reply && pos > 0 {
+                parent[i] = Some(group[pos - 1]);
+                uf.union(i, group[pos - 1]);
+            }
+        }
+    }
+
+    // Thread root: the earliest parentless message in each component
+    let mut roots: HashMap<usize, usize> = HashMap::new();
+    for i in 0..n {
+        let component = uf.find(i);
+        let candidate = roots.entry(component).or_insert(i);
+        let current = *candidate;
+        let better = match (parent[i].is_none(), parent[current].is_none()) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => chronological(&messages[i]) < chronological(&messages[current]),
+        };
+        if better {
+            *candidate = i;
+        }
+    }
+
+    (0..n)
+        .map(|i| {
+            // Walk up to the root, bounded in case headers form a cycle
+            let mut depth = 0;
+            let mut cursor = i;
+            while let Some(p) = parent[cursor] {
+                depth += 1;
+                cursor = p;
+                if depth >= n {
+                    break;
+                }
+            }
+            let root = roots[&uf.find(i)];
+            ThreadedMessage {
+                document_id: messages[i].document_id.clone(),
+                thread_id: messages[root].document_id.clone(),
+                parent_document_id: parent[i].map(|p| messages[p].document_id.clone()),
+                depth,
+            }
+        })
+        .collect()
+}
+
+/// Sender -> recipient edges for one message, without self-edges.
+pub fn correspondence_edges(message: &EmailHeaders) -> Vec<NewEmailCorrespondence> {
+    let Some(sender) = message.sender() else {
+        return Vec::new();
+    };
+    let sent_at = message.date.map(|d| d.to_rfc3339());
+
+    let mut seen: Vec<String> = vec![sender.clone()];
+    let mut edges = Vec::new();
+    for (kind, addrs) in [("to", &message.to), ("cc", &message.cc)] {
+        for addr in addrs {
+            let recipient = addr.to_lowercase();
+            if seen.contains(&recipient) {
+                continue;
+            }
+            seen.push(recipient.clone());
+            edges.push(NewEmailCorrespondence {
+                document_id: message.document_id.clone(),
+                sender: sender.clone(),
+                recipient,
+                kind: kind.to_string(),
+                sent_at: sent_at.clone(),
+            });
+        }
+    }
+    edges
+}
+
+/// Counts from a thread rebuild.
+#[derive(Debug, Clone, Default)]
+pub struct ThreadRebuildStats {
+    pub messages: usize,
+    pub threads: usize,
+    pub edges: usize,
+}
+
+/// Rebuild threads and correspondent edges for all emails, or one source's.
+pub async fn rebuild_email_threads(
+    doc_repo: &DieselDocumentRepository,
+    source_id: Option<&str>,
+) -> Result<ThreadRebuildStats, DieselError> {
+    let rows = doc_repo.get_email_metadata(source_id).await?;
+    let messages: Vec<EmailHeaders> = rows
+        .iter()
+        .filter_map(|(id, metadata)| {
+            let value: serde_json::Value = serde_json::from_str(metadata).ok()?;
+            EmailHeaders::from_metadata(id, &value)
+        })
+        .collect();
+
+    let threaded = build_threads(&messages);
+    let now = Utc::now().to_rfc3339();
+
+    let records: Vec<EmailMessageRecord> = messages
+        .iter()
+        .zip(&threaded)
+        .map(|(m, t)| EmailMessageRecord {
+            document_id: t.document_id.clone(),
+            thread_id: t.thread_id.clone(),
+            parent_document_id: t.parent_document_id.clone(),
+            message_id: m.message_id.clone(),
+            subject: m.subject.clone(),
+            sender: m.sender(),
+            sent_at: m.date.map(|d| d.to_rfc3339()),
+            depth: t.depth as i32,
+            updated_at: now.clone(),
+        })
+        .collect();
+    let edges: Vec<NewEmailCorrespondence> =
+        messages.iter().flat_map(correspondence_edges).collect();
+
+    let mut thread_ids: Vec<&str> = threaded.iter().map(|t| t.thread_id.as_str()).collect();
+    thread_ids.sort_unstable();
+    thread_ids.dedup();
+
+    let stats = ThreadRebuildStats {
+        messages: records.len(),
+        threads: thread_ids.len(),
+        edges: edges.len(),
+    };
+
+    doc_repo
+        .replace_email_threads(source_id, &records, &edges)
+        .await?;
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(id: &str, message_id: Option<&str>, subject: &str, day: u32) -> EmailHeaders {
+        EmailHeaders {
+            document_id: id.to_string(),
+            message_id: message_id.map(|s| s.to_string()),
+            subject: Some(subject.to_string()),
+            from: vec![format!("{}@example.gov", id)],
+            date: Some(
+                DateTime::parse_from_rfc3339(&format!("2024-01-{:02}T12:00:00Z", day))
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn thread_of<'a>(threads: &'a [ThreadedMessage], id: &str) -> &'a ThreadedMessage {
+        threads.iter().find(|t| t.document_id == id).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_subject() {
+        assert_eq!(
+            normalize_subject("RE: Fwd: [foia] Budget  memo"),
+            "budget memo"
+        );
+        assert_eq!(normalize_subject("Re[2]: budget memo"), "budget memo");
+        assert_eq!(normalize_subject("Budget memo"), "budget memo");
+        assert!(is_reply_subject("[foia] RE: Budget"));
+        assert!(!is_reply_subject("Regarding the budget"));
+    }
+
+    #[test]
+    fn test_threads_from_headers() {
+        let root = msg("a", Some("1@x"), "Budget", 1);
+        let mut reply = msg("b", Some("2@x"), "Re: Budget", 2);
+        reply.in_reply_to = vec!["1@x".to_string()];
+        reply.references = vec!["1@x".to_string()];
+        let mut nested = msg("c", Some("3@x"), "Re: Budget", 3);
+        nested.in_reply_to = vec!["2@x".to_string()];
+        nested.references = vec!["1@x".to_string(), "2@x".to_string()];
+        let other = msg("d", Some("4@x"), "Travel", 1);
+
+        let threads = build_threads(&[nested, reply, root, other]);
+        assert_eq!(thread_of(&threads, "c").thread_id, "a");
+        assert_eq!(
+            thread_of(&threads, "c").parent_document_id.as_deref(),
+            Some("b")
+        );
+        assert_eq!(thread_of(&threads, "c").depth, 2);
+        assert_eq!(thread_of(&threads, "a").depth, 0);
+        assert_eq!(thread_of(&threads, "d").thread_id, "d");
+    }
+
+    #[test]
+    fn test_missing_root_groups_siblings() {
+        let mut first = msg("a", Some("2@x"), "Re: Budget", 2);
+        first.in_reply_to = vec!["1@x".to_string()];
+        let mut second = msg("b", Some("3@x"), "Re: Budget", 3);
+        second.in_reply_to = vec!["1@x".to_string()];
+
+        let threads = build_threads(&[second, first]);
+        assert_eq!(thread_of(&threads, "a").thread_id, "a");
+        assert_eq!(thread_of(&threads, "b").thread_id, "a");
+        assert_eq!(thread_of(&threads, "b").parent_document_id, None);
+    }
+
+    #[test]
+    fn test_subject_fallback() {
+        let root = msg("a", None, "Budget memo", 1);
+        let reply = msg("b", None, "RE: budget memo", 2);
+        let unrelated_reply = msg("c", None, "Re: Travel", 2);
+
+        let threads = build_threads(&[reply, root, unrelated_reply]);
+        assert_eq!(thread_of(&threads, "b").thread_id, "a");
+        assert_eq!(
+            thread_of(&threads, "b").parent_document_id.as_deref(),
+            Some("a")
+        );
+        assert_eq!(thread_of(&threads, "c").thread_id, "c");
+    }
+
+    #[test]
+    fn test_correspondence_edges() {
+        let mut m = msg("a", None, "Budget", 1);
+        m.from = vec!["Alice@Example.gov".to_string()];
+        m.to = vec![
+            "bob@example.gov".to_string(),
+            "alice@example.gov".to_string(),
+        ];
+        m.cc = vec![
+            "BOB@example.gov".to_string(),
+            "carol@example.gov".to_string(),
+        ];
+
+        let edges: Vec<(String, String)> = correspondence_edges(&m)
+            .into_iter()
+            .map(|e| (e.recipient, e.kind))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("bob@example.gov".to_string(), "to".to_string()),
+                ("carol@example.gov".to_string(), "cc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_headers_from_metadata() {
+        let metadata = serde_json::json!({
+            "import_source": "mailbox",
+            "message_id": "<2@x>",
+            "in_reply_to": ["1@x"],
+            "references": "1@x",
+            "subject": "Re: Budget",
+            "from": ["alice@example.gov"],
+            "to": ["bob@example.gov"],
+            "date": "2024-01-02T12:00:00+00:00",
+        });
+        let headers = EmailHeaders::from_metadata("doc", &metadata).unwrap();
+        assert_eq!(headers.message_id.as_deref(), Some("2@x"));
+        assert_eq!(headers.references, vec!["1@x"]);
+        assert!(headers.date.is_some());
+
+        assert!(EmailHeaders::from_metadata("doc", &serde_json::json!({"title": "x"})).is_none());
+    }
+}
@@ -1,13 +1,19 @@
 pub mod annotation;
 pub mod date_detection;
+pub mod email_threads;
 pub mod ner;
+pub mod pii;
 
 #[allow(unused_imports)]
 pub use annotation::{
     AnnotationError, AnnotationEvent, AnnotationManager, AnnotationOutput, Annotator,
-    BatchAnnotationResult, DateAnnotator, LlmAnnotator, NerAnnotator, UrlAnnotator,
+    BatchAnnotationResult, DateAnnotator, LlmAnnotator, NerAnnotator, PiiAnnotator, UrlAnnotator,
 };
 #[allow(unused_imports)]
 pub use date_detection::{detect_date, DateConfidence, DateEstimate, DateSource};
 #[allow(unused_imports)]
+pub use email_threads::{build_threads, rebuild_email_threads, EmailHeaders, ThreadRebuildStats};
+#[allow(unused_imports)]
 pub use ner::{NerBackend, NerResult, RegexNerBackend};
+#[allow(unused_imports)]
+pub use pii::detect_pii;
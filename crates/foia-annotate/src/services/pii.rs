@@ -0,0 +1,287 @@
+//! PII detection for publishing documents without re-leaking personal data.
+//!
+//! Agencies routinely miss redactions. Before a document is published on a
+//! public mirror, its text is scanned for Social Security numbers, phone
+//! numbers, email addresses, dates of birth, alien registration numbers and
+//! street addresses. Detection favours recall over precision: a masked false
+//! positive costs a little readability, a missed SSN cannot be taken back.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use foia::models::{PiiSpan, PiiType};
+
+// ============================================================================
+// Patterns
+// ============================================================================
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b")
+        .expect("email pattern should compile")
+});
+
+/// Formatted SSN: 123-45-6789 or 123 45 6789.
+static SSN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(\d{3})([- ])(\d{2})([- ])(\d{4})\b").expect("ssn pattern should compile")
+});
+
+/// Unformatted SSN introduced by a label: "SSN: 123456789".
+static SSN_LABELED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:SSN|SSAN|social\s+security\s+(?:no\.?|number|#))\s*[:#]?\s*(\d{9})\b")
+        .expect("labeled ssn pattern should compile")
+});
+
+/// Alien registration number: A123456789, A-123-456-789, A# 12345678.
+static A_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bA(?:#|-)?\s?(?:\d{3}[- ]?\d{3}[- ]?\d{3}|\d{8})\b")
+        .expect("a-number pattern should compile")
+});
+
+/// Alien registration number introduced by a label, without the A prefix.
+static A_NUMBER_LABELED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:alien\s+(?:registration\s+)?(?:no\.?|number|#)|A-number)\s*[:#]?\s*(\d{8,9})\b",
+    )
+    .expect("labeled a-number pattern should compile")
+});
+
+/// A date following a date-of-birth label. Only the date is captured.
+static DATE_OF_BIRTH: LazyLock<Regex> = LazyLock::new(|| {
+    let month = r"(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Sept|Oct|Nov|Dec)[a-z]*\.?";
+    Regex::new(&format!(
+        r"(?i)\b(?:DOB|D\.O\.B\.?|date\s+of\s+birth|birth\s*date|born(?:\s+on)?)\s*[:\-]?\s*(\d{{1,2}}[/.-]\d{{1,2}}[/.-]\d{{2,4}}|\d{{4}}-\d{{2}}-\d{{2}}|{month}\s+\d{{1,2}},?\s+\d{{4}}|\d{{1,2}}\s+{month}\s+\d{{4}})"
+    ))
+    .expect("date of birth pattern should compile")
+});
+
+/// North American phone number: (202) 555-0143, 202-555-0143, +1 202.555.0143.
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\+?1[-.\s]?)?(?:\(\d{3}\)\s?|\b\d{3}[-.\s])\d{3}[-.\s]\d{4}\b")
+        .expect("phone pattern should compile")
+});
+
+/// Street address: house number, capitalized street name and a street suffix,
+/// with an optional unit.
+static STREET_ADDRESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b\d{1,6}\s+(?:[NSEW]\.?\s+)?(?:[A-Z][A-Za-z'-]+\.?\s+){1,3}(?:Street|St|Avenue|Ave|Road|Rd|Boulevard|Blvd|Drive|Dr|Lane|Ln|Court|Ct|Place|Pl|Way|Terrace|Ter|Circle|Cir|Parkway|Pkwy|Highway|Hwy)\b\.?(?:,?\s+(?:Apt|Apartment|Suite|Ste|Unit|#)\.?\s*[A-Za-z0-9-]+)?",
+    )
+    .expect("street address pattern should compile")
+});
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Detect PII in text.
+///
+/// Returns non-overlapping spans ordered by position. Where patterns overlap,
+/// the earlier detector in priority order wins (emails before phone numbers,
+/// SSNs before street addresses, and so on).
+pub fn detect_pii(text: &str) -> Vec<PiiSpan> {
+    let mut candidates = Vec::new();
+
+    push_matches(&EMAIL, None, PiiType::Email, text, &mut candidates);
+    for cap in SSN.captures_iter(text) {
+        // Separators must agree and the number must be assignable
+        if cap[2] == cap[4] && is_valid_ssn(&cap[1], &cap[3], &cap[5]) {
+            let m = cap.get(0).expect("group 0 always present");
+            candidates.push(span(PiiType::Ssn, m.start(), m.end(), text));
+        }
+    }
+    for cap in SSN_LABELED.captures_iter(text) {
+        let m = cap.get(1).expect("ssn digits are captured");
+        let d = m.as_str();
+        if is_valid_ssn(&d[0..3], &d[3..5], &d[5..9]) {
+            candidates.push(span(PiiType::Ssn, m.start(), m.end(), text));
+        }
+    }
+    push_matches(&A_NUMBER, None, PiiType::ANumber, text, &mut candidates);
+    push_matches(
+        &A_NUMBER_LABELED,
+        Some(1),
+        PiiType::ANumber,
+        text,
+        &mut candidates,
+    );
+    push_matches(
+        &DATE_OF_BIRTH,
+        Some(1),
+        PiiType::DateOfBirth,
+        text,
+        &mut candidates,
+    );
+    push_matches(&PHONE, None, PiiType::Phone, text, &mut candidates);
+    push_matches(
+        &STREET_ADDRESS,
+        None,
+        PiiType::StreetAddress,
+        text,
+        &mut candidates,
+    );
+
+    // Candidates are in priority order; keep each unless it overlaps a kept span
+    let mut kept: Vec<PiiSpan> = Vec::new();
+    for candidate in candidates {
+        if !kept
+            .iter()
+            .any(|k| candidate.start < k.end && k.start < candidate.end)
+        {
+            kept.push(candidate);
+        }
+    }
+    kept.sort_by_key(|s| s.start);
+    kept
+}
+
+fn push_matches(
+    pattern: &Regex,
+    group: Option<usize>,
+    pii_type: PiiType,
+    text: &str,
+    out: &mut Vec<PiiSpan>,
+) {
+    match group {
+        None => {
+            for m in pattern.find_iter(text) {
+                out.push(span(pii_type, m.start(), m.end(), text));
+            }
+        }
+        Some(g) => {
+            for cap in pattern.captures_iter(text) {
+                if let Some(m) = cap.get(g) {
+                    out.push(span(pii_type, m.start(), m.end(), text));
+                }
+            }
+        }
+    }
+}
+
+fn span(pii_type: PiiType, start: usize, end: usize, text: &str) -> PiiSpan {
+    PiiSpan {
+        pii_type,
+        start,
+        end,
+        text: text[start..end].to_string(),
+    }
+}
+
+/// SSA never assigns area 000, 666 or 900-999, group 00 or serial 0000.
+fn is_valid_ssn(area: &str, group: &str, serial: &str) -> bool {
+    let area: u32 = area.parse().unwrap_or(0);
+    area != 0 && area != 666 && area < 900 && group != "00" && serial != "0000"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(text: &str) -> Vec<(PiiType, String)> {
+        detect_pii(text)
+            .into_iter()
+            .map(|s| (s.pii_type, s.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_ssn() {
+        let found = detected("Subject SSN 123-45-6789 and SSN: 234567890.");
+        assert_eq!(
+            found,
+            vec![
+                (PiiType::Ssn, "123-45-6789".to_string()),
+                (PiiType::Ssn, "234567890".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_ssn() {
+        assert!(detected("Ref 000-12-3456, 666-12-3456, 912-34-5678, 123-00-4567").is_empty());
+        // Mixed separators are not an SSN
+        assert!(detected("Code 123-45 6789").is_empty());
+    }
+
+    #[test]
+    fn test_detect_phone_formats() {
+        let found = detected("Call (202) 555-0143, 202.555.0199 or +1 202-555-0100.");
+        let phones: Vec<&str> = found
+            .iter()
+            .filter(|(t, _)| *t == PiiType::Phone)
+            .map(|(_, s)| s.as_str())
+            .collect();
+        assert_eq!(
+            phones,
+            vec!["(202) 555-0143", "202.555.0199", "+1 202-555-0100"]
+        );
+    }
+
+    #[test]
+    fn test_detect_email() {
+        let found = detected("Contact j.doe+foia@mail.example.org for details.");
+        assert_eq!(
+            found,
+            vec![(PiiType::Email, "j.doe+foia@mail.example.org".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_detect_date_of_birth_only_with_label() {
+        let found = detected("DOB: 04/12/1961. Memo dated 05/01/1999. Born on March 3, 1950.");
+        assert_eq!(
+            found,
+            vec![
+                (PiiType::DateOfBirth, "04/12/1961".to_string()),
+                (PiiType::DateOfBirth, "March 3, 1950".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_a_number() {
+        let found = detected("File A123456789, A-012-345-678 and Alien Number: 87654321.");
+        assert_eq!(
+            found,
+            vec![
+                (PiiType::ANumber, "A123456789".to_string()),
+                (PiiType::ANumber, "A-012-345-678".to_string()),
+                (PiiType::ANumber, "87654321".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_street_address() {
+        let found = detected("She lives at 742 Evergreen Terrace, Apt 3B near 12 N. Main St.");
+        assert_eq!(
+            found,
+            vec![
+                (
+                    PiiType::StreetAddress,
+                    "742 Evergreen Terrace, Apt 3B".to_string()
+                ),
+                (PiiType::StreetAddress, "12 N. Main St.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans_do_not_overlap() {
+        let text = "SSN 123-45-6789 phone 202-555-0143";
+        let spans = detect_pii(text);
+        assert_eq!(spans.len(), 2);
+        for s in &spans {
+            assert_eq!(&text[s.start..s.end], s.text);
+        }
+        assert!(spans[0].end <= spans[1].start);
+    }
+
+    #[test]
+    fn test_no_pii_in_plain_text() {
+        assert!(
+            detected("The Central Intelligence Agency met in 1962 regarding Project 45.")
+                .is_empty()
+        );
+    }
+}
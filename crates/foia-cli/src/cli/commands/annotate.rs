@@ -10,7 +10,9 @@ use foia::config::{Config, Settings};
 use foia::work_queue::ExecutionStrategy;
 use foia_annotate::services::annotation::{
     AnnotationEvent, AnnotationManager, Annotator, DateAnnotator, LlmAnnotator, NerAnnotator,
+    PiiAnnotator,
 };
+use foia_annotate::services::email_threads::rebuild_email_threads;
 
 use super::daemon::{ConfigWatcher, DaemonAction, ReloadMode};
 use super::helpers::truncate;
@@ -375,6 +377,70 @@ pub async fn cmd_extract_entities(
     Ok(())
 }
 
+/// Detect PII in documents and store spans for public-mode masking.
+pub async fn cmd_detect_pii(
+    settings: &Settings,
+    source_id: Option<&str>,
+    limit: usize,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let doc_repo = repos.documents;
+
+    let annotator = PiiAnnotator::new();
+    let manager = AnnotationManager::new(doc_repo.clone());
+
+    let stale = doc_repo.reset_stale_pii_scans(source_id).await?;
+    if stale > 0 {
+        println!(
+            "{} {} document(s) have a new version since their last scan",
+            style("→").cyan(),
+            stale
+        );
+    }
+
+    let total_count = manager.count_needing(&annotator, source_id).await?;
+
+    if total_count == 0 {
+        println!("{} No documents need PII detection", style("!").yellow());
+        println!("  Documents need OCR complete status with extracted text");
+        return Ok(());
+    }
+
+    let effective_limit = if limit > 0 {
+        limit
+    } else {
+        total_count as usize
+    };
+
+    println!(
+        "{} Scanning up to {} documents for PII",
+        style("→").cyan(),
+        effective_limit
+    );
+
+    let (event_tx, event_rx) = mpsc::channel::<AnnotationEvent>(100);
+    let event_handler = spawn_progress_handler(event_rx, "PII detection");
+
+    let annotator_arc: Arc<dyn Annotator> = Arc::new(annotator);
+    manager
+        .run_batch(annotator_arc, source_id, limit, None, ExecutionStrategy::Wide, event_tx)
+        .await?;
+
+    if let Err(e) = event_handler.await {
+        tracing::warn!("Event handler task failed: {}", e);
+    }
+
+    let counts = doc_repo.count_pii_by_type().await?;
+    if !counts.is_empty() {
+        println!("\n{} Detected PII spans:", style("→").cyan());
+        for (pii_type, count) in counts {
+            println!("  {:<16} {}", pii_type, count);
+        }
+    }
+
+    Ok(())
+}
+
 /// Reset annotations for documents, allowing them to be re-annotated.
 pub async fn cmd_annotate_reset(
     settings: &Settings,
@@ -429,3 +495,36 @@ pub async fn cmd_annotate_reset(
 
     Ok(())
 }
+
+/// Rebuild email threads and the correspondent graph from imported headers.
+pub async fn cmd_annotate_threads(
+    settings: &Settings,
+    source_id: Option<&str>,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let doc_repo = repos.documents;
+
+    let scope = source_id.unwrap_or("all sources");
+    println!(
+        "{} Rebuilding email threads for {}",
+        style("→").cyan(),
+        scope
+    );
+
+    let stats = rebuild_email_threads(&doc_repo, source_id).await?;
+
+    if stats.messages == 0 {
+        println!("{} No email documents found", style("!").yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{} Threaded {} messages into {} conversations ({} correspondent edges)",
+        style("✓").green(),
+        stats.messages,
+        stats.threads,
+        stats.edges
+    );
+
+    Ok(())
+}
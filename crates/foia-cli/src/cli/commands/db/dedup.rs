@@ -10,8 +10,11 @@ use diesel::{ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 
 use foia::config::Settings;
+use foia::repository::models::NewFoiaRequestDocument;
 use foia::schema::{
-    document_analysis_results, document_pages, document_versions, documents, virtual_files,
+    document_analysis_results, document_pages, document_pii_spans, document_versions, documents,
+    email_correspondence, email_messages, foia_request_documents, foia_request_events,
+    virtual_files,
 };
 
 /// Strategy for choosing which document to keep during deduplication.
@@ -131,6 +134,7 @@ pub async fn cmd_db_dedup(
 
     let mut total_deleted = 0u64;
     let mut total_refs_updated = 0u64;
+    let mut skipped_held = 0u64;
 
     // Inline schema for document_annotations (not in main schema.rs)
     diesel::table! {
@@ -162,14 +166,18 @@ pub async fn cmd_db_dedup(
                 synopsis: Option<String>,
                 #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
                 tags: Option<String>,
+                #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+                held_id: Option<String>,
             }
 
             let docs: Vec<DocInfo> = foia::with_conn!(pool, conn, {
                 diesel::sql_query(
                     r#"
-                    SELECT d.id, d.source_id, d.created_at, d.extracted_text, d.synopsis, d.tags
+                    SELECT d.id, d.source_id, d.created_at, d.extracted_text, d.synopsis, d.tags,
+                           h.document_id AS held_id
                     FROM documents d
                     JOIN document_versions dv ON dv.document_id = d.id
+                    LEFT JOIN document_legal_holds h ON h.document_id = d.id
                     WHERE dv.content_hash = $1
                     ORDER BY d.created_at ASC
                     "#,
@@ -184,6 +192,13 @@ pub async fn cmd_db_dedup(
                 continue;
             }
 
+            // Documents under legal hold must not be merged away
+            if docs.iter().any(|d| d.held_id.is_some()) {
+                skipped_held += 1;
+                pb.inc(1);
+                continue;
+            }
+
             // Choose keeper based on strategy
             let keeper_idx = match strategy {
                 KeepStrategy::Oldest => 0,
@@ -253,11 +268,101 @@ pub async fn cmd_db_dedup(
                     .await
                 })?;
                 total_refs_updated += updated as u64;
+
+                // Update FOIA request correspondence
+                let updated: usize = foia::with_conn!(pool, conn, {
+                    diesel::update(
+                        foia_request_events::table
+                            .filter(foia_request_events::document_id.eq_any(dup_ids)),
+                    )
+                    .set(foia_request_events::document_id.eq(keeper_id))
+                    .execute(&mut conn)
+                    .await
+                })?;
+                total_refs_updated += updated as u64;
+
+                // Link the keeper to requests the duplicates were linked to;
+                // the duplicates' own links are deleted below
+                let dup_links: Vec<(String, String)> = foia::with_conn!(pool, conn, {
+                    foia_request_documents::table
+                        .filter(foia_request_documents::document_id.eq_any(dup_ids))
+                        .select((
+                            foia_request_documents::request_id,
+                            foia_request_documents::linked_at,
+                        ))
+                        .load(&mut conn)
+                        .await
+                })?;
+                if !dup_links.is_empty() {
+                    let mut linked: Vec<String> = foia::with_conn!(pool, conn, {
+                        foia_request_documents::table
+                            .filter(foia_request_documents::document_id.eq(keeper_id))
+                            .select(foia_request_documents::request_id)
+                            .load(&mut conn)
+                            .await
+                    })?;
+                    for (request_id, linked_at) in &dup_links {
+                        if linked.contains(request_id) {
+                            continue;
+                        }
+                        let link = NewFoiaRequestDocument {
+                            request_id,
+                            document_id: keeper_id,
+                            linked_at,
+                        };
+                        foia::with_conn!(pool, conn, {
+                            diesel::insert_into(foia_request_documents::table)
+                                .values(&link)
+                                .execute(&mut conn)
+                                .await
+                        })?;
+                        linked.push(request_id.clone());
+                        total_refs_updated += 1;
+                    }
+                }
             }
 
             // Batch delete in order respecting foreign keys
 
-            // 1. document_pages
+            // 1. document_pii_spans (the keeper has its own for the same content)
+            foia::with_conn!(pool, conn, {
+                diesel::delete(
+                    document_pii_spans::table
+                        .filter(document_pii_spans::document_id.eq_any(&batch_deletes)),
+                )
+                .execute(&mut conn)
+                .await
+            })?;
+
+            // 2. email thread rows (rebuilt by `annotate threads`)
+            foia::with_conn!(pool, conn, {
+                diesel::delete(
+                    email_messages::table
+                        .filter(email_messages::document_id.eq_any(&batch_deletes)),
+                )
+                .execute(&mut conn)
+                .await
+            })?;
+            foia::with_conn!(pool, conn, {
+                diesel::delete(
+                    email_correspondence::table
+                        .filter(email_correspondence::document_id.eq_any(&batch_deletes)),
+                )
+                .execute(&mut conn)
+                .await
+            })?;
+
+            // 3. FOIA request links (re-pointed to the keeper above)
+            foia::with_conn!(pool, conn, {
+                diesel::delete(
+                    foia_request_documents::table
+                        .filter(foia_request_documents::document_id.eq_any(&batch_deletes)),
+                )
+                .execute(&mut conn)
+                .await
+            })?;
+
+            // 4. document_pages
             foia::with_conn!(pool, conn, {
                 diesel::delete(
                     document_pages::table
@@ -267,7 +372,7 @@ pub async fn cmd_db_dedup(
                 .await
             })?;
 
-            // 2. virtual_files
+            // 5. virtual_files
             foia::with_conn!(pool, conn, {
                 diesel::delete(
                     virtual_files::table.filter(virtual_files::document_id.eq_any(&batch_deletes)),
@@ -276,7 +381,7 @@ pub async fn cmd_db_dedup(
                 .await
             })?;
 
-            // 3. document_versions
+            // 6. document_versions
             foia::with_conn!(pool, conn, {
                 diesel::delete(
                     document_versions::table
@@ -286,7 +391,7 @@ pub async fn cmd_db_dedup(
                 .await
             })?;
 
-            // 4. document_analysis_results (any remaining)
+            // 7. document_analysis_results (any remaining)
             foia::with_conn!(pool, conn, {
                 diesel::delete(
                     document_analysis_results::table
@@ -296,7 +401,7 @@ pub async fn cmd_db_dedup(
                 .await
             })?;
 
-            // 5. document_annotations (any remaining)
+            // 8. document_annotations (any remaining)
             foia::with_conn!(pool, conn, {
                 diesel::delete(
                     document_annotations::table
@@ -306,7 +411,7 @@ pub async fn cmd_db_dedup(
                 .await
             })?;
 
-            // 6. documents
+            // 9. documents
             foia::with_conn!(pool, conn, {
                 diesel::delete(documents::table.filter(documents::id.eq_any(&batch_deletes)))
                     .execute(&mut conn)
@@ -320,6 +425,14 @@ pub async fn cmd_db_dedup(
 
     pb.finish_with_message(format!("deleted: {}", total_deleted));
 
+    if skipped_held > 0 {
+        println!(
+            "\n{} Skipped {} duplicate groups containing documents under legal hold.",
+            style("!").yellow(),
+            skipped_held
+        );
+    }
+
     if dry_run {
         println!(
             "\n{} Dry run complete. Would delete {} documents ({} references would be updated).",
@@ -43,8 +43,6 @@ pub(super) async fn add_discovered_urls(
     urls: Vec<DiscoveredUrl>,
     dry_run: bool,
 ) -> anyhow::Result<usize> {
-    use foia::models::CrawlUrl;
-
     if dry_run {
         println!(
             "\n{} Dry run - would add {} URLs:",
@@ -84,13 +82,7 @@ pub(super) async fn add_discovered_urls(
 
     let mut added = 0;
     for discovered in urls {
-        let crawl_url = CrawlUrl::new(
-            discovered.url.clone(),
-            source_id.to_string(),
-            discovered.source_method,
-            discovered.query_used.clone(),
-            0,
-        );
+        let crawl_url = discovered.to_crawl_url(source_id);
 
         match crawl_repo.add_url(&crawl_url).await {
             Ok(true) => added += 1,
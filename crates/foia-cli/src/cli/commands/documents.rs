@@ -6,7 +6,7 @@ use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 
 use foia::config::Settings;
-use foia::models::Document;
+use foia::models::{BatesNumber, Document};
 use foia::repository::DieselDocumentRepository;
 
 use super::helpers::{format_bytes, mime_short, truncate};
@@ -61,6 +61,7 @@ fn extract_and_ocr_from_archive(
 /// Extract and optionally OCR an email attachment.
 fn extract_and_ocr_from_email(
     file_path: &Path,
+    attachment_index: usize,
     attachment_name: &str,
     attachment_mime: &str,
     run_ocr: bool,
@@ -73,7 +74,7 @@ fn extract_and_ocr_from_email(
         return (None, VirtualFileStatus::Pending);
     }
 
-    match EmailExtractor::extract_attachment(file_path, attachment_name) {
+    match EmailExtractor::extract_attachment_at(file_path, attachment_index) {
         Ok(extracted) => match text_extractor.extract(&extracted.file_path, attachment_mime) {
             Ok(result) => (Some(result.text), VirtualFileStatus::OcrComplete),
             Err(e) => {
@@ -173,13 +174,38 @@ async fn process_email(
         }
     };
 
+    // Mailbox imports record attachments up front; fill those in rather
+    // than inserting duplicates
+    let existing = doc_repo
+        .get_virtual_files(&doc.id, version_id as i32)
+        .await
+        .unwrap_or_default();
+
     let files_discovered = parsed.attachments.len();
     let mut files_extracted = 0;
 
-    for attachment in &parsed.attachments {
+    for (index, attachment) in parsed.attachments.iter().enumerate() {
+        // Match on the part index; rows recorded before it was part of the
+        // path only match when the filename is unambiguous
+        let archive_path = VirtualFile::email_attachment_path(index, &attachment.filename);
+        let unique_name = parsed
+            .attachments
+            .iter()
+            .filter(|a| a.filename == attachment.filename)
+            .count()
+            == 1;
+        let recorded = existing.iter().find(|vf| {
+            vf.archive_path == archive_path
+                || (unique_name && vf.archive_path == attachment.filename)
+        });
+        if recorded.is_some_and(|vf| vf.status != VirtualFileStatus::Pending) {
+            continue;
+        }
+
         let (text, status) = if attachment.is_extractable() {
             let result = extract_and_ocr_from_email(
                 &file_path,
+                index,
                 &attachment.filename,
                 &attachment.mime_type,
                 run_ocr,
@@ -193,24 +219,31 @@ async fn process_email(
             (None, VirtualFileStatus::Unsupported)
         };
 
-        let mut vf = VirtualFile::new(
-            doc.id.clone(),
-            version_id,
-            attachment.filename.clone(),
-            attachment.filename.clone(),
-            attachment.mime_type.clone(),
-            attachment.size,
-        );
-        vf.extracted_text = text;
-        vf.status = status;
+        let result = if let Some(vf) = recorded {
+            doc_repo
+                .update_virtual_file_text(&vf.id, text.as_deref(), status)
+                .await
+        } else {
+            let mut vf = VirtualFile::new(
+                doc.id.clone(),
+                version_id,
+                archive_path,
+                attachment.filename.clone(),
+                attachment.mime_type.clone(),
+                attachment.size,
+            );
+            vf.extracted_text = text;
+            vf.status = status;
+            doc_repo.insert_virtual_file(&vf).await
+        };
 
-        if let Err(e) = doc_repo.insert_virtual_file(&vf).await {
+        if let Err(e) = result {
             tracing::warn!("Failed to save virtual file {}: {}", attachment.filename, e);
         }
     }
 
     // Mark emails with no attachments as processed
-    if parsed.attachments.is_empty() {
+    if parsed.attachments.is_empty() && existing.is_empty() {
         let placeholder = VirtualFile::new(
             doc.id.clone(),
             version_id,
@@ -439,39 +472,27 @@ pub async fn cmd_ls(
 }
 
 /// Show document info/metadata.
-pub async fn cmd_info(settings: &Settings, doc_id: &str) -> anyhow::Result<()> {
+///
+/// The document is found by ID, partial ID or title, or by a Bates number
+/// stamped on one of its pages.
+pub async fn cmd_info(
+    settings: &Settings,
+    doc_id: Option<&str>,
+    bates: Option<&str>,
+) -> anyhow::Result<()> {
     let repos = settings.repositories()?;
     let doc_repo = repos.documents;
 
-    // Try to find document by ID
-    let doc = match doc_repo.get(doc_id).await? {
-        Some(d) => d,
-        None => {
-            // Try to find by partial ID or title search
-            let all_docs = doc_repo.get_all().await?;
-            let matches: Vec<_> = all_docs
-                .into_iter()
-                .filter(|d| {
-                    d.id.starts_with(doc_id)
-                        || d.title.to_lowercase().contains(&doc_id.to_lowercase())
-                })
-                .collect();
-
-            match matches.len() {
-                0 => {
-                    println!("{} Document not found: {}", style("✗").red(), doc_id);
-                    return Ok(());
-                }
-                1 => matches.into_iter().next().unwrap(),
-                _ => {
-                    println!("{} Multiple matches found:", style("!").yellow());
-                    for d in &matches {
-                        println!("  {} - {}", &d.id[..8], truncate(&d.title, 50));
-                    }
-                    return Ok(());
-                }
-            }
-        }
+    let (doc, bates_page) = match (doc_id, bates) {
+        (_, Some(bates)) => match find_by_bates(&doc_repo, bates).await? {
+            Some(found) => found,
+            None => return Ok(()),
+        },
+        (Some(doc_id), None) => match find_document(&doc_repo, doc_id).await? {
+            Some(doc) => (doc, None),
+            None => return Ok(()),
+        },
+        (None, None) => anyhow::bail!("Specify a document ID or --bates"),
     };
 
     // Display document info
@@ -493,6 +514,13 @@ pub async fn cmd_info(settings: &Settings, doc_id: &str) -> anyhow::Result<()> {
         doc.updated_at.format("%Y-%m-%d %H:%M:%S")
     );
 
+    if let Some(range) = doc_repo.get_bates_range(&doc.id).await? {
+        println!("{:<18} {} ({} pages)", "Bates:", range, range.page_count());
+    }
+    if let Some((bates, page)) = &bates_page {
+        println!("{:<18} {} is page {}", "Bates Match:", bates, page);
+    }
+
     if let Some(synopsis) = &doc.synopsis {
         println!("\n{}", style("Synopsis").bold());
         println!("{}", "-".repeat(60));
@@ -555,6 +583,88 @@ pub async fn cmd_info(settings: &Settings, doc_id: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Find a document by ID, partial ID or title, printing why when none or
+/// several match.
+async fn find_document(
+    doc_repo: &DieselDocumentRepository,
+    doc_id: &str,
+) -> anyhow::Result<Option<Document>> {
+    if let Some(doc) = doc_repo.get(doc_id).await? {
+        return Ok(Some(doc));
+    }
+
+    // Try to find by partial ID or title search
+    let all_docs = doc_repo.get_all().await?;
+    let matches: Vec<_> = all_docs
+        .into_iter()
+        .filter(|d| {
+            d.id.starts_with(doc_id) || d.title.to_lowercase().contains(&doc_id.to_lowercase())
+        })
+        .collect();
+
+    match matches.len() {
+        0 => {
+            println!("{} Document not found: {}", style("✗").red(), doc_id);
+            Ok(None)
+        }
+        1 => Ok(matches.into_iter().next()),
+        _ => {
+            println!("{} Multiple matches found:", style("!").yellow());
+            for d in &matches {
+                println!("  {} - {}", &d.id[..8], truncate(&d.title, 50));
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Find the document stamped with a Bates number, along with the number and
+/// the page it falls on.
+async fn find_by_bates(
+    doc_repo: &DieselDocumentRepository,
+    bates: &str,
+) -> anyhow::Result<Option<(Document, Option<(String, u32)>)>> {
+    let Some(number) = BatesNumber::parse(bates) else {
+        println!("{} Not a Bates number: {}", style("✗").red(), bates);
+        return Ok(None);
+    };
+
+    let matches = doc_repo.find_by_bates(&number).await?;
+    let found = match matches.as_slice() {
+        [] => {
+            println!("{} No document stamped {}", style("✗").red(), number);
+            return Ok(None);
+        }
+        [found] => found,
+        _ => {
+            println!(
+                "{} {} falls within several documents:",
+                style("!").yellow(),
+                number
+            );
+            for m in &matches {
+                println!(
+                    "  {} - page {} of {}",
+                    &m.document_id[..8],
+                    m.page_number,
+                    m.range
+                );
+            }
+            return Ok(None);
+        }
+    };
+
+    let Some(doc) = doc_repo.get(&found.document_id).await? else {
+        println!(
+            "{} Document not found: {}",
+            style("✗").red(),
+            found.document_id
+        );
+        return Ok(None);
+    };
+    Ok(Some((doc, Some((number.to_string(), found.page_number)))))
+}
+
 /// Output document content to stdout.
 pub async fn cmd_read(settings: &Settings, doc_id: &str, text_only: bool) -> anyhow::Result<()> {
     let repos = settings.repositories()?;
@@ -0,0 +1,685 @@
+//! Export commands for packaging fetched documents and dataset snapshots.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use console::style;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use foia::config::Settings;
+use foia::models::{CrawlRequest, Document, DocumentVersion};
+use foia::repository::diesel_document::BrowseParams;
+use foia::repository::DieselDocumentRepository;
+use foia::storage::bagit::{gpg_available, BagSignature, BagWriter};
+#[cfg(feature = "dataset")]
+use foia::storage::dataset::{
+    analysis_row, document_row, entity_row, page_row, version_row, DatasetTable, DatasetWriter,
+    ParquetDatasetWriter, Row, SqliteDatasetWriter,
+};
+use foia::storage::wacz::{WaczCapture, WaczWriter};
+use foia::storage::warc::ResponseIndex;
+
+use super::helpers::{format_bytes, truncate};
+
+/// Package a source's fetched documents as a WACZ web archive.
+///
+/// Every stored version with an HTTP(S) URL is written with the `response`
+/// record captured when it was fetched, if the source has WARC capture
+/// enabled, or else as a `resource` record dated by `acquired_at`. Each is
+/// followed by a `metadata` record holding its hashes and provenance.
+/// Versions that were imported rather than fetched have nothing to replay
+/// and are skipped.
+pub async fn cmd_export_wacz(
+    settings: &Settings,
+    source_id: &str,
+    output: &Path,
+    title: Option<&str>,
+    batch_size: usize,
+) -> anyhow::Result<()> {
+    let ctx = settings.create_db_context()?;
+    let source = ctx.sources().get(source_id).await?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Source '{}' not found. Use 'source list' to see available sources.",
+            source_id
+        )
+    })?;
+    let doc_repo = ctx.documents();
+    let title = title.map(str::to_string).unwrap_or(source.name);
+
+    println!(
+        "{} Exporting {} to {}",
+        style("→").cyan(),
+        source_id,
+        output.display()
+    );
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} [{elapsed_precise}] {pos} captures {wide_msg}")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let responses = ResponseIndex::scan(&settings.data_dir.join("warc").join(source_id))?;
+    let mut writer = WaczWriter::create(output, &title)?;
+    let mut captured = 0usize;
+    let mut not_fetched = 0usize;
+    let mut missing = 0usize;
+    let mut last_version_id = 0;
+
+    loop {
+        let batch = doc_repo
+            .get_stored_versions_after(last_version_id, batch_size, Some(source_id))
+            .await?;
+        if batch.is_empty() {
+            break;
+        }
+
+        for stored in batch {
+            last_version_id = stored.version.id;
+            let version = &stored.version;
+
+            let url = version.source_url.as_deref().unwrap_or(&stored.source_url);
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                not_fetched += 1;
+                continue;
+            }
+
+            let path =
+                version.resolve_path(&settings.documents_dir, &stored.source_url, &stored.title);
+            let content = match std::fs::read(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    pb.println(format!(
+                        "  {} {} ({}: {})",
+                        style("!").yellow(),
+                        url,
+                        path.display(),
+                        e
+                    ));
+                    missing += 1;
+                    continue;
+                }
+            };
+
+            let response = if responses.is_empty() {
+                None
+            } else {
+                responses.get(url, &format!("sha256:{}", version.content_hash))?
+            };
+            if response.is_some() {
+                captured += 1;
+            }
+
+            pb.set_message(truncate(&stored.title, 40));
+            writer.add_capture(&WaczCapture {
+                url,
+                date: version.acquired_at,
+                mime_type: &version.mime_type,
+                title: &stored.title,
+                content: &content,
+                content_hash: &version.content_hash,
+                response: response.as_ref(),
+                provenance: serde_json::json!({
+                    "document_id": stored.document_id,
+                    "source_id": stored.source_id,
+                    "document_url": stored.source_url,
+                    "version_id": version.id,
+                    "content_hash": version.content_hash,
+                    "content_hash_blake3": version.content_hash_blake3,
+                    "file_size": version.file_size,
+                    "acquired_at": version.acquired_at,
+                    "server_date": version.server_date,
+                    "original_filename": version.original_filename,
+                    "archive_snapshot_id": version.archive_snapshot_id,
+                    "earliest_archived_at": version.earliest_archived_at,
+                }),
+            })?;
+            pb.inc(1);
+        }
+    }
+
+    pb.finish_and_clear();
+    let summary = writer.finish()?;
+
+    println!(
+        "{} Wrote {} capture(s) ({} of WARC data) to {}",
+        style("✓").green(),
+        summary.captures,
+        format_bytes(summary.warc_bytes),
+        output.display()
+    );
+    println!(
+        "  {} with the HTTP response captured at fetch time, {} as stored content",
+        captured,
+        summary.captures - captured
+    );
+    if not_fetched > 0 {
+        println!(
+            "  {} version(s) without an HTTP URL skipped",
+            style(not_fetched).dim()
+        );
+    }
+    if missing > 0 {
+        println!(
+            "  {} {} stored file(s) missing; run 'foia storage verify --repair'",
+            style("!").yellow(),
+            missing
+        );
+    }
+
+    Ok(())
+}
+
+/// Package selected documents as a BagIt evidence bundle.
+///
+/// Every version of each document is included with its original file and
+/// extracted text. `provenance.json` records hashes, URLs, dates, archive
+/// snapshots and the crawl request log for each version, and the tag
+/// manifest covering it is signed with gpg unless `sign` is false.
+///
+/// A bundle with missing files or hash mismatches is discarded before
+/// signing unless `allow_incomplete` is set, in which case the failures are
+/// listed in `bag-info.txt`.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_export_bundle(
+    settings: &Settings,
+    ids: &[String],
+    ids_file: Option<&Path>,
+    query: Option<&str>,
+    source_id: Option<&str>,
+    tags: &[String],
+    output: &Path,
+    sign: bool,
+    key: Option<&str>,
+    allow_incomplete: bool,
+) -> anyhow::Result<()> {
+    if sign && !gpg_available() {
+        anyhow::bail!("gpg not found. Install GnuPG to sign the bundle, or pass --no-sign.");
+    }
+
+    let ctx = settings.create_db_context()?;
+    let doc_repo = ctx.documents();
+    let crawl_repo = ctx.crawl();
+
+    let mut ids = ids.to_vec();
+    if let Some(path) = ids_file {
+        ids.extend(
+            std::fs::read_to_string(path)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        );
+    }
+
+    let documents = if !ids.is_empty() {
+        let mut seen = HashSet::new();
+        let mut documents = Vec::new();
+        for id in ids {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            match doc_repo.get(&id).await? {
+                Some(doc) => documents.push(doc),
+                None => anyhow::bail!("Document not found: {}", id),
+            }
+        }
+        documents
+    } else if query.is_some() || source_id.is_some() || !tags.is_empty() {
+        select_documents(&doc_repo, query, source_id, tags).await?
+    } else {
+        anyhow::bail!("Specify document IDs, --ids-file, or a --query, --source or --tag filter");
+    };
+
+    if documents.is_empty() {
+        println!("{} No documents matched", style("!").yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{} Bundling {} document(s) to {}",
+        style("→").cyan(),
+        documents.len(),
+        output.display()
+    );
+
+    let pb = ProgressBar::new(documents.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{bar:30.cyan/blue}] {pos}/{len} {wide_msg}")
+            .unwrap(),
+    );
+
+    let mut bag = BagWriter::create(output)?;
+    let mut entries = Vec::with_capacity(documents.len());
+    let mut version_count = 0usize;
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+
+    for doc in &documents {
+        pb.set_message(truncate(&doc.title, 40));
+
+        // Oldest first, so each version claims the requests since the previous one
+        let mut versions: Vec<&DocumentVersion> = doc.versions.iter().collect();
+        versions.sort_by_key(|v| v.acquired_at);
+        let current_id = doc.current_version().map(|v| v.id);
+        let mut requests_by_url: HashMap<String, Vec<CrawlRequest>> = HashMap::new();
+        let mut version_entries = Vec::with_capacity(versions.len());
+
+        for (i, version) in versions.iter().enumerate() {
+            let url = version.source_url.as_deref().unwrap_or(&doc.source_url);
+            if !requests_by_url.contains_key(url) {
+                let requests = crawl_repo.get_requests_for_url(&doc.source_id, url).await?;
+                requests_by_url.insert(url.to_string(), requests);
+            }
+            let after = i.checked_sub(1).map(|prev| versions[prev].acquired_at);
+            let until = (i + 1 < versions.len()).then_some(version.acquired_at);
+            let requests = requests_in_window(&requests_by_url[url], after, until);
+
+            let dir = format!("{}/v{}", doc.id, version.id);
+            let path = version.resolve_path(&settings.documents_dir, &doc.source_url, &doc.title);
+            let (file, sha256_verified, blake3_verified) = match std::fs::read(&path) {
+                Ok(content) => {
+                    let file_name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| "original".to_string());
+                    let bag_path = format!("{}/original/{}", dir, file_name);
+                    let sha256 = bag.add_payload(&bag_path, &content)?;
+                    let blake3_verified = version
+                        .content_hash_blake3
+                        .as_ref()
+                        .map(|hash| *hash == DocumentVersion::compute_hash_blake3(&content));
+                    (
+                        Some(format!("data/{}", bag_path)),
+                        Some(sha256 == version.content_hash),
+                        blake3_verified,
+                    )
+                }
+                Err(e) => {
+                    pb.println(format!(
+                        "  {} {} v{} ({}: {})",
+                        style("!").yellow(),
+                        doc.id,
+                        version.id,
+                        path.display(),
+                        e
+                    ));
+                    missing.push(format!("{} v{}", doc.id, version.id));
+                    (None, None, None)
+                }
+            };
+            if sha256_verified == Some(false) || blake3_verified == Some(false) {
+                mismatched.push(format!("{} v{}", doc.id, version.id));
+            }
+
+            let text = match doc_repo
+                .get_combined_page_text(&doc.id, version.id as i32)
+                .await?
+            {
+                Some(text) => Some(text),
+                None if current_id == Some(version.id) => doc.extracted_text.clone(),
+                None => None,
+            };
+            let text_file = match text {
+                Some(text) => {
+                    let bag_path = format!("{}/extracted.txt", dir);
+                    bag.add_payload(&bag_path, text.as_bytes())?;
+                    Some(format!("data/{}", bag_path))
+                }
+                None => None,
+            };
+
+            let archive_urls = doc_repo.get_archive_urls(version, &doc.source_url).await?;
+
+            version_entries.push(serde_json::json!({
+                "version_id": version.id,
+                "file": file,
+                "extracted_text": text_file,
+                "original_filename": version.original_filename,
+                "mime_type": version.mime_type,
+                "file_size": version.file_size,
+                "sha256": version.content_hash,
+                "blake3": version.content_hash_blake3,
+                "sha256_verified": sha256_verified,
+                "blake3_verified": blake3_verified,
+                "source_url": url,
+                "acquired_at": version.acquired_at,
+                "server_date": version.server_date,
+                "archive_snapshot_id": version.archive_snapshot_id,
+                "earliest_archived_at": version.earliest_archived_at,
+                "archive_urls": archive_urls,
+                "crawl_requests": requests,
+            }));
+            version_count += 1;
+        }
+
+        entries.push(serde_json::json!({
+            "id": doc.id,
+            "source_id": doc.source_id,
+            "title": doc.title,
+            "source_url": doc.source_url,
+            "discovery_method": doc.discovery_method,
+            "created_at": doc.created_at,
+            "versions": version_entries,
+        }));
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    if !missing.is_empty() {
+        println!(
+            "  {} {} stored file(s) missing; run 'foia storage verify --repair'",
+            style("!").yellow(),
+            missing.len()
+        );
+    }
+    if !mismatched.is_empty() {
+        println!(
+            "  {} {} file(s) no longer match their recorded hashes:",
+            style("✗").red(),
+            mismatched.len()
+        );
+        for version in &mismatched {
+            println!("    {}", version);
+        }
+    }
+    if !allow_incomplete && !(missing.is_empty() && mismatched.is_empty()) {
+        // Nothing has been signed yet; don't leave a partial bag behind
+        drop(bag);
+        let _ = if output.is_dir() {
+            std::fs::remove_dir_all(output)
+        } else {
+            std::fs::remove_file(output)
+        };
+        anyhow::bail!(
+            "Bundle is incomplete ({} missing, {} mismatched). Repair storage, or pass --allow-incomplete to bundle anyway.",
+            missing.len(),
+            mismatched.len()
+        );
+    }
+
+    let provenance = serde_json::json!({
+        "created_at": Utc::now(),
+        "software": format!("foia {}", env!("CARGO_PKG_VERSION")),
+        "selection": {
+            "ids": documents.iter().map(|d| &d.id).collect::<Vec<_>>(),
+            "query": query,
+            "source_id": source_id,
+            "tags": tags,
+        },
+        "documents": entries,
+    });
+    bag.add_tag_file("provenance.json", &serde_json::to_vec_pretty(&provenance)?)?;
+
+    let mut bag_info = vec![
+        (
+            "Bag-Software-Agent",
+            format!("foia {}", env!("CARGO_PKG_VERSION")),
+        ),
+        ("Bagging-Date", Utc::now().format("%Y-%m-%d").to_string()),
+        (
+            "External-Description",
+            format!(
+                "{} document(s), {} version(s) with provenance in provenance.json",
+                documents.len(),
+                version_count
+            ),
+        ),
+    ];
+    bag_info.extend(missing.iter().map(|v| ("Missing-File", v.clone())));
+    bag_info.extend(mismatched.iter().map(|v| ("Hash-Mismatch", v.clone())));
+    let signature = if sign {
+        BagSignature::Gpg { key }
+    } else {
+        BagSignature::None
+    };
+    let summary = bag.finish(&bag_info, signature)?;
+
+    println!(
+        "{} Bundled {} document(s), {} file(s) ({}) to {}",
+        style("✓").green(),
+        documents.len(),
+        summary.files,
+        format_bytes(summary.bytes),
+        output.display()
+    );
+    match &summary.signature {
+        Some(name) => println!("  Signed tag manifest: {}", name),
+        None => println!("  {} Bundle is not signed", style("!").yellow()),
+    }
+    if !(missing.is_empty() && mismatched.is_empty()) {
+        println!(
+            "  {} Bundle is incomplete; failures are listed in bag-info.txt",
+            style("!").yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Output format for `export dataset`.
+#[cfg(feature = "dataset")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DatasetFormat {
+    /// One Parquet file per table in the output directory
+    Parquet,
+    /// A single read-only SQLite database
+    Sqlite,
+}
+
+/// Copy document tables to Parquet files or a SQLite snapshot.
+///
+/// Each table is read in primary key order, `batch_size` rows at a time, and
+/// every batch is written before the next is loaded, so exports have no row
+/// cap and run in constant memory.
+#[cfg(feature = "dataset")]
+pub async fn cmd_export_dataset(
+    settings: &Settings,
+    format: DatasetFormat,
+    output: &Path,
+    source_id: Option<&str>,
+    tables: &[DatasetTable],
+    include_text: bool,
+    batch_size: usize,
+) -> anyhow::Result<()> {
+    let ctx = settings.create_db_context()?;
+    if let Some(source_id) = source_id {
+        ctx.sources().get(source_id).await?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Source '{}' not found. Use 'source list' to see available sources.",
+                source_id
+            )
+        })?;
+    }
+    let doc_repo = ctx.documents();
+
+    let tables = if tables.is_empty() {
+        &DatasetTable::ALL[..]
+    } else {
+        tables
+    };
+    let mut selected: Vec<DatasetTable> = Vec::new();
+    for table in tables {
+        if !selected.contains(table) {
+            selected.push(*table);
+        }
+    }
+
+    let mut writer: Box<dyn DatasetWriter> = match format {
+        DatasetFormat::Parquet => Box::new(ParquetDatasetWriter::create(output)?),
+        DatasetFormat::Sqlite => Box::new(SqliteDatasetWriter::create(output)?),
+    };
+
+    println!(
+        "{} Exporting {} to {}",
+        style("→").cyan(),
+        selected
+            .iter()
+            .map(|t| t.name())
+            .collect::<Vec<_>>()
+            .join(", "),
+        output.display()
+    );
+
+    let to_page_row = |rec| page_row(rec, include_text);
+    let to_analysis_row = |rec| analysis_row(rec, include_text);
+
+    // Keyset pagination over an integer primary key
+    macro_rules! copy_by_id {
+        ($pb:expr, $fetch:ident, $to_row:expr) => {{
+            let mut after = 0;
+            loop {
+                let batch = doc_repo.$fetch(after, batch_size, source_id).await?;
+                let Some(last) = batch.last() else {
+                    break;
+                };
+                after = last.id;
+                let rows: Vec<Row> = batch.into_iter().map($to_row).collect();
+                writer.write_rows(&rows)?;
+                $pb.inc(rows.len() as u64);
+            }
+        }};
+    }
+
+    for table in selected {
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} [{elapsed_precise}] {pos} rows {wide_msg}")
+                .unwrap(),
+        );
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb.set_message(table.name());
+
+        writer.start_table(table)?;
+        match table {
+            DatasetTable::Documents => {
+                let mut after = String::new();
+                loop {
+                    let batch = doc_repo
+                        .get_document_records_after(&after, batch_size, source_id)
+                        .await?;
+                    let Some(last) = batch.last() else {
+                        break;
+                    };
+                    after = last.id.clone();
+                    let rows: Vec<Row> = batch
+                        .into_iter()
+                        .map(|rec| document_row(rec, include_text))
+                        .collect();
+                    writer.write_rows(&rows)?;
+                    pb.inc(rows.len() as u64);
+                }
+            }
+            DatasetTable::Versions => copy_by_id!(pb, get_version_records_after, version_row),
+            DatasetTable::Pages => copy_by_id!(pb, get_page_records_after, to_page_row),
+            DatasetTable::Entities => copy_by_id!(pb, get_entity_records_after, entity_row),
+            DatasetTable::AnalysisResults => {
+                copy_by_id!(pb, get_analysis_records_after, to_analysis_row)
+            }
+        }
+
+        pb.finish_and_clear();
+        println!(
+            "  {} {}: {} row(s)",
+            style("✓").green(),
+            table,
+            pb.position()
+        );
+    }
+
+    writer.finish()?;
+    println!("{} Wrote {}", style("✓").green(), output.display());
+    if !include_text {
+        println!("  {}", style("Text columns left empty (--no-text)").dim());
+    }
+
+    Ok(())
+}
+
+/// Load every document matching a query, source and tags, oldest first.
+async fn select_documents(
+    doc_repo: &DieselDocumentRepository,
+    query: Option<&str>,
+    source_id: Option<&str>,
+    tags: &[String],
+) -> anyhow::Result<Vec<Document>> {
+    const PAGE_SIZE: u32 = 500;
+
+    let mut documents = Vec::new();
+    loop {
+        let page = doc_repo
+            .browse(BrowseParams {
+                source_id,
+                status: None,
+                categories: &[],
+                tags,
+                search_query: query,
+                sort_field: Some("created_at"),
+                sort_order: Some("asc"),
+                limit: PAGE_SIZE,
+                offset: documents.len() as u32,
+                after_id: None,
+            })
+            .await?;
+        let done = page.len() < PAGE_SIZE as usize;
+        documents.extend(page);
+        if done {
+            break;
+        }
+    }
+    Ok(documents)
+}
+
+/// Requests made after `after` and up to `until`.
+///
+/// A version owns the requests since the previous version was acquired; the
+/// latest version also keeps later revalidations, such as 304 responses
+/// confirming the content was unchanged.
+fn requests_in_window(
+    requests: &[CrawlRequest],
+    after: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Vec<&CrawlRequest> {
+    requests
+        .iter()
+        .filter(|r| !after.is_some_and(|after| r.request_at <= after))
+        .filter(|r| !until.is_some_and(|until| r.request_at > until))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_requests_in_window() {
+        let at = |hour| Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+        let requests: Vec<CrawlRequest> = [1, 2, 3, 4]
+            .into_iter()
+            .map(|hour| {
+                let mut request = CrawlRequest::new(
+                    "src".to_string(),
+                    "https://example.gov/a.pdf".to_string(),
+                    "GET".to_string(),
+                );
+                request.request_at = at(hour);
+                request
+            })
+            .collect();
+
+        // First version acquired at 2:00, second at 3:00
+        let first = requests_in_window(&requests, None, Some(at(2)));
+        assert_eq!(first.len(), 2);
+        let latest = requests_in_window(&requests, Some(at(2)), None);
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].request_at, at(3));
+    }
+}
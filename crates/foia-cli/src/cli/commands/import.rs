@@ -1,15 +1,26 @@
-//! Import commands for WARC files, URL lists, and stdin content.
+//! Import commands for WARC/WACZ files, load files, URL lists, stdin content,
+//! and document platforms (DocumentCloud, MuckRock).
 
 use std::io::Read;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use console::style;
 
 use foia::config::Settings;
+use foia::http_client::HttpClient;
 use foia::models::{CrawlUrl, DiscoveryMethod};
-use foia_import::{FileStorageMode, ImportRunner, ImportStats, WarcImportSource};
+use foia::privacy::PrivacyConfig;
+use foia::storage::{save_document_async, DocumentInput, SaveOutcome};
+use foia_import::{FileStorageMode, ImportRunner, ImportStats, WaczImportSource, WarcImportSource};
+
+/// Whether a path names a WACZ package rather than a bare WARC file.
+fn is_wacz(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wacz"))
+}
 
-/// Import documents from WARC archive files.
+/// Import documents from WARC archive files and WACZ packages.
 #[allow(clippy::too_many_arguments)]
 pub async fn cmd_import(
     settings: &Settings,
@@ -64,30 +75,54 @@ pub async fn cmd_import(
             continue;
         }
 
-        // Create import source for this file
-        let mut source = match WarcImportSource::new(
-            warc_path.clone(),
-            source_id.map(|s| s.to_string()),
-            filter,
-            settings.clone(),
-        )
-        .await
-        {
-            Ok(s) => s,
-            Err(e) => {
-                println!(
-                    "{} Failed to initialize WARC source {}: {}",
-                    style("✗").red(),
-                    warc_path.display(),
-                    e
-                );
-                total_errors += 1;
-                continue;
+        // Create import source for this file and run it
+        let run_result = if is_wacz(warc_path) {
+            match WaczImportSource::new(
+                warc_path.clone(),
+                source_id.map(|s| s.to_string()),
+                filter,
+                settings.clone(),
+            )
+            .await
+            {
+                Ok(mut source) => Some(runner.run(&mut source, &config).await),
+                Err(e) => {
+                    println!(
+                        "{} Failed to initialize WACZ source {}: {}",
+                        style("✗").red(),
+                        warc_path.display(),
+                        e
+                    );
+                    None
+                }
+            }
+        } else {
+            match WarcImportSource::new(
+                warc_path.clone(),
+                source_id.map(|s| s.to_string()),
+                filter,
+                settings.clone(),
+            )
+            .await
+            {
+                Ok(mut source) => Some(runner.run(&mut source, &config).await),
+                Err(e) => {
+                    println!(
+                        "{} Failed to initialize WARC source {}: {}",
+                        style("✗").red(),
+                        warc_path.display(),
+                        e
+                    );
+                    None
+                }
             }
         };
+        let Some(run_result) = run_result else {
+            total_errors += 1;
+            continue;
+        };
 
-        // Run import for this file
-        match runner.run(&mut source, &config).await {
+        match run_result {
             Ok(stats) => {
                 total_stats.merge(&stats);
 
@@ -324,6 +359,13 @@ pub async fn cmd_import_stdin(
 
     // Compute content hash and storage path
     let content_hash = DocumentVersion::compute_hash(&content);
+    if doc_repo
+        .find_tombstone(Some(url), Some(&content_hash))
+        .await?
+        .is_some()
+    {
+        anyhow::bail!("{} matches a deleted document", url);
+    }
     let title = original_filename
         .clone()
         .unwrap_or_else(|| "document".to_string());
@@ -413,41 +455,11 @@ pub async fn cmd_import_concordance(
     move_files: bool,
     link_files: bool,
 ) -> anyhow::Result<()> {
-    use foia_import::{ConcordanceImportSource, FileStorageMode, ImportRunner, MultiPageMode};
+    use foia_import::{ConcordanceImportSource, ImportRunner, MultiPageMode};
 
     settings.ensure_directories()?;
 
-    // Determine storage mode
-    let storage_mode = if move_files {
-        FileStorageMode::Move
-    } else if link_files {
-        FileStorageMode::HardLink
-    } else {
-        // Auto-detect: use hard links if on same filesystem
-        ImportRunner::detect_storage_mode(path, &settings.documents_dir)
-    };
-
-    // Log the storage mode being used
-    match storage_mode {
-        FileStorageMode::Copy => {
-            println!(
-                "{} Storage mode: copy (different filesystem or default)",
-                style("→").cyan()
-            );
-        }
-        FileStorageMode::Move => {
-            println!(
-                "{} Storage mode: move (originals will be deleted)",
-                style("!").yellow()
-            );
-        }
-        FileStorageMode::HardLink => {
-            println!(
-                "{} Storage mode: hard link (same filesystem detected)",
-                style("→").cyan()
-            );
-        }
-    }
+    let storage_mode = resolve_storage_mode(settings, path, move_files, link_files);
 
     // Create import source
     let mut source = ConcordanceImportSource::new(
@@ -480,3 +492,449 @@ pub async fn cmd_import_concordance(
 
     Ok(())
 }
+
+/// E-discovery load file formats handled by `import load-file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LoadFileFormat {
+    /// EDRM XML
+    Edrm,
+    /// Summation DII
+    Dii,
+    /// Relativity-style delimited load file (CSV, tab or Concordance delimiters)
+    Relativity,
+}
+
+impl LoadFileFormat {
+    /// Guess the format from a load file's extension.
+    fn detect(path: &std::path::Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("xml") => Self::Edrm,
+            Some("dii") => Self::Dii,
+            _ => Self::Relativity,
+        }
+    }
+}
+
+/// Import documents from an EDRM XML, Summation DII or Relativity-style load file.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_import_load_file(
+    settings: &Settings,
+    path: &std::path::Path,
+    format: Option<LoadFileFormat>,
+    source_id: &str,
+    url_prefix: Option<&str>,
+    verify: bool,
+    tags: &[String],
+    limit: usize,
+    dry_run: bool,
+    resume: bool,
+    move_files: bool,
+    link_files: bool,
+) -> anyhow::Result<()> {
+    use foia_import::{DiiImportSource, EdrmImportSource, RelativityImportSource};
+
+    settings.ensure_directories()?;
+
+    let storage_mode = resolve_storage_mode(settings, path, move_files, link_files);
+    let format = format.unwrap_or_else(|| LoadFileFormat::detect(path));
+    let url_prefix = url_prefix.map(|s| s.to_string());
+
+    // Create config with existing URLs loaded
+    let runner = ImportRunner::new(settings);
+    let mut config = runner
+        .create_config(
+            Some(source_id.to_string()),
+            limit,
+            dry_run,
+            resume,
+            storage_mode,
+        )
+        .await?;
+    config.verify = verify;
+    config.tags = tags.to_vec();
+
+    let path = path.to_path_buf();
+    let stats = match format {
+        LoadFileFormat::Edrm => {
+            let mut source = EdrmImportSource::new(path, url_prefix, settings.clone())?;
+            runner.run(&mut source, &config).await?
+        }
+        LoadFileFormat::Dii => {
+            let mut source = DiiImportSource::new(path, url_prefix, settings.clone())?;
+            runner.run(&mut source, &config).await?
+        }
+        LoadFileFormat::Relativity => {
+            let mut source = RelativityImportSource::new(path, url_prefix, settings.clone())?;
+            runner.run(&mut source, &config).await?
+        }
+    };
+
+    if stats.errors > 0 {
+        anyhow::bail!("{} error(s) during import", stats.errors);
+    }
+
+    Ok(())
+}
+
+/// Import every file under a directory tree.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_import_dir(
+    settings: &Settings,
+    path: &std::path::Path,
+    source_id: Option<&str>,
+    include: &[String],
+    exclude: &[String],
+    source_from_folder: bool,
+    tags_from_folders: bool,
+    tags: &[String],
+    limit: usize,
+    dry_run: bool,
+    resume: bool,
+    move_files: bool,
+    link_files: bool,
+) -> anyhow::Result<()> {
+    use foia_import::{DirectoryImportSource, FolderMapping};
+
+    settings.ensure_directories()?;
+
+    let storage_mode = resolve_storage_mode(settings, path, move_files, link_files);
+
+    let mut source = DirectoryImportSource::new(
+        path.to_path_buf(),
+        include,
+        exclude,
+        FolderMapping {
+            source_from_folder,
+            tags_from_folders,
+        },
+        settings.clone(),
+    )?;
+
+    let runner = ImportRunner::new(settings);
+    let mut config = runner
+        .create_config(
+            source_id.map(|s| s.to_string()),
+            limit,
+            dry_run,
+            resume,
+            storage_mode,
+        )
+        .await?;
+    config.tags = tags.to_vec();
+
+    let stats = runner.run(&mut source, &config).await?;
+
+    if stats.no_source > 0 {
+        println!(
+            "{} {} file(s) in the root folder skipped (no --source given)",
+            style("!").yellow(),
+            stats.no_source
+        );
+    }
+    if stats.errors > 0 {
+        anyhow::bail!("{} error(s) during import", stats.errors);
+    }
+
+    Ok(())
+}
+
+/// Import an mbox file or Maildir, one document per message.
+pub async fn cmd_import_mailbox(
+    settings: &Settings,
+    path: &std::path::Path,
+    source_id: &str,
+    tags: &[String],
+    limit: usize,
+    dry_run: bool,
+    resume: bool,
+) -> anyhow::Result<()> {
+    use foia_import::MailboxImportSource;
+
+    settings.ensure_directories()?;
+
+    let mut source = MailboxImportSource::new(path.to_path_buf(), settings.clone())?;
+
+    let runner = ImportRunner::new(settings);
+    let mut config = runner
+        .create_config(
+            Some(source_id.to_string()),
+            limit,
+            dry_run,
+            resume,
+            FileStorageMode::Copy,
+        )
+        .await?;
+    config.tags = tags.to_vec();
+
+    let stats = runner.run(&mut source, &config).await?;
+
+    if stats.errors > 0 {
+        anyhow::bail!("{} error(s) during import", stats.errors);
+    }
+
+    Ok(())
+}
+
+/// Import documents from a DocumentCloud project or search.
+pub async fn cmd_import_documentcloud(
+    settings: &Settings,
+    source_id: &str,
+    project: Option<&str>,
+    query: Option<&str>,
+    limit: usize,
+    dry_run: bool,
+    privacy_config: &PrivacyConfig,
+) -> anyhow::Result<()> {
+    use foia_scrape::discovery::sources::documentcloud::{parse_project_id, DocumentCloudClient};
+
+    let client = platform_client("documentcloud", privacy_config)?;
+    let documentcloud = DocumentCloudClient::new(client.clone());
+
+    let docs = match (project, query) {
+        (Some(project), _) => {
+            let project_id = parse_project_id(project)
+                .ok_or_else(|| anyhow::anyhow!("Invalid DocumentCloud project: {}", project))?;
+            println!(
+                "{} Listing DocumentCloud project {}",
+                style("→").cyan(),
+                project_id
+            );
+            documentcloud.project(project_id, limit).await?
+        }
+        (None, Some(query)) => {
+            println!(
+                "{} Searching DocumentCloud for \"{}\"",
+                style("→").cyan(),
+                query
+            );
+            documentcloud.search(query, limit).await?
+        }
+        (None, None) => anyhow::bail!("Specify --project or --query"),
+    };
+
+    let inputs = docs.iter().map(|doc| doc.to_input()).collect();
+    save_platform_documents(settings, &client, source_id, inputs, dry_run).await
+}
+
+/// Import the files attached to MuckRock requests.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_import_muckrock(
+    settings: &Settings,
+    source_id: &str,
+    request: Option<&str>,
+    query: Option<&str>,
+    status: Option<&str>,
+    agency: Option<u64>,
+    limit: usize,
+    dry_run: bool,
+    privacy_config: &PrivacyConfig,
+) -> anyhow::Result<()> {
+    use foia_scrape::discovery::sources::muckrock::{
+        parse_request_id, MuckRockClient, RequestFilter,
+    };
+
+    let client = platform_client("muckrock", privacy_config)?;
+    let muckrock = MuckRockClient::new(client.clone());
+
+    let requests = if let Some(request) = request {
+        let request_id = parse_request_id(request)
+            .ok_or_else(|| anyhow::anyhow!("Invalid MuckRock request: {}", request))?;
+        println!(
+            "{} Fetching MuckRock request {}",
+            style("→").cyan(),
+            request_id
+        );
+        vec![muckrock.request(request_id).await?]
+    } else {
+        let filter = RequestFilter {
+            query: query.map(String::from),
+            status: status.map(String::from),
+            agency,
+            jurisdiction: None,
+        };
+        println!("{} Listing MuckRock requests", style("→").cyan());
+        muckrock.requests(&filter, limit).await?
+    };
+
+    println!("  Found {} request(s)", requests.len());
+    let inputs = requests.iter().flat_map(|r| r.documents()).collect();
+    save_platform_documents(settings, &client, source_id, inputs, dry_run).await
+}
+
+/// HTTP client for talking to a document platform's API and file hosts.
+fn platform_client(name: &str, privacy_config: &PrivacyConfig) -> anyhow::Result<HttpClient> {
+    HttpClient::builder(name, Duration::from_secs(60), Duration::from_millis(1000))
+        .privacy(privacy_config)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))
+}
+
+/// Download each listed file and save it with its platform metadata.
+///
+/// Files whose URL is already stored are skipped, as are files deleted
+/// earlier (by URL or content), so a takedown stays in effect.
+async fn save_platform_documents(
+    settings: &Settings,
+    client: &HttpClient,
+    source_id: &str,
+    inputs: Vec<DocumentInput>,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    settings.ensure_directories()?;
+    let repos = settings.repositories()?;
+    if !dry_run && repos.sources.get(source_id).await?.is_none() {
+        anyhow::bail!(
+            "Source '{}' not found. Use 'source list' to see available sources.",
+            source_id
+        );
+    }
+    let doc_repo = repos.documents;
+
+    let mut stats = ImportStats::default();
+    let mut deleted = 0usize;
+    for mut input in inputs {
+        stats.scanned += 1;
+        if !doc_repo.get_by_url(&input.url).await?.is_empty() {
+            stats.skipped += 1;
+            continue;
+        }
+        if doc_repo
+            .find_tombstone(Some(&input.url), None)
+            .await?
+            .is_some()
+        {
+            println!("  {} {} was deleted", style("-").dim(), input.url);
+            deleted += 1;
+            continue;
+        }
+        if dry_run {
+            println!("  {} {} ({})", style("→").dim(), input.title, input.url);
+            stats.imported += 1;
+            continue;
+        }
+
+        let response = match client.get(&input.url, None, None).await {
+            Ok(response) => response,
+            Err(e) => {
+                println!("  {} {}: {}", style("✗").red(), input.url, e);
+                stats.errors += 1;
+                continue;
+            }
+        };
+        if !response.is_success() {
+            println!(
+                "  {} {} returned {}",
+                style("✗").red(),
+                input.url,
+                response.status
+            );
+            stats.errors += 1;
+            continue;
+        }
+        let content = match response.bytes().await {
+            Ok(content) => content,
+            Err(e) => {
+                println!("  {} {}: {}", style("✗").red(), input.url, e);
+                stats.errors += 1;
+                continue;
+            }
+        };
+
+        if input.mime_type == "application/octet-stream" {
+            if let Some(kind) = infer::get(&content) {
+                input.mime_type = kind.mime_type().to_string();
+            }
+        }
+
+        match save_document_async(
+            &doc_repo,
+            &content,
+            &input,
+            source_id,
+            &settings.documents_dir,
+        )
+        .await
+        {
+            Ok(SaveOutcome::Deleted) => {
+                println!(
+                    "  {} {} matches a deleted document",
+                    style("-").dim(),
+                    input.url
+                );
+                deleted += 1;
+            }
+            Ok(_) => {
+                println!("  {} {}", style("✓").green(), input.title);
+                stats.imported += 1;
+            }
+            Err(e) => {
+                println!("  {} {}: {}", style("✗").red(), input.url, e);
+                stats.errors += 1;
+            }
+        }
+    }
+
+    let verb = if dry_run {
+        "would be imported"
+    } else {
+        "imported"
+    };
+    println!(
+        "{} Import complete: {} {}, {} already stored, {} deleted, {} failed",
+        style("✓").green(),
+        stats.imported,
+        verb,
+        stats.skipped,
+        deleted,
+        stats.errors
+    );
+
+    if stats.errors > 0 {
+        anyhow::bail!("{} error(s) during import", stats.errors);
+    }
+    Ok(())
+}
+
+/// Pick how imported files reach the documents directory and report it.
+fn resolve_storage_mode(
+    settings: &Settings,
+    path: &std::path::Path,
+    move_files: bool,
+    link_files: bool,
+) -> FileStorageMode {
+    let storage_mode = if move_files {
+        FileStorageMode::Move
+    } else if link_files {
+        FileStorageMode::HardLink
+    } else {
+        // Auto-detect: use hard links if on same filesystem
+        ImportRunner::detect_storage_mode(path, &settings.documents_dir)
+    };
+
+    match storage_mode {
+        FileStorageMode::Copy => {
+            println!(
+                "{} Storage mode: copy (different filesystem or default)",
+                style("→").cyan()
+            );
+        }
+        FileStorageMode::Move => {
+            println!(
+                "{} Storage mode: move (originals will be deleted)",
+                style("!").yellow()
+            );
+        }
+        FileStorageMode::HardLink => {
+            println!(
+                "{} Storage mode: hard link (same filesystem detected)",
+                style("→").cyan()
+            );
+        }
+    }
+
+    storage_mode
+}
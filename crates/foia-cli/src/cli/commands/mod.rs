@@ -10,16 +10,20 @@ mod db;
 mod discover;
 mod documents;
 mod entities;
+mod export;
 mod helpers;
 mod import;
 mod init;
 mod llm;
 #[cfg(feature = "gis")]
 mod regions;
+mod removal;
+mod requests;
 mod scrape;
 mod serve;
 mod source;
 mod state;
+mod storage;
 
 use std::path::PathBuf;
 
@@ -128,6 +132,12 @@ enum Commands {
         /// Show detailed progress for each file
         #[arg(short = 'P', long)]
         progress: bool,
+        /// ID to hold URL leases under (default: hostname and process ID)
+        #[arg(long)]
+        worker_id: Option<String>,
+        /// Seconds a claimed URL stays reserved before other workers may take it
+        #[arg(long, default_value = "600")]
+        lease_secs: u64,
     },
 
     /// Manage crawl state
@@ -148,6 +158,18 @@ enum Commands {
         command: DbCommands,
     },
 
+    /// Content store maintenance (integrity checks, orphaned files)
+    Storage {
+        #[command(subcommand)]
+        command: StorageCommands,
+    },
+
+    /// Export documents for replay, hand-off or analysis (WACZ, BagIt, Parquet, SQLite)
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+
     /// Scrape documents from one or more sources (crawl + download combined)
     Scrape {
         /// Source IDs to scrape (can specify multiple, or use --all)
@@ -283,6 +305,16 @@ enum Commands {
         /// (requires allow_potentially_insecure_circuits in config)
         #[arg(long)]
         use_arti: bool,
+
+        /// Public mode: mask detected PII and withhold documents not yet
+        /// scanned with `detect-pii`
+        #[arg(long)]
+        public: bool,
+
+        /// Bearer token that enables deleting documents and managing legal
+        /// holds through the API (ignored in public mode)
+        #[arg(long, env = "FOIA_ADMIN_TOKEN", hide_env_values = true)]
+        admin_token: Option<String>,
     },
 
     /// Refresh metadata for existing documents (server date, original filename)
@@ -361,6 +393,16 @@ enum Commands {
         limit: usize,
     },
 
+    /// Detect PII (SSNs, phone numbers, emails, dates of birth, A-numbers,
+    /// street addresses) and store spans per page for public-mode masking
+    DetectPii {
+        /// Source ID (optional, processes all sources if not specified)
+        source_id: Option<String>,
+        /// Limit number of documents to process (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+    },
+
     /// Backfill the document_entities table from existing NER annotations
     BackfillEntities {
         /// Source ID (optional, processes all sources if not specified)
@@ -422,10 +464,27 @@ enum Commands {
         format: String,
     },
 
+    /// Delete documents or manage legal holds and tombstones
+    Documents {
+        #[command(subcommand)]
+        command: DocumentsCommands,
+    },
+
+    /// Track FOIA requests we filed: deadlines, status, correspondence
+    #[command(alias = "request")]
+    Requests {
+        #[command(subcommand)]
+        command: RequestsCommands,
+    },
+
     /// Show document metadata and info
     Info {
         /// Document ID or search term
-        doc_id: String,
+        #[arg(required_unless_present = "bates")]
+        doc_id: Option<String>,
+        /// Find the document and page stamped with a Bates number
+        #[arg(long, conflicts_with = "doc_id")]
+        bates: Option<String>,
     },
 
     /// Output document content to stdout
@@ -549,6 +608,14 @@ enum StateCommands {
         #[arg(long)]
         confirm: bool,
     },
+    /// Return stale in-progress URLs to the queue
+    Recover {
+        /// Source ID (optional, recovers all sources if not specified)
+        source_id: Option<String>,
+        /// Also recover URLs claimed without a lease (only when no downloads are running)
+        #[arg(long)]
+        unleased: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -658,13 +725,19 @@ enum AnnotateCommands {
         #[arg(long)]
         confirm: bool,
     },
+    /// Rebuild email threads and the correspondent graph
+    Threads {
+        /// Source ID (optional, rebuilds all sources if not specified)
+        #[arg(long)]
+        source_id: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
 enum ImportCommands {
-    /// Import documents from WARC (Web Archive) files
+    /// Import documents from WARC (Web Archive) files and WACZ packages
     Warc {
-        /// WARC file(s) to import (supports .warc and .warc.gz)
+        /// WARC file(s) to import (supports .warc, .warc.gz and .wacz)
         files: Vec<PathBuf>,
         /// Source ID to associate imported documents with (auto-detected from URLs if not specified)
         #[arg(short, long)]
@@ -753,6 +826,141 @@ enum ImportCommands {
         #[arg(long, conflicts_with = "r#move")]
         link: bool,
     },
+    /// Import documents from EDRM XML, Summation DII or Relativity-style load files
+    LoadFile {
+        /// Path to the load file (.xml, .dii, or a delimited .csv/.dat/.txt)
+        path: PathBuf,
+        /// Load file format (detected from the extension if not specified)
+        #[arg(short, long, value_enum)]
+        format: Option<import::LoadFileFormat>,
+        /// Source ID to associate imported documents with (required)
+        #[arg(short, long)]
+        source: String,
+        /// Base URL prepended to each document's filename to form its canonical URL
+        #[arg(long)]
+        url_prefix: Option<String>,
+        /// Skip queuing imported URLs for scraper verification
+        #[arg(long)]
+        no_verify: bool,
+        /// Comma-separated tags to apply to all imported documents
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Limit number of documents to import (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+        /// Dry run - show what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+        /// Disable resume support
+        #[arg(long)]
+        no_resume: bool,
+        /// Move files instead of copying (deletes originals after import)
+        #[arg(long, conflicts_with = "link")]
+        r#move: bool,
+        /// Use hard links instead of copying (saves disk space)
+        #[arg(long, conflicts_with = "r#move")]
+        link: bool,
+    },
+    /// Import every file under a directory tree (USB drives, shared folders)
+    Dir {
+        /// Root directory to walk recursively
+        path: PathBuf,
+        /// Source ID for imported documents (fallback for root files with --source-from-folder)
+        #[arg(short, long, required_unless_present = "source_from_folder")]
+        source: Option<String>,
+        /// Only import files matching these globs (e.g. "*.pdf", "Finance/**")
+        #[arg(long)]
+        include: Vec<String>,
+        /// Skip files and folders matching these globs
+        #[arg(long)]
+        exclude: Vec<String>,
+        /// Use each top-level folder name as the source ID (created if missing)
+        #[arg(long)]
+        source_from_folder: bool,
+        /// Tag documents with the names of the folders containing them
+        #[arg(long)]
+        tag_from_folders: bool,
+        /// Comma-separated tags to apply to all imported documents
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Limit number of documents to import (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+        /// Dry run - show what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+        /// Disable resume support
+        #[arg(long)]
+        no_resume: bool,
+        /// Move files instead of copying (deletes originals after import)
+        #[arg(long, conflicts_with = "link")]
+        r#move: bool,
+        /// Use hard links instead of copying (saves disk space)
+        #[arg(long, conflicts_with = "r#move")]
+        link: bool,
+    },
+    /// Import an mbox file or Maildir directory, one document per message
+    Mailbox {
+        /// Path to an mbox file or a Maildir (directory with cur/ and new/)
+        path: PathBuf,
+        /// Source ID to associate imported messages with (required)
+        #[arg(short, long)]
+        source: String,
+        /// Comma-separated tags to apply to all imported messages
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        /// Limit number of messages to import (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+        /// Dry run - show what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+        /// Disable resume support
+        #[arg(long)]
+        no_resume: bool,
+    },
+    /// Import documents from a DocumentCloud project or search
+    Documentcloud {
+        /// Source ID to associate imported documents with (required)
+        #[arg(short, long)]
+        source: String,
+        /// Project ID or URL to import
+        #[arg(long, required_unless_present = "query", conflicts_with = "query")]
+        project: Option<String>,
+        /// Search query to import results for
+        #[arg(short, long)]
+        query: Option<String>,
+        /// Limit number of documents to import (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+        /// Dry run - list documents without downloading them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import files attached to MuckRock FOIA requests
+    Muckrock {
+        /// Source ID to associate imported documents with (required)
+        #[arg(short, long)]
+        source: String,
+        /// Request ID or request page URL to import
+        #[arg(long, conflicts_with_all = ["query", "status", "agency"])]
+        request: Option<String>,
+        /// Search request titles
+        #[arg(short, long)]
+        query: Option<String>,
+        /// Only requests with this status (e.g. done, partial, processed)
+        #[arg(long)]
+        status: Option<String>,
+        /// Only requests to this MuckRock agency ID
+        #[arg(long)]
+        agency: Option<u64>,
+        /// Limit number of requests to fetch (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+        /// Dry run - list files without downloading them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -836,6 +1044,369 @@ enum DbCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum DocumentsCommands {
+    /// Delete documents, their derived data and files, leaving tombstones
+    Delete {
+        /// Document IDs to delete
+        #[arg(required = true)]
+        doc_ids: Vec<String>,
+        /// Reason recorded on the tombstone (e.g. takedown request reference)
+        #[arg(short, long)]
+        reason: Option<String>,
+        /// Keep stored files on disk (only remove database rows)
+        #[arg(long)]
+        keep_files: bool,
+        /// Confirm deletion
+        #[arg(long)]
+        confirm: bool,
+    },
+
+    /// Place a legal hold on a document (blocks deletion and dedup merges)
+    Hold {
+        /// Document ID
+        doc_id: String,
+        /// Reason for the hold
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+
+    /// Release a legal hold
+    Release {
+        /// Document ID
+        doc_id: String,
+    },
+
+    /// List documents under legal hold
+    Holds,
+
+    /// List tombstones of deleted documents
+    Tombstones {
+        /// Limit number of results
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+        /// Clear tombstones for this document ID so it can be re-acquired
+        #[arg(long)]
+        clear: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RequestsCommands {
+    /// Record a request (a draft unless --filed is given)
+    Add {
+        /// Agency the request is addressed to
+        agency: String,
+        /// Short description of the records sought
+        subject: String,
+        /// Tracking number assigned by the agency
+        #[arg(short, long)]
+        tracking: Option<String>,
+        /// Date the agency received the request (YYYY-MM-DD)
+        #[arg(long)]
+        filed: Option<String>,
+        /// Full text or notes on the records requested
+        #[arg(long)]
+        description: Option<String>,
+        /// Days the agency has to respond
+        #[arg(long, default_value = "20")]
+        days: u32,
+        /// Count the response window in calendar days instead of business days
+        #[arg(long)]
+        calendar_days: bool,
+    },
+
+    /// List tracked requests
+    List {
+        /// Filter by status (draft, filed, acknowledged, processing, partial,
+        /// fulfilled, no_records, denied, appealed, withdrawn)
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Filter by agency (substring match)
+        #[arg(short, long)]
+        agency: Option<String>,
+        /// Only requests still awaiting a response
+        #[arg(long)]
+        open: bool,
+        /// Output format (table, json, ids)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Show a request with its history and linked documents
+    Show {
+        /// Request ID or agency tracking number
+        id: String,
+    },
+
+    /// Change a request's details
+    Edit {
+        /// Request ID or agency tracking number
+        id: String,
+        #[arg(long)]
+        agency: Option<String>,
+        #[arg(long)]
+        subject: Option<String>,
+        /// Agency tracking number (empty to clear)
+        #[arg(short, long)]
+        tracking: Option<String>,
+        /// Description (empty to clear)
+        #[arg(long)]
+        description: Option<String>,
+        /// Date the agency received the request (YYYY-MM-DD)
+        #[arg(long)]
+        filed: Option<String>,
+        /// Days the agency has to respond
+        #[arg(long)]
+        days: Option<u32>,
+        /// Extension days claimed by the agency
+        #[arg(long)]
+        extension: Option<u32>,
+        /// Count the response window in calendar days
+        #[arg(long, conflicts_with = "business_days")]
+        calendar_days: bool,
+        /// Count the response window in business days
+        #[arg(long)]
+        business_days: bool,
+    },
+
+    /// Change a request's status (filing starts the deadline clock)
+    Status {
+        /// Request ID or agency tracking number
+        id: String,
+        /// New status
+        status: String,
+        /// Date of the change (YYYY-MM-DD, default today)
+        #[arg(long)]
+        date: Option<String>,
+        /// Note recorded with the change
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// Log correspondence or a note
+    Log {
+        /// Request ID or agency tracking number
+        id: String,
+        /// One-line summary
+        summary: String,
+        /// Entry kind (sent, received, note)
+        #[arg(short, long, default_value = "note")]
+        kind: String,
+        /// Date of the correspondence (YYYY-MM-DD, default today)
+        #[arg(long)]
+        date: Option<String>,
+        /// Stored document holding the letter or email
+        #[arg(long)]
+        document: Option<String>,
+        /// Read the full text of the correspondence from a file
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Link documents released in response to a request
+    Link {
+        /// Request ID or agency tracking number
+        id: String,
+        /// Document IDs
+        #[arg(required = true)]
+        doc_ids: Vec<String>,
+    },
+
+    /// Remove document links from a request
+    Unlink {
+        /// Request ID or agency tracking number
+        id: String,
+        /// Document IDs
+        #[arg(required = true)]
+        doc_ids: Vec<String>,
+    },
+
+    /// Delete a request and its history (linked documents are kept)
+    Delete {
+        /// Request ID or agency tracking number
+        id: String,
+        /// Confirm deletion
+        #[arg(long)]
+        confirm: bool,
+    },
+
+    /// Report open requests past their statutory deadline
+    Overdue {
+        /// Also include requests due within this many days
+        #[arg(short, long)]
+        within: Option<i64>,
+        /// Output format (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Draft a request letter from a template
+    Draft {
+        /// Fill agency, subject and description from a tracked request
+        #[arg(short, long)]
+        request: Option<String>,
+        /// Template name or path (see `requests templates`)
+        #[arg(short, long, default_value = "federal")]
+        template: String,
+        /// Agency profile key from letters.toml, or the agency's name
+        #[arg(short, long)]
+        agency: Option<String>,
+        /// Short description of the records sought
+        #[arg(long)]
+        subject: Option<String>,
+        /// Full description of the records requested
+        #[arg(long)]
+        description: Option<String>,
+        /// Cite a document we already hold (repeatable)
+        #[arg(long = "cite", value_name = "DOC_ID")]
+        cite: Vec<String>,
+        /// Cite every document linked to the --request
+        #[arg(long)]
+        cite_linked: bool,
+        /// Include a fee waiver request
+        #[arg(long)]
+        fee_waiver: bool,
+        /// Include an expedited processing request (needs the
+        /// expedited_justification field)
+        #[arg(long)]
+        expedited: bool,
+        /// Set a template field (repeatable)
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
+        /// Output format (markdown, text, pdf)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        /// Write to a file instead of stdout (required for pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// List letter templates
+    Templates,
+}
+
+#[derive(Subcommand)]
+enum StorageCommands {
+    /// Verify stored files against their recorded SHA-256/BLAKE3 hashes
+    Verify {
+        /// Only verify documents from this source
+        #[arg(short, long)]
+        source: Option<String>,
+        /// Limit number of files to check in this run (0 = unlimited)
+        #[arg(short, long, default_value = "0")]
+        limit: usize,
+        /// Re-fetch missing or corrupted files from their source URL or an archive snapshot
+        #[arg(long)]
+        repair: bool,
+        /// Also report files on disk that no document version references
+        #[arg(long)]
+        orphans: bool,
+        /// Maximum files to check per second (0 = unlimited)
+        #[arg(long, default_value = "0")]
+        rate: f64,
+        /// Number of versions to load per batch (default: 1000)
+        #[arg(long, default_value = "1000")]
+        batch_size: usize,
+        /// Start a fresh pass instead of resuming an interrupted one
+        #[arg(long)]
+        no_resume: bool,
+        /// Run continuously, starting a new pass every --interval seconds
+        #[arg(long)]
+        daemon: bool,
+        /// Seconds between passes in daemon mode (default: 86400)
+        #[arg(long, default_value = "86400")]
+        interval: u64,
+    },
+
+    /// Quarantine unreferenced files and purge expired quarantine batches
+    Gc {
+        /// Only report what would be quarantined and reclaimed
+        #[arg(long)]
+        dry_run: bool,
+        /// Days a file stays in quarantine before it is deleted (default: 7)
+        #[arg(long, default_value = "7")]
+        grace_days: u64,
+        /// Ignore unreferenced files changed on disk within this many hours (default: 24)
+        #[arg(long, default_value = "24")]
+        min_age_hours: u64,
+        /// Number of versions to load per batch (default: 1000)
+        #[arg(long, default_value = "1000")]
+        batch_size: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportCommands {
+    /// Package a source's fetched documents as a WACZ web archive
+    Wacz {
+        /// Source ID to export
+        source_id: String,
+        /// Output .wacz file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Package title (defaults to the source name)
+        #[arg(long)]
+        title: Option<String>,
+        /// Number of versions to load per batch (default: 1000)
+        #[arg(long, default_value = "1000")]
+        batch_size: usize,
+    },
+    /// Package documents as a signed BagIt evidence bundle with provenance
+    Bundle {
+        /// Document IDs to include
+        #[arg(conflicts_with_all = ["query", "source", "tag"])]
+        ids: Vec<String>,
+        /// File listing document IDs, one per line
+        #[arg(long, conflicts_with_all = ["query", "source", "tag"])]
+        ids_file: Option<PathBuf>,
+        /// Include documents whose title or synopsis matches this text
+        #[arg(short, long)]
+        query: Option<String>,
+        /// Include documents from this source
+        #[arg(short, long)]
+        source: Option<String>,
+        /// Include documents with this tag (repeatable)
+        #[arg(short, long)]
+        tag: Vec<String>,
+        /// Output directory, or a .zip file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// GPG key to sign with (defaults to gpg's default key)
+        #[arg(long, conflicts_with = "no_sign")]
+        sign_key: Option<String>,
+        /// Skip signing the checksum file
+        #[arg(long)]
+        no_sign: bool,
+        /// Write (and sign) the bundle even if files are missing or fail
+        /// their hash check
+        #[arg(long)]
+        allow_incomplete: bool,
+    },
+    /// Export documents, pages, entities and analysis results for data analysis
+    #[cfg(feature = "dataset")]
+    Dataset {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "parquet")]
+        format: export::DatasetFormat,
+        /// Output directory for Parquet, or database file for SQLite
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Only export documents from this source
+        #[arg(short, long)]
+        source: Option<String>,
+        /// Comma-separated tables to export (documents, versions, pages, entities, analysis_results; default: all)
+        #[arg(long, value_delimiter = ',')]
+        tables: Vec<foia::storage::dataset::DatasetTable>,
+        /// Leave extracted, page and analysis text out of the export
+        #[arg(long)]
+        no_text: bool,
+        /// Number of rows to load per batch (default: 1000)
+        #[arg(long, default_value = "1000")]
+        batch_size: usize,
+    },
+}
+
 /// Run the CLI.
 pub async fn run() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -869,8 +1440,15 @@ pub async fn run() -> anyhow::Result<()> {
             | Commands::Source { .. }
             | Commands::Config { .. }
             | Commands::Serve { .. }
+            | Commands::DetectPii { .. }
             | Commands::BackfillEntities { .. }
             | Commands::SearchEntities { .. }
+            | Commands::Documents { .. }
+            | Commands::Requests { .. }
+            | Commands::Storage {
+                command: StorageCommands::Gc { .. }
+            }
+            | Commands::Export { .. }
     );
     if needs_tor {
         if let Err(e) = config.privacy.check_tor_availability() {
@@ -901,6 +1479,8 @@ pub async fn run() -> anyhow::Result<()> {
             workers,
             limit,
             progress,
+            worker_id,
+            lease_secs,
         } => {
             scrape::cmd_download(
                 &settings,
@@ -909,6 +1489,8 @@ pub async fn run() -> anyhow::Result<()> {
                 limit,
                 progress,
                 &config.privacy,
+                worker_id.as_deref(),
+                lease_secs,
             )
             .await
         }
@@ -919,6 +1501,10 @@ pub async fn run() -> anyhow::Result<()> {
             StateCommands::Clear { source_id, confirm } => {
                 state::cmd_crawl_clear(&settings, &source_id, confirm).await
             }
+            StateCommands::Recover {
+                source_id,
+                unleased,
+            } => state::cmd_crawl_recover(&settings, source_id.as_deref(), unleased).await,
         },
         Commands::Config { command } => match command {
             ConfigCommands::Transfer { file } => {
@@ -973,6 +1559,273 @@ pub async fn run() -> anyhow::Result<()> {
                 regions::cmd_load_regions(&settings, file.as_deref()).await
             }
         },
+        Commands::Documents { command } => match command {
+            DocumentsCommands::Delete {
+                doc_ids,
+                reason,
+                keep_files,
+                confirm,
+            } => {
+                removal::cmd_documents_delete(
+                    &settings,
+                    &doc_ids,
+                    reason.as_deref(),
+                    keep_files,
+                    confirm,
+                )
+                .await
+            }
+            DocumentsCommands::Hold { doc_id, reason } => {
+                removal::cmd_documents_hold(&settings, &doc_id, reason.as_deref()).await
+            }
+            DocumentsCommands::Release { doc_id } => {
+                removal::cmd_documents_release(&settings, &doc_id).await
+            }
+            DocumentsCommands::Holds => removal::cmd_documents_holds(&settings).await,
+            DocumentsCommands::Tombstones { limit, clear } => {
+                removal::cmd_documents_tombstones(&settings, limit, clear.as_deref()).await
+            }
+        },
+        Commands::Requests { command } => match command {
+            RequestsCommands::Add {
+                agency,
+                subject,
+                tracking,
+                filed,
+                description,
+                days,
+                calendar_days,
+            } => {
+                requests::cmd_requests_add(
+                    &settings,
+                    &agency,
+                    &subject,
+                    tracking,
+                    description,
+                    filed.as_deref(),
+                    days,
+                    calendar_days,
+                )
+                .await
+            }
+            RequestsCommands::List {
+                status,
+                agency,
+                open,
+                format,
+            } => {
+                requests::cmd_requests_list(&settings, status.as_deref(), agency, open, &format)
+                    .await
+            }
+            RequestsCommands::Show { id } => requests::cmd_requests_show(&settings, &id).await,
+            RequestsCommands::Edit {
+                id,
+                agency,
+                subject,
+                tracking,
+                description,
+                filed,
+                days,
+                extension,
+                calendar_days,
+                business_days,
+            } => {
+                let deadline_rule = if calendar_days {
+                    Some(foia::models::DeadlineRule::Calendar)
+                } else if business_days {
+                    Some(foia::models::DeadlineRule::Business)
+                } else {
+                    None
+                };
+                let edits = requests::RequestEdits {
+                    agency,
+                    subject,
+                    tracking_number: tracking,
+                    description,
+                    filed,
+                    response_days: days,
+                    extension_days: extension,
+                    deadline_rule,
+                };
+                requests::cmd_requests_edit(&settings, &id, edits).await
+            }
+            RequestsCommands::Status {
+                id,
+                status,
+                date,
+                note,
+            } => {
+                requests::cmd_requests_status(
+                    &settings,
+                    &id,
+                    &status,
+                    date.as_deref(),
+                    note.as_deref(),
+                )
+                .await
+            }
+            RequestsCommands::Log {
+                id,
+                summary,
+                kind,
+                date,
+                document,
+                file,
+            } => {
+                requests::cmd_requests_log(
+                    &settings,
+                    &id,
+                    &kind,
+                    &summary,
+                    date.as_deref(),
+                    document.as_deref(),
+                    file.as_deref(),
+                )
+                .await
+            }
+            RequestsCommands::Link { id, doc_ids } => {
+                requests::cmd_requests_link(&settings, &id, &doc_ids).await
+            }
+            RequestsCommands::Unlink { id, doc_ids } => {
+                requests::cmd_requests_unlink(&settings, &id, &doc_ids).await
+            }
+            RequestsCommands::Delete { id, confirm } => {
+                requests::cmd_requests_delete(&settings, &id, confirm).await
+            }
+            RequestsCommands::Overdue { within, format } => {
+                requests::cmd_requests_overdue(&settings, within, &format).await
+            }
+            RequestsCommands::Draft {
+                request,
+                template,
+                agency,
+                subject,
+                description,
+                cite,
+                cite_linked,
+                fee_waiver,
+                expedited,
+                set,
+                format,
+                output,
+            } => {
+                let opts = requests::DraftOptions {
+                    request,
+                    template,
+                    agency,
+                    subject,
+                    description,
+                    cite,
+                    cite_linked,
+                    fee_waiver,
+                    expedited,
+                    set,
+                    format,
+                    output,
+                };
+                requests::cmd_requests_draft(&settings, opts).await
+            }
+            RequestsCommands::Templates => requests::cmd_requests_templates(&settings).await,
+        },
+        Commands::Storage { command } => match command {
+            StorageCommands::Verify {
+                source,
+                limit,
+                repair,
+                orphans,
+                rate,
+                batch_size,
+                no_resume,
+                daemon,
+                interval,
+            } => {
+                storage::cmd_storage_verify(
+                    &settings,
+                    source.as_deref(),
+                    limit,
+                    repair,
+                    orphans,
+                    rate,
+                    batch_size,
+                    !no_resume,
+                    daemon,
+                    interval,
+                    &config.privacy,
+                )
+                .await
+            }
+            StorageCommands::Gc {
+                dry_run,
+                grace_days,
+                min_age_hours,
+                batch_size,
+            } => {
+                storage::cmd_storage_gc(&settings, dry_run, grace_days, min_age_hours, batch_size)
+                    .await
+            }
+        },
+        Commands::Export { command } => match command {
+            ExportCommands::Wacz {
+                source_id,
+                output,
+                title,
+                batch_size,
+            } => {
+                export::cmd_export_wacz(
+                    &settings,
+                    &source_id,
+                    &output,
+                    title.as_deref(),
+                    batch_size,
+                )
+                .await
+            }
+            ExportCommands::Bundle {
+                ids,
+                ids_file,
+                query,
+                source,
+                tag,
+                output,
+                sign_key,
+                no_sign,
+                allow_incomplete,
+            } => {
+                export::cmd_export_bundle(
+                    &settings,
+                    &ids,
+                    ids_file.as_deref(),
+                    query.as_deref(),
+                    source.as_deref(),
+                    &tag,
+                    &output,
+                    !no_sign,
+                    sign_key.as_deref(),
+                    allow_incomplete,
+                )
+                .await
+            }
+            #[cfg(feature = "dataset")]
+            ExportCommands::Dataset {
+                format,
+                output,
+                source,
+                tables,
+                no_text,
+                batch_size,
+            } => {
+                export::cmd_export_dataset(
+                    &settings,
+                    format,
+                    &output,
+                    source.as_deref(),
+                    &tables,
+                    !no_text,
+                    batch_size,
+                )
+                .await
+            }
+        },
         Commands::Scrape {
             source_ids,
             all,
@@ -1056,6 +1909,8 @@ pub async fn run() -> anyhow::Result<()> {
             no_migrate,
             no_hidden_service,
             use_arti,
+            public,
+            admin_token,
         } => {
             serve::cmd_serve(
                 &settings,
@@ -1064,6 +1919,8 @@ pub async fn run() -> anyhow::Result<()> {
                 no_migrate,
                 no_hidden_service,
                 use_arti,
+                public,
+                admin_token,
             )
             .await
         }
@@ -1100,6 +1957,9 @@ pub async fn run() -> anyhow::Result<()> {
             Some(AnnotateCommands::Reset { source_id, confirm }) => {
                 annotate::cmd_annotate_reset(&settings, source_id.as_deref(), confirm).await
             }
+            Some(AnnotateCommands::Threads { source_id }) => {
+                annotate::cmd_annotate_threads(&settings, source_id.as_deref()).await
+            }
             None => {
                 let strategy = if deep {
                     ExecutionStrategy::Deep
@@ -1130,6 +1990,9 @@ pub async fn run() -> anyhow::Result<()> {
         Commands::ExtractEntities { source_id, limit } => {
             annotate::cmd_extract_entities(&settings, source_id.as_deref(), limit).await
         }
+        Commands::DetectPii { source_id, limit } => {
+            annotate::cmd_detect_pii(&settings, source_id.as_deref(), limit).await
+        }
         Commands::BackfillEntities { source_id, limit } => {
             entities::cmd_backfill_entities(&settings, source_id.as_deref(), limit).await
         }
@@ -1173,7 +2036,9 @@ pub async fn run() -> anyhow::Result<()> {
             )
             .await
         }
-        Commands::Info { doc_id } => documents::cmd_info(&settings, &doc_id).await,
+        Commands::Info { doc_id, bates } => {
+            documents::cmd_info(&settings, doc_id.as_deref(), bates.as_deref()).await
+        }
         Commands::Read { doc_id, text } => documents::cmd_read(&settings, &doc_id, text).await,
         Commands::Search {
             query,
@@ -1252,6 +2117,119 @@ pub async fn run() -> anyhow::Result<()> {
                 )
                 .await
             }
+            ImportCommands::LoadFile {
+                path,
+                format,
+                source,
+                url_prefix,
+                no_verify,
+                tag,
+                limit,
+                dry_run,
+                no_resume,
+                r#move,
+                link,
+            } => {
+                import::cmd_import_load_file(
+                    &settings,
+                    &path,
+                    format,
+                    &source,
+                    url_prefix.as_deref(),
+                    !no_verify,
+                    &tag,
+                    limit,
+                    dry_run,
+                    !no_resume,
+                    r#move,
+                    link,
+                )
+                .await
+            }
+            ImportCommands::Dir {
+                path,
+                source,
+                include,
+                exclude,
+                source_from_folder,
+                tag_from_folders,
+                tag,
+                limit,
+                dry_run,
+                no_resume,
+                r#move,
+                link,
+            } => {
+                import::cmd_import_dir(
+                    &settings,
+                    &path,
+                    source.as_deref(),
+                    &include,
+                    &exclude,
+                    source_from_folder,
+                    tag_from_folders,
+                    &tag,
+                    limit,
+                    dry_run,
+                    !no_resume,
+                    r#move,
+                    link,
+                )
+                .await
+            }
+            ImportCommands::Mailbox {
+                path,
+                source,
+                tag,
+                limit,
+                dry_run,
+                no_resume,
+            } => {
+                import::cmd_import_mailbox(
+                    &settings, &path, &source, &tag, limit, dry_run, !no_resume,
+                )
+                .await
+            }
+            ImportCommands::Documentcloud {
+                source,
+                project,
+                query,
+                limit,
+                dry_run,
+            } => {
+                import::cmd_import_documentcloud(
+                    &settings,
+                    &source,
+                    project.as_deref(),
+                    query.as_deref(),
+                    limit,
+                    dry_run,
+                    &config.privacy,
+                )
+                .await
+            }
+            ImportCommands::Muckrock {
+                source,
+                request,
+                query,
+                status,
+                agency,
+                limit,
+                dry_run,
+            } => {
+                import::cmd_import_muckrock(
+                    &settings,
+                    &source,
+                    request.as_deref(),
+                    query.as_deref(),
+                    status.as_deref(),
+                    agency,
+                    limit,
+                    dry_run,
+                    &config.privacy,
+                )
+                .await
+            }
         },
         Commands::Discover { command } => match command {
             DiscoverCommands::Pattern {
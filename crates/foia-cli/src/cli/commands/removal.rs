@@ -0,0 +1,195 @@
+//! Document deletion, tombstone and legal-hold commands.
+
+use console::style;
+
+use foia::config::Settings;
+use foia::repository::diesel_document::DeleteOutcome;
+use foia::storage::remove_version_files;
+
+use super::helpers::truncate;
+
+/// Delete documents with their dependent rows and stored files.
+///
+/// Each deletion leaves tombstones so the scraper will not re-download the
+/// document. Files are only removed when no other document shares the same
+/// content; otherwise they are kept and the overlap is reported.
+pub async fn cmd_documents_delete(
+    settings: &Settings,
+    doc_ids: &[String],
+    reason: Option<&str>,
+    keep_files: bool,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let doc_repo = repos.documents;
+
+    if !confirm {
+        println!(
+            "{} This will permanently delete {} document(s) and all derived data:",
+            style("!").yellow(),
+            doc_ids.len()
+        );
+        for id in doc_ids {
+            match doc_repo.get(id).await? {
+                Some(doc) => println!("  {} {}", id, truncate(&doc.title, 60)),
+                None => println!("  {} {}", id, style("(not found)").dim()),
+            }
+        }
+        println!("  Use --confirm to proceed.");
+        return Ok(());
+    }
+
+    let mut deleted = 0usize;
+    for id in doc_ids {
+        match doc_repo.delete_with_tombstone(id, reason).await? {
+            DeleteOutcome::NotFound => {
+                println!("{} Document not found: {}", style("✗").red(), id);
+            }
+            DeleteOutcome::OnHold(hold) => {
+                println!(
+                    "{} {} is under legal hold since {}{}",
+                    style("✗").red(),
+                    id,
+                    hold.placed_at.format("%Y-%m-%d"),
+                    hold.reason.map(|r| format!(" ({})", r)).unwrap_or_default()
+                );
+            }
+            DeleteOutcome::Deleted {
+                source_url,
+                title,
+                versions,
+            } => {
+                deleted += 1;
+                println!(
+                    "{} Deleted {} {}",
+                    style("✓").green(),
+                    id,
+                    truncate(&title, 60)
+                );
+
+                if keep_files {
+                    continue;
+                }
+                let files = remove_version_files(
+                    &doc_repo,
+                    &settings.documents_dir,
+                    id,
+                    &source_url,
+                    &title,
+                    &versions,
+                )
+                .await?;
+                for path in &files.removed {
+                    println!("  Removed {}", path.display());
+                }
+                for (path, shared) in &files.shared {
+                    println!(
+                        "  {} Kept {}: content shared with {} other document(s)",
+                        style("!").yellow(),
+                        path.display(),
+                        shared
+                    );
+                }
+            }
+        }
+    }
+
+    println!(
+        "\n{} Deleted {} of {} document(s)",
+        style("✓").green(),
+        deleted,
+        doc_ids.len()
+    );
+
+    Ok(())
+}
+
+/// Place a legal hold on a document.
+pub async fn cmd_documents_hold(
+    settings: &Settings,
+    doc_id: &str,
+    reason: Option<&str>,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    if repos.documents.place_legal_hold(doc_id, reason).await? {
+        println!("{} Legal hold placed on {}", style("✓").green(), doc_id);
+    } else {
+        println!("{} Document not found: {}", style("✗").red(), doc_id);
+    }
+    Ok(())
+}
+
+/// Release a legal hold on a document.
+pub async fn cmd_documents_release(settings: &Settings, doc_id: &str) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    if repos.documents.release_legal_hold(doc_id).await? {
+        println!("{} Legal hold released on {}", style("✓").green(), doc_id);
+    } else {
+        println!("{} {} is not under legal hold", style("!").yellow(), doc_id);
+    }
+    Ok(())
+}
+
+/// List documents under legal hold.
+pub async fn cmd_documents_holds(settings: &Settings) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let holds = repos.documents.list_legal_holds().await?;
+
+    if holds.is_empty() {
+        println!("No documents under legal hold");
+        return Ok(());
+    }
+
+    println!("{:<38} {:<12} Reason", "Document", "Since");
+    println!("{}", "-".repeat(70));
+    for hold in holds {
+        println!(
+            "{:<38} {:<12} {}",
+            hold.document_id,
+            hold.placed_at.format("%Y-%m-%d"),
+            hold.reason.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// List tombstones, or clear those for a document so it can be re-acquired.
+pub async fn cmd_documents_tombstones(
+    settings: &Settings,
+    limit: usize,
+    clear: Option<&str>,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let doc_repo = repos.documents;
+
+    if let Some(doc_id) = clear {
+        let removed = doc_repo.clear_tombstones(doc_id).await?;
+        println!(
+            "{} Cleared {} tombstone(s) for {}",
+            style("✓").green(),
+            removed,
+            doc_id
+        );
+        return Ok(());
+    }
+
+    let tombstones = doc_repo.list_tombstones(limit).await?;
+    if tombstones.is_empty() {
+        println!("No tombstones");
+        return Ok(());
+    }
+
+    for t in tombstones {
+        println!(
+            "{} {} [{}] {}",
+            t.deleted_at.format("%Y-%m-%d"),
+            t.document_id,
+            t.source_id,
+            truncate(&t.source_url, 60)
+        );
+        if let Some(reason) = t.reason {
+            println!("    {}", style(reason).dim());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,641 @@
+//! Tracking for FOIA requests we filed.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use console::style;
+
+use foia::config::Settings;
+use foia::letters::{CitedDocument, LetterContext, LetterFormat, LetterProfiles, LetterTemplate};
+use foia::models::{DeadlineRule, FoiaRequest, RequestEventKind, RequestStatus};
+use foia::repository::diesel_foia_request::{FoiaRequestFilter, NewRequestEvent};
+use foia::repository::{DieselError, DieselFoiaRequestRepository};
+
+use super::helpers::truncate;
+
+/// Inputs for `requests draft`; fields given here override profiles and the
+/// tracked request.
+#[derive(Debug, Default)]
+pub struct DraftOptions {
+    pub request: Option<String>,
+    pub template: String,
+    pub agency: Option<String>,
+    pub subject: Option<String>,
+    pub description: Option<String>,
+    pub cite: Vec<String>,
+    pub cite_linked: bool,
+    pub fee_waiver: bool,
+    pub expedited: bool,
+    pub set: Vec<String>,
+    pub format: String,
+    pub output: Option<PathBuf>,
+}
+
+/// Field changes for `requests edit`; `None` leaves a field unchanged.
+#[derive(Debug, Default)]
+pub struct RequestEdits {
+    pub agency: Option<String>,
+    pub subject: Option<String>,
+    pub tracking_number: Option<String>,
+    pub description: Option<String>,
+    pub filed: Option<String>,
+    pub response_days: Option<u32>,
+    pub extension_days: Option<u32>,
+    pub deadline_rule: Option<DeadlineRule>,
+}
+
+fn parse_date(s: &str) -> anyhow::Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}' (expected YYYY-MM-DD)", s))
+}
+
+/// Timestamp for an event on `date`, or now if no date was given.
+fn event_time(date: Option<&str>) -> anyhow::Result<DateTime<Utc>> {
+    match date {
+        Some(d) => Ok(parse_date(d)?.and_time(NaiveTime::MIN).and_utc()),
+        None => Ok(Utc::now()),
+    }
+}
+
+fn parse_status(s: &str) -> anyhow::Result<RequestStatus> {
+    RequestStatus::from_str(s).ok_or_else(|| {
+        let valid: Vec<_> = RequestStatus::ALL.iter().map(|s| s.as_str()).collect();
+        anyhow::anyhow!("Unknown status '{}' (valid: {})", s, valid.join(", "))
+    })
+}
+
+async fn find_request(
+    repo: &DieselFoiaRequestRepository,
+    id: &str,
+) -> anyhow::Result<Option<FoiaRequest>> {
+    let request = repo.find(id).await?;
+    if request.is_none() {
+        println!("{} Request not found: {}", style("✗").red(), id);
+    }
+    Ok(request)
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn format_due(request: &FoiaRequest, today: NaiveDate) -> String {
+    match (request.due_date(), request.days_remaining(today)) {
+        (Some(due), Some(days)) if days < 0 => format!("{} ({} overdue)", due, -days),
+        (Some(due), Some(days)) => format!("{} ({} left)", due, days),
+        (Some(due), None) => due.to_string(),
+        (None, _) => "-".to_string(),
+    }
+}
+
+fn request_json(request: &FoiaRequest, today: NaiveDate) -> serde_json::Value {
+    serde_json::json!({
+        "id": request.id,
+        "agency": request.agency,
+        "tracking_number": request.tracking_number,
+        "subject": request.subject,
+        "status": request.status.as_str(),
+        "filed_at": request.filed_at,
+        "due_date": request.due_date(),
+        "days_remaining": request.days_remaining(today),
+        "deadline_rule": request.deadline_rule.as_str(),
+        "response_days": request.response_days,
+        "extension_days": request.extension_days,
+        "closed_at": request.closed_at,
+    })
+}
+
+fn print_table(requests: &[FoiaRequest], today: NaiveDate) {
+    println!(
+        "\n{:<36}  {:<20}  {:<30}  {:<12}  Due",
+        "ID", "Agency", "Subject", "Status"
+    );
+    println!("{}", "-".repeat(130));
+    for request in requests {
+        println!(
+            "{:<36}  {:<20}  {:<30}  {:<12}  {}",
+            request.id,
+            truncate(&request.agency, 20),
+            truncate(&request.subject, 30),
+            request.status.as_str(),
+            format_due(request, today)
+        );
+    }
+}
+
+/// Record a new request, as a draft or already filed.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_requests_add(
+    settings: &Settings,
+    agency: &str,
+    subject: &str,
+    tracking_number: Option<String>,
+    description: Option<String>,
+    filed: Option<&str>,
+    response_days: u32,
+    calendar_days: bool,
+) -> anyhow::Result<()> {
+    let filed_at = filed.map(|d| event_time(Some(d))).transpose()?;
+    let repo = settings.repositories()?.foia_requests;
+
+    let mut request = FoiaRequest::new(agency, subject);
+    request.tracking_number = tracking_number;
+    request.description = description;
+    request.response_days = response_days;
+    if calendar_days {
+        request.deadline_rule = DeadlineRule::Calendar;
+    }
+    repo.create(&request).await?;
+
+    if let Some(at) = filed_at {
+        if let Some(filed) = repo
+            .set_status(&request.id, RequestStatus::Filed, at, None)
+            .await?
+        {
+            request = filed;
+        }
+    }
+
+    println!("{} Added request {}", style("✓").green(), request.id);
+    if let Some(due) = request.due_date() {
+        println!("  Response due {}", due);
+    }
+    Ok(())
+}
+
+/// List tracked requests.
+pub async fn cmd_requests_list(
+    settings: &Settings,
+    status: Option<&str>,
+    agency: Option<String>,
+    open_only: bool,
+    format: &str,
+) -> anyhow::Result<()> {
+    let filter = FoiaRequestFilter {
+        status: status.map(parse_status).transpose()?,
+        agency,
+        open_only,
+    };
+    let repo = settings.repositories()?.foia_requests;
+    let requests = repo.list(&filter).await?;
+
+    if requests.is_empty() {
+        println!("{} No requests found", style("!").yellow());
+        return Ok(());
+    }
+
+    let today = today();
+    match format {
+        "json" => {
+            let output: Vec<_> = requests.iter().map(|r| request_json(r, today)).collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        "ids" => {
+            for request in &requests {
+                println!("{}", request.id);
+            }
+        }
+        _ => {
+            print_table(&requests, today);
+            println!("\n{} requests", requests.len());
+        }
+    }
+    Ok(())
+}
+
+/// Show a request with its history and linked documents.
+pub async fn cmd_requests_show(settings: &Settings, id: &str) -> anyhow::Result<()> {
+    let repo = settings.repositories()?.foia_requests;
+    let Some(request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+    let today = today();
+
+    println!("\n{}", style(&request.subject).bold());
+    println!("  ID:        {}", request.id);
+    println!("  Agency:    {}", request.agency);
+    if let Some(tracking) = &request.tracking_number {
+        println!("  Tracking:  {}", tracking);
+    }
+    println!("  Status:    {}", request.status.as_str());
+    if let Some(filed) = request.filed_at {
+        println!("  Filed:     {}", filed);
+    }
+    println!(
+        "  Deadline:  {} {} days{}",
+        request.response_days,
+        request.deadline_rule.as_str(),
+        if request.extension_days > 0 {
+            format!(" + {} extension", request.extension_days)
+        } else {
+            String::new()
+        }
+    );
+    println!("  Due:       {}", format_due(&request, today));
+    if let Some(closed) = request.closed_at {
+        println!("  Closed:    {}", closed);
+    }
+    if let Some(description) = &request.description {
+        println!("\n{}", description);
+    }
+
+    let events = repo.events(&request.id).await?;
+    if !events.is_empty() {
+        println!("\n{}", style("History").bold());
+        for event in events {
+            let what = match (event.kind, event.status) {
+                (RequestEventKind::Status, Some(status)) => format!("→ {}", status.as_str()),
+                (kind, _) => kind.as_str().to_string(),
+            };
+            println!(
+                "  {}  {:<14} {}",
+                event.occurred_at.format("%Y-%m-%d"),
+                what,
+                event.summary.unwrap_or_default()
+            );
+            if let Some(doc_id) = event.document_id {
+                println!("              {}", style(doc_id).dim());
+            }
+        }
+    }
+
+    let documents = repo.documents(&request.id).await?;
+    if !documents.is_empty() {
+        println!("\n{}", style("Documents").bold());
+        for doc in documents {
+            println!("  {}  {}", doc.document_id, truncate(&doc.title, 60));
+        }
+    }
+    Ok(())
+}
+
+/// Change a request's details.
+pub async fn cmd_requests_edit(
+    settings: &Settings,
+    id: &str,
+    edits: RequestEdits,
+) -> anyhow::Result<()> {
+    let filed_at = edits.filed.as_deref().map(parse_date).transpose()?;
+    let repo = settings.repositories()?.foia_requests;
+    let Some(mut request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+
+    if let Some(agency) = edits.agency {
+        request.agency = agency;
+    }
+    if let Some(subject) = edits.subject {
+        request.subject = subject;
+    }
+    if let Some(tracking) = edits.tracking_number {
+        request.tracking_number = Some(tracking).filter(|t| !t.is_empty());
+    }
+    if let Some(description) = edits.description {
+        request.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if filed_at.is_some() {
+        request.filed_at = filed_at;
+    }
+    if let Some(days) = edits.response_days {
+        request.response_days = days;
+    }
+    if let Some(days) = edits.extension_days {
+        request.extension_days = days;
+    }
+    if let Some(rule) = edits.deadline_rule {
+        request.deadline_rule = rule;
+    }
+
+    repo.update(&request).await?;
+    println!("{} Updated request {}", style("✓").green(), request.id);
+    if let Some(due) = request.due_date() {
+        println!("  Response due {}", due);
+    }
+    Ok(())
+}
+
+/// Change a request's status.
+pub async fn cmd_requests_status(
+    settings: &Settings,
+    id: &str,
+    status: &str,
+    date: Option<&str>,
+    note: Option<&str>,
+) -> anyhow::Result<()> {
+    let status = parse_status(status)?;
+    let at = event_time(date)?;
+    let repo = settings.repositories()?.foia_requests;
+    let Some(request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+
+    if let Some(updated) = repo.set_status(&request.id, status, at, note).await? {
+        println!(
+            "{} {} → {}",
+            style("✓").green(),
+            updated.id,
+            updated.status.as_str()
+        );
+        if updated.status.is_open() {
+            println!("  Due: {}", format_due(&updated, today()));
+        }
+    }
+    Ok(())
+}
+
+/// Log correspondence or a note on a request.
+pub async fn cmd_requests_log(
+    settings: &Settings,
+    id: &str,
+    kind: &str,
+    summary: &str,
+    date: Option<&str>,
+    document_id: Option<&str>,
+    file: Option<&Path>,
+) -> anyhow::Result<()> {
+    let kind = match RequestEventKind::from_str(kind) {
+        Some(RequestEventKind::Status) | None => {
+            anyhow::bail!("Unknown kind '{}' (valid: sent, received, note)", kind)
+        }
+        Some(kind) => kind,
+    };
+    let occurred_at = event_time(date)?;
+    let body = file.map(std::fs::read_to_string).transpose()?;
+
+    let repos = settings.repositories()?;
+    let repo = repos.foia_requests;
+    let Some(request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+    if let Some(doc_id) = document_id {
+        if repos.documents.get(doc_id).await?.is_none() {
+            println!("{} Document not found: {}", style("✗").red(), doc_id);
+            return Ok(());
+        }
+    }
+
+    repo.add_event(
+        &request.id,
+        kind,
+        &NewRequestEvent {
+            summary: Some(summary),
+            body: body.as_deref(),
+            document_id,
+            occurred_at: Some(occurred_at),
+        },
+    )
+    .await?;
+    println!(
+        "{} Logged {} on {}",
+        style("✓").green(),
+        kind.as_str(),
+        request.id
+    );
+    Ok(())
+}
+
+/// Link released documents to a request.
+pub async fn cmd_requests_link(
+    settings: &Settings,
+    id: &str,
+    doc_ids: &[String],
+) -> anyhow::Result<()> {
+    let repo = settings.repositories()?.foia_requests;
+    let Some(request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+
+    for doc_id in doc_ids {
+        match repo.link_document(&request.id, doc_id).await {
+            Ok(true) => println!("{} Linked {}", style("✓").green(), doc_id),
+            Ok(false) => println!("{} {} is already linked", style("!").yellow(), doc_id),
+            Err(DieselError::NotFound) => {
+                println!("{} Document not found: {}", style("✗").red(), doc_id)
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+/// Remove document links from a request.
+pub async fn cmd_requests_unlink(
+    settings: &Settings,
+    id: &str,
+    doc_ids: &[String],
+) -> anyhow::Result<()> {
+    let repo = settings.repositories()?.foia_requests;
+    let Some(request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+
+    for doc_id in doc_ids {
+        if repo.unlink_document(&request.id, doc_id).await? {
+            println!("{} Unlinked {}", style("✓").green(), doc_id);
+        } else {
+            println!("{} {} is not linked", style("!").yellow(), doc_id);
+        }
+    }
+    Ok(())
+}
+
+/// Delete a request with its history. Linked documents are kept.
+pub async fn cmd_requests_delete(
+    settings: &Settings,
+    id: &str,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    let repo = settings.repositories()?.foia_requests;
+    let Some(request) = find_request(&repo, id).await? else {
+        return Ok(());
+    };
+
+    if !confirm {
+        println!(
+            "{} This will delete request {} ({}) and its history. Use --confirm to proceed.",
+            style("!").yellow(),
+            request.id,
+            truncate(&request.subject, 40)
+        );
+        return Ok(());
+    }
+
+    repo.delete(&request.id).await?;
+    println!("{} Deleted request {}", style("✓").green(), request.id);
+    Ok(())
+}
+
+/// Report open requests past their deadline, or due within `within` days.
+pub async fn cmd_requests_overdue(
+    settings: &Settings,
+    within: Option<i64>,
+    format: &str,
+) -> anyhow::Result<()> {
+    let repo = settings.repositories()?.foia_requests;
+    let today = today();
+    let due = repo.due_within(today, within.unwrap_or(-1)).await?;
+
+    if due.is_empty() {
+        match within {
+            Some(days) => println!(
+                "{} No open requests due within {} days",
+                style("✓").green(),
+                days
+            ),
+            None => println!("{} No overdue requests", style("✓").green()),
+        }
+        return Ok(());
+    }
+
+    match format {
+        "json" => {
+            let output: Vec<_> = due.iter().map(|(r, _)| request_json(r, today)).collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        _ => {
+            let overdue = due.iter().filter(|(_, days)| *days < 0).count();
+            let total = due.len();
+            let requests: Vec<_> = due.into_iter().map(|(r, _)| r).collect();
+            print_table(&requests, today);
+            println!("\n{} overdue, {} due soon", overdue, total - overdue);
+        }
+    }
+    Ok(())
+}
+
+/// Where letter templates and `letters.toml` live.
+fn letters_dir(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("letters")
+}
+
+/// Render a request letter from a template.
+pub async fn cmd_requests_draft(settings: &Settings, opts: DraftOptions) -> anyhow::Result<()> {
+    let format = LetterFormat::from_str(&opts.format).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown format '{}' (valid: markdown, text, pdf)",
+            opts.format
+        )
+    })?;
+    if format == LetterFormat::Pdf && opts.output.is_none() {
+        anyhow::bail!("PDF output needs --output");
+    }
+    let set: Vec<(&str, &str)> = opts
+        .set
+        .iter()
+        .map(|kv| {
+            kv.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Invalid --set '{}' (expected key=value)", kv))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let dir = letters_dir(settings);
+    let template = LetterTemplate::load(&dir, &opts.template)?;
+    let profiles = LetterProfiles::load(&dir)?;
+    let repos = settings.repositories()?;
+
+    let request = match &opts.request {
+        Some(id) => match find_request(&repos.foia_requests, id).await? {
+            Some(request) => Some(request),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
+    let agency = opts
+        .agency
+        .as_deref()
+        .or(request.as_ref().map(|r| r.agency.as_str()));
+    let mut ctx = LetterContext::new(&profiles, agency.and_then(|a| profiles.agency(a)));
+    ctx.fee_waiver = opts.fee_waiver;
+    ctx.expedited = opts.expedited;
+    ctx.set("today", today().format("%B %-d, %Y").to_string());
+
+    let mut cite = opts.cite;
+    if let Some(request) = &request {
+        // The profile's formal agency name wins over what was typed when
+        // the request was recorded
+        ctx.set_default("agency", request.agency.clone());
+        ctx.set("subject", request.subject.clone());
+        if let Some(description) = &request.description {
+            ctx.set("description", description.clone());
+        }
+        if let Some(tracking) = &request.tracking_number {
+            ctx.set("tracking_number", tracking.clone());
+        }
+        if opts.cite_linked {
+            for linked in repos.foia_requests.documents(&request.id).await? {
+                if !cite.contains(&linked.document_id) {
+                    cite.push(linked.document_id);
+                }
+            }
+        }
+    } else if opts.cite_linked {
+        anyhow::bail!("--cite-linked needs --request");
+    }
+    // An --agency that names a profile is a key, not the agency's name
+    if let Some(agency) = &opts.agency {
+        if profiles.agency(agency).is_none() {
+            ctx.set("agency", agency.clone());
+        }
+    }
+    if let Some(subject) = &opts.subject {
+        ctx.set("subject", subject.clone());
+    }
+    if let Some(description) = &opts.description {
+        ctx.set("description", description.clone());
+    }
+    for (key, value) in set {
+        ctx.set(key.trim(), value);
+    }
+
+    for doc_id in &cite {
+        let doc = repos
+            .documents
+            .get(doc_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Document not found: {}", doc_id))?;
+        ctx.cited.push(CitedDocument {
+            id: doc.id,
+            title: doc.title,
+            url: doc.source_url,
+        });
+    }
+
+    let letter = template.render(&ctx)?;
+    let bytes = format.render(&letter);
+    match &opts.output {
+        Some(path) => {
+            std::fs::write(path, bytes)?;
+            println!(
+                "{} Wrote {} letter to {}",
+                style("✓").green(),
+                template.name,
+                path.display()
+            );
+        }
+        None => print!("{}", String::from_utf8_lossy(&bytes)),
+    }
+    Ok(())
+}
+
+/// List built-in and custom letter templates.
+pub async fn cmd_requests_templates(settings: &Settings) -> anyhow::Result<()> {
+    let dir = letters_dir(settings);
+    let templates = LetterTemplate::list(&dir)?;
+
+    println!("\n{:<20}  Description", "Template");
+    println!("{}", "-".repeat(70));
+    for template in &templates {
+        println!(
+            "{:<20}  {}",
+            template.name,
+            template.description.as_deref().unwrap_or("-")
+        );
+    }
+    println!(
+        "\nCustom templates: {}/<name>.md; profiles: {}/letters.toml",
+        dir.display(),
+        dir.display()
+    );
+    Ok(())
+}
@@ -6,10 +6,20 @@ use std::time::Duration;
 use console::style;
 
 use foia::config::{Config, Settings};
+use foia::models::{ScraperStats, ServiceStatus};
 use foia::privacy::PrivacyConfig;
+use foia::repository::diesel_crawl::UrlLease;
 use foia::repository::DieselCrawlRepository;
 
+use super::helpers::open_portal_sessions;
+use super::scrape_cmd::maybe_update_heartbeat;
+
 /// Download pending documents from the queue.
+///
+/// URLs are claimed under a lease held by `worker_id` (default: hostname and
+/// process ID), so downloads on several hosts sharing one database split the
+/// queue between them.
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_download(
     settings: &Settings,
     source_id: Option<&str>,
@@ -17,6 +27,8 @@ pub async fn cmd_download(
     limit: usize,
     show_progress: bool,
     privacy_config: &PrivacyConfig,
+    worker_id: Option<&str>,
+    lease_secs: u64,
 ) -> anyhow::Result<()> {
     use crate::cli::progress::DownloadProgress;
     use foia_scrape::services::download::{DownloadConfig, DownloadEvent, DownloadService};
@@ -31,6 +43,7 @@ pub async fn cmd_download(
 
     let doc_repo = Arc::new(repos.documents);
     let crawl_repo = Arc::new(repos.crawl);
+    let service_status_repo = repos.service_status;
 
     if initial_pending == 0 {
         println!("{} No pending documents to download", style("!").yellow());
@@ -51,8 +64,35 @@ pub async fn cmd_download(
         initial_pending
     );
 
-    // Load config for via mappings
+    // Load config for via mappings, crawl budgets, WARC capture and portal logins
     let config = Config::load().await;
+    let budgets = config
+        .scrapers
+        .iter()
+        .filter_map(|(id, scraper)| Some((id.clone(), scraper.budget.clone()?)))
+        .collect();
+    let warc_captures = config
+        .scrapers
+        .iter()
+        .filter(|(id, _)| source_id.is_none_or(|sid| sid == id.as_str()))
+        .filter_map(|(id, scraper)| Some((id.clone(), scraper.warc.open(&settings.data_dir, id)?)))
+        .collect();
+
+    let portal_sessions = open_portal_sessions(settings, &config, source_id, privacy_config).await;
+
+    let worker_id = worker_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(ServiceStatus::default_worker_id);
+
+    // Register the worker so other hosts can see it in `foia status`
+    let mut service_status = ServiceStatus::new_worker(&worker_id, source_id);
+    service_status.set_running(Some(&format!(
+        "Downloading {}",
+        source_id.unwrap_or("all sources")
+    )));
+    if let Err(e) = service_status_repo.upsert(&service_status).await {
+        tracing::warn!("Failed to register service status: {}", e);
+    }
 
     // Create service
     let service = DownloadService::new(
@@ -65,6 +105,13 @@ pub async fn cmd_download(
             privacy: privacy_config.clone(),
             via: config.via,
             via_mode: config.via_mode,
+            budgets,
+            lease: Some(UrlLease {
+                worker_id: worker_id.clone(),
+                duration: chrono::Duration::seconds(lease_secs as i64),
+            }),
+            warc_captures,
+            portal_sessions,
         },
     );
 
@@ -80,11 +127,26 @@ pub async fn cmd_download(
 
     // Spawn event handler task (UI layer)
     let progress_clone = progress_display.clone();
+    let task = source_id.unwrap_or("all sources").to_string();
     let event_handler = tokio::spawn(async move {
         let mut downloaded = 0usize;
         let mut skipped = 0usize;
+        let mut failed = 0usize;
+        let mut last_heartbeat = std::time::Instant::now();
 
         while let Some(event) = event_rx.recv().await {
+            maybe_update_heartbeat(
+                &mut last_heartbeat,
+                Duration::from_secs(30),
+                &mut service_status,
+                &service_status_repo,
+                &task,
+                (downloaded + skipped + failed) as u64,
+                downloaded as u64,
+                failed as u64,
+            )
+            .await;
+
             match event {
                 DownloadEvent::Started {
                     worker_id,
@@ -122,18 +184,25 @@ pub async fn cmd_download(
                         progress.finish_download(worker_id, true).await;
                     }
                 }
-                DownloadEvent::Unchanged { worker_id, .. } => {
+                DownloadEvent::Unchanged { worker_id, .. }
+                | DownloadEvent::Tombstoned { worker_id, .. } => {
                     skipped += 1;
                     if let Some(ref progress) = progress_clone {
                         progress.set_summary(downloaded, skipped);
                         progress.finish_download(worker_id, true).await;
                     }
                 }
+                DownloadEvent::LeaseLost { worker_id, .. } => {
+                    if let Some(ref progress) = progress_clone {
+                        progress.finish_download(worker_id, false).await;
+                    }
+                }
                 DownloadEvent::Failed {
                     worker_id,
                     url,
                     error,
                 } => {
+                    failed += 1;
                     if let Some(ref progress) = progress_clone {
                         progress.println(&format!(
                             "{} Failed to download {}: {}",
@@ -153,6 +222,19 @@ pub async fn cmd_download(
                 }
             }
         }
+
+        service_status.update_scraper_stats(ScraperStats {
+            session_processed: (downloaded + skipped + failed) as u64,
+            session_new: downloaded as u64,
+            session_errors: failed as u64,
+            rate_per_min: None,
+            queue_size: None,
+            browser_failures: None,
+        });
+        service_status.set_stopped();
+        if let Err(e) = service_status_repo.upsert(&service_status).await {
+            tracing::warn!("Failed to update final service status: {}", e);
+        }
     });
 
     // Run download service (business logic)
@@ -1,9 +1,16 @@
 //! Helper functions for scrape commands.
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
+use foia::config::{Config, Settings};
+use foia::http_client::HttpClient;
 use foia::models::{Document, DocumentVersion};
+use foia::privacy::PrivacyConfig;
 use foia::storage::{compute_storage_path_with_dedup, mime_to_extension};
+use foia_scrape::configurable::PortalSession;
 
 /// Parse server date from Last-Modified header.
 pub fn parse_server_date(last_modified: Option<&str>) -> Option<chrono::DateTime<chrono::Utc>> {
@@ -14,6 +21,55 @@ pub fn parse_server_date(last_modified: Option<&str>) -> Option<chrono::DateTime
     })
 }
 
+/// Log in to the requester portals of configured sources with `auth`.
+///
+/// Each session keeps its cookies in its own client. A failed login is only
+/// reported here; fetches log in again when they come back logged out.
+pub async fn open_portal_sessions(
+    settings: &Settings,
+    config: &Config,
+    source_id: Option<&str>,
+    privacy: &PrivacyConfig,
+) -> HashMap<String, Arc<PortalSession>> {
+    let mut sessions = HashMap::new();
+    for (id, scraper) in &config.scrapers {
+        let Some(auth) = scraper.auth.as_ref() else {
+            continue;
+        };
+        if source_id.is_some_and(|sid| sid != id.as_str()) {
+            continue;
+        }
+
+        let mut builder = HttpClient::builder(
+            id,
+            Duration::from_secs(settings.request_timeout),
+            Duration::from_millis(settings.request_delay_ms),
+        )
+        .privacy(privacy)
+        .cookie_store(true);
+        if let Some(ua) = scraper.user_agent.as_deref() {
+            builder = builder.user_agent(ua);
+        }
+        let client = match builder.build() {
+            Ok(c) => c.with_via_config(config.via.clone(), config.via_mode),
+            Err(e) => {
+                tracing::warn!("[{}] Failed to create portal client: {}", id, e);
+                continue;
+            }
+        };
+
+        let cookies_file = auth.cookies_path(&settings.data_dir, id);
+        let Some(session) = PortalSession::from_config(id, scraper, client, cookies_file) else {
+            continue;
+        };
+        if let Err(e) = session.ensure_logged_in().await {
+            tracing::warn!("[{}] Portal login failed: {:#}", id, e);
+        }
+        sessions.insert(id.clone(), Arc::new(session));
+    }
+    sessions
+}
+
 /// Update document metadata without re-downloading content.
 pub fn update_document_metadata(
     doc: &Document,
@@ -3,6 +3,7 @@
 //! Split into submodules:
 //! - `helpers.rs`: Helper functions for document processing
 //! - `scrape_cmd.rs`: Main scrape command
+//! - `schedule.rs`: Per-source schedules for the daemon
 //! - `download.rs`: Download pending documents
 //! - `status.rs`: Show system status
 //! - `refresh.rs`: Refresh document metadata
@@ -11,6 +12,7 @@ mod discovery;
 mod download;
 mod helpers;
 mod refresh;
+mod schedule;
 mod scrape_cmd;
 mod single_source;
 mod status;
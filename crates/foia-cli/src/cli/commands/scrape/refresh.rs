@@ -1,23 +1,27 @@
 //! Refresh metadata for documents.
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use console::style;
 use indicatif::ProgressBar;
 
-use super::helpers::{process_get_response_for_refresh, RefreshResult};
+use super::helpers::{open_portal_sessions, process_get_response_for_refresh, RefreshResult};
 use crate::cli::commands::helpers::truncate;
 use foia::config::{Config, Settings};
+use foia::http_client::HttpClient;
 use foia::models::Document;
 use foia::privacy::PrivacyConfig;
 use foia::repository::DieselDocumentRepository;
+use foia_scrape::configurable::PortalSession;
 
 /// Shared GET request handling for refresh.
 /// Returns (should_continue, should_skip_increment).
 #[allow(clippy::too_many_arguments)]
 async fn try_get_refresh(
     client: &foia::http_client::HttpClient,
+    session: Option<&PortalSession>,
     url: &str,
     doc: &Document,
     current_version: &foia::models::DocumentVersion,
@@ -28,7 +32,14 @@ async fn try_get_refresh(
     redownloaded: &Arc<AtomicUsize>,
     skipped: &Arc<AtomicUsize>,
 ) -> bool {
-    match client.get(url, None, None).await {
+    let fetched = match session {
+        Some(session) => session.get(client, url, None, None).await,
+        None => client
+            .get(url, None, None)
+            .await
+            .map_err(anyhow::Error::from),
+    };
+    match fetched {
         Ok(response) if response.is_success() => {
             let result =
                 process_get_response_for_refresh(response, doc, current_version, documents_dir)
@@ -130,10 +141,22 @@ pub async fn cmd_refresh(
         return Ok(());
     }
 
-    // Load config for via mappings
+    // Load config for via mappings, WARC capture and portal logins
     let config = Config::load().await;
+    let portal_sessions =
+        Arc::new(open_portal_sessions(settings, &config, source_id, privacy_config).await);
     let via_mappings = Arc::new(config.via);
     let via_mode = config.via_mode;
+    let warc_captures: Arc<HashMap<_, _>> = Arc::new(
+        config
+            .scrapers
+            .iter()
+            .filter(|(id, _)| source_id.is_none_or(|sid| sid == id.as_str()))
+            .filter_map(|(id, scraper)| {
+                Some((id.clone(), scraper.warc.open(&settings.data_dir, id)?))
+            })
+            .collect(),
+    );
 
     println!(
         "{} Refreshing metadata for {} documents using {} workers",
@@ -175,6 +198,8 @@ pub async fn cmd_refresh(
         let pb = pb.clone();
         let privacy = privacy_config.clone();
         let via = via_mappings.clone();
+        let warc_captures = warc_captures.clone();
+        let portal_sessions = portal_sessions.clone();
 
         let handle = tokio::spawn(async move {
             let client = match foia::http_client::HttpClient::builder(
@@ -199,6 +224,9 @@ pub async fn cmd_refresh(
                 client
             };
 
+            // Sources with a portal session or WARC capture get their own client
+            let mut source_clients: HashMap<String, HttpClient> = HashMap::new();
+
             loop {
                 let _permit = semaphore.acquire().await.unwrap();
 
@@ -214,6 +242,23 @@ pub async fn cmd_refresh(
 
                 pb.set_message(truncate(&doc.title, 40));
 
+                let session = portal_sessions.get(&doc.source_id).map(Arc::as_ref);
+                let capture = warc_captures.get(&doc.source_id);
+                let client: &HttpClient = if session.is_some() || capture.is_some() {
+                    source_clients
+                        .entry(doc.source_id.clone())
+                        .or_insert_with(|| {
+                            let client =
+                                session.map_or_else(|| client.clone(), |s| s.client().clone());
+                            match capture {
+                                Some(capture) => client.with_warc_capture(capture.clone()),
+                                None => client,
+                            }
+                        })
+                } else {
+                    &client
+                };
+
                 let url = &doc.source_url;
                 let current_version = match doc.current_version() {
                     Some(v) => v,
@@ -223,11 +268,15 @@ pub async fn cmd_refresh(
                     }
                 };
 
-                // Try HEAD request first
-                let head_result = client.head(url, None, None).await;
+                // Try HEAD request first. Portals answer HEAD for an expired
+                // session with the login page's headers, so those go straight to GET.
+                let head_result = match session {
+                    Some(_) => None,
+                    None => Some(client.head(url, None, None).await),
+                };
 
                 match head_result {
-                    Ok(head_response) if head_response.is_success() => {
+                    Some(Ok(head_response)) if head_response.is_success() => {
                         let _head_etag = head_response.etag().map(|s| s.to_string());
                         let head_last_modified =
                             head_response.last_modified().map(|s| s.to_string());
@@ -273,7 +322,8 @@ pub async fn cmd_refresh(
                         } else {
                             // Need to do full GET to get metadata
                             if try_get_refresh(
-                                &client,
+                                client,
+                                session,
                                 url,
                                 &doc,
                                 current_version,
@@ -294,7 +344,8 @@ pub async fn cmd_refresh(
                     _ => {
                         // HEAD failed or not supported, try GET
                         if try_get_refresh(
-                            &client,
+                            client,
+                            session,
                             url,
                             &doc,
                             current_version,
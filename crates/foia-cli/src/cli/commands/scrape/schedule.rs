@@ -0,0 +1,103 @@
+//! Per-source schedules for the scrape daemon.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use foia::config::{ScheduleConfig, Settings};
+
+/// A scheduled source and when it next runs.
+pub(super) struct SourceSchedule {
+    pub source_id: String,
+    pub config: ScheduleConfig,
+    pub last_scraped: Option<DateTime<Utc>>,
+    /// Next start, `Ok(None)` if the cron expression never matches, or the
+    /// parse error for an invalid schedule.
+    pub next_run: Result<Option<DateTime<Utc>>, String>,
+}
+
+/// Schedules for every source that has one, by source ID.
+pub(super) async fn source_schedules(
+    settings: &Settings,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<SourceSchedule>> {
+    let repos = settings.repositories()?;
+    let last_scraped: HashMap<String, Option<DateTime<Utc>>> = repos
+        .sources
+        .get_all()
+        .await?
+        .into_iter()
+        .map(|s| (s.id, s.last_scraped))
+        .collect();
+
+    let mut schedules: Vec<SourceSchedule> = repos
+        .scraper_configs
+        .get_all()
+        .await?
+        .into_iter()
+        .filter_map(|(source_id, config)| {
+            let config = config.schedule?;
+            let last = last_scraped.get(&source_id).copied().flatten();
+            let next_run = config
+                .parse()
+                .map(|s| s.next_run(&source_id, last, now))
+                .map_err(|e| e.to_string());
+            Some(SourceSchedule {
+                source_id,
+                config,
+                last_scraped: last,
+                next_run,
+            })
+        })
+        .collect();
+    schedules.sort_by(|a, b| a.source_id.cmp(&b.source_id));
+    Ok(schedules)
+}
+
+/// Sources due to run now, and when the next one comes due.
+#[derive(Default)]
+pub(super) struct DueSources {
+    pub due: Vec<String>,
+    /// When each due source's run must stop because its window closes.
+    pub deadlines: HashMap<String, DateTime<Utc>>,
+    pub upcoming: Option<DateTime<Utc>>,
+}
+
+/// Split `source_ids` into those due now and the earliest upcoming run.
+///
+/// Sources without a schedule are due on every daemon check; sources with
+/// an invalid schedule are skipped with a warning.
+pub(super) async fn due_sources(
+    settings: &Settings,
+    source_ids: &[String],
+) -> anyhow::Result<DueSources> {
+    let now = Utc::now();
+    let schedules: HashMap<String, SourceSchedule> = source_schedules(settings, now)
+        .await?
+        .into_iter()
+        .map(|s| (s.source_id.clone(), s))
+        .collect();
+
+    let mut sources = DueSources::default();
+    for source_id in source_ids {
+        let Some(schedule) = schedules.get(source_id) else {
+            sources.due.push(source_id.clone());
+            continue;
+        };
+        match &schedule.next_run {
+            Ok(Some(at)) if *at <= now => {
+                sources.due.push(source_id.clone());
+                let closes = schedule.config.parse().ok().and_then(|s| s.window_end(now));
+                if let Some(closes) = closes {
+                    sources.deadlines.insert(source_id.clone(), closes);
+                }
+            }
+            Ok(Some(at)) => {
+                sources.upcoming = Some(sources.upcoming.map_or(*at, |u| u.min(*at)));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping {}: {}", source_id, e),
+        }
+    }
+    Ok(sources)
+}
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use console::style;
 
 use crate::cli::commands::daemon::{ConfigWatcher, DaemonAction, ReloadMode};
@@ -13,6 +14,7 @@ use foia::privacy::PrivacyConfig;
 use foia::repository::DieselServiceStatusRepository;
 use foia_scrape::{DieselRateLimitBackend, InMemoryRateLimitBackend, RateLimiter};
 
+use super::schedule::{due_sources, DueSources};
 use super::single_source::cmd_scrape_single_tui;
 
 /// Update service heartbeat if interval has elapsed.
@@ -44,6 +46,15 @@ pub(super) async fn maybe_update_heartbeat(
     }
 }
 
+/// Seconds until the next daemon check: the interval, or sooner if a
+/// scheduled source comes due first.
+fn sleep_secs(interval: u64, upcoming: Option<DateTime<Utc>>) -> u64 {
+    match upcoming {
+        Some(at) => ((at - Utc::now()).num_seconds().max(1) as u64).min(interval),
+        None => interval,
+    }
+}
+
 /// Scrape documents from one or more sources.
 #[allow(clippy::too_many_arguments)]
 pub async fn cmd_scrape(
@@ -141,8 +152,47 @@ pub async fn cmd_scrape(
                 }
             }
         }
+
+        // In daemon mode, only start sources whose schedule says they're due
+        let DueSources {
+            due: running,
+            deadlines,
+            upcoming,
+        } = if daemon {
+            match due_sources(settings, &sources_to_scrape).await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::warn!("Failed to check source schedules: {}", e);
+                    DueSources::default()
+                }
+            }
+        } else {
+            DueSources {
+                due: sources_to_scrape.clone(),
+                ..Default::default()
+            }
+        };
+
+        if daemon && running.is_empty() {
+            match upcoming {
+                Some(at) => println!(
+                    "{} No sources due (next at {})",
+                    style("→").dim(),
+                    at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ),
+                None => println!("{} No sources due", style("→").dim()),
+            }
+            match config_watcher
+                .sleep_or_reload(sleep_secs(interval, upcoming), "reloading")
+                .await
+            {
+                DaemonAction::Exit => return Ok(()),
+                DaemonAction::Continue | DaemonAction::Reload => continue,
+            }
+        }
+
         // Initialize TUI with fixed status pane at top (1 header + 1 line per source)
-        let num_status_lines = (sources_to_scrape.len() + 1).min(10) as u16; // Cap at 10 lines
+        let num_status_lines = (running.len() + 1).min(10) as u16; // Cap at 10 lines
         let tui_guard = crate::cli::tui::TuiGuard::new(num_status_lines)?;
 
         // Set header
@@ -151,17 +201,13 @@ pub async fn cmd_scrape(
             &format!(
                 "{} Scraping {} source{}...",
                 style("→").cyan(),
-                sources_to_scrape.len(),
-                if sources_to_scrape.len() == 1 {
-                    ""
-                } else {
-                    "s"
-                }
+                running.len(),
+                if running.len() == 1 { "" } else { "s" }
             ),
         );
 
         // Initialize status lines for each source
-        let source_lines: std::collections::HashMap<String, u16> = sources_to_scrape
+        let source_lines: std::collections::HashMap<String, u16> = running
             .iter()
             .enumerate()
             .take(9) // Only show first 9 sources in status (line 0 is header)
@@ -175,9 +221,9 @@ pub async fn cmd_scrape(
             );
         }
 
-        if sources_to_scrape.len() == 1 {
+        if running.len() == 1 {
             // Single source - run directly but catch errors in daemon mode
-            let source_id = &sources_to_scrape[0];
+            let source_id = &running[0];
             let line = source_lines.get(source_id).copied();
             let result = cmd_scrape_single_tui(
                 settings,
@@ -189,6 +235,7 @@ pub async fn cmd_scrape(
                 tui_guard.is_active(),
                 Some(rate_limiter.clone()),
                 privacy_config,
+                deadlines.get(source_id).copied(),
             )
             .await;
 
@@ -219,13 +266,14 @@ pub async fn cmd_scrape(
         } else {
             // Multiple sources - run in parallel
             let mut handles = Vec::new();
-            for source_id in &sources_to_scrape {
+            for source_id in &running {
                 let settings = settings.clone();
                 let source_id_clone = source_id.clone();
                 let line = source_lines.get(source_id).copied();
                 let tui_active = tui_guard.is_active();
                 let rate_limiter_clone = rate_limiter.clone();
                 let privacy_config_clone = privacy_config.clone();
+                let deadline = deadlines.get(source_id).copied();
                 let handle = tokio::spawn(async move {
                     cmd_scrape_single_tui(
                         &settings,
@@ -237,6 +285,7 @@ pub async fn cmd_scrape(
                         tui_active,
                         Some(rate_limiter_clone),
                         &privacy_config_clone,
+                        deadline,
                     )
                     .await
                 });
@@ -293,7 +342,10 @@ pub async fn cmd_scrape(
             break;
         }
 
-        match config_watcher.sleep_or_reload(interval, "reloading").await {
+        match config_watcher
+            .sleep_or_reload(sleep_secs(interval, upcoming), "reloading")
+            .await
+        {
             DaemonAction::Exit => return Ok(()),
             DaemonAction::Continue | DaemonAction::Reload => {}
         }
@@ -3,12 +3,15 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+
 use console::style;
 
 use foia::config::{Config, Settings, DEFAULT_REFRESH_TTL_DAYS};
 use foia::llm::LlmClient;
 use foia::models::{ScraperStats, ServiceStatus, Source, SourceType};
 use foia::privacy::PrivacyConfig;
+use foia::storage::SaveOutcome;
 use foia_scrape::{ConfigurableScraper, RateLimiter};
 
 use super::scrape_cmd::maybe_update_heartbeat;
@@ -25,6 +28,7 @@ pub(super) async fn cmd_scrape_single_tui(
     tui_active: bool,
     rate_limiter: Option<Arc<RateLimiter>>,
     privacy_config: &PrivacyConfig,
+    deadline: Option<DateTime<Utc>>,
 ) -> anyhow::Result<()> {
     settings.ensure_directories()?;
 
@@ -110,13 +114,7 @@ pub(super) async fn cmd_scrape_single_tui(
             if !discovery_urls.is_empty() {
                 let mut added = 0usize;
                 for discovered in discovery_urls {
-                    let crawl_url = foia::models::CrawlUrl::new(
-                        discovered.url.clone(),
-                        source_id.to_string(),
-                        discovered.source_method,
-                        discovered.query_used.clone(),
-                        0,
-                    );
+                    let crawl_url = discovered.to_crawl_url(source_id);
                     match crawl_repo.add_url(&crawl_url).await {
                         Ok(true) => added += 1,
                         Ok(false) => {} // Already exists
@@ -195,7 +193,9 @@ pub(super) async fn cmd_scrape_single_tui(
         limiter_opt,
         Some(privacy_config),
     )
-    .map_err(|e| anyhow::anyhow!("Failed to create scraper: {}", e))?;
+    .map_err(|e| anyhow::anyhow!("Failed to create scraper: {}", e))?
+    // Scheduled runs stop when the source's window closes
+    .with_deadline(deadline);
 
     // Apply per-source via mappings for caching proxy support if configured
     let scraper = if !scraper_config.via.is_empty() {
@@ -205,6 +205,18 @@ pub(super) async fn cmd_scrape_single_tui(
         scraper
     };
 
+    // Log in to requester portals, before WARC capture so session checks aren't archived
+    let scraper = match &scraper_config.auth {
+        Some(auth) => scraper.with_portal_session(auth.cookies_path(&settings.data_dir, source_id)),
+        None => scraper,
+    };
+
+    // Tee raw HTTP exchanges into rotating WARC files if configured
+    let scraper = match scraper_config.warc.open(&settings.data_dir, source_id) {
+        Some(capture) => scraper.with_warc_capture(capture),
+        None => scraper,
+    };
+
     let stream = match scraper.scrape_stream(workers).await {
         Ok(s) => s,
         Err(e) => {
@@ -250,7 +262,7 @@ pub(super) async fn cmd_scrape_single_tui(
         };
 
         // Save document using helper
-        if let Err(e) = crate::cli::helpers::save_scraped_document_async(
+        match crate::cli::helpers::save_scraped_document_async(
             &doc_repo,
             content,
             &result,
@@ -259,13 +271,20 @@ pub(super) async fn cmd_scrape_single_tui(
         )
         .await
         {
-            tracing::warn!("Failed to save document: {}", e);
-            errors_this_session += 1;
-            service_status.record_error(&e.to_string());
-            if let Err(e) = service_status_repo.upsert(&service_status).await {
-                tracing::warn!("Failed to update service status on error: {}", e);
+            Ok(SaveOutcome::Deleted) => {
+                tracing::info!("Skipping deleted document: {}", result.url);
+                continue;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("Failed to save document: {}", e);
+                errors_this_session += 1;
+                service_status.record_error(&e.to_string());
+                if let Err(e) = service_status_repo.upsert(&service_status).await {
+                    tracing::warn!("Failed to update service status on error: {}", e);
+                }
+                continue;
             }
-            continue;
         }
 
         count += 1;
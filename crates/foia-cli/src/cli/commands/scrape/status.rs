@@ -18,6 +18,8 @@ use foia::config::Settings;
 use foia::models::{DocumentStatus, ServiceStatus};
 use foia::repository::util::redact_url_password;
 
+use super::schedule::{source_schedules, SourceSchedule};
+
 /// Show overall system status.
 pub async fn cmd_status(
     settings: &Settings,
@@ -212,6 +214,15 @@ async fn display_status_json(settings: &Settings, _source_id: Option<&str>) -> a
             "downloaded": s.downloaded,
             "ocr_done": s.ocr_done,
         })).collect::<Vec<_>>(),
+        "schedules": data.schedules.iter().map(|s| serde_json::json!({
+            "source_id": s.source_id,
+            "cron": s.config.cron,
+            "windows": s.config.windows,
+            "timezone": s.config.timezone,
+            "last_scraped": s.last_scraped,
+            "next_run": s.next_run.as_ref().ok().flatten(),
+            "error": s.next_run.as_ref().err(),
+        })).collect::<Vec<_>>(),
         "database": data.database_url,
         "data_dir": data.data_dir,
     });
@@ -228,6 +239,7 @@ struct StatusData {
     status_counts: HashMap<String, u64>,
     pending_downloads: u64,
     sources: Vec<SourceStats>,
+    schedules: Vec<SourceSchedule>,
     services: Vec<ServiceStatus>,
     last_updated: String,
 }
@@ -255,6 +267,7 @@ async fn fetch_status_data(settings: &Settings) -> anyhow::Result<StatusData> {
     let source_counts = doc_repo.get_all_source_counts().await?;
     let source_status_counts = doc_repo.get_source_status_counts().await?;
     let services = service_repo.get_all().await.unwrap_or_default();
+    let schedules = source_schedules(settings, chrono::Utc::now()).await?;
 
     // Only include sources that have at least one document
     let sources: Vec<SourceStats> = sources_list
@@ -291,6 +304,7 @@ async fn fetch_status_data(settings: &Settings) -> anyhow::Result<StatusData> {
         status_counts,
         pending_downloads,
         sources,
+        schedules,
         services,
         last_updated: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
     })
@@ -409,11 +423,60 @@ async fn display_status_simple(settings: &Settings) -> anyhow::Result<()> {
         }
     }
 
+    if !data.schedules.is_empty() {
+        println!();
+        println!(
+            "{:<26} {:<20} {:<18} {}",
+            style("SCHEDULES").cyan().bold(),
+            "Schedule",
+            "Last run",
+            "Next run"
+        );
+        for schedule in &data.schedules {
+            println!(
+                "  {:<24} {:<20} {:<18} {}",
+                truncate_string(&schedule.source_id, 24),
+                truncate_string(&describe_schedule(schedule), 20),
+                schedule
+                    .last_scraped
+                    .map(format_local)
+                    .unwrap_or_else(|| "never".to_string()),
+                format_next_run(schedule)
+            );
+        }
+    }
+
     println!("{}", separator);
 
     Ok(())
 }
 
+/// Cron expression and windows, e.g. `0 3 * * * 22:00-06:00`.
+fn describe_schedule(schedule: &SourceSchedule) -> String {
+    let mut parts: Vec<&str> = schedule.config.cron.iter().map(String::as_str).collect();
+    parts.extend(schedule.config.windows.iter().map(String::as_str));
+    if parts.is_empty() {
+        "every check".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+fn format_local(at: chrono::DateTime<chrono::Utc>) -> String {
+    at.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+fn format_next_run(schedule: &SourceSchedule) -> String {
+    match &schedule.next_run {
+        Ok(Some(at)) if *at <= chrono::Utc::now() => "due now".to_string(),
+        Ok(Some(at)) => format_local(*at),
+        Ok(None) => "never (cron never matches)".to_string(),
+        Err(e) => format!("invalid: {}", e),
+    }
+}
+
 /// Run status display in live TUI mode.
 async fn run_live_status(settings: &Settings, interval: u64) -> anyhow::Result<()> {
     // Fetch initial data before entering TUI mode
@@ -9,6 +9,7 @@ use foia::privacy::{CTorHiddenService, HiddenServiceProvider};
 use foia::repository::migrations;
 
 /// Start the web server.
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_serve(
     settings: &Settings,
     config: &Config,
@@ -16,6 +17,8 @@ pub async fn cmd_serve(
     no_migrate: bool,
     no_hidden_service: bool,
     use_arti: bool,
+    public: bool,
+    admin_token: Option<String>,
 ) -> anyhow::Result<()> {
     let (host, port) = parse_bind_address(bind)?;
 
@@ -56,6 +59,18 @@ pub async fn cmd_serve(
         }
     }
 
+    if public {
+        println!(
+            "{} Public mode: detected PII is masked, unscanned documents are withheld",
+            style("→").cyan()
+        );
+    } else if admin_token.is_some() {
+        println!(
+            "{} Admin token set: documents can be deleted and held through the API",
+            style("→").cyan()
+        );
+    }
+
     // Determine hidden service configuration
     let mut hs_config = config.privacy.hidden_service.clone();
 
@@ -87,15 +102,33 @@ pub async fn cmd_serve(
             port
         );
         println!("  Press Ctrl+C to stop");
-        return foia_server::serve(settings, &host, port).await;
+        return foia_server::serve(settings, &host, port, public, admin_token).await;
     }
 
     match hs_config.provider {
         HiddenServiceProvider::CTor => {
-            start_with_ctor(settings, config, &hs_config, &host, port).await
+            start_with_ctor(
+                settings,
+                config,
+                &hs_config,
+                &host,
+                port,
+                public,
+                admin_token,
+            )
+            .await
         }
         HiddenServiceProvider::Arti => {
-            start_with_arti(settings, config, &hs_config, &host, port).await
+            start_with_arti(
+                settings,
+                config,
+                &hs_config,
+                &host,
+                port,
+                public,
+                admin_token,
+            )
+            .await
         }
         HiddenServiceProvider::None => {
             unreachable!("already handled by is_enabled() check")
@@ -110,6 +143,8 @@ async fn start_with_ctor(
     hs_config: &foia::privacy::HiddenServiceConfig,
     host: &str,
     port: u16,
+    public: bool,
+    admin_token: Option<String>,
 ) -> anyhow::Result<()> {
     // Check if C-Tor is available
     if !CTorHiddenService::is_available(hs_config) {
@@ -176,7 +211,7 @@ async fn start_with_ctor(
     println!();
 
     // Start the actual server
-    let result = foia_server::serve(settings, host, port).await;
+    let result = foia_server::serve(settings, host, port, public, admin_token).await;
 
     // Shutdown hidden service when server stops
     hs.shutdown();
@@ -191,6 +226,8 @@ async fn start_with_arti(
     _hs_config: &foia::privacy::HiddenServiceConfig,
     host: &str,
     port: u16,
+    public: bool,
+    admin_token: Option<String>,
 ) -> anyhow::Result<()> {
     // Arti hidden service support is experimental and not yet implemented
     // For now, we just show a message and fall back to clearnet
@@ -211,7 +248,7 @@ async fn start_with_arti(
         port
     );
     println!("  Press Ctrl+C to stop");
-    foia_server::serve(settings, host, port).await
+    foia_server::serve(settings, host, port, public, admin_token).await
 }
 
 /// Parse a bind address that can be:
@@ -138,6 +138,33 @@ pub async fn cmd_crawl_clear(
     Ok(())
 }
 
+/// Return stale `fetching` URLs to the queue.
+pub async fn cmd_crawl_recover(
+    settings: &Settings,
+    source_id: Option<&str>,
+    include_unleased: bool,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let recovered = repos
+        .crawl
+        .recover_stale_urls(source_id, include_unleased)
+        .await?;
+
+    println!(
+        "{} Returned {} stale URL(s) to the queue",
+        style("✓").green(),
+        recovered
+    );
+    if !include_unleased {
+        println!(
+            "  {} URLs claimed without a lease are kept; use --unleased once no downloads are running",
+            style("→").dim()
+        );
+    }
+
+    Ok(())
+}
+
 /// Discover document URLs from a source (does not download).
 pub async fn cmd_crawl(settings: &Settings, source_id: &str, _limit: usize) -> anyhow::Result<()> {
     settings.ensure_directories()?;
@@ -236,6 +263,18 @@ pub async fn cmd_crawl(settings: &Settings, source_id: &str, _limit: usize) -> a
         scraper
     };
 
+    // Log in to requester portals, before WARC capture so session checks aren't archived
+    let scraper = match &scraper_config.auth {
+        Some(auth) => scraper.with_portal_session(auth.cookies_path(&settings.data_dir, source_id)),
+        None => scraper,
+    };
+
+    // Tee listing and API pages into the source's WARC capture if configured
+    let scraper = match scraper_config.warc.open(&settings.data_dir, source_id) {
+        Some(capture) => scraper.with_warc_capture(capture),
+        None => scraper,
+    };
+
     let pb = ProgressBar::new_spinner();
     pb.set_style(
         ProgressStyle::default_spinner()
@@ -244,6 +283,10 @@ pub async fn cmd_crawl(settings: &Settings, source_id: &str, _limit: usize) -> a
     );
     pb.set_message(format!("Discovering URLs from {}...", source.name));
 
+    if let Err(e) = scraper.ensure_logged_in().await {
+        pb.finish_and_clear();
+        return Err(e);
+    }
     let urls = scraper.discover().await;
     pb.finish_and_clear();
 
@@ -0,0 +1,547 @@
+//! Content store maintenance commands.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Utc;
+use console::style;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use foia::config::{Config, Settings};
+use foia::http_client::HttpClient;
+use foia::privacy::PrivacyConfig;
+use foia::repository::diesel_document::StoredVersion;
+use foia::repository::DieselDocumentRepository;
+use foia::storage::gc::{
+    expired_batches, is_content_path, list_batches, older_than, purge_batch, quarantine_files,
+    restore_referenced,
+};
+use foia::storage::verify::{
+    find_orphans, restore_content, verify_file, VerifyCheckpoint, VerifyOutcome,
+};
+
+use super::helpers::{format_bytes, truncate};
+
+/// Verify stored files against their recorded hashes.
+///
+/// Walks `document_versions` in id order, re-hashing each file. Progress is
+/// checkpointed after every batch so large stores can be verified across
+/// several runs. With `repair`, missing or corrupted files are re-fetched
+/// from the version's source URL or a known archive snapshot, and only
+/// written back if the fetched bytes match the recorded hashes.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_storage_verify(
+    settings: &Settings,
+    source_id: Option<&str>,
+    limit: usize,
+    repair: bool,
+    orphans: bool,
+    rate: f64,
+    batch_size: usize,
+    resume: bool,
+    daemon: bool,
+    interval: u64,
+    privacy_config: &PrivacyConfig,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let doc_repo = repos.documents;
+    let checkpoint_path = VerifyCheckpoint::path(&settings.data_dir, source_id);
+
+    let client = if repair {
+        let client = HttpClient::builder(
+            "storage-verify",
+            Duration::from_secs(30),
+            Duration::from_millis(500),
+        )
+        .privacy(privacy_config)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
+
+        // Apply via mappings for caching proxy support
+        let config = Config::load().await;
+        Some(if !config.via.is_empty() {
+            client.with_via_config(config.via, config.via_mode)
+        } else {
+            client
+        })
+    } else {
+        None
+    };
+
+    loop {
+        let checkpoint = verify_pass(
+            &doc_repo,
+            &settings.documents_dir,
+            &checkpoint_path,
+            source_id,
+            limit,
+            client.as_ref(),
+            rate,
+            batch_size,
+            resume,
+        )
+        .await?;
+
+        print_verify_summary(&checkpoint);
+
+        if orphans {
+            report_orphans(&doc_repo, &settings.documents_dir, batch_size).await?;
+        }
+
+        if !daemon {
+            break;
+        }
+
+        println!(
+            "\n{} Sleeping {}s before next verification pass...",
+            style("→").dim(),
+            interval
+        );
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+    }
+
+    Ok(())
+}
+
+/// Run (or resume) a single verification pass.
+#[allow(clippy::too_many_arguments)]
+async fn verify_pass(
+    doc_repo: &DieselDocumentRepository,
+    documents_dir: &Path,
+    checkpoint_path: &Path,
+    source_id: Option<&str>,
+    limit: usize,
+    client: Option<&HttpClient>,
+    rate: f64,
+    batch_size: usize,
+    resume: bool,
+) -> anyhow::Result<VerifyCheckpoint> {
+    let mut checkpoint = match VerifyCheckpoint::load(checkpoint_path) {
+        Some(cp) if resume && !cp.is_complete() => {
+            println!(
+                "{} Resuming verification after version {} ({} checked so far)",
+                style("→").cyan(),
+                cp.last_version_id,
+                cp.checked
+            );
+            cp
+        }
+        _ => VerifyCheckpoint {
+            started_at: Some(Utc::now()),
+            ..Default::default()
+        },
+    };
+
+    println!(
+        "{} Verifying stored files in {}{}",
+        style("→").cyan(),
+        documents_dir.display(),
+        if client.is_some() {
+            " (repair enabled)"
+        } else {
+            ""
+        }
+    );
+
+    let delay = if rate > 0.0 {
+        Some(Duration::from_secs_f64(1.0 / rate))
+    } else {
+        None
+    };
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} [{elapsed_precise}] {pos} files ({per_sec}) {wide_msg}")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let mut checked_this_run = 0usize;
+    let mut finished = true;
+
+    'outer: loop {
+        let batch = doc_repo
+            .get_stored_versions_after(checkpoint.last_version_id, batch_size, source_id)
+            .await?;
+        if batch.is_empty() {
+            break;
+        }
+
+        for stored in batch {
+            if limit > 0 && checked_this_run >= limit {
+                finished = false;
+                break 'outer;
+            }
+
+            let path =
+                stored
+                    .version
+                    .resolve_path(documents_dir, &stored.source_url, &stored.title);
+            pb.set_message(truncate(&stored.title, 40));
+
+            let outcome = {
+                let path = path.clone();
+                let version = stored.version.clone();
+                tokio::task::spawn_blocking(move || verify_file(&path, &version)).await?
+            };
+
+            match outcome {
+                Ok(VerifyOutcome::Ok) => {}
+                Ok(outcome) => {
+                    if matches!(outcome, VerifyOutcome::Missing) {
+                        checkpoint.missing += 1;
+                    } else {
+                        checkpoint.corrupted += 1;
+                    }
+                    pb.println(format!(
+                        "  {} {} {} ({})",
+                        style("✗").red(),
+                        outcome.as_str(),
+                        stored.document_id,
+                        path.display()
+                    ));
+
+                    if let Some(client) = client {
+                        if try_repair(client, doc_repo, &stored, &path).await {
+                            checkpoint.repaired += 1;
+                            pb.println(format!(
+                                "    {} restored {}",
+                                style("↻").green(),
+                                stored.document_id
+                            ));
+                        }
+                    }
+                }
+                Err(e) => {
+                    pb.println(format!(
+                        "  {} Failed to read {}: {}",
+                        style("!").yellow(),
+                        path.display(),
+                        e
+                    ));
+                }
+            }
+
+            checkpoint.checked += 1;
+            checkpoint.last_version_id = stored.version.id;
+            checked_this_run += 1;
+            pb.inc(1);
+
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        if let Err(e) = checkpoint.save(checkpoint_path) {
+            tracing::warn!("Failed to save verification checkpoint: {}", e);
+        }
+    }
+
+    pb.finish_and_clear();
+
+    if finished {
+        checkpoint.completed_at = Some(Utc::now());
+    }
+    if let Err(e) = checkpoint.save(checkpoint_path) {
+        tracing::warn!("Failed to save verification checkpoint: {}", e);
+    }
+
+    Ok(checkpoint)
+}
+
+/// Try to restore a missing or corrupted file from its source or an archive.
+async fn try_repair(
+    client: &HttpClient,
+    doc_repo: &DieselDocumentRepository,
+    stored: &StoredVersion,
+    path: &Path,
+) -> bool {
+    let mut candidates: Vec<String> = Vec::new();
+    if let Some(ref url) = stored.version.source_url {
+        candidates.push(url.clone());
+    }
+    if !candidates.contains(&stored.source_url) {
+        candidates.push(stored.source_url.clone());
+    }
+    match doc_repo
+        .get_archive_urls(&stored.version, &stored.source_url)
+        .await
+    {
+        Ok(urls) => candidates.extend(urls),
+        Err(e) => tracing::debug!("Failed to load archive snapshots: {}", e),
+    }
+
+    for url in candidates {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            continue;
+        }
+        let response = match client.get(&url, None, None).await {
+            Ok(r) if r.is_success() => r,
+            Ok(r) => {
+                tracing::debug!("Repair fetch {} returned {}", url, r.status);
+                continue;
+            }
+            Err(e) => {
+                tracing::debug!("Repair fetch {} failed: {}", url, e);
+                continue;
+            }
+        };
+        let content = match response.bytes().await {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::debug!("Failed to read body from {}: {}", url, e);
+                continue;
+            }
+        };
+        match restore_content(path, &stored.version, &content) {
+            Ok(true) => return true,
+            Ok(false) => {
+                tracing::debug!("Content from {} no longer matches recorded hash", url);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to write restored file {}: {}", path.display(), e);
+                return false;
+            }
+        }
+    }
+
+    false
+}
+
+/// Find and print files under `documents_dir` that no version references.
+async fn report_orphans(
+    doc_repo: &DieselDocumentRepository,
+    documents_dir: &Path,
+    batch_size: usize,
+) -> anyhow::Result<Vec<(PathBuf, u64)>> {
+    println!(
+        "\n{} Scanning for orphaned files in {}",
+        style("→").cyan(),
+        documents_dir.display()
+    );
+
+    let referenced = collect_referenced_paths(doc_repo, documents_dir, batch_size).await?;
+    let documents_dir = documents_dir.to_path_buf();
+    let orphans =
+        tokio::task::spawn_blocking(move || find_orphans(&documents_dir, &referenced)).await??;
+
+    if orphans.is_empty() {
+        println!("  {} No orphaned files", style("✓").green());
+        return Ok(orphans);
+    }
+
+    let total: u64 = orphans.iter().map(|(_, size)| size).sum();
+    for (path, size) in &orphans {
+        println!(
+            "  {} {} ({})",
+            style("?").yellow(),
+            path.display(),
+            format_bytes(*size)
+        );
+    }
+    println!(
+        "  {} orphaned files ({})",
+        style(orphans.len()).yellow(),
+        format_bytes(total)
+    );
+
+    Ok(orphans)
+}
+
+/// Resolve the storage path of every version in the database.
+async fn collect_referenced_paths(
+    doc_repo: &DieselDocumentRepository,
+    documents_dir: &Path,
+    batch_size: usize,
+) -> anyhow::Result<HashSet<PathBuf>> {
+    let mut referenced = HashSet::new();
+    let mut after_id = 0i64;
+
+    loop {
+        let batch = doc_repo
+            .get_stored_versions_after(after_id, batch_size, None)
+            .await?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after_id = last.version.id;
+        for stored in &batch {
+            referenced.insert(stored.version.resolve_path(
+                documents_dir,
+                &stored.source_url,
+                &stored.title,
+            ));
+        }
+    }
+
+    Ok(referenced)
+}
+
+/// Quarantine unreferenced files and purge expired quarantine batches.
+///
+/// Orphans younger than `min_age_hours` are left alone in case a download
+/// is still being recorded. Quarantined files are deleted once their batch
+/// is older than `grace_days`; any that become referenced again first are
+/// moved back into place.
+pub async fn cmd_storage_gc(
+    settings: &Settings,
+    dry_run: bool,
+    grace_days: u64,
+    min_age_hours: u64,
+    batch_size: usize,
+) -> anyhow::Result<()> {
+    let repos = settings.repositories()?;
+    let doc_repo = repos.documents;
+    let documents_dir = settings.documents_dir.clone();
+    let now = Utc::now();
+
+    println!(
+        "{} Collecting referenced files{}",
+        style("→").cyan(),
+        if dry_run { " (dry run)" } else { "" }
+    );
+    let referenced = collect_referenced_paths(&doc_repo, &documents_dir, batch_size).await?;
+    println!(
+        "  {} files referenced by document versions",
+        referenced.len()
+    );
+
+    let grace = chrono::Duration::days(grace_days as i64);
+    let min_age = Duration::from_secs(min_age_hours * 3600);
+
+    let (restored, orphans, expired) = {
+        let documents_dir = documents_dir.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<_> {
+            let restored = if dry_run {
+                Vec::new()
+            } else {
+                restore_referenced(&documents_dir, &referenced)?
+            };
+            let orphans = find_orphans(&documents_dir, &referenced)?
+                .into_iter()
+                .filter(|(path, _)| is_content_path(&documents_dir, path))
+                .collect();
+            let orphans = older_than(orphans, min_age, std::time::SystemTime::now());
+            let expired = expired_batches(&documents_dir, grace, now)?;
+            Ok((restored, orphans, expired))
+        })
+        .await??
+    };
+
+    for path in &restored {
+        println!(
+            "  {} Restored referenced file {}",
+            style("↻").green(),
+            path.display()
+        );
+    }
+
+    let orphan_bytes: u64 = orphans.iter().map(|(_, size)| size).sum();
+    let expired_files: usize = expired.iter().map(|b| b.files).sum();
+    let expired_bytes: u64 = expired.iter().map(|b| b.bytes).sum();
+
+    if dry_run {
+        for (path, size) in &orphans {
+            println!(
+                "  {} {} ({})",
+                style("?").yellow(),
+                path.display(),
+                format_bytes(*size)
+            );
+        }
+        for batch in &expired {
+            println!(
+                "  {} {} ({} files, {})",
+                style("✗").red(),
+                batch.path.display(),
+                batch.files,
+                format_bytes(batch.bytes)
+            );
+        }
+        println!("\n{} Dry run summary:", style("!").yellow());
+        println!(
+            "  Would quarantine: {} files ({})",
+            orphans.len(),
+            format_bytes(orphan_bytes)
+        );
+        println!(
+            "  Would purge:      {} files ({}) from {} expired batches",
+            expired_files,
+            format_bytes(expired_bytes),
+            expired.len()
+        );
+        println!("  Space reclaimed:  {}", format_bytes(expired_bytes));
+        println!(
+            "  Pending:          {} reclaimable after the {}-day grace period",
+            format_bytes(orphan_bytes),
+            grace_days
+        );
+        return Ok(());
+    }
+
+    let quarantined = if orphans.is_empty() {
+        None
+    } else {
+        let documents_dir = documents_dir.clone();
+        Some(
+            tokio::task::spawn_blocking(move || quarantine_files(&documents_dir, &orphans, now))
+                .await??,
+        )
+    };
+
+    for batch in &expired {
+        if let Err(e) = purge_batch(batch) {
+            eprintln!(
+                "  {} Failed to purge {}: {}",
+                style("✗").red(),
+                batch.path.display(),
+                e
+            );
+        }
+    }
+
+    let remaining = list_batches(&documents_dir)?;
+    let remaining_bytes: u64 = remaining.iter().map(|b| b.bytes).sum();
+
+    println!("\n{} Garbage collection complete:", style("✓").green());
+    if let Some(batch) = quarantined {
+        println!(
+            "  Quarantined:      {} files ({}) in {}",
+            batch.files,
+            format_bytes(batch.bytes),
+            batch.path.display()
+        );
+    } else {
+        println!("  Quarantined:      0 files");
+    }
+    println!(
+        "  Purged:           {} files from {} expired batches",
+        expired_files,
+        expired.len()
+    );
+    println!("  Space reclaimed:  {}", format_bytes(expired_bytes));
+    println!(
+        "  In quarantine:    {} across {} batches",
+        format_bytes(remaining_bytes),
+        remaining.len()
+    );
+
+    Ok(())
+}
+
+fn print_verify_summary(checkpoint: &VerifyCheckpoint) {
+    let status = if checkpoint.is_complete() {
+        "complete"
+    } else {
+        "paused (run again to resume)"
+    };
+    println!("\n{} Verification {}:", style("✓").green(), status);
+    println!("  Files checked:  {}", checkpoint.checked);
+    println!("  Missing:        {}", style(checkpoint.missing).yellow());
+    println!("  Corrupted:      {}", style(checkpoint.corrupted).red());
+    if checkpoint.repaired > 0 {
+        println!("  Repaired:       {}", style(checkpoint.repaired).green());
+    }
+}
@@ -1,14 +1,18 @@
 //! Import system for ingesting documents from various sources.
 //!
 //! This module provides a trait-based abstraction for importing documents
-//! from different formats (WARC, Concordance DAT/OPT, URL lists, etc.)
+//! from different formats (WARC/WACZ, e-discovery load files, URL lists, etc.)
 //! with unified progress tracking, duplicate detection, and resume support.
 
 mod runner;
 pub mod sources;
 
 pub use runner::{FileStorageMode, ImportConfig, ImportRunner};
-pub use sources::{ConcordanceImportSource, MultiPageMode, WarcImportSource};
+pub use sources::{
+    ConcordanceImportSource, DiiImportSource, DirectoryImportSource, EdrmImportSource,
+    FolderMapping, MailboxFormat, MailboxImportSource, MultiPageMode, RelativityImportSource,
+    WaczImportSource, WarcImportSource,
+};
 
 use std::path::{Path, PathBuf};
 
@@ -1,6 +1,7 @@
 //! Import runner that orchestrates import operations with progress tracking.
 
 use std::collections::HashSet;
+use std::path::Path;
 use std::time::Duration;
 
 use console::style;
@@ -22,6 +23,24 @@ pub enum FileStorageMode {
     HardLink,
 }
 
+impl FileStorageMode {
+    /// Store a source file at `dest`, whose parent directory must exist.
+    ///
+    /// `content` is the already-read file content, written as-is in copy mode.
+    /// Hard links fall back to a copy when linking fails (e.g. across
+    /// filesystems).
+    pub fn store(&self, source: &Path, dest: &Path, content: &[u8]) -> std::io::Result<()> {
+        match self {
+            Self::Copy => std::fs::write(dest, content),
+            Self::Move => std::fs::rename(source, dest),
+            Self::HardLink => std::fs::hard_link(source, dest).or_else(|e| {
+                tracing::debug!("Hard link failed ({}), falling back to copy", e);
+                std::fs::copy(source, dest).map(|_| ())
+            }),
+        }
+    }
+}
+
 /// Configuration for import operations.
 #[derive(Debug, Clone)]
 pub struct ImportConfig {
@@ -13,21 +13,18 @@
 //! - **OPT file**: Image load file (standard CSV)
 //!   - Maps Bates numbers to actual file paths
 //!   - Format: `BatesID,Volume,ImagePath,FirstPage,Field5,Field6,PageCount`
+//!
+//! Native and extracted text paths in the DAT (e.g. "Native Path",
+//! "Text Path") are picked up as well; see [`super::load_file`].
 
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
-use console::style;
-
-use crate::{
-    guess_mime_type, runner::FileStorageMode, ImportConfig, ImportProgress, ImportSource,
-    ImportStats,
-};
-use foia::models::{Document, DocumentVersion};
-use foia::repository::extract_filename_parts;
-use foia::storage::compute_storage_path_with_dedup;
+use super::load_file::{LoadFile, LoadFileColumns, LoadFileDocument};
+use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
+use foia::models::BatesRange;
 
 /// Concordance DAT field delimiter (þ, thorn character).
 /// In UTF-8 this is encoded as 0xC3 0xBE.
@@ -38,21 +35,10 @@ const FIELD_DELIMITER_LATIN1: u8 = 0xFE;
 /// Concordance DAT text qualifier (DC4 control character).
 const TEXT_QUALIFIER: u8 = 0x14;
 
-/// A document defined by a Bates range in the DAT file.
-#[derive(Debug, Clone)]
-struct ConcordanceDocument {
-    /// Beginning Bates number.
-    begin_bates: String,
-    /// Ending Bates number.
-    end_bates: String,
-    /// Additional metadata fields from DAT.
-    metadata: HashMap<String, String>,
-}
-
 /// A page mapping from the OPT file.
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
-struct OptPage {
+pub(crate) struct OptPage {
     /// Bates ID for this page.
     bates_id: String,
     /// Volume identifier.
@@ -85,18 +71,12 @@ pub struct ConcordanceImportSource {
     dat_path: PathBuf,
     /// Path to the OPT file.
     opt_path: PathBuf,
-    /// Base directory for resolving file paths.
-    base_path: PathBuf,
     /// Field names from DAT header.
     dat_fields: Vec<String>,
-    /// Parsed documents from DAT.
-    documents: Vec<ConcordanceDocument>,
-    /// Page mappings from OPT (Bates ID -> page info).
-    pages: HashMap<String, OptPage>,
+    /// Parsed documents with their OPT pages attached.
+    load_file: LoadFile,
     /// Multi-page handling mode.
     multi_page_mode: MultiPageMode,
-    /// URL prefix for constructing canonical URLs from filenames.
-    url_prefix: Option<String>,
     /// Settings for database access.
     settings: foia::config::Settings,
 }
@@ -116,23 +96,31 @@ impl ConcordanceImportSource {
         let (dat_path, opt_path, base_path) = Self::resolve_paths(&path)?;
 
         // Parse DAT file
-        let (dat_fields, documents) = Self::parse_dat(&dat_path)?;
+        let (dat_fields, mut documents) = Self::parse_dat(&dat_path)?;
         tracing::info!("Parsed {} documents from DAT file", documents.len());
 
         // Parse OPT file
         let pages = Self::parse_opt(&opt_path)?;
         tracing::info!("Parsed {} page mappings from OPT file", pages.len());
 
+        for doc in &mut documents {
+            Self::attach_pages(doc, &pages);
+        }
+
+        let load_file_dir = dat_path.parent().unwrap_or(&base_path).to_path_buf();
         Ok(Self {
             source_path: path,
             dat_path,
             opt_path,
-            base_path,
             dat_fields,
-            documents,
-            pages,
+            load_file: LoadFile {
+                format: "concordance",
+                base_path,
+                load_file_dir,
+                url_prefix,
+                documents,
+            },
             multi_page_mode,
-            url_prefix,
             settings,
         })
     }
@@ -177,7 +165,7 @@ impl ConcordanceImportSource {
     }
 
     /// Parse DAT file with þ delimiter and 0x14 text qualifier.
-    fn parse_dat(path: &Path) -> anyhow::Result<(Vec<String>, Vec<ConcordanceDocument>)> {
+    fn parse_dat(path: &Path) -> anyhow::Result<(Vec<String>, Vec<LoadFileDocument>)> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
 
@@ -194,13 +182,8 @@ impl ConcordanceImportSource {
             anyhow::bail!("No fields found in DAT header");
         }
 
-        // Find field indices
-        let begin_idx = fields.iter().position(|f| {
-            f.eq_ignore_ascii_case("Begin Bates") || f.eq_ignore_ascii_case("BEGDOC")
-        });
-        let end_idx = fields
-            .iter()
-            .position(|f| f.eq_ignore_ascii_case("End Bates") || f.eq_ignore_ascii_case("ENDDOC"));
+        // Find Bates, native and text columns
+        let columns = LoadFileColumns::from_header(fields.clone());
 
         // Parse data rows
         for line_result in lines {
@@ -210,38 +193,9 @@ impl ConcordanceImportSource {
             }
 
             let values = Self::parse_dat_row(&line);
-            if values.is_empty() {
-                continue;
+            if let Some(doc) = columns.document(&values) {
+                documents.push(doc);
             }
-
-            // Build metadata map
-            let metadata: HashMap<String, String> = fields
-                .iter()
-                .zip(values.iter())
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect();
-
-            // Extract Bates numbers
-            let begin_bates = begin_idx
-                .and_then(|i| values.get(i))
-                .cloned()
-                .or_else(|| values.first().cloned())
-                .unwrap_or_default();
-
-            let end_bates = end_idx
-                .and_then(|i| values.get(i))
-                .cloned()
-                .unwrap_or_else(|| begin_bates.clone());
-
-            if begin_bates.is_empty() {
-                continue;
-            }
-
-            documents.push(ConcordanceDocument {
-                begin_bates,
-                end_bates,
-                metadata,
-            });
         }
 
         Ok((fields, documents))
@@ -252,7 +206,31 @@ impl ConcordanceImportSource {
     /// Format: þfield1þ0x14þfield2þ0x14þfield3þ
     /// - þ marks field boundaries (can be UTF-8 0xC3 0xBE or Latin-1 0xFE)
     /// - 0x14 separates fields
+    ///
+    /// Empty fields are kept when 0x14 separators are present, so values
+    /// stay aligned with the header.
     fn parse_dat_row(line: &[u8]) -> Vec<String> {
+        if line.contains(&TEXT_QUALIFIER) {
+            return line
+                .split(|&b| b == TEXT_QUALIFIER)
+                .map(|raw| {
+                    let mut raw = raw;
+                    while let [rest @ .., b'\r' | b'\n'] = raw {
+                        raw = rest;
+                    }
+                    let raw = raw
+                        .strip_prefix(FIELD_DELIMITER_UTF8)
+                        .or_else(|| raw.strip_prefix(&[FIELD_DELIMITER_LATIN1]))
+                        .unwrap_or(raw);
+                    let raw = raw
+                        .strip_suffix(FIELD_DELIMITER_UTF8)
+                        .or_else(|| raw.strip_suffix(&[FIELD_DELIMITER_LATIN1]))
+                        .unwrap_or(raw);
+                    String::from_utf8_lossy(raw).trim().to_string()
+                })
+                .collect();
+        }
+
         let mut fields = Vec::new();
         let mut current = Vec::new();
         let mut i = 0;
@@ -317,7 +295,7 @@ impl ConcordanceImportSource {
     }
 
     /// Parse OPT file (standard CSV format).
-    fn parse_opt(path: &Path) -> anyhow::Result<HashMap<String, OptPage>> {
+    pub(crate) fn parse_opt(path: &Path) -> anyhow::Result<HashMap<String, OptPage>> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         let mut pages = HashMap::new();
@@ -358,56 +336,24 @@ impl ConcordanceImportSource {
         Ok(pages)
     }
 
-    /// Resolve a file path from OPT, trying multiple strategies.
-    fn resolve_file_path(&self, opt_page: &OptPage) -> Option<PathBuf> {
-        // Convert Windows backslashes to forward slashes
-        let normalized_path = opt_page.image_path.replace('\\', "/");
-        let path = Path::new(&normalized_path);
-
-        // Try multiple resolution strategies
-        let candidates = [
-            // Relative to base (volume root)
-            self.base_path.join(path),
-            // Relative to DATA directory parent
-            self.dat_path
-                .parent()
-                .unwrap_or(&self.base_path)
-                .parent()
-                .unwrap_or(&self.base_path)
-                .join(path),
-            // Strip leading component if it matches volume
-            path.strip_prefix(&opt_page.volume)
-                .map(|p| self.base_path.join(p))
-                .unwrap_or_else(|_| self.base_path.join(path)),
-            // Just the filename in IMAGES
-            self.base_path
-                .join("IMAGES")
-                .join(path.file_name().unwrap_or_default()),
-        ];
-
-        for candidate in &candidates {
-            if candidate.exists() {
-                return Some(candidate.clone());
-            }
-        }
-
-        // Return first attempt even if it doesn't exist
-        Some(candidates[0].clone())
-    }
+    /// Attach the OPT page images covering a document's Bates range.
+    pub(crate) fn attach_pages(doc: &mut LoadFileDocument, pages: &HashMap<String, OptPage>) {
+        let Some(first) = pages.get(&doc.begin_bates) else {
+            return;
+        };
+        doc.volume = Some(first.volume.clone()).filter(|v| !v.is_empty());
+        doc.image_paths.push(first.image_path.clone());
 
-    /// Generate URL for a document.
-    fn generate_url(&self, doc: &ConcordanceDocument) -> String {
-        if let Some(prefix) = &self.url_prefix {
-            if let Some(page) = self.pages.get(&doc.begin_bates) {
-                let normalized = page.image_path.replace('\\', "/");
-                let filename = Path::new(&normalized)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or(&doc.begin_bates);
-                return format!("{}/{}", prefix.trim_end_matches('/'), filename);
+        if let Some(range) = BatesRange::parse(&doc.begin_bates, &doc.end_bates) {
+            for page in 2..=range.page_count().min(u64::from(u32::MAX)) as u32 {
+                let Some(bates) = range.page_number(page) else {
+                    break;
+                };
+                if let Some(opt) = pages.get(&bates.to_string()) {
+                    doc.image_paths.push(opt.image_path.clone());
+                }
             }
         }
-        format!("concordance://{}..{}", doc.begin_bates, doc.end_bates)
     }
 }
 
@@ -430,7 +376,7 @@ impl ImportSource for ConcordanceImportSource {
     }
 
     fn total_count(&self) -> Option<u64> {
-        Some(self.documents.len() as u64)
+        Some(self.load_file.documents.len() as u64)
     }
 
     async fn run_import(
@@ -438,264 +384,16 @@ impl ImportSource for ConcordanceImportSource {
         config: &ImportConfig,
         start_position: u64,
     ) -> anyhow::Result<(ImportProgress, ImportStats)> {
-        let mut stats = ImportStats::default();
-        let mut position = start_position;
-
-        // Get database context
-        let ctx = self.settings.create_db_context()?;
-        let doc_repo = ctx.documents();
-
-        // Get source ID
-        let source_id = config
-            .source_id
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Source ID is required for Concordance import"))?;
-
-        // Skip to resume position
-        let docs_to_process = self.documents.iter().skip(start_position as usize);
-
-        for doc in docs_to_process {
-            // Check limits
-            if config.limit > 0 && stats.imported >= config.limit {
-                break;
-            }
-            if config.scan_limit > 0 && stats.scanned >= config.scan_limit {
-                break;
-            }
-
-            stats.scanned += 1;
-
-            // Generate URL for dedup check
-            let url = self.generate_url(doc);
-
-            // Check for duplicate
-            if config.existing_urls.contains(&url) {
-                stats.skipped += 1;
-                position += 1;
-                continue;
-            }
-
-            // Find the file for this document
-            let opt_page = match self.pages.get(&doc.begin_bates) {
-                Some(p) => p,
-                None => {
-                    tracing::debug!("No OPT mapping for Bates number: {}", doc.begin_bates);
-                    stats.missing_files += 1;
-                    position += 1;
-                    continue;
-                }
-            };
-
-            let file_path = match self.resolve_file_path(opt_page) {
-                Some(p) => p,
-                None => {
-                    tracing::debug!("Could not resolve path for: {}", opt_page.image_path);
-                    stats.missing_files += 1;
-                    position += 1;
-                    continue;
-                }
-            };
-
-            if !file_path.exists() {
-                tracing::debug!(
-                    "File not found: {} (Bates: {})",
-                    file_path.display(),
-                    doc.begin_bates
-                );
-                stats.missing_files += 1;
-                position += 1;
-                continue;
-            }
-
-            // Generate title
-            let title = format!(
-                "{} - {}",
-                doc.begin_bates,
-                if doc.begin_bates != doc.end_bates {
-                    &doc.end_bates
-                } else {
-                    file_path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("Document")
-                }
-            );
-
-            // For dry run, we just need basic info
-            if config.dry_run {
-                let file_size = file_path.metadata().map(|m| m.len()).unwrap_or(0);
-                let mime_type = guess_mime_type(&file_path);
-                println!(
-                    "  {} [{}] {} ({}, {} bytes)",
-                    style("+").green(),
-                    source_id,
-                    url,
-                    mime_type,
-                    file_size
-                );
-                stats.imported += 1;
-                position += 1;
-                continue;
-            }
-
-            // Build metadata
-            let metadata = serde_json::json!({
-                "import_source": "concordance",
-                "begin_bates": doc.begin_bates,
-                "end_bates": doc.end_bates,
-                "volume": opt_page.volume,
-                "original_path": opt_page.image_path,
-                "dat_fields": doc.metadata,
-            });
-
-            // Read file content
-            let content = match std::fs::read(&file_path) {
-                Ok(c) => c,
-                Err(e) => {
-                    tracing::warn!("Failed to read {}: {}", file_path.display(), e);
-                    stats.errors += 1;
-                    position += 1;
-                    continue;
-                }
-            };
-
-            // Compute hash, MIME type, and storage path
-            let mime_type = infer::get(&content)
-                .map(|t| t.mime_type().to_string())
-                .unwrap_or_else(|| guess_mime_type(&file_path));
-            let content_hash = DocumentVersion::compute_hash(&content);
-            let (basename, extension) = extract_filename_parts(&url, &title, &mime_type);
-            let (relative_path, dedup_index) = compute_storage_path_with_dedup(
-                &config.documents_dir,
-                &content_hash,
-                &basename,
-                &extension,
-                &content,
-            );
-            let dest_path = config.documents_dir.join(&relative_path);
-
-            if let Some(parent) = dest_path.parent() {
-                if let Err(e) = std::fs::create_dir_all(parent) {
-                    tracing::warn!("Failed to create directory: {}", e);
-                    stats.errors += 1;
-                    position += 1;
-                    continue;
-                }
-            }
-
-            // Perform the storage-mode-specific file operation
-            let file_op_failed = match config.storage_mode {
-                FileStorageMode::Copy => {
-                    if let Err(e) = std::fs::write(&dest_path, &content) {
-                        tracing::warn!("Failed to write {}: {}", dest_path.display(), e);
-                        true
-                    } else {
-                        false
-                    }
-                }
-                FileStorageMode::Move => {
-                    if let Err(e) = std::fs::rename(&file_path, &dest_path) {
-                        tracing::warn!("Failed to move {}: {}", file_path.display(), e);
-                        true
-                    } else {
-                        false
-                    }
-                }
-                FileStorageMode::HardLink => {
-                    if let Err(e) = std::fs::hard_link(&file_path, &dest_path) {
-                        tracing::debug!("Hard link failed ({}), falling back to copy", e);
-                        if let Err(e) = std::fs::copy(&file_path, &dest_path) {
-                            tracing::warn!("Failed to copy {}: {}", file_path.display(), e);
-                            true
-                        } else {
-                            false
-                        }
-                    } else {
-                        false
-                    }
-                }
-            };
-
-            if file_op_failed {
-                stats.errors += 1;
-                position += 1;
-                continue;
-            }
-
-            // Create document version and save
-            let mut version = DocumentVersion::new_with_metadata(
-                &content,
-                mime_type,
-                Some(url.clone()),
-                file_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .map(|s| s.to_string()),
-                None,
-            );
-            version.dedup_index = dedup_index;
-
-            let save_result: anyhow::Result<bool> = {
-                let existing = doc_repo.get_by_url(&url).await?;
-                if let Some(mut doc) = existing.into_iter().next() {
-                    if doc.add_version(version) {
-                        doc_repo.save_with_versions(&doc).await?;
-                    }
-                } else {
-                    let mut doc = Document::new(
-                        uuid::Uuid::new_v4().to_string(),
-                        source_id.to_string(),
-                        title,
-                        url.clone(),
-                        version,
-                        metadata,
-                    );
-                    doc.tags = config.tags.clone();
-                    doc_repo.save_with_versions(&doc).await?;
-                }
-                Ok(true)
-            };
-
-            match save_result {
-                Ok(_) => {
-                    stats.imported += 1;
-                    stats.imported_urls.push(url);
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to save {}: {}", url, e);
-                    stats.errors += 1;
-                }
-            }
-
-            position += 1;
-
-            // Checkpoint
-            if config.enable_resume
-                && config.checkpoint_interval > 0
-                && stats.scanned % config.checkpoint_interval == 0
-            {
-                let progress = ImportProgress {
-                    position,
-                    done: false,
-                    error: None,
-                };
-                let _ = self.save_progress(&progress);
-            }
-        }
-
-        let progress = ImportProgress {
-            position,
-            done: position >= self.documents.len() as u64,
-            error: None,
-        };
-
-        Ok((progress, stats))
+        self.load_file
+            .import(&*self, &self.settings, config, start_position)
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sources::load_file::LoadFileText;
 
     #[test]
     fn test_parse_dat_row_simple() {
@@ -724,108 +422,67 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_url_with_prefix() {
-        let mut pages = HashMap::new();
-        pages.insert(
-            "EFTA00000001".to_string(),
-            OptPage {
-                bates_id: "EFTA00000001".to_string(),
-                volume: "VOL00001".to_string(),
-                image_path: r"IMAGES\0001\EFTA00000001.pdf".to_string(),
-                is_first_page: true,
-                page_count: Some(1),
-            },
-        );
-
-        let source = ConcordanceImportSource {
-            source_path: PathBuf::from("/tmp"),
-            dat_path: PathBuf::from("/tmp/test.DAT"),
-            opt_path: PathBuf::from("/tmp/test.OPT"),
-            base_path: PathBuf::from("/tmp"),
-            dat_fields: vec!["Begin Bates".into(), "End Bates".into()],
-            documents: Vec::new(),
-            pages,
-            multi_page_mode: MultiPageMode::First,
-            url_prefix: Some("https://www.justice.gov/epstein/files/DataSet%201".into()),
-            settings: foia::config::Settings::default(),
-        };
-
-        let doc = ConcordanceDocument {
-            begin_bates: "EFTA00000001".to_string(),
-            end_bates: "EFTA00000001".to_string(),
-            metadata: HashMap::new(),
-        };
-
-        assert_eq!(
-            source.generate_url(&doc),
-            "https://www.justice.gov/epstein/files/DataSet%201/EFTA00000001.pdf"
-        );
+    fn test_parse_dat_row_keeps_empty_fields() {
+        // þABCþ0x14þþ0x14þDEFþ
+        let line = [
+            0xFE, b'A', b'B', b'C', 0xFE, 0x14, 0xFE, 0xFE, 0x14, 0xFE, b'D', b'E', b'F', 0xFE,
+            b'\r',
+        ];
+        let fields = ConcordanceImportSource::parse_dat_row(&line);
+        assert_eq!(fields, vec!["ABC", "", "DEF"]);
     }
 
     #[test]
-    fn test_generate_url_without_prefix() {
-        let source = ConcordanceImportSource {
-            source_path: PathBuf::from("/tmp"),
-            dat_path: PathBuf::from("/tmp/test.DAT"),
-            opt_path: PathBuf::from("/tmp/test.OPT"),
-            base_path: PathBuf::from("/tmp"),
-            dat_fields: vec!["Begin Bates".into(), "End Bates".into()],
-            documents: Vec::new(),
-            pages: HashMap::new(),
-            multi_page_mode: MultiPageMode::First,
-            url_prefix: None,
-            settings: foia::config::Settings::default(),
-        };
-
-        let doc = ConcordanceDocument {
-            begin_bates: "EFTA00000001".to_string(),
-            end_bates: "EFTA00000005".to_string(),
-            metadata: HashMap::new(),
+    fn test_dat_with_opt_pages_and_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("DATA");
+        std::fs::create_dir_all(&data).unwrap();
+
+        let row = |values: &[&str]| -> Vec<u8> {
+            let mut line = Vec::new();
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    line.push(0x14);
+                }
+                line.push(0xFE);
+                line.extend_from_slice(v.as_bytes());
+                line.push(0xFE);
+            }
+            line.extend_from_slice(b"\r\n");
+            line
         };
-
+        let mut dat = row(&["Begin Bates", "End Bates", "Native Path", "Text Path"]);
+        dat.extend(row(&["ABC0001", "ABC0003", "", r"TEXT\ABC0001.txt"]));
+        dat.extend(row(&["ABC0004", "ABC0004", r"NATIVES\ABC0004.xlsx", ""]));
+        std::fs::write(data.join("VOL001.DAT"), dat).unwrap();
+        std::fs::write(
+            data.join("VOL001.OPT"),
+            "ABC0001,VOL001,IMAGES\\ABC0001.tif,Y,,,3\n\
+             ABC0002,VOL001,IMAGES\\ABC0002.tif,,,,\n\
+             ABC0003,VOL001,IMAGES\\ABC0003.tif,,,,\n",
+        )
+        .unwrap();
+
+        let source = ConcordanceImportSource::new(
+            dir.path().to_path_buf(),
+            MultiPageMode::First,
+            None,
+            foia::config::Settings::default(),
+        )
+        .unwrap();
+        let docs = &source.load_file.documents;
+        assert_eq!(docs.len(), 2);
+
+        assert_eq!(docs[0].image_paths.len(), 3);
+        assert_eq!(docs[0].primary_path(), Some(r"IMAGES\ABC0001.tif"));
+        assert_eq!(docs[0].volume.as_deref(), Some("VOL001"));
         assert_eq!(
-            source.generate_url(&doc),
-            "concordance://EFTA00000001..EFTA00000005"
-        );
-    }
-
-    #[test]
-    fn test_generate_url_prefix_with_trailing_slash() {
-        let mut pages = HashMap::new();
-        pages.insert(
-            "DOC001".to_string(),
-            OptPage {
-                bates_id: "DOC001".to_string(),
-                volume: "VOL001".to_string(),
-                image_path: r"IMAGES\DOC001.tif".to_string(),
-                is_first_page: true,
-                page_count: None,
-            },
+            docs[0].text,
+            Some(LoadFileText::Path(r"TEXT\ABC0001.txt".to_string()))
         );
 
-        let source = ConcordanceImportSource {
-            source_path: PathBuf::from("/tmp"),
-            dat_path: PathBuf::from("/tmp/test.DAT"),
-            opt_path: PathBuf::from("/tmp/test.OPT"),
-            base_path: PathBuf::from("/tmp"),
-            dat_fields: Vec::new(),
-            documents: Vec::new(),
-            pages,
-            multi_page_mode: MultiPageMode::First,
-            url_prefix: Some("https://example.com/files/".into()),
-            settings: foia::config::Settings::default(),
-        };
-
-        let doc = ConcordanceDocument {
-            begin_bates: "DOC001".to_string(),
-            end_bates: "DOC001".to_string(),
-            metadata: HashMap::new(),
-        };
-
-        // Should not produce double slash
-        assert_eq!(
-            source.generate_url(&doc),
-            "https://example.com/files/DOC001.tif"
-        );
+        assert!(docs[1].image_paths.is_empty());
+        assert_eq!(docs[1].primary_path(), Some(r"NATIVES\ABC0004.xlsx"));
+        assert!(docs[1].text.is_none());
     }
 }
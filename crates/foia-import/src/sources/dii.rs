@@ -0,0 +1,302 @@
+//! Summation DII load file importer.
+//!
+//! A DII file is a sequence of records, each introduced by an `@T` token
+//! carrying the document's first Bates number:
+//!
+//! ```text
+//! ; Record 1
+//! @T ABC0001
+//! @C ENDDOC ABC0003
+//! @C CUSTODIAN J. Smith
+//! @FULLTEXT DOC
+//! @EDOC \NATIVES\ABC0001.msg
+//! @D @I\IMAGES\001\
+//! ABC0001.TIF
+//! ABC0002.TIF
+//! ABC0003.TIF
+//! ```
+//!
+//! `@D` sets the image directory (`@I` is the image root) and the lines after
+//! it list one image per page. `@FULLTEXT DOC` means a text file named after
+//! the first image sits beside it; `@FULLTEXT PAGE` means one per page.
+//! Without an `ENDDOC` field the range is derived from the page count.
+
+use std::path::{Path, PathBuf};
+
+use super::load_file::{
+    read_text_lossy, LoadFile, LoadFileColumns, LoadFileDocument, LoadFileText,
+};
+use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
+use foia::models::BatesNumber;
+
+/// Summation DII import source.
+pub struct DiiImportSource {
+    /// Path to the DII file.
+    source_path: PathBuf,
+    /// Parsed documents.
+    load_file: LoadFile,
+    /// Settings for database access.
+    settings: foia::config::Settings,
+}
+
+impl DiiImportSource {
+    /// Create a new DII import source from the path to the DII file.
+    pub fn new(
+        path: PathBuf,
+        url_prefix: Option<String>,
+        settings: foia::config::Settings,
+    ) -> anyhow::Result<Self> {
+        let text = read_text_lossy(&path)?;
+        let documents = parse_dii(&text);
+        tracing::info!("Parsed {} documents from DII file", documents.len());
+
+        let base_path = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Ok(Self {
+            load_file: LoadFile {
+                format: "dii",
+                load_file_dir: base_path.clone(),
+                base_path,
+                url_prefix,
+                documents,
+            },
+            source_path: path,
+            settings,
+        })
+    }
+}
+
+/// How a DII record references its extracted text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FullText {
+    Document,
+    Page,
+}
+
+/// One `@T` record as written in the file.
+#[derive(Debug, Default)]
+struct DiiRecord {
+    begin_bates: String,
+    fields: Vec<(String, String)>,
+    image_dir: String,
+    images: Vec<String>,
+    native: Option<String>,
+    full_text: Option<FullText>,
+}
+
+impl DiiRecord {
+    fn into_document(self) -> Option<LoadFileDocument> {
+        let mut header = vec!["BEGDOC".to_string()];
+        let mut values = vec![self.begin_bates.clone()];
+        for (name, value) in self.fields {
+            header.push(name);
+            values.push(value);
+        }
+        let mut doc = LoadFileColumns::from_header(header).document(&values)?;
+
+        let join = |name: &str| {
+            if self.image_dir.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}\\{}", self.image_dir, name)
+            }
+        };
+        let text_path = |image: &str| {
+            let stem = Path::new(image)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(image);
+            join(&format!("{}.txt", stem))
+        };
+
+        doc.text = match self.full_text {
+            None => None,
+            Some(FullText::Document) => Some(LoadFileText::Path(text_path(
+                self.images
+                    .first()
+                    .map(String::as_str)
+                    .unwrap_or(self.begin_bates.as_str()),
+            ))),
+            Some(FullText::Page) if !self.images.is_empty() => Some(LoadFileText::Pages(
+                self.images.iter().map(|i| text_path(i)).collect(),
+            )),
+            Some(FullText::Page) => None,
+        };
+        doc.image_paths = self.images.iter().map(|i| join(i)).collect();
+        doc.native_path = doc.native_path.or(self.native);
+
+        // Derive the end of the range from the page count when not given
+        if doc.end_bates == doc.begin_bates && doc.image_paths.len() > 1 {
+            if let Some(begin) = BatesNumber::parse(&doc.begin_bates) {
+                doc.end_bates = BatesNumber {
+                    number: begin.number + doc.image_paths.len() as u64 - 1,
+                    ..begin
+                }
+                .to_string();
+            }
+        }
+
+        Some(doc)
+    }
+}
+
+/// Parse the documents in a DII file.
+fn parse_dii(text: &str) -> Vec<LoadFileDocument> {
+    let mut documents = Vec::new();
+    let mut current: Option<DiiRecord> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        let Some(token_line) = line.strip_prefix('@') else {
+            // A page image under the current @D directory
+            if let Some(record) = current.as_mut() {
+                record.images.push(line.to_string());
+            }
+            continue;
+        };
+
+        let (token, value) = token_line
+            .split_once(char::is_whitespace)
+            .map(|(t, v)| (t, v.trim()))
+            .unwrap_or((token_line, ""));
+
+        if token.eq_ignore_ascii_case("T") {
+            if let Some(doc) = current.take().and_then(DiiRecord::into_document) {
+                documents.push(doc);
+            }
+            current = Some(DiiRecord {
+                begin_bates: value.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(record) = current.as_mut() else {
+            continue;
+        };
+        match token.to_ascii_uppercase().as_str() {
+            "C" => {
+                if let Some((name, value)) = value.split_once(char::is_whitespace) {
+                    record
+                        .fields
+                        .push((name.to_string(), value.trim().to_string()));
+                }
+            }
+            "D" => {
+                // "@I" marks the image root, which is the production root here
+                let dir = value.strip_prefix("@I").unwrap_or(value);
+                record.image_dir = dir.trim_end_matches(['\\', '/']).to_string();
+            }
+            "EDOC" => record.native = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "FULLTEXT" => {
+                record.full_text = Some(if value.eq_ignore_ascii_case("PAGE") {
+                    FullText::Page
+                } else {
+                    FullText::Document
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(doc) = current.and_then(DiiRecord::into_document) {
+        documents.push(doc);
+    }
+    documents
+}
+
+#[async_trait::async_trait]
+impl ImportSource for DiiImportSource {
+    fn format_id(&self) -> &'static str {
+        "dii"
+    }
+
+    fn display_name(&self) -> &str {
+        "Summation DII"
+    }
+
+    fn source_path(&self) -> &Path {
+        &self.source_path
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn total_count(&self) -> Option<u64> {
+        Some(self.load_file.documents.len() as u64)
+    }
+
+    async fn run_import(
+        &mut self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        self.load_file
+            .import(&*self, &self.settings, config, start_position)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dii() {
+        let text = "; Record 1\r\n\
+            @T ABC0001\r\n\
+            @C CUSTODIAN J. Smith\r\n\
+            @FULLTEXT DOC\r\n\
+            @EDOC \\NATIVES\\ABC0001.msg\r\n\
+            @D @I\\IMAGES\\001\\\r\n\
+            ABC0001.TIF\r\n\
+            ABC0002.TIF\r\n\
+            ABC0003.TIF\r\n\
+            \r\n\
+            ; Record 2\r\n\
+            @T ABC0004\r\n\
+            @C ENDDOC ABC0005\r\n\
+            @FULLTEXT PAGE\r\n\
+            @D @I\\IMAGES\\002\r\n\
+            ABC0004.TIF\r\n\
+            ABC0005.TIF\r\n";
+
+        let docs = parse_dii(text);
+        assert_eq!(docs.len(), 2);
+
+        let first = &docs[0];
+        assert_eq!(first.begin_bates, "ABC0001");
+        assert_eq!(first.end_bates, "ABC0003");
+        assert_eq!(first.native_path.as_deref(), Some(r"\NATIVES\ABC0001.msg"));
+        assert_eq!(
+            first.image_paths,
+            vec![
+                r"\IMAGES\001\ABC0001.TIF",
+                r"\IMAGES\001\ABC0002.TIF",
+                r"\IMAGES\001\ABC0003.TIF"
+            ]
+        );
+        assert_eq!(
+            first.text,
+            Some(LoadFileText::Path(r"\IMAGES\001\ABC0001.txt".to_string()))
+        );
+        assert_eq!(
+            first.fields.get("CUSTODIAN").map(String::as_str),
+            Some("J. Smith")
+        );
+
+        let second = &docs[1];
+        assert_eq!(second.end_bates, "ABC0005");
+        assert_eq!(
+            second.text,
+            Some(LoadFileText::Pages(vec![
+                r"\IMAGES\002\ABC0004.txt".to_string(),
+                r"\IMAGES\002\ABC0005.txt".to_string(),
+            ]))
+        );
+    }
+}
@@ -0,0 +1,548 @@
+//! Local directory tree import source.
+//!
+//! Imports every file under a directory, such as a USB drive or a folder tree
+//! handed over by a records officer. Files are discovered up front in a
+//! stable (sorted) order so the position in that list can be checkpointed
+//! and resumed.
+//!
+//! Include/exclude globs select files: a pattern containing `/` matches the
+//! path relative to the root, any other pattern matches the file or folder
+//! name. `*` and `?` stay within one path segment, `**` crosses segments.
+//! Matching is case-insensitive, since exported trees often mix case.
+//! Hidden files and folders (starting with `.`) and symlinks are skipped.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use console::style;
+use regex::Regex;
+
+use crate::{guess_mime_type, ImportConfig, ImportProgress, ImportSource, ImportStats};
+use foia::models::{Document, DocumentVersion, Source, SourceType};
+use foia::repository::extract_filename_parts;
+use foia::storage::compute_storage_path_with_dedup;
+
+/// How sub-folders of the import root map onto documents.
+#[derive(Debug, Clone, Default)]
+pub struct FolderMapping {
+    /// Use the top-level folder name as the source ID. Files directly in the
+    /// root fall back to the configured source.
+    pub source_from_folder: bool,
+    /// Tag each document with the names of the folders containing it.
+    pub tags_from_folders: bool,
+}
+
+/// A compiled include/exclude glob.
+#[derive(Debug, Clone)]
+struct Glob {
+    regex: Regex,
+    /// Match against the full relative path rather than the name.
+    match_path: bool,
+}
+
+impl Glob {
+    fn new(pattern: &str) -> anyhow::Result<Self> {
+        let pattern = pattern.trim_start_matches("./");
+        let mut re = String::from("(?i)^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    // "**/" also matches zero folders
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        re.push_str("(?:.*/)?");
+                    } else {
+                        re.push_str(".*");
+                    }
+                }
+                '*' => re.push_str("[^/]*"),
+                '?' => re.push_str("[^/]"),
+                c => re.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        re.push('$');
+
+        Ok(Self {
+            regex: Regex::new(&re)
+                .map_err(|e| anyhow::anyhow!("Invalid glob '{}': {}", pattern, e))?,
+            match_path: pattern.contains('/'),
+        })
+    }
+
+    fn matches(&self, relative_path: &str) -> bool {
+        if self.match_path {
+            self.regex.is_match(relative_path)
+        } else {
+            let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+            self.regex.is_match(name)
+        }
+    }
+}
+
+/// Directory tree import source.
+pub struct DirectoryImportSource {
+    /// Root of the tree being imported.
+    root: PathBuf,
+    /// Name of the root folder, used to build stable document URLs.
+    root_name: String,
+    /// Files to import, relative to the root, using `/` separators.
+    files: Vec<String>,
+    /// Sub-folder mapping options.
+    mapping: FolderMapping,
+    /// Settings for database access.
+    settings: foia::config::Settings,
+}
+
+impl DirectoryImportSource {
+    /// Create a new directory import source, discovering files up front.
+    pub fn new(
+        root: PathBuf,
+        include: &[String],
+        exclude: &[String],
+        mapping: FolderMapping,
+        settings: foia::config::Settings,
+    ) -> anyhow::Result<Self> {
+        if !root.is_dir() {
+            anyhow::bail!("Not a directory: {}", root.display());
+        }
+
+        let include = include
+            .iter()
+            .map(|p| Glob::new(p))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let exclude = exclude
+            .iter()
+            .map(|p| Glob::new(p))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut files = Vec::new();
+        Self::walk(&root, "", &include, &exclude, &mut files)?;
+        files.sort();
+        tracing::info!("Found {} files under {}", files.len(), root.display());
+
+        let root_name = root
+            .canonicalize()
+            .unwrap_or_else(|_| root.clone())
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("import")
+            .to_string();
+
+        Ok(Self {
+            root,
+            root_name,
+            files,
+            mapping,
+            settings,
+        })
+    }
+
+    /// Recursively collect files below `dir` that pass the filters.
+    fn walk(
+        dir: &Path,
+        prefix: &str,
+        include: &[Glob],
+        exclude: &[Glob],
+        files: &mut Vec<String>,
+    ) -> anyhow::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if exclude.iter().any(|g| g.matches(&relative)) {
+                continue;
+            }
+
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                Self::walk(&entry.path(), &relative, include, exclude, files)?;
+            } else if file_type.is_file()
+                && (include.is_empty() || include.iter().any(|g| g.matches(&relative)))
+            {
+                files.push(relative);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stable URL identifying a file for deduplication.
+    fn file_url(&self, relative: &str) -> String {
+        format!("dir://{}/{}", self.root_name, relative)
+    }
+
+    /// Folders containing a file, outermost first.
+    fn folders(relative: &str) -> Vec<&str> {
+        let mut parts: Vec<&str> = relative.split('/').collect();
+        parts.pop();
+        parts
+    }
+
+    /// Source ID for a file, if one can be determined.
+    fn source_for<'a>(&self, relative: &'a str, config: &'a ImportConfig) -> Option<&'a str> {
+        if self.mapping.source_from_folder {
+            if let Some(&folder) = Self::folders(relative).first() {
+                return Some(folder);
+            }
+        }
+        config.source_id.as_deref()
+    }
+
+    /// Tags for a file: configured tags plus folder names when mapped.
+    fn tags_for(&self, relative: &str, config: &ImportConfig) -> Vec<String> {
+        let mut tags = config.tags.clone();
+        if self.mapping.tags_from_folders {
+            for folder in Self::folders(relative) {
+                if !tags.iter().any(|t| t == folder) {
+                    tags.push(folder.to_string());
+                }
+            }
+        }
+        tags
+    }
+
+    /// Make sure a folder-derived source exists, creating it if needed.
+    async fn ensure_source(
+        &self,
+        source_id: &str,
+        sources: &foia::repository::SourceRepository,
+        known: &mut HashSet<String>,
+    ) -> anyhow::Result<()> {
+        if known.contains(source_id) {
+            return Ok(());
+        }
+        if sources.get(source_id).await?.is_none() {
+            println!("  {} Creating source '{}'...", style("→").dim(), source_id);
+            sources
+                .save(&Source::new(
+                    source_id.to_string(),
+                    SourceType::Custom,
+                    source_id.to_string(),
+                    format!("dir://{}/{}", self.root_name, source_id),
+                ))
+                .await?;
+        }
+        known.insert(source_id.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ImportSource for DirectoryImportSource {
+    fn format_id(&self) -> &'static str {
+        "directory"
+    }
+
+    fn display_name(&self) -> &str {
+        "Directory tree"
+    }
+
+    fn source_path(&self) -> &Path {
+        &self.root
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn total_count(&self) -> Option<u64> {
+        Some(self.files.len() as u64)
+    }
+
+    async fn run_import(
+        &mut self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        let mut stats = ImportStats::default();
+        let mut position = start_position;
+
+        let ctx = self.settings.create_db_context()?;
+        let doc_repo = ctx.documents();
+        let source_repo = ctx.sources();
+        let mut known_sources = HashSet::new();
+
+        for relative in self.files.iter().skip(start_position as usize) {
+            if config.limit > 0 && stats.imported >= config.limit {
+                break;
+            }
+            if config.scan_limit > 0 && stats.scanned >= config.scan_limit {
+                break;
+            }
+
+            stats.scanned += 1;
+            position += 1;
+
+            let url = self.file_url(relative);
+            if config.existing_urls.contains(&url) {
+                stats.skipped += 1;
+                continue;
+            }
+
+            let Some(source_id) = self.source_for(relative, config) else {
+                stats.no_source += 1;
+                continue;
+            };
+
+            let file_path = self.root.join(relative);
+            let file_meta = match std::fs::metadata(&file_path) {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::debug!("File not found: {} ({})", file_path.display(), e);
+                    stats.missing_files += 1;
+                    continue;
+                }
+            };
+
+            if config.dry_run {
+                println!(
+                    "  {} [{}] {} ({}, {} bytes)",
+                    style("+").green(),
+                    source_id,
+                    relative,
+                    guess_mime_type(&file_path),
+                    file_meta.len()
+                );
+                stats.imported += 1;
+                continue;
+            }
+
+            if self.mapping.source_from_folder {
+                self.ensure_source(source_id, &source_repo, &mut known_sources)
+                    .await?;
+            }
+
+            let content = match std::fs::read(&file_path) {
+                Ok(c) => c,
+                Err(e) => {
+                    tracing::warn!("Failed to read {}: {}", file_path.display(), e);
+                    stats.errors += 1;
+                    continue;
+                }
+            };
+
+            let filename = file_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("document")
+                .to_string();
+            let modified: Option<DateTime<Utc>> = file_meta.modified().ok().map(Into::into);
+
+            let mime_type = infer::get(&content)
+                .map(|t| t.mime_type().to_string())
+                .unwrap_or_else(|| guess_mime_type(&file_path));
+            let content_hash = DocumentVersion::compute_hash(&content);
+            let (basename, extension) = extract_filename_parts(&url, &filename, &mime_type);
+            let (storage_path, dedup_index) = compute_storage_path_with_dedup(
+                &config.documents_dir,
+                &content_hash,
+                &basename,
+                &extension,
+                &content,
+            );
+            let dest_path = config.documents_dir.join(&storage_path);
+
+            if let Some(parent) = dest_path.parent() {
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    tracing::warn!("Failed to create directory: {}", e);
+                    stats.errors += 1;
+                    continue;
+                }
+            }
+
+            if let Err(e) = config.storage_mode.store(&file_path, &dest_path, &content) {
+                tracing::warn!("Failed to store {}: {}", file_path.display(), e);
+                stats.errors += 1;
+                continue;
+            }
+
+            let mut version = DocumentVersion::new_with_metadata(
+                &content,
+                mime_type,
+                Some(url.clone()),
+                Some(filename.clone()),
+                modified,
+            );
+            version.dedup_index = dedup_index;
+
+            let metadata = serde_json::json!({
+                "import_source": "directory",
+                "import_root": self.root.display().to_string(),
+                "relative_path": relative,
+            });
+
+            let save_result: anyhow::Result<bool> = async {
+                let existing = doc_repo.get_by_url(&url).await?;
+                if let Some(mut doc) = existing.into_iter().next() {
+                    if doc.add_version(version) {
+                        return Ok(doc_repo.save_with_versions(&doc).await?);
+                    }
+                    Ok(true)
+                } else {
+                    let mut doc = Document::new(
+                        uuid::Uuid::new_v4().to_string(),
+                        source_id.to_string(),
+                        filename,
+                        url.clone(),
+                        version,
+                        metadata,
+                    );
+                    doc.tags = self.tags_for(relative, config);
+                    Ok(doc_repo.save_with_versions(&doc).await?)
+                }
+            }
+            .await;
+
+            match save_result {
+                Ok(true) => {
+                    stats.imported += 1;
+                    stats.imported_urls.push(url);
+                }
+                Ok(false) => {
+                    tracing::info!("Skipping deleted document: {}", url);
+                    stats.skipped += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to save {}: {}", url, e);
+                    stats.errors += 1;
+                }
+            }
+
+            if config.enable_resume
+                && config.checkpoint_interval > 0
+                && stats.scanned % config.checkpoint_interval == 0
+            {
+                let _ = self.save_progress(&ImportProgress {
+                    position,
+                    done: false,
+                    error: None,
+                });
+            }
+        }
+
+        let progress = ImportProgress {
+            position,
+            done: position >= self.files.len() as u64,
+            error: None,
+        };
+
+        Ok((progress, stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files_for(root: &Path, include: &[&str], exclude: &[&str]) -> Vec<String> {
+        let include: Vec<String> = include.iter().map(|s| s.to_string()).collect();
+        let exclude: Vec<String> = exclude.iter().map(|s| s.to_string()).collect();
+        let source = DirectoryImportSource::new(
+            root.to_path_buf(),
+            &include,
+            &exclude,
+            FolderMapping::default(),
+            foia::config::Settings::default(),
+        )
+        .unwrap();
+        source.files
+    }
+
+    fn make_tree(root: &Path) {
+        for path in [
+            "memo.pdf",
+            "Finance/2019/budget.PDF",
+            "Finance/2019/notes.txt",
+            "Legal/contract.docx",
+            "Legal/drafts/old.pdf",
+            ".hidden/secret.pdf",
+            "Thumbs.db",
+        ] {
+            let full = root.join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(full, path.as_bytes()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        let name = Glob::new("*.pdf").unwrap();
+        assert!(name.matches("a/b/report.PDF"));
+        assert!(!name.matches("a/b/report.pdf.txt"));
+
+        let path = Glob::new("Finance/*/*.pdf").unwrap();
+        assert!(path.matches("finance/2019/budget.pdf"));
+        assert!(!path.matches("Finance/2019/q1/budget.pdf"));
+
+        let deep = Glob::new("**/drafts/**").unwrap();
+        assert!(deep.matches("Legal/drafts/old.pdf"));
+        assert!(deep.matches("drafts/x/y.pdf"));
+
+        let single = Glob::new("file?.txt").unwrap();
+        assert!(single.matches("file1.txt"));
+        assert!(!single.matches("file10.txt"));
+    }
+
+    #[test]
+    fn test_walk_with_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+
+        assert_eq!(
+            files_for(dir.path(), &[], &["Thumbs.db"]),
+            vec![
+                "Finance/2019/budget.PDF",
+                "Finance/2019/notes.txt",
+                "Legal/contract.docx",
+                "Legal/drafts/old.pdf",
+                "memo.pdf",
+            ]
+        );
+        assert_eq!(
+            files_for(dir.path(), &["*.pdf"], &["drafts"]),
+            vec!["Finance/2019/budget.PDF", "memo.pdf"]
+        );
+    }
+
+    #[test]
+    fn test_folder_mapping() {
+        let dir = tempfile::tempdir().unwrap();
+        make_tree(dir.path());
+        let source = DirectoryImportSource::new(
+            dir.path().to_path_buf(),
+            &[],
+            &[],
+            FolderMapping {
+                source_from_folder: true,
+                tags_from_folders: true,
+            },
+            foia::config::Settings::default(),
+        )
+        .unwrap();
+
+        let config = ImportConfig {
+            source_id: Some("fallback".to_string()),
+            tags: vec!["usb-2024".to_string()],
+            ..ImportConfig::default()
+        };
+        assert_eq!(
+            source.source_for("Finance/2019/budget.PDF", &config),
+            Some("Finance")
+        );
+        assert_eq!(source.source_for("memo.pdf", &config), Some("fallback"));
+        assert_eq!(
+            source.tags_for("Finance/2019/budget.PDF", &config),
+            vec!["usb-2024", "Finance", "2019"]
+        );
+    }
+}
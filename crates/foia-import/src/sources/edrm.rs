@@ -0,0 +1,237 @@
+//! EDRM XML load file importer.
+//!
+//! EDRM XML describes each produced document as a `<Document>` element with
+//! its fields as `<Tag>` elements and its files under `<Files>`:
+//!
+//! ```xml
+//! <Document DocID="ABC0001" MimeType="application/pdf">
+//!   <Tags>
+//!     <Tag TagName="#BegBates" TagValue="ABC0001" />
+//!     <Tag TagName="#EndBates" TagValue="ABC0003" />
+//!   </Tags>
+//!   <Files>
+//!     <File FileType="Native">
+//!       <ExternalFile FilePath="NATIVES\001" FileName="ABC0001.pdf" />
+//!     </File>
+//!     <File FileType="Text">
+//!       <ExternalFile FilePath="TEXT\001" FileName="ABC0001.txt" />
+//!     </File>
+//!   </Files>
+//! </Document>
+//! ```
+//!
+//! Text may also be carried inline in an `<InlineContent>` element. Without a
+//! Bates tag the `DocID` attribute is used.
+
+use std::path::{Path, PathBuf};
+
+use super::load_file::{
+    read_text_lossy, LoadFile, LoadFileColumns, LoadFileDocument, LoadFileText,
+};
+use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
+
+/// EDRM XML import source.
+pub struct EdrmImportSource {
+    /// Path to the XML file.
+    source_path: PathBuf,
+    /// Parsed documents.
+    load_file: LoadFile,
+    /// Settings for database access.
+    settings: foia::config::Settings,
+}
+
+impl EdrmImportSource {
+    /// Create a new EDRM XML import source from the path to the XML file.
+    pub fn new(
+        path: PathBuf,
+        url_prefix: Option<String>,
+        settings: foia::config::Settings,
+    ) -> anyhow::Result<Self> {
+        let xml = read_text_lossy(&path)?;
+        let documents = parse_edrm(&xml)?;
+        tracing::info!("Parsed {} documents from EDRM XML", documents.len());
+
+        let base_path = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Ok(Self {
+            load_file: LoadFile {
+                format: "edrm",
+                load_file_dir: base_path.clone(),
+                base_path,
+                url_prefix,
+                documents,
+            },
+            source_path: path,
+            settings,
+        })
+    }
+}
+
+/// Join an `ExternalFile`'s `FilePath` and `FileName` attributes.
+fn external_file_path(node: roxmltree::Node) -> Option<String> {
+    let name = node.attribute("FileName").filter(|n| !n.is_empty())?;
+    match node
+        .attribute("FilePath")
+        .map(|p| p.trim_end_matches(['\\', '/']))
+    {
+        Some(dir) if !dir.is_empty() => Some(format!("{}\\{}", dir, name)),
+        _ => Some(name.to_string()),
+    }
+}
+
+/// Parse the documents in an EDRM XML load file.
+fn parse_edrm(xml: &str) -> anyhow::Result<Vec<LoadFileDocument>> {
+    let tree =
+        roxmltree::Document::parse(xml).map_err(|e| anyhow::anyhow!("Invalid EDRM XML: {}", e))?;
+
+    let mut documents = Vec::new();
+    for node in tree.descendants().filter(|n| n.has_tag_name("Document")) {
+        // DocID goes first so any Bates tag takes precedence over it
+        let mut header = vec!["DocID".to_string()];
+        let mut values = vec![node.attribute("DocID").unwrap_or_default().to_string()];
+        for tag in node.descendants().filter(|n| n.has_tag_name("Tag")) {
+            if let (Some(name), Some(value)) = (tag.attribute("TagName"), tag.attribute("TagValue"))
+            {
+                header.push(name.trim_start_matches('#').to_string());
+                values.push(value.to_string());
+            }
+        }
+
+        let Some(mut doc) = LoadFileColumns::from_header(header).document(&values) else {
+            continue;
+        };
+
+        for file in node.descendants().filter(|n| n.has_tag_name("File")) {
+            let file_type = file.attribute("FileType").unwrap_or_default();
+            let paths = file
+                .children()
+                .filter(|n| n.has_tag_name("ExternalFile"))
+                .filter_map(external_file_path);
+
+            if file_type.eq_ignore_ascii_case("Native") {
+                if let Some(path) = paths.into_iter().next() {
+                    doc.native_path = Some(path);
+                }
+            } else if file_type.eq_ignore_ascii_case("Image") {
+                doc.image_paths.extend(paths);
+            } else if file_type.eq_ignore_ascii_case("Text") {
+                let inline = file
+                    .children()
+                    .find(|n| n.has_tag_name("InlineContent"))
+                    .and_then(|n| n.text())
+                    .filter(|t| !t.trim().is_empty());
+                doc.text = match inline {
+                    Some(text) => Some(LoadFileText::Inline(text.to_string())),
+                    None => paths.into_iter().next().map(LoadFileText::Path),
+                };
+            }
+        }
+
+        documents.push(doc);
+    }
+
+    Ok(documents)
+}
+
+#[async_trait::async_trait]
+impl ImportSource for EdrmImportSource {
+    fn format_id(&self) -> &'static str {
+        "edrm"
+    }
+
+    fn display_name(&self) -> &str {
+        "EDRM XML"
+    }
+
+    fn source_path(&self) -> &Path {
+        &self.source_path
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn total_count(&self) -> Option<u64> {
+        Some(self.load_file.documents.len() as u64)
+    }
+
+    async fn run_import(
+        &mut self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        self.load_file
+            .import(&*self, &self.settings, config, start_position)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_edrm() {
+        let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<Root DataInterchangeType="Update">
+  <Batch>
+    <Documents>
+      <Document DocID="DOC-1" DocType="File">
+        <Tags>
+          <Tag TagName="#BegBates" TagValue="ABC0001" TagDataType="Text" />
+          <Tag TagName="#EndBates" TagValue="ABC0003" TagDataType="Text" />
+          <Tag TagName="Custodian" TagValue="J. Smith" TagDataType="Text" />
+        </Tags>
+        <Files>
+          <File FileType="Native">
+            <ExternalFile FilePath="NATIVES\001\" FileName="ABC0001.docx" />
+          </File>
+          <File FileType="Text">
+            <ExternalFile FilePath="TEXT\001" FileName="ABC0001.txt" />
+          </File>
+          <File FileType="Image">
+            <ExternalFile FilePath="IMAGES" FileName="ABC0001.tif" />
+            <ExternalFile FilePath="IMAGES" FileName="ABC0002.tif" />
+          </File>
+        </Files>
+      </Document>
+      <Document DocID="ABC0004">
+        <Files>
+          <File FileType="Text">
+            <InlineContent>Meeting moved to Tuesday.</InlineContent>
+          </File>
+        </Files>
+      </Document>
+    </Documents>
+  </Batch>
+</Root>"##;
+
+        let docs = parse_edrm(xml).unwrap();
+        assert_eq!(docs.len(), 2);
+
+        assert_eq!(docs[0].begin_bates, "ABC0001");
+        assert_eq!(docs[0].end_bates, "ABC0003");
+        assert_eq!(
+            docs[0].native_path.as_deref(),
+            Some(r"NATIVES\001\ABC0001.docx")
+        );
+        assert_eq!(docs[0].image_paths.len(), 2);
+        assert_eq!(
+            docs[0].text,
+            Some(LoadFileText::Path(r"TEXT\001\ABC0001.txt".to_string()))
+        );
+        assert_eq!(
+            docs[0].fields.get("Custodian").map(String::as_str),
+            Some("J. Smith")
+        );
+
+        // Falls back to DocID and inline text
+        assert_eq!(docs[1].begin_bates, "ABC0004");
+        assert_eq!(docs[1].end_bates, "ABC0004");
+        assert_eq!(
+            docs[1].text,
+            Some(LoadFileText::Inline(
+                "Meeting moved to Tuesday.".to_string()
+            ))
+        );
+    }
+}
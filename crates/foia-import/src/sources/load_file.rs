@@ -0,0 +1,854 @@
+//! Shared import logic for e-discovery load files.
+//!
+//! Concordance DAT/OPT, EDRM XML, Summation DII and Relativity-style CSV
+//! productions all describe the same thing: a document spanning a Bates
+//! range, backed by a native file and/or page images, often with an
+//! extracted text file. Each format parser produces [`LoadFileDocument`]s
+//! and [`LoadFile::import`] does the rest: resolving paths, storing the
+//! file, recording the Bates range and importing extracted text directly so
+//! the document never needs OCR.
+//!
+//! A multi-page document is stored once, from its native file when there is
+//! one and otherwise from the image of its first page; the Bates range maps
+//! the remaining pages onto it.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use console::style;
+
+use crate::{guess_mime_type, ImportConfig, ImportProgress, ImportSource, ImportStats};
+use foia::models::{BatesRange, Document, DocumentPage, DocumentVersion, PageOcrStatus};
+use foia::repository::{extract_filename_parts, DieselDocumentRepository};
+use foia::storage::compute_storage_path_with_dedup;
+
+/// Header names (normalized, see [`normalize_field`]) holding the first Bates number.
+const BEGIN_FIELDS: &[&str] = &[
+    "beginbates",
+    "begbates",
+    "begdoc",
+    "begno",
+    "bates",
+    "prodbegbates",
+    "controlnumber",
+    "docid",
+];
+/// Header names holding the last Bates number.
+const END_FIELDS: &[&str] = &["endbates", "enddoc", "endno", "prodendbates"];
+/// Header names holding the native file path.
+const NATIVE_FIELDS: &[&str] = &[
+    "nativefile",
+    "nativepath",
+    "nativelink",
+    "nativefilepath",
+    "filepath",
+    "native",
+];
+/// Header names holding extracted text, either a path or the text itself.
+const TEXT_FIELDS: &[&str] = &[
+    "extractedtext",
+    "textpath",
+    "textlink",
+    "textfile",
+    "textfilepath",
+    "fulltext",
+    "textprecedence",
+    "text",
+];
+
+/// Folders productions conventionally keep files in, tried by file name
+/// when the recorded path does not resolve.
+const CONVENTIONAL_DIRS: &[&str] = &["IMAGES", "NATIVES", "NATIVE", "TEXT"];
+
+/// Extracted text referenced by a load file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LoadFileText {
+    /// Path to a text file, relative to the production.
+    Path(String),
+    /// One text file per page, in page order.
+    Pages(Vec<String>),
+    /// Text carried in the load file itself.
+    Inline(String),
+}
+
+/// A produced document described by a load file.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LoadFileDocument {
+    /// First Bates number.
+    pub begin_bates: String,
+    /// Last Bates number (equal to `begin_bates` for single pages).
+    pub end_bates: String,
+    /// Native file path as written in the load file.
+    pub native_path: Option<String>,
+    /// Page image paths in page order.
+    pub image_paths: Vec<String>,
+    /// Extracted text.
+    pub text: Option<LoadFileText>,
+    /// Volume identifier, if the load file names one.
+    pub volume: Option<String>,
+    /// All other fields from the load file.
+    pub fields: HashMap<String, String>,
+}
+
+impl LoadFileDocument {
+    /// The file stored for this document: the native, else the first page.
+    pub fn primary_path(&self) -> Option<&str> {
+        self.native_path
+            .as_deref()
+            .or_else(|| self.image_paths.first().map(String::as_str))
+    }
+}
+
+/// Normalize a header name for matching: lower case, alphanumerics only.
+pub(crate) fn normalize_field(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn find_field(header: &[String], names: &[&str]) -> Option<usize> {
+    // Earlier names take precedence over later (more generic) ones
+    names.iter().find_map(|name| {
+        header
+            .iter()
+            .position(|field| normalize_field(field) == *name)
+    })
+}
+
+/// Column positions of the well-known fields in a delimited load file.
+#[derive(Debug, Clone)]
+pub(crate) struct LoadFileColumns {
+    header: Vec<String>,
+    begin: usize,
+    end: Option<usize>,
+    native: Option<usize>,
+    text: Option<usize>,
+}
+
+impl LoadFileColumns {
+    /// Locate fields by header name. Without a recognizable Bates column,
+    /// the first column is used.
+    pub fn from_header(header: Vec<String>) -> Self {
+        Self {
+            begin: find_field(&header, BEGIN_FIELDS).unwrap_or(0),
+            end: find_field(&header, END_FIELDS),
+            native: find_field(&header, NATIVE_FIELDS),
+            text: find_field(&header, TEXT_FIELDS),
+            header,
+        }
+    }
+
+    /// Build a document from one row. Returns `None` for rows without a
+    /// Bates number.
+    pub fn document(&self, values: &[String]) -> Option<LoadFileDocument> {
+        let get = |i: Option<usize>| {
+            i.and_then(|i| values.get(i))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+
+        let begin_bates = get(Some(self.begin))?.to_string();
+        let end_bates = get(self.end).unwrap_or(begin_bates.as_str()).to_string();
+        let text = get(self.text).map(|t| {
+            if looks_like_path(t) {
+                LoadFileText::Path(t.to_string())
+            } else {
+                LoadFileText::Inline(t.to_string())
+            }
+        });
+
+        let fields = self
+            .header
+            .iter()
+            .zip(values.iter())
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != self.text)
+            .map(|(_, (k, v))| (k.clone(), v.clone()))
+            .collect();
+
+        Some(LoadFileDocument {
+            begin_bates,
+            end_bates,
+            native_path: get(self.native).map(|s| s.to_string()),
+            image_paths: Vec::new(),
+            text,
+            volume: None,
+            fields,
+        })
+    }
+}
+
+/// Whether a text field value is a file reference rather than the text.
+fn looks_like_path(value: &str) -> bool {
+    !value.contains('\n')
+        && value.len() < 260
+        && Path::new(&value.replace('\\', "/"))
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("txt"))
+}
+
+/// Split delimited text into records, honoring quoted fields.
+///
+/// Quoted fields may contain the delimiter, newlines and doubled quotes.
+pub(crate) fn parse_delimited(text: &str, delimiter: char, quote: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == quote {
+                if chars.peek() == Some(&quote) {
+                    field.push(quote);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == quote && field.is_empty() {
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            if record.iter().any(|f| !f.is_empty()) {
+                records.push(std::mem::take(&mut record));
+            }
+            record.clear();
+        } else {
+            field.push(c);
+        }
+    }
+
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// Read a load file as text, decoding Latin-1 when it is not valid UTF-8.
+pub(crate) fn read_text_lossy(path: &Path) -> std::io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(decode_text(bytes))
+}
+
+fn decode_text(mut bytes: Vec<u8>) -> String {
+    // Drop a UTF-8 byte order mark
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        bytes.drain(..3);
+    }
+    match String::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// A parsed load file and how to resolve the paths it contains.
+#[derive(Debug, Clone)]
+pub(crate) struct LoadFile {
+    /// Format ID, recorded as `import_source` and used for fallback URLs.
+    pub format: &'static str,
+    /// Production root (volume directory).
+    pub base_path: PathBuf,
+    /// Directory containing the load file itself.
+    pub load_file_dir: PathBuf,
+    /// URL prefix for constructing canonical URLs from filenames.
+    pub url_prefix: Option<String>,
+    /// Parsed documents.
+    pub documents: Vec<LoadFileDocument>,
+}
+
+impl LoadFile {
+    /// Canonical URL for a document.
+    ///
+    /// Concordance URLs have always been derived from the OPT image of the
+    /// first page, so they stay that way even when a native file is stored;
+    /// otherwise re-importing an existing production would duplicate it.
+    pub fn url(&self, doc: &LoadFileDocument) -> String {
+        let path = if self.format == "concordance" {
+            doc.image_paths.first().map(String::as_str)
+        } else {
+            doc.primary_path()
+        };
+        if let (Some(prefix), Some(path)) = (&self.url_prefix, path) {
+            let normalized = path.replace('\\', "/");
+            let filename = Path::new(&normalized)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&doc.begin_bates);
+            return format!("{}/{}", prefix.trim_end_matches('/'), filename);
+        }
+        format!("{}://{}..{}", self.format, doc.begin_bates, doc.end_bates)
+    }
+
+    /// Resolve a path from the load file, trying the usual production layouts.
+    ///
+    /// Returns the first candidate that exists, or the first candidate if
+    /// none do. Paths that climb out with `..` are refused, since imports
+    /// with `--move` rename whatever file they resolve to.
+    pub fn resolve_path(&self, raw: &str, volume: Option<&str>) -> Option<PathBuf> {
+        // Convert Windows backslashes and drop any leading separator or
+        // drive-relative marker, since paths are relative to the production
+        let normalized = raw.replace('\\', "/");
+        let normalized = normalized.trim_start_matches("./").trim_start_matches('/');
+        let path = Path::new(normalized);
+        if !path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return None;
+        }
+
+        let mut candidates = vec![
+            // Relative to the production root
+            self.base_path.join(path),
+            // Relative to the parent of the load file's directory (DATA/..)
+            self.load_file_dir
+                .parent()
+                .unwrap_or(&self.base_path)
+                .join(path),
+            // Relative to the load file itself
+            self.load_file_dir.join(path),
+        ];
+        // Strip a leading component naming the volume
+        if let Some(stripped) = volume.and_then(|v| path.strip_prefix(v).ok()) {
+            candidates.push(self.base_path.join(stripped));
+        }
+        // Just the filename in a conventional folder
+        if let Some(name) = path.file_name() {
+            for dir in CONVENTIONAL_DIRS {
+                candidates.push(self.base_path.join(dir).join(name));
+            }
+        }
+
+        let found = candidates.iter().find(|c| c.exists());
+        Some(found.unwrap_or(&candidates[0]).clone())
+    }
+
+    /// Load a document's extracted text, if it has any.
+    fn load_text(&self, doc: &LoadFileDocument) -> Option<String> {
+        match doc.text.as_ref()? {
+            LoadFileText::Inline(text) => Some(text.clone()),
+            LoadFileText::Path(raw) => self.read_text(raw, doc.volume.as_deref()),
+            LoadFileText::Pages(raws) => {
+                // Form feeds keep the page breaks for import_text
+                let pages: Vec<String> = raws
+                    .iter()
+                    .map(|raw| self.read_text(raw, doc.volume.as_deref()))
+                    .collect::<Option<_>>()?;
+                Some(pages.join("\x0c"))
+            }
+        }
+    }
+
+    fn read_text(&self, raw: &str, volume: Option<&str>) -> Option<String> {
+        let Some(path) = self.resolve_path(raw, volume) else {
+            tracing::warn!("Text path {} leaves the production; not reading it", raw);
+            return None;
+        };
+        match std::fs::read(&path) {
+            Ok(bytes) => Some(decode_text(bytes)),
+            Err(e) => {
+                tracing::debug!("Text file {} not readable: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Import the parsed documents, starting at `start_position`.
+    pub async fn import(
+        &self,
+        source: &dyn ImportSource,
+        settings: &foia::config::Settings,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        let mut stats = ImportStats::default();
+        let mut position = start_position;
+
+        // Get database context
+        let ctx = settings.create_db_context()?;
+        let doc_repo = ctx.documents();
+
+        let source_id = config.source_id.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Source ID is required for {} import", source.display_name())
+        })?;
+
+        // Skip to resume position
+        let docs_to_process = self.documents.iter().skip(start_position as usize);
+
+        for doc in docs_to_process {
+            // Check limits
+            if config.limit > 0 && stats.imported >= config.limit {
+                break;
+            }
+            if config.scan_limit > 0 && stats.scanned >= config.scan_limit {
+                break;
+            }
+
+            stats.scanned += 1;
+            position += 1;
+
+            // Generate URL for dedup check
+            let url = self.url(doc);
+            if config.existing_urls.contains(&url) {
+                stats.skipped += 1;
+                continue;
+            }
+
+            // Find the file for this document. Text-only productions store
+            // the text itself.
+            let text = self.load_text(doc);
+            let raw_path = doc.primary_path().or(match &doc.text {
+                Some(LoadFileText::Path(raw)) => Some(raw.as_str()),
+                _ => None,
+            });
+            let file_path = match raw_path {
+                Some(raw) => match self.resolve_path(raw, doc.volume.as_deref()) {
+                    Some(path) => Some(path),
+                    None => {
+                        tracing::warn!(
+                            "Path {} leaves the production; skipping {}",
+                            raw,
+                            doc.begin_bates
+                        );
+                        stats.errors += 1;
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            let (content, file_path) = match file_path {
+                Some(path) if path.exists() => {
+                    if config.dry_run {
+                        (Vec::new(), Some(path))
+                    } else {
+                        match std::fs::read(&path) {
+                            Ok(c) => (c, Some(path)),
+                            Err(e) => {
+                                tracing::warn!("Failed to read {}: {}", path.display(), e);
+                                stats.errors += 1;
+                                continue;
+                            }
+                        }
+                    }
+                }
+                Some(path) if text.is_none() => {
+                    tracing::debug!(
+                        "File not found: {} (Bates: {})",
+                        path.display(),
+                        doc.begin_bates
+                    );
+                    stats.missing_files += 1;
+                    continue;
+                }
+                _ => match &text {
+                    Some(t) => (t.clone().into_bytes(), None),
+                    None => {
+                        tracing::debug!("No file or text for Bates number: {}", doc.begin_bates);
+                        stats.missing_files += 1;
+                        continue;
+                    }
+                },
+            };
+
+            // Generate title
+            let title = format!(
+                "{} - {}",
+                doc.begin_bates,
+                if doc.begin_bates != doc.end_bates {
+                    &doc.end_bates
+                } else {
+                    file_path
+                        .as_deref()
+                        .and_then(|p| p.file_stem())
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("Document")
+                }
+            );
+
+            let mime_type = match &file_path {
+                Some(p) => infer::get(&content)
+                    .map(|t| t.mime_type().to_string())
+                    .unwrap_or_else(|| guess_mime_type(p)),
+                None => "text/plain".to_string(),
+            };
+
+            if config.dry_run {
+                let file_size = file_path
+                    .as_deref()
+                    .and_then(|p| p.metadata().ok())
+                    .map(|m| m.len())
+                    .unwrap_or(content.len() as u64);
+                println!(
+                    "  {} [{}] {} ({}, {} bytes{})",
+                    style("+").green(),
+                    source_id,
+                    url,
+                    mime_type,
+                    file_size,
+                    if text.is_some() { ", with text" } else { "" }
+                );
+                stats.imported += 1;
+                continue;
+            }
+
+            let metadata = serde_json::json!({
+                "import_source": self.format,
+                "begin_bates": doc.begin_bates,
+                "end_bates": doc.end_bates,
+                "volume": doc.volume,
+                "original_path": doc.primary_path(),
+                "page_images": doc.image_paths.len(),
+                "dat_fields": doc.fields,
+            });
+
+            // Compute hash and storage path
+            let content_hash = DocumentVersion::compute_hash(&content);
+            let (basename, extension) = extract_filename_parts(&url, &title, &mime_type);
+            let (relative_path, dedup_index) = compute_storage_path_with_dedup(
+                &config.documents_dir,
+                &content_hash,
+                &basename,
+                &extension,
+                &content,
+            );
+            let dest_path = config.documents_dir.join(&relative_path);
+
+            if let Some(parent) = dest_path.parent() {
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    tracing::warn!("Failed to create directory: {}", e);
+                    stats.errors += 1;
+                    continue;
+                }
+            }
+
+            // Inline text has no source file to move or link
+            let stored = match &file_path {
+                Some(p) => config.storage_mode.store(p, &dest_path, &content),
+                None => std::fs::write(&dest_path, &content),
+            };
+            if let Err(e) = stored {
+                tracing::warn!("Failed to store {}: {}", url, e);
+                stats.errors += 1;
+                continue;
+            }
+
+            let mut version = DocumentVersion::new_with_metadata(
+                &content,
+                mime_type,
+                Some(url.clone()),
+                file_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .and_then(|n| n.to_str())
+                    .map(|s| s.to_string()),
+                None,
+            );
+            version.dedup_index = dedup_index;
+
+            let save_result: anyhow::Result<bool> = async {
+                let existing = doc_repo.get_by_url(&url).await?;
+                let doc_id = if let Some(mut existing) = existing.into_iter().next() {
+                    if existing.add_version(version)
+                        && !doc_repo.save_with_versions(&existing).await?
+                    {
+                        return Ok(false);
+                    }
+                    existing.id
+                } else {
+                    let mut new_doc = Document::new(
+                        uuid::Uuid::new_v4().to_string(),
+                        source_id.to_string(),
+                        title,
+                        url.clone(),
+                        version,
+                        metadata,
+                    );
+                    new_doc.tags = config.tags.clone();
+                    if !doc_repo.save_with_versions(&new_doc).await? {
+                        return Ok(false);
+                    }
+                    new_doc.id
+                };
+                match BatesRange::parse(&doc.begin_bates, &doc.end_bates) {
+                    Some(range) => {
+                        doc_repo.set_bates_range(&doc_id, &range).await?;
+                    }
+                    None => tracing::debug!(
+                        "Unparseable Bates range {}..{}",
+                        doc.begin_bates,
+                        doc.end_bates
+                    ),
+                }
+                if let Some(text) = &text {
+                    import_text(&doc_repo, &doc_id, text, self.format).await?;
+                }
+                Ok(true)
+            }
+            .await;
+
+            match save_result {
+                Ok(true) => {
+                    stats.imported += 1;
+                    stats.imported_urls.push(url);
+                }
+                Ok(false) => {
+                    tracing::info!("Skipping deleted document: {}", url);
+                    stats.skipped += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to save {}: {}", url, e);
+                    stats.errors += 1;
+                }
+            }
+
+            // Checkpoint
+            if config.enable_resume
+                && config.checkpoint_interval > 0
+                && stats.scanned % config.checkpoint_interval == 0
+            {
+                let progress = ImportProgress {
+                    position,
+                    done: false,
+                    error: None,
+                };
+                let _ = source.save_progress(&progress);
+            }
+        }
+
+        let progress = ImportProgress {
+            position,
+            done: position >= self.documents.len() as u64,
+            error: None,
+        };
+
+        Ok((progress, stats))
+    }
+}
+
+/// Store produced text as the document's pages and mark text extraction
+/// complete, so the analysis pipeline skips OCR.
+///
+/// Form feeds separate pages, as in text exported per page.
+async fn import_text(
+    doc_repo: &DieselDocumentRepository,
+    doc_id: &str,
+    text: &str,
+    format: &str,
+) -> anyhow::Result<()> {
+    let Some(version_id) = doc_repo.get_current_version_id(doc_id).await? else {
+        return Ok(());
+    };
+    if doc_repo.count_pages(doc_id, version_id as i32).await? > 0 {
+        return Ok(());
+    }
+
+    let mut page_texts: Vec<&str> = text.split('\x0c').collect();
+    while page_texts.len() > 1 && page_texts.last().is_some_and(|t| t.trim().is_empty()) {
+        page_texts.pop();
+    }
+
+    let pages: Vec<DocumentPage> = page_texts
+        .iter()
+        .enumerate()
+        .map(|(i, page_text)| {
+            let mut page = DocumentPage::new(doc_id.to_string(), version_id, i as u32 + 1);
+            page.pdf_text = Some(page_text.to_string());
+            page.final_text = Some(page_text.to_string());
+            page.ocr_status = PageOcrStatus::OcrComplete;
+            page
+        })
+        .collect();
+
+    doc_repo.save_pages_batch(&pages).await?;
+    doc_repo.stamp_bates_pages(doc_id).await?;
+    doc_repo.finalize_document(doc_id).await?;
+
+    // Record completion so this document won't be picked up for OCR
+    doc_repo
+        .store_analysis_result_for_document(
+            doc_id,
+            version_id as i32,
+            "ocr",
+            "load_file",
+            Some(format),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_file(url_prefix: Option<&str>) -> LoadFile {
+        LoadFile {
+            format: "concordance",
+            base_path: PathBuf::from("/tmp"),
+            load_file_dir: PathBuf::from("/tmp/DATA"),
+            url_prefix: url_prefix.map(|s| s.to_string()),
+            documents: Vec::new(),
+        }
+    }
+
+    fn document(begin: &str, end: &str, image: Option<&str>) -> LoadFileDocument {
+        LoadFileDocument {
+            begin_bates: begin.to_string(),
+            end_bates: end.to_string(),
+            image_paths: image.into_iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_url_with_prefix() {
+        let doc = document(
+            "EFTA00000001",
+            "EFTA00000001",
+            Some(r"IMAGES\0001\EFTA00000001.pdf"),
+        );
+        assert_eq!(
+            load_file(Some("https://www.justice.gov/epstein/files/DataSet%201")).url(&doc),
+            "https://www.justice.gov/epstein/files/DataSet%201/EFTA00000001.pdf"
+        );
+    }
+
+    #[test]
+    fn test_url_without_prefix() {
+        let doc = document("EFTA00000001", "EFTA00000005", None);
+        assert_eq!(
+            load_file(None).url(&doc),
+            "concordance://EFTA00000001..EFTA00000005"
+        );
+    }
+
+    #[test]
+    fn test_url_prefix_with_trailing_slash() {
+        let doc = document("DOC001", "DOC001", Some(r"IMAGES\DOC001.tif"));
+        // Should not produce double slash
+        assert_eq!(
+            load_file(Some("https://example.com/files/")).url(&doc),
+            "https://example.com/files/DOC001.tif"
+        );
+    }
+
+    #[test]
+    fn test_url_prefers_native() {
+        let mut doc = document("DOC001", "DOC003", Some(r"IMAGES\DOC001.tif"));
+        doc.native_path = Some(r"NATIVES\DOC001.xlsx".to_string());
+        let mut relativity = load_file(Some("https://example.com"));
+        relativity.format = "relativity";
+        assert_eq!(relativity.url(&doc), "https://example.com/DOC001.xlsx");
+    }
+
+    #[test]
+    fn test_concordance_url_uses_first_image() {
+        let mut doc = document("DOC001", "DOC003", Some(r"IMAGES\DOC001.tif"));
+        doc.native_path = Some(r"NATIVES\DOC001.xlsx".to_string());
+        assert_eq!(
+            load_file(Some("https://example.com")).url(&doc),
+            "https://example.com/DOC001.tif"
+        );
+        assert_eq!(doc.primary_path(), Some(r"NATIVES\DOC001.xlsx"));
+    }
+
+    #[test]
+    fn test_columns_from_header() {
+        let header: Vec<String> = [
+            "Control Number",
+            "End Bates",
+            "Native Path",
+            "Extracted Text",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let columns = LoadFileColumns::from_header(header);
+
+        let row: Vec<String> = [
+            "ABC0001",
+            "ABC0003",
+            r"NATIVES\ABC0001.msg",
+            r"TEXT\ABC0001.txt",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let doc = columns.document(&row).unwrap();
+        assert_eq!(doc.begin_bates, "ABC0001");
+        assert_eq!(doc.end_bates, "ABC0003");
+        assert_eq!(doc.native_path.as_deref(), Some(r"NATIVES\ABC0001.msg"));
+        assert_eq!(
+            doc.text,
+            Some(LoadFileText::Path(r"TEXT\ABC0001.txt".to_string()))
+        );
+        assert!(!doc.fields.contains_key("Extracted Text"));
+
+        let inline: Vec<String> = ["ABC0004", "", "", "Dear Sir,\nPlease find..."]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let doc = columns.document(&inline).unwrap();
+        assert_eq!(doc.end_bates, "ABC0004");
+        assert!(doc.native_path.is_none());
+        assert!(matches!(doc.text, Some(LoadFileText::Inline(_))));
+    }
+
+    #[test]
+    fn test_parse_delimited_quotes() {
+        let records = parse_delimited(
+            "\"Bates\",\"Text\"\r\n\"A1\",\"He said \"\"hi\"\", then\nleft\"\n\nA2,plain\n",
+            ',',
+            '"',
+        );
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1][1], "He said \"hi\", then\nleft");
+        assert_eq!(records[2], vec!["A2", "plain"]);
+    }
+
+    #[test]
+    fn test_resolve_path_conventional_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("NATIVES")).unwrap();
+        std::fs::write(dir.path().join("NATIVES/ABC0001.msg"), b"x").unwrap();
+
+        let lf = LoadFile {
+            base_path: dir.path().to_path_buf(),
+            load_file_dir: dir.path().join("DATA"),
+            ..load_file(None)
+        };
+        assert_eq!(
+            lf.resolve_path(r"\\server\share\ABC0001.msg", None),
+            Some(dir.path().join("NATIVES/ABC0001.msg"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_path_rejects_parent_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let lf = LoadFile {
+            base_path: dir.path().join("VOL001"),
+            load_file_dir: dir.path().join("VOL001/DATA"),
+            ..load_file(None)
+        };
+        assert_eq!(lf.resolve_path(r"..\..\etc\passwd", None), None);
+        assert_eq!(lf.resolve_path("NATIVES/../../secret.pdf", None), None);
+        assert!(lf.resolve_path(r".\NATIVES\ABC0001.pdf", None).is_some());
+    }
+}
@@ -0,0 +1,802 @@
+//! Mailbox importer for mbox files and Maildir directories.
+//!
+//! Splits a mailbox into one `message/rfc822` document per message. Thread
+//! headers (Message-ID, In-Reply-To, References) and the correspondents are
+//! kept in document metadata, attachments are recorded as virtual files, and
+//! sender/recipient addresses are saved as `email` entities.
+//!
+//! ## Formats
+//!
+//! - **mbox**: a single file where each message starts with a `From ` line
+//!   following a blank line. `>From ` escaping (mboxrd/mboxo) is undone.
+//!   Resume uses the byte offset of the next message.
+//! - **Maildir**: a directory with `cur/` and `new/` subdirectories, one file
+//!   per message. Maildir++ subfolders (`.Sent/cur`, ...) are included and
+//!   recorded as the message's folder. Resume uses the message index.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use console::style;
+use mail_parser::{Address, HeaderValue, MessageParser, MimeHeaders};
+
+use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
+use foia::models::{Document, DocumentVersion, VirtualFile, VirtualFileStatus};
+use foia::repository::diesel_document::entities::EMAIL_ENTITY_TYPE;
+use foia::repository::models::NewDocumentEntity;
+use foia::repository::{extract_filename_parts, DieselDocumentRepository};
+use foia::storage::compute_storage_path_with_dedup;
+
+/// Mailbox storage format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailboxFormat {
+    Mbox,
+    Maildir,
+}
+
+impl MailboxFormat {
+    /// Detect the format of a mailbox path.
+    pub fn detect(path: &Path) -> Option<Self> {
+        if path.is_file() {
+            Some(Self::Mbox)
+        } else if is_maildir(path) {
+            Some(Self::Maildir)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_maildir(path: &Path) -> bool {
+    path.join("cur").is_dir() || path.join("new").is_dir()
+}
+
+/// A raw message split out of an mbox file.
+#[derive(Debug, Clone)]
+struct MboxMessage {
+    /// Byte offset of the message's `From ` separator line.
+    offset: u64,
+    /// Message bytes without the separator line.
+    raw: Vec<u8>,
+}
+
+/// Splits an mbox stream into messages.
+struct MboxReader<R> {
+    reader: R,
+    /// Byte offset of the next unread line.
+    offset: u64,
+    /// Offset of an already-consumed separator starting the next message.
+    next_start: Option<u64>,
+}
+
+impl<R: BufRead> MboxReader<R> {
+    /// Create a reader positioned at `offset`, which must be the start of a
+    /// line (normally a message separator).
+    fn new(reader: R, offset: u64) -> Self {
+        Self {
+            reader,
+            offset,
+            next_start: None,
+        }
+    }
+
+    /// Offset to resume from after the last returned message.
+    fn resume_position(&self) -> u64 {
+        self.next_start.unwrap_or(self.offset)
+    }
+
+    fn read_line(&mut self, line: &mut Vec<u8>) -> std::io::Result<usize> {
+        line.clear();
+        let n = self.reader.read_until(b'\n', line)?;
+        self.offset += n as u64;
+        Ok(n)
+    }
+
+    fn next_message(&mut self) -> std::io::Result<Option<MboxMessage>> {
+        let mut line = Vec::new();
+
+        let start = match self.next_start.take() {
+            Some(start) => start,
+            None => loop {
+                let line_start = self.offset;
+                if self.read_line(&mut line)? == 0 {
+                    return Ok(None);
+                }
+                if line.starts_with(b"From ") {
+                    break line_start;
+                }
+            },
+        };
+
+        let mut raw = Vec::new();
+        let mut prev_blank = false;
+        loop {
+            let line_start = self.offset;
+            if self.read_line(&mut line)? == 0 {
+                break;
+            }
+            if prev_blank && line.starts_with(b"From ") {
+                self.next_start = Some(line_start);
+                break;
+            }
+            prev_blank = line == b"\n" || line == b"\r\n";
+
+            // mboxrd: ">From " and ">>From " lose one level of quoting
+            let quotes = line.iter().take_while(|&&b| b == b'>').count();
+            if quotes > 0 && line[quotes..].starts_with(b"From ") {
+                raw.extend_from_slice(&line[1..]);
+            } else {
+                raw.extend_from_slice(&line);
+            }
+        }
+
+        // Drop the blank line that separates messages
+        if raw.ends_with(b"\r\n\r\n") {
+            raw.truncate(raw.len() - 2);
+        } else if raw.ends_with(b"\n\n") {
+            raw.truncate(raw.len() - 1);
+        }
+
+        Ok(Some(MboxMessage { offset: start, raw }))
+    }
+}
+
+/// List message files in a Maildir as `(folder, path)`, sorted.
+fn list_maildir(root: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut folders = vec![("INBOX".to_string(), root.to_path_buf())];
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(folder) = name.strip_prefix('.') {
+            if !folder.is_empty() && entry.file_type()?.is_dir() && is_maildir(&entry.path()) {
+                folders.push((folder.to_string(), entry.path()));
+            }
+        }
+    }
+
+    let mut messages = Vec::new();
+    for (folder, dir) in folders {
+        for sub in ["cur", "new"] {
+            let Ok(entries) = std::fs::read_dir(dir.join(sub)) else {
+                continue;
+            };
+            for entry in entries {
+                let entry = entry?;
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if !hidden && entry.file_type()?.is_file() {
+                    messages.push((folder.clone(), entry.path()));
+                }
+            }
+        }
+    }
+    messages.sort();
+    Ok(messages)
+}
+
+/// An attachment pulled from a message.
+#[derive(Debug, Clone)]
+struct MailAttachment {
+    filename: String,
+    mime_type: String,
+    content: Vec<u8>,
+}
+
+/// Headers and attachments of a parsed message.
+#[derive(Debug, Clone, Default)]
+struct MailMessage {
+    message_id: Option<String>,
+    in_reply_to: Vec<String>,
+    references: Vec<String>,
+    subject: Option<String>,
+    from: Vec<String>,
+    to: Vec<String>,
+    cc: Vec<String>,
+    date: Option<DateTime<Utc>>,
+    attachments: Vec<MailAttachment>,
+}
+
+fn header_list(value: &HeaderValue) -> Vec<String> {
+    value
+        .as_text_list()
+        .map(|ids| ids.into_iter().map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn addresses(address: Option<&Address>) -> Vec<String> {
+    address
+        .map(|addrs| {
+            addrs
+                .iter()
+                .filter_map(|a| a.address())
+                .map(|a| a.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl MailMessage {
+    fn parse(raw: &[u8]) -> Option<Self> {
+        let message = MessageParser::default().parse(raw)?;
+
+        let attachments = message
+            .attachments()
+            .filter_map(|part| {
+                let filename = part.attachment_name()?.to_string();
+                let mime_type = part
+                    .content_type()
+                    .map(|ct| match ct.subtype() {
+                        Some(subtype) => format!("{}/{}", ct.ctype(), subtype),
+                        None => ct.ctype().to_string(),
+                    })
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                Some(MailAttachment {
+                    filename,
+                    mime_type,
+                    content: part.contents().to_vec(),
+                })
+            })
+            .collect();
+
+        Some(Self {
+            message_id: message.message_id().map(|s| s.to_string()),
+            in_reply_to: header_list(message.in_reply_to()),
+            references: header_list(message.references()),
+            subject: message.subject().map(|s| s.to_string()),
+            from: addresses(message.from()),
+            to: addresses(message.to()),
+            cc: addresses(message.cc()),
+            date: message
+                .date()
+                .and_then(|d| DateTime::from_timestamp(d.to_timestamp(), 0)),
+            attachments,
+        })
+    }
+
+    /// Every address on the message, sender first, without duplicates.
+    fn correspondents(&self) -> Vec<&str> {
+        let mut seen: Vec<&str> = Vec::new();
+        for addr in self.from.iter().chain(&self.to).chain(&self.cc) {
+            if !seen.iter().any(|s| s.eq_ignore_ascii_case(addr)) {
+                seen.push(addr);
+            }
+        }
+        seen
+    }
+
+    fn metadata(&self, mailbox: &Path, folder: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "import_source": "mailbox",
+            "mailbox": mailbox.display().to_string(),
+            "folder": folder,
+            "message_id": self.message_id,
+            "in_reply_to": self.in_reply_to,
+            "references": self.references,
+            "subject": self.subject,
+            "from": self.from,
+            "to": self.to,
+            "cc": self.cc,
+            "date": self.date.map(|d| d.to_rfc3339()),
+            "attachment_count": self.attachments.len(),
+        })
+    }
+}
+
+/// Mailbox import source.
+pub struct MailboxImportSource {
+    /// Path to the mbox file or Maildir directory.
+    path: PathBuf,
+    format: MailboxFormat,
+    /// Mailbox name used in URLs for messages without a Message-ID.
+    name: String,
+    /// Maildir messages, discovered up front.
+    maildir_messages: Vec<(String, PathBuf)>,
+    /// Settings for database access.
+    settings: foia::config::Settings,
+}
+
+impl MailboxImportSource {
+    /// Create a new mailbox import source, detecting the format from the path.
+    pub fn new(path: PathBuf, settings: foia::config::Settings) -> anyhow::Result<Self> {
+        let format = MailboxFormat::detect(&path).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is neither an mbox file nor a Maildir (no cur/ or new/)",
+                path.display()
+            )
+        })?;
+
+        let maildir_messages = match format {
+            MailboxFormat::Maildir => list_maildir(&path)?,
+            MailboxFormat::Mbox => Vec::new(),
+        };
+
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("mailbox")
+            .to_string();
+
+        Ok(Self {
+            path,
+            format,
+            name,
+            maildir_messages,
+            settings,
+        })
+    }
+
+    /// Stable URL for a message: its Message-ID when present, otherwise a
+    /// content hash scoped to the mailbox.
+    fn message_url(&self, message: &MailMessage, raw: &[u8]) -> String {
+        match &message.message_id {
+            Some(id) => format!("mid:{}", id),
+            None => format!(
+                "mailbox://{}/{}",
+                self.name,
+                &DocumentVersion::compute_hash(raw)[..16]
+            ),
+        }
+    }
+
+    /// Import a single raw message, updating `stats`.
+    async fn import_message(
+        &self,
+        raw: &[u8],
+        folder: Option<&str>,
+        config: &ImportConfig,
+        doc_repo: &DieselDocumentRepository,
+        stats: &mut ImportStats,
+    ) {
+        stats.scanned += 1;
+
+        let Some(message) = MailMessage::parse(raw) else {
+            tracing::warn!("Failed to parse message in {}", self.path.display());
+            stats.errors += 1;
+            return;
+        };
+
+        let url = self.message_url(&message, raw);
+        if config.existing_urls.contains(&url) {
+            stats.skipped += 1;
+            return;
+        }
+
+        let Some(source_id) = config.source_id.as_deref() else {
+            stats.no_source += 1;
+            return;
+        };
+
+        let title = message
+            .subject
+            .clone()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| "(no subject)".to_string());
+
+        if config.dry_run {
+            println!(
+                "  {} {} ({} attachment(s))",
+                style("+").green(),
+                title,
+                message.attachments.len()
+            );
+            stats.imported += 1;
+            return;
+        }
+
+        match self
+            .save_message(
+                raw, &message, &url, &title, source_id, folder, config, doc_repo,
+            )
+            .await
+        {
+            Ok(true) => {
+                stats.imported += 1;
+                stats.imported_urls.push(url);
+            }
+            Ok(false) => {
+                tracing::info!("Skipping deleted message: {}", url);
+                stats.skipped += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to save {}: {}", url, e);
+                stats.errors += 1;
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn save_message(
+        &self,
+        raw: &[u8],
+        message: &MailMessage,
+        url: &str,
+        title: &str,
+        source_id: &str,
+        folder: Option<&str>,
+        config: &ImportConfig,
+        doc_repo: &DieselDocumentRepository,
+    ) -> anyhow::Result<bool> {
+        let mime_type = "message/rfc822".to_string();
+        let content_hash = DocumentVersion::compute_hash(raw);
+        let filename = format!("{}.eml", title);
+        let (basename, extension) = extract_filename_parts(url, &filename, &mime_type);
+        let (storage_path, dedup_index) = compute_storage_path_with_dedup(
+            &config.documents_dir,
+            &content_hash,
+            &basename,
+            &extension,
+            raw,
+        );
+
+        // Messages are carved out of the mailbox, so they are always written
+        let dest_path = config.documents_dir.join(&storage_path);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest_path, raw)?;
+
+        let mut version = DocumentVersion::new_with_metadata(
+            raw,
+            mime_type,
+            Some(url.to_string()),
+            Some(filename),
+            message.date,
+        );
+        version.dedup_index = dedup_index;
+
+        let existing = doc_repo.get_by_url(url).await?;
+        let document_id = if let Some(mut doc) = existing.into_iter().next() {
+            if !doc.add_version(version) {
+                return Ok(true);
+            }
+            if !doc_repo.save_with_versions(&doc).await? {
+                return Ok(false);
+            }
+            doc.id
+        } else {
+            let mut doc = Document::new(
+                uuid::Uuid::new_v4().to_string(),
+                source_id.to_string(),
+                title.to_string(),
+                url.to_string(),
+                version,
+                message.metadata(&self.path, folder),
+            );
+            doc.tags = config.tags.clone();
+            if !doc_repo.save_with_versions(&doc).await? {
+                return Ok(false);
+            }
+            doc.id
+        };
+
+        if let Some(version_id) = doc_repo.get_current_version_id(&document_id).await? {
+            for (index, attachment) in message.attachments.iter().enumerate() {
+                doc_repo
+                    .insert_virtual_file(&attachment_virtual_file(
+                        &document_id,
+                        version_id,
+                        index,
+                        attachment,
+                    ))
+                    .await?;
+            }
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let correspondents = message.correspondents();
+        let normalized: Vec<String> = correspondents.iter().map(|a| a.to_lowercase()).collect();
+        let entity_rows: Vec<NewDocumentEntity<'_>> = correspondents
+            .iter()
+            .zip(normalized.iter())
+            .map(|(addr, norm)| NewDocumentEntity {
+                document_id: &document_id,
+                entity_type: EMAIL_ENTITY_TYPE,
+                entity_text: addr,
+                normalized_text: norm,
+                latitude: None,
+                longitude: None,
+                created_at: &now,
+            })
+            .collect();
+        // Replace rather than add to the addresses from earlier versions
+        doc_repo.delete_email_entities(&document_id).await?;
+        doc_repo.save_document_entities(&entity_rows).await?;
+
+        Ok(true)
+    }
+
+    async fn import_mbox(
+        &self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        let mut stats = ImportStats::default();
+        let ctx = self.settings.create_db_context()?;
+        let doc_repo = ctx.documents();
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(start_position))?;
+        let mut reader = MboxReader::new(BufReader::new(file), start_position);
+
+        let mut done = false;
+        loop {
+            if config.limit > 0 && stats.imported >= config.limit {
+                break;
+            }
+            if config.scan_limit > 0 && stats.scanned >= config.scan_limit {
+                break;
+            }
+
+            let Some(message) = reader.next_message()? else {
+                done = true;
+                break;
+            };
+            tracing::trace!("Message at offset {}", message.offset);
+
+            self.import_message(&message.raw, None, config, &doc_repo, &mut stats)
+                .await;
+
+            if config.enable_resume
+                && !config.dry_run
+                && config.checkpoint_interval > 0
+                && stats.scanned % config.checkpoint_interval == 0
+            {
+                let _ = self.save_progress(&ImportProgress {
+                    position: reader.resume_position(),
+                    done: false,
+                    error: None,
+                });
+            }
+        }
+
+        let progress = ImportProgress {
+            position: reader.resume_position(),
+            done,
+            error: None,
+        };
+        Ok((progress, stats))
+    }
+
+    async fn import_maildir(
+        &self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        let mut stats = ImportStats::default();
+        let mut position = start_position;
+        let ctx = self.settings.create_db_context()?;
+        let doc_repo = ctx.documents();
+
+        for (folder, path) in self.maildir_messages.iter().skip(start_position as usize) {
+            if config.limit > 0 && stats.imported >= config.limit {
+                break;
+            }
+            if config.scan_limit > 0 && stats.scanned >= config.scan_limit {
+                break;
+            }
+            position += 1;
+
+            match std::fs::read(path) {
+                Ok(raw) => {
+                    self.import_message(&raw, Some(folder), config, &doc_repo, &mut stats)
+                        .await;
+                }
+                Err(e) => {
+                    tracing::debug!("Message file not found: {} ({})", path.display(), e);
+                    stats.missing_files += 1;
+                    continue;
+                }
+            }
+
+            if config.enable_resume
+                && !config.dry_run
+                && config.checkpoint_interval > 0
+                && stats.scanned % config.checkpoint_interval == 0
+            {
+                let _ = self.save_progress(&ImportProgress {
+                    position,
+                    done: false,
+                    error: None,
+                });
+            }
+        }
+
+        let progress = ImportProgress {
+            position,
+            done: position >= self.maildir_messages.len() as u64,
+            error: None,
+        };
+        Ok((progress, stats))
+    }
+}
+
+/// Build the virtual file recorded for an attachment.
+///
+/// Plain-text attachments carry their text directly; other extractable
+/// types stay pending until `foia archive` runs OCR/extraction on them.
+fn attachment_virtual_file(
+    document_id: &str,
+    version_id: i64,
+    index: usize,
+    attachment: &MailAttachment,
+) -> VirtualFile {
+    let mut vf = VirtualFile::new(
+        document_id.to_string(),
+        version_id,
+        VirtualFile::email_attachment_path(index, &attachment.filename),
+        attachment.filename.clone(),
+        attachment.mime_type.clone(),
+        attachment.content.len() as u64,
+    );
+    if attachment.mime_type.starts_with("text/plain") {
+        vf.extracted_text = Some(String::from_utf8_lossy(&attachment.content).into_owned());
+        vf.status = VirtualFileStatus::OcrComplete;
+    } else if !foia::utils::is_extractable_mimetype(&attachment.mime_type) {
+        vf.status = VirtualFileStatus::Unsupported;
+    }
+    vf
+}
+
+#[async_trait::async_trait]
+impl ImportSource for MailboxImportSource {
+    fn format_id(&self) -> &'static str {
+        match self.format {
+            MailboxFormat::Mbox => "mbox",
+            MailboxFormat::Maildir => "maildir",
+        }
+    }
+
+    fn display_name(&self) -> &str {
+        match self.format {
+            MailboxFormat::Mbox => "mbox mailbox",
+            MailboxFormat::Maildir => "Maildir mailbox",
+        }
+    }
+
+    fn source_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn total_count(&self) -> Option<u64> {
+        match self.format {
+            MailboxFormat::Mbox => None,
+            MailboxFormat::Maildir => Some(self.maildir_messages.len() as u64),
+        }
+    }
+
+    async fn run_import(
+        &mut self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        match self.format {
+            MailboxFormat::Mbox => self.import_mbox(config, start_position).await,
+            MailboxFormat::Maildir => self.import_maildir(config, start_position).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MBOX: &str = "From alice@example.gov Mon Jan  1 00:00:00 2024\n\
+Message-ID: <1@example.gov>\n\
+From: Alice <alice@example.gov>\n\
+To: bob@example.gov\n\
+Subject: First\n\
+\n\
+Body one\n\
+>From the archives\n\
+\n\
+From bob@example.gov Mon Jan  1 01:00:00 2024\n\
+Message-ID: <2@example.gov>\n\
+In-Reply-To: <1@example.gov>\n\
+References: <1@example.gov>\n\
+From: bob@example.gov\n\
+To: alice@example.gov\n\
+Cc: Carol <CAROL@example.gov>, alice@example.gov\n\
+Subject: Re: First\n\
+\n\
+Body two\n";
+
+    fn read_all(data: &[u8], offset: u64) -> Vec<MboxMessage> {
+        let mut reader = MboxReader::new(&data[offset as usize..], offset);
+        let mut messages = Vec::new();
+        while let Some(m) = reader.next_message().unwrap() {
+            messages.push(m);
+        }
+        messages
+    }
+
+    #[test]
+    fn test_mbox_split_and_unescape() {
+        let messages = read_all(MBOX.as_bytes(), 0);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].offset, 0);
+
+        let first = String::from_utf8(messages[0].raw.clone()).unwrap();
+        assert!(first.starts_with("Message-ID: <1@example.gov>"));
+        assert!(first.ends_with("Body one\nFrom the archives\n"));
+
+        // Resuming at the second separator yields only the second message
+        let resumed = read_all(MBOX.as_bytes(), messages[1].offset);
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].raw, messages[1].raw);
+    }
+
+    #[test]
+    fn test_parse_thread_headers() {
+        let messages = read_all(MBOX.as_bytes(), 0);
+        let reply = MailMessage::parse(&messages[1].raw).unwrap();
+
+        assert_eq!(reply.message_id.as_deref(), Some("2@example.gov"));
+        assert_eq!(reply.in_reply_to, vec!["1@example.gov"]);
+        assert_eq!(reply.references, vec!["1@example.gov"]);
+        assert_eq!(reply.subject.as_deref(), Some("Re: First"));
+        assert_eq!(
+            reply.correspondents(),
+            vec!["bob@example.gov", "alice@example.gov", "CAROL@example.gov"]
+        );
+    }
+
+    #[test]
+    fn test_parse_attachments() {
+        let raw = "From: a@example.gov\r\n\
+Subject: Files\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+\r\n\
+--b\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+See attached.\r\n\
+--b\r\n\
+Content-Type: text/plain; name=\"notes.txt\"\r\n\
+Content-Disposition: attachment; filename=\"notes.txt\"\r\n\
+\r\n\
+meeting notes\r\n\
+--b--\r\n";
+        let message = MailMessage::parse(raw.as_bytes()).unwrap();
+        assert_eq!(message.attachments.len(), 1);
+
+        let vf = attachment_virtual_file("doc", 1, 0, &message.attachments[0]);
+        assert_eq!(vf.filename, "notes.txt");
+        assert_eq!(vf.archive_path, "0/notes.txt");
+        assert_eq!(vf.status, VirtualFileStatus::OcrComplete);
+        assert_eq!(vf.extracted_text.as_deref(), Some("meeting notes"));
+    }
+
+    #[test]
+    fn test_list_maildir() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["cur/1", "new/2", ".Sent/cur/3", ".Sent/tmp/4", "tmp/5"] {
+            let full = dir.path().join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(full, b"Subject: x\n\nbody\n").unwrap();
+        }
+
+        assert_eq!(
+            MailboxFormat::detect(dir.path()),
+            Some(MailboxFormat::Maildir)
+        );
+        let messages: Vec<_> = list_maildir(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|(folder, path)| (folder, path.file_name().unwrap().to_owned()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("INBOX".to_string(), "1".into()),
+                ("INBOX".to_string(), "2".into()),
+                ("Sent".to_string(), "3".into()),
+            ]
+        );
+    }
+}
@@ -1,7 +1,20 @@
 //! Import source implementations.
 
 pub mod concordance;
+pub mod dii;
+pub mod directory;
+pub mod edrm;
+mod load_file;
+pub mod mailbox;
+pub mod relativity;
+pub mod wacz;
 pub mod warc;
 
 pub use concordance::{ConcordanceImportSource, MultiPageMode};
+pub use dii::DiiImportSource;
+pub use directory::{DirectoryImportSource, FolderMapping};
+pub use edrm::EdrmImportSource;
+pub use mailbox::{MailboxFormat, MailboxImportSource};
+pub use relativity::RelativityImportSource;
+pub use wacz::WaczImportSource;
 pub use warc::WarcImportSource;
@@ -0,0 +1,180 @@
+//! Relativity-style delimited load file importer.
+//!
+//! Review platforms export productions as a delimited file with a header
+//! row naming the fields, typically "Control Number" or "Begin Bates",
+//! "End Bates", a native file path and an "Extracted Text" column holding
+//! either a path to a text file or the text itself.
+//!
+//! Three dialects are recognized from the header line:
+//! - Concordance-style: 0x14 delimiter, þ qualifier, ® for embedded newlines
+//! - Tab-separated
+//! - Comma-separated with double-quote qualifiers (the default)
+//!
+//! If an OPT image load file with the same name sits beside it, page images
+//! are attached as for Concordance imports.
+
+use std::path::{Path, PathBuf};
+
+use super::concordance::ConcordanceImportSource;
+use super::load_file::{
+    parse_delimited, read_text_lossy, LoadFile, LoadFileColumns, LoadFileDocument, LoadFileText,
+};
+use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
+
+/// Relativity-style load file import source.
+pub struct RelativityImportSource {
+    /// Path to the load file.
+    source_path: PathBuf,
+    /// Parsed documents.
+    load_file: LoadFile,
+    /// Settings for database access.
+    settings: foia::config::Settings,
+}
+
+impl RelativityImportSource {
+    /// Create a new import source from the path to a CSV or DAT load file.
+    pub fn new(
+        path: PathBuf,
+        url_prefix: Option<String>,
+        settings: foia::config::Settings,
+    ) -> anyhow::Result<Self> {
+        let text = read_text_lossy(&path)?;
+        let mut documents = parse_load_file(&text)?;
+        tracing::info!("Parsed {} documents from load file", documents.len());
+
+        let opt_path = ["opt", "OPT"]
+            .iter()
+            .map(|ext| path.with_extension(ext))
+            .find(|p| p.exists());
+        if let Some(opt_path) = opt_path {
+            let pages = ConcordanceImportSource::parse_opt(&opt_path)?;
+            tracing::info!("Parsed {} page mappings from OPT file", pages.len());
+            for doc in &mut documents {
+                ConcordanceImportSource::attach_pages(doc, &pages);
+            }
+        }
+
+        let base_path = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Ok(Self {
+            load_file: LoadFile {
+                format: "relativity",
+                load_file_dir: base_path.clone(),
+                base_path,
+                url_prefix,
+                documents,
+            },
+            source_path: path,
+            settings,
+        })
+    }
+}
+
+/// Parse the documents in a delimited load file.
+fn parse_load_file(text: &str) -> anyhow::Result<Vec<LoadFileDocument>> {
+    let header_line = text.lines().next().unwrap_or_default();
+    let concordance_style = header_line.contains('þ');
+    let (delimiter, quote) = if concordance_style {
+        ('\x14', 'þ')
+    } else if header_line.contains('\t') && !header_line.contains(',') {
+        ('\t', '"')
+    } else {
+        (',', '"')
+    };
+
+    let mut records = parse_delimited(text, delimiter, quote).into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Empty load file"))?;
+    let columns = LoadFileColumns::from_header(header);
+
+    Ok(records
+        .filter_map(|values| columns.document(&values))
+        .map(|mut doc| {
+            // Concordance-style exports replace newlines in text with ®
+            if let (true, Some(LoadFileText::Inline(text))) = (concordance_style, &mut doc.text) {
+                *text = text.replace('®', "\n");
+            }
+            doc
+        })
+        .collect())
+}
+
+#[async_trait::async_trait]
+impl ImportSource for RelativityImportSource {
+    fn format_id(&self) -> &'static str {
+        "relativity"
+    }
+
+    fn display_name(&self) -> &str {
+        "Relativity load file"
+    }
+
+    fn source_path(&self) -> &Path {
+        &self.source_path
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn total_count(&self) -> Option<u64> {
+        Some(self.load_file.documents.len() as u64)
+    }
+
+    async fn run_import(
+        &mut self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        self.load_file
+            .import(&*self, &self.settings, config, start_position)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_load_file() {
+        let text = "\"Control Number\",\"End Bates\",\"FILE_PATH\",\"Extracted Text\",\"Custodian\"\r\n\
+            \"REL0001\",\"REL0002\",\"NATIVES\\REL0001.docx\",\"TEXT\\REL0001.txt\",\"Smith, J.\"\r\n\
+            \"REL0003\",\"\",\"\",\"Short note,\nsecond line\",\"Doe\"\r\n";
+
+        let docs = parse_load_file(text).unwrap();
+        assert_eq!(docs.len(), 2);
+
+        assert_eq!(docs[0].begin_bates, "REL0001");
+        assert_eq!(docs[0].end_bates, "REL0002");
+        assert_eq!(
+            docs[0].native_path.as_deref(),
+            Some(r"NATIVES\REL0001.docx")
+        );
+        assert_eq!(
+            docs[0].text,
+            Some(LoadFileText::Path(r"TEXT\REL0001.txt".to_string()))
+        );
+        assert_eq!(
+            docs[0].fields.get("Custodian").map(String::as_str),
+            Some("Smith, J.")
+        );
+
+        assert_eq!(docs[1].end_bates, "REL0003");
+        assert_eq!(
+            docs[1].text,
+            Some(LoadFileText::Inline("Short note,\nsecond line".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_concordance_style_load_file() {
+        let text = "þBegBatesþ\x14þTextþ\r\nþX0001þ\x14þline one®line twoþ\r\n";
+        let docs = parse_load_file(text).unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(
+            docs[0].text,
+            Some(LoadFileText::Inline("line one\nline two".to_string()))
+        );
+    }
+}
@@ -0,0 +1,555 @@
+//! WACZ web archive package importer.
+//!
+//! WACZ packages (from Browsertrix, ArchiveWeb.page and other Webrecorder
+//! tools) are zips with WARC files under `archive/`, CDXJ indexes under
+//! `indexes/` and the captured pages listed in `pages/pages.jsonl`.
+//!
+//! When the package has a CDXJ index and its WARCs are stored uncompressed
+//! in the zip, as the spec requires, each indexed response is read directly
+//! at its offset. Otherwise every WARC in the package is scanned in order.
+//! Records are imported as by [`WarcImportSource`], with titles taken from
+//! `pages.jsonl` and the capture's WARC record kept in metadata.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use flate2::read::MultiGzDecoder;
+use warc::{BufferedBody, Record, WarcHeader, WarcReader};
+use zip::{CompressionMethod, ZipArchive};
+
+use super::warc::WarcImportSource;
+use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
+use foia::config::Settings;
+use foia::repository::DieselDocumentRepository;
+
+/// A CDXJ index entry locating a WARC record in the package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CdxjEntry {
+    url: String,
+    filename: String,
+    offset: u64,
+    length: u64,
+}
+
+/// Read a number that CDXJ writers emit as either a JSON string or number.
+fn json_u64(value: &serde_json::Value, key: &str) -> Option<u64> {
+    match value.get(key)? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse a CDXJ line (`<surt> <timestamp> <json>`).
+///
+/// Revisits and non-success captures are skipped since they carry no
+/// content of their own.
+fn parse_cdxj_line(line: &str) -> Option<CdxjEntry> {
+    let mut parts = line.trim().splitn(3, ' ');
+    let _key = parts.next()?;
+    let _timestamp = parts.next()?;
+    let json: serde_json::Value = serde_json::from_str(parts.next()?).ok()?;
+
+    if json.get("mime").and_then(|m| m.as_str()) == Some("warc/revisit") {
+        return None;
+    }
+    if let Some(status) = json_u64(&json, "status") {
+        if status != 200 && status != 206 {
+            return None;
+        }
+    }
+
+    Some(CdxjEntry {
+        url: json.get("url")?.as_str()?.to_string(),
+        filename: json.get("filename")?.as_str()?.to_string(),
+        offset: json_u64(&json, "offset")?,
+        length: json_u64(&json, "length")?,
+    })
+}
+
+/// Read a text entry from the package, decompressing `.gz` entries.
+fn read_entry_text(archive: &mut ZipArchive<File>, name: &str) -> anyhow::Result<String> {
+    let mut entry = archive.by_name(name)?;
+    let mut text = String::new();
+    if name.ends_with(".gz") {
+        MultiGzDecoder::new(&mut entry).read_to_string(&mut text)?;
+    } else {
+        entry.read_to_string(&mut text)?;
+    }
+    Ok(text)
+}
+
+/// Parse a single WARC record, gzip-compressed or not.
+fn parse_record(raw: &[u8]) -> anyhow::Result<Record<BufferedBody>> {
+    let reader: Box<dyn BufRead + '_> = if raw.starts_with(&[0x1f, 0x8b]) {
+        Box::new(BufReader::new(MultiGzDecoder::new(raw)))
+    } else {
+        Box::new(raw)
+    };
+    WarcReader::new(reader)
+        .iter_records()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No WARC record at indexed offset"))?
+        .map_err(|e| anyhow::anyhow!("Malformed WARC record: {}", e))
+}
+
+/// Read the WARC record at an indexed offset within a stored zip entry.
+fn read_indexed_record(
+    file: &mut File,
+    data_start: u64,
+    entry: &CdxjEntry,
+) -> anyhow::Result<Record<BufferedBody>> {
+    let mut raw = vec![0; entry.length as usize];
+    file.seek(SeekFrom::Start(data_start + entry.offset))?;
+    file.read_exact(&mut raw)?;
+    parse_record(&raw)
+}
+
+/// The parts of a WACZ package needed for import.
+#[derive(Debug, Default)]
+struct WaczLayout {
+    /// Indexed responses, in index order.
+    entries: Vec<CdxjEntry>,
+    /// WARC entry names under `archive/`, sorted.
+    warc_names: Vec<String>,
+    /// Start of each stored WARC's data within the zip, by WARC filename.
+    stored_offsets: HashMap<String, u64>,
+    /// Page titles from `pages.jsonl`, by URL.
+    titles: HashMap<String, String>,
+}
+
+impl WaczLayout {
+    fn read(path: &Path) -> anyhow::Result<Self> {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        let mut layout = Self::default();
+
+        for name in &names {
+            let Some(filename) = name.strip_prefix("archive/") else {
+                continue;
+            };
+            if filename.is_empty() || filename.ends_with('/') {
+                continue;
+            }
+            layout.warc_names.push(name.clone());
+            let entry = archive.by_name(name)?;
+            if entry.compression() == CompressionMethod::Stored {
+                layout
+                    .stored_offsets
+                    .insert(filename.to_string(), entry.data_start());
+            }
+        }
+        layout.warc_names.sort();
+
+        let index_names = names.iter().filter(|n| {
+            n.starts_with("indexes/")
+                && (n.ends_with(".cdxj") || n.ends_with(".cdxj.gz") || n.ends_with(".cdx.gz"))
+        });
+        for name in index_names {
+            let text = read_entry_text(&mut archive, name)?;
+            layout
+                .entries
+                .extend(text.lines().filter_map(parse_cdxj_line));
+        }
+
+        let page_names = names
+            .iter()
+            .filter(|n| n.starts_with("pages/") && n.ends_with(".jsonl"));
+        for name in page_names {
+            let text = read_entry_text(&mut archive, name)?;
+            for line in text.lines() {
+                let Ok(page) = serde_json::from_str::<serde_json::Value>(line) else {
+                    continue;
+                };
+                let url = page.get("url").and_then(|u| u.as_str());
+                let title = page.get("title").and_then(|t| t.as_str());
+                if let (Some(url), Some(title)) = (url, title.filter(|t| !t.is_empty())) {
+                    layout
+                        .titles
+                        .entry(url.to_string())
+                        .or_insert_with(|| title.to_string());
+                }
+            }
+        }
+
+        Ok(layout)
+    }
+
+    /// Whether every indexed record can be read at its offset.
+    fn is_indexed(&self) -> bool {
+        !self.entries.is_empty()
+            && self
+                .entries
+                .iter()
+                .all(|e| self.stored_offsets.contains_key(&e.filename))
+    }
+}
+
+/// WACZ package import source.
+pub struct WaczImportSource {
+    /// Path to the .wacz file.
+    wacz_path: PathBuf,
+    /// Index, WARC entries and page titles read from the package.
+    layout: WaczLayout,
+    /// Record handling shared with WARC imports (source detection, filter).
+    warc: WarcImportSource,
+    /// Settings for database access.
+    settings: Settings,
+}
+
+impl WaczImportSource {
+    /// Create a new WACZ import source.
+    ///
+    /// Arguments match [`WarcImportSource::new`].
+    pub async fn new(
+        wacz_path: PathBuf,
+        source_id: Option<String>,
+        filter: Option<&str>,
+        settings: Settings,
+    ) -> anyhow::Result<Self> {
+        let layout = WaczLayout::read(&wacz_path)?;
+        if layout.is_indexed() {
+            tracing::info!("Read {} index entries from WACZ", layout.entries.len());
+        } else {
+            tracing::info!(
+                "WACZ has no usable index, scanning {} WARC file(s)",
+                layout.warc_names.len()
+            );
+        }
+
+        let warc =
+            WarcImportSource::new(wacz_path.clone(), source_id, filter, settings.clone()).await?;
+
+        Ok(Self {
+            wacz_path,
+            layout,
+            warc,
+            settings,
+        })
+    }
+
+    /// Metadata tying an imported document to its WARC record.
+    fn provenance(&self, warc_name: &str, record: &Record<BufferedBody>) -> serde_json::Value {
+        serde_json::json!({
+            "wacz": self.wacz_path.file_name().map(|n| n.to_string_lossy()),
+            "warc_filename": warc_name,
+            "warc_record_id": record.header(WarcHeader::RecordID),
+            "warc_date": record.header(WarcHeader::Date),
+        })
+    }
+
+    /// Save progress at the configured checkpoint interval.
+    fn checkpoint(&self, config: &ImportConfig, stats: &ImportStats, position: u64) {
+        if config.enable_resume
+            && !config.dry_run
+            && config.checkpoint_interval > 0
+            && stats.scanned % config.checkpoint_interval == 0
+        {
+            let progress = ImportProgress {
+                position,
+                done: false,
+                error: None,
+            };
+            let _ = self.save_progress(&progress);
+        }
+    }
+
+    fn limit_reached(config: &ImportConfig, stats: &ImportStats) -> bool {
+        (config.limit > 0 && stats.imported >= config.limit)
+            || (config.scan_limit > 0 && stats.scanned >= config.scan_limit)
+    }
+
+    /// Import indexed records by seeking to each one. Position is the
+    /// number of index entries consumed. Returns the position and whether
+    /// every entry was consumed.
+    async fn import_indexed(
+        &self,
+        config: &ImportConfig,
+        start_position: u64,
+        doc_repo: &DieselDocumentRepository,
+        existing_urls: &mut HashSet<String>,
+        stats: &mut ImportStats,
+    ) -> anyhow::Result<(u64, bool)> {
+        let mut file = File::open(&self.wacz_path)?;
+        let mut position = start_position;
+
+        for entry in self.layout.entries.iter().skip(start_position as usize) {
+            if Self::limit_reached(config, stats) {
+                break;
+            }
+            stats.scanned += 1;
+            position += 1;
+            self.checkpoint(config, stats, position);
+
+            // Skip known URLs without reading the record
+            if existing_urls.contains(&entry.url) {
+                stats.skipped += 1;
+                continue;
+            }
+
+            let data_start = self.layout.stored_offsets[&entry.filename];
+            let record = match read_indexed_record(&mut file, data_start, entry) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable record for {}: {}", entry.url, e);
+                    stats.errors += 1;
+                    continue;
+                }
+            };
+
+            let title = self.layout.titles.get(&entry.url).cloned();
+            let metadata = self.provenance(&entry.filename, &record);
+            self.warc
+                .import_record(
+                    &record,
+                    title,
+                    metadata,
+                    doc_repo,
+                    config,
+                    existing_urls,
+                    stats,
+                )
+                .await;
+        }
+
+        Ok((position, position >= self.layout.entries.len() as u64))
+    }
+
+    /// Import by scanning every WARC in the package. Position is the number
+    /// of records consumed across all WARCs. Returns the position and
+    /// whether every record was consumed.
+    async fn import_sequential(
+        &self,
+        config: &ImportConfig,
+        start_position: u64,
+        doc_repo: &DieselDocumentRepository,
+        existing_urls: &mut HashSet<String>,
+        stats: &mut ImportStats,
+    ) -> anyhow::Result<(u64, bool)> {
+        let mut archive = ZipArchive::new(File::open(&self.wacz_path)?)?;
+        let mut position = 0u64;
+        let mut done = true;
+
+        'warcs: for name in &self.layout.warc_names {
+            // Zip entry readers can't be held across awaits, so extract first
+            let mut extracted = tempfile::NamedTempFile::new()?;
+            std::io::copy(&mut archive.by_name(name)?, &mut extracted)?;
+            let file = extracted.reopen()?;
+            let reader: Box<dyn BufRead + Send> = if name.ends_with(".gz") {
+                Box::new(BufReader::new(MultiGzDecoder::new(file)))
+            } else {
+                Box::new(BufReader::new(file))
+            };
+            let warc_name = name.trim_start_matches("archive/");
+
+            for record_result in WarcReader::new(reader).iter_records() {
+                if Self::limit_reached(config, stats) {
+                    done = false;
+                    break 'warcs;
+                }
+                position += 1;
+                if position <= start_position {
+                    continue;
+                }
+                stats.scanned += 1;
+                self.checkpoint(config, stats, position);
+
+                let record = match record_result {
+                    Ok(r) => r,
+                    Err(e) => {
+                        tracing::warn!("Skipping malformed record: {}", e);
+                        stats.errors += 1;
+                        continue;
+                    }
+                };
+
+                let title = record
+                    .header(WarcHeader::TargetURI)
+                    .and_then(|uri| self.layout.titles.get(uri.as_ref()).cloned());
+                let metadata = self.provenance(warc_name, &record);
+                self.warc
+                    .import_record(
+                        &record,
+                        title,
+                        metadata,
+                        doc_repo,
+                        config,
+                        existing_urls,
+                        stats,
+                    )
+                    .await;
+            }
+        }
+
+        Ok((position, done))
+    }
+}
+
+#[async_trait::async_trait]
+impl ImportSource for WaczImportSource {
+    fn format_id(&self) -> &'static str {
+        "wacz"
+    }
+
+    fn display_name(&self) -> &str {
+        "WACZ package"
+    }
+
+    fn source_path(&self) -> &Path {
+        &self.wacz_path
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn total_count(&self) -> Option<u64> {
+        self.layout
+            .is_indexed()
+            .then_some(self.layout.entries.len() as u64)
+    }
+
+    async fn run_import(
+        &mut self,
+        config: &ImportConfig,
+        start_position: u64,
+    ) -> anyhow::Result<(ImportProgress, ImportStats)> {
+        let ctx = self.settings.create_db_context()?;
+        let doc_repo = ctx.documents();
+        let mut existing_urls = config.existing_urls.clone();
+        let mut stats = ImportStats::default();
+
+        let (position, done) = if self.layout.is_indexed() {
+            self.import_indexed(
+                config,
+                start_position,
+                &doc_repo,
+                &mut existing_urls,
+                &mut stats,
+            )
+            .await?
+        } else {
+            self.import_sequential(
+                config,
+                start_position,
+                &doc_repo,
+                &mut existing_urls,
+                &mut stats,
+            )
+            .await?
+        };
+
+        let progress = ImportProgress {
+            position,
+            done,
+            error: None,
+        };
+        Ok((progress, stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use chrono::Utc;
+    use foia::models::DocumentVersion;
+    use foia::storage::wacz::{WaczCapture, WaczWriter};
+    use foia::storage::warc::{new_record_id, WarcWriter};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_cdxj_line() {
+        let entry = parse_cdxj_line(
+            r#"gov,agency)/a.pdf 20240105120000 {"url": "https://agency.gov/a.pdf", "status": "200", "offset": "1024", "length": 512, "filename": "data.warc.gz"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.url, "https://agency.gov/a.pdf");
+        assert_eq!(entry.filename, "data.warc.gz");
+        assert_eq!((entry.offset, entry.length), (1024, 512));
+
+        assert!(parse_cdxj_line(
+            r#"gov,agency)/b 20240105120000 {"url": "https://agency.gov/b", "status": 404, "offset": 0, "length": 1, "filename": "data.warc"}"#
+        )
+        .is_none());
+        assert!(parse_cdxj_line(
+            r#"gov,agency)/c 20240105120000 {"url": "https://agency.gov/c", "mime": "warc/revisit", "offset": 0, "length": 1, "filename": "data.warc"}"#
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_parse_gzip_record() {
+        let mut warc = WarcWriter::new(Vec::new());
+        warc.write_record(
+            &[
+                ("WARC-Type", "resource".to_string()),
+                ("WARC-Record-ID", new_record_id()),
+                ("WARC-Date", "2024-01-05T12:00:00Z".to_string()),
+                (
+                    "WARC-Target-URI",
+                    "https://agency.gov/notes.txt".to_string(),
+                ),
+            ],
+            b"notes",
+        )
+        .unwrap();
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&warc.into_inner()).unwrap();
+        let record = parse_record(&gz.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            record.header(WarcHeader::TargetURI).as_deref(),
+            Some("https://agency.gov/notes.txt")
+        );
+        assert_eq!(record.body(), b"notes");
+    }
+
+    #[test]
+    fn test_read_exported_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.wacz");
+
+        let content = b"<html><title>Annual report</title></html>";
+        let hash = DocumentVersion::compute_hash(content);
+        let mut writer = WaczWriter::create(&path, "Export").unwrap();
+        writer
+            .add_capture(&WaczCapture {
+                url: "https://agency.gov/reports/",
+                date: Utc::now(),
+                mime_type: "text/html",
+                title: "Annual report",
+                content,
+                content_hash: &hash,
+                response: None,
+                provenance: serde_json::json!({ "document_id": "doc-1" }),
+            })
+            .unwrap();
+        writer.finish().unwrap();
+
+        let layout = WaczLayout::read(&path).unwrap();
+        assert!(layout.is_indexed());
+        assert_eq!(layout.entries.len(), 1);
+        assert_eq!(
+            layout
+                .titles
+                .get("https://agency.gov/reports/")
+                .map(String::as_str),
+            Some("Annual report")
+        );
+
+        let entry = &layout.entries[0];
+        let mut file = File::open(&path).unwrap();
+        let record =
+            read_indexed_record(&mut file, layout.stored_offsets[&entry.filename], entry).unwrap();
+        assert_eq!(
+            record.header(WarcHeader::WarcType).as_deref(),
+            Some("resource")
+        );
+        assert_eq!(record.body(), content);
+    }
+}
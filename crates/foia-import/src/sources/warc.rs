@@ -8,19 +8,21 @@
 //! - Byte-offset resume for uncompressed files
 //! - Source auto-detection from URL patterns
 //! - HTTP response parsing and content extraction
+//! - `resource` records, such as browser captures, imported as-is
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use console::style;
-use warc::{WarcHeader, WarcReader};
+use warc::{BufferedBody, Record, WarcHeader, WarcReader};
 
 use crate::{ImportConfig, ImportProgress, ImportSource, ImportStats};
 use foia::config::Settings;
-use foia::storage::{save_document_async, DocumentInput};
+use foia::repository::DieselDocumentRepository;
+use foia::storage::{save_document_async, DocumentInput, SaveOutcome};
 
 /// A BufReader wrapper that tracks total bytes consumed.
 /// Uses Arc<AtomicU64> so position can be read even after reader is consumed.
@@ -192,6 +194,140 @@ impl WarcImportSource {
         None
     }
 
+    /// Import a single WARC record if it is a successful HTTP response or a
+    /// `resource` record holding the content directly.
+    ///
+    /// `title` overrides the title derived from the URL, and `metadata` is
+    /// stored on the new document.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn import_record(
+        &self,
+        record: &Record<BufferedBody>,
+        title: Option<String>,
+        metadata: serde_json::Value,
+        doc_repo: &DieselDocumentRepository,
+        config: &ImportConfig,
+        existing_urls: &mut HashSet<String>,
+        stats: &mut ImportStats,
+    ) {
+        // Only process response and resource records
+        let warc_type = record.header(WarcHeader::WarcType);
+        let is_resource = match warc_type.as_deref() {
+            Some("response") => false,
+            Some("resource") => true,
+            _ => return,
+        };
+
+        // Get target URI
+        let target_uri = match record.header(WarcHeader::TargetURI) {
+            Some(uri) => uri.to_string(),
+            None => return,
+        };
+
+        // Apply filter
+        if let Some(ref regex) = self.filter_regex {
+            if !regex.is_match(&target_uri) {
+                stats.filtered += 1;
+                return;
+            }
+        }
+
+        // Get body content
+        let body = record.body();
+        if body.is_empty() {
+            return;
+        }
+
+        let (content, content_type) = if is_resource {
+            let content_type = record
+                .header(WarcHeader::ContentType)
+                .map(|t| t.split(';').next().unwrap_or(&t).trim().to_string());
+            (body, content_type)
+        } else {
+            // Parse HTTP response from body
+            let (headers, content) = match parse_http_response(body) {
+                Some(parsed) => parsed,
+                None => {
+                    tracing::debug!("Could not parse HTTP response for {}", target_uri);
+                    return;
+                }
+            };
+
+            // Skip non-success responses
+            if !headers.status_ok {
+                return;
+            }
+            (content, headers.content_type)
+        };
+
+        // Skip empty content
+        if content.is_empty() {
+            return;
+        }
+
+        // Auto-detect source from URL
+        let source_id = match self.find_source_for_url(&target_uri) {
+            Some(sid) => sid,
+            None => {
+                stats.no_source += 1;
+                tracing::debug!("No matching source for URL: {}", target_uri);
+                return;
+            }
+        };
+
+        // Check if document already exists (O(1) HashSet lookup)
+        if existing_urls.contains(&target_uri) {
+            stats.skipped += 1;
+            return;
+        }
+
+        // Extract title from URL unless one was given
+        let title = title.unwrap_or_else(|| foia::utils::extract_title_from_url(&target_uri));
+
+        // Determine MIME type
+        let mime_type = content_type
+            .unwrap_or_else(|| foia::utils::guess_mime_from_url(&target_uri).to_string());
+
+        if config.dry_run {
+            println!(
+                "  {} [{}] {} ({}, {} bytes)",
+                style("+").green(),
+                source_id,
+                target_uri,
+                mime_type,
+                content.len()
+            );
+            stats.imported += 1;
+        } else {
+            let input = DocumentInput {
+                url: target_uri.clone(),
+                title,
+                mime_type,
+                metadata,
+                original_filename: None,
+                server_date: None,
+            };
+
+            match save_document_async(doc_repo, content, &input, &source_id, &config.documents_dir)
+                .await
+            {
+                Ok(SaveOutcome::Deleted) => {
+                    tracing::info!("Skipping deleted document: {}", target_uri);
+                    stats.skipped += 1;
+                }
+                Ok(_) => {
+                    // Add to URL cache to avoid re-importing in same session
+                    existing_urls.insert(target_uri);
+                    stats.imported += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to import {}: {}", target_uri, e);
+                    stats.errors += 1;
+                }
+            }
+        }
+    }
+
     /// Process WARC records from a reader.
     async fn process_records<R: BufRead>(
         &self,
@@ -206,7 +342,6 @@ impl WarcImportSource {
 
         let ctx = self.settings.create_db_context()?;
         let doc_repo = ctx.documents();
-        let documents_dir = &config.documents_dir;
 
         // Create mutable copy of existing_urls for session dedup
         let mut existing_urls = config.existing_urls.clone();
@@ -250,110 +385,16 @@ impl WarcImportSource {
                 }
             };
 
-            // Only process response records
-            let warc_type = record.header(WarcHeader::WarcType);
-            if warc_type.as_deref() != Some("response") {
-                continue;
-            }
-
-            // Get target URI
-            let target_uri = match record.header(WarcHeader::TargetURI) {
-                Some(uri) => uri.to_string(),
-                None => continue,
-            };
-
-            // Apply filter
-            if let Some(ref regex) = self.filter_regex {
-                if !regex.is_match(&target_uri) {
-                    stats.filtered += 1;
-                    continue;
-                }
-            }
-
-            // Get body content
-            let body = record.body();
-            if body.is_empty() {
-                continue;
-            }
-
-            // Parse HTTP response from body
-            let (headers, content) = match parse_http_response(body) {
-                Some(parsed) => parsed,
-                None => {
-                    tracing::debug!("Could not parse HTTP response for {}", target_uri);
-                    continue;
-                }
-            };
-
-            // Skip non-success responses
-            if !headers.status_ok {
-                continue;
-            }
-
-            // Skip empty content
-            if content.is_empty() {
-                continue;
-            }
-
-            // Auto-detect source from URL
-            let source_id = match self.find_source_for_url(&target_uri) {
-                Some(sid) => sid,
-                None => {
-                    stats.no_source += 1;
-                    tracing::debug!("No matching source for URL: {}", target_uri);
-                    continue;
-                }
-            };
-
-            // Check if document already exists (O(1) HashSet lookup)
-            if existing_urls.contains(&target_uri) {
-                stats.skipped += 1;
-                continue;
-            }
-
-            // Extract title from URL
-            let title = foia::utils::extract_title_from_url(&target_uri);
-
-            // Determine MIME type
-            let mime_type = headers
-                .content_type
-                .clone()
-                .unwrap_or_else(|| foia::utils::guess_mime_from_url(&target_uri).to_string());
-
-            if config.dry_run {
-                println!(
-                    "  {} [{}] {} ({}, {} bytes)",
-                    style("+").green(),
-                    source_id,
-                    target_uri,
-                    mime_type,
-                    content.len()
-                );
-                stats.imported += 1;
-            } else {
-                let input = DocumentInput {
-                    url: target_uri.clone(),
-                    title,
-                    mime_type,
-                    metadata: serde_json::json!({}),
-                    original_filename: None,
-                    server_date: None,
-                };
-
-                match save_document_async(&doc_repo, content, &input, &source_id, documents_dir)
-                    .await
-                {
-                    Ok(_) => {
-                        // Add to URL cache to avoid re-importing in same session
-                        existing_urls.insert(target_uri);
-                        stats.imported += 1;
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to import {}: {}", target_uri, e);
-                        stats.errors += 1;
-                    }
-                }
-            }
+            self.import_record(
+                &record,
+                None,
+                serde_json::json!({}),
+                &doc_repo,
+                config,
+                &mut existing_urls,
+                &mut stats,
+            )
+            .await;
         }
 
         // Get final position
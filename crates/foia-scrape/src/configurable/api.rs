@@ -3,7 +3,10 @@
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use super::budget::CrawlBudget;
 use super::extract::{extract_path, extract_url, extract_urls};
+use super::frontier::FrontierScorer;
+use super::incremental::ListingTracker;
 use super::ConfigurableScraper;
 use crate::config::ScraperConfig;
 use crate::HttpClient;
@@ -18,6 +21,7 @@ impl ConfigurableScraper {
         source_id: &str,
         crawl_repo: &Option<Arc<DieselCrawlRepository>>,
         url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
     ) {
         let api = match &config.discovery.api {
             Some(api) => api,
@@ -34,12 +38,20 @@ impl ConfigurableScraper {
 
         info!("Starting streaming API discovery from {}", api_url);
 
+        let mut listings = ListingTracker::new(config, source_id, crawl_repo);
+        let scorer = FrontierScorer::new(config);
         let mut page = 1u32;
         let mut total_urls = 0;
         let mut rate_limited = false;
         let mut last_error: Option<String> = None;
+        // Whether pagination reached its end rather than failing
+        let mut completed = false;
 
         loop {
+            if budget.exhausted().await {
+                break;
+            }
+
             let mut params: Vec<(String, String)> = Vec::new();
             params.push((api.pagination.page_param.clone(), page.to_string()));
 
@@ -111,31 +123,37 @@ impl ConfigurableScraper {
 
             if results.is_empty() {
                 info!("No more results on page {}", page);
+                completed = true;
                 break;
             }
 
+            let urls: Vec<String> = results
+                .iter()
+                .flat_map(|item| extract_urls(item, &api.url_extraction))
+                .collect();
+            let unchanged = listings.should_stop(&url_with_params, &urls).await;
+
             let mut page_urls = 0;
-            for item in results {
-                for url in extract_urls(item, &api.url_extraction) {
-                    // Track URL in database
-                    if let Some(repo) = crawl_repo {
-                        let crawl_url = CrawlUrl::new(
-                            url.clone(),
-                            source_id.to_string(),
-                            DiscoveryMethod::ApiResult,
-                            Some(api_url.clone()),
-                            1,
-                        );
-                        let _ = repo.add_url(&crawl_url).await;
-                    }
+            for url in urls {
+                // Track URL in database
+                if let Some(repo) = crawl_repo {
+                    let crawl_url = CrawlUrl::new(
+                        url.clone(),
+                        source_id.to_string(),
+                        DiscoveryMethod::ApiResult,
+                        Some(api_url.clone()),
+                        1,
+                    )
+                    .with_priority(scorer.score(&url, 1, None, 0.0));
+                    let _ = repo.add_url(&crawl_url).await;
+                }
 
-                    // Send URL to download queue
-                    if url_tx.send(url).await.is_err() {
-                        return; // Receiver dropped
-                    }
-                    page_urls += 1;
-                    total_urls += 1;
+                // Send URL to download queue
+                if url_tx.send(url).await.is_err() {
+                    return; // Receiver dropped
                 }
+                page_urls += 1;
+                total_urls += 1;
             }
 
             info!(
@@ -147,11 +165,22 @@ impl ConfigurableScraper {
             );
 
             if results.len() < api.pagination.page_size as usize {
+                completed = true;
+                break;
+            }
+
+            if unchanged {
+                info!("Page {} unchanged since last crawl, stopping", page);
+                completed = true;
                 break;
             }
 
             page += 1;
         }
+        if completed {
+            listings.complete().await;
+        }
+        listings.report();
 
         // Report results with appropriate log level
         if rate_limited {
@@ -184,6 +213,7 @@ impl ConfigurableScraper {
         source_id: &str,
         crawl_repo: &Option<Arc<DieselCrawlRepository>>,
         url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
     ) {
         let api = match &config.discovery.api {
             Some(api) => api,
@@ -211,14 +241,24 @@ impl ConfigurableScraper {
             .as_deref()
             .unwrap_or("next_cursor");
 
+        let mut listings = ListingTracker::new(config, source_id, crawl_repo);
+        let scorer = FrontierScorer::new(config);
         let mut total_urls = 0;
         let mut rate_limited = false;
         let mut last_error: Option<String> = None;
+        let mut out_of_budget = false;
 
         for query in queries {
             let mut cursor: Option<String> = None;
+            let mut completed = false;
+            listings.reset();
 
             loop {
+                if budget.exhausted().await {
+                    out_of_budget = true;
+                    break;
+                }
+
                 let mut url = api_url.clone();
                 let mut params = Vec::new();
 
@@ -277,43 +317,54 @@ impl ConfigurableScraper {
                 };
 
                 if results.is_empty() {
+                    completed = true;
                     break;
                 }
 
-                for item in results {
-                    for doc_url in extract_urls(item, &api.url_extraction) {
-                        if let Some(repo) = crawl_repo {
-                            let crawl_url = CrawlUrl::new(
-                                doc_url.clone(),
-                                source_id.to_string(),
-                                DiscoveryMethod::ApiResult,
-                                Some(url.clone()),
-                                1,
-                            );
-                            let _ = repo.add_url(&crawl_url).await;
-                        }
+                let doc_urls: Vec<String> = results
+                    .iter()
+                    .flat_map(|item| extract_urls(item, &api.url_extraction))
+                    .collect();
+                let unchanged = listings.should_stop(&url, &doc_urls).await;
 
-                        if url_tx.send(doc_url).await.is_err() {
-                            return;
-                        }
-                        total_urls += 1;
+                for doc_url in doc_urls {
+                    if let Some(repo) = crawl_repo {
+                        let crawl_url = CrawlUrl::new(
+                            doc_url.clone(),
+                            source_id.to_string(),
+                            DiscoveryMethod::ApiResult,
+                            Some(url.clone()),
+                            1,
+                        )
+                        .with_priority(scorer.score(&doc_url, 1, None, 0.0));
+                        let _ = repo.add_url(&crawl_url).await;
+                    }
+
+                    if url_tx.send(doc_url).await.is_err() {
+                        return;
                     }
+                    total_urls += 1;
                 }
 
                 cursor = extract_path(&data, cursor_path)
                     .as_str()
                     .map(|s| s.to_string());
 
-                if cursor.is_none() {
+                if cursor.is_none() || unchanged {
+                    completed = true;
                     break;
                 }
             }
+            if completed {
+                listings.complete().await;
+            }
 
-            // If rate limited, don't continue to next query
-            if rate_limited {
+            // If rate limited or out of budget, don't continue to next query
+            if rate_limited || out_of_budget {
                 break;
             }
         }
+        listings.report();
 
         // Report results with appropriate log level
         if rate_limited {
@@ -359,6 +410,10 @@ impl ConfigurableScraper {
 
         let mut page = 1u32;
         loop {
+            if budget.exhausted().await {
+                break;
+            }
+
             let mut params: Vec<(String, String)> = Vec::new();
             params.push((api.pagination.page_param.clone(), page.to_string()));
 
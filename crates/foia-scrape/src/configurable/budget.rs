@@ -0,0 +1,64 @@
+//! Per-run crawl budget and deadline checks.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tracing::info;
+
+use crate::config::{BudgetConfig, ScraperConfig};
+use foia::repository::DieselCrawlRepository;
+
+/// A source's crawl budget for one run, shared by discovery and the
+/// download workers.
+///
+/// Limits apply only if the source sets `budget` and a crawl repository is
+/// available; the deadline only if the run was given one.
+#[derive(Clone)]
+pub(crate) struct CrawlBudget {
+    limits: Option<Arc<(BudgetConfig, Arc<DieselCrawlRepository>)>>,
+    deadline: Option<DateTime<Utc>>,
+    source_id: String,
+    started_at: DateTime<Utc>,
+}
+
+impl CrawlBudget {
+    /// Start counting the run's usage from now.
+    pub(crate) fn new(
+        config: &ScraperConfig,
+        source_id: &str,
+        crawl_repo: &Option<Arc<DieselCrawlRepository>>,
+        deadline: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            limits: config.budget.clone().zip(crawl_repo.clone()).map(Arc::new),
+            deadline,
+            source_id: source_id.to_string(),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Whether the deadline has passed or any limit has been reached since
+    /// the run started. Logs the reason when it has.
+    pub(crate) async fn exhausted(&self) -> bool {
+        if self.deadline.is_some_and(|at| Utc::now() >= at) {
+            info!("[{}] Run deadline reached", self.source_id);
+            return true;
+        }
+        let Some((budget, repo)) = self.limits.as_deref() else {
+            return false;
+        };
+        match repo
+            .get_budget_usage(&self.source_id, self.started_at)
+            .await
+        {
+            Ok(usage) if usage.exhausts(budget) => {
+                info!(
+                    "[{}] Crawl budget reached ({} requests, {} bytes, {} new documents)",
+                    self.source_id, usage.requests, usage.bytes, usage.new_documents
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
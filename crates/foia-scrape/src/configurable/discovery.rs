@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use super::budget::CrawlBudget;
 use super::ConfigurableScraper;
 use crate::config::ScraperConfig;
 use crate::HttpClient;
@@ -18,6 +19,7 @@ impl ConfigurableScraper {
         source_id: &str,
         crawl_repo: &Option<Arc<DieselCrawlRepository>>,
         url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
         browser_config: &Option<BrowserEngineConfig>,
     ) {
         match config.discovery.discovery_type.as_str() {
@@ -28,18 +30,31 @@ impl ConfigurableScraper {
                     source_id,
                     crawl_repo,
                     url_tx,
+                    budget,
                     browser_config,
                 )
                 .await;
             }
             "api_paginated" => {
                 Self::discover_api_paginated_streaming(
-                    config, client, source_id, crawl_repo, url_tx,
+                    config, client, source_id, crawl_repo, url_tx, budget,
                 )
                 .await;
             }
             "api_cursor" => {
-                Self::discover_api_cursor_streaming(config, client, source_id, crawl_repo, url_tx)
+                Self::discover_api_cursor_streaming(
+                    config, client, source_id, crawl_repo, url_tx, budget,
+                )
+                .await;
+            }
+            "form_search" => {
+                Self::discover_form_search_streaming(
+                    config, client, source_id, crawl_repo, url_tx, budget,
+                )
+                .await;
+            }
+            "sitemap" => {
+                Self::discover_sitemap_streaming(config, client, source_id, crawl_repo, url_tx)
                     .await;
             }
             _ => {}
@@ -54,22 +69,35 @@ impl ConfigurableScraper {
         source_id: &str,
         crawl_repo: &Option<Arc<DieselCrawlRepository>>,
         url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
     ) {
         match config.discovery.discovery_type.as_str() {
             "html_crawl" => {
                 Self::discover_html_crawl_streaming_no_browser(
-                    config, client, source_id, crawl_repo, url_tx,
+                    config, client, source_id, crawl_repo, url_tx, budget,
                 )
                 .await;
             }
             "api_paginated" => {
                 Self::discover_api_paginated_streaming(
-                    config, client, source_id, crawl_repo, url_tx,
+                    config, client, source_id, crawl_repo, url_tx, budget,
                 )
                 .await;
             }
             "api_cursor" => {
-                Self::discover_api_cursor_streaming(config, client, source_id, crawl_repo, url_tx)
+                Self::discover_api_cursor_streaming(
+                    config, client, source_id, crawl_repo, url_tx, budget,
+                )
+                .await;
+            }
+            "form_search" => {
+                Self::discover_form_search_streaming(
+                    config, client, source_id, crawl_repo, url_tx, budget,
+                )
+                .await;
+            }
+            "sitemap" => {
+                Self::discover_sitemap_streaming(config, client, source_id, crawl_repo, url_tx)
                     .await;
             }
             _ => {}
@@ -83,6 +111,8 @@ impl ConfigurableScraper {
             "api_paginated" => self.discover_api_paginated().await,
             "api_cursor" => self.discover_api_cursor().await,
             "api_nested" => self.discover_api_nested().await,
+            "form_search" => self.discover_form_search().await,
+            "sitemap" => self.discover_sitemap().await,
             _ => Vec::new(),
         }
     }
@@ -3,7 +3,7 @@
 use chrono::Utc;
 use tracing::debug;
 
-use super::ConfigurableScraper;
+use super::{ConfigurableScraper, PortalSession};
 use crate::{extract_title_from_url, HttpClient, ScraperResult};
 #[cfg(feature = "browser")]
 use foia::browser::BrowserFetcher;
@@ -19,9 +19,47 @@ pub(crate) enum FetchError {
     UrlFailed(String),
 }
 
+/// A portal responded with its login page instead of the document.
+pub(crate) struct LoggedOut;
+
 impl ConfigurableScraper {
     /// Static fetch method for use in workers.
-    pub(crate) async fn fetch_url(client: &HttpClient, url: &str) -> Option<ScraperResult> {
+    ///
+    /// With a portal session, a logged-out response triggers one re-login
+    /// and retry.
+    pub(crate) async fn fetch_url(
+        client: &HttpClient,
+        url: &str,
+        session: Option<&PortalSession>,
+    ) -> Option<ScraperResult> {
+        let Some(session) = session else {
+            return Self::fetch_url_once(client, url, None).await.ok().flatten();
+        };
+
+        let generation = session.generation();
+        if let Ok(result) = Self::fetch_url_once(client, url, Some(session)).await {
+            return result;
+        }
+        debug!("Portal session expired while fetching {}", url);
+        if let Err(e) = session.relogin(generation).await {
+            tracing::warn!("Portal re-login failed: {:#}", e);
+            return None;
+        }
+        Self::fetch_url_once(client, url, Some(session))
+            .await
+            .unwrap_or_else(|LoggedOut| {
+                debug!("Still logged out after re-login: {}", url);
+                None
+            })
+    }
+
+    /// Fetch once, reporting `LoggedOut` when `session` says the response
+    /// is a login page.
+    async fn fetch_url_once(
+        client: &HttpClient,
+        url: &str,
+        session: Option<&PortalSession>,
+    ) -> Result<Option<ScraperResult>, LoggedOut> {
         debug!("Fetching: {}", url);
 
         // Get cached headers for conditional GET (refresh scenario)
@@ -34,21 +72,24 @@ impl ConfigurableScraper {
             Ok(r) => r,
             Err(e) => {
                 debug!("Failed to fetch {}: {}", url, e);
-                return None;
+                return Ok(None);
             }
         };
 
         if response.is_not_modified() {
-            return Some(ScraperResult::not_modified(
+            return Ok(Some(ScraperResult::not_modified(
                 url.to_string(),
                 response.etag().map(|s| s.to_string()),
                 response.last_modified().map(|s| s.to_string()),
-            ));
+            )));
         }
 
         if !response.is_success() {
+            if session.is_some_and(|s| s.is_logged_out(response.status.as_u16(), None)) {
+                return Err(LoggedOut);
+            }
             debug!("HTTP {} for {}", response.status, url);
-            return None;
+            return Ok(None);
         }
 
         // Extract headers before consuming response with bytes()
@@ -72,10 +113,19 @@ impl ConfigurableScraper {
             Ok(b) => b,
             Err(e) => {
                 debug!("Failed to read response for {}: {}", url, e);
-                return None;
+                return Ok(None);
             }
         };
 
+        // Expired portal sessions usually answer 200 with the login page
+        if let Some(session) = session {
+            if mime_type.starts_with("text/html")
+                && session.is_logged_out(200, Some(&String::from_utf8_lossy(&content)))
+            {
+                return Err(LoggedOut);
+            }
+        }
+
         let mut result = ScraperResult {
             url: url.to_string(),
             title,
@@ -97,7 +147,7 @@ impl ConfigurableScraper {
             "fetched_at": result.fetched_at.to_rfc3339(),
         });
 
-        Some(result)
+        Ok(Some(result))
     }
 
     /// Fetch URL using browser for anti-bot protected sites.
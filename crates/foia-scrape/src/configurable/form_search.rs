@@ -0,0 +1,788 @@
+//! Search form discovery (`form_search`).
+//!
+//! Some reading rooms only list documents behind a search form, often an
+//! ASP.NET WebForms page whose `__VIEWSTATE` must be posted back or a Drupal
+//! view with a per-render `form_build_id`. Each submission fetches the form,
+//! carries every current field value forward, fills in one query and/or date
+//! window, and then walks the result pages.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use tracing::{debug, info, warn};
+use url::Url;
+
+use super::budget::CrawlBudget;
+use super::extract::resolve_url;
+use super::frontier::FrontierScorer;
+use super::incremental::ListingTracker;
+use super::ConfigurableScraper;
+use crate::config::{DateWindowConfig, ScraperConfig};
+use crate::HttpClient;
+use foia::models::{CrawlUrl, DiscoveryMethod};
+use foia::repository::DieselCrawlRepository;
+
+/// Result pages followed per submission when `max_pages` is unset.
+const DEFAULT_MAX_PAGES: u32 = 50;
+
+/// HTTP method declared by a form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FormMethod {
+    Get,
+    Post,
+}
+
+/// A request for the next page of a form search.
+#[derive(Debug, Clone, PartialEq)]
+enum PageRequest {
+    Get(String),
+    Post {
+        url: String,
+        fields: Vec<(String, String)>,
+    },
+}
+
+impl PageRequest {
+    fn url(&self) -> &str {
+        match self {
+            PageRequest::Get(url) => url,
+            PageRequest::Post { url, .. } => url,
+        }
+    }
+}
+
+/// A parsed form: where it submits and the values it would send.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct FormState {
+    pub(super) action: String,
+    pub(super) method: FormMethod,
+    pub(super) fields: Vec<(String, String)>,
+}
+
+impl FormState {
+    /// Parse the first form matching `form_selector` (default `form`).
+    fn parse(html: &str, page_url: &str, form_selector: Option<&str>) -> Option<Self> {
+        let document = Html::parse_document(html);
+        let form_sel = Selector::parse(form_selector.unwrap_or("form")).ok()?;
+        let form = document.select(&form_sel).next()?;
+        Self::from_element(form, page_url)
+    }
+
+    /// Read a `<form>` element.
+    ///
+    /// Hidden fields, text inputs, checked boxes, selected options and
+    /// textareas are kept; buttons are left out so a submission only names
+    /// the button it configures.
+    pub(super) fn from_element(form: ElementRef, page_url: &str) -> Option<Self> {
+        let action = match form.value().attr("action").map(str::trim) {
+            Some(action) if !action.is_empty() => resolve_link(page_url, action)?,
+            _ => page_url.to_string(),
+        };
+        let method = match form.value().attr("method") {
+            Some(m) if m.eq_ignore_ascii_case("post") => FormMethod::Post,
+            _ => FormMethod::Get,
+        };
+
+        let field_sel = Selector::parse("input[name], select[name], textarea[name]").ok()?;
+        let mut fields = Vec::new();
+        for element in form.select(&field_sel) {
+            let el = element.value();
+            let name = el.attr("name").unwrap_or_default().to_string();
+            if el.attr("disabled").is_some() {
+                continue;
+            }
+            match el.name() {
+                "input" => {
+                    let kind = el.attr("type").unwrap_or("text").to_ascii_lowercase();
+                    match kind.as_str() {
+                        "submit" | "button" | "image" | "reset" | "file" => {}
+                        "checkbox" | "radio" => {
+                            if el.attr("checked").is_some() {
+                                let value = el.attr("value").unwrap_or("on");
+                                fields.push((name, value.to_string()));
+                            }
+                        }
+                        _ => fields.push((name, el.attr("value").unwrap_or("").to_string())),
+                    }
+                }
+                "select" => {
+                    for value in selected_options(element) {
+                        fields.push((name.clone(), value));
+                    }
+                }
+                _ => fields.push((name, element.text().collect())),
+            }
+        }
+
+        Some(Self {
+            action,
+            method,
+            fields,
+        })
+    }
+
+    /// Set a field, replacing any current values.
+    pub(super) fn set(&mut self, name: &str, value: &str) {
+        self.fields.retain(|(n, _)| n != name);
+        self.fields.push((name.to_string(), value.to_string()));
+    }
+
+    /// Build the request that submits this form.
+    fn request(&self) -> PageRequest {
+        match self.method {
+            FormMethod::Post => PageRequest::Post {
+                url: self.action.clone(),
+                fields: self.fields.clone(),
+            },
+            FormMethod::Get => {
+                let mut url = match Url::parse(&self.action) {
+                    Ok(url) => url,
+                    Err(_) => return PageRequest::Get(self.action.clone()),
+                };
+                url.set_query(None);
+                url.query_pairs_mut().extend_pairs(&self.fields);
+                PageRequest::Get(url.to_string())
+            }
+        }
+    }
+}
+
+/// Values a `<select>` submits: its selected options, or the first option.
+fn selected_options(select: ElementRef) -> Vec<String> {
+    let option_sel = Selector::parse("option").expect("valid selector");
+    let options: Vec<ElementRef> = select.select(&option_sel).collect();
+    let value_of = |option: &ElementRef| {
+        option
+            .value()
+            .attr("value")
+            .map(str::to_string)
+            .unwrap_or_else(|| option.text().collect::<String>().trim().to_string())
+    };
+
+    let selected: Vec<String> = options
+        .iter()
+        .filter(|o| o.value().attr("selected").is_some())
+        .map(value_of)
+        .collect();
+    if !selected.is_empty() || select.value().attr("multiple").is_some() {
+        return selected;
+    }
+    options.first().map(value_of).into_iter().collect()
+}
+
+/// Resolve an href against the page, skipping fragments and script links.
+fn resolve_link(page_url: &str, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty()
+        || href.starts_with('#')
+        || href.starts_with("javascript:")
+        || href.starts_with("mailto:")
+        || href.starts_with("tel:")
+    {
+        return None;
+    }
+    Url::parse(page_url)
+        .and_then(|base| base.join(href))
+        .map(|u| u.to_string())
+        .ok()
+}
+
+/// Extract the event target and argument from an ASP.NET postback call.
+fn parse_postback(script: &str) -> Option<(String, String)> {
+    let patterns = [
+        r#"__doPostBack\(\s*'([^']*)'\s*,\s*'([^']*)'\s*\)"#,
+        r#"WebForm_PostBackOptions\(\s*"([^"]*)"\s*,\s*"([^"]*)""#,
+    ];
+    patterns.iter().find_map(|pattern| {
+        let caps = Regex::new(pattern).ok()?.captures(script)?;
+        Some((caps[1].to_string(), caps[2].to_string()))
+    })
+}
+
+/// Work out the request for the page after this one.
+///
+/// Links are followed directly; `__doPostBack` links and submit buttons
+/// re-post the results page's own form, which carries the updated viewstate.
+fn next_page_request(
+    html: &str,
+    page_url: &str,
+    next_selector: &str,
+    form_selector: Option<&str>,
+) -> Option<PageRequest> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(next_selector).ok()?;
+    let next = document.select(&selector).next()?;
+    let el = next.value();
+    if el.attr("disabled").is_some() || el.attr("aria-disabled") == Some("true") {
+        return None;
+    }
+
+    let script = [el.attr("href"), el.attr("onclick")]
+        .into_iter()
+        .flatten()
+        .find_map(parse_postback);
+    if let Some((target, argument)) = script {
+        let mut form = FormState::parse(html, page_url, form_selector)?;
+        form.set("__EVENTTARGET", &target);
+        form.set("__EVENTARGUMENT", &argument);
+        return Some(form.request());
+    }
+
+    if let Some(href) = el.attr("href") {
+        return resolve_link(page_url, href).map(PageRequest::Get);
+    }
+
+    // A named submit button sends itself with the rest of the form
+    let name = el.attr("name")?;
+    let mut form = FormState::parse(html, page_url, form_selector)?;
+    form.set(name, el.attr("value").unwrap_or_default());
+    Some(form.request())
+}
+
+/// Collect result links in page order, filtered by the document patterns.
+fn extract_result_links(
+    html: &str,
+    page_url: &str,
+    selectors: &[String],
+    document_patterns: &[Regex],
+) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for selector in selectors {
+        let Ok(sel) = Selector::parse(selector) else {
+            warn!("Invalid result link selector: {}", selector);
+            continue;
+        };
+        for element in document.select(&sel) {
+            let Some(url) = element
+                .value()
+                .attr("href")
+                .and_then(|href| resolve_link(page_url, href))
+            else {
+                continue;
+            };
+            let matches =
+                document_patterns.is_empty() || document_patterns.iter().any(|p| p.is_match(&url));
+            if matches && seen.insert(url.clone()) {
+                links.push(url);
+            }
+        }
+    }
+    links
+}
+
+/// Split the configured range into formatted `(from, to)` windows.
+fn date_windows(
+    config: &DateWindowConfig,
+    today: NaiveDate,
+) -> Result<Vec<(String, String)>, String> {
+    let parse = |s: &str| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| format!("invalid date '{}': {}", s, e))
+    };
+    let start = parse(&config.start)?;
+    let end = match config.end.as_deref() {
+        Some(end) => parse(end)?,
+        None => today,
+    };
+    let step = i64::from(config.window_days.max(1));
+
+    let mut windows = Vec::new();
+    let mut from = start;
+    while from <= end {
+        let to = (from + Duration::days(step - 1)).min(end);
+        windows.push((
+            format_date(from, &config.date_format)?,
+            format_date(to, &config.date_format)?,
+        ));
+        from = to + Duration::days(1);
+    }
+    Ok(windows)
+}
+
+/// Format a date, reporting bad format strings instead of panicking.
+fn format_date(date: NaiveDate, format: &str) -> Result<String, String> {
+    let mut out = String::new();
+    write!(out, "{}", date.format(format))
+        .map_err(|_| format!("invalid date_format '{}'", format))?;
+    Ok(out)
+}
+
+/// One form submission: a label for logging and the fields it sets.
+#[derive(Debug, Clone, PartialEq)]
+struct Submission {
+    label: String,
+    fields: Vec<(String, String)>,
+}
+
+/// Combine search queries and date windows into submissions.
+///
+/// With neither configured the form is submitted once as-is.
+fn build_submissions(
+    queries: &[String],
+    query_field: Option<&str>,
+    windows: Option<(&DateWindowConfig, Vec<(String, String)>)>,
+) -> Vec<Submission> {
+    let queries: Vec<Option<&String>> = match query_field {
+        Some(_) if !queries.is_empty() => queries.iter().map(Some).collect(),
+        _ => vec![None],
+    };
+
+    let mut submissions = Vec::new();
+    for query in queries {
+        let base = match (query, query_field) {
+            (Some(q), Some(field)) => Submission {
+                label: format!("query '{}'", q),
+                fields: vec![(field.to_string(), q.clone())],
+            },
+            _ => Submission {
+                label: "form".to_string(),
+                fields: Vec::new(),
+            },
+        };
+        match &windows {
+            Some((config, ranges)) => {
+                for (from, to) in ranges {
+                    let mut submission = base.clone();
+                    submission.label = format!("{} {}..{}", base.label, from, to);
+                    submission
+                        .fields
+                        .push((config.from_field.clone(), from.clone()));
+                    submission
+                        .fields
+                        .push((config.to_field.clone(), to.clone()));
+                    submissions.push(submission);
+                }
+            }
+            None => submissions.push(base),
+        }
+    }
+    submissions
+}
+
+/// Send a page request and return the page URL and body.
+///
+/// GETs bypass the browser pool so they share the client's cookie jar with
+/// the POSTs that follow.
+async fn fetch_page(client: &HttpClient, request: &PageRequest) -> Result<String, String> {
+    let response = match request {
+        PageRequest::Get(url) => client.get_with_headers(url, HashMap::new()).await,
+        PageRequest::Post { url, fields } => client.post(url, fields).await,
+    }
+    .map_err(|e| e.to_string())?;
+
+    if !response.is_success() {
+        return Err(format!("HTTP {}", response.status.as_u16()));
+    }
+    response.text().await.map_err(|e| e.to_string())
+}
+
+impl ConfigurableScraper {
+    /// Streaming search form discovery.
+    pub(crate) async fn discover_form_search_streaming(
+        config: &ScraperConfig,
+        client: &HttpClient,
+        source_id: &str,
+        crawl_repo: &Option<Arc<DieselCrawlRepository>>,
+        url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
+    ) {
+        let discovery = &config.discovery;
+        let form = match &discovery.form {
+            Some(form) => form,
+            None => {
+                warn!(
+                    "[{}] form_search discovery requires a `form` section",
+                    source_id
+                );
+                return;
+            }
+        };
+
+        let base_url = discovery
+            .base_url
+            .as_deref()
+            .or(config.base_url.as_deref())
+            .unwrap_or_default();
+        let form_url = resolve_url(base_url, &form.form_url);
+
+        let selectors: Vec<String> = if !form.result_links.is_empty() {
+            form.result_links.clone()
+        } else if !discovery.document_links.is_empty() {
+            discovery.document_links.clone()
+        } else {
+            vec!["a".to_string()]
+        };
+        let patterns: Vec<Regex> = discovery
+            .document_patterns
+            .iter()
+            .filter_map(|p| match Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    warn!("[{}] Invalid document pattern '{}': {}", source_id, p, e);
+                    None
+                }
+            })
+            .collect();
+        if form.result_links.is_empty()
+            && discovery.document_links.is_empty()
+            && patterns.is_empty()
+        {
+            warn!(
+                "[{}] form_search needs `form.result_links` or `document_patterns` to tell results apart",
+                source_id
+            );
+            return;
+        }
+
+        if !discovery.search_queries.is_empty() && form.query_field.is_none() {
+            warn!(
+                "[{}] search_queries ignored: form.query_field is not set",
+                source_id
+            );
+        }
+        let windows = match &form.date_windows {
+            Some(window_config) => match date_windows(window_config, Utc::now().date_naive()) {
+                Ok(ranges) => Some((window_config, ranges)),
+                Err(e) => {
+                    warn!("[{}] form.date_windows: {}", source_id, e);
+                    return;
+                }
+            },
+            None => None,
+        };
+        let submissions = build_submissions(
+            &discovery.search_queries,
+            form.query_field.as_deref(),
+            windows,
+        );
+        let max_pages = form.max_pages.unwrap_or(DEFAULT_MAX_PAGES);
+
+        info!(
+            "[{}] Starting form search discovery at {} ({} submission(s))",
+            source_id,
+            form_url,
+            submissions.len()
+        );
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut total_urls = 0usize;
+        let mut listings = ListingTracker::new(config, source_id, crawl_repo);
+        let scorer = FrontierScorer::new(config);
+        let mut out_of_budget = false;
+
+        for submission in &submissions {
+            // Fetch the form fresh so viewstate and build tokens are current
+            let form_html = match fetch_page(client, &PageRequest::Get(form_url.clone())).await {
+                Ok(html) => html,
+                Err(e) => {
+                    warn!("[{}] Failed to fetch form {}: {}", source_id, form_url, e);
+                    continue;
+                }
+            };
+            let mut state =
+                match FormState::parse(&form_html, &form_url, form.form_selector.as_deref()) {
+                    Some(state) => state,
+                    None => {
+                        warn!(
+                            "[{}] No form matching '{}' on {}",
+                            source_id,
+                            form.form_selector.as_deref().unwrap_or("form"),
+                            form_url
+                        );
+                        return;
+                    }
+                };
+            for (name, value) in &form.fields {
+                state.set(name, value);
+            }
+            for (name, value) in &submission.fields {
+                state.set(name, value);
+            }
+
+            let mut request = state.request();
+            let mut previous_links: Vec<String> = Vec::new();
+            let mut failed = false;
+            listings.reset();
+            for page in 1..=max_pages {
+                if budget.exhausted().await {
+                    out_of_budget = true;
+                    break;
+                }
+
+                let page_url = request.url().to_string();
+                let html = match fetch_page(client, &request).await {
+                    Ok(html) => html,
+                    Err(e) => {
+                        warn!(
+                            "[{}] {} page {} failed: {} - {}",
+                            source_id, submission.label, page, e, page_url
+                        );
+                        failed = true;
+                        break;
+                    }
+                };
+
+                let links = extract_result_links(&html, &page_url, &selectors, &patterns);
+                if page > 1 && links == previous_links {
+                    debug!(
+                        "{} page {} repeats the previous page",
+                        submission.label, page
+                    );
+                    break;
+                }
+
+                // Postbacks reuse the form URL, so key listings by submission and page
+                let listing_key = format!("{}#{} page {}", form_url, submission.label, page);
+                let unchanged = listings.should_stop(&listing_key, &links).await;
+
+                let mut new_urls = 0;
+                for url in &links {
+                    if !seen.insert(url.clone()) {
+                        continue;
+                    }
+                    if let Some(repo) = crawl_repo {
+                        let crawl_url = CrawlUrl::new(
+                            url.clone(),
+                            source_id.to_string(),
+                            DiscoveryMethod::FormSearch,
+                            Some(form_url.clone()),
+                            1,
+                        )
+                        .with_priority(scorer.score(url, 1, None, 0.0));
+                        let _ = repo.add_url(&crawl_url).await;
+                    }
+                    if url_tx.send(url.clone()).await.is_err() {
+                        return; // Receiver dropped
+                    }
+                    new_urls += 1;
+                    total_urls += 1;
+                }
+
+                info!(
+                    "[{}] {} page {}: {} result links ({} new, total: {})",
+                    source_id,
+                    submission.label,
+                    page,
+                    links.len(),
+                    new_urls,
+                    total_urls
+                );
+
+                if unchanged {
+                    debug!(
+                        "{} page {} unchanged since last crawl, stopping",
+                        submission.label, page
+                    );
+                    break;
+                }
+
+                let next = form.next_selector.as_deref().and_then(|selector| {
+                    next_page_request(&html, &page_url, selector, form.form_selector.as_deref())
+                });
+                match next {
+                    Some(next) => request = next,
+                    None => break,
+                }
+                previous_links = links;
+            }
+            if out_of_budget {
+                break;
+            }
+            if !failed {
+                listings.complete().await;
+            }
+        }
+
+        listings.report();
+        info!(
+            "[{}] Form search complete: {} URLs from {} submission(s)",
+            source_id,
+            total_urls,
+            submissions.len()
+        );
+    }
+
+    /// Search form discovery (legacy non-streaming interface).
+    pub(crate) async fn discover_form_search(&self) -> Vec<String> {
+        let (url_tx, mut url_rx) = tokio::sync::mpsc::channel(100);
+        let discover = async move {
+            Self::discover_form_search_streaming(
+                &self.config,
+                &self.client,
+                &self.source.id,
+                &self.crawl_repo,
+                &url_tx,
+                &CrawlBudget::new(
+                    &self.config,
+                    &self.source.id,
+                    &self.crawl_repo,
+                    self.deadline,
+                ),
+            )
+            .await;
+        };
+        let collect = async {
+            let mut urls = Vec::new();
+            while let Some(url) = url_rx.recv().await {
+                urls.push(url);
+            }
+            urls
+        };
+        let ((), urls) = tokio::join!(discover, collect);
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEBFORMS_PAGE: &str = r#"<html><body>
+        <form method="post" action="./Search.aspx" id="aspnetForm">
+            <input type="hidden" name="__VIEWSTATE" value="dDwtMTA4MzE0MjEwNTs7Pg==">
+            <input type="hidden" name="__EVENTVALIDATION" value="/wEWAgL+raDpAgK">
+            <input type="text" name="ctl00$Main$txtKeyword" value="">
+            <select name="ctl00$Main$ddlType">
+                <option value="">All</option>
+                <option value="memo" selected>Memo</option>
+            </select>
+            <input type="checkbox" name="ctl00$Main$chkArchived" value="1">
+            <input type="checkbox" name="ctl00$Main$chkPublic" checked>
+            <textarea name="notes">none</textarea>
+            <input type="submit" name="ctl00$Main$btnSearch" value="Search">
+            <table class="results">
+                <tr><td><a href="Docs/2021-001.pdf">2021-001</a></td></tr>
+                <tr><td><a href="/Docs/2021-002.pdf">2021-002</a></td></tr>
+                <tr><td><a href="Detail.aspx?id=3">Details</a></td></tr>
+            </table>
+            <a id="next" href="javascript:__doPostBack('ctl00$Main$gvResults','Page$2')">Next</a>
+        </form>
+    </body></html>"#;
+
+    #[test]
+    fn test_parse_webforms_form() {
+        let form = FormState::parse(
+            WEBFORMS_PAGE,
+            "https://foia.example.gov/Room/Search.aspx",
+            None,
+        )
+        .unwrap();
+        assert_eq!(form.method, FormMethod::Post);
+        assert_eq!(form.action, "https://foia.example.gov/Room/Search.aspx");
+        let names: Vec<&str> = form.fields.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "__VIEWSTATE",
+                "__EVENTVALIDATION",
+                "ctl00$Main$txtKeyword",
+                "ctl00$Main$ddlType",
+                "ctl00$Main$chkPublic",
+                "notes"
+            ]
+        );
+        assert_eq!(form.fields[3].1, "memo");
+        assert_eq!(form.fields[4].1, "on");
+    }
+
+    #[test]
+    fn test_next_page_postback_reposts_form() {
+        let page_url = "https://foia.example.gov/Room/Search.aspx";
+        let request = next_page_request(WEBFORMS_PAGE, page_url, "#next", None).unwrap();
+        let PageRequest::Post { url, fields } = request else {
+            panic!("expected a POST");
+        };
+        assert_eq!(url, page_url);
+        assert!(fields.contains(&("__EVENTTARGET".into(), "ctl00$Main$gvResults".into())));
+        assert!(fields.contains(&("__EVENTARGUMENT".into(), "Page$2".into())));
+        assert!(fields.contains(&("__VIEWSTATE".into(), "dDwtMTA4MzE0MjEwNTs7Pg==".into())));
+    }
+
+    #[test]
+    fn test_next_page_link_and_get_form() {
+        let html = r##"<form action="/search" method="get">
+                <input name="keys" value="budget">
+                <input type="hidden" name="form_build_id" value="form-abc">
+            </form>
+            <a class="next" href="?keys=budget&page=1">next</a>
+            <a class="last" href="#" disabled>last</a>"##;
+        let page_url = "https://example.gov/search?keys=budget";
+        assert_eq!(
+            next_page_request(html, page_url, "a.next", None),
+            Some(PageRequest::Get(
+                "https://example.gov/search?keys=budget&page=1".to_string()
+            ))
+        );
+        assert_eq!(next_page_request(html, page_url, "a.last", None), None);
+
+        let form = FormState::parse(html, page_url, None).unwrap();
+        assert_eq!(
+            form.request(),
+            PageRequest::Get(
+                "https://example.gov/search?keys=budget&form_build_id=form-abc".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_result_links_filters_by_pattern() {
+        let patterns = vec![Regex::new(r"\.pdf$").unwrap()];
+        let links = extract_result_links(
+            WEBFORMS_PAGE,
+            "https://foia.example.gov/Room/Search.aspx",
+            &["table.results a".to_string()],
+            &patterns,
+        );
+        assert_eq!(
+            links,
+            vec![
+                "https://foia.example.gov/Room/Docs/2021-001.pdf",
+                "https://foia.example.gov/Docs/2021-002.pdf"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_date_windows_and_submissions() {
+        let config = DateWindowConfig {
+            from_field: "from".to_string(),
+            to_field: "to".to_string(),
+            start: "2024-01-01".to_string(),
+            end: Some("2024-03-05".to_string()),
+            window_days: 31,
+            date_format: "%m/%d/%Y".to_string(),
+        };
+        let today = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        let ranges = date_windows(&config, today).unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                ("01/01/2024".to_string(), "01/31/2024".to_string()),
+                ("02/01/2024".to_string(), "03/02/2024".to_string()),
+                ("03/03/2024".to_string(), "03/05/2024".to_string()),
+            ]
+        );
+
+        let queries = vec!["budget".to_string(), "audit".to_string()];
+        let submissions = build_submissions(&queries, Some("q"), Some((&config, ranges)));
+        assert_eq!(submissions.len(), 6);
+        assert_eq!(
+            submissions[4].fields,
+            vec![
+                ("q".to_string(), "audit".to_string()),
+                ("from".to_string(), "02/01/2024".to_string()),
+                ("to".to_string(), "03/02/2024".to_string()),
+            ]
+        );
+
+        // Without a query field the form is submitted once
+        let plain = build_submissions(&queries, None, None);
+        assert_eq!(plain.len(), 1);
+        assert!(plain[0].fields.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! Frontier scoring (`discovery.frontier`).
+//!
+//! Large sites have whole sections with nothing worth downloading. Each
+//! discovered URL is scored on its depth, weighted URL patterns, whether its
+//! anchor text reads like a document, and how productive the page linking to
+//! it was. The score orders the HTML crawl frontier and is stored as the
+//! URL's `priority`, which the crawl queue claims highest first.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use regex::Regex;
+use tracing::warn;
+
+use crate::config::ScraperConfig;
+
+const DEFAULT_DEPTH_WEIGHT: i32 = 10;
+const DEFAULT_ANCHOR_WEIGHT: i32 = 20;
+const DEFAULT_YIELD_WEIGHT: i32 = 30;
+
+/// Words that mark anchor text as a document link.
+const DOCUMENT_WORDS: &[&str] = &[
+    "attachment",
+    "audit",
+    "contract",
+    "csv",
+    "doc",
+    "docx",
+    "download",
+    "exhibit",
+    "letter",
+    "memo",
+    "memorandum",
+    "minutes",
+    "pdf",
+    "report",
+    "transcript",
+    "xls",
+    "xlsx",
+    "zip",
+];
+
+/// Scores discovered URLs. Every URL scores 0 unless the source sets
+/// `discovery.frontier`, which keeps the queue in plain breadth-first order.
+pub(crate) struct FrontierScorer {
+    enabled: bool,
+    depth_weight: i32,
+    url_weights: Vec<(Regex, i32)>,
+    anchor_weight: i32,
+    yield_weight: i32,
+}
+
+impl FrontierScorer {
+    pub(crate) fn new(config: &ScraperConfig) -> Self {
+        let frontier = config.discovery.frontier.as_ref();
+        let url_weights = frontier
+            .map(|f| {
+                f.url_weights
+                    .iter()
+                    .filter_map(|(pattern, weight)| match Regex::new(pattern) {
+                        Ok(re) => Some((re, *weight)),
+                        Err(e) => {
+                            warn!("Invalid frontier URL pattern '{}': {}", pattern, e);
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            enabled: frontier.is_some(),
+            depth_weight: frontier
+                .and_then(|f| f.depth_weight)
+                .unwrap_or(DEFAULT_DEPTH_WEIGHT),
+            url_weights,
+            anchor_weight: frontier
+                .and_then(|f| f.anchor_weight)
+                .unwrap_or(DEFAULT_ANCHOR_WEIGHT),
+            yield_weight: frontier
+                .and_then(|f| f.yield_weight)
+                .unwrap_or(DEFAULT_YIELD_WEIGHT),
+        }
+    }
+
+    /// Score a URL found `depth` hops from the start pages.
+    ///
+    /// `parent_yield` is the share of the linking page's links that were
+    /// documents (see [`page_yield`]).
+    pub(crate) fn score(
+        &self,
+        url: &str,
+        depth: u32,
+        anchor: Option<&str>,
+        parent_yield: f64,
+    ) -> i32 {
+        if !self.enabled {
+            return 0;
+        }
+
+        let mut score = 0i32.saturating_sub(self.depth_weight.saturating_mul(depth as i32));
+        for (pattern, weight) in &self.url_weights {
+            if pattern.is_match(url) {
+                score = score.saturating_add(*weight);
+            }
+        }
+        if anchor.is_some_and(looks_like_document) {
+            score = score.saturating_add(self.anchor_weight);
+        }
+        score.saturating_add((self.yield_weight as f64 * parent_yield.clamp(0.0, 1.0)) as i32)
+    }
+}
+
+/// Share of a page's links that were documents.
+pub(crate) fn page_yield(doc_links: usize, page_links: usize) -> f64 {
+    let total = doc_links + page_links;
+    if total == 0 {
+        0.0
+    } else {
+        doc_links as f64 / total as f64
+    }
+}
+
+/// Whether anchor text reads like a link to a document.
+fn looks_like_document(anchor: &str) -> bool {
+    anchor
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| DOCUMENT_WORDS.contains(&word.to_lowercase().as_str()))
+}
+
+/// Pages waiting to be crawled, highest score first and breadth-first
+/// among equal scores.
+#[derive(Default)]
+pub(crate) struct Frontier {
+    heap: BinaryHeap<FrontierEntry>,
+    pushed: u64,
+}
+
+struct FrontierEntry {
+    score: i32,
+    order: Reverse<u64>,
+    url: String,
+    depth: u32,
+}
+
+impl Frontier {
+    pub(crate) fn push(&mut self, url: String, depth: u32, score: i32) {
+        self.heap.push(FrontierEntry {
+            score,
+            order: Reverse(self.pushed),
+            url,
+            depth,
+        });
+        self.pushed += 1;
+    }
+
+    /// Next page as `(url, depth, score)`.
+    pub(crate) fn pop(&mut self) -> Option<(String, u32, i32)> {
+        self.heap.pop().map(|e| (e.url, e.depth, e.score))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+}
+
+impl Ord for FrontierEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.score, self.order).cmp(&(other.score, other.order))
+    }
+}
+
+impl PartialOrd for FrontierEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for FrontierEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FrontierEntry {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FrontierConfig;
+
+    fn scorer(frontier: Option<FrontierConfig>) -> FrontierScorer {
+        let mut config = ScraperConfig::default();
+        config.discovery.frontier = frontier;
+        FrontierScorer::new(&config)
+    }
+
+    #[test]
+    fn test_scoring() {
+        let off = scorer(None);
+        assert_eq!(off.score("https://a.gov/foia/x", 3, Some("PDF"), 1.0), 0);
+
+        let on = scorer(Some(FrontierConfig {
+            url_weights: [("/foia/".to_string(), 25), ("/news/".to_string(), -40)].into(),
+            ..Default::default()
+        }));
+        assert_eq!(on.score("https://a.gov/page", 2, None, 0.0), -20);
+        assert_eq!(on.score("https://a.gov/foia/page", 1, None, 0.0), 15);
+        assert_eq!(on.score("https://a.gov/news/page", 1, None, 0.0), -50);
+        assert_eq!(
+            on.score("https://a.gov/x", 1, Some("Final Report (PDF)"), 0.0),
+            10
+        );
+        assert_eq!(on.score("https://a.gov/x", 1, Some("Reporting"), 0.0), -10);
+        assert_eq!(on.score("https://a.gov/x", 1, None, page_yield(3, 1)), 12);
+    }
+
+    #[test]
+    fn test_frontier_order() {
+        let mut frontier = Frontier::default();
+        frontier.push("a".to_string(), 1, 0);
+        frontier.push("b".to_string(), 2, 30);
+        frontier.push("c".to_string(), 1, 0);
+        frontier.push("d".to_string(), 1, -10);
+        let order: Vec<String> =
+            std::iter::from_fn(|| frontier.pop().map(|(url, ..)| url)).collect();
+        assert_eq!(order, ["b", "a", "c", "d"]);
+    }
+}
@@ -1,6 +1,6 @@
 //! HTML-based discovery methods (BFS crawl).
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use regex::Regex;
@@ -8,7 +8,10 @@ use scraper::{Html, Selector};
 use tracing::{info, warn};
 use url::Url;
 
+use super::budget::CrawlBudget;
 use super::extract::resolve_url;
+use super::frontier::{page_yield, Frontier, FrontierScorer};
+use super::incremental::ListingTracker;
 use super::ConfigurableScraper;
 use crate::config::{PaginationConfig, ScraperConfig};
 use crate::google_drive::{
@@ -19,7 +22,7 @@ use crate::HttpClient;
 #[cfg(feature = "browser")]
 use foia::browser::BrowserEngineConfig;
 #[cfg(feature = "browser")]
-use foia::browser::BrowserFetcher;
+use foia::browser::{BrowserFetcher, BrowserStep};
 use foia::models::{CrawlUrl, DiscoveryMethod};
 use foia::repository::DieselCrawlRepository;
 #[cfg(feature = "browser")]
@@ -113,13 +116,14 @@ impl CrawlerConfig {
     }
 }
 
-/// Initialize the BFS frontier with seed URLs.
+/// Initialize the crawl frontier with seed URLs.
 fn seed_frontier(
     config: &ScraperConfig,
     base_url: &str,
+    scorer: &FrontierScorer,
     visited: &mut HashSet<String>,
-) -> VecDeque<(String, u32)> {
-    let mut frontier: VecDeque<(String, u32)> = VecDeque::new();
+) -> Frontier {
+    let mut frontier = Frontier::default();
 
     // Seed the frontier with start paths
     let start_paths = if config.discovery.start_paths.is_empty() {
@@ -131,7 +135,8 @@ fn seed_frontier(
     for start_path in start_paths {
         let start_url = resolve_url(base_url, &start_path);
         if visited.insert(start_url.clone()) {
-            frontier.push_back((start_url, 0));
+            let score = scorer.score(&start_url, 0, None, 0.0);
+            frontier.push(start_url, 0, score);
         }
     }
 
@@ -142,7 +147,8 @@ fn seed_frontier(
             let search_path = template.replace("{query}", &encoded_query);
             let search_url = resolve_url(base_url, &search_path);
             if visited.insert(search_url.clone()) {
-                frontier.push_back((search_url, 0));
+                let score = scorer.score(&search_url, 0, None, 0.0);
+                frontier.push(search_url, 0, score);
             }
         }
     }
@@ -151,20 +157,24 @@ fn seed_frontier(
 }
 
 /// Fetch a page using browser or HTTP client.
+///
+/// Returns the page HTML and any links collected by browser steps.
+/// `steps` overrides the browser config's steps for this page.
 #[cfg(feature = "browser")]
 async fn fetch_page_html(
     url: &str,
     use_browser: bool,
+    steps: Option<&[BrowserStep]>,
     browser_fetcher: &mut Option<BrowserFetcher>,
     client: &HttpClient,
     failure_stats: &mut (u64, u64), // (consecutive, total)
-) -> Option<String> {
+) -> Option<(String, Vec<String>)> {
     if use_browser {
         if let Some(ref mut browser) = browser_fetcher {
-            match browser.fetch(url).await {
+            match browser.fetch_with_steps(url, steps).await {
                 Ok(resp) => {
                     failure_stats.0 = 0; // Reset consecutive failures
-                    return Some(resp.content);
+                    return Some((resp.content, resp.links));
                 }
                 Err(e) => {
                     failure_stats.0 += 1;
@@ -180,7 +190,7 @@ async fn fetch_page_html(
     }
     // Fall back to HTTP client
     match client.get_text(url).await {
-        Ok(html) => Some(html),
+        Ok(html) => Some((html, Vec::new())),
         Err(e) => {
             debug!("Fetch failed for {}: {}", url, e);
             None
@@ -241,6 +251,7 @@ async fn send_document_url(
     source_id: &str,
     parent_url: &str,
     depth: u32,
+    priority: i32,
     discovery_method: DiscoveryMethod,
     crawl_repo: &Option<Arc<DieselCrawlRepository>>,
     url_tx: &tokio::sync::mpsc::Sender<String>,
@@ -257,7 +268,8 @@ async fn send_document_url(
             discovery_method,
             Some(parent_url.to_string()),
             depth + 1,
-        );
+        )
+        .with_priority(priority);
         let _ = repo.add_url(&crawl_url).await;
     }
 
@@ -347,6 +359,7 @@ impl ConfigurableScraper {
         source_id: &str,
         crawl_repo: &Option<Arc<DieselCrawlRepository>>,
         url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
         browser_config: &Option<BrowserEngineConfig>,
     ) {
         let crawler_config = CrawlerConfig::from_scraper_config(config);
@@ -357,9 +370,11 @@ impl ConfigurableScraper {
             .as_ref()
             .map(|cfg| BrowserFetcher::new(cfg.clone()));
 
-        // BFS frontier and visited set
+        // Scored frontier (plain BFS unless `frontier` is configured) and visited set
+        let scorer = FrontierScorer::new(config);
         let mut visited: HashSet<String> = HashSet::new();
-        let mut frontier = seed_frontier(config, &crawler_config.base_url, &mut visited);
+        let mut frontier = seed_frontier(config, &crawler_config.base_url, &scorer, &mut visited);
+        let mut listings = ListingTracker::new(config, source_id, crawl_repo);
 
         info!(
             "Starting recursive HTML crawl discovery with {} seed URLs",
@@ -370,12 +385,18 @@ impl ConfigurableScraper {
         let mut docs_found = 0u64;
         let mut failure_stats = (0u64, 0u64); // (consecutive, total)
         let initial_frontier_size = frontier.len();
+        let mut out_of_budget = false;
 
-        while let Some((current_url, depth)) = frontier.pop_front() {
+        while let Some((current_url, depth, priority)) = frontier.pop() {
             if depth > crawler_config.max_depth {
                 continue;
             }
 
+            if budget.exhausted().await {
+                out_of_budget = true;
+                break;
+            }
+
             // Track crawl URL
             let crawl_url = CrawlUrl::new(
                 current_url.clone(),
@@ -387,20 +408,30 @@ impl ConfigurableScraper {
                 },
                 None,
                 depth,
-            );
+            )
+            .with_priority(priority);
             client.track_url(&crawl_url).await;
 
+            // Level steps replace the browser config's steps for pages at that depth
+            let level_steps = config
+                .discovery
+                .levels
+                .get(depth as usize)
+                .map(|level| level.browser_steps.as_slice())
+                .filter(|steps| !steps.is_empty());
+
             // Fetch the page
-            let html = match fetch_page_html(
+            let (html, step_links) = match fetch_page_html(
                 &current_url,
                 crawler_config.use_browser,
+                level_steps,
                 &mut browser_fetcher,
                 client,
                 &mut failure_stats,
             )
             .await
             {
-                Some(html) => html,
+                Some(page) => page,
                 None => continue,
             };
 
@@ -415,7 +446,7 @@ impl ConfigurableScraper {
             }
 
             // Parse and extract links
-            let (doc_urls, page_urls) = extract_links_from_html(
+            let (doc_urls, page_urls, anchors) = extract_links_from_html(
                 &html,
                 &current_url,
                 &crawler_config.base_url,
@@ -424,6 +455,17 @@ impl ConfigurableScraper {
                 &page_link_selector,
             );
 
+            // Merge links collected by extract_links steps
+            let (step_doc_urls, step_page_urls) = classify_extracted_links(
+                &step_links,
+                &current_url,
+                &crawler_config.base_url,
+                &crawler_config.allowed_domain,
+                &crawler_config.document_patterns,
+            );
+            let doc_urls: Vec<String> = doc_urls.into_iter().chain(step_doc_urls).collect();
+            let page_urls: Vec<String> = page_urls.into_iter().chain(step_page_urls).collect();
+
             // Process Google Drive folders and filter them from page URLs
             let (gdrive_doc_urls, page_urls) =
                 process_google_drive_folders(page_urls, client, client.via_mappings()).await;
@@ -434,14 +476,34 @@ impl ConfigurableScraper {
                 .map(convert_google_drive_file_url)
                 .collect();
 
+            // On incremental crawls, don't go past pages with nothing new
+            let links: Vec<String> = doc_urls
+                .iter()
+                .chain(&gdrive_doc_urls)
+                .chain(&page_urls)
+                .cloned()
+                .collect();
+            let prune = listings.should_prune(&current_url, &links).await;
+            let parent_yield = page_yield(doc_urls.len() + gdrive_doc_urls.len(), page_urls.len());
+            let score = |url: &str| {
+                scorer.score(
+                    url,
+                    depth + 1,
+                    anchors.get(url).map(String::as_str),
+                    parent_yield,
+                )
+            };
+
             // Send document URLs to download queue
             for full_url in doc_urls {
                 debug!("Found document: {}", full_url);
+                let priority = score(&full_url);
                 if send_document_url(
                     full_url,
                     source_id,
                     &current_url,
                     depth,
+                    priority,
                     DiscoveryMethod::HtmlLink,
                     crawl_repo,
                     url_tx,
@@ -460,11 +522,13 @@ impl ConfigurableScraper {
             // Send Google Drive files to download queue
             for full_url in gdrive_doc_urls {
                 debug!("Found Google Drive document: {}", full_url);
+                let priority = score(&full_url);
                 if send_document_url(
                     full_url,
                     source_id,
                     &current_url,
                     depth,
+                    priority,
                     DiscoveryMethod::GoogleDriveFolder,
                     crawl_repo,
                     url_tx,
@@ -480,14 +544,25 @@ impl ConfigurableScraper {
                 docs_found += 1;
             }
 
+            if prune {
+                debug!("Unchanged since last crawl: {}", current_url);
+                continue;
+            }
+
             // Add page URLs to frontier
             for page_url in page_urls {
                 if visited.insert(page_url.clone()) {
-                    frontier.push_back((page_url, depth + 1));
+                    let priority = score(&page_url);
+                    frontier.push(page_url, depth + 1, priority);
                 }
             }
         }
 
+        if !out_of_budget {
+            listings.complete().await;
+        }
+        listings.report();
+
         let browser_url = browser_config
             .as_ref()
             .and_then(|c| c.remote_url.as_deref());
@@ -509,6 +584,7 @@ impl ConfigurableScraper {
         _source_id: &str,
         _crawl_repo: &Option<Arc<DieselCrawlRepository>>,
         url_tx: &tokio::sync::mpsc::Sender<String>,
+        budget: &CrawlBudget,
     ) {
         let default_base = String::new();
         let base_url = config
@@ -519,6 +595,9 @@ impl ConfigurableScraper {
             .unwrap_or(&default_base);
 
         for start_path in &config.discovery.start_paths {
+            if budget.exhausted().await {
+                return;
+            }
             let start_url = resolve_url(base_url, start_path);
             let html = match client.get_text(&start_url).await {
                 Ok(html) => html,
@@ -739,7 +818,8 @@ impl ConfigurableScraper {
     }
 }
 
-/// Extract document and page links from HTML content.
+/// Extract document and page links from HTML content, with the anchor text
+/// of each link.
 fn extract_links_from_html(
     html: &str,
     current_url: &str,
@@ -747,14 +827,15 @@ fn extract_links_from_html(
     allowed_domain: &str,
     document_patterns: &[Regex],
     page_link_selector: &str,
-) -> (Vec<String>, Vec<String>) {
+) -> (Vec<String>, Vec<String>, HashMap<String, String>) {
     let document = Html::parse_document(html);
     let mut doc_urls: Vec<String> = Vec::new();
     let mut page_urls: Vec<String> = Vec::new();
+    let mut anchors: HashMap<String, String> = HashMap::new();
 
     let selector = match Selector::parse(page_link_selector) {
         Ok(s) => s,
-        Err(_) => return (doc_urls, page_urls),
+        Err(_) => return (doc_urls, page_urls, anchors),
     };
 
     for element in document.select(&selector) {
@@ -763,62 +844,21 @@ fn extract_links_from_html(
             None => continue,
         };
 
-        // Skip empty, javascript, mailto, tel links
-        if href.is_empty()
-            || href.starts_with('#')
-            || href.starts_with("javascript:")
-            || href.starts_with("mailto:")
-            || href.starts_with("tel:")
-        {
-            continue;
-        }
-
-        // Resolve URL
-        let full_url = if href.starts_with("http://") || href.starts_with("https://") {
-            href.to_string()
-        } else if href.starts_with('/') {
-            if let Ok(parsed) = Url::parse(current_url) {
-                format!(
-                    "{}://{}{}",
-                    parsed.scheme(),
-                    parsed.host_str().unwrap_or(""),
-                    href
-                )
-            } else {
-                format!("{}{}", base_url, href)
-            }
-        } else if href.starts_with("//") {
-            format!("https:{}", href)
-        } else if let Ok(base) = Url::parse(current_url) {
-            base.join(href).map(|u| u.to_string()).unwrap_or_default()
-        } else {
-            continue;
+        let (full_url, is_document) = match classify_link(
+            href,
+            current_url,
+            base_url,
+            allowed_domain,
+            document_patterns,
+        ) {
+            Some(link) => link,
+            None => continue,
         };
 
-        if full_url.is_empty() {
-            continue;
-        }
-
-        // Check if link should be followed
-        let url_host = full_url
-            .parse::<Url>()
-            .map(|u| u.host_str().unwrap_or("").to_string())
-            .unwrap_or_default();
-        let current_host = current_url
-            .parse::<Url>()
-            .map(|u| u.host_str().unwrap_or("").to_string())
-            .unwrap_or_default();
-
-        let is_allowed_domain = allowed_domain.is_empty() || url_host.ends_with(allowed_domain);
-        let is_same_host = url_host == current_host;
-
-        if !is_allowed_domain && !is_same_host {
-            continue;
-        }
-
-        // Check if it's a document
-        let is_document = !document_patterns.is_empty()
-            && document_patterns.iter().any(|p| p.is_match(&full_url));
+        let text = element.text().collect::<Vec<_>>().join(" ");
+        anchors
+            .entry(full_url.clone())
+            .or_insert_with(|| text.trim().to_string());
 
         if is_document {
             doc_urls.push(full_url);
@@ -830,5 +870,164 @@ fn extract_links_from_html(
         }
     }
 
+    (doc_urls, page_urls, anchors)
+}
+
+/// Classify links collected by browser steps as documents or pages.
+#[cfg(feature = "browser")]
+fn classify_extracted_links(
+    links: &[String],
+    current_url: &str,
+    base_url: &str,
+    allowed_domain: &str,
+    document_patterns: &[Regex],
+) -> (Vec<String>, Vec<String>) {
+    let mut doc_urls: Vec<String> = Vec::new();
+    let mut page_urls: Vec<String> = Vec::new();
+    for href in links {
+        match classify_link(
+            href,
+            current_url,
+            base_url,
+            allowed_domain,
+            document_patterns,
+        ) {
+            Some((full_url, true)) => doc_urls.push(full_url),
+            Some((full_url, false)) => {
+                if !foia::utils::has_file_extension(&full_url) {
+                    page_urls.push(full_url);
+                }
+            }
+            None => {}
+        }
+    }
     (doc_urls, page_urls)
 }
+
+/// Resolve a link and decide whether it should be followed.
+///
+/// Returns the absolute URL and whether it matches a document pattern, or
+/// `None` for skipped schemes and links outside the allowed domain.
+fn classify_link(
+    href: &str,
+    current_url: &str,
+    base_url: &str,
+    allowed_domain: &str,
+    document_patterns: &[Regex],
+) -> Option<(String, bool)> {
+    // Skip empty, javascript, mailto, tel links
+    if href.is_empty()
+        || href.starts_with('#')
+        || href.starts_with("javascript:")
+        || href.starts_with("mailto:")
+        || href.starts_with("tel:")
+    {
+        return None;
+    }
+
+    // Resolve URL
+    let full_url = if href.starts_with("http://") || href.starts_with("https://") {
+        href.to_string()
+    } else if href.starts_with('/') {
+        if let Ok(parsed) = Url::parse(current_url) {
+            format!(
+                "{}://{}{}",
+                parsed.scheme(),
+                parsed.host_str().unwrap_or(""),
+                href
+            )
+        } else {
+            format!("{}{}", base_url, href)
+        }
+    } else if href.starts_with("//") {
+        format!("https:{}", href)
+    } else if let Ok(base) = Url::parse(current_url) {
+        base.join(href).map(|u| u.to_string()).unwrap_or_default()
+    } else {
+        return None;
+    };
+
+    if full_url.is_empty() {
+        return None;
+    }
+
+    // Check if link should be followed
+    let url_host = full_url
+        .parse::<Url>()
+        .map(|u| u.host_str().unwrap_or("").to_string())
+        .unwrap_or_default();
+    let current_host = current_url
+        .parse::<Url>()
+        .map(|u| u.host_str().unwrap_or("").to_string())
+        .unwrap_or_default();
+
+    let is_allowed_domain = allowed_domain.is_empty() || url_host.ends_with(allowed_domain);
+    let is_same_host = url_host == current_host;
+
+    if !is_allowed_domain && !is_same_host {
+        return None;
+    }
+
+    // Check if it's a document
+    let is_document =
+        !document_patterns.is_empty() && document_patterns.iter().any(|p| p.is_match(&full_url));
+
+    Some((full_url, is_document))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_link() {
+        let patterns = vec![Regex::new(r"\.pdf$").unwrap()];
+        let classify = |href: &str| {
+            classify_link(
+                href,
+                "https://agency.gov/foia/reading-room",
+                "https://agency.gov",
+                "agency.gov",
+                &patterns,
+            )
+        };
+
+        assert_eq!(
+            classify("/files/report.pdf"),
+            Some(("https://agency.gov/files/report.pdf".to_string(), true))
+        );
+        assert_eq!(
+            classify("page-2"),
+            Some(("https://agency.gov/foia/page-2".to_string(), false))
+        );
+        assert_eq!(
+            classify("//cdn.agency.gov/a.pdf"),
+            Some(("https://cdn.agency.gov/a.pdf".to_string(), true))
+        );
+        assert_eq!(classify("https://elsewhere.com/a.pdf"), None);
+        assert_eq!(classify("#top"), None);
+        assert_eq!(classify("javascript:void(0)"), None);
+        assert_eq!(classify("mailto:foia@agency.gov"), None);
+    }
+
+    #[cfg(feature = "browser")]
+    #[test]
+    fn test_classify_extracted_links() {
+        let patterns = vec![Regex::new(r"/download/").unwrap()];
+        let links = vec![
+            "/download/1".to_string(),
+            "/results?page=2".to_string(),
+            "/images/logo.png".to_string(),
+            "https://elsewhere.com/download/2".to_string(),
+        ];
+        let (docs, pages) = classify_extracted_links(
+            &links,
+            "https://agency.gov/search",
+            "https://agency.gov",
+            "agency.gov",
+            &patterns,
+        );
+        assert_eq!(docs, vec!["https://agency.gov/download/1"]);
+        assert_eq!(pages, vec!["https://agency.gov/results?page=2"]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Incremental discovery over listing pages.
+//!
+//! Every listing page (an HTML page in the BFS crawl, a page of API results,
+//! a page of form search results) is fingerprinted by the links it yields.
+//! With `incremental.newest_first`, discovery stops going past pages that
+//! hold nothing new, so a run over an unchanged source costs one request
+//! per listing instead of a full walk.
+//!
+//! Fingerprints are only recorded once a walk has run to its end, so a crawl
+//! cut short can't leave behind a first page that stops the next run before
+//! it reaches the pages that were never crawled.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+use crate::config::ScraperConfig;
+use foia::models::ListingPage;
+use foia::repository::DieselCrawlRepository;
+
+/// Tracks listing pages for one discovery run.
+///
+/// Does nothing unless the source sets `discovery.incremental` and a crawl
+/// repository is available.
+pub(crate) struct ListingTracker<'a> {
+    repo: Option<&'a DieselCrawlRepository>,
+    source_id: &'a str,
+    newest_first: bool,
+    unchanged_pages: u32,
+    max_age: Option<Duration>,
+    /// Consecutive unchanged pages in the current pagination sequence.
+    run: u32,
+    /// Pages where discovery stopped early.
+    stopped: u64,
+    /// Pages of the current walk, recorded once it completes.
+    pending: Vec<PendingListing>,
+}
+
+/// A listing page waiting for its walk to complete.
+struct PendingListing {
+    url: String,
+    fingerprint: String,
+    link_count: u32,
+    walked: bool,
+}
+
+impl<'a> ListingTracker<'a> {
+    pub(crate) fn new(
+        config: &ScraperConfig,
+        source_id: &'a str,
+        crawl_repo: &'a Option<Arc<DieselCrawlRepository>>,
+    ) -> Self {
+        let incremental = config.discovery.incremental.as_ref();
+        Self {
+            repo: incremental.and(crawl_repo.as_deref()),
+            source_id,
+            newest_first: incremental.is_some_and(|i| i.newest_first),
+            unchanged_pages: incremental
+                .and_then(|i| i.unchanged_pages)
+                .unwrap_or(1)
+                .max(1),
+            max_age: incremental
+                .and_then(|i| i.full_crawl_days)
+                .map(|days| Duration::days(days.into())),
+            run: 0,
+            stopped: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Start a new pagination sequence (next query or form submission).
+    ///
+    /// Pages of a previous sequence that didn't complete are dropped.
+    pub(crate) fn reset(&mut self) {
+        self.run = 0;
+        self.pending.clear();
+    }
+
+    /// Record the pages of the current walk, which ran to its last page or
+    /// stopped at unchanged pages.
+    ///
+    /// Skip this when the walk was cut short (rate limit, failed request,
+    /// dropped receiver) so the next run walks those pages again.
+    pub(crate) async fn complete(&mut self) {
+        let Some(repo) = self.repo else {
+            return;
+        };
+        for page in self.pending.drain(..) {
+            if let Err(e) = repo
+                .record_listing(
+                    self.source_id,
+                    &page.url,
+                    &page.fingerprint,
+                    page.link_count,
+                    page.walked,
+                )
+                .await
+            {
+                warn!(
+                    "[{}] Failed to record listing fingerprint for {}: {}",
+                    self.source_id, page.url, e
+                );
+            }
+        }
+    }
+
+    /// Record a page of a paginated listing. Returns true once
+    /// `unchanged_pages` pages in a row were unchanged, meaning pagination
+    /// should stop after this page.
+    ///
+    /// Call before the page's links are added to the crawl repository, and
+    /// [`complete`](Self::complete) once pagination ends normally.
+    pub(crate) async fn should_stop(&mut self, url: &str, links: &[String]) -> bool {
+        self.check(url, links, self.unchanged_pages).await
+    }
+
+    /// Record a page reached by following links. Returns true when the
+    /// page is unchanged and its links needn't be followed.
+    ///
+    /// Call before the page's links are added to the crawl repository.
+    pub(crate) async fn should_prune(&mut self, url: &str, links: &[String]) -> bool {
+        self.run = 0;
+        self.check(url, links, 1).await
+    }
+
+    /// Log how much of the listing was skipped.
+    pub(crate) fn report(&self) {
+        if self.stopped > 0 {
+            info!(
+                "[{}] Incremental: stopped at {} unchanged listing page{}",
+                self.source_id,
+                self.stopped,
+                if self.stopped == 1 { "" } else { "s" }
+            );
+        }
+    }
+
+    async fn check(&mut self, url: &str, links: &[String], threshold: u32) -> bool {
+        let Some(repo) = self.repo else {
+            return false;
+        };
+        let fingerprint = ListingPage::fingerprint(links);
+
+        let mut unchanged = false;
+        if self.newest_first {
+            match repo.get_listing(self.source_id, url).await {
+                Ok(Some(page)) => {
+                    let has_new_links =
+                        page.fingerprint != fingerprint && self.has_new_links(repo, links).await;
+                    unchanged =
+                        page.is_unchanged(&fingerprint, has_new_links, self.max_age, Utc::now());
+                }
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "[{}] Failed to load listing fingerprint for {}: {}",
+                    self.source_id, url, e
+                ),
+            }
+        }
+
+        self.run = if unchanged { self.run + 1 } else { 0 };
+        let stop = unchanged && self.run >= threshold;
+        if stop {
+            self.stopped += 1;
+        }
+
+        self.pending.push(PendingListing {
+            url: url.to_string(),
+            fingerprint,
+            link_count: links.len() as u32,
+            walked: !stop,
+        });
+        stop
+    }
+
+    /// Whether any link is missing from the crawl repository. Lookup errors
+    /// count as new so discovery errs towards crawling.
+    async fn has_new_links(&self, repo: &DieselCrawlRepository, links: &[String]) -> bool {
+        for link in links {
+            if !repo.url_exists(self.source_id, link).await.unwrap_or(false) {
+                return true;
+            }
+        }
+        false
+    }
+}
@@ -7,13 +7,17 @@
 #![allow(dead_code)]
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+
 use super::config::{ScraperConfig, ViaMode};
 use super::HttpClient;
 #[cfg(feature = "browser")]
 use foia::config::BrowserEngineConfig;
+use foia::http_client::WarcCapture;
 use foia::models::Source;
 #[allow(unused_imports)]
 use foia::privacy::PrivacyConfig;
@@ -21,12 +25,20 @@ use foia::rate_limit::RateLimiter;
 use foia::repository::DieselCrawlRepository;
 
 mod api;
+mod budget;
 mod discovery;
 mod extract;
 mod fetch;
+mod form_search;
+mod frontier;
 mod html_crawl;
+mod incremental;
+mod session;
+mod sitemap;
 mod stream;
 
+pub use session::PortalSession;
+
 /// Configurable scraper driven by JSON configuration.
 pub struct ConfigurableScraper {
     pub(crate) source: Source,
@@ -38,6 +50,10 @@ pub struct ConfigurableScraper {
     /// Browser fetcher for anti-bot protected sites (created lazily when needed).
     #[cfg(feature = "browser")]
     pub(crate) browser_config: Option<BrowserEngineConfig>,
+    /// Logged-in portal session for sources with `auth`.
+    pub(crate) session: Option<Arc<PortalSession>>,
+    /// When discovery and downloads stop, if the run has a time limit.
+    pub(crate) deadline: Option<DateTime<Utc>>,
 }
 
 impl ConfigurableScraper {
@@ -115,6 +131,10 @@ impl ConfigurableScraper {
         if let Some(repo) = crawl_repo.clone() {
             builder = builder.crawl_repo(repo);
         }
+        // Search forms (ASP.NET_SessionId) and portal logins keep session state in cookies
+        if config.discovery.discovery_type == "form_search" || config.auth.is_some() {
+            builder = builder.cookie_store(true);
+        }
         let client = builder.build()?;
 
         #[cfg(feature = "browser")]
@@ -133,6 +153,8 @@ impl ConfigurableScraper {
             refresh_ttl_days,
             #[cfg(feature = "browser")]
             browser_config,
+            session: None,
+            deadline: None,
         })
     }
 
@@ -160,6 +182,56 @@ impl ConfigurableScraper {
         self
     }
 
+    /// Log in to the source's requester portal, keeping cookies in `cookies_file`.
+    ///
+    /// Does nothing unless the config has an `auth` section. Call before
+    /// [`with_warc_capture`](Self::with_warc_capture) so session checks stay
+    /// out of the capture.
+    pub fn with_portal_session(mut self, cookies_file: PathBuf) -> Self {
+        if self.config.auth.is_none() {
+            return self;
+        }
+
+        // Browser fetches load the same cookies
+        #[cfg(feature = "browser")]
+        if let Some(browser) = self.browser_config.as_mut() {
+            browser.cookies_file = Some(cookies_file.clone());
+        }
+
+        self.session = PortalSession::from_config(
+            &self.source.id,
+            &self.config,
+            self.client.clone(),
+            cookies_file,
+        )
+        .map(Arc::new);
+        self
+    }
+
+    /// Log in to the portal, if the source has a session.
+    pub async fn ensure_logged_in(&self) -> anyhow::Result<()> {
+        if let Some(session) = &self.session {
+            session
+                .ensure_logged_in()
+                .await
+                .map_err(|e| anyhow::anyhow!("Portal login failed: {:#}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Stop discovery and downloads at `deadline`, such as when the
+    /// source's schedule window closes.
+    pub fn with_deadline(mut self, deadline: Option<DateTime<Utc>>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Tee every HTTP exchange made by this scraper into a WARC capture.
+    pub fn with_warc_capture(mut self, capture: Arc<WarcCapture>) -> Self {
+        self.client = self.client.with_warc_capture(capture);
+        self
+    }
+
     /// Configure URL rewriting for caching proxies (uses Strict mode).
     #[deprecated(note = "Use with_via_config instead to also set via_mode")]
     pub fn with_via_mappings(mut self, via: HashMap<String, String>) -> Self {
@@ -0,0 +1,612 @@
+//! Logged-in sessions for requester portals (`auth`).
+//!
+//! Responses to our own requests often sit behind a portal login. The
+//! session logs in with an HTML form or browser steps, keeps the cookies in
+//! the scraper's client jar and a cookie file, and logs in again when a
+//! download comes back logged out.
+//!
+//! Credentials only travel in the login POST (sent with
+//! [`HttpClient::post_credentials`], which is never captured) or in typed
+//! browser input. Request logs have cookie headers redacted.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use reqwest::cookie::CookieStore;
+use scraper::{Html, Selector};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use super::extract::resolve_url;
+use super::form_search::{FormMethod, FormState};
+use crate::config::ScraperConfig;
+use crate::{HttpClient, HttpResponse};
+use foia::browser::{
+    add_cookies_to_jar, read_cookie_file, write_cookie_file, BrowserCookie, BrowserEngineConfig,
+    BrowserEngineType, BrowserFetcher, BrowserStep,
+};
+use foia::config::{CredentialSource, PortalAuthConfig, PortalLogin};
+
+/// Logged-out markers used when the config lists none: a password input.
+const DEFAULT_LOGGED_OUT_MARKERS: &[&str] =
+    &[r#"type="password""#, "type='password'", "type=password"];
+
+/// Username and password for a portal account.
+#[derive(Clone)]
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Credentials {
+    /// Read credentials from the configured source.
+    fn resolve(source: &CredentialSource, source_id: &str) -> Result<Self> {
+        match source {
+            CredentialSource::Env {
+                username_var,
+                password_var,
+            } => {
+                let var = |name: &str| {
+                    std::env::var(name)
+                        .with_context(|| format!("environment variable {} is not set", name))
+                };
+                Ok(Self {
+                    username: var(username_var)?,
+                    password: var(password_var)?,
+                })
+            }
+            CredentialSource::File { path, key } => {
+                check_keyring_permissions(path)?;
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read keyring {}", path.display()))?;
+                parse_keyring(&content, key.as_deref().unwrap_or(source_id))
+                    .with_context(|| format!("keyring {}", path.display()))
+            }
+        }
+    }
+}
+
+/// Refuse keyring files that other users could read.
+fn check_keyring_permissions(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .with_context(|| format!("failed to read keyring {}", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            anyhow::bail!(
+                "keyring {} is readable by other users (mode {:o}); run chmod 600",
+                path.display(),
+                mode & 0o777
+            );
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Look up `{"<key>": {"username": ..., "password": ...}}` in a keyring.
+fn parse_keyring(content: &str, key: &str) -> Result<Credentials> {
+    let keyring: serde_json::Value = serde_json::from_str(content).context("invalid JSON")?;
+    let entry = keyring
+        .get(key)
+        .with_context(|| format!("no entry for '{}'", key))?;
+    let field = |name: &str| {
+        entry
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .with_context(|| format!("entry '{}' has no {}", key, name))
+    };
+    Ok(Credentials {
+        username: field("username")?,
+        password: field("password")?,
+    })
+}
+
+/// Whether a response looks like the portal sent us back to the login page.
+fn is_logged_out(status: u16, body: Option<&str>, markers: &[String]) -> bool {
+    if status == 401 || status == 403 {
+        return true;
+    }
+    let Some(body) = body else {
+        return false;
+    };
+    if markers.is_empty() {
+        let body = body.to_ascii_lowercase();
+        DEFAULT_LOGGED_OUT_MARKERS.iter().any(|m| body.contains(m))
+    } else {
+        markers.iter().any(|m| body.contains(m.as_str()))
+    }
+}
+
+/// Find the login form: `form_selector`, or the first form with a password input.
+fn find_login_form(html: &str, page_url: &str, form_selector: Option<&str>) -> Option<FormState> {
+    let document = Html::parse_document(html);
+    let form_sel = Selector::parse(form_selector.unwrap_or("form")).ok()?;
+    let password_sel = Selector::parse(r#"input[type="password" i]"#).ok()?;
+    let form = document
+        .select(&form_sel)
+        .find(|form| form_selector.is_some() || form.select(&password_sel).next().is_some())?;
+    FormState::from_element(form, page_url)
+}
+
+/// Replace `{username}` and `{password}` in typed text.
+fn fill_credentials(steps: &[BrowserStep], credentials: &Credentials) -> Vec<BrowserStep> {
+    steps
+        .iter()
+        .map(|step| match step {
+            BrowserStep::Type {
+                selector,
+                text,
+                clear,
+            } => BrowserStep::Type {
+                selector: selector.clone(),
+                text: text
+                    .replace("{username}", &credentials.username)
+                    .replace("{password}", &credentials.password),
+                clear: *clear,
+            },
+            BrowserStep::Repeat { times, steps } => BrowserStep::Repeat {
+                times: *times,
+                steps: fill_credentials(steps, credentials),
+            },
+            other => other.clone(),
+        })
+        .collect()
+}
+
+/// A requester portal session shared by discovery and download workers.
+pub struct PortalSession {
+    source_id: String,
+    auth: PortalAuthConfig,
+    base_url: String,
+    client: HttpClient,
+    cookies_file: PathBuf,
+    browser_config: Option<BrowserEngineConfig>,
+    /// Completed logins, so concurrent workers that hit an expired session
+    /// log in once between them.
+    generation: AtomicU64,
+    login_lock: Mutex<()>,
+}
+
+impl PortalSession {
+    /// Create a session. `client` must keep cookies (see `cookie_store`).
+    pub fn new(
+        source_id: &str,
+        auth: PortalAuthConfig,
+        base_url: &str,
+        client: HttpClient,
+        cookies_file: PathBuf,
+        browser_config: Option<BrowserEngineConfig>,
+    ) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            auth,
+            base_url: base_url.to_string(),
+            client,
+            cookies_file,
+            browser_config,
+            generation: AtomicU64::new(0),
+            login_lock: Mutex::new(()),
+        }
+    }
+
+    /// Create the session for a source config with `auth`.
+    ///
+    /// The login page is resolved against the config's base URL.
+    pub fn from_config(
+        source_id: &str,
+        config: &ScraperConfig,
+        client: HttpClient,
+        cookies_file: PathBuf,
+    ) -> Option<Self> {
+        let auth = config.auth.clone()?;
+        let base_url = config
+            .base_url
+            .clone()
+            .or_else(|| config.discovery.base_url.clone())
+            .unwrap_or_else(|| auth.login.login_url().to_string());
+        let login_browser = config.browser.clone().map(|c| c.with_env_overrides());
+        Some(Self::new(
+            source_id,
+            auth,
+            &base_url,
+            client,
+            cookies_file,
+            login_browser,
+        ))
+    }
+
+    /// Client holding the session cookies.
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// File the session cookies are kept in.
+    pub fn cookies_file(&self) -> &Path {
+        &self.cookies_file
+    }
+
+    /// Number of logins so far; pass to [`relogin`](Self::relogin).
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Whether a response means the session has expired.
+    pub fn is_logged_out(&self, status: u16, body: Option<&str>) -> bool {
+        is_logged_out(status, body, &self.auth.logged_out_markers)
+    }
+
+    /// Load saved cookies, then log in unless they still work.
+    ///
+    /// Without a `check_url`, saved cookies are trusted until a download
+    /// comes back logged out.
+    pub async fn ensure_logged_in(&self) -> Result<()> {
+        let restored = self.restore();
+
+        let Some(check_url) = self.auth.check_url.as_deref() else {
+            if restored {
+                return Ok(());
+            }
+            return self.relogin(self.generation()).await;
+        };
+
+        let check_url = self.resolve(check_url);
+        let generation = self.generation();
+        if restored {
+            let response = self
+                .client
+                .get_with_headers(&check_url, Default::default())
+                .await
+                .with_context(|| format!("failed to fetch {}", check_url))?;
+            let status = response.status.as_u16();
+            let body = response.text().await.unwrap_or_default();
+            if !self.is_logged_out(status, Some(&body)) {
+                debug!("[{}] Saved portal session is valid", self.source_id);
+                return Ok(());
+            }
+            info!("[{}] Saved portal session has expired", self.source_id);
+        }
+        self.relogin(generation).await
+    }
+
+    /// GET `url` through `client`, logging in again once if the portal
+    /// answers with its login page.
+    ///
+    /// `client` must share this session's cookie jar. HTML bodies are
+    /// buffered to look for logged-out markers.
+    pub async fn get(
+        &self,
+        client: &HttpClient,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<HttpResponse> {
+        let mut relogged = false;
+        loop {
+            let generation = self.generation();
+            let response = client.get(url, etag, last_modified).await?;
+            let response = if response.is_success()
+                && response
+                    .content_type()
+                    .is_some_and(|t| t.starts_with("text/html"))
+            {
+                response.buffered().await?
+            } else {
+                response
+            };
+            let body = response.buffered_body().map(String::from_utf8_lossy);
+            if !self.is_logged_out(response.status.as_u16(), body.as_deref()) {
+                return Ok(response);
+            }
+            if relogged {
+                anyhow::bail!("still logged out of the portal after logging in again");
+            }
+            debug!(
+                "[{}] Portal session expired while fetching {}",
+                self.source_id, url
+            );
+            self.relogin(generation).await?;
+            relogged = true;
+        }
+    }
+
+    /// Log in again unless another worker already has since `seen_generation`.
+    pub async fn relogin(&self, seen_generation: u64) -> Result<()> {
+        let _guard = self.login_lock.lock().await;
+        if self.generation() != seen_generation {
+            return Ok(());
+        }
+        let credentials = Credentials::resolve(&self.auth.credentials, &self.source_id)?;
+        let cookies = match &self.auth.login {
+            PortalLogin::Form {
+                login_url,
+                form_selector,
+                username_field,
+                password_field,
+                fields,
+            } => {
+                let login_url = self.resolve(login_url);
+                self.login_with_form(
+                    &login_url,
+                    form_selector.as_deref(),
+                    (username_field, password_field),
+                    fields,
+                    &credentials,
+                )
+                .await?
+            }
+            PortalLogin::Browser { login_url, steps } => {
+                let login_url = self.resolve(login_url);
+                self.login_with_browser(&login_url, &fill_credentials(steps, &credentials))
+                    .await?
+            }
+        };
+
+        if let Err(e) = write_cookie_file(&self.cookies_file, &cookies) {
+            warn!(
+                "[{}] Failed to save session cookies to {}: {}",
+                self.source_id,
+                self.cookies_file.display(),
+                e
+            );
+        }
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        info!("[{}] Logged in to portal", self.source_id);
+        Ok(())
+    }
+
+    /// Post the login form with the credentials filled in.
+    async fn login_with_form(
+        &self,
+        login_url: &str,
+        form_selector: Option<&str>,
+        (username_field, password_field): (&str, &str),
+        extra_fields: &std::collections::HashMap<String, String>,
+        credentials: &Credentials,
+    ) -> Result<Vec<BrowserCookie>> {
+        let page = self
+            .client
+            .get_with_headers(login_url, Default::default())
+            .await
+            .with_context(|| format!("failed to fetch login page {}", login_url))?
+            .text()
+            .await?;
+        let mut form = find_login_form(&page, login_url, form_selector)
+            .with_context(|| format!("no login form found on {}", login_url))?;
+        if form.method != FormMethod::Post {
+            // A GET form would put the password in the URL, and from there in logs
+            anyhow::bail!("login form on {} does not use POST", login_url);
+        }
+        for (name, value) in extra_fields {
+            form.set(name, value);
+        }
+        form.set(username_field, &credentials.username);
+        form.set(password_field, &credentials.password);
+
+        let response = self
+            .client
+            .post_credentials(&form.action, &form.fields)
+            .await
+            .context("login request failed")?;
+        let status = response.status.as_u16();
+        let body = response.text().await.unwrap_or_default();
+        if !(200..400).contains(&status) || self.is_logged_out(status, Some(&body)) {
+            anyhow::bail!("portal login was rejected (HTTP {})", status);
+        }
+        Ok(self.jar_cookies())
+    }
+
+    /// Run the login steps in a fresh browser and keep its cookies.
+    async fn login_with_browser(
+        &self,
+        login_url: &str,
+        steps: &[BrowserStep],
+    ) -> Result<Vec<BrowserCookie>> {
+        let mut config = self.browser_config.clone().unwrap_or_default();
+        // Start logged out, and use an engine that can run steps
+        config.cookies_file = None;
+        if config.engine == BrowserEngineType::Cookies {
+            config.engine = BrowserEngineType::Standard;
+        }
+
+        let mut browser = BrowserFetcher::new(config);
+        let result = browser.fetch_with_steps(login_url, Some(steps)).await;
+        browser.close().await;
+        let response = result.context("browser login failed")?;
+
+        if self.is_logged_out(response.status, Some(&response.content)) {
+            anyhow::bail!("portal login was rejected at {}", response.final_url);
+        }
+        if let Some(jar) = self.client.cookie_jar() {
+            if let Ok(url) = Url::parse(&response.final_url) {
+                add_cookies_to_jar(jar, &response.cookies, &url);
+            }
+        }
+        Ok(response.cookies)
+    }
+
+    /// Load the cookie file into the client jar. Returns false if there was none.
+    fn restore(&self) -> bool {
+        if !self.cookies_file.exists() {
+            return false;
+        }
+        let (Some(jar), Ok(url)) = (self.client.cookie_jar(), Url::parse(&self.base_url)) else {
+            return false;
+        };
+        match read_cookie_file(&self.cookies_file) {
+            Ok(cookies) if !cookies.is_empty() => {
+                add_cookies_to_jar(jar, &cookies, &url);
+                debug!(
+                    "[{}] Restored {} session cookie(s)",
+                    self.source_id,
+                    cookies.len()
+                );
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                warn!(
+                    "[{}] Ignoring unreadable session file {}: {}",
+                    self.source_id,
+                    self.cookies_file.display(),
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Cookies the client jar would send to the portal.
+    ///
+    /// The jar can't be listed, so this asks for the cookies sent to the base
+    /// URL and stores them against its host.
+    fn jar_cookies(&self) -> Vec<BrowserCookie> {
+        let (Some(jar), Ok(url)) = (self.client.cookie_jar(), Url::parse(&self.base_url)) else {
+            return Vec::new();
+        };
+        let domain = url.host_str().unwrap_or_default().to_string();
+        let Some(header) = jar.cookies(&url) else {
+            return Vec::new();
+        };
+        header
+            .to_str()
+            .unwrap_or_default()
+            .split("; ")
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| BrowserCookie {
+                name: name.to_string(),
+                value: value.to_string(),
+                domain: domain.clone(),
+                path: "/".to_string(),
+                secure: url.scheme() == "https",
+                http_only: true,
+            })
+            .collect()
+    }
+
+    fn resolve(&self, url: &str) -> String {
+        resolve_url(&self.base_url, url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keyring() {
+        let keyring = r#"{
+            "city-portal": {"username": "records@example.org", "password": "hunter2"},
+            "broken": {"username": "x"}
+        }"#;
+        let credentials = parse_keyring(keyring, "city-portal").unwrap();
+        assert_eq!(credentials.username, "records@example.org");
+        assert_eq!(credentials.password, "hunter2");
+        assert!(!format!("{:?}", credentials).contains("hunter2"));
+
+        assert!(parse_keyring(keyring, "missing").is_err());
+        assert!(parse_keyring(keyring, "broken").is_err());
+    }
+
+    #[test]
+    fn test_is_logged_out() {
+        assert!(is_logged_out(401, None, &[]));
+        assert!(is_logged_out(403, Some("Forbidden"), &[]));
+        assert!(!is_logged_out(200, None, &[]));
+        assert!(is_logged_out(
+            200,
+            Some(r#"<input TYPE="Password" name="pw">"#),
+            &[]
+        ));
+        assert!(!is_logged_out(200, Some("<h1>My Requests</h1>"), &[]));
+
+        let markers = vec!["Session expired".to_string()];
+        assert!(is_logged_out(
+            200,
+            Some("Session expired. Sign in."),
+            &markers
+        ));
+        assert!(!is_logged_out(
+            200,
+            Some(r#"<input type="password">"#),
+            &markers
+        ));
+    }
+
+    #[test]
+    fn test_find_login_form() {
+        let html = r#"<html><body>
+            <form action="/search" method="get"><input name="q"></form>
+            <form action="/Account/Login" method="post">
+                <input type="hidden" name="__RequestVerificationToken" value="tok">
+                <input name="Email">
+                <input type="password" name="Password">
+                <input type="submit" value="Sign in">
+            </form>
+        </body></html>"#;
+        let mut form = find_login_form(html, "https://portal.example.gov/login", None).unwrap();
+        assert_eq!(form.action, "https://portal.example.gov/Account/Login");
+        assert_eq!(form.method, FormMethod::Post);
+        form.set("Email", "me@example.org");
+        form.set("Password", "pw");
+        assert_eq!(
+            form.fields,
+            vec![
+                ("__RequestVerificationToken".to_string(), "tok".to_string()),
+                ("Email".to_string(), "me@example.org".to_string()),
+                ("Password".to_string(), "pw".to_string()),
+            ]
+        );
+
+        let form = find_login_form(html, "https://portal.example.gov/login", Some("form")).unwrap();
+        assert_eq!(form.method, FormMethod::Get);
+        assert!(find_login_form("<form></form>", "https://x.gov/", None).is_none());
+    }
+
+    #[test]
+    fn test_fill_credentials() {
+        let steps: Vec<BrowserStep> = serde_json::from_str(
+            r##"[
+                {"action": "type", "selector": "#user", "text": "{username}"},
+                {"action": "repeat", "times": 1, "steps": [
+                    {"action": "type", "selector": "#pass", "text": "{password}"}
+                ]},
+                {"action": "click", "selector": "#login"}
+            ]"##,
+        )
+        .unwrap();
+        let credentials = Credentials {
+            username: "me".to_string(),
+            password: "secret".to_string(),
+        };
+        let filled = fill_credentials(&steps, &credentials);
+        assert!(matches!(&filled[0], BrowserStep::Type { text, .. } if text == "me"));
+        let BrowserStep::Repeat { steps: inner, .. } = &filled[1] else {
+            panic!("expected repeat");
+        };
+        assert!(matches!(&inner[0], BrowserStep::Type { text, .. } if text == "secret"));
+        assert_eq!(filled[2], steps[2]);
+
+        // Filled steps are logged, so the password must not show up at any depth
+        let logged = format!("{:?}", filled);
+        assert!(!logged.contains("secret"), "{}", logged);
+        assert!(logged.contains("#pass"));
+    }
+}
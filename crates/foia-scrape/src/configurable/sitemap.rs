@@ -0,0 +1,282 @@
+//! Sitemap discovery (`sitemap`).
+//!
+//! For sources whose sitemap is their best index. Sitemap indexes are walked
+//! breadth-first and every entry's `<lastmod>` is stored, so later runs only
+//! enqueue URLs that are new or changed and skip child sitemaps whose
+//! lastmod hasn't moved.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use regex::Regex;
+use tracing::{debug, info, warn};
+
+use super::extract::resolve_url;
+use super::frontier::FrontierScorer;
+use super::ConfigurableScraper;
+use crate::config::ScraperConfig;
+use crate::discovery::sources::sitemap::{fetch_sitemap, robots_sitemaps, Sitemap, SITEMAP_PATHS};
+use crate::HttpClient;
+use foia::models::{CrawlUrl, DiscoveryMethod};
+use foia::repository::DieselCrawlRepository;
+
+/// Sitemap files fetched per run when `max_sitemaps` is unset.
+const DEFAULT_MAX_SITEMAPS: u32 = 1000;
+
+/// A sitemap waiting to be fetched.
+struct PendingSitemap {
+    url: String,
+    /// Index that listed this sitemap, and the lastmod it gave.
+    parent: Option<(String, Option<String>)>,
+}
+
+/// Whether an entry needs enqueueing, given the lastmod stored for it
+/// (`None` if never seen). Entries without a lastmod are only new once.
+fn is_new_or_changed(stored: Option<&Option<String>>, lastmod: Option<&str>) -> bool {
+    match stored {
+        None => true,
+        Some(previous) => lastmod.is_some() && previous.as_deref() != lastmod,
+    }
+}
+
+impl ConfigurableScraper {
+    /// Streaming sitemap discovery.
+    pub(crate) async fn discover_sitemap_streaming(
+        config: &ScraperConfig,
+        client: &HttpClient,
+        source_id: &str,
+        crawl_repo: &Option<Arc<DieselCrawlRepository>>,
+        url_tx: &tokio::sync::mpsc::Sender<String>,
+    ) {
+        let discovery = &config.discovery;
+        let sitemap_config = discovery.sitemap.clone().unwrap_or_default();
+        let base_url = discovery
+            .base_url
+            .as_deref()
+            .or(config.base_url.as_deref())
+            .unwrap_or_default();
+        let max_sitemaps = sitemap_config.max_sitemaps.unwrap_or(DEFAULT_MAX_SITEMAPS) as usize;
+        let scorer = FrontierScorer::new(config);
+        let patterns: Vec<Regex> = discovery
+            .document_patterns
+            .iter()
+            .filter_map(|p| match Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    warn!("[{}] Invalid document pattern '{}': {}", source_id, p, e);
+                    None
+                }
+            })
+            .collect();
+
+        let stored: HashMap<String, Option<String>> = match crawl_repo {
+            Some(repo) => repo
+                .get_sitemap_lastmods(source_id)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("[{}] Failed to load sitemap state: {}", source_id, e);
+                    HashMap::new()
+                }),
+            None => HashMap::new(),
+        };
+
+        // Configured sitemaps, then robots.txt, then the usual locations
+        let mut roots: Vec<String> = sitemap_config
+            .urls
+            .iter()
+            .map(|url| resolve_url(base_url, url))
+            .collect();
+        if roots.is_empty() {
+            if let Ok(robots) = client.get_text(&resolve_url(base_url, "/robots.txt")).await {
+                roots = robots_sitemaps(&robots);
+            }
+        }
+        let mut guessing = roots.is_empty();
+        if guessing {
+            roots = SITEMAP_PATHS
+                .iter()
+                .map(|path| resolve_url(base_url, path))
+                .collect();
+        }
+
+        info!(
+            "[{}] Starting sitemap discovery from {} ({} URLs known)",
+            source_id,
+            roots.join(", "),
+            stored.len()
+        );
+
+        let mut pending: VecDeque<PendingSitemap> = roots
+            .into_iter()
+            .map(|url| PendingSitemap { url, parent: None })
+            .collect();
+        let mut fetched: HashSet<String> = HashSet::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let (mut new_urls, mut changed_urls, mut unchanged_urls, mut skipped_sitemaps) =
+            (0usize, 0usize, 0usize, 0usize);
+
+        while let Some(sitemap) = pending.pop_front() {
+            if fetched.contains(&sitemap.url) {
+                continue;
+            }
+            if fetched.len() >= max_sitemaps {
+                warn!(
+                    "[{}] Stopping after {} sitemaps (max_sitemaps)",
+                    source_id, max_sitemaps
+                );
+                break;
+            }
+            fetched.insert(sitemap.url.clone());
+
+            debug!("Fetching sitemap: {}", sitemap.url);
+            let parsed = match fetch_sitemap(client, &sitemap.url).await {
+                Ok(parsed) => parsed,
+                Err(e) if guessing => {
+                    debug!("No sitemap at {}: {}", sitemap.url, e);
+                    continue;
+                }
+                Err(e) => {
+                    warn!(
+                        "[{}] Failed to fetch sitemap {}: {}",
+                        source_id, sitemap.url, e
+                    );
+                    continue;
+                }
+            };
+            // The first guessed location that works is the sitemap
+            if guessing {
+                pending.clear();
+                guessing = false;
+            }
+
+            match parsed {
+                Sitemap::Index(entries) => {
+                    for entry in entries {
+                        if entry.lastmod.is_some()
+                            && !is_new_or_changed(stored.get(&entry.loc), entry.lastmod.as_deref())
+                        {
+                            skipped_sitemaps += 1;
+                            continue;
+                        }
+                        pending.push_back(PendingSitemap {
+                            url: entry.loc,
+                            parent: Some((sitemap.url.clone(), entry.lastmod)),
+                        });
+                    }
+                }
+                Sitemap::UrlSet(entries) => {
+                    for entry in entries {
+                        let lastmod = entry.lastmod.as_deref();
+                        let images = if sitemap_config.include_images {
+                            entry.images.as_slice()
+                        } else {
+                            &[]
+                        };
+                        for url in std::iter::once(&entry.loc).chain(images) {
+                            if !patterns.is_empty() && !patterns.iter().any(|p| p.is_match(url)) {
+                                continue;
+                            }
+                            if !seen.insert(url.clone()) {
+                                continue;
+                            }
+                            let previous = stored.get(url);
+                            if !is_new_or_changed(previous, lastmod) {
+                                unchanged_urls += 1;
+                                continue;
+                            }
+
+                            if let Some(repo) = crawl_repo {
+                                let crawl_url = CrawlUrl::new(
+                                    url.clone(),
+                                    source_id.to_string(),
+                                    DiscoveryMethod::Sitemap,
+                                    Some(sitemap.url.clone()),
+                                    1,
+                                )
+                                .with_priority(scorer.score(url, 1, entry.title.as_deref(), 0.0));
+                                // Changed entries are fetched again
+                                let added = repo.add_url(&crawl_url).await.unwrap_or(false);
+                                if !added {
+                                    let _ = repo.mark_url_for_refresh(source_id, url).await;
+                                }
+                                let _ = repo
+                                    .record_sitemap_entry(source_id, url, &sitemap.url, lastmod)
+                                    .await;
+                            }
+
+                            if previous.is_some() {
+                                changed_urls += 1;
+                            } else {
+                                new_urls += 1;
+                            }
+                            if url_tx.send(url.clone()).await.is_err() {
+                                return; // Receiver dropped
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Store a child sitemap's lastmod only once it has been read, so
+            // a failed fetch is retried on the next run
+            if let (Some(repo), Some((parent, lastmod))) = (crawl_repo, &sitemap.parent) {
+                let _ = repo
+                    .record_sitemap_entry(source_id, &sitemap.url, parent, lastmod.as_deref())
+                    .await;
+            }
+        }
+
+        info!(
+            "[{}] Sitemap discovery complete: {} new, {} changed, {} unchanged URLs \
+             from {} sitemap(s), {} unchanged sitemap(s) skipped",
+            source_id,
+            new_urls,
+            changed_urls,
+            unchanged_urls,
+            fetched.len(),
+            skipped_sitemaps
+        );
+    }
+
+    /// Sitemap discovery (legacy non-streaming interface).
+    pub(crate) async fn discover_sitemap(&self) -> Vec<String> {
+        let (url_tx, mut url_rx) = tokio::sync::mpsc::channel(100);
+        let discover = async move {
+            Self::discover_sitemap_streaming(
+                &self.config,
+                &self.client,
+                &self.source.id,
+                &self.crawl_repo,
+                &url_tx,
+            )
+            .await;
+        };
+        let collect = async {
+            let mut urls = Vec::new();
+            while let Some(url) = url_rx.recv().await {
+                urls.push(url);
+            }
+            urls
+        };
+        let ((), urls) = tokio::join!(discover, collect);
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_new_or_changed() {
+        let dated = Some("2024-06-01".to_string());
+        assert!(is_new_or_changed(None, None));
+        assert!(is_new_or_changed(None, Some("2024-06-01")));
+        assert!(!is_new_or_changed(Some(&dated), Some("2024-06-01")));
+        assert!(is_new_or_changed(Some(&dated), Some("2024-06-02")));
+        // A vanished lastmod isn't a change
+        assert!(!is_new_or_changed(Some(&dated), None));
+        assert!(!is_new_or_changed(Some(&None), None));
+        assert!(is_new_or_changed(Some(&None), Some("2024-06-01")));
+    }
+}
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 use tracing::debug;
 
+use super::budget::CrawlBudget;
 #[cfg(feature = "browser")]
 use super::fetch::FetchError;
 use super::ConfigurableScraper;
@@ -40,19 +41,30 @@ impl ConfigurableScraper {
         #[cfg(feature = "browser")]
         self.preflight_browser_check().await?;
 
+        // Log in before discovery so listing pages are fetched with the session
+        self.ensure_logged_in().await?;
+
         let (result_tx, result_rx) = tokio::sync::mpsc::channel::<ScraperResult>(100);
         let (url_tx, url_rx) = tokio::sync::mpsc::channel::<String>(500);
 
         // Query total count from API if available
         let total_count = self.get_api_total_count().await;
 
+        // Budgets cover this run only, across discovery and downloads
+        let budget = CrawlBudget::new(
+            &self.config,
+            &self.source.id,
+            &self.crawl_repo,
+            self.deadline,
+        );
+
         // Spawn download workers
         let workers = self
-            .spawn_download_workers(concurrency, url_rx, result_tx.clone())
+            .spawn_download_workers(concurrency, url_rx, result_tx.clone(), budget.clone())
             .await;
 
         // Spawn discovery task
-        let discovery_handle = self.spawn_discovery_task(url_tx).await;
+        let discovery_handle = self.spawn_discovery_task(url_tx, budget).await;
 
         // Spawn coordinator to clean up when done
         tokio::spawn(async move {
@@ -126,6 +138,7 @@ impl ConfigurableScraper {
         count: usize,
         url_rx: tokio::sync::mpsc::Receiver<String>,
         result_tx: tokio::sync::mpsc::Sender<ScraperResult>,
+        budget: CrawlBudget,
     ) -> Vec<tokio::task::JoinHandle<()>> {
         let url_rx = Arc::new(tokio::sync::Mutex::new(url_rx));
         let mut handles = Vec::with_capacity(count);
@@ -146,6 +159,8 @@ impl ConfigurableScraper {
             let url_rx = url_rx.clone();
             let result_tx = result_tx.clone();
             let client = self.client.clone();
+            let session = self.session.clone();
+            let budget = budget.clone();
             #[cfg(feature = "browser")]
             let browser_config = browser_config.clone();
             #[cfg(feature = "browser")]
@@ -172,6 +187,11 @@ impl ConfigurableScraper {
                         continue;
                     }
 
+                    // Stopping every worker closes the channel, which ends discovery
+                    if budget.exhausted().await {
+                        break;
+                    }
+
                     client.mark_fetching(&url).await;
 
                     #[cfg(feature = "browser")]
@@ -201,11 +221,11 @@ impl ConfigurableScraper {
                             }
                         }
                     } else {
-                        Self::fetch_url(&client, &url).await
+                        Self::fetch_url(&client, &url, session.as_deref()).await
                     };
 
                     #[cfg(not(feature = "browser"))]
-                    let fetch_result = Self::fetch_url(&client, &url).await;
+                    let fetch_result = Self::fetch_url(&client, &url, session.as_deref()).await;
 
                     match fetch_result {
                         Some(result) => {
@@ -244,6 +264,7 @@ impl ConfigurableScraper {
     pub(crate) async fn spawn_discovery_task(
         &self,
         url_tx: tokio::sync::mpsc::Sender<String>,
+        budget: CrawlBudget,
     ) -> tokio::task::JoinHandle<()> {
         let source_id = self.source.id.clone();
         let config = self.config.clone();
@@ -314,11 +335,13 @@ impl ConfigurableScraper {
                 &source_id,
                 &crawl_repo,
                 &url_tx,
+                &budget,
                 &browser_config,
             )
             .await;
             #[cfg(not(feature = "browser"))]
-            Self::discover_streaming(&config, &client, &source_id, &crawl_repo, &url_tx).await;
+            Self::discover_streaming(&config, &client, &source_id, &crawl_repo, &url_tx, &budget)
+                .await;
         })
     }
 
@@ -1,6 +1,6 @@
 //! Discovery result types.
 
-use foia::models::DiscoveryMethod;
+use foia::models::{CrawlUrl, DiscoveryMethod};
 
 /// A discovered URL with metadata about how it was found.
 #[derive(Debug, Clone)]
@@ -33,6 +33,10 @@ pub struct DiscoveredUrl {
 
     /// Snippet or description text.
     pub snippet: Option<String>,
+
+    /// Platform metadata (agency, request status, dates) to store on the
+    /// document once it is downloaded.
+    pub document_metadata: Option<serde_json::Value>,
 }
 
 impl DiscoveredUrl {
@@ -47,6 +51,7 @@ impl DiscoveredUrl {
             confidence: 0.5,
             title: None,
             snippet: None,
+            document_metadata: None,
         }
     }
 
@@ -76,6 +81,27 @@ impl DiscoveredUrl {
         self
     }
 
+    /// Set the metadata to store on the downloaded document.
+    pub fn with_document_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.document_metadata = Some(metadata);
+        self
+    }
+
+    /// Convert to a crawl queue entry for `source_id`.
+    pub fn to_crawl_url(&self, source_id: &str) -> CrawlUrl {
+        let crawl_url = CrawlUrl::new(
+            self.url.clone(),
+            source_id.to_string(),
+            self.source_method,
+            self.query_used.clone(),
+            0,
+        );
+        match &self.document_metadata {
+            Some(metadata) => crawl_url.with_document_metadata(metadata.clone()),
+            None => crawl_url,
+        }
+    }
+
     /// Check if this URL looks like a listing page based on patterns.
     pub fn detect_listing_page(&mut self) {
         if is_listing_url(&self.url) {
@@ -167,6 +193,32 @@ mod tests {
         assert_eq!(url.confidence, 0.9);
     }
 
+    #[test]
+    fn crawl_url_carries_document_metadata() {
+        let url = DiscoveredUrl::new(
+            "https://example.gov/doc.pdf".to_string(),
+            DiscoveryMethod::ApiResult,
+            "muckrock".to_string(),
+        )
+        .with_query("body cameras".to_string())
+        .with_document_metadata(serde_json::json!({"agency": "Police Department"}));
+
+        let crawl_url = url.to_crawl_url("muckrock");
+        assert_eq!(crawl_url.source_id, "muckrock");
+        assert_eq!(crawl_url.parent_url.as_deref(), Some("body cameras"));
+        assert_eq!(crawl_url.document_metadata()["agency"], "Police Department");
+
+        let plain = DiscoveredUrl::new(
+            "https://example.gov/other.pdf".to_string(),
+            DiscoveryMethod::SearchEngine,
+            "duckduckgo".to_string(),
+        );
+        assert_eq!(
+            plain.to_crawl_url("example").document_metadata(),
+            serde_json::json!({})
+        );
+    }
+
     #[test]
     fn detect_listing_page_from_url() {
         let mut url = DiscoveredUrl::new(
@@ -0,0 +1,424 @@
+//! DocumentCloud discovery source.
+//!
+//! Lists documents through DocumentCloud's public API, either by full-text
+//! search or by project, and maps each one to its PDF and metadata. Only
+//! public documents that finished processing are returned.
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::debug;
+
+use super::{create_discovery_client, fetch_api_text};
+use crate::discovery::{DiscoveredUrl, DiscoveryError, DiscoverySource, DiscoverySourceConfig};
+use crate::HttpClient;
+use foia::models::DiscoveryMethod;
+use foia::storage::DocumentInput;
+
+/// Base URL of the DocumentCloud API.
+pub const DOCUMENTCLOUD_API_URL: &str = "https://api.www.documentcloud.org/api";
+
+/// Asset host used when a document doesn't report its own.
+const DEFAULT_ASSET_URL: &str = "https://s3.documentcloud.org/";
+
+/// Results requested per API page.
+const PER_PAGE: usize = 100;
+
+/// A document as returned by the DocumentCloud API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DocumentCloudDocument {
+    pub id: u64,
+    pub title: String,
+    pub slug: String,
+    #[serde(default)]
+    pub access: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub asset_url: Option<String>,
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub page_count: Option<u32>,
+    #[serde(default)]
+    pub published_url: Option<String>,
+    #[serde(default)]
+    pub related_article: Option<String>,
+    /// User-defined key/value data; each key maps to a list of strings.
+    #[serde(default)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+}
+
+impl DocumentCloudDocument {
+    /// Whether the document is public and its files are available.
+    pub fn is_available(&self) -> bool {
+        self.access.as_deref().unwrap_or("public") == "public"
+            && self.status.as_deref().unwrap_or("success") == "success"
+    }
+
+    /// URL of the original PDF.
+    pub fn pdf_url(&self) -> String {
+        let asset_url = self
+            .asset_url
+            .as_deref()
+            .filter(|u| !u.is_empty())
+            .unwrap_or(DEFAULT_ASSET_URL);
+        format!(
+            "{}/documents/{}/{}.pdf",
+            asset_url.trim_end_matches('/'),
+            self.id,
+            self.slug
+        )
+    }
+
+    /// Agency named in the document's data, falling back to its source.
+    pub fn agency(&self) -> Option<&str> {
+        self.data
+            .get("agency")
+            .and_then(|v| v.as_array())
+            .and_then(|values| values.first())
+            .and_then(|v| v.as_str())
+            .or(self.source.as_deref())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Document metadata in the shape stored on imported documents.
+    pub fn metadata(&self) -> serde_json::Value {
+        json!({
+            "platform": "documentcloud",
+            "documentcloud_id": self.id,
+            "canonical_url": non_empty(&self.canonical_url),
+            "agency": self.agency(),
+            "source": non_empty(&self.source),
+            "description": non_empty(&self.description),
+            "language": non_empty(&self.language),
+            "page_count": self.page_count,
+            "published_url": non_empty(&self.published_url),
+            "related_article": non_empty(&self.related_article),
+            "data": self.data,
+        })
+    }
+
+    /// Build the input for saving the PDF as a document.
+    pub fn to_input(&self) -> DocumentInput {
+        DocumentInput {
+            url: self.pdf_url(),
+            title: self.title.clone(),
+            mime_type: "application/pdf".to_string(),
+            metadata: self.metadata(),
+            original_filename: Some(format!("{}.pdf", self.slug)),
+            server_date: self
+                .created_at
+                .as_deref()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|d| d.with_timezone(&Utc)),
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|s| !s.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    #[serde(default)]
+    next: Option<String>,
+    results: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectEntry {
+    document: DocumentCloudDocument,
+}
+
+/// Parse a page of search results, returning the documents and next page URL.
+pub fn parse_search_page(
+    body: &str,
+) -> Result<(Vec<DocumentCloudDocument>, Option<String>), DiscoveryError> {
+    let page: Page<DocumentCloudDocument> = serde_json::from_str(body)
+        .map_err(|e| DiscoveryError::Parse(format!("DocumentCloud search: {}", e)))?;
+    Ok((page.results, page.next))
+}
+
+/// Parse a page of project documents (requested with `expand=document`).
+pub fn parse_project_page(
+    body: &str,
+) -> Result<(Vec<DocumentCloudDocument>, Option<String>), DiscoveryError> {
+    let page: Page<ProjectEntry> = serde_json::from_str(body)
+        .map_err(|e| DiscoveryError::Parse(format!("DocumentCloud project: {}", e)))?;
+    let docs = page.results.into_iter().map(|e| e.document).collect();
+    Ok((docs, page.next))
+}
+
+/// Extract a project ID from an ID, a `slug-123` string or a project URL.
+pub fn parse_project_id(project: &str) -> Option<u64> {
+    let last = project.trim_end_matches('/').rsplit('/').next()?;
+    last.rsplit('-').next()?.parse().ok()
+}
+
+/// Client for DocumentCloud's search and project endpoints.
+pub struct DocumentCloudClient {
+    client: HttpClient,
+    api_url: String,
+}
+
+impl DocumentCloudClient {
+    /// Create a client for the public DocumentCloud API.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            api_url: DOCUMENTCLOUD_API_URL.to_string(),
+        }
+    }
+
+    /// Use a different API base URL.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// URL of the first page of search results for `query`.
+    pub fn search_url(&self, query: &str) -> String {
+        format!(
+            "{}/documents/search/?q={}&per_page={}",
+            self.api_url,
+            urlencoding::encode(query),
+            PER_PAGE
+        )
+    }
+
+    /// URL of the first page of documents in a project.
+    pub fn project_url(&self, project_id: u64) -> String {
+        format!(
+            "{}/projects/{}/documents/?expand=document&per_page={}",
+            self.api_url, project_id, PER_PAGE
+        )
+    }
+
+    /// Search for documents (0 = no limit).
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<DocumentCloudDocument>, DiscoveryError> {
+        self.collect(self.search_url(query), limit, parse_search_page)
+            .await
+    }
+
+    /// List the documents in a project (0 = no limit).
+    pub async fn project(
+        &self,
+        project_id: u64,
+        limit: usize,
+    ) -> Result<Vec<DocumentCloudDocument>, DiscoveryError> {
+        self.collect(self.project_url(project_id), limit, parse_project_page)
+            .await
+    }
+
+    /// Follow `next` links until the results or `limit` run out.
+    async fn collect(
+        &self,
+        first_url: String,
+        limit: usize,
+        parse: fn(&str) -> Result<(Vec<DocumentCloudDocument>, Option<String>), DiscoveryError>,
+    ) -> Result<Vec<DocumentCloudDocument>, DiscoveryError> {
+        let mut docs = Vec::new();
+        let mut next = Some(first_url);
+
+        while let Some(url) = next.take() {
+            debug!("Fetching DocumentCloud page: {}", url);
+            let body = fetch_api_text(&self.client, "DocumentCloud", &url).await?;
+            let (page, next_url) = parse(&body)?;
+            docs.extend(page.into_iter().filter(|d| d.is_available()));
+
+            if limit > 0 && docs.len() >= limit {
+                docs.truncate(limit);
+                break;
+            }
+            next = next_url;
+        }
+
+        Ok(docs)
+    }
+}
+
+/// Discovery source backed by DocumentCloud search.
+///
+/// Each search term is run as a query. A `project` custom parameter lists
+/// that project's documents as well.
+#[derive(Default)]
+pub struct DocumentCloudSource {}
+
+impl DocumentCloudSource {
+    /// Create a new DocumentCloud source.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl DiscoverySource for DocumentCloudSource {
+    fn name(&self) -> &str {
+        "documentcloud"
+    }
+
+    fn method(&self) -> DiscoveryMethod {
+        DiscoveryMethod::ApiResult
+    }
+
+    async fn discover(
+        &self,
+        _target_domain: &str,
+        search_terms: &[String],
+        config: &DiscoverySourceConfig,
+    ) -> Result<Vec<DiscoveredUrl>, DiscoveryError> {
+        let client = DocumentCloudClient::new(create_discovery_client(
+            "documentcloud",
+            config,
+            None,
+            None,
+        )?);
+
+        let mut found: Vec<(DocumentCloudDocument, Option<String>)> = Vec::new();
+        if let Some(project) = config.custom_params.get("project") {
+            let project_id = match project {
+                serde_json::Value::Number(n) => n.as_u64(),
+                serde_json::Value::String(s) => parse_project_id(s),
+                _ => None,
+            }
+            .ok_or_else(|| DiscoveryError::Config(format!("Invalid project: {}", project)))?;
+            for doc in client.project(project_id, config.max_results).await? {
+                found.push((doc, None));
+            }
+        }
+        for term in search_terms {
+            for doc in client.search(term, config.max_results).await? {
+                found.push((doc, Some(term.clone())));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut discovered: Vec<DiscoveredUrl> = found
+            .into_iter()
+            .filter(|(doc, _)| seen.insert(doc.id))
+            .map(|(doc, query)| {
+                let mut url = DiscoveredUrl::new(
+                    doc.pdf_url(),
+                    DiscoveryMethod::ApiResult,
+                    "documentcloud".to_string(),
+                )
+                .with_metadata(Some(doc.title.clone()), doc.description.clone())
+                .with_document_metadata(doc.metadata());
+                if let Some(query) = query {
+                    url = url.with_query(query);
+                }
+                url
+            })
+            .collect();
+        if config.max_results > 0 {
+            discovered.truncate(config.max_results);
+        }
+
+        Ok(discovered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEARCH: &str = include_str!("../../../tests/fixtures/documentcloud_search.json");
+    const PROJECT: &str = include_str!("../../../tests/fixtures/documentcloud_project.json");
+
+    #[test]
+    fn parses_search_page() {
+        let (docs, next) = parse_search_page(SEARCH).unwrap();
+        assert_eq!(docs.len(), 2);
+        assert!(next.unwrap().contains("page=2"));
+
+        let doc = &docs[0];
+        assert_eq!(
+            doc.pdf_url(),
+            "https://s3.documentcloud.org/documents/20059100/use-of-force-policy-2019.pdf"
+        );
+        assert_eq!(doc.agency(), Some("Springfield Police Department"));
+        // Empty source and no data leave the agency unset.
+        assert_eq!(docs[1].agency(), None);
+    }
+
+    #[test]
+    fn maps_document_to_input() {
+        let (docs, _) = parse_search_page(SEARCH).unwrap();
+        let input = docs[0].to_input();
+
+        assert_eq!(input.title, "Use of Force Policy 2019");
+        assert_eq!(input.mime_type, "application/pdf");
+        assert_eq!(
+            input.original_filename.as_deref(),
+            Some("use-of-force-policy-2019.pdf")
+        );
+        assert_eq!(
+            input.server_date.unwrap().to_rfc3339(),
+            "2021-03-02T18:41:07.132948+00:00"
+        );
+        assert_eq!(input.metadata["platform"], "documentcloud");
+        assert_eq!(input.metadata["documentcloud_id"], 20059100);
+        assert_eq!(input.metadata["page_count"], 14);
+        assert_eq!(input.metadata["data"]["year"][0], "2019");
+        assert!(input.metadata["related_article"].is_null());
+    }
+
+    #[test]
+    fn parses_project_page_and_skips_unfinished() {
+        let (docs, next) = parse_project_page(PROJECT).unwrap();
+        assert_eq!(docs.len(), 2);
+        assert!(next.is_none());
+        assert!(docs[0].is_available());
+        assert!(!docs[1].is_available());
+        assert_eq!(docs[0].agency(), Some("City of Springfield"));
+    }
+
+    #[test]
+    fn parses_project_ids() {
+        assert_eq!(parse_project_id("12345"), Some(12345));
+        assert_eq!(parse_project_id("police-records-12345"), Some(12345));
+        assert_eq!(
+            parse_project_id("https://www.documentcloud.org/projects/police-records-12345/"),
+            Some(12345)
+        );
+        assert_eq!(parse_project_id("police-records"), None);
+    }
+
+    #[test]
+    fn builds_api_urls() {
+        let client = HttpClient::builder(
+            "test",
+            std::time::Duration::from_secs(1),
+            std::time::Duration::ZERO,
+        )
+        .build()
+        .unwrap();
+        let dc = DocumentCloudClient::new(client).with_api_url("http://localhost:8000/api/");
+
+        assert_eq!(
+            dc.search_url("use of force"),
+            "http://localhost:8000/api/documents/search/?q=use%20of%20force&per_page=100"
+        );
+        assert_eq!(
+            dc.project_url(7),
+            "http://localhost:8000/api/projects/7/documents/?expand=document&per_page=100"
+        );
+    }
+}
@@ -3,11 +3,15 @@
 //! Each source provides a way to discover URLs for a target domain.
 
 pub mod common_paths;
+pub mod documentcloud;
+pub mod muckrock;
 pub mod search;
 pub mod sitemap;
 pub mod wayback;
 
 pub use common_paths::CommonPathsSource;
+pub use documentcloud::DocumentCloudSource;
+pub use muckrock::MuckRockSource;
 pub use sitemap::SitemapSource;
 pub use wayback::WaybackSource;
 
@@ -35,6 +39,11 @@ impl SourceRegistry {
             "common_paths".to_string(),
             Arc::new(CommonPathsSource::new()),
         );
+        sources.insert(
+            "documentcloud".to_string(),
+            Arc::new(DocumentCloudSource::new()),
+        );
+        sources.insert("muckrock".to_string(), Arc::new(MuckRockSource::new()));
 
         Self { sources }
     }
@@ -82,12 +91,33 @@ pub fn create_discovery_client(
     .map_err(|e| DiscoveryError::Config(format!("Failed to create HTTP client: {}", e)))
 }
 
+/// Fetch an API response body, mapping HTTP failures to discovery errors.
+pub(crate) async fn fetch_api_text(
+    client: &HttpClient,
+    service_name: &str,
+    url: &str,
+) -> Result<String, DiscoveryError> {
+    let response = client.get(url, None, None).await?;
+    if response.is_rate_limited() {
+        return Err(DiscoveryError::RateLimited(service_name.to_string()));
+    }
+    if !response.status.is_success() {
+        return Err(DiscoveryError::Unavailable(format!(
+            "{} returned {}",
+            service_name, response.status
+        )));
+    }
+    Ok(response.text().await?)
+}
+
 /// Helper to create sources from config.
 pub fn create_source(name: &str) -> Result<Arc<dyn DiscoverySource>, DiscoveryError> {
     match name.to_lowercase().as_str() {
         "sitemap" => Ok(Arc::new(SitemapSource::new())),
         "wayback" => Ok(Arc::new(WaybackSource::new())),
         "common_paths" | "paths" => Ok(Arc::new(CommonPathsSource::new())),
+        "documentcloud" => Ok(Arc::new(DocumentCloudSource::new())),
+        "muckrock" => Ok(Arc::new(MuckRockSource::new())),
         _ => Err(DiscoveryError::Config(format!("Unknown source: {}", name))),
     }
 }
@@ -0,0 +1,583 @@
+//! MuckRock discovery source.
+//!
+//! Lists FOIA requests through MuckRock's public API and maps the files
+//! attached to their communications into documents. Each file carries the
+//! request's agency, status and filing date.
+
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::{create_discovery_client, fetch_api_text};
+use crate::discovery::{DiscoveredUrl, DiscoveryError, DiscoverySource, DiscoverySourceConfig};
+use crate::HttpClient;
+use foia::models::DiscoveryMethod;
+use foia::storage::DocumentInput;
+
+/// Base URL of the MuckRock site.
+pub const MUCKROCK_URL: &str = "https://www.muckrock.com";
+
+/// Results requested per API page.
+const PAGE_SIZE: usize = 50;
+
+/// A FOIA request as returned by the MuckRock API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MuckRockRequest {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub slug: Option<String>,
+    pub status: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub agency: Option<u64>,
+    #[serde(default)]
+    pub jurisdiction: Option<u64>,
+    #[serde(default)]
+    pub datetime_submitted: Option<String>,
+    #[serde(default)]
+    pub date_due: Option<String>,
+    #[serde(default)]
+    pub datetime_done: Option<String>,
+    #[serde(default)]
+    pub tracking_id: Option<String>,
+    #[serde(default)]
+    pub absolute_url: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub communications: Vec<MuckRockCommunication>,
+    /// Agency name, resolved separately from the agency endpoint.
+    #[serde(skip)]
+    pub agency_name: Option<String>,
+}
+
+/// A message sent or received as part of a request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MuckRockCommunication {
+    pub id: u64,
+    #[serde(default)]
+    pub subject: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    /// True when the message came from the agency.
+    #[serde(default)]
+    pub response: bool,
+    #[serde(default)]
+    pub files: Vec<MuckRockFile>,
+}
+
+/// A file attached to a communication.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MuckRockFile {
+    pub id: u64,
+    /// Download URL.
+    pub ffile: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// DocumentCloud ID when the file was also uploaded there.
+    #[serde(default)]
+    pub doc_id: Option<String>,
+    #[serde(default)]
+    pub pages: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Agency {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    #[serde(default)]
+    next: Option<String>,
+    results: Vec<T>,
+}
+
+/// Human-readable label for a request status code.
+pub fn status_label(status: &str) -> &str {
+    match status {
+        "started" => "Draft",
+        "submitted" => "Processing",
+        "ack" => "Awaiting Acknowledgement",
+        "processed" => "Awaiting Response",
+        "appealing" => "Awaiting Appeal",
+        "fix" => "Fix Required",
+        "payment" => "Payment Required",
+        "lawsuit" => "In Litigation",
+        "rejected" => "Rejected",
+        "no_docs" => "No Responsive Documents",
+        "done" => "Completed",
+        "partial" => "Partially Completed",
+        "abandoned" => "Withdrawn",
+        other => other,
+    }
+}
+
+/// Parse a MuckRock timestamp or date.
+///
+/// The API mixes RFC 3339 timestamps, naive timestamps (UTC) and bare dates.
+pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Extract a request ID from an ID or a request page URL.
+///
+/// Request pages look like `/foi/{jurisdiction}-{id}/{slug}-{id}/`.
+pub fn parse_request_id(request: &str) -> Option<u64> {
+    let last = request.trim_end_matches('/').rsplit('/').next()?;
+    last.rsplit('-').next()?.parse().ok()
+}
+
+impl MuckRockRequest {
+    /// URL of the request page.
+    pub fn request_url(&self) -> String {
+        match self.absolute_url.as_deref() {
+            Some(path) if path.starts_with("http") => path.to_string(),
+            Some(path) => format!("{}{}", MUCKROCK_URL, path),
+            None => format!("{}/api_v1/foia/{}/", MUCKROCK_URL, self.id),
+        }
+    }
+
+    /// Request-level metadata shared by every file.
+    pub fn metadata(&self) -> serde_json::Value {
+        json!({
+            "platform": "muckrock",
+            "muckrock_request_id": self.id,
+            "request_title": self.title,
+            "request_url": self.request_url(),
+            "agency": self.agency_name,
+            "agency_id": self.agency,
+            "jurisdiction_id": self.jurisdiction,
+            "request_status": self.status,
+            "request_status_label": status_label(&self.status),
+            "filing_date": self.datetime_submitted,
+            "date_due": self.date_due,
+            "date_done": self.datetime_done,
+            "tracking_id": self.tracking_id.as_deref().filter(|s| !s.is_empty()),
+            "requester": self.username,
+            "request_tags": self.tags,
+        })
+    }
+
+    /// All attached files, as inputs for saving documents.
+    pub fn documents(&self) -> Vec<DocumentInput> {
+        let request_meta = self.metadata();
+        let mut seen = HashSet::new();
+        let mut inputs = Vec::new();
+
+        for comm in &self.communications {
+            for file in &comm.files {
+                if file.ffile.is_empty() || !seen.insert(file.ffile.as_str()) {
+                    continue;
+                }
+
+                let mut metadata = request_meta.clone();
+                if let Some(obj) = metadata.as_object_mut() {
+                    obj.insert("communication_id".into(), json!(comm.id));
+                    obj.insert("communication_subject".into(), json!(comm.subject));
+                    obj.insert("communication_date".into(), json!(comm.date));
+                    obj.insert("from_agency".into(), json!(comm.response));
+                    obj.insert("file_id".into(), json!(file.id));
+                    obj.insert(
+                        "description".into(),
+                        json!(file.description.as_deref().filter(|s| !s.is_empty())),
+                    );
+                    obj.insert("page_count".into(), json!(file.pages));
+                    obj.insert(
+                        "documentcloud_id".into(),
+                        json!(file.doc_id.as_deref().filter(|s| !s.is_empty())),
+                    );
+                }
+
+                let original_filename = file
+                    .ffile
+                    .split(['?', '#'])
+                    .next()
+                    .and_then(|path| path.rsplit('/').next())
+                    .filter(|name| !name.is_empty())
+                    .map(|name| urlencoding::decode(name).map_or(name.to_string(), String::from));
+                let title = file
+                    .title
+                    .clone()
+                    .filter(|t| !t.is_empty())
+                    .or_else(|| original_filename.clone())
+                    .unwrap_or_else(|| self.title.clone());
+
+                inputs.push(DocumentInput {
+                    url: file.ffile.clone(),
+                    title,
+                    mime_type: foia::utils::guess_mime_from_url(&file.ffile).to_string(),
+                    metadata,
+                    original_filename,
+                    server_date: file
+                        .date
+                        .as_deref()
+                        .or(comm.date.as_deref())
+                        .and_then(parse_date),
+                });
+            }
+        }
+
+        inputs
+    }
+}
+
+/// Parse a page of requests, returning the requests and next page URL.
+pub fn parse_request_page(
+    body: &str,
+) -> Result<(Vec<MuckRockRequest>, Option<String>), DiscoveryError> {
+    let page: Page<MuckRockRequest> = serde_json::from_str(body)
+        .map_err(|e| DiscoveryError::Parse(format!("MuckRock requests: {}", e)))?;
+    Ok((page.results, page.next))
+}
+
+/// Parse a single request.
+pub fn parse_request(body: &str) -> Result<MuckRockRequest, DiscoveryError> {
+    serde_json::from_str(body)
+        .map_err(|e| DiscoveryError::Parse(format!("MuckRock request: {}", e)))
+}
+
+/// Parse an agency, returning its name.
+pub fn parse_agency_name(body: &str) -> Result<String, DiscoveryError> {
+    let agency: Agency = serde_json::from_str(body)
+        .map_err(|e| DiscoveryError::Parse(format!("MuckRock agency: {}", e)))?;
+    Ok(agency.name)
+}
+
+/// Filters for listing requests.
+#[derive(Debug, Clone, Default)]
+pub struct RequestFilter {
+    /// Title search.
+    pub query: Option<String>,
+    /// Status code (e.g. `done`).
+    pub status: Option<String>,
+    /// Agency ID.
+    pub agency: Option<u64>,
+    /// Jurisdiction ID.
+    pub jurisdiction: Option<u64>,
+}
+
+/// Client for MuckRock's request and agency endpoints.
+pub struct MuckRockClient {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl MuckRockClient {
+    /// Create a client for the public MuckRock API.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            base_url: MUCKROCK_URL.to_string(),
+        }
+    }
+
+    /// Use a different site base URL.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// URL of the first page of requests matching `filter`.
+    pub fn requests_url(&self, filter: &RequestFilter) -> String {
+        let mut url = format!(
+            "{}/api_v1/foia/?format=json&page_size={}",
+            self.base_url, PAGE_SIZE
+        );
+        if let Some(ref query) = filter.query {
+            url.push_str(&format!("&title={}", urlencoding::encode(query)));
+        }
+        if let Some(ref status) = filter.status {
+            url.push_str(&format!("&status={}", urlencoding::encode(status)));
+        }
+        if let Some(agency) = filter.agency {
+            url.push_str(&format!("&agency={}", agency));
+        }
+        if let Some(jurisdiction) = filter.jurisdiction {
+            url.push_str(&format!("&jurisdiction={}", jurisdiction));
+        }
+        url
+    }
+
+    /// URL of a single request.
+    pub fn request_url(&self, request_id: u64) -> String {
+        format!("{}/api_v1/foia/{}/?format=json", self.base_url, request_id)
+    }
+
+    /// URL of an agency.
+    pub fn agency_url(&self, agency_id: u64) -> String {
+        format!("{}/api_v1/agency/{}/?format=json", self.base_url, agency_id)
+    }
+
+    /// Fetch a single request with its agency name resolved.
+    pub async fn request(&self, request_id: u64) -> Result<MuckRockRequest, DiscoveryError> {
+        let body = fetch_api_text(&self.client, "MuckRock", &self.request_url(request_id)).await?;
+        let mut requests = vec![parse_request(&body)?];
+        self.resolve_agencies(&mut requests).await;
+        Ok(requests.remove(0))
+    }
+
+    /// List requests matching `filter` (0 = no limit), with agency names resolved.
+    pub async fn requests(
+        &self,
+        filter: &RequestFilter,
+        limit: usize,
+    ) -> Result<Vec<MuckRockRequest>, DiscoveryError> {
+        let mut requests = Vec::new();
+        let mut next = Some(self.requests_url(filter));
+
+        while let Some(url) = next.take() {
+            debug!("Fetching MuckRock page: {}", url);
+            let body = fetch_api_text(&self.client, "MuckRock", &url).await?;
+            let (page, next_url) = parse_request_page(&body)?;
+            requests.extend(page);
+
+            if limit > 0 && requests.len() >= limit {
+                requests.truncate(limit);
+                break;
+            }
+            next = next_url;
+        }
+
+        self.resolve_agencies(&mut requests).await;
+        Ok(requests)
+    }
+
+    /// Look up agency names, once per agency.
+    ///
+    /// Failures are logged and leave the name unset.
+    async fn resolve_agencies(&self, requests: &mut [MuckRockRequest]) {
+        let mut names: HashMap<u64, Option<String>> = HashMap::new();
+        for request in requests.iter_mut() {
+            let Some(agency_id) = request.agency else {
+                continue;
+            };
+            if !names.contains_key(&agency_id) {
+                let url = self.agency_url(agency_id);
+                let name = match fetch_api_text(&self.client, "MuckRock", &url).await {
+                    Ok(body) => parse_agency_name(&body).ok(),
+                    Err(e) => {
+                        warn!("Failed to look up MuckRock agency {}: {}", agency_id, e);
+                        None
+                    }
+                };
+                names.insert(agency_id, name);
+            }
+            request.agency_name = names[&agency_id].clone();
+        }
+    }
+}
+
+/// Discovery source backed by MuckRock requests.
+///
+/// Each search term is matched against request titles; `status`, `agency`
+/// and `jurisdiction` custom parameters narrow the listing. Returns the
+/// files attached to matching requests.
+#[derive(Default)]
+pub struct MuckRockSource {}
+
+impl MuckRockSource {
+    /// Create a new MuckRock source.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl DiscoverySource for MuckRockSource {
+    fn name(&self) -> &str {
+        "muckrock"
+    }
+
+    fn method(&self) -> DiscoveryMethod {
+        DiscoveryMethod::ApiResult
+    }
+
+    async fn discover(
+        &self,
+        _target_domain: &str,
+        search_terms: &[String],
+        config: &DiscoverySourceConfig,
+    ) -> Result<Vec<DiscoveredUrl>, DiscoveryError> {
+        let client = MuckRockClient::new(create_discovery_client("muckrock", config, None, None)?);
+        let param_str = |key: &str| {
+            config.custom_params.get(key).and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+        let base = RequestFilter {
+            query: None,
+            status: param_str("status"),
+            agency: param_str("agency").and_then(|s| s.parse().ok()),
+            jurisdiction: param_str("jurisdiction").and_then(|s| s.parse().ok()),
+        };
+
+        let queries: Vec<Option<String>> = if search_terms.is_empty() {
+            vec![None]
+        } else {
+            search_terms.iter().cloned().map(Some).collect()
+        };
+
+        let mut seen = HashSet::new();
+        let mut discovered = Vec::new();
+        for query in queries {
+            let filter = RequestFilter {
+                query: query.clone(),
+                ..base.clone()
+            };
+            for request in client.requests(&filter, config.max_results).await? {
+                for input in request.documents() {
+                    if !seen.insert(input.url.clone()) {
+                        continue;
+                    }
+                    let mut url = DiscoveredUrl::new(
+                        input.url,
+                        DiscoveryMethod::ApiResult,
+                        "muckrock".to_string(),
+                    )
+                    .with_metadata(Some(input.title), Some(request.title.clone()))
+                    .with_document_metadata(input.metadata);
+                    if let Some(ref query) = query {
+                        url = url.with_query(query.clone());
+                    }
+                    discovered.push(url);
+                }
+            }
+        }
+        if config.max_results > 0 {
+            discovered.truncate(config.max_results);
+        }
+
+        Ok(discovered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUESTS: &str = include_str!("../../../tests/fixtures/muckrock_foia.json");
+    const AGENCY: &str = include_str!("../../../tests/fixtures/muckrock_agency.json");
+
+    fn fixture_request() -> MuckRockRequest {
+        let (mut requests, next) = parse_request_page(REQUESTS).unwrap();
+        assert!(next.is_none());
+        let mut request = requests.remove(0);
+        request.agency_name = Some(parse_agency_name(AGENCY).unwrap());
+        request
+    }
+
+    #[test]
+    fn maps_request_metadata() {
+        let request = fixture_request();
+        assert_eq!(
+            request.request_url(),
+            "https://www.muckrock.com/foi/springfield-18/body-camera-retention-policy-71823/"
+        );
+
+        let meta = request.metadata();
+        assert_eq!(meta["agency"], "Springfield Police Department");
+        assert_eq!(meta["agency_id"], 4321);
+        assert_eq!(meta["request_status"], "done");
+        assert_eq!(meta["request_status_label"], "Completed");
+        assert_eq!(meta["filing_date"], "2019-03-11T14:47:52.563894");
+        assert_eq!(meta["tracking_id"], "W012345-031119");
+    }
+
+    #[test]
+    fn maps_attached_files_to_documents() {
+        let docs = fixture_request().documents();
+        assert_eq!(docs.len(), 2);
+
+        let schedule = &docs[0];
+        assert_eq!(
+            schedule.url,
+            "https://cdn.muckrock.com/foia_files/2019/05/02/Retention_Schedule.pdf"
+        );
+        assert_eq!(schedule.title, "Retention Schedule");
+        assert_eq!(schedule.mime_type, "application/pdf");
+        assert_eq!(
+            schedule.original_filename.as_deref(),
+            Some("Retention_Schedule.pdf")
+        );
+        assert_eq!(
+            schedule.server_date.unwrap().to_rfc3339(),
+            "2019-05-02T10:15:00+00:00"
+        );
+        assert_eq!(schedule.metadata["agency"], "Springfield Police Department");
+        assert_eq!(schedule.metadata["from_agency"], true);
+        assert_eq!(schedule.metadata["communication_id"], 652210);
+        assert_eq!(
+            schedule.metadata["documentcloud_id"],
+            "6012345-Retention-Schedule"
+        );
+
+        // Untitled files fall back to their filename.
+        assert_eq!(docs[1].title, "cover_letter.pdf");
+        assert!(docs[1].metadata["documentcloud_id"].is_null());
+    }
+
+    #[test]
+    fn parses_dates_and_ids() {
+        assert!(parse_date("2019-03-11T14:47:52.563894").is_some());
+        assert!(parse_date("2019-03-11T14:47:52+02:00").is_some());
+        assert_eq!(
+            parse_date("2019-04-09").unwrap().to_rfc3339(),
+            "2019-04-09T00:00:00+00:00"
+        );
+        assert!(parse_date("soon").is_none());
+
+        assert_eq!(parse_request_id("71823"), Some(71823));
+        assert_eq!(
+            parse_request_id(
+                "https://www.muckrock.com/foi/springfield-18/body-camera-retention-policy-71823/"
+            ),
+            Some(71823)
+        );
+    }
+
+    #[test]
+    fn builds_filtered_listing_url() {
+        let client = HttpClient::builder(
+            "test",
+            std::time::Duration::from_secs(1),
+            std::time::Duration::ZERO,
+        )
+        .build()
+        .unwrap();
+        let muckrock = MuckRockClient::new(client).with_base_url("http://localhost:8000/");
+        let filter = RequestFilter {
+            query: Some("body camera".to_string()),
+            status: Some("done".to_string()),
+            agency: Some(4321),
+            jurisdiction: None,
+        };
+
+        assert_eq!(
+            muckrock.requests_url(&filter),
+            "http://localhost:8000/api_v1/foia/?format=json&page_size=50\
+             &title=body%20camera&status=done&agency=4321"
+        );
+    }
+}
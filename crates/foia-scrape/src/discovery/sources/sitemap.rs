@@ -1,6 +1,9 @@
 //! Sitemap and robots.txt discovery source.
 //!
-//! Parses sitemap.xml files and robots.txt to discover URLs.
+//! Parses sitemap.xml files and robots.txt to discover URLs. Sitemaps may
+//! be gzipped and may use the news and image extensions.
+
+use std::io::Read;
 
 use async_trait::async_trait;
 use tracing::{debug, warn};
@@ -8,10 +11,11 @@ use tracing::{debug, warn};
 use super::create_discovery_client;
 use crate::discovery::url_utils::{dedup_and_limit, extract_xml_locs, normalize_base_url};
 use crate::discovery::{DiscoveredUrl, DiscoveryError, DiscoverySource, DiscoverySourceConfig};
+use crate::HttpClient;
 use foia::models::DiscoveryMethod;
 
 /// Standard sitemap locations to check.
-const SITEMAP_PATHS: &[&str] = &[
+pub const SITEMAP_PATHS: &[&str] = &[
     "/sitemap.xml",
     "/sitemap_index.xml",
     "/sitemap/sitemap.xml",
@@ -19,6 +23,176 @@ const SITEMAP_PATHS: &[&str] = &[
     "/sitemap/index.xml",
 ];
 
+/// Largest uncompressed sitemap the protocol allows (50 MB).
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
+/// A `<url>` or `<sitemap>` entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SitemapEntry {
+    pub loc: String,
+    /// `<lastmod>`, or the news publication date when that is missing.
+    pub lastmod: Option<String>,
+    /// `<news:title>` from the news extension.
+    pub title: Option<String>,
+    /// `<image:loc>` URLs from the image extension.
+    pub images: Vec<String>,
+}
+
+/// A parsed sitemap file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// `<sitemapindex>`: each entry is a child sitemap.
+    Index(Vec<SitemapEntry>),
+    /// `<urlset>`: each entry is a page or document.
+    UrlSet(Vec<SitemapEntry>),
+}
+
+impl Sitemap {
+    /// Parse sitemap XML.
+    ///
+    /// Elements are matched by local name, since sitemaps in the wild mix
+    /// namespace prefixes. Malformed XML falls back to scanning for `<loc>`
+    /// lines, without lastmod.
+    pub fn parse(xml: &str) -> Self {
+        match roxmltree::Document::parse(xml) {
+            Ok(doc) => {
+                let root = doc.root_element();
+                let entries = root
+                    .children()
+                    .filter(|n| n.is_element())
+                    .filter_map(parse_entry)
+                    .collect();
+                if root.tag_name().name() == "sitemapindex" {
+                    Sitemap::Index(entries)
+                } else {
+                    Sitemap::UrlSet(entries)
+                }
+            }
+            Err(e) => {
+                debug!("Sitemap is not well-formed XML ({}), scanning for <loc>", e);
+                let entries = extract_xml_locs(xml)
+                    .into_iter()
+                    .map(|loc| SitemapEntry {
+                        loc,
+                        ..Default::default()
+                    })
+                    .collect();
+                if xml.contains("<sitemapindex") {
+                    Sitemap::Index(entries)
+                } else {
+                    Sitemap::UrlSet(entries)
+                }
+            }
+        }
+    }
+
+    /// Entries of either kind.
+    pub fn entries(&self) -> &[SitemapEntry] {
+        match self {
+            Sitemap::Index(entries) | Sitemap::UrlSet(entries) => entries,
+        }
+    }
+}
+
+fn parse_entry(node: roxmltree::Node) -> Option<SitemapEntry> {
+    let mut entry = SitemapEntry::default();
+    let mut published = None;
+    for child in node.children().filter(|n| n.is_element()) {
+        match child.tag_name().name() {
+            "loc" => entry.loc = node_text(child).unwrap_or_default(),
+            "lastmod" => entry.lastmod = node_text(child),
+            "news" => {
+                for field in child.children().filter(|n| n.is_element()) {
+                    match field.tag_name().name() {
+                        "publication_date" => published = node_text(field),
+                        "title" => entry.title = node_text(field),
+                        _ => {}
+                    }
+                }
+            }
+            "image" => {
+                let loc = child
+                    .children()
+                    .find(|n| n.tag_name().name() == "loc")
+                    .and_then(node_text);
+                entry.images.extend(loc);
+            }
+            _ => {}
+        }
+    }
+    if entry.loc.is_empty() {
+        return None;
+    }
+    entry.lastmod = entry.lastmod.or(published);
+    Some(entry)
+}
+
+fn node_text(node: roxmltree::Node) -> Option<String> {
+    node.text()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Decode a sitemap body, gunzipping it when needed.
+///
+/// `.xml.gz` files are detected by their magic bytes, since servers label
+/// them inconsistently and reqwest only decodes `Content-Encoding: gzip`.
+/// Files that inflate past the protocol's 50 MB limit are rejected.
+pub fn decode_sitemap(body: &[u8]) -> Result<String, DiscoveryError> {
+    decode_sitemap_within(body, MAX_SITEMAP_BYTES)
+}
+
+fn decode_sitemap_within(body: &[u8], limit: u64) -> Result<String, DiscoveryError> {
+    if body.starts_with(&[0x1f, 0x8b]) {
+        let mut xml = String::new();
+        flate2::read::GzDecoder::new(body)
+            .take(limit + 1)
+            .read_to_string(&mut xml)
+            .map_err(|e| DiscoveryError::Parse(format!("Invalid gzipped sitemap: {}", e)))?;
+        if xml.len() as u64 > limit {
+            return Err(DiscoveryError::Parse(format!(
+                "Gzipped sitemap inflates past {} bytes",
+                limit
+            )));
+        }
+        Ok(xml)
+    } else {
+        Ok(String::from_utf8_lossy(body).into_owned())
+    }
+}
+
+/// Fetch and parse one sitemap file.
+pub async fn fetch_sitemap(client: &HttpClient, url: &str) -> Result<Sitemap, DiscoveryError> {
+    let response = client.get(url, None, None).await?;
+    if response.is_rate_limited() {
+        return Err(DiscoveryError::RateLimited(url.to_string()));
+    }
+    if !response.is_success() {
+        return Err(DiscoveryError::Unavailable(format!(
+            "{} returned {}",
+            url, response.status
+        )));
+    }
+    let body = response.bytes().await?;
+    Ok(Sitemap::parse(&decode_sitemap(&body)?))
+}
+
+/// Read the `Sitemap:` directives from a robots.txt body.
+pub fn robots_sitemaps(robots_txt: &str) -> Vec<String> {
+    robots_txt
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.to_lowercase().starts_with("sitemap:") {
+                Some(line[8..].trim().to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Discovery source that parses sitemaps and robots.txt.
 #[derive(Default)]
 pub struct SitemapSource {}
@@ -54,17 +228,7 @@ impl SitemapSource {
             }
         };
 
-        // Parse Sitemap: directives
-        text.lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.to_lowercase().starts_with("sitemap:") {
-                    Some(line[8..].trim().to_string())
-                } else {
-                    None
-                }
-            })
-            .collect()
+        robots_sitemaps(&text)
     }
 
     /// Fetch and parse a sitemap XML file (non-recursive).
@@ -90,42 +254,36 @@ impl SitemapSource {
 
             debug!("Fetching sitemap: {}", sitemap_url);
 
-            let text = match client.get_text(&sitemap_url).await {
-                Ok(t) => t,
-                Err(e) => {
-                    warn!("Failed to fetch sitemap {}: {}", sitemap_url, e);
-                    continue;
-                }
-            };
-
-            // Check if this is a sitemap index
-            if text.contains("<sitemapindex") {
-                // Extract sitemap URLs and add to pending
-                for loc in self.extract_locs(&text) {
-                    if !processed.contains(&loc) {
-                        pending_sitemaps.push(loc);
+            match fetch_sitemap(&client, &sitemap_url).await {
+                // Sitemap index: queue the child sitemaps
+                Ok(Sitemap::Index(entries)) => {
+                    for entry in entries {
+                        if !processed.contains(&entry.loc) {
+                            pending_sitemaps.push(entry.loc);
+                        }
                     }
                 }
-            } else {
-                // Regular sitemap - extract URLs
-                match self.extract_urls_from_sitemap(&text) {
-                    Ok(urls) => all_urls.extend(urls),
-                    Err(e) => warn!("Failed to parse sitemap {}: {}", sitemap_url, e),
+                Ok(Sitemap::UrlSet(entries)) => {
+                    debug!("Extracted {} URLs from sitemap", entries.len());
+                    for entry in entries {
+                        all_urls.push(entry.loc);
+                        all_urls.extend(entry.images);
+                    }
                 }
+                Err(e) => warn!("Failed to fetch sitemap {}: {}", sitemap_url, e),
             }
         }
 
         Ok(all_urls)
     }
 
-    /// Extract <loc> values from XML.
-    fn extract_locs(&self, xml: &str) -> Vec<String> {
-        extract_xml_locs(xml)
-    }
-
     /// Extract URLs from a sitemap XML.
     fn extract_urls_from_sitemap(&self, xml: &str) -> Result<Vec<String>, DiscoveryError> {
-        let urls = extract_xml_locs(xml);
+        let urls: Vec<String> = Sitemap::parse(xml)
+            .entries()
+            .iter()
+            .map(|entry| entry.loc.clone())
+            .collect();
         debug!("Extracted {} URLs from sitemap", urls.len());
         Ok(urls)
     }
@@ -244,6 +402,85 @@ mod tests {
         assert_eq!(urls[0], "https://example.gov/search?q=test&page=1");
     }
 
+    #[test]
+    fn parse_index_and_extensions() {
+        let index = Sitemap::parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap>
+    <loc>https://example.gov/sitemap-2024.xml.gz</loc>
+    <lastmod>2024-06-01</lastmod>
+  </sitemap>
+</sitemapindex>"#,
+        );
+        assert_eq!(
+            index,
+            Sitemap::Index(vec![SitemapEntry {
+                loc: "https://example.gov/sitemap-2024.xml.gz".to_string(),
+                lastmod: Some("2024-06-01".to_string()),
+                ..Default::default()
+            }])
+        );
+
+        let urlset = Sitemap::parse(
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:news="http://www.google.com/schemas/sitemap-news/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc>https://example.gov/releases/42</loc>
+    <news:news>
+      <news:publication_date>2024-06-03T12:00:00Z</news:publication_date>
+      <news:title>Audit report released</news:title>
+    </news:news>
+    <image:image><image:loc>https://example.gov/scans/42-1.jpg</image:loc></image:image>
+    <image:image><image:loc>https://example.gov/scans/42-2.jpg</image:loc></image:image>
+  </url>
+  <url><loc>https://example.gov/report.pdf</loc><lastmod>2024-05-30</lastmod></url>
+</urlset>"#,
+        );
+        let Sitemap::UrlSet(entries) = urlset else {
+            panic!("expected a urlset");
+        };
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].lastmod.as_deref(), Some("2024-06-03T12:00:00Z"));
+        assert_eq!(entries[0].title.as_deref(), Some("Audit report released"));
+        assert_eq!(
+            entries[0].images,
+            vec![
+                "https://example.gov/scans/42-1.jpg",
+                "https://example.gov/scans/42-2.jpg"
+            ]
+        );
+        assert_eq!(entries[1].lastmod.as_deref(), Some("2024-05-30"));
+    }
+
+    #[test]
+    fn parse_gzipped_and_malformed_sitemaps() {
+        use std::io::Write;
+
+        let xml = "<urlset><url><loc>https://example.gov/a.pdf</loc></url></urlset>";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(decode_sitemap(&gzipped).unwrap(), xml);
+        assert_eq!(decode_sitemap(xml.as_bytes()).unwrap(), xml);
+        assert_eq!(
+            decode_sitemap_within(&gzipped, xml.len() as u64).unwrap(),
+            xml
+        );
+        assert!(matches!(
+            decode_sitemap_within(&gzipped, xml.len() as u64 - 1),
+            Err(DiscoveryError::Parse(_))
+        ));
+
+        // Unclosed tags still yield their <loc> lines
+        let broken = "<urlset>\n<url><loc>https://example.gov/b.pdf</loc>\n<url>";
+        assert_eq!(
+            Sitemap::parse(broken).entries()[0].loc,
+            "https://example.gov/b.pdf"
+        );
+    }
+
     #[test]
     fn is_likely_listing_detection() {
         let source = SitemapSource::new();
@@ -167,7 +167,7 @@ pub async fn save_scraped_document_async(
     result: &ScraperResult,
     source_id: &str,
     documents_dir: &Path,
-) -> anyhow::Result<bool> {
+) -> anyhow::Result<foia::storage::SaveOutcome> {
     foia::storage::save_document_async(
         doc_repo,
         content,
@@ -6,6 +6,7 @@
 mod types;
 mod youtube_download;
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -20,7 +21,8 @@ use foia::repository::{extract_filename_parts, DieselCrawlRepository, DieselDocu
 use foia::storage::compute_storage_path_with_dedup;
 
 use types::{
-    handle_download_failure, handle_unchanged, save_or_update_document, send_failure_event,
+    handle_download_failure, handle_lease_lost, handle_tombstoned, handle_unchanged, hold_lease,
+    log_download_request, save_or_update_document, send_failure_event,
 };
 pub use types::{DownloadConfig, DownloadEvent, DownloadResult};
 use youtube_download::download_youtube_video;
@@ -63,6 +65,10 @@ impl DownloadService {
         let failed = Arc::new(AtomicUsize::new(0));
 
         let mut handles = Vec::with_capacity(workers);
+        let budgets = Arc::new(self.config.budgets.clone());
+        let warc_captures = Arc::new(self.config.warc_captures.clone());
+        let portal_sessions = Arc::new(self.config.portal_sessions.clone());
+        let started_at = chrono::Utc::now();
 
         for worker_id in 0..workers {
             let crawl_repo = self.crawl_repo.clone();
@@ -74,6 +80,10 @@ impl DownloadService {
             let via = self.config.via.clone();
             let via_mode = self.config.via_mode;
             let source_id = source_id.map(|s| s.to_string());
+            let budgets = budgets.clone();
+            let lease = self.config.lease.clone();
+            let warc_captures = warc_captures.clone();
+            let portal_sessions = portal_sessions.clone();
             let downloaded = downloaded.clone();
             let deduplicated = deduplicated.clone();
             let skipped = skipped.clone();
@@ -99,6 +109,9 @@ impl DownloadService {
                     client
                 };
 
+                // Sources with a portal session or WARC capture get their own client
+                let mut source_clients: HashMap<String, HttpClient> = HashMap::new();
+
                 loop {
                     // Check limit
                     if let Some(max) = limit {
@@ -107,12 +120,28 @@ impl DownloadService {
                         }
                     }
 
-                    // Claim a URL to process
-                    let crawl_url = match crawl_repo.claim_pending_url(source_id.as_deref()).await {
+                    // Claim a URL to process, skipping sources over budget
+                    let crawl_url = match crawl_repo
+                        .claim_pending_url_within(
+                            source_id.as_deref(),
+                            &budgets,
+                            started_at,
+                            lease.as_ref(),
+                        )
+                        .await
+                    {
                         Ok(Some(url)) => url,
                         Ok(None) => {
                             tokio::time::sleep(Duration::from_millis(100)).await;
-                            match crawl_repo.claim_pending_url(source_id.as_deref()).await {
+                            match crawl_repo
+                                .claim_pending_url_within(
+                                    source_id.as_deref(),
+                                    &budgets,
+                                    started_at,
+                                    lease.as_ref(),
+                                )
+                                .await
+                            {
                                 Ok(Some(url)) => url,
                                 _ => break,
                             }
@@ -126,6 +155,13 @@ impl DownloadService {
                     let url = crawl_url.url.clone();
                     let filename = extract_title_from_url(&url);
 
+                    // Never re-acquire a deleted document
+                    if let Ok(Some(_)) = doc_repo.find_tombstone(Some(&url), None).await {
+                        handle_tombstoned(&crawl_url, &crawl_repo, &skipped, &event_tx, worker_id)
+                            .await;
+                        continue;
+                    }
+
                     let _ = event_tx
                         .send(DownloadEvent::Started {
                             worker_id,
@@ -147,6 +183,7 @@ impl DownloadService {
                             &event_tx,
                             &downloaded,
                             &failed,
+                            &skipped,
                             proxy_url.as_deref(),
                         )
                         .await;
@@ -157,15 +194,45 @@ impl DownloadService {
                         // If YouTube download failed, continue to try regular HTTP
                     }
 
-                    // Fetch the URL
-                    let response = match client
-                        .get(
-                            &url,
-                            crawl_url.etag.as_deref(),
-                            crawl_url.last_modified.as_deref(),
-                        )
-                        .await
-                    {
+                    // Fetch the URL, with the portal's cookies for sources behind a login
+                    let session = portal_sessions.get(&crawl_url.source_id);
+                    let capture = warc_captures.get(&crawl_url.source_id);
+                    let client: &HttpClient = if session.is_some() || capture.is_some() {
+                        source_clients
+                            .entry(crawl_url.source_id.clone())
+                            .or_insert_with(|| {
+                                let client =
+                                    session.map_or_else(|| client.clone(), |s| s.client().clone());
+                                match capture {
+                                    Some(capture) => client.with_warc_capture(capture.clone()),
+                                    None => client,
+                                }
+                            })
+                    } else {
+                        &client
+                    };
+                    let etag = crawl_url.etag.as_deref();
+                    let last_modified = crawl_url.last_modified.as_deref();
+                    // Keep the lease alive while the fetch runs, so long downloads
+                    // aren't claimed and fetched again by another host
+                    let fetch = async {
+                        match session {
+                            Some(session) => session.get(client, &url, etag, last_modified).await,
+                            None => client
+                                .get(&url, etag, last_modified)
+                                .await
+                                .map_err(anyhow::Error::from),
+                        }
+                    };
+                    let fetched =
+                        match hold_lease(fetch, &crawl_url, &crawl_repo, lease.as_ref()).await {
+                            Some(fetched) => fetched,
+                            None => {
+                                handle_lease_lost(&url, &event_tx, worker_id).await;
+                                continue;
+                            }
+                        };
+                    let response = match fetched {
                         Ok(r) => r,
                         Err(e) => {
                             handle_download_failure(
@@ -183,12 +250,26 @@ impl DownloadService {
                     };
 
                     if response.is_not_modified() {
+                        log_download_request(
+                            &crawl_url,
+                            &crawl_repo,
+                            response.status.as_u16(),
+                            None,
+                        )
+                        .await;
                         handle_unchanged(&crawl_url, &crawl_repo, &skipped, &event_tx, worker_id)
                             .await;
                         continue;
                     }
 
                     if !response.is_success() {
+                        log_download_request(
+                            &crawl_url,
+                            &crawl_repo,
+                            response.status.as_u16(),
+                            None,
+                        )
+                        .await;
                         handle_download_failure(
                             &crawl_url,
                             &crawl_repo,
@@ -211,6 +292,7 @@ impl DownloadService {
                         .content_type()
                         .map(|s| s.to_string())
                         .unwrap_or_else(|| "application/octet-stream".to_string());
+                    let status = response.status.as_u16();
                     let etag = response.etag().map(|s| s.to_string());
                     let last_modified = response.last_modified().map(|s| s.to_string());
                     let server_date = last_modified.as_ref().and_then(|lm| {
@@ -219,9 +301,15 @@ impl DownloadService {
                             .map(|dt| dt.with_timezone(&chrono::Utc))
                     });
 
-                    let content = match response.bytes().await {
-                        Ok(b) => b,
-                        Err(e) => {
+                    let body =
+                        hold_lease(response.bytes(), &crawl_url, &crawl_repo, lease.as_ref()).await;
+                    let content = match body {
+                        None => {
+                            handle_lease_lost(&url, &event_tx, worker_id).await;
+                            continue;
+                        }
+                        Some(Ok(b)) => b,
+                        Some(Err(e)) => {
                             handle_download_failure(
                                 &crawl_url,
                                 &crawl_repo,
@@ -236,6 +324,22 @@ impl DownloadService {
                         }
                     };
 
+                    log_download_request(
+                        &crawl_url,
+                        &crawl_repo,
+                        status,
+                        Some(content.len() as u64),
+                    )
+                    .await;
+
+                    // Leave the URL to whoever holds it now
+                    if let Some(ref lease) = lease {
+                        if let Ok(false) = crawl_repo.renew_lease(&crawl_url, lease).await {
+                            handle_lease_lost(&url, &event_tx, worker_id).await;
+                            continue;
+                        }
+                    }
+
                     let _ = event_tx
                         .send(DownloadEvent::Progress {
                             worker_id,
@@ -248,6 +352,13 @@ impl DownloadService {
                     let hashes = DocumentVersion::compute_dual_hashes(&content);
                     let file_size = content.len() as i64;
 
+                    // Same content as a deleted document, served from another URL
+                    if let Ok(Some(_)) = doc_repo.find_tombstone(None, Some(&hashes.sha256)).await {
+                        handle_tombstoned(&crawl_url, &crawl_repo, &skipped, &event_tx, worker_id)
+                            .await;
+                        continue;
+                    }
+
                     // Check for existing file with same content
                     let (dedup_index, was_deduplicated) = match doc_repo
                         .find_existing_file(&hashes.sha256, &hashes.blake3, file_size)
@@ -333,12 +444,23 @@ impl DownloadService {
                         &crawl_url.source_id,
                         title,
                         version,
-                        serde_json::json!({}),
+                        crawl_url.document_metadata(),
                         "crawl",
                     )
                     .await
                     {
-                        Ok(new_doc) => new_doc,
+                        Ok(Some(new_doc)) => new_doc,
+                        Ok(None) => {
+                            handle_tombstoned(
+                                &crawl_url,
+                                &crawl_repo,
+                                &skipped,
+                                &event_tx,
+                                worker_id,
+                            )
+                            .await;
+                            continue;
+                        }
                         Err(e) => {
                             handle_download_failure(
                                 &crawl_url,
@@ -10,9 +10,12 @@ use tokio::sync::mpsc;
 
 use tracing::warn;
 
-use crate::config::ViaMode;
-use foia::models::{CrawlUrl, Document, DocumentVersion, UrlStatus};
+use crate::config::{BudgetConfig, ViaMode};
+use crate::configurable::PortalSession;
+use foia::http_client::WarcCapture;
+use foia::models::{CrawlRequest, CrawlUrl, Document, DocumentVersion, UrlStatus};
 use foia::privacy::PrivacyConfig;
+use foia::repository::diesel_crawl::UrlLease;
 use foia::repository::{DieselCrawlRepository, DieselDocumentRepository};
 
 /// Events emitted during download operations.
@@ -46,6 +49,10 @@ pub enum DownloadEvent {
     },
     /// Document unchanged (304 Not Modified)
     Unchanged { worker_id: usize, url: String },
+    /// URL or content matches a deleted document and was skipped
+    Tombstoned { worker_id: usize, url: String },
+    /// Lease expired mid-download and another worker took the URL over
+    LeaseLost { worker_id: usize, url: String },
     /// Download failed
     Failed {
         worker_id: usize,
@@ -77,6 +84,43 @@ pub struct DownloadConfig {
     pub via: HashMap<String, String>,
     /// Via mode controlling when via mappings are used.
     pub via_mode: ViaMode,
+    /// Per-source crawl budgets, keyed by source ID. Sources that have used
+    /// up their budget since the download started are no longer claimed.
+    pub budgets: HashMap<String, BudgetConfig>,
+    /// Claim URLs under a lease, so several hosts can share one queue.
+    pub lease: Option<UrlLease>,
+    /// WARC captures for sources that have capture enabled, keyed by source ID.
+    pub warc_captures: HashMap<String, Arc<WarcCapture>>,
+    /// Logged-in portal sessions for sources with `auth`, keyed by source ID.
+    pub portal_sessions: HashMap<String, Arc<PortalSession>>,
+}
+
+/// Log a download against its source so it counts toward the crawl budget.
+///
+/// The download client isn't tied to one source, so it doesn't log its own
+/// requests. Only the URL, status and size are recorded.
+pub async fn log_download_request(
+    crawl_url: &CrawlUrl,
+    crawl_repo: &Arc<DieselCrawlRepository>,
+    status: u16,
+    size: Option<u64>,
+) {
+    let mut request = CrawlRequest::new(
+        crawl_url.source_id.clone(),
+        crawl_url.url.clone(),
+        "GET".to_string(),
+    );
+    request.response_status = Some(status);
+    request.response_at = Some(chrono::Utc::now());
+    request.response_size = size;
+    request.was_conditional = crawl_url.etag.is_some() || crawl_url.last_modified.is_some();
+    request.was_not_modified = status == 304;
+    if let Err(e) = crawl_repo.log_request(&request).await {
+        warn!(
+            "Failed to log download request for {}: {}",
+            crawl_url.url, e
+        );
+    }
 }
 
 /// Handle a download failure: update status, increment counter, send event.
@@ -155,8 +199,82 @@ pub async fn handle_unchanged(
         .await;
 }
 
+/// Mark a URL as skipped because it matches a deleted document's tombstone.
+pub async fn handle_tombstoned(
+    crawl_url: &CrawlUrl,
+    crawl_repo: &Arc<DieselCrawlRepository>,
+    skipped: &Arc<AtomicUsize>,
+    event_tx: &mpsc::Sender<DownloadEvent>,
+    worker_id: usize,
+) {
+    let mut skipped_url = crawl_url.clone();
+    skipped_url.status = UrlStatus::Skipped;
+    skipped_url.last_error = Some("matches deleted document".to_string());
+    if let Err(e) = crawl_repo.update_url(&skipped_url).await {
+        warn!(
+            "Failed to update crawl URL status for {}: {}",
+            crawl_url.url, e
+        );
+    }
+    skipped.fetch_add(1, Ordering::Relaxed);
+    let _ = event_tx
+        .send(DownloadEvent::Tombstoned {
+            worker_id,
+            url: crawl_url.url.clone(),
+        })
+        .await;
+}
+
+/// Run a fetch while renewing the worker's lease on its URL every third of
+/// the lease duration.
+///
+/// Returns None, dropping the fetch, as soon as a renewal fails, since
+/// another host may already have claimed the URL.
+pub async fn hold_lease<T>(
+    fetch: impl std::future::Future<Output = T>,
+    crawl_url: &CrawlUrl,
+    crawl_repo: &Arc<DieselCrawlRepository>,
+    lease: Option<&UrlLease>,
+) -> Option<T> {
+    let Some(lease) = lease else {
+        return Some(fetch.await);
+    };
+    let period = (lease.duration / 3)
+        .to_std()
+        .unwrap_or_default()
+        .max(Duration::from_secs(1));
+    let mut renewals = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    tokio::pin!(fetch);
+    loop {
+        tokio::select! {
+            result = &mut fetch => return Some(result),
+            _ = renewals.tick() => {
+                if !matches!(crawl_repo.renew_lease(crawl_url, lease).await, Ok(true)) {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Drop a URL whose lease was lost, leaving it to whoever holds it now.
+pub async fn handle_lease_lost(
+    url: &str,
+    event_tx: &mpsc::Sender<DownloadEvent>,
+    worker_id: usize,
+) {
+    warn!("Lease on {} expired during download, dropping it", url);
+    let _ = event_tx
+        .send(DownloadEvent::LeaseLost {
+            worker_id,
+            url: url.to_string(),
+        })
+        .await;
+}
+
 /// Save a document version, either adding to existing document or creating new.
-/// Returns whether this created a new document.
+/// Returns whether this created a new document, or `None` when the save was
+/// refused because the URL or content matches a deleted document.
 #[allow(clippy::too_many_arguments)]
 pub async fn save_or_update_document(
     doc_repo: &Arc<DieselDocumentRepository>,
@@ -166,13 +284,13 @@ pub async fn save_or_update_document(
     version: DocumentVersion,
     metadata: serde_json::Value,
     discovery_method: &str,
-) -> Result<bool, foia::repository::DieselError> {
+) -> Result<Option<bool>, foia::repository::DieselError> {
     let existing = doc_repo.get_by_url(url).await?.into_iter().next();
     let new_document = existing.is_none();
 
     if let Some(mut doc) = existing {
-        if doc.add_version(version) {
-            doc_repo.save_with_versions(&doc).await?;
+        if doc.add_version(version) && !doc_repo.save_with_versions(&doc).await? {
+            return Ok(None);
         }
     } else {
         let doc = Document::with_discovery_method(
@@ -184,8 +302,10 @@ pub async fn save_or_update_document(
             metadata,
             discovery_method.to_string(),
         );
-        doc_repo.save_with_versions(&doc).await?;
+        if !doc_repo.save_with_versions(&doc).await? {
+            return Ok(None);
+        }
     }
 
-    Ok(new_document)
+    Ok(Some(new_document))
 }
@@ -11,7 +11,9 @@ use crate::services::youtube;
 use foia::models::{CrawlUrl, DocumentVersion, UrlStatus};
 use foia::repository::{DieselCrawlRepository, DieselDocumentRepository};
 
-use super::types::{handle_download_failure, save_or_update_document, DownloadEvent};
+use super::types::{
+    handle_download_failure, handle_tombstoned, save_or_update_document, DownloadEvent,
+};
 
 /// Download a YouTube video and store it as a document.
 /// Returns true if handled (success or failure), false if should fall back to HTTP.
@@ -26,6 +28,7 @@ pub async fn download_youtube_video(
     event_tx: &mpsc::Sender<DownloadEvent>,
     downloaded: &Arc<AtomicUsize>,
     failed: &Arc<AtomicUsize>,
+    skipped: &Arc<AtomicUsize>,
     proxy_url: Option<&str>,
 ) -> bool {
     debug!("Attempting YouTube download: {}", url);
@@ -104,7 +107,11 @@ pub async fn download_youtube_video(
             )
             .await
             {
-                Ok(new_doc) => new_doc,
+                Ok(Some(new_doc)) => new_doc,
+                Ok(None) => {
+                    handle_tombstoned(crawl_url, crawl_repo, skipped, event_tx, worker_id).await;
+                    return true;
+                }
                 Err(e) => {
                     handle_download_failure(
                         crawl_url,
@@ -8,6 +8,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+use super::super::public::TextPolicy;
 use super::super::AppState;
 use super::api_types::{
     AnnotationListStats, AnnotationsListResponse, ApiResponse, UpdateAnnotationResponse,
@@ -82,14 +83,18 @@ pub async fn list_annotations(
             .unwrap_or_default()
     };
 
+    let mut policies = state.text_policies(&documents).await;
     let items: Vec<AnnotationResponse> = documents
         .into_iter()
-        .map(|doc| AnnotationResponse {
-            document_id: doc.id,
-            title: doc.title,
-            synopsis: doc.synopsis.clone(),
-            tags: doc.tags.clone(),
-            has_annotation: doc.synopsis.is_some() || !doc.tags.is_empty(),
+        .map(|doc| {
+            let policy = policies.remove(&doc.id).unwrap_or(TextPolicy::Withheld);
+            AnnotationResponse {
+                has_annotation: doc.synopsis.is_some() || !doc.tags.is_empty(),
+                document_id: doc.id,
+                title: policy.apply_title(doc.title),
+                synopsis: policy.apply(doc.synopsis),
+                tags: doc.tags,
+            }
         })
         .collect();
 
@@ -132,14 +137,17 @@ pub async fn get_annotation(
     Path(doc_id): Path<String>,
 ) -> impl IntoResponse {
     match state.doc_repo.get(&doc_id).await {
-        Ok(Some(doc)) => ApiResponse::ok(AnnotationResponse {
-            document_id: doc.id,
-            title: doc.title,
-            synopsis: doc.synopsis,
-            tags: doc.tags,
-            has_annotation: true,
-        })
-        .into_response(),
+        Ok(Some(doc)) => {
+            let policy = state.text_policy(&doc).await;
+            ApiResponse::ok(AnnotationResponse {
+                document_id: doc.id,
+                title: policy.apply_title(doc.title),
+                synopsis: policy.apply(doc.synopsis),
+                tags: doc.tags,
+                has_annotation: true,
+            })
+            .into_response()
+        }
         Ok(None) => not_found("Document not found").into_response(),
         Err(e) => internal_error(e).into_response(),
     }
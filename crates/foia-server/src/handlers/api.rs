@@ -8,6 +8,7 @@ use axum::{
 use serde::Deserialize;
 use utoipa::IntoParams;
 
+use super::super::public::TextPolicy;
 use super::super::AppState;
 use super::api_types::{
     ApiResponse, CategoryStat, CrawlState, CrawlStats, DocumentStats, FailedUrl, MimeTypeStat,
@@ -309,17 +310,19 @@ pub async fn api_recent_docs(
 
     match state.doc_repo.get_recent(limit as u32).await {
         Ok(docs) => {
+            let mut policies = state.text_policies(&docs).await;
             let doc_list: Vec<RecentDocument> = docs
                 .into_iter()
                 .filter(|d| source_id.is_none() || Some(d.source_id.as_str()) == source_id)
                 .map(|d| {
+                    let policy = policies.remove(&d.id).unwrap_or(TextPolicy::Withheld);
                     let mime_type = d.current_version().map(|v| v.mime_type.clone());
                     let file_size = d.current_version().map(|v| v.file_size);
                     RecentDocument {
                         id: d.id,
-                        title: d.title,
+                        title: policy.apply_title(d.title),
                         source_id: d.source_id,
-                        synopsis: d.synopsis,
+                        synopsis: policy.apply(d.synopsis),
                         tags: d.tags,
                         status: format!("{:?}", d.status),
                         updated_at: d.updated_at.to_rfc3339(),
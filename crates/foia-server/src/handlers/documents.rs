@@ -9,7 +9,7 @@ use axum::{
 use serde::Deserialize;
 
 use super::super::template_structs::{
-    DocumentDetailTemplate, ErrorTemplate, VersionItem, VirtualFileRow,
+    DocumentDetailTemplate, ErrorTemplate, ThreadMessageRow, VersionItem, VirtualFileRow,
 };
 use super::super::AppState;
 use super::helpers::{find_sources_with_hash, VersionInfo};
@@ -171,8 +171,14 @@ pub async fn document_detail(
             (false, String::new(), String::new(), String::new())
         };
 
+    let policy = state.text_policy(&doc).await;
+    let title = policy.apply_title(doc.title.clone());
+    let extracted_text = policy.apply(doc.extracted_text.clone());
+    let bates_range = state.doc_repo.get_bates_range(&doc.id).await.ok().flatten();
+    let thread_messages = load_thread(&state, &doc.id).await;
+
     let template = DocumentDetailTemplate {
-        title: &doc.title,
+        title: &title,
         doc_id: &doc.id,
         source_id: &doc.source_id,
         source_url: &doc.source_url,
@@ -188,11 +194,15 @@ pub async fn document_detail(
             )
             .await
             .is_empty(),
-        has_extracted_text: doc.extracted_text.is_some(),
-        extracted_text_val: doc.extracted_text.clone().unwrap_or_default(),
+        has_bates: bates_range.is_some(),
+        bates_range_val: bates_range.map(|r| r.to_string()).unwrap_or_default(),
+        has_extracted_text: extracted_text.is_some(),
+        extracted_text_val: extracted_text.clone().unwrap_or_default(),
         virtual_files: virtual_files.clone(),
         has_virtual_files: !virtual_files.is_empty(),
         virtual_files_count: virtual_files.len(),
+        has_thread: thread_messages.len() > 1,
+        thread_messages,
         has_prev,
         prev_id_val,
         prev_title_val,
@@ -216,6 +226,48 @@ pub async fn document_detail(
     )
 }
 
+/// Load the email thread containing a document, if it has been threaded.
+///
+/// Subjects follow each message's text policy in public mode. Senders are
+/// lowercased header addresses that span masking cannot match, so they are
+/// withheld there, like the correspondent graph.
+async fn load_thread(state: &AppState, doc_id: &str) -> Vec<ThreadMessageRow> {
+    let Ok(Some(message)) = state.doc_repo.get_email_message(doc_id).await else {
+        return vec![];
+    };
+    let thread = state
+        .doc_repo
+        .get_email_thread(&message.thread_id)
+        .await
+        .unwrap_or_default();
+
+    let mut rows = Vec::with_capacity(thread.len());
+    for m in thread {
+        let policy = state.text_policy_by_id(&m.document_id).await;
+        let date_str = m
+            .sent_at
+            .as_deref()
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        rows.push(ThreadMessageRow {
+            is_current: m.document_id == doc_id,
+            subject: policy
+                .apply(m.subject)
+                .unwrap_or_else(|| "(no subject)".to_string()),
+            sender: if state.public {
+                String::new()
+            } else {
+                m.sender.unwrap_or_default()
+            },
+            date_str,
+            depth: m.depth,
+            id: m.document_id,
+        });
+    }
+    rows
+}
+
 /// Get document versions as JSON.
 pub async fn document_versions(
     State(state): State<AppState>,
@@ -8,11 +8,14 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+use super::super::public::TextPolicy;
 use super::super::AppState;
 use super::api_types::ApiResponse;
 use super::helpers::{
-    internal_error, not_found, paginate, parse_csv_param, DocumentSummary, PaginatedResponse,
+    bad_request, internal_error, not_found, paginate, parse_csv_param, DocumentSummary,
+    PaginatedResponse,
 };
+use foia::models::BatesNumber;
 use foia::repository::diesel_document::BrowseParams;
 
 /// Query parameters for document search/listing.
@@ -68,6 +71,7 @@ pub async fn list_documents(
             sort_order: params.order.as_deref(),
             limit: per_page as u32,
             offset: offset as u32,
+            after_id: None,
         })
         .await
     {
@@ -87,7 +91,14 @@ pub async fn list_documents(
         .await
         .unwrap_or(documents.len() as u64);
 
-    let items: Vec<DocumentSummary> = documents.into_iter().map(DocumentSummary::from).collect();
+    let mut policies = state.text_policies(&documents).await;
+    let items: Vec<DocumentSummary> = documents
+        .into_iter()
+        .map(|doc| {
+            let policy = policies.remove(&doc.id).unwrap_or(TextPolicy::Withheld);
+            DocumentSummary::from(doc).with_policy(&policy)
+        })
+        .collect();
 
     Json(PaginatedResponse::new(items, page, per_page, total)).into_response()
 }
@@ -108,12 +119,68 @@ pub async fn get_document(
     Path(doc_id): Path<String>,
 ) -> impl IntoResponse {
     match state.doc_repo.get(&doc_id).await {
-        Ok(Some(doc)) => ApiResponse::ok(DocumentSummary::from(doc)).into_response(),
+        Ok(Some(doc)) => {
+            let policy = state.text_policy(&doc).await;
+            ApiResponse::ok(DocumentSummary::from(doc).with_policy(&policy)).into_response()
+        }
         Ok(None) => not_found("Document not found").into_response(),
         Err(e) => internal_error(e).into_response(),
     }
 }
 
+/// A document page resolved from a Bates number.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatesLookupResult {
+    pub document_id: String,
+    pub title: String,
+    /// 1-indexed page the number is stamped on
+    pub page_number: u32,
+    /// The document's full Bates range
+    pub bates_range: String,
+}
+
+/// Find the document and page stamped with a Bates number.
+#[utoipa::path(
+    get,
+    path = "/api/bates/{bates}",
+    params(("bates" = String, Path, description = "Bates number, e.g. ABC000123")),
+    responses(
+        (status = 200, description = "Documents whose Bates range contains the number", body = Vec<BatesLookupResult>),
+        (status = 400, description = "Not a Bates number")
+    ),
+    tag = "Documents"
+)]
+pub async fn lookup_bates(
+    State(state): State<AppState>,
+    Path(bates): Path<String>,
+) -> impl IntoResponse {
+    let Some(number) = BatesNumber::parse(&bates) else {
+        return bad_request("Not a Bates number").into_response();
+    };
+
+    let matches = match state.doc_repo.find_by_bates(&number).await {
+        Ok(m) => m,
+        Err(e) => return internal_error(e).into_response(),
+    };
+
+    let mut results = Vec::with_capacity(matches.len());
+    for m in matches {
+        let title = match state.doc_repo.get(&m.document_id).await {
+            Ok(Some(doc)) => doc.title,
+            Ok(None) => continue,
+            Err(e) => return internal_error(e).into_response(),
+        };
+        results.push(BatesLookupResult {
+            document_id: m.document_id,
+            title,
+            page_number: m.page_number,
+            bates_range: m.range.to_string(),
+        });
+    }
+
+    ApiResponse::ok(results).into_response()
+}
+
 /// Get document content/text.
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct ContentQuery {
@@ -171,11 +238,13 @@ pub async fn get_document_content(
         .await
         .unwrap_or_default();
 
+    let policy = state.text_policy(&doc).await;
+
     let page_contents: Vec<PageContent> = pages
         .into_iter()
         .map(|p| PageContent {
             page_number: p.page_number,
-            text: p.final_text.or(p.ocr_text).or(p.pdf_text),
+            text: policy.apply(p.final_text.or(p.ocr_text).or(p.pdf_text)),
         })
         .collect();
 
@@ -183,7 +252,7 @@ pub async fn get_document_content(
 
     ApiResponse::ok(DocumentContentResponse {
         id: doc.id,
-        extracted_text: doc.extracted_text,
+        extracted_text: policy.apply(doc.extracted_text),
         page_count,
         pages: page_contents,
     })
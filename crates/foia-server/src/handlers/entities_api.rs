@@ -1,5 +1,7 @@
 //! Entity search and browse API endpoints.
 
+use std::collections::BTreeMap;
+
 use axum::{
     extract::{Path, Query, State},
     response::IntoResponse,
@@ -11,7 +13,7 @@ use utoipa::{IntoParams, ToSchema};
 use super::super::AppState;
 use super::api_types::ApiResponse;
 use super::helpers::{bad_request, internal_error, not_found, paginate, PaginatedResponse};
-use foia::repository::diesel_document::entities::EntityFilter;
+use foia::repository::diesel_document::entities::{EntityFilter, EMAIL_ENTITY_TYPE};
 #[cfg(feature = "gis")]
 use foia::services::geolookup;
 
@@ -54,6 +56,17 @@ pub struct LocationsQuery {
     pub per_page: Option<usize>,
 }
 
+/// Query parameters for the correspondent graph.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CorrespondentsQuery {
+    /// Filter by source
+    pub source: Option<String>,
+    /// Only edges sent from or to this address
+    pub address: Option<String>,
+    /// Maximum number of edges (default: 200, max: 1000)
+    pub limit: Option<usize>,
+}
+
 /// A matched entity in search results.
 #[derive(Debug, Serialize, ToSchema)]
 pub struct MatchedEntity {
@@ -95,6 +108,31 @@ pub struct GeocodedLocation {
     pub document_id: String,
 }
 
+/// Email address in the correspondent graph.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorrespondentNode {
+    pub address: String,
+    /// Messages sent by this address (within the returned edges)
+    pub sent: u64,
+    /// Messages received by this address (within the returned edges)
+    pub received: u64,
+}
+
+/// Messages from one address to another.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorrespondentLink {
+    pub sender: String,
+    pub recipient: String,
+    pub count: u64,
+}
+
+/// Who emailed whom and how often, for /api/correspondents.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorrespondentGraph {
+    pub nodes: Vec<CorrespondentNode>,
+    pub edges: Vec<CorrespondentLink>,
+}
+
 /// Search documents by entity filters.
 #[utoipa::path(
     get,
@@ -126,6 +164,12 @@ pub async fn search_entities(
         }
     }
 
+    // Header addresses are withheld in public mode, like the correspondent graph
+    let exclude_type = state.public.then(|| EMAIL_ENTITY_TYPE.to_string());
+    if exclude_type.is_some() && params.entity_type.as_deref() == Some(EMAIL_ENTITY_TYPE) {
+        return not_found("Email entities are not available in public mode").into_response();
+    }
+
     let mut filters = Vec::new();
 
     if let Some(q) = &params.q {
@@ -134,6 +178,7 @@ pub async fn search_entities(
                 entity_type: params.entity_type.clone(),
                 text: q.clone(),
                 exact: params.exact.unwrap_or(false),
+                exclude_type: exclude_type.clone(),
             });
         }
     }
@@ -149,12 +194,14 @@ pub async fn search_entities(
                     entity_type: Some(entity_type.trim().to_string()),
                     text: text.trim().to_string(),
                     exact: params.exact.unwrap_or(false),
+                    exclude_type: exclude_type.clone(),
                 });
             } else {
                 filters.push(EntityFilter {
                     entity_type: None,
                     text: pair.to_string(),
                     exact: params.exact.unwrap_or(false),
+                    exclude_type: exclude_type.clone(),
                 });
             }
         }
@@ -232,6 +279,9 @@ pub async fn top_entities(
     Query(params): Query<TopEntitiesQuery>,
 ) -> impl IntoResponse {
     let entity_type = params.entity_type.as_deref().unwrap_or("organization");
+    if state.public && entity_type == EMAIL_ENTITY_TYPE {
+        return not_found("Email entities are not available in public mode").into_response();
+    }
     let limit = params.limit.unwrap_or(20).clamp(1, 100);
 
     match state.doc_repo.get_top_entities(entity_type, limit).await {
@@ -274,6 +324,7 @@ pub async fn document_entities(
         Ok(entities) => {
             let items: Vec<MatchedEntity> = entities
                 .into_iter()
+                .filter(|e| !(state.public && e.entity_type == EMAIL_ENTITY_TYPE))
                 .map(|e| MatchedEntity {
                     entity_type: e.entity_type,
                     entity_text: e.entity_text,
@@ -326,6 +377,69 @@ pub async fn entity_locations(
     Json(PaginatedResponse::new(items, page, per_page, total)).into_response()
 }
 
+/// Get the email correspondent graph built by `foia annotate threads`.
+#[utoipa::path(
+    get,
+    path = "/api/correspondents",
+    params(CorrespondentsQuery),
+    responses(
+        (status = 200, description = "Correspondent graph", body = CorrespondentGraph),
+        (status = 404, description = "Not available in public mode")
+    ),
+    tag = "Entities"
+)]
+pub async fn correspondents(
+    State(state): State<AppState>,
+    Query(params): Query<CorrespondentsQuery>,
+) -> impl IntoResponse {
+    // Every node is an email address, so there is nothing left to publish
+    if state.public {
+        return not_found("Correspondent graph is not available in public mode").into_response();
+    }
+
+    let limit = params.limit.unwrap_or(200).clamp(1, 1000);
+    let edges = match state
+        .doc_repo
+        .get_correspondent_graph(params.source.as_deref(), params.address.as_deref(), limit)
+        .await
+    {
+        Ok(edges) => edges,
+        Err(e) => return internal_error(e).into_response(),
+    };
+
+    let mut nodes: BTreeMap<&str, CorrespondentNode> = BTreeMap::new();
+    for edge in &edges {
+        nodes
+            .entry(edge.sender.as_str())
+            .or_insert_with(|| CorrespondentNode {
+                address: edge.sender.clone(),
+                sent: 0,
+                received: 0,
+            })
+            .sent += edge.count;
+        nodes
+            .entry(edge.recipient.as_str())
+            .or_insert_with(|| CorrespondentNode {
+                address: edge.recipient.clone(),
+                sent: 0,
+                received: 0,
+            })
+            .received += edge.count;
+    }
+    let nodes: Vec<CorrespondentNode> = nodes.into_values().collect();
+
+    let edges = edges
+        .into_iter()
+        .map(|e| CorrespondentLink {
+            sender: e.sender,
+            recipient: e.recipient,
+            count: e.count,
+        })
+        .collect();
+
+    ApiResponse::ok(CorrespondentGraph { nodes, edges }).into_response()
+}
+
 async fn handle_near_query(
     state: &AppState,
     near_str: &str,
@@ -463,6 +577,7 @@ async fn build_search_results(
             .get(id)
             .map(|es| {
                 es.iter()
+                    .filter(|e| !(state.public && e.entity_type == EMAIL_ENTITY_TYPE))
                     .map(|e| MatchedEntity {
                         entity_type: e.entity_type.clone(),
                         entity_text: e.entity_text.clone(),
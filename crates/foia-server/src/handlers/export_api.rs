@@ -6,14 +6,26 @@ use axum::{
     http::{header, StatusCode},
     response::{IntoResponse, Response},
 };
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Write;
 use utoipa::{IntoParams, ToSchema};
 
+use super::super::public::TextPolicy;
 use super::super::AppState;
 use super::api_types::{AnnotationExport, ApiResponse, ExportStatsResponse};
-use super::helpers::{internal_error, parse_csv_param};
+use super::helpers::{bad_request, internal_error, parse_csv_param};
+use foia::models::Document;
 use foia::repository::diesel_document::BrowseParams;
+use foia::repository::DieselError;
+
+/// CSV header for the document metadata columns.
+const CSV_HEADER: &str = "id,source_id,title,source_url,status,synopsis,tags,created_at,updated_at,mime_type,file_size,page_count,content_hash";
+
+/// Documents loaded per query by the streaming export.
+const STREAM_BATCH_SIZE: usize = 500;
 
 /// Export format options.
 #[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ToSchema)]
@@ -44,6 +56,36 @@ pub struct ExportQuery {
     pub limit: Option<usize>,
 }
 
+/// Streaming export format options.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamFormat {
+    #[default]
+    Jsonl,
+    Csv,
+}
+
+/// Query params for streaming export.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StreamExportQuery {
+    /// Export format (jsonl, csv)
+    #[serde(default)]
+    pub format: StreamFormat,
+    /// Filter by source ID
+    pub source: Option<String>,
+    /// Filter by tags (comma-separated)
+    pub tags: Option<String>,
+    /// Filter by types (comma-separated)
+    pub types: Option<String>,
+    /// Include full text content
+    #[serde(default)]
+    pub include_text: bool,
+    /// Resume after the document this cursor was emitted with
+    pub cursor: Option<String>,
+    /// Stop after this many documents (default: no limit)
+    pub limit: Option<usize>,
+}
+
 /// Document export record.
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ExportDocument {
@@ -64,7 +106,86 @@ pub struct ExportDocument {
     pub extracted_text: Option<String>,
 }
 
+/// Streamed document record with the cursor to resume after it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StreamedDocument {
+    #[serde(flatten)]
+    pub document: ExportDocument,
+    pub cursor: String,
+}
+
+impl ExportDocument {
+    /// Build an export record, applying the document's text policy to its
+    /// title, synopsis and, with `include_text`, extracted text.
+    ///
+    /// Documents missing from `policies` are treated as withheld.
+    fn new(doc: Document, policies: &HashMap<String, TextPolicy>, include_text: bool) -> Self {
+        let policy = policies.get(&doc.id).unwrap_or(&TextPolicy::Withheld);
+        let (mime_type, file_size, page_count, content_hash) =
+            if let Some(v) = doc.current_version() {
+                (
+                    Some(v.mime_type.clone()),
+                    Some(v.file_size),
+                    v.page_count,
+                    Some(v.content_hash.clone()),
+                )
+            } else {
+                (None, None, None, None)
+            };
+        let extracted_text = if include_text {
+            policy.apply(doc.extracted_text)
+        } else {
+            None
+        };
+        ExportDocument {
+            id: doc.id,
+            source_id: doc.source_id,
+            title: policy.apply_title(doc.title),
+            source_url: doc.source_url,
+            status: doc.status.as_str().to_string(),
+            synopsis: policy.apply(doc.synopsis),
+            tags: doc.tags,
+            created_at: doc.created_at.to_rfc3339(),
+            updated_at: doc.updated_at.to_rfc3339(),
+            mime_type,
+            file_size,
+            page_count,
+            content_hash,
+            extracted_text,
+        }
+    }
+
+    /// Render the metadata columns of a CSV row, without a line ending.
+    fn csv_row(&self) -> String {
+        let synopsis_escaped = self
+            .synopsis
+            .as_ref()
+            .map(|s| escape_csv(s))
+            .unwrap_or_default();
+
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.id,
+            self.source_id,
+            escape_csv(&self.title),
+            escape_csv(&self.source_url),
+            self.status,
+            synopsis_escaped,
+            escape_csv(&self.tags.join(";")),
+            self.created_at,
+            self.updated_at,
+            self.mime_type.as_deref().unwrap_or(""),
+            self.file_size.unwrap_or(0),
+            self.page_count.unwrap_or(0),
+            self.content_hash.as_deref().unwrap_or("")
+        )
+    }
+}
+
 /// Export documents in various formats.
+///
+/// Results are buffered and capped by `limit`; use
+/// `/api/export/documents/stream` for full-corpus exports.
 #[utoipa::path(
     get,
     path = "/api/export/documents",
@@ -97,41 +218,11 @@ pub async fn export_documents(
         Err(e) => return internal_error(e).into_response(),
     };
 
+    let policies = state.text_policies(&documents).await;
+
     let export_docs: Vec<ExportDocument> = documents
         .into_iter()
-        .map(|doc| {
-            let (mime_type, file_size, page_count, content_hash) =
-                if let Some(v) = doc.current_version() {
-                    (
-                        Some(v.mime_type.clone()),
-                        Some(v.file_size),
-                        v.page_count,
-                        Some(v.content_hash.clone()),
-                    )
-                } else {
-                    (None, None, None, None)
-                };
-            ExportDocument {
-                id: doc.id,
-                source_id: doc.source_id,
-                title: doc.title,
-                source_url: doc.source_url,
-                status: doc.status.as_str().to_string(),
-                synopsis: doc.synopsis,
-                tags: doc.tags,
-                created_at: doc.created_at.to_rfc3339(),
-                updated_at: doc.updated_at.to_rfc3339(),
-                mime_type,
-                file_size,
-                page_count,
-                content_hash,
-                extracted_text: if params.include_text {
-                    doc.extracted_text
-                } else {
-                    None
-                },
-            }
-        })
+        .map(|doc| ExportDocument::new(doc, &policies, params.include_text))
         .collect();
 
     match params.format {
@@ -168,39 +259,9 @@ pub async fn export_documents(
         }
         ExportFormat::Csv => {
             let mut output = Vec::new();
-            writeln!(
-                output,
-                "id,source_id,title,source_url,status,synopsis,tags,created_at,updated_at,mime_type,file_size,page_count,content_hash"
-            )
-            .ok();
-
+            writeln!(output, "{}", CSV_HEADER).ok();
             for doc in &export_docs {
-                let tags_str = doc.tags.join(";");
-                let synopsis_escaped = doc
-                    .synopsis
-                    .as_ref()
-                    .map(|s| escape_csv(s))
-                    .unwrap_or_default();
-                let title_escaped = escape_csv(&doc.title);
-
-                writeln!(
-                    output,
-                    "{},{},{},{},{},{},{},{},{},{},{},{},{}",
-                    doc.id,
-                    doc.source_id,
-                    title_escaped,
-                    escape_csv(&doc.source_url),
-                    doc.status,
-                    synopsis_escaped,
-                    escape_csv(&tags_str),
-                    doc.created_at,
-                    doc.updated_at,
-                    doc.mime_type.as_deref().unwrap_or(""),
-                    doc.file_size.unwrap_or(0),
-                    doc.page_count.unwrap_or(0),
-                    doc.content_hash.as_deref().unwrap_or("")
-                )
-                .ok();
+                writeln!(output, "{}", doc.csv_row()).ok();
             }
 
             Response::builder()
@@ -217,8 +278,185 @@ pub async fn export_documents(
     }
 }
 
+/// Stream documents as JSONL or CSV, without a row cap.
+///
+/// Documents are read in ID order, a batch at a time, and each batch is
+/// written out before the next is loaded, so memory use stays flat however
+/// large the export. Every record carries an opaque `cursor`; if the
+/// connection drops, request again with the last cursor received to continue
+/// from the next document. CSV output omits the header row when resuming so
+/// it can be appended to the partial file.
+#[utoipa::path(
+    get,
+    path = "/api/export/documents/stream",
+    params(StreamExportQuery),
+    responses(
+        (status = 200, description = "Streamed documents, one per line (format varies by query param)", content_type = "application/x-ndjson"),
+        (status = 400, description = "Invalid cursor")
+    ),
+    tag = "Export"
+)]
+pub async fn export_documents_stream(
+    State(state): State<AppState>,
+    Query(params): Query<StreamExportQuery>,
+) -> impl IntoResponse {
+    let after_id = match params.cursor.as_deref() {
+        Some(cursor) => match decode_cursor(cursor) {
+            Some(id) => id,
+            None => return bad_request("Invalid cursor").into_response(),
+        },
+        None => String::new(),
+    };
+
+    let header = (params.format == StreamFormat::Csv && params.cursor.is_none()).then(|| {
+        let mut header = CSV_HEADER.to_string();
+        if params.include_text {
+            header.push_str(",extracted_text");
+        }
+        header.push_str(",cursor\n");
+        header
+    });
+    let (content_type, filename) = match params.format {
+        StreamFormat::Jsonl => ("application/x-ndjson", "documents.jsonl"),
+        StreamFormat::Csv => ("text/csv", "documents.csv"),
+    };
+
+    let export = ExportStream {
+        state,
+        format: params.format,
+        types: parse_csv_param(params.types.as_ref()),
+        tags: parse_csv_param(params.tags.as_ref()),
+        source: params.source,
+        include_text: params.include_text,
+        after_id,
+        remaining: params.limit,
+        header,
+    };
+    let stream = futures::stream::unfold(Some(export), |export| async move {
+        let mut export = export?;
+        match export.next_chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk), Some(export))),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!(
+                    "Streaming export failed after document {}: {}",
+                    export.after_id,
+                    e
+                );
+                Some((Err(std::io::Error::other(e.to_string())), None))
+            }
+        }
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from_stream(stream))
+        .unwrap()
+        .into_response()
+}
+
+/// Keyset-paginated state for a streaming export.
+struct ExportStream {
+    state: AppState,
+    format: StreamFormat,
+    source: Option<String>,
+    types: Vec<String>,
+    tags: Vec<String>,
+    include_text: bool,
+    after_id: String,
+    remaining: Option<usize>,
+    header: Option<String>,
+}
+
+impl ExportStream {
+    /// Load and render the next batch, or `None` once the export is done.
+    async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, DieselError> {
+        let limit = match self.remaining {
+            Some(0) => return Ok(self.header.take().map(String::into_bytes)),
+            Some(n) => n.min(STREAM_BATCH_SIZE),
+            None => STREAM_BATCH_SIZE,
+        };
+        let documents = self
+            .state
+            .doc_repo
+            .browse(BrowseParams {
+                source_id: self.source.as_deref(),
+                categories: &self.types,
+                tags: &self.tags,
+                limit: limit as u32,
+                after_id: Some(&self.after_id),
+                ..Default::default()
+            })
+            .await?;
+        let Some(last) = documents.last() else {
+            return Ok(self.header.take().map(String::into_bytes));
+        };
+        self.after_id = last.id.clone();
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(documents.len());
+        }
+
+        let policies = self.state.text_policies(&documents).await;
+
+        let mut output = self
+            .header
+            .take()
+            .map(String::into_bytes)
+            .unwrap_or_default();
+        for doc in documents {
+            write_streamed(&mut output, self.format, doc, &policies, self.include_text);
+        }
+        Ok(Some(output))
+    }
+}
+
+/// Render one document of a streaming export, with its resume cursor.
+fn write_streamed(
+    output: &mut Vec<u8>,
+    format: StreamFormat,
+    doc: Document,
+    policies: &HashMap<String, TextPolicy>,
+    include_text: bool,
+) {
+    let cursor = encode_cursor(&doc.id);
+    let document = ExportDocument::new(doc, policies, include_text);
+    match format {
+        StreamFormat::Jsonl => {
+            if let Ok(line) = serde_json::to_string(&StreamedDocument { document, cursor }) {
+                writeln!(output, "{}", line).ok();
+            }
+        }
+        StreamFormat::Csv => {
+            write!(output, "{}", document.csv_row()).ok();
+            if include_text {
+                let text = document.extracted_text.as_deref().unwrap_or("");
+                write!(output, ",{}", escape_csv(text)).ok();
+            }
+            writeln!(output, ",{}", cursor).ok();
+        }
+    }
+}
+
+/// Encode a document ID as an opaque resume cursor.
+fn encode_cursor(document_id: &str) -> String {
+    URL_SAFE_NO_PAD.encode(document_id)
+}
+
+/// Decode a resume cursor back to the document ID it was emitted after.
+fn decode_cursor(cursor: &str) -> Option<String> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
 fn escape_csv(s: &str) -> String {
-    if s.contains(',') || s.contains('"') || s.contains('\n') {
+    if s.contains(',') || s.contains('"') || s.contains('\n') || s.contains('\r') {
         format!("\"{}\"", s.replace('"', "\"\""))
     } else {
         s.to_string()
@@ -288,13 +526,16 @@ pub async fn export_annotations(
         Err(e) => return internal_error(e).into_response(),
     };
 
+    let policies = state.text_policies(&documents).await;
     let annotations: Vec<AnnotationExport> = documents
         .into_iter()
         .filter(|d| d.synopsis.is_some() || !d.tags.is_empty())
         .map(|d| AnnotationExport {
+            synopsis: policies
+                .get(&d.id)
+                .and_then(|policy| policy.apply(d.synopsis)),
             id: d.id,
             source_url: d.source_url,
-            synopsis: d.synopsis,
             tags: d.tags,
         })
         .collect();
@@ -333,3 +574,74 @@ pub async fn export_annotations(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::public::WITHHELD_TITLE;
+    use foia::models::{DocumentVersion, PiiMask, PiiType};
+
+    fn email_document(id: &str) -> Document {
+        let version = DocumentVersion::new(b"mail", "message/rfc822".to_string(), None);
+        let mut doc = Document::new(
+            id.to_string(),
+            "agency_email".to_string(),
+            "Re: call jdoe@example.com".to_string(),
+            format!("https://example.gov/{}", id),
+            version,
+            serde_json::json!({}),
+        );
+        doc.synopsis = Some("Asks jdoe@example.com to call back".to_string());
+        doc.extracted_text = Some("From: jdoe@example.com".to_string());
+        doc
+    }
+
+    #[test]
+    fn test_stream_applies_policy_to_every_field() {
+        let mask = PiiMask::new([(PiiType::Email, "jdoe@example.com")]);
+        let policies = HashMap::from([
+            ("masked".to_string(), TextPolicy::Masked(mask)),
+            ("withheld".to_string(), TextPolicy::Withheld),
+        ]);
+
+        for format in [StreamFormat::Jsonl, StreamFormat::Csv] {
+            let mut output = Vec::new();
+            for id in ["masked", "withheld", "unknown"] {
+                write_streamed(&mut output, format, email_document(id), &policies, true);
+            }
+            let output = String::from_utf8(output).unwrap();
+            assert!(!output.contains("jdoe@example.com"), "{}", output);
+            assert!(output.contains("[REDACTED EMAIL]"));
+            assert!(output.contains(WITHHELD_TITLE));
+        }
+    }
+
+    #[test]
+    fn test_unmasked_export_keeps_text() {
+        let policies = HashMap::from([("doc".to_string(), TextPolicy::Unmasked)]);
+        let document = ExportDocument::new(email_document("doc"), &policies, false);
+        assert_eq!(document.title, "Re: call jdoe@example.com");
+        assert!(document.synopsis.is_some());
+        assert!(document.extracted_text.is_none());
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        for id in ["", "doc-1", "a/b?c=d&e", "документ"] {
+            let cursor = encode_cursor(id);
+            assert!(cursor
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+            assert_eq!(decode_cursor(&cursor).as_deref(), Some(id));
+        }
+        assert_eq!(decode_cursor("not a cursor!"), None);
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("plain"), "plain");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("line\r\nbreak"), "\"line\r\nbreak\"");
+    }
+}
@@ -4,6 +4,7 @@ use axum::{http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::super::public::TextPolicy;
 use super::super::AppState;
 use super::api_types::ApiResponse;
 use foia::models::{Document, DocumentVersion};
@@ -19,7 +20,6 @@ pub fn not_found(message: &str) -> impl IntoResponse + use<'_> {
 }
 
 /// Create a bad request error response.
-#[allow(dead_code)]
 pub fn bad_request(message: &str) -> impl IntoResponse + use<'_> {
     ApiResponse::error(StatusCode::BAD_REQUEST, message.to_string())
 }
@@ -69,6 +69,15 @@ pub struct DocumentSummary {
     pub current_version: Option<VersionSummary>,
 }
 
+impl DocumentSummary {
+    /// Apply a document's text policy to its title and synopsis.
+    pub fn with_policy(mut self, policy: &TextPolicy) -> Self {
+        self.title = policy.apply_title(self.title);
+        self.synopsis = policy.apply(self.synopsis);
+        self
+    }
+}
+
 impl From<Document> for DocumentSummary {
     fn from(doc: Document) -> Self {
         let current_version = doc
@@ -13,6 +13,8 @@ mod helpers;
 mod ocr;
 pub mod openapi;
 mod pages;
+mod removal_api;
+mod requests_api;
 mod scrape_api;
 mod search_api;
 mod static_files;
@@ -29,14 +31,21 @@ pub use api::{
 };
 pub use browse::browse_documents;
 pub use documents::{document_detail, document_versions};
-pub use documents_api::{get_document, get_document_content, list_documents};
+pub use documents_api::{get_document, get_document_content, list_documents, lookup_bates};
 pub use duplicates::list_duplicates;
 pub use entities_api::{
-    document_entities, entity_locations, entity_types, search_entities, top_entities,
+    correspondents, document_entities, entity_locations, entity_types, search_entities,
+    top_entities,
 };
-pub use export_api::{export_annotations, export_documents, export_stats};
+pub use export_api::{export_annotations, export_documents, export_documents_stream, export_stats};
 pub use ocr::{api_reocr_document, api_reocr_status};
 pub use pages::api_document_pages;
+pub use removal_api::{delete_document, get_legal_hold, place_legal_hold, release_legal_hold};
+pub use requests_api::{
+    create_request, create_request_event, delete_request, get_request, link_request_document,
+    list_request_documents, list_request_events, list_requests, overdue_requests,
+    unlink_request_document, update_request,
+};
 pub use scrape_api::{get_scrape_status, list_queue, list_scrapers, retry_failed};
 pub use search_api::search_content;
 pub use static_files::{serve_css, serve_file, serve_js};
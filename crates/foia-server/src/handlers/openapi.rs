@@ -12,6 +12,8 @@ use super::export_api;
 use super::helpers;
 use super::ocr;
 use super::pages;
+use super::removal_api;
+use super::requests_api;
 use super::scrape_api;
 use super::tags;
 use super::timeline;
@@ -31,6 +33,11 @@ use super::versions_api;
         documents_api::list_documents,
         documents_api::get_document,
         documents_api::get_document_content,
+        documents_api::lookup_bates,
+        removal_api::delete_document,
+        removal_api::get_legal_hold,
+        removal_api::place_legal_hold,
+        removal_api::release_legal_hold,
         // Pages
         pages::api_document_pages,
         // OCR
@@ -52,6 +59,7 @@ use super::versions_api;
         scrape_api::retry_failed,
         // Export
         export_api::export_documents,
+        export_api::export_documents_stream,
         export_api::export_annotations,
         export_api::export_stats,
         // Entities
@@ -60,6 +68,19 @@ use super::versions_api;
         entities_api::top_entities,
         entities_api::entity_locations,
         entities_api::document_entities,
+        entities_api::correspondents,
+        // Requests
+        requests_api::list_requests,
+        requests_api::create_request,
+        requests_api::overdue_requests,
+        requests_api::get_request,
+        requests_api::update_request,
+        requests_api::delete_request,
+        requests_api::list_request_events,
+        requests_api::create_request_event,
+        requests_api::list_request_documents,
+        requests_api::link_request_document,
+        requests_api::unlink_request_document,
         // Timeline
         timeline::timeline_aggregate,
         timeline::timeline_source,
@@ -86,6 +107,10 @@ use super::versions_api;
         // Document API types
         documents_api::DocumentContentResponse,
         documents_api::PageContent,
+        documents_api::BatesLookupResult,
+        removal_api::DeleteResponse,
+        removal_api::LegalHoldResponse,
+        removal_api::LegalHoldRequest,
         // Version API types
         versions_api::VersionResponse,
         api_types::VersionsListResponse,
@@ -113,6 +138,8 @@ use super::versions_api;
         // Export API types
         export_api::ExportFormat,
         export_api::ExportDocument,
+        export_api::StreamFormat,
+        export_api::StreamedDocument,
         api_types::ExportStatsResponse,
         api_types::AnnotationExport,
         // Entity API types
@@ -121,6 +148,16 @@ use super::versions_api;
         entities_api::EntityTypeStats,
         entities_api::TopEntity,
         entities_api::GeocodedLocation,
+        entities_api::CorrespondentNode,
+        entities_api::CorrespondentLink,
+        entities_api::CorrespondentGraph,
+        // Request tracking types
+        requests_api::RequestResponse,
+        requests_api::RequestEventResponse,
+        requests_api::LinkedDocumentResponse,
+        requests_api::CreateRequestBody,
+        requests_api::UpdateRequestBody,
+        requests_api::CreateEventBody,
         // OCR types
         ocr::ReOcrRequest,
         ocr::ReOcrResponse,
@@ -149,6 +186,7 @@ use super::versions_api;
         (name = "Scrapers", description = "Scraper control and monitoring"),
         (name = "Export", description = "Bulk data export"),
         (name = "Entities", description = "NER-extracted entity search"),
+        (name = "Requests", description = "Tracking for FOIA requests we filed"),
         (name = "Timeline", description = "Document timeline visualization"),
         (name = "Status", description = "System status, sources, types, and tags"),
     )
@@ -28,6 +28,8 @@ pub struct PageData {
     pub image_base64: Option<String>,
     pub ocr_status: String,
     pub deepseek_text: Option<String>,
+    /// Bates number stamped on the page, for productions.
+    pub bates_number: Option<String>,
 }
 
 /// Pages API response.
@@ -126,7 +128,10 @@ pub async fn api_document_pages(
         }
     }
 
-    let is_pdf = version.mime_type.contains("pdf");
+    // Rendered page images cannot be masked, so public mode only shows them
+    // for documents scanned clean
+    let policy = state.text_policy(&doc).await;
+    let is_pdf = version.mime_type.contains("pdf") && policy.allows_originals();
     let pdf_path = version.resolve_path(&state.documents_dir, &doc.source_url, &doc.title);
 
     let page_data_list: Vec<PageData> = if is_pdf {
@@ -135,11 +140,12 @@ pub async fn api_document_pages(
             let path = pdf_path.clone();
             let page_num = page.page_number;
             let page_id = page.id;
-            let ocr_text = page.ocr_text;
-            let pdf_text = page.pdf_text;
-            let final_text = page.final_text;
+            let ocr_text = policy.apply(page.ocr_text);
+            let pdf_text = policy.apply(page.pdf_text);
+            let final_text = policy.apply(page.final_text);
             let ocr_status = page.ocr_status.as_str().to_string();
-            let deepseek_text = deepseek_map.get(&page_id).cloned().flatten();
+            let deepseek_text = policy.apply(deepseek_map.get(&page_id).cloned().flatten());
+            let bates_number = page.bates_number;
 
             let handle = tokio::task::spawn_blocking(move || {
                 let image_base64 = render_pdf_page_to_base64(&path, page_num);
@@ -151,6 +157,7 @@ pub async fn api_document_pages(
                     image_base64,
                     ocr_status,
                     deepseek_text,
+                    bates_number,
                 }
             });
             handles.push(handle);
@@ -168,15 +175,16 @@ pub async fn api_document_pages(
         selected_pages
             .into_iter()
             .map(|page| {
-                let deepseek_text = deepseek_map.get(&page.id).cloned().flatten();
+                let deepseek_text = policy.apply(deepseek_map.get(&page.id).cloned().flatten());
                 PageData {
                     page_number: page.page_number,
-                    ocr_text: page.ocr_text,
-                    pdf_text: page.pdf_text,
-                    final_text: page.final_text,
+                    ocr_text: policy.apply(page.ocr_text),
+                    pdf_text: policy.apply(page.pdf_text),
+                    final_text: policy.apply(page.final_text),
                     image_base64: None,
                     ocr_status: page.ocr_status.as_str().to_string(),
                     deepseek_text,
+                    bates_number: page.bates_number,
                 }
             })
             .collect()
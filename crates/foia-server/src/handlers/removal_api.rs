@@ -0,0 +1,269 @@
+//! Document deletion and legal-hold API endpoints.
+//!
+//! These can't be undone, so they answer 404 in public mode and otherwise
+//! need the server's admin token as a bearer token.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::super::AppState;
+use super::api_types::ApiResponse;
+use super::helpers::{internal_error, not_found};
+use foia::repository::diesel_document::{DeleteOutcome, LegalHold};
+use foia::storage::remove_version_files;
+
+const PUBLIC_MODE: &str = "Document removal is not available in public mode";
+
+/// Check that a request comes from the operator.
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), Response> {
+    if state.public {
+        return Err(not_found(PUBLIC_MODE).into_response());
+    }
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err(ApiResponse::error(
+            StatusCode::FORBIDDEN,
+            "Start the server with --admin-token to delete documents or manage legal holds",
+        )
+        .into_response());
+    };
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token {
+        Some(token) if tokens_match(token, expected) => Ok(()),
+        _ => Err(
+            ApiResponse::error(StatusCode::UNAUTHORIZED, "Missing or invalid admin token")
+                .into_response(),
+        ),
+    }
+}
+
+/// Compare tokens without returning early on the first differing byte.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Query parameters for document deletion.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteQuery {
+    /// Reason recorded on the tombstone (e.g. takedown request reference)
+    pub reason: Option<String>,
+    /// Keep stored files on disk (default: false)
+    #[serde(default)]
+    pub keep_files: bool,
+}
+
+/// Result of a document deletion.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteResponse {
+    pub id: String,
+    /// Number of stored files removed from disk
+    pub files_removed: usize,
+    /// Files kept because other documents share their content
+    pub files_shared: usize,
+}
+
+/// Legal hold on a document.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LegalHoldResponse {
+    pub document_id: String,
+    pub reason: Option<String>,
+    pub placed_at: String,
+}
+
+impl From<LegalHold> for LegalHoldResponse {
+    fn from(hold: LegalHold) -> Self {
+        Self {
+            document_id: hold.document_id,
+            reason: hold.reason,
+            placed_at: hold.placed_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Request body for placing a legal hold.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LegalHoldRequest {
+    pub reason: Option<String>,
+}
+
+/// Delete a document, its derived data and files, leaving a tombstone.
+#[utoipa::path(
+    delete,
+    path = "/api/documents/{doc_id}",
+    params(
+        ("doc_id" = String, Path, description = "Document ID"),
+        DeleteQuery,
+    ),
+    responses(
+        (status = 200, description = "Document deleted", body = DeleteResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "No admin token configured"),
+        (status = 409, description = "Document is under legal hold")
+    ),
+    tag = "Documents"
+)]
+pub async fn delete_document(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(doc_id): Path<String>,
+    Query(params): Query<DeleteQuery>,
+) -> impl IntoResponse {
+    if let Err(response) = authorize(&state, &headers) {
+        return response;
+    }
+    let outcome = match state
+        .doc_repo
+        .delete_with_tombstone(&doc_id, params.reason.as_deref())
+        .await
+    {
+        Ok(o) => o,
+        Err(e) => return internal_error(e).into_response(),
+    };
+
+    match outcome {
+        DeleteOutcome::NotFound => not_found("Document not found").into_response(),
+        DeleteOutcome::OnHold(_) => {
+            ApiResponse::error(StatusCode::CONFLICT, "Document is under legal hold").into_response()
+        }
+        DeleteOutcome::Deleted {
+            source_url,
+            title,
+            versions,
+        } => {
+            let (files_removed, files_shared) = if params.keep_files {
+                (0, 0)
+            } else {
+                match remove_version_files(
+                    &state.doc_repo,
+                    &state.documents_dir,
+                    &doc_id,
+                    &source_url,
+                    &title,
+                    &versions,
+                )
+                .await
+                {
+                    Ok(files) => (files.removed.len(), files.shared.len()),
+                    Err(e) => return internal_error(e).into_response(),
+                }
+            };
+
+            ApiResponse::ok(DeleteResponse {
+                id: doc_id,
+                files_removed,
+                files_shared,
+            })
+            .into_response()
+        }
+    }
+}
+
+/// Get the legal hold on a document.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{doc_id}/hold",
+    params(("doc_id" = String, Path, description = "Document ID")),
+    responses(
+        (status = 200, description = "Legal hold", body = LegalHoldResponse),
+        (status = 404, description = "Document is not under legal hold"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "No admin token configured")
+    ),
+    tag = "Documents"
+)]
+pub async fn get_legal_hold(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(doc_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = authorize(&state, &headers) {
+        return response;
+    }
+    match state.doc_repo.get_legal_hold(&doc_id).await {
+        Ok(Some(hold)) => ApiResponse::ok(LegalHoldResponse::from(hold)).into_response(),
+        Ok(None) => not_found("Document is not under legal hold").into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Place a legal hold on a document, blocking deletion and dedup merges.
+#[utoipa::path(
+    put,
+    path = "/api/documents/{doc_id}/hold",
+    params(("doc_id" = String, Path, description = "Document ID")),
+    request_body = LegalHoldRequest,
+    responses(
+        (status = 200, description = "Legal hold placed", body = LegalHoldResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "No admin token configured")
+    ),
+    tag = "Documents"
+)]
+pub async fn place_legal_hold(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(doc_id): Path<String>,
+    Json(body): Json<LegalHoldRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = authorize(&state, &headers) {
+        return response;
+    }
+    match state
+        .doc_repo
+        .place_legal_hold(&doc_id, body.reason.as_deref())
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return not_found("Document not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    }
+
+    match state.doc_repo.get_legal_hold(&doc_id).await {
+        Ok(Some(hold)) => ApiResponse::ok(LegalHoldResponse::from(hold)).into_response(),
+        Ok(None) => not_found("Document not found").into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Release the legal hold on a document.
+#[utoipa::path(
+    delete,
+    path = "/api/documents/{doc_id}/hold",
+    params(("doc_id" = String, Path, description = "Document ID")),
+    responses(
+        (status = 200, description = "Legal hold released"),
+        (status = 404, description = "Document is not under legal hold"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "No admin token configured")
+    ),
+    tag = "Documents"
+)]
+pub async fn release_legal_hold(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(doc_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(response) = authorize(&state, &headers) {
+        return response;
+    }
+    match state.doc_repo.release_legal_hold(&doc_id).await {
+        Ok(true) => ApiResponse::ok(doc_id).into_response(),
+        Ok(false) => not_found("Document is not under legal hold").into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
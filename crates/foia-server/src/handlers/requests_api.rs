@@ -0,0 +1,671 @@
+//! Tracking API for FOIA requests we filed.
+//!
+//! None of this is published: every endpoint answers 404 in public mode.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::super::AppState;
+use super::api_types::ApiResponse;
+use super::helpers::{bad_request, internal_error, not_found};
+use foia::models::{
+    DeadlineRule, FoiaRequest, RequestEvent, RequestEventKind, RequestStatus, DEFAULT_RESPONSE_DAYS,
+};
+use foia::repository::diesel_foia_request::{FoiaRequestFilter, LinkedDocument, NewRequestEvent};
+use foia::repository::DieselError;
+
+const PUBLIC_MODE: &str = "Request tracking is not available in public mode";
+
+/// A tracked FOIA request with its computed deadline.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RequestResponse {
+    pub id: String,
+    pub agency: String,
+    pub tracking_number: Option<String>,
+    pub subject: String,
+    pub description: Option<String>,
+    pub status: String,
+    /// Date the agency received the request (YYYY-MM-DD)
+    pub filed_at: Option<String>,
+    /// Statutory response deadline including extensions (YYYY-MM-DD)
+    pub due_date: Option<String>,
+    /// Days left until the deadline, negative once overdue (open requests only)
+    pub days_remaining: Option<i64>,
+    pub overdue: bool,
+    /// "business" or "calendar"
+    pub deadline_rule: String,
+    pub response_days: u32,
+    pub extension_days: u32,
+    pub closed_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl RequestResponse {
+    fn new(request: FoiaRequest, today: NaiveDate) -> Self {
+        Self {
+            due_date: request.due_date().map(|d| d.to_string()),
+            days_remaining: request.days_remaining(today),
+            overdue: request.is_overdue(today),
+            id: request.id,
+            agency: request.agency,
+            tracking_number: request.tracking_number,
+            subject: request.subject,
+            description: request.description,
+            status: request.status.as_str().to_string(),
+            filed_at: request.filed_at.map(|d| d.to_string()),
+            deadline_rule: request.deadline_rule.as_str().to_string(),
+            response_days: request.response_days,
+            extension_days: request.extension_days,
+            closed_at: request.closed_at.map(|d| d.to_string()),
+            created_at: request.created_at.to_rfc3339(),
+            updated_at: request.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// An entry in a request's history.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RequestEventResponse {
+    pub id: i64,
+    /// status, sent, received or note
+    pub kind: String,
+    /// New status, for status changes
+    pub status: Option<String>,
+    pub summary: Option<String>,
+    pub body: Option<String>,
+    pub document_id: Option<String>,
+    pub occurred_at: String,
+}
+
+impl From<RequestEvent> for RequestEventResponse {
+    fn from(event: RequestEvent) -> Self {
+        Self {
+            id: event.id,
+            kind: event.kind.as_str().to_string(),
+            status: event.status.map(|s| s.as_str().to_string()),
+            summary: event.summary,
+            body: event.body,
+            document_id: event.document_id,
+            occurred_at: event.occurred_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A document released in response to a request.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinkedDocumentResponse {
+    pub document_id: String,
+    pub title: String,
+    pub source_id: String,
+    pub linked_at: String,
+}
+
+impl From<LinkedDocument> for LinkedDocumentResponse {
+    fn from(doc: LinkedDocument) -> Self {
+        Self {
+            document_id: doc.document_id,
+            title: doc.title,
+            source_id: doc.source_id,
+            linked_at: doc.linked_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Query parameters for listing requests.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListRequestsQuery {
+    /// Filter by status
+    pub status: Option<String>,
+    /// Filter by agency (case-insensitive substring)
+    pub agency: Option<String>,
+    /// Only requests still awaiting a response
+    #[serde(default)]
+    pub open: bool,
+}
+
+/// Query parameters for the overdue report.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OverdueQuery {
+    /// Also include requests due within this many days
+    pub within: Option<i64>,
+}
+
+/// Request body for recording a new request.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateRequestBody {
+    pub agency: String,
+    pub subject: String,
+    pub tracking_number: Option<String>,
+    pub description: Option<String>,
+    /// Date the agency received the request (YYYY-MM-DD); omit for a draft
+    pub filed_at: Option<String>,
+    /// Days the agency has to respond (default 20)
+    pub response_days: Option<u32>,
+    /// "business" (default) or "calendar"
+    pub deadline_rule: Option<String>,
+}
+
+/// Request body for changing a request's details. Omitted fields are kept.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateRequestBody {
+    pub agency: Option<String>,
+    pub subject: Option<String>,
+    pub tracking_number: Option<String>,
+    pub description: Option<String>,
+    /// Date the agency received the request (YYYY-MM-DD)
+    pub filed_at: Option<String>,
+    pub response_days: Option<u32>,
+    pub extension_days: Option<u32>,
+    /// "business" or "calendar"
+    pub deadline_rule: Option<String>,
+}
+
+/// Request body for adding to a request's history.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateEventBody {
+    /// status, sent, received or note
+    pub kind: String,
+    /// New status (required when kind is "status")
+    pub status: Option<String>,
+    pub summary: Option<String>,
+    /// Full text of the correspondence
+    pub body: Option<String>,
+    /// Stored document holding the correspondence
+    pub document_id: Option<String>,
+    /// Date of the event (YYYY-MM-DD, default today)
+    pub date: Option<String>,
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}' (expected YYYY-MM-DD)", s))
+}
+
+fn parse_status(s: &str) -> Result<RequestStatus, String> {
+    RequestStatus::from_str(s).ok_or_else(|| format!("Unknown status '{}'", s))
+}
+
+fn parse_rule(s: &str) -> Result<DeadlineRule, String> {
+    DeadlineRule::from_str(s).ok_or_else(|| format!("Unknown deadline rule '{}'", s))
+}
+
+fn event_time(date: Option<&str>) -> Result<DateTime<Utc>, String> {
+    match date {
+        Some(d) => Ok(parse_date(d)?.and_time(NaiveTime::MIN).and_utc()),
+        None => Ok(Utc::now()),
+    }
+}
+
+/// List tracked requests.
+#[utoipa::path(
+    get,
+    path = "/api/requests",
+    params(ListRequestsQuery),
+    responses(
+        (status = 200, description = "Requests, most recently filed first", body = Vec<RequestResponse>),
+        (status = 404, description = "Not available in public mode")
+    ),
+    tag = "Requests"
+)]
+pub async fn list_requests(
+    State(state): State<AppState>,
+    Query(params): Query<ListRequestsQuery>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let status = match params.status.as_deref().map(parse_status).transpose() {
+        Ok(s) => s,
+        Err(e) => return bad_request(&e).into_response(),
+    };
+    let filter = FoiaRequestFilter {
+        status,
+        agency: params.agency,
+        open_only: params.open,
+    };
+
+    let today = today();
+    match state.request_repo.list(&filter).await {
+        Ok(requests) => ApiResponse::ok(
+            requests
+                .into_iter()
+                .map(|r| RequestResponse::new(r, today))
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Open requests past their statutory deadline, or due within `within` days.
+#[utoipa::path(
+    get,
+    path = "/api/requests/overdue",
+    params(OverdueQuery),
+    responses(
+        (status = 200, description = "Requests by days remaining, most overdue first", body = Vec<RequestResponse>),
+        (status = 404, description = "Not available in public mode")
+    ),
+    tag = "Requests"
+)]
+pub async fn overdue_requests(
+    State(state): State<AppState>,
+    Query(params): Query<OverdueQuery>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let today = today();
+    match state
+        .request_repo
+        .due_within(today, params.within.unwrap_or(-1))
+        .await
+    {
+        Ok(due) => ApiResponse::ok(
+            due.into_iter()
+                .map(|(r, _)| RequestResponse::new(r, today))
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Record a new request.
+#[utoipa::path(
+    post,
+    path = "/api/requests",
+    request_body = CreateRequestBody,
+    responses(
+        (status = 200, description = "Request recorded", body = RequestResponse),
+        (status = 400, description = "Invalid date or deadline rule"),
+        (status = 404, description = "Not available in public mode")
+    ),
+    tag = "Requests"
+)]
+pub async fn create_request(
+    State(state): State<AppState>,
+    Json(body): Json<CreateRequestBody>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let filed_at = match body.filed_at.as_deref().map(|d| event_time(Some(d))) {
+        Some(Err(e)) => return bad_request(&e).into_response(),
+        Some(Ok(at)) => Some(at),
+        None => None,
+    };
+    let mut request = FoiaRequest::new(&body.agency, &body.subject);
+    request.tracking_number = body.tracking_number;
+    request.description = body.description;
+    request.response_days = body.response_days.unwrap_or(DEFAULT_RESPONSE_DAYS);
+    if let Some(rule) = body.deadline_rule.as_deref() {
+        match parse_rule(rule) {
+            Ok(rule) => request.deadline_rule = rule,
+            Err(e) => return bad_request(&e).into_response(),
+        }
+    }
+
+    if let Err(e) = state.request_repo.create(&request).await {
+        return internal_error(e).into_response();
+    }
+    if let Some(at) = filed_at {
+        match state
+            .request_repo
+            .set_status(&request.id, RequestStatus::Filed, at, None)
+            .await
+        {
+            Ok(Some(filed)) => request = filed,
+            Ok(None) => {}
+            Err(e) => return internal_error(e).into_response(),
+        }
+    }
+    ApiResponse::ok(RequestResponse::new(request, today())).into_response()
+}
+
+/// Get a request by ID or agency tracking number.
+#[utoipa::path(
+    get,
+    path = "/api/requests/{id}",
+    params(("id" = String, Path, description = "Request ID or agency tracking number")),
+    responses(
+        (status = 200, description = "Request", body = RequestResponse),
+        (status = 404, description = "Request not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn get_request(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    match state.request_repo.find(&id).await {
+        Ok(Some(request)) => {
+            ApiResponse::ok(RequestResponse::new(request, today())).into_response()
+        }
+        Ok(None) => not_found("Request not found").into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Change a request's details. Use the events endpoint to change status.
+#[utoipa::path(
+    put,
+    path = "/api/requests/{id}",
+    params(("id" = String, Path, description = "Request ID or agency tracking number")),
+    request_body = UpdateRequestBody,
+    responses(
+        (status = 200, description = "Updated request", body = RequestResponse),
+        (status = 400, description = "Invalid date or deadline rule"),
+        (status = 404, description = "Request not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn update_request(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<UpdateRequestBody>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let mut request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+
+    if let Some(filed) = body.filed_at.as_deref() {
+        match parse_date(filed) {
+            Ok(date) => request.filed_at = Some(date),
+            Err(e) => return bad_request(&e).into_response(),
+        }
+    }
+    if let Some(rule) = body.deadline_rule.as_deref() {
+        match parse_rule(rule) {
+            Ok(rule) => request.deadline_rule = rule,
+            Err(e) => return bad_request(&e).into_response(),
+        }
+    }
+    if let Some(agency) = body.agency {
+        request.agency = agency;
+    }
+    if let Some(subject) = body.subject {
+        request.subject = subject;
+    }
+    if let Some(tracking) = body.tracking_number {
+        request.tracking_number = Some(tracking).filter(|t| !t.is_empty());
+    }
+    if let Some(description) = body.description {
+        request.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if let Some(days) = body.response_days {
+        request.response_days = days;
+    }
+    if let Some(days) = body.extension_days {
+        request.extension_days = days;
+    }
+
+    if let Err(e) = state.request_repo.update(&request).await {
+        return internal_error(e).into_response();
+    }
+    ApiResponse::ok(RequestResponse::new(request, today())).into_response()
+}
+
+/// Delete a request and its history. Linked documents are kept.
+#[utoipa::path(
+    delete,
+    path = "/api/requests/{id}",
+    params(("id" = String, Path, description = "Request ID or agency tracking number")),
+    responses(
+        (status = 200, description = "Request deleted"),
+        (status = 404, description = "Request not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn delete_request(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+    match state.request_repo.delete(&request.id).await {
+        Ok(_) => ApiResponse::ok(request.id).into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// A request's status history, correspondence and notes, oldest first.
+#[utoipa::path(
+    get,
+    path = "/api/requests/{id}/events",
+    params(("id" = String, Path, description = "Request ID or agency tracking number")),
+    responses(
+        (status = 200, description = "Request history", body = Vec<RequestEventResponse>),
+        (status = 404, description = "Request not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn list_request_events(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+    match state.request_repo.events(&request.id).await {
+        Ok(events) => ApiResponse::ok(
+            events
+                .into_iter()
+                .map(RequestEventResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Add to a request's history. A "status" event changes the request's
+/// status; filing starts the deadline clock.
+#[utoipa::path(
+    post,
+    path = "/api/requests/{id}/events",
+    params(("id" = String, Path, description = "Request ID or agency tracking number")),
+    request_body = CreateEventBody,
+    responses(
+        (status = 200, description = "Request after the event", body = RequestResponse),
+        (status = 400, description = "Invalid kind, status or date"),
+        (status = 404, description = "Request or document not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn create_request_event(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<CreateEventBody>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let Some(kind) = RequestEventKind::from_str(&body.kind) else {
+        return bad_request("kind must be one of status, sent, received, note").into_response();
+    };
+    let at = match event_time(body.date.as_deref()) {
+        Ok(at) => at,
+        Err(e) => return bad_request(&e).into_response(),
+    };
+    let request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+
+    if kind == RequestEventKind::Status {
+        let status = match body.status.as_deref().map(parse_status) {
+            Some(Ok(status)) => status,
+            Some(Err(e)) => return bad_request(&e).into_response(),
+            None => return bad_request("status is required for status events").into_response(),
+        };
+        return match state
+            .request_repo
+            .set_status(&request.id, status, at, body.summary.as_deref())
+            .await
+        {
+            Ok(Some(updated)) => {
+                ApiResponse::ok(RequestResponse::new(updated, today())).into_response()
+            }
+            Ok(None) => not_found("Request not found").into_response(),
+            Err(e) => internal_error(e).into_response(),
+        };
+    }
+
+    if let Some(doc_id) = body.document_id.as_deref() {
+        match state.doc_repo.get(doc_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return not_found("Document not found").into_response(),
+            Err(e) => return internal_error(e).into_response(),
+        }
+    }
+    let event = NewRequestEvent {
+        summary: body.summary.as_deref(),
+        body: body.body.as_deref(),
+        document_id: body.document_id.as_deref(),
+        occurred_at: Some(at),
+    };
+    if let Err(e) = state
+        .request_repo
+        .add_event(&request.id, kind, &event)
+        .await
+    {
+        return internal_error(e).into_response();
+    }
+    ApiResponse::ok(RequestResponse::new(request, today())).into_response()
+}
+
+/// Documents released in response to a request.
+#[utoipa::path(
+    get,
+    path = "/api/requests/{id}/documents",
+    params(("id" = String, Path, description = "Request ID or agency tracking number")),
+    responses(
+        (status = 200, description = "Linked documents", body = Vec<LinkedDocumentResponse>),
+        (status = 404, description = "Request not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn list_request_documents(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+    match state.request_repo.documents(&request.id).await {
+        Ok(docs) => ApiResponse::ok(
+            docs.into_iter()
+                .map(LinkedDocumentResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Link a released document to a request.
+#[utoipa::path(
+    put,
+    path = "/api/requests/{id}/documents/{doc_id}",
+    params(
+        ("id" = String, Path, description = "Request ID or agency tracking number"),
+        ("doc_id" = String, Path, description = "Document ID"),
+    ),
+    responses(
+        (status = 200, description = "Document linked (idempotent)"),
+        (status = 404, description = "Request or document not found")
+    ),
+    tag = "Requests"
+)]
+pub async fn link_request_document(
+    State(state): State<AppState>,
+    Path((id, doc_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+    match state.request_repo.link_document(&request.id, &doc_id).await {
+        Ok(_) => ApiResponse::ok(doc_id).into_response(),
+        Err(DieselError::NotFound) => not_found("Document not found").into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+/// Remove a document link from a request.
+#[utoipa::path(
+    delete,
+    path = "/api/requests/{id}/documents/{doc_id}",
+    params(
+        ("id" = String, Path, description = "Request ID or agency tracking number"),
+        ("doc_id" = String, Path, description = "Document ID"),
+    ),
+    responses(
+        (status = 200, description = "Document unlinked"),
+        (status = 404, description = "Request not found or document not linked")
+    ),
+    tag = "Requests"
+)]
+pub async fn unlink_request_document(
+    State(state): State<AppState>,
+    Path((id, doc_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    if state.public {
+        return not_found(PUBLIC_MODE).into_response();
+    }
+    let request = match state.request_repo.find(&id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return not_found("Request not found").into_response(),
+        Err(e) => return internal_error(e).into_response(),
+    };
+    match state
+        .request_repo
+        .unlink_document(&request.id, &doc_id)
+        .await
+    {
+        Ok(true) => ApiResponse::ok(doc_id).into_response(),
+        Ok(false) => not_found("Document is not linked to this request").into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
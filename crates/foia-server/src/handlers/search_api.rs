@@ -1,5 +1,7 @@
 //! Full-text search API endpoint for page content.
 
+use std::collections::HashMap;
+
 use axum::{
     extract::{Query, State},
     response::IntoResponse,
@@ -8,6 +10,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+use super::super::public::TextPolicy;
 use super::super::AppState;
 use super::helpers::{bad_request, internal_error, paginate, PaginatedResponse};
 use foia::models::DocumentVersion;
@@ -86,9 +89,28 @@ pub async fn search_content(
         Err(e) => return internal_error(e).into_response(),
     };
 
+    let mut policies: HashMap<String, TextPolicy> = HashMap::new();
+    if state.public {
+        for r in &rows {
+            if !policies.contains_key(&r.document_id) {
+                let policy = state.text_policy_by_id(&r.document_id).await;
+                policies.insert(r.document_id.clone(), policy);
+            }
+        }
+    }
+
     let items: Vec<SearchResult> = rows
         .into_iter()
-        .map(|r| {
+        .filter_map(|r| {
+            let headline = match policies.get(&r.document_id) {
+                None => r.headline,
+                // Unscanned text stays unpublished
+                Some(TextPolicy::Withheld) => return None,
+                // A query for one of the document's PII values must not reveal
+                // which document contains it
+                Some(TextPolicy::Masked(mask)) if mask.mask(q) != q => return None,
+                Some(policy) => policy.apply_str(&r.headline),
+            };
             let file_url = DocumentVersion::build_file_url(
                 &r.content_hash,
                 &r.version_mime_type,
@@ -97,14 +119,14 @@ pub async fn search_content(
                 &r.source_url,
                 &r.title,
             );
-            SearchResult {
+            Some(SearchResult {
                 document_id: r.document_id,
                 title: r.title,
                 source_id: r.source_id,
                 page_number: r.page_number,
-                headline: r.headline,
+                headline,
                 file_url,
-            }
+            })
         })
         .collect();
 
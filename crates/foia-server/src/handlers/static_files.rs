@@ -40,6 +40,16 @@ pub async fn serve_file(
         return (StatusCode::NOT_FOUND, "File not found").into_response();
     }
 
+    // Original files cannot be masked; public mode serves only files whose
+    // documents were scanned and found free of PII
+    if !state.allows_original_file(&path).await {
+        return (
+            StatusCode::FORBIDDEN,
+            "Original file withheld: document may contain personal data",
+        )
+            .into_response();
+    }
+
     let file_path = canonical_docs_dir.join(&path);
 
     let canonical_file = match file_path.canonicalize() {
@@ -9,6 +9,7 @@
 mod assets;
 mod cache;
 mod handlers;
+mod public;
 mod routes;
 mod template_structs;
 
@@ -20,7 +21,10 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use foia::config::Settings;
-use foia::repository::{DieselCrawlRepository, DieselDocumentRepository, DieselSourceRepository};
+use foia::repository::{
+    DieselCrawlRepository, DieselDocumentRepository, DieselFoiaRequestRepository,
+    DieselSourceRepository,
+};
 
 use cache::StatsCache;
 
@@ -45,30 +49,52 @@ pub struct AppState {
     pub doc_repo: Arc<DieselDocumentRepository>,
     pub source_repo: Arc<DieselSourceRepository>,
     pub crawl_repo: Arc<DieselCrawlRepository>,
+    pub request_repo: Arc<DieselFoiaRequestRepository>,
     pub documents_dir: PathBuf,
     pub stats_cache: Arc<StatsCache>,
     /// DeepSeek OCR job status (only one can run at a time).
     pub deepseek_job: Arc<RwLock<DeepSeekJobStatus>>,
+    /// Public mode: mask detected PII and withhold unscanned documents.
+    pub public: bool,
+    /// Bearer token required to delete documents and manage legal holds.
+    /// Without one, those endpoints are disabled.
+    pub admin_token: Option<String>,
 }
 
 impl AppState {
-    pub async fn new(settings: &Settings) -> anyhow::Result<Self> {
+    pub async fn new(
+        settings: &Settings,
+        public: bool,
+        admin_token: Option<String>,
+    ) -> anyhow::Result<Self> {
         let ctx = settings.create_db_context()?;
 
         Ok(Self {
             doc_repo: Arc::new(ctx.documents()),
             source_repo: Arc::new(ctx.sources()),
             crawl_repo: Arc::new(ctx.crawl()),
+            request_repo: Arc::new(ctx.foia_requests()),
             documents_dir: settings.documents_dir.clone(),
             stats_cache: Arc::new(StatsCache::new()),
             deepseek_job: Arc::new(RwLock::new(DeepSeekJobStatus::default())),
+            public,
+            admin_token,
         })
     }
 }
 
 /// Start the web server.
-pub async fn serve(settings: &Settings, host: &str, port: u16) -> anyhow::Result<()> {
-    let state = AppState::new(settings).await?;
+///
+/// With `public` set, text is published with detected PII masked (see `public`).
+/// Deleting documents and managing legal holds needs `admin_token`.
+pub async fn serve(
+    settings: &Settings,
+    host: &str,
+    port: u16,
+    public: bool,
+    admin_token: Option<String>,
+) -> anyhow::Result<()> {
+    let state = AppState::new(settings, public, admin_token).await?;
     let app = create_router(state);
 
     let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
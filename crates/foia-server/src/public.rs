@@ -0,0 +1,191 @@
+//! Public mode: publish documents without re-leaking personal data.
+//!
+//! When the server runs with `--public`, text is masked using the PII spans
+//! stored by `foia detect-pii`. Documents whose current version has not been
+//! scanned yet have their text withheld, and original files or page images are only served
+//! for documents that were scanned and found clean, since neither can be
+//! masked. Stored data is never modified, so public mode is fully reversible.
+//! Requests that could change stored data are refused.
+
+use std::collections::HashMap;
+
+use axum::extract::{Request, State};
+use axum::http::{Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use foia::models::{pii_scanned_version, Document, PiiMask};
+
+use super::handlers::api_types::ApiResponse;
+use super::AppState;
+
+/// Refuse every request but reads in public mode.
+pub async fn read_only(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if state.public
+        && !matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        )
+    {
+        return ApiResponse::error(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "The server is read-only in public mode",
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+/// Title shown for documents whose text is withheld.
+pub const WITHHELD_TITLE: &str = "[withheld until scanned for PII]";
+
+/// How a document's text may be published.
+#[derive(Debug, Clone)]
+pub enum TextPolicy {
+    /// Public mode is off; serve text as stored.
+    Unmasked,
+    /// Scanned for PII; mask detected values (the mask may be empty).
+    Masked(PiiMask),
+    /// Not scanned (or the scan failed); publish no text.
+    Withheld,
+}
+
+impl TextPolicy {
+    /// Apply the policy to a piece of text.
+    pub fn apply(&self, text: Option<String>) -> Option<String> {
+        match self {
+            Self::Unmasked => text,
+            Self::Masked(mask) => mask.mask_opt(text),
+            Self::Withheld => None,
+        }
+    }
+
+    /// Apply the policy to text that must be returned as a string.
+    pub fn apply_str(&self, text: &str) -> String {
+        self.apply(Some(text.to_string())).unwrap_or_default()
+    }
+
+    /// Apply the policy to a document title.
+    ///
+    /// Titles can come from the text (e.g. email subjects), so they follow
+    /// the text policy, with a placeholder where the text is withheld.
+    pub fn apply_title(&self, title: String) -> String {
+        match self {
+            Self::Withheld => WITHHELD_TITLE.to_string(),
+            _ => self.apply(Some(title)).unwrap_or_default(),
+        }
+    }
+
+    /// Whether original files and rendered page images may be served.
+    pub fn allows_originals(&self) -> bool {
+        match self {
+            Self::Unmasked => true,
+            Self::Masked(mask) => mask.is_empty(),
+            Self::Withheld => false,
+        }
+    }
+}
+
+/// Whether the document's current version has a successful PII scan.
+///
+/// A scan of an earlier version doesn't count: the new content may hold PII
+/// the recorded spans don't cover.
+pub fn is_pii_scanned(doc: &Document) -> bool {
+    pii_scanned_version(&doc.metadata)
+        .is_some_and(|v| doc.current_version().map(|c| c.id) == Some(v))
+}
+
+impl AppState {
+    /// Resolve the text policy for a document.
+    pub async fn text_policy(&self, doc: &Document) -> TextPolicy {
+        if !self.public {
+            return TextPolicy::Unmasked;
+        }
+        if !is_pii_scanned(doc) {
+            return TextPolicy::Withheld;
+        }
+        match self.doc_repo.get_pii_mask(&doc.id).await {
+            Ok(mask) => TextPolicy::Masked(mask),
+            Err(e) => {
+                tracing::warn!("Failed to load PII spans for {}: {}", doc.id, e);
+                TextPolicy::Withheld
+            }
+        }
+    }
+
+    /// Resolve text policies for many documents with a single span query.
+    pub async fn text_policies(&self, docs: &[Document]) -> HashMap<String, TextPolicy> {
+        if !self.public {
+            return docs
+                .iter()
+                .map(|d| (d.id.clone(), TextPolicy::Unmasked))
+                .collect();
+        }
+
+        let ids: Vec<String> = docs.iter().map(|d| d.id.clone()).collect();
+        let mut masks = match self.doc_repo.get_pii_masks_batch(&ids).await {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::warn!("Failed to load PII spans: {}", e);
+                return docs
+                    .iter()
+                    .map(|d| (d.id.clone(), TextPolicy::Withheld))
+                    .collect();
+            }
+        };
+
+        docs.iter()
+            .map(|d| {
+                let policy = if is_pii_scanned(d) {
+                    TextPolicy::Masked(masks.remove(&d.id).unwrap_or_default())
+                } else {
+                    TextPolicy::Withheld
+                };
+                (d.id.clone(), policy)
+            })
+            .collect()
+    }
+
+    /// Resolve the text policy for a document by ID.
+    ///
+    /// Unknown documents are withheld in public mode.
+    pub async fn text_policy_by_id(&self, doc_id: &str) -> TextPolicy {
+        if !self.public {
+            return TextPolicy::Unmasked;
+        }
+        match self.doc_repo.get(doc_id).await {
+            Ok(Some(doc)) => self.text_policy(&doc).await,
+            _ => TextPolicy::Withheld,
+        }
+    }
+
+    /// Whether a stored file (path relative to the documents directory) may be
+    /// served. Every document referencing the file must allow originals.
+    pub async fn allows_original_file(&self, relative_path: &str) -> bool {
+        if !self.public {
+            return true;
+        }
+
+        // Stored files are named "<name>-<first 8 hash chars>.<ext>"
+        let Some(prefix) = std::path::Path::new(relative_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.rsplit_once('-'))
+            .map(|(_, hash)| hash)
+            .filter(|h| h.len() == 8 && h.chars().all(|c| c.is_ascii_hexdigit()))
+        else {
+            return false;
+        };
+
+        let doc_ids = match self.doc_repo.find_document_ids_by_hash_prefix(prefix).await {
+            Ok(ids) if !ids.is_empty() => ids,
+            _ => return false,
+        };
+        for id in doc_ids {
+            if !self.text_policy_by_id(&id).await.allows_originals() {
+                return false;
+            }
+        }
+        true
+    }
+}
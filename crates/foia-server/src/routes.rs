@@ -1,15 +1,19 @@
 //! Router configuration for the web server.
 
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{get, post, put},
     Router,
 };
 use tower_http::cors::CorsLayer;
 
 use super::handlers;
+use super::public;
 use super::AppState;
 
 /// Create the main router with all routes.
+///
+/// In public mode, only GET, HEAD and OPTIONS requests are served.
 pub fn create_router(state: AppState) -> Router {
     Router::new()
         // Health check for container orchestration
@@ -38,7 +42,16 @@ pub fn create_router(state: AppState) -> Router {
         // ===========================================
         // Documents API - search, filter, paginate
         .route("/api/documents", get(handlers::list_documents))
-        .route("/api/documents/:doc_id", get(handlers::get_document))
+        .route(
+            "/api/documents/:doc_id",
+            get(handlers::get_document).delete(handlers::delete_document),
+        )
+        .route(
+            "/api/documents/:doc_id/hold",
+            get(handlers::get_legal_hold)
+                .put(handlers::place_legal_hold)
+                .delete(handlers::release_legal_hold),
+        )
         .route(
             "/api/documents/:doc_id/content",
             get(handlers::get_document_content),
@@ -65,6 +78,7 @@ pub fn create_router(state: AppState) -> Router {
             get(handlers::get_version),
         )
         .route("/api/versions/hash/:hash", get(handlers::find_by_hash))
+        .route("/api/bates/:bates", get(handlers::lookup_bates))
         // Annotations API - LLM-generated metadata
         .route("/api/annotations", get(handlers::list_annotations))
         .route("/api/annotations/stats", get(handlers::annotation_stats))
@@ -79,6 +93,10 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/scrapers/retry", post(handlers::retry_failed))
         // Export API - bulk data export
         .route("/api/export/documents", get(handlers::export_documents))
+        .route(
+            "/api/export/documents/stream",
+            get(handlers::export_documents_stream),
+        )
         .route("/api/export/annotations", get(handlers::export_annotations))
         .route("/api/export/stats", get(handlers::export_stats))
         // Search API - full-text page content search
@@ -92,6 +110,31 @@ pub fn create_router(state: AppState) -> Router {
             "/api/documents/:doc_id/entities",
             get(handlers::document_entities),
         )
+        .route("/api/correspondents", get(handlers::correspondents))
+        // Requests API - tracking for FOIA requests we filed
+        .route(
+            "/api/requests",
+            get(handlers::list_requests).post(handlers::create_request),
+        )
+        .route("/api/requests/overdue", get(handlers::overdue_requests))
+        .route(
+            "/api/requests/:id",
+            get(handlers::get_request)
+                .put(handlers::update_request)
+                .delete(handlers::delete_request),
+        )
+        .route(
+            "/api/requests/:id/events",
+            get(handlers::list_request_events).post(handlers::create_request_event),
+        )
+        .route(
+            "/api/requests/:id/documents",
+            get(handlers::list_request_documents),
+        )
+        .route(
+            "/api/requests/:id/documents/:doc_id",
+            put(handlers::link_request_document).delete(handlers::unlink_request_document),
+        )
         // Legacy/existing API endpoints
         .route("/api/timeline", get(handlers::timeline_aggregate))
         .route("/api/timeline/:source_id", get(handlers::timeline_source))
@@ -104,8 +147,66 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/types", get(handlers::api_type_stats))
         .route("/api/sources", get(handlers::api_sources))
         // OpenAPI spec
-        .route("/api", get(handlers::openapi_spec).options(handlers::openapi_spec))
+        .route(
+            "/api",
+            get(handlers::openapi_spec).options(handlers::openapi_spec),
+        )
         .route("/api/openapi.json", get(handlers::openapi_spec))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            public::read_only,
+        ))
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use foia::config::Settings;
+    use tower::ServiceExt;
+
+    async fn status(router: &Router, method: Method, uri: &str) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_public_mode_rejects_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Settings::with_data_dir(dir.path().to_path_buf());
+        let state = AppState::new(&settings, true, Some("secret".to_string()))
+            .await
+            .unwrap();
+        let router = create_router(state);
+
+        for (method, uri) in [
+            (Method::PUT, "/api/annotations/doc-1"),
+            (Method::POST, "/api/documents/doc-1/reocr"),
+            (Method::POST, "/api/scrapers/retry"),
+            (Method::DELETE, "/api/documents/doc-1"),
+            (Method::PUT, "/api/documents/doc-1/hold"),
+            (Method::DELETE, "/api/documents/doc-1/hold"),
+            (Method::POST, "/api/requests"),
+        ] {
+            assert_eq!(
+                status(&router, method.clone(), uri).await,
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{} {}",
+                method,
+                uri
+            );
+        }
+        assert_eq!(
+            status(&router, Method::GET, "/health").await,
+            StatusCode::OK
+        );
+    }
+}
@@ -64,6 +64,16 @@ pub struct VirtualFileRow {
     pub status_badge: String,
 }
 
+/// Helper struct for a message in an email thread view.
+pub struct ThreadMessageRow {
+    pub id: String,
+    pub subject: String,
+    pub sender: String,
+    pub date_str: String,
+    pub depth: i32,
+    pub is_current: bool,
+}
+
 /// Helper struct for type statistics.
 pub struct TypeStat {
     pub category: String,
@@ -163,11 +173,15 @@ pub struct DocumentDetailTemplate<'a> {
     pub has_versions: bool,
     pub other_sources: Vec<String>,
     pub has_other_sources: bool,
+    pub has_bates: bool,
+    pub bates_range_val: String,
     pub has_extracted_text: bool,
     pub extracted_text_val: String,
     pub virtual_files: Vec<VirtualFileRow>,
     pub has_virtual_files: bool,
     pub virtual_files_count: usize,
+    pub thread_messages: Vec<ThreadMessageRow>,
+    pub has_thread: bool,
     pub has_prev: bool,
     pub prev_id_val: String,
     pub prev_title_val: String,
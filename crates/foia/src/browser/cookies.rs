@@ -2,6 +2,7 @@
 
 #![allow(dead_code)]
 
+use std::path::Path;
 #[cfg(feature = "browser")]
 use std::path::PathBuf;
 
@@ -10,7 +11,6 @@ use std::sync::Arc;
 #[cfg(feature = "browser")]
 use std::time::Duration;
 
-#[cfg(feature = "browser")]
 use anyhow::Result;
 #[cfg(feature = "browser")]
 use tracing::{debug, info, warn};
@@ -20,44 +20,90 @@ use chromiumoxide::cdp::browser_protocol::network::CookieParam;
 #[cfg(feature = "browser")]
 use chromiumoxide::Page;
 
+use super::types::BrowserCookie;
 #[cfg(feature = "browser")]
-use super::types::{BrowserCookie, BrowserFetchResponse};
+use super::types::BrowserFetchResponse;
 
 #[cfg(feature = "browser")]
 use super::BrowserFetcher;
 
+/// Read a cookies file.
+///
+/// Accepts both the CDP format written by `browser-test --save-cookies`
+/// and [`BrowserCookie`] entries. Entries without a name or domain are skipped.
+pub fn read_cookie_file(path: &Path) -> Result<Vec<BrowserCookie>> {
+    let content = std::fs::read_to_string(path)?;
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&content)?;
+
+    let text = |entry: &serde_json::Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| entry.get(*k).and_then(|v| v.as_str()))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let flag = |entry: &serde_json::Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| entry.get(*k).and_then(|v| v.as_bool()))
+            .unwrap_or(false)
+    };
+
+    Ok(entries
+        .iter()
+        .map(|entry| BrowserCookie {
+            name: text(entry, &["name", "key"]),
+            value: text(entry, &["value"]),
+            domain: text(entry, &["domain"]),
+            path: Some(text(entry, &["path"]))
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| "/".to_string()),
+            secure: flag(entry, &["secure"]),
+            http_only: flag(entry, &["http_only", "httpOnly"]),
+        })
+        .filter(|c| !c.name.is_empty() && !c.domain.is_empty())
+        .collect())
+}
+
+/// Write cookies in the format read by `cookies_file`.
+///
+/// The file holds live session tokens, so on Unix it is only readable by
+/// its owner.
+pub fn write_cookie_file(path: &Path, cookies: &[BrowserCookie]) -> Result<()> {
+    let json = serde_json::to_string_pretty(cookies)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, json)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Add cookies to a reqwest jar for requests to `url`.
+pub fn add_cookies_to_jar(jar: &reqwest::cookie::Jar, cookies: &[BrowserCookie], url: &url::Url) {
+    for cookie in cookies {
+        let cookie_str = format!(
+            "{}={}; Domain={}; Path={}",
+            cookie.name, cookie.value, cookie.domain, cookie.path
+        );
+        jar.add_cookie_str(&cookie_str, url);
+    }
+}
+
 #[cfg(feature = "browser")]
 impl BrowserFetcher {
     /// Load cookies from a JSON file.
     pub(crate) async fn load_cookies(&self, page: &Page, path: &PathBuf) -> Result<()> {
         debug!("Loading cookies from {:?}", path);
 
-        let content = std::fs::read_to_string(path)?;
-        let cookies: Vec<serde_json::Value> = serde_json::from_str(&content)?;
-
-        for cookie in cookies {
-            let name = cookie
-                .get("name")
-                .or_else(|| cookie.get("key"))
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-            let value = cookie
-                .get("value")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-            let domain = cookie
-                .get("domain")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-
-            if name.is_empty() || domain.is_empty() {
-                continue;
-            }
-
+        for cookie in read_cookie_file(path)? {
+            let name = cookie.name.as_str();
             let cookie_param = CookieParam::builder()
                 .name(name)
-                .value(value)
-                .domain(domain)
+                .value(cookie.value.as_str())
+                .domain(cookie.domain.as_str())
                 .build();
 
             match cookie_param {
@@ -92,32 +138,10 @@ impl BrowserFetcher {
 
         debug!("Fetching {} with cookies", url);
 
-        let content = std::fs::read_to_string(cookies_file)?;
-        let cookies: Vec<serde_json::Value> = serde_json::from_str(&content)?;
-
         // Build reqwest client with cookies
         let jar = reqwest::cookie::Jar::default();
-        for cookie in &cookies {
-            let name = cookie
-                .get("name")
-                .or_else(|| cookie.get("key"))
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-            let value = cookie
-                .get("value")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-            let domain = cookie
-                .get("domain")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-
-            if !name.is_empty() && !domain.is_empty() {
-                let cookie_str = format!("{}={}; Domain={}", name, value, domain);
-                if let Ok(url_parsed) = url.parse::<reqwest::Url>() {
-                    jar.add_cookie_str(&cookie_str, &url_parsed);
-                }
-            }
+        if let Ok(url_parsed) = url.parse::<reqwest::Url>() {
+            add_cookies_to_jar(&jar, &read_cookie_file(cookies_file)?, &url_parsed);
         }
 
         // ALLOWED: Cookie synchronization requires a custom reqwest client with cookie_provider
@@ -154,6 +178,7 @@ impl BrowserFetcher {
             content,
             content_type,
             cookies: Vec::new(), // Cookies were loaded from file, not extracted
+            links: Vec::new(),
         })
     }
 
@@ -163,11 +188,7 @@ impl BrowserFetcher {
         cookies: &[BrowserCookie],
         path: &PathBuf,
     ) -> Result<()> {
-        let json = serde_json::to_string_pretty(&cookies)?;
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        std::fs::write(path, json)?;
+        write_cookie_file(path, cookies)?;
 
         info!("Saved {} cookies to {:?}", cookies.len(), path);
 
@@ -198,3 +219,45 @@ impl BrowserFetcher {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cookies.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"name": "SESSION", "value": "abc", "domain": ".example.gov", "httpOnly": true},
+                {"name": "", "value": "x", "domain": "example.gov"}
+            ]"#,
+        )
+        .unwrap();
+
+        let cookies = read_cookie_file(&path).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].path, "/");
+        assert!(cookies[0].http_only);
+
+        write_cookie_file(&path, &cookies).unwrap();
+        let reread = read_cookie_file(&path).unwrap();
+        assert_eq!(reread[0].value, "abc");
+        assert!(reread[0].http_only);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o077, 0);
+        }
+
+        let jar = reqwest::cookie::Jar::default();
+        let url: url::Url = "https://portal.example.gov/requests".parse().unwrap();
+        add_cookies_to_jar(&jar, &reread, &url);
+        use reqwest::cookie::CookieStore;
+        let header = jar.cookies(&url).unwrap();
+        assert_eq!(header.to_str().unwrap(), "SESSION=abc");
+    }
+}
@@ -18,10 +18,12 @@ use chromiumoxide::cdp::browser_protocol::page::NavigateParams;
 use chromiumoxide::Page;
 
 #[cfg(feature = "browser")]
-use super::config::BrowserEngineType;
+use super::config::{BrowserEngineType, BrowserStep};
 #[cfg(feature = "browser")]
 use super::stealth::STEALTH_SCRIPTS;
 #[cfg(feature = "browser")]
+use super::steps::run_steps;
+#[cfg(feature = "browser")]
 use super::types::{BrowserCookie, BrowserFetchResponse};
 
 #[cfg(feature = "browser")]
@@ -137,8 +139,22 @@ fn check_for_block_indicators(content: &str, url: &str) {
 impl BrowserFetcher {
     /// Fetch a URL using the browser.
     pub async fn fetch(&mut self, url: &str) -> Result<BrowserFetchResponse> {
+        self.fetch_with_steps(url, None).await
+    }
+
+    /// Fetch a URL and run interaction steps before capturing the page.
+    ///
+    /// `None` runs the steps from the engine config.
+    pub async fn fetch_with_steps(
+        &mut self,
+        url: &str,
+        steps: Option<&[BrowserStep]>,
+    ) -> Result<BrowserFetchResponse> {
         // For cookies-only mode, use regular HTTP
         if self.config.engine == BrowserEngineType::Cookies {
+            if steps.is_some_and(|s| !s.is_empty()) {
+                warn!("Browser steps are ignored by the cookies engine");
+            }
             return self.fetch_with_cookies(url).await;
         }
 
@@ -153,13 +169,19 @@ impl BrowserFetcher {
         let page = browser.new_page("about:blank").await?;
 
         // Use inner function to ensure page is always closed
-        let result = self.fetch_inner(&page, url).await;
+        let steps = steps.unwrap_or(&self.config.steps);
+        let result = self.fetch_inner(&page, url, steps).await;
         let _ = page.close().await;
         result
     }
 
     /// Inner fetch logic - page cleanup handled by caller.
-    pub(crate) async fn fetch_inner(&self, page: &Page, url: &str) -> Result<BrowserFetchResponse> {
+    pub(crate) async fn fetch_inner(
+        &self,
+        page: &Page,
+        url: &str,
+        steps: &[BrowserStep],
+    ) -> Result<BrowserFetchResponse> {
         // Set realistic user agent first (before any navigation)
         page.execute(SetUserAgentOverrideParams::new(
             BROWSER_USER_AGENT.to_string(),
@@ -196,6 +218,13 @@ impl BrowserFetcher {
         )
         .await;
 
+        // Run scripted interactions (accept terms, fill search forms, paginate)
+        let links = if steps.is_empty() {
+            Vec::new()
+        } else {
+            run_steps(page, steps, Duration::from_secs(self.config.timeout)).await?
+        };
+
         // Get final URL and content
         let final_url = page
             .url()
@@ -215,6 +244,7 @@ impl BrowserFetcher {
             content,
             content_type: "text/html".to_string(),
             cookies,
+            links,
         })
     }
 
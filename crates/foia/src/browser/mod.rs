@@ -12,9 +12,11 @@ mod fetch;
 mod pool;
 mod selection;
 mod stealth;
+mod steps;
 mod types;
 
-pub use config::{BrowserEngineConfig, BrowserEngineType};
+pub use config::{BrowserEngineConfig, BrowserEngineType, BrowserStep};
+pub use cookies::{add_cookies_to_jar, read_cookie_file, write_cookie_file};
 #[cfg(feature = "browser")]
 pub use pool::{BrowserPool, BrowserPoolConfig};
 pub use selection::SelectionStrategyType;
@@ -330,6 +332,16 @@ impl BrowserFetcher {
         ))
     }
 
+    pub async fn fetch_with_steps(
+        &mut self,
+        _url: &str,
+        _steps: Option<&[BrowserStep]>,
+    ) -> Result<BrowserFetchResponse> {
+        Err(anyhow::anyhow!(
+            "Browser support not compiled. Rebuild with: cargo build --features browser"
+        ))
+    }
+
     pub async fn save_cookies(&mut self, _path: &PathBuf) -> Result<()> {
         Err(anyhow::anyhow!(
             "Browser support not compiled. Rebuild with: cargo build --features browser"
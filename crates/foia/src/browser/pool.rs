@@ -9,7 +9,7 @@ use tracing::{debug, info, warn};
 
 use super::selection::{BrowserSelectionStrategy, SelectionStrategyType};
 use super::types::{BinaryFetchResponse, BrowserFetchResponse};
-use super::{BrowserEngineConfig, BrowserFetcher};
+use super::{BrowserEngineConfig, BrowserFetcher, BrowserStep};
 
 /// Health tracking for a single browser.
 #[derive(Debug)]
@@ -213,6 +213,18 @@ impl BrowserPool {
     /// On connection failure, tries next browser (up to pool size attempts).
     #[cfg(feature = "browser")]
     pub async fn fetch(&self, url: &str) -> Result<BrowserFetchResponse> {
+        self.fetch_with_steps(url, None).await
+    }
+
+    /// Fetch a URL and run interaction steps on the selected browser.
+    ///
+    /// `None` runs the steps from the engine config.
+    #[cfg(feature = "browser")]
+    pub async fn fetch_with_steps(
+        &self,
+        url: &str,
+        steps: Option<&[BrowserStep]>,
+    ) -> Result<BrowserFetchResponse> {
         let count = self.fetchers.len();
         if count == 0 {
             return Err(anyhow::anyhow!("No browsers configured in pool"));
@@ -235,7 +247,7 @@ impl BrowserPool {
                 debug!("Attempting fetch from browser {} ({})", idx, self.urls[idx]);
 
                 let mut guard = fetcher.lock().await;
-                match guard.fetch(url).await {
+                match guard.fetch_with_steps(url, steps).await {
                     Ok(response) => {
                         drop(guard);
                         self.mark_success(idx).await;
@@ -254,7 +266,7 @@ impl BrowserPool {
             for idx in 0..count {
                 let fetcher = &self.fetchers[idx];
                 let mut guard = fetcher.lock().await;
-                match guard.fetch(url).await {
+                match guard.fetch_with_steps(url, steps).await {
                     Ok(response) => {
                         drop(guard);
                         self.mark_success(idx).await;
@@ -344,6 +356,18 @@ impl BrowserPool {
         ))
     }
 
+    /// Stub step fetch for when browser feature is disabled.
+    #[cfg(not(feature = "browser"))]
+    pub async fn fetch_with_steps(
+        &self,
+        _url: &str,
+        _steps: Option<&[BrowserStep]>,
+    ) -> Result<BrowserFetchResponse> {
+        Err(anyhow::anyhow!(
+            "Browser support not compiled. Rebuild with: cargo build --features browser"
+        ))
+    }
+
     /// Stub binary fetch for when browser feature is disabled.
     #[cfg(not(feature = "browser"))]
     pub async fn fetch_binary(
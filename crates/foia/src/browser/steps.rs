@@ -0,0 +1,345 @@
+//! Scripted interaction steps run by the browser fetcher.
+//!
+//! Clicks and typing go through CDP input events so pages see trusted
+//! user actions; the remaining steps are small scripts evaluated in the page.
+
+#[cfg(feature = "browser")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "browser")]
+use anyhow::{Context, Result};
+#[cfg(feature = "browser")]
+use chromiumoxide::element::Element;
+#[cfg(feature = "browser")]
+use chromiumoxide::Page;
+#[cfg(feature = "browser")]
+use futures::future::BoxFuture;
+#[cfg(feature = "browser")]
+use tracing::debug;
+
+#[cfg(feature = "browser")]
+use super::config::BrowserStep;
+
+/// Pause after actions that may update the page.
+#[cfg(feature = "browser")]
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Interval between checks while waiting for an element.
+#[cfg(feature = "browser")]
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Quote a value as a JavaScript string literal.
+#[cfg(feature = "browser")]
+fn js_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Script that sets a `<select>` value and fires change events.
+///
+/// Evaluates to false when the element or option is missing.
+#[cfg(feature = "browser")]
+pub(crate) fn select_script(selector: &str, value: &str) -> String {
+    format!(
+        r#"(() => {{
+    const el = document.querySelector({selector});
+    if (!el) return false;
+    el.value = {value};
+    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+    return el.value === {value};
+}})()"#,
+        selector = js_string(selector),
+        value = js_string(value)
+    )
+}
+
+/// Script that empties an input. Evaluates to false when it is missing.
+#[cfg(feature = "browser")]
+pub(crate) fn clear_script(selector: &str) -> String {
+    format!(
+        r#"(() => {{
+    const el = document.querySelector({selector});
+    if (!el) return false;
+    el.value = '';
+    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+    return true;
+}})()"#,
+        selector = js_string(selector)
+    )
+}
+
+/// Script that scrolls an element into view, or the page to the bottom.
+///
+/// Evaluates to false when the element is missing.
+#[cfg(feature = "browser")]
+pub(crate) fn scroll_script(selector: Option<&str>) -> String {
+    match selector {
+        Some(selector) => format!(
+            r#"(() => {{
+    const el = document.querySelector({selector});
+    if (!el) return false;
+    el.scrollIntoView({{ block: 'center' }});
+    return true;
+}})()"#,
+            selector = js_string(selector)
+        ),
+        None => {
+            "(() => { window.scrollTo(0, document.body.scrollHeight); return true; })()".to_string()
+        }
+    }
+}
+
+/// Script returning absolute URLs from `attribute` of matching elements.
+///
+/// Fragment-only and `javascript:` values are skipped.
+#[cfg(feature = "browser")]
+pub(crate) fn extract_links_script(selector: &str, attribute: &str) -> String {
+    format!(
+        r#"Array.from(document.querySelectorAll({selector}))
+    .map(el => el.getAttribute({attribute}))
+    .filter(v => v && !v.startsWith('#') && !v.toLowerCase().startsWith('javascript:'))
+    .map(v => {{ try {{ return new URL(v, document.baseURI).href; }} catch (e) {{ return null; }} }})
+    .filter(Boolean)"#,
+        selector = js_string(selector),
+        attribute = js_string(attribute)
+    )
+}
+
+/// Run steps against a loaded page, returning the links they extracted.
+#[cfg(feature = "browser")]
+pub(crate) async fn run_steps(
+    page: &Page,
+    steps: &[BrowserStep],
+    timeout: Duration,
+) -> Result<Vec<String>> {
+    let mut links = Vec::new();
+    for step in steps {
+        run_step(page, step, timeout, &mut links).await?;
+    }
+    Ok(links)
+}
+
+#[cfg(feature = "browser")]
+fn run_step<'a>(
+    page: &'a Page,
+    step: &'a BrowserStep,
+    timeout: Duration,
+    links: &'a mut Vec<String>,
+) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+        // Debug output redacts typed text, which may be a login credential
+        debug!("Browser step: {:?}", step);
+        match step {
+            BrowserStep::Click {
+                selector,
+                navigate,
+                optional,
+            } => {
+                let element = if *optional {
+                    match page.find_element(selector.as_str()).await {
+                        Ok(element) => element,
+                        Err(_) => {
+                            debug!("Optional click target not found: {}", selector);
+                            return Ok(());
+                        }
+                    }
+                } else {
+                    wait_for_element(page, selector, timeout).await?
+                };
+                element
+                    .click()
+                    .await
+                    .with_context(|| format!("click {}", selector))?;
+                if *navigate {
+                    tokio::time::timeout(timeout, page.wait_for_navigation())
+                        .await
+                        .with_context(|| format!("navigation after clicking {}", selector))??;
+                } else {
+                    tokio::time::sleep(SETTLE_DELAY).await;
+                }
+            }
+            BrowserStep::Type {
+                selector,
+                text,
+                clear,
+            } => {
+                let element = wait_for_element(page, selector, timeout).await?;
+                if *clear {
+                    evaluate_check(page, clear_script(selector), "clear", selector).await?;
+                }
+                element
+                    .click()
+                    .await
+                    .with_context(|| format!("focus {}", selector))?;
+                element
+                    .type_str(text)
+                    .await
+                    .with_context(|| format!("type into {}", selector))?;
+            }
+            BrowserStep::Select { selector, value } => {
+                wait_for_element(page, selector, timeout).await?;
+                evaluate_check(page, select_script(selector, value), "select", selector).await?;
+                tokio::time::sleep(SETTLE_DELAY).await;
+            }
+            BrowserStep::Wait { selector, ms } => {
+                if let Some(selector) = selector {
+                    wait_for_element(page, selector, timeout).await?;
+                }
+                if let Some(ms) = ms {
+                    tokio::time::sleep(Duration::from_millis(*ms)).await;
+                }
+            }
+            BrowserStep::Scroll { selector, times } => {
+                let script = scroll_script(selector.as_deref());
+                let target = selector.as_deref().unwrap_or("page");
+                for _ in 0..*times {
+                    evaluate_check(page, script.clone(), "scroll", target).await?;
+                    tokio::time::sleep(SETTLE_DELAY).await;
+                }
+            }
+            BrowserStep::Evaluate { script } => {
+                page.evaluate(script.as_str())
+                    .await
+                    .context("evaluate script")?;
+            }
+            BrowserStep::ExtractLinks {
+                selector,
+                attribute,
+            } => {
+                let found: Vec<String> = page
+                    .evaluate(extract_links_script(selector, attribute))
+                    .await
+                    .with_context(|| format!("extract links from {}", selector))?
+                    .into_value()
+                    .with_context(|| format!("extract links from {}", selector))?;
+                debug!("Extracted {} link(s) from {}", found.len(), selector);
+                for link in found {
+                    if !links.contains(&link) {
+                        links.push(link);
+                    }
+                }
+            }
+            BrowserStep::Repeat { times, steps } => {
+                for i in 0..*times {
+                    for step in steps {
+                        if let Err(e) = run_step(page, step, timeout, links).await {
+                            debug!("Repeat stopped after {} iteration(s): {}", i, e);
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Poll for an element until it appears or `timeout` passes.
+#[cfg(feature = "browser")]
+async fn wait_for_element(page: &Page, selector: &str, timeout: Duration) -> Result<Element> {
+    let start = Instant::now();
+    loop {
+        match page.find_element(selector).await {
+            Ok(element) => return Ok(element),
+            Err(_) if start.elapsed() < timeout => tokio::time::sleep(POLL_INTERVAL).await,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "no element matches {} after {}s: {}",
+                    selector,
+                    timeout.as_secs(),
+                    e
+                ))
+            }
+        }
+    }
+}
+
+/// Evaluate a script that reports success as a boolean.
+#[cfg(feature = "browser")]
+async fn evaluate_check(page: &Page, script: String, action: &str, target: &str) -> Result<()> {
+    let ok: bool = page
+        .evaluate(script)
+        .await
+        .with_context(|| format!("{} {}", action, target))?
+        .into_value()
+        .unwrap_or(false);
+    if !ok {
+        anyhow::bail!("{} failed: {} not found", action, target);
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "browser"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_quote_selectors() {
+        let script = select_script(r#"select[name="type"]"#, "it's");
+        assert!(script.contains(r#"document.querySelector("select[name=\"type\"]")"#));
+        assert!(script.contains(r#"el.value = "it's";"#));
+
+        let script = extract_links_script(".results a", "data-href");
+        assert!(script.contains(r#"querySelectorAll(".results a")"#));
+        assert!(script.contains(r#"getAttribute("data-href")"#));
+
+        assert!(scroll_script(None).contains("scrollTo"));
+        assert!(scroll_script(Some("#more")).contains(r##"querySelector("#more")"##));
+    }
+
+    /// Run every step type against a local fixture page.
+    ///
+    /// Run with: cargo test --features browser browser_steps_fixture -- --ignored
+    #[tokio::test]
+    #[ignore = "requires a local Chrome/Chromium"]
+    async fn browser_steps_fixture() {
+        use crate::browser::{BrowserEngineConfig, BrowserEngineType, BrowserFetcher};
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/browser_steps.html")
+            .canonicalize()
+            .unwrap();
+        let url = format!("file://{}", fixture.display());
+
+        let steps: Vec<BrowserStep> = serde_json::from_str(
+            r##"[
+                {"action": "click", "selector": "#accept"},
+                {"action": "type", "selector": "#from", "text": "2024-01-01"},
+                {"action": "select", "selector": "#kind", "value": "pdf"},
+                {"action": "click", "selector": "#search"},
+                {"action": "wait", "selector": "#results li"},
+                {"action": "repeat", "times": 10, "steps": [
+                    {"action": "extract_links", "selector": "#results a"},
+                    {"action": "click", "selector": "#next:not([disabled])"}
+                ]},
+                {"action": "click", "selector": "#missing", "optional": true},
+                {"action": "evaluate", "script": "document.body.dataset.done = 'yes'"}
+            ]"##,
+        )
+        .unwrap();
+
+        let mut fetcher = BrowserFetcher::new(BrowserEngineConfig {
+            engine: BrowserEngineType::Standard,
+            headless: true,
+            timeout: 5,
+            ..Default::default()
+        });
+        let response = fetcher.fetch_with_steps(&url, Some(&steps)).await.unwrap();
+        fetcher.close().await;
+
+        let names: Vec<&str> = response
+            .links
+            .iter()
+            .map(|l| l.rsplit('/').next().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "2024-01-01-pdf-1.pdf",
+                "2024-01-01-pdf-2.pdf",
+                "2024-01-01-pdf-3.pdf"
+            ]
+        );
+        assert!(response.content.contains(r#"data-done="yes""#));
+    }
+}
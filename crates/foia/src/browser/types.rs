@@ -10,6 +10,8 @@ pub struct BrowserFetchResponse {
     pub content_type: String,
     /// Cookies from the browser session (for subsequent HTTP requests).
     pub cookies: Vec<BrowserCookie>,
+    /// Links collected by `extract_links` steps, in discovery order.
+    pub links: Vec<String>,
 }
 
 /// Cookie extracted from browser session.
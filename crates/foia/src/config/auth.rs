@@ -0,0 +1,171 @@
+//! Requester portal login configuration.
+//!
+//! Portals that hold responses to our own FOIA requests (GovQA-style
+//! systems and similar) need an authenticated session. The config only
+//! names where credentials live; the values are read at login time and
+//! never serialized.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::browser::BrowserStep;
+
+/// Login settings for an authenticated source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortalAuthConfig {
+    /// Where to read the username and password.
+    pub credentials: CredentialSource,
+    /// How to log in.
+    pub login: PortalLogin,
+    /// Session cookie file (default: `{data_dir}/sessions/{source_id}.json`).
+    ///
+    /// Uses the same format as the browser `cookies_file`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cookies_file: Option<PathBuf>,
+    /// Page fetched before scraping to check whether saved cookies still work.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_url: Option<String>,
+    /// Text that only appears on logged-out pages.
+    ///
+    /// Defaults to a password input, i.e. being served the login form.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logged_out_markers: Vec<String>,
+}
+
+/// Reference to stored credentials.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "from", rename_all = "snake_case")]
+pub enum CredentialSource {
+    /// Environment variables holding the username and password.
+    Env {
+        username_var: String,
+        password_var: String,
+    },
+    /// JSON keyring file mapping keys to `{"username", "password"}` entries.
+    ///
+    /// The file must not be readable by group or others.
+    File {
+        path: PathBuf,
+        /// Entry to use (default: the source ID).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+}
+
+/// Login flow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum PortalLogin {
+    /// Fetch an HTML login form and post it with the credentials filled in.
+    Form {
+        login_url: String,
+        /// CSS selector for the login form (default: the form with a password input).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        form_selector: Option<String>,
+        username_field: String,
+        password_field: String,
+        /// Extra fields to send (e.g. a "remember me" checkbox).
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        fields: HashMap<String, String>,
+    },
+    /// Run browser steps on the login page; `{username}` and `{password}`
+    /// in `type` steps are replaced with the credentials.
+    Browser {
+        login_url: String,
+        steps: Vec<BrowserStep>,
+    },
+}
+
+impl PortalAuthConfig {
+    /// Session cookie file, defaulting to `{data_dir}/sessions/{source_id}.json`.
+    pub fn cookies_path(&self, data_dir: &Path, source_id: &str) -> PathBuf {
+        self.cookies_file.clone().unwrap_or_else(|| {
+            data_dir
+                .join("sessions")
+                .join(format!("{}.json", source_id))
+        })
+    }
+}
+
+impl PortalLogin {
+    /// Page the login flow starts from.
+    pub fn login_url(&self) -> &str {
+        match self {
+            PortalLogin::Form { login_url, .. } | PortalLogin::Browser { login_url, .. } => {
+                login_url
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portal_auth_serde() {
+        let json = r##"{
+            "credentials": {"from": "env", "username_var": "PORTAL_USER", "password_var": "PORTAL_PASS"},
+            "login": {
+                "method": "form",
+                "login_url": "/login",
+                "username_field": "email",
+                "password_field": "password"
+            },
+            "check_url": "/my-requests"
+        }"##;
+        let config: PortalAuthConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.credentials,
+            CredentialSource::Env {
+                username_var: "PORTAL_USER".to_string(),
+                password_var: "PORTAL_PASS".to_string(),
+            }
+        );
+        assert_eq!(config.login.login_url(), "/login");
+        assert!(config.logged_out_markers.is_empty());
+
+        let json = r##"{
+            "credentials": {"from": "file", "path": "/etc/foia/keyring.json"},
+            "login": {
+                "method": "browser",
+                "login_url": "https://portal.example.gov/",
+                "steps": [
+                    {"action": "type", "selector": "#user", "text": "{username}"},
+                    {"action": "type", "selector": "#pass", "text": "{password}"},
+                    {"action": "click", "selector": "#login", "navigate": true}
+                ]
+            }
+        }"##;
+        let config: PortalAuthConfig = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            config.credentials,
+            CredentialSource::File { key: None, .. }
+        ));
+        let PortalLogin::Browser { steps, .. } = &config.login else {
+            panic!("expected browser login");
+        };
+        assert_eq!(steps.len(), 3);
+    }
+
+    #[test]
+    fn test_cookies_path() {
+        let json = r#"{
+            "credentials": {"from": "env", "username_var": "U", "password_var": "P"},
+            "login": {"method": "browser", "login_url": "/login", "steps": []}
+        }"#;
+        let mut config: PortalAuthConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.cookies_path(Path::new("/data"), "city"),
+            PathBuf::from("/data/sessions/city.json")
+        );
+
+        config.cookies_file = Some(PathBuf::from("/etc/foia/city.json"));
+        assert_eq!(
+            config.cookies_path(Path::new("/data"), "city"),
+            PathBuf::from("/etc/foia/city.json")
+        );
+    }
+}
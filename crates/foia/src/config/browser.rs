@@ -123,6 +123,141 @@ pub struct BrowserEngineConfig {
     /// Options: round-robin (default), random, per-domain.
     #[serde(default, deserialize_with = "deserialize_null_default")]
     pub selection: SelectionStrategyType,
+
+    /// Interaction steps run on every page after it loads.
+    /// Levels can override these with their own `browser_steps`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<BrowserStep>,
+}
+
+/// A scripted interaction, run in order once a page has loaded.
+///
+/// Selectors are CSS selectors. A failing step fails the fetch unless it
+/// is marked `optional`. Typed text may be a login credential, so `Debug`
+/// output never includes it.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BrowserStep {
+    /// Click the first element matching `selector`.
+    Click {
+        selector: String,
+        /// Wait for the page to navigate after clicking (form submits).
+        #[serde(default)]
+        navigate: bool,
+        /// Skip the step when the element is missing.
+        #[serde(default)]
+        optional: bool,
+    },
+    /// Type text into an input.
+    Type {
+        selector: String,
+        text: String,
+        /// Clear the input's current value first.
+        #[serde(default)]
+        clear: bool,
+    },
+    /// Choose an option of a `<select>` by value.
+    Select { selector: String, value: String },
+    /// Wait for an element to appear, or for a fixed time in milliseconds.
+    Wait {
+        #[serde(default)]
+        selector: Option<String>,
+        #[serde(default)]
+        ms: Option<u64>,
+    },
+    /// Scroll an element into view, or the page to the bottom.
+    Scroll {
+        #[serde(default)]
+        selector: Option<String>,
+        /// Times to scroll, for pages that load more content on scroll.
+        #[serde(default = "default_step_times")]
+        times: u32,
+    },
+    /// Run JavaScript in the page.
+    Evaluate { script: String },
+    /// Collect absolute URLs from an attribute of matching elements.
+    ExtractLinks {
+        #[serde(default = "default_link_selector")]
+        selector: String,
+        #[serde(default = "default_link_attribute")]
+        attribute: String,
+    },
+    /// Run `steps` up to `times` times, stopping early when one fails.
+    ///
+    /// Used for JavaScript paginators: extract links, click "next", repeat.
+    Repeat {
+        #[serde(default = "default_step_times")]
+        times: u32,
+        steps: Vec<BrowserStep>,
+    },
+}
+
+impl std::fmt::Debug for BrowserStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Click {
+                selector,
+                navigate,
+                optional,
+            } => f
+                .debug_struct("Click")
+                .field("selector", selector)
+                .field("navigate", navigate)
+                .field("optional", optional)
+                .finish(),
+            Self::Type {
+                selector, clear, ..
+            } => f
+                .debug_struct("Type")
+                .field("selector", selector)
+                .field("text", &"<redacted>")
+                .field("clear", clear)
+                .finish(),
+            Self::Select { selector, value } => f
+                .debug_struct("Select")
+                .field("selector", selector)
+                .field("value", value)
+                .finish(),
+            Self::Wait { selector, ms } => f
+                .debug_struct("Wait")
+                .field("selector", selector)
+                .field("ms", ms)
+                .finish(),
+            Self::Scroll { selector, times } => f
+                .debug_struct("Scroll")
+                .field("selector", selector)
+                .field("times", times)
+                .finish(),
+            Self::Evaluate { script } => {
+                f.debug_struct("Evaluate").field("script", script).finish()
+            }
+            Self::ExtractLinks {
+                selector,
+                attribute,
+            } => f
+                .debug_struct("ExtractLinks")
+                .field("selector", selector)
+                .field("attribute", attribute)
+                .finish(),
+            Self::Repeat { times, steps } => f
+                .debug_struct("Repeat")
+                .field("times", times)
+                .field("steps", steps)
+                .finish(),
+        }
+    }
+}
+
+fn default_step_times() -> u32 {
+    1
+}
+
+fn default_link_selector() -> String {
+    "a[href]".to_string()
+}
+
+fn default_link_attribute() -> String {
+    "href".to_string()
 }
 
 fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -332,6 +467,59 @@ mod tests {
         assert_eq!(config.wait_for_selector, Some("#content".to_string()));
     }
 
+    #[test]
+    fn test_browser_steps_serde() {
+        let json = r##"{
+            "steps": [
+                {"action": "click", "selector": "#accept", "navigate": true},
+                {"action": "type", "selector": "input[name=from]", "text": "2024-01-01"},
+                {"action": "select", "selector": "#type", "value": "pdf"},
+                {"action": "wait", "ms": 500},
+                {"action": "scroll"},
+                {"action": "repeat", "times": 5, "steps": [
+                    {"action": "extract_links", "selector": ".results a"},
+                    {"action": "click", "selector": ".next"}
+                ]}
+            ]
+        }"##;
+
+        let config: BrowserEngineConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.steps.len(), 6);
+        assert_eq!(
+            config.steps[0],
+            BrowserStep::Click {
+                selector: "#accept".to_string(),
+                navigate: true,
+                optional: false,
+            }
+        );
+        assert_eq!(
+            config.steps[4],
+            BrowserStep::Scroll {
+                selector: None,
+                times: 1
+            }
+        );
+        let BrowserStep::Repeat { times, steps } = &config.steps[5] else {
+            panic!("expected repeat step");
+        };
+        assert_eq!(*times, 5);
+        assert_eq!(
+            steps[0],
+            BrowserStep::ExtractLinks {
+                selector: ".results a".to_string(),
+                attribute: "href".to_string(),
+            }
+        );
+
+        let round_trip: BrowserEngineConfig =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_trip, config);
+        assert!(!serde_json::to_string(&BrowserEngineConfig::default())
+            .unwrap()
+            .contains("steps"));
+    }
+
     #[test]
     fn test_remote_urls_alias() {
         let json = r#"{"remote_urls": ["ws://a:9222", "ws://b:9222"]}"#;
@@ -1,9 +1,11 @@
 //! Configuration management for foia using the prefer crate.
 
 mod analysis;
+pub mod auth;
 pub mod browser;
 pub mod discovery;
 mod loader;
+pub mod schedule;
 pub mod scraper;
 mod settings;
 
@@ -19,9 +21,11 @@ use crate::privacy::PrivacyConfig;
 use crate::repository::util::validate_database_url;
 
 pub use analysis::{AnalysisConfig, AnalysisMethodConfig, OcrConfig};
-pub use browser::{BrowserEngineConfig, BrowserEngineType, SelectionStrategyType};
+pub use auth::{CredentialSource, PortalAuthConfig, PortalLogin};
+pub use browser::{BrowserEngineConfig, BrowserEngineType, BrowserStep, SelectionStrategyType};
 pub use loader::{load_settings_with_options, LoadOptions};
-pub use scraper::{ScraperConfig, ViaMode};
+pub use schedule::{Schedule, ScheduleConfig, ScheduleError};
+pub use scraper::{BudgetConfig, ScraperConfig, ViaMode};
 pub use settings::Settings;
 
 /// Default refresh TTL in days (14 days).
@@ -0,0 +1,553 @@
+//! Per-source crawl schedules.
+//!
+//! A schedule decides when the scrape daemon starts a source: on a cron
+//! expression, only inside allowed time windows, or both, with a jitter so
+//! sources sharing a schedule don't all start on the same minute. Times are
+//! read in the schedule's timezone.
+//!
+//! Timezones are `UTC`, `local` (the host's zone), an IANA name such as
+//! `America/New_York`, or a fixed offset such as `-05:00`. Named and local
+//! zones follow daylight saving time.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Timelike, Utc,
+};
+use serde::{Deserialize, Serialize};
+
+/// Schedule settings as written in a scraper config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// Five-field cron expression (`minute hour day-of-month month
+    /// day-of-week`) or `@hourly`, `@daily`, `@weekly`, `@monthly`.
+    ///
+    /// Without one, the source runs on every daemon check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+    /// Times the source may be scraped, as `HH:MM-HH:MM`; windows may wrap
+    /// midnight. Runs start inside a window and stop when it closes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<String>,
+    /// Timezone for `cron` and `windows` (default: UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Delay each run by up to this many minutes, fixed per source and
+    /// scheduled time.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub jitter_minutes: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl ScheduleConfig {
+    /// Parse the schedule, validating every field.
+    pub fn parse(&self) -> Result<Schedule, ScheduleError> {
+        Ok(Schedule {
+            cron: self.cron.as_deref().map(CronExpr::parse).transpose()?,
+            windows: self
+                .windows
+                .iter()
+                .map(|w| TimeWindow::parse(w))
+                .collect::<Result<_, _>>()?,
+            zone: self
+                .timezone
+                .as_deref()
+                .map(ScheduleZone::parse)
+                .transpose()?
+                .unwrap_or(ScheduleZone::Utc),
+            jitter: Duration::minutes(self.jitter_minutes as i64),
+        })
+    }
+}
+
+/// Errors from parsing a schedule.
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Invalid cron expression '{0}': {1}")]
+    Cron(String, String),
+    #[error("Invalid time window '{0}' (expected HH:MM-HH:MM)")]
+    Window(String),
+    #[error("Unknown timezone '{0}' (use UTC, local, an IANA name or an offset like -05:00)")]
+    Timezone(String),
+}
+
+/// A parsed schedule.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    cron: Option<CronExpr>,
+    windows: Vec<TimeWindow>,
+    zone: ScheduleZone,
+    jitter: Duration,
+}
+
+impl Schedule {
+    /// When a source last run at `last_run` should next start.
+    ///
+    /// Runs missed while the daemon was down are due immediately, once.
+    /// The result is never earlier than `now`; `None` means the cron
+    /// expression never matches (e.g. February 31).
+    pub fn next_run(
+        &self,
+        source_id: &str,
+        last_run: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let at = match (&self.cron, last_run) {
+            (Some(cron), Some(last)) => {
+                let local = self.zone.local_time(last);
+                let next = self.zone.utc_time(cron.next_after(local)?);
+                next + self.jitter_for(source_id, next)
+            }
+            _ => now,
+        };
+        Some(self.next_in_window(source_id, at.max(now)))
+    }
+
+    /// Check whether a run may start at `at`.
+    pub fn in_window(&self, at: DateTime<Utc>) -> bool {
+        let time = self.zone.local_time(at).time();
+        self.windows.is_empty() || self.windows.iter().any(|w| w.contains(time))
+    }
+
+    /// When the window containing `at` closes; `None` if the schedule has
+    /// no windows or `at` is outside them. Overlapping windows close at the
+    /// latest end.
+    pub fn window_end(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = self.zone.local_time(at);
+        self.windows
+            .iter()
+            .filter(|w| w.contains(local.time()))
+            .map(|w| {
+                let day = if w.end > local.time() {
+                    local.date()
+                } else {
+                    local.date() + Duration::days(1)
+                };
+                self.zone.utc_time(day.and_time(w.end))
+            })
+            .max()
+    }
+
+    /// `at` if it falls in a window, else the next window opening.
+    fn next_in_window(&self, source_id: &str, at: DateTime<Utc>) -> DateTime<Utc> {
+        if self.in_window(at) {
+            return at;
+        }
+        let local = self.zone.local_time(at);
+        let open = self
+            .windows
+            .iter()
+            .map(|w| {
+                let day = if w.start > local.time() {
+                    local.date()
+                } else {
+                    local.date() + Duration::days(1)
+                };
+                day.and_time(w.start)
+            })
+            .min()
+            .expect("in_window is true without windows");
+        let open = self.zone.utc_time(open);
+        let jittered = open + self.jitter_for(source_id, open);
+        if self.in_window(jittered) {
+            jittered
+        } else {
+            open
+        }
+    }
+
+    /// Fixed delay for one source and scheduled time, below the jitter.
+    fn jitter_for(&self, source_id: &str, at: DateTime<Utc>) -> Duration {
+        let range = self.jitter.num_seconds();
+        if range <= 0 {
+            return Duration::zero();
+        }
+        // FNV-1a, so the offset is stable across builds
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in source_id.bytes().chain(at.timestamp().to_le_bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        Duration::seconds((hash % range as u64) as i64)
+    }
+}
+
+/// Timezone a schedule is read in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScheduleZone {
+    Utc,
+    Local,
+    Named(chrono_tz::Tz),
+    Fixed(FixedOffset),
+}
+
+impl ScheduleZone {
+    fn parse(s: &str) -> Result<Self, ScheduleError> {
+        let invalid = || ScheduleError::Timezone(s.to_string());
+        match s {
+            "UTC" | "utc" | "Z" => Ok(Self::Utc),
+            "local" => Ok(Self::Local),
+            _ => {
+                let (sign, rest) = match s.as_bytes().first() {
+                    Some(b'+') => (1, &s[1..]),
+                    Some(b'-') => (-1, &s[1..]),
+                    _ => return s.parse().map(Self::Named).map_err(|_| invalid()),
+                };
+                let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+                let hours: i32 = hours.parse().map_err(|_| invalid())?;
+                let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+                FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+                    .map(Self::Fixed)
+                    .ok_or_else(invalid)
+            }
+        }
+    }
+
+    fn local_time(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Self::Utc => at.naive_utc(),
+            Self::Local => at.with_timezone(&Local).naive_local(),
+            Self::Named(tz) => at.with_timezone(&tz).naive_local(),
+            Self::Fixed(offset) => at.with_timezone(&offset).naive_local(),
+        }
+    }
+
+    fn utc_time(self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            Self::Utc => local.and_utc(),
+            Self::Fixed(offset) => (local - offset).and_utc(),
+            Self::Local => resolve_local(&Local, local),
+            Self::Named(tz) => resolve_local(&tz, local),
+        }
+    }
+}
+
+/// Convert a wall-clock time in `tz` to UTC. Times skipped by a DST change
+/// move forward an hour; repeated times take the earlier instant.
+fn resolve_local<Z: TimeZone>(tz: &Z, local: NaiveDateTime) -> DateTime<Utc> {
+    (0..=2)
+        .find_map(|h| {
+            tz.from_local_datetime(&(local + Duration::hours(h)))
+                .earliest()
+        })
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+/// A daily time range, possibly wrapping midnight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TimeWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl TimeWindow {
+    fn parse(s: &str) -> Result<Self, ScheduleError> {
+        let invalid = || ScheduleError::Window(s.to_string());
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        let window = Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        if window.start == window.end {
+            return Err(invalid());
+        }
+        Ok(window)
+    }
+
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// A five-field cron expression; each field is a bitset of allowed values.
+#[derive(Debug, Clone, PartialEq)]
+struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and day-of-week were both restricted, so either
+    /// matching is enough (standard cron behavior).
+    day_or_weekday: bool,
+}
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+impl CronExpr {
+    fn parse(expr: &str) -> Result<Self, ScheduleError> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let invalid = |msg: String| ScheduleError::Cron(expr.to_string(), msg);
+        if fields.len() != 5 {
+            return Err(invalid(format!("expected 5 fields, got {}", fields.len())));
+        }
+        let field = |i: usize, min: u32, max: u32, names: &[&str]| {
+            parse_field(fields[i], min, max, names).map_err(invalid)
+        };
+        let mut weekdays = field(4, 0, 7, WEEKDAY_NAMES)?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: field(0, 0, 59, &[])?,
+            hours: field(1, 0, 23, &[])?,
+            days: field(2, 1, 31, &[])?,
+            months: field(3, 1, 12, MONTH_NAMES)?,
+            weekdays,
+            day_or_weekday: !fields[2].starts_with('*') && !fields[4].starts_with('*'),
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.day_or_weekday {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// First matching minute strictly after `after`, searching five years.
+    fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..(5 * 366) {
+            if self.matches_day(date) {
+                let from = if date == start.date() {
+                    start.time()
+                } else {
+                    NaiveTime::MIN
+                };
+                for hour in from.hour()..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let first = if hour == from.hour() {
+                        from.minute()
+                    } else {
+                        0
+                    };
+                    if let Some(minute) = (first..60).find(|m| self.minutes & (1 << m) != 0) {
+                        return date.and_hms_opt(hour, minute, 0);
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+/// Parse one cron field (`*`, `5`, `1-5`, `*/15`, `mon-fri`, lists) into a
+/// bitset.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let lower = s.to_ascii_lowercase();
+        if let Some(i) = names.iter().position(|n| *n == lower) {
+            // Month names start at 1, weekday names at 0
+            return Ok(i as u32 + min);
+        }
+        let n: u32 = s.parse().map_err(|_| format!("bad value '{}'", s))?;
+        if n < min || n > max {
+            return Err(format!("{} out of range {}-{}", n, min, max));
+        }
+        Ok(n)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("bad step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be positive".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (value(lo)?, value(hi)?)
+        } else {
+            let n = value(range)?;
+            // `5/15` means from 5 to the end in steps of 15
+            (n, if step > 1 { max } else { n })
+        };
+        if lo > hi {
+            return Err(format!("empty range '{}'", range));
+        }
+        for n in (lo..=hi).step_by(step as usize) {
+            bits |= 1 << n;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    fn schedule(cron: Option<&str>, windows: &[&str], tz: Option<&str>) -> Schedule {
+        ScheduleConfig {
+            cron: cron.map(String::from),
+            windows: windows.iter().map(|w| w.to_string()).collect(),
+            timezone: tz.map(String::from),
+            jitter_minutes: 0,
+        }
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let next = |expr: &str, after: &str| {
+            CronExpr::parse(expr)
+                .unwrap()
+                .next_after(utc(after).naive_utc())
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        };
+        assert_eq!(
+            next("*/15 * * * *", "2024-03-01 10:07").as_deref(),
+            Some("2024-03-01 10:15")
+        );
+        assert_eq!(
+            next("0 3 * * *", "2024-03-01 03:00").as_deref(),
+            Some("2024-03-02 03:00")
+        );
+        assert_eq!(
+            next("30 2 1 * *", "2024-01-15 00:00").as_deref(),
+            Some("2024-02-01 02:30")
+        );
+        // 2024-03-02 is a Saturday
+        assert_eq!(
+            next("0 9 * * mon-fri", "2024-03-02 00:00").as_deref(),
+            Some("2024-03-04 09:00")
+        );
+        // Day-of-month or day-of-week when both are set
+        assert_eq!(
+            next("0 0 15 * sun", "2024-03-01 00:00").as_deref(),
+            Some("2024-03-03 00:00")
+        );
+        assert_eq!(
+            next("@monthly", "2024-12-05 00:00").as_deref(),
+            Some("2025-01-01 00:00")
+        );
+        assert_eq!(next("0 0 31 2 *", "2024-01-01 00:00"), None);
+
+        assert!(CronExpr::parse("* * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_run_with_windows() {
+        // Monthly, only overnight, five hours behind UTC
+        let s = schedule(Some("0 0 1 * *"), &["22:00-06:00"], Some("-05:00"));
+        let now = utc("2024-03-10 12:00");
+        // Never run: due now if in the window, else when it opens
+        assert_eq!(s.next_run("a", None, now), Some(utc("2024-03-11 03:00")));
+        assert_eq!(
+            s.next_run("a", None, utc("2024-03-11 04:00")),
+            Some(utc("2024-03-11 04:00"))
+        );
+        // Last run in February: the March 1 run was missed, so it's due now
+        // (or at the next window)
+        assert_eq!(
+            s.next_run("a", Some(utc("2024-02-01 05:00")), now),
+            Some(utc("2024-03-11 03:00"))
+        );
+        // Last run March 1: next is April 1 00:00 local
+        assert_eq!(
+            s.next_run("a", Some(utc("2024-03-01 05:30")), now),
+            Some(utc("2024-04-01 05:00"))
+        );
+        assert!(s.in_window(utc("2024-03-11 10:59")));
+        assert!(!s.in_window(utc("2024-03-11 11:00")));
+
+        // A run started in the window stops when it closes at 06:00 local
+        assert_eq!(
+            s.window_end(utc("2024-03-11 04:00")),
+            Some(utc("2024-03-11 11:00"))
+        );
+        assert_eq!(
+            s.window_end(utc("2024-03-11 10:59")),
+            Some(utc("2024-03-11 11:00"))
+        );
+        assert_eq!(s.window_end(utc("2024-03-11 12:00")), None);
+        assert_eq!(schedule(Some("@daily"), &[], None).window_end(now), None);
+    }
+
+    #[test]
+    fn test_named_timezone_follows_dst() {
+        // Overnight in New York is 03:00-11:00 UTC in winter, 02:00-10:00 in summer
+        let s = schedule(None, &["22:00-06:00"], Some("America/New_York"));
+        assert!(s.in_window(utc("2024-01-15 03:00")));
+        assert!(!s.in_window(utc("2024-01-15 02:30")));
+        assert!(s.in_window(utc("2024-07-15 02:00")));
+        assert!(!s.in_window(utc("2024-07-15 10:30")));
+
+        // 02:30 doesn't exist on 2024-03-10; the run moves to 03:30 EDT
+        let s = schedule(Some("30 2 * * *"), &[], Some("America/New_York"));
+        assert_eq!(
+            s.next_run("a", Some(utc("2024-03-09 07:30")), utc("2024-03-09 12:00")),
+            Some(utc("2024-03-10 07:30"))
+        );
+        assert_eq!(
+            s.next_run("a", Some(utc("2024-03-10 07:30")), utc("2024-03-10 12:00")),
+            Some(utc("2024-03-11 06:30"))
+        );
+    }
+
+    #[test]
+    fn test_jitter_is_stable_and_bounded() {
+        let mut config = ScheduleConfig {
+            cron: Some("0 * * * *".to_string()),
+            jitter_minutes: 30,
+            ..Default::default()
+        };
+        let s = config.parse().unwrap();
+        let last = Some(utc("2024-03-01 10:00"));
+        let now = utc("2024-03-01 10:05");
+        let a = s.next_run("a", last, now).unwrap();
+        assert_eq!(Some(a), s.next_run("a", last, now));
+        assert!(a >= utc("2024-03-01 11:00") && a < utc("2024-03-01 11:30"));
+
+        config.timezone = Some("Mars/Olympus".to_string());
+        assert!(matches!(config.parse(), Err(ScheduleError::Timezone(_))));
+        config.timezone = None;
+        config.windows = vec!["25:00-03:00".to_string()];
+        assert!(matches!(config.parse(), Err(ScheduleError::Window(_))));
+    }
+}
@@ -8,11 +8,16 @@
 //! for workspace split (config is in core, scrapers is a domain crate).
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use super::browser::BrowserEngineConfig;
+use super::auth::PortalAuthConfig;
+use super::browser::{BrowserEngineConfig, BrowserStep};
 use super::discovery::ExternalDiscoveryConfig;
+use super::schedule::ScheduleConfig;
+use crate::http_client::{WarcCapture, DEFAULT_WARC_FILE_BYTES};
 use crate::privacy::SourcePrivacyConfig;
 
 /// Via proxy mode - controls how URL rewriting through caching proxies works.
@@ -91,6 +96,10 @@ pub struct ScraperConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[prefer(skip)]
     pub browser: Option<BrowserEngineConfig>,
+    /// Login for requester portals that need an authenticated session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[prefer(skip)]
+    pub auth: Option<PortalAuthConfig>,
     /// Per-source privacy configuration.
     #[serde(default, skip_serializing_if = "SourcePrivacyConfig::is_default")]
     #[prefer(default)]
@@ -108,6 +117,18 @@ pub struct ScraperConfig {
     /// Per-source via proxy mode (overrides global setting).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub via_mode: Option<ViaMode>,
+    /// Capture raw HTTP exchanges to WARC files.
+    #[serde(default, skip_serializing_if = "WarcCaptureConfig::is_default")]
+    #[prefer(default)]
+    pub warc: WarcCaptureConfig,
+    /// When the scrape daemon runs this source (default: every check).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[prefer(skip)]
+    pub schedule: Option<ScheduleConfig>,
+    /// Limits on how much a single run may fetch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[prefer(default)]
+    pub budget: Option<BudgetConfig>,
 }
 
 impl ScraperConfig {
@@ -142,6 +163,14 @@ pub struct DiscoveryConfig {
     #[serde(default)]
     #[prefer(default)]
     pub api: Option<ApiConfig>,
+    /// Search form submission settings (for `form_search` discovery).
+    #[serde(default)]
+    #[prefer(default)]
+    pub form: Option<FormSearchConfig>,
+    /// Sitemap settings (for `sitemap` discovery).
+    #[serde(default)]
+    #[prefer(default)]
+    pub sitemap: Option<SitemapConfig>,
     /// Maximum recursion depth for BFS crawling (default: 10)
     #[serde(default)]
     #[prefer(default)]
@@ -171,6 +200,14 @@ pub struct DiscoveryConfig {
     #[serde(default)]
     #[prefer(default)]
     pub expand_search_terms: bool,
+    /// Stop paginating at listing pages that haven't changed since last run.
+    #[serde(default)]
+    #[prefer(default)]
+    pub incremental: Option<IncrementalConfig>,
+    /// Score URLs so the most promising are crawled and fetched first.
+    #[serde(default)]
+    #[prefer(default)]
+    pub frontier: Option<FrontierConfig>,
 
     /// External discovery configuration (search engines, sitemaps, Wayback, etc.)
     #[serde(default, skip_serializing_if = "ExternalDiscoveryConfig::is_default")]
@@ -213,6 +250,10 @@ pub struct LevelConfig {
     #[serde(default)]
     #[prefer(default)]
     pub use_browser: bool,
+    /// Browser steps for pages at this level, replacing the source's steps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[prefer(skip)]
+    pub browser_steps: Vec<BrowserStep>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
@@ -351,6 +392,175 @@ pub struct ApiChildConfig {
     pub url_extraction: UrlExtractionConfig,
 }
 
+/// Search form discovery for reading rooms that only expose documents
+/// through a submitted form (ASP.NET WebForms, Drupal views, etc.).
+///
+/// Each submission fetches the form page fresh so hidden fields such as
+/// `__VIEWSTATE` and `form_build_id` are current, then fills in one search
+/// query and/or date window and follows result pagination.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct FormSearchConfig {
+    /// Page containing the search form, resolved against the base URL.
+    #[serde(default)]
+    #[prefer(default)]
+    pub form_url: String,
+    /// CSS selector for the form element (default: first `form` on the page).
+    #[serde(default)]
+    #[prefer(default)]
+    pub form_selector: Option<String>,
+    /// Field that receives each entry of `search_queries`.
+    #[serde(default)]
+    #[prefer(default)]
+    pub query_field: Option<String>,
+    /// Fixed fields set on every submission (filters, submit button name).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[prefer(default)]
+    pub fields: HashMap<String, String>,
+    /// Split a date range into windows and submit each one.
+    #[serde(default)]
+    #[prefer(default)]
+    pub date_windows: Option<DateWindowConfig>,
+    /// CSS selectors for result links (default: `document_links`, then `a`).
+    #[serde(default)]
+    #[prefer(default)]
+    pub result_links: Vec<String>,
+    /// CSS selector for the next-page control. Plain links are followed;
+    /// `__doPostBack` links and submit buttons re-post the results form.
+    #[serde(default)]
+    #[prefer(default)]
+    pub next_selector: Option<String>,
+    /// Maximum result pages per submission (default: 50).
+    #[serde(default)]
+    #[prefer(default)]
+    pub max_pages: Option<u32>,
+}
+
+/// Date range split into fixed-size windows for form submissions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct DateWindowConfig {
+    /// Field that receives the window start date.
+    #[serde(default)]
+    #[prefer(default)]
+    pub from_field: String,
+    /// Field that receives the window end date.
+    #[serde(default)]
+    #[prefer(default)]
+    pub to_field: String,
+    /// First day of the range (`YYYY-MM-DD`).
+    #[serde(default)]
+    #[prefer(default)]
+    pub start: String,
+    /// Last day of the range (`YYYY-MM-DD`, default: today).
+    #[serde(default)]
+    #[prefer(default)]
+    pub end: Option<String>,
+    /// Days per window (default: 30).
+    #[serde(default = "default_window_days")]
+    #[prefer(default)]
+    pub window_days: u32,
+    /// chrono format for submitted dates (default: `%Y-%m-%d`).
+    #[serde(default = "default_date_format")]
+    #[prefer(default)]
+    pub date_format: String,
+}
+
+fn default_window_days() -> u32 {
+    30
+}
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+/// Sitemap-driven discovery.
+///
+/// Walks sitemap indexes and enqueues only entries that are new or whose
+/// `<lastmod>` changed since the last run; child sitemaps whose lastmod is
+/// unchanged aren't fetched at all.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct SitemapConfig {
+    /// Sitemap or sitemap index URLs, resolved against the base URL
+    /// (default: `Sitemap:` lines in robots.txt, then `/sitemap.xml`).
+    #[serde(default)]
+    #[prefer(default)]
+    pub urls: Vec<String>,
+    /// Also enqueue `<image:loc>` URLs from the image extension.
+    #[serde(default)]
+    #[prefer(default)]
+    pub include_images: bool,
+    /// Maximum sitemap files fetched per run (default: 1000).
+    #[serde(default)]
+    #[prefer(default)]
+    pub max_sitemaps: Option<u32>,
+}
+
+/// Incremental crawling over listing pages.
+///
+/// Each listing page's links are fingerprinted. On a newest-first listing,
+/// a page that matches its last fingerprint, or only shifted and has no
+/// new links, means everything after it is already known, so pagination
+/// stops there.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct IncrementalConfig {
+    /// Listings put new items first. Without this, fingerprints are only
+    /// recorded and the crawl always walks every page.
+    #[serde(default)]
+    #[prefer(default)]
+    pub newest_first: bool,
+    /// Consecutive unchanged pages before pagination stops (default: 1).
+    #[serde(default)]
+    #[prefer(default)]
+    pub unchanged_pages: Option<u32>,
+    /// Walk past unchanged pages anyway once this many days have passed
+    /// since the crawl last went beyond them (default: never).
+    #[serde(default)]
+    #[prefer(default)]
+    pub full_crawl_days: Option<u32>,
+}
+
+/// Frontier scoring.
+///
+/// Every discovered URL gets a score, and higher scores are crawled and
+/// downloaded first. Without this, URLs are taken breadth-first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct FrontierConfig {
+    /// Score lost per hop from the start pages (default: 10).
+    #[serde(default)]
+    #[prefer(default)]
+    pub depth_weight: Option<i32>,
+    /// Score added when a URL matches the regex; negative values demote
+    /// sections such as news or events.
+    #[serde(default)]
+    #[prefer(default)]
+    pub url_weights: HashMap<String, i32>,
+    /// Score added when the anchor text reads like a document link,
+    /// e.g. "Download PDF" or "Final Report" (default: 20).
+    #[serde(default)]
+    #[prefer(default)]
+    pub anchor_weight: Option<i32>,
+    /// Score added for pages linked from a page whose links were all
+    /// documents, scaled by the share that were (default: 30).
+    #[serde(default)]
+    #[prefer(default)]
+    pub yield_weight: Option<i32>,
+}
+
+/// Per-run crawl budget for a source.
+///
+/// Once any limit is reached, no more URLs are claimed for the source
+/// until the next run. Pending URLs stay queued.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct BudgetConfig {
+    /// Maximum HTTP requests, including listing pages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_requests: Option<u64>,
+    /// Maximum response bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    /// Maximum documents added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_new_documents: Option<u64>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
 pub struct FetchConfig {
     #[serde(default)]
@@ -376,6 +586,41 @@ impl FetchConfig {
     }
 }
 
+/// Per-source WARC capture of every request/response pair.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, prefer::FromValue)]
+pub struct WarcCaptureConfig {
+    /// Tee every HTTP exchange into `{data_dir}/warc/{source_id}/`.
+    #[serde(default)]
+    #[prefer(default)]
+    pub enabled: bool,
+    /// Start a new file once the current one reaches this size (default 1024).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_mb: Option<u64>,
+}
+
+impl WarcCaptureConfig {
+    /// Check if the config equals the default (for skip_serializing_if).
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Open the source's capture under `{data_dir}/warc/{source_id}/`, if enabled.
+    ///
+    /// Every client fetching for the source should share the returned capture.
+    pub fn open(&self, data_dir: &Path, source_id: &str) -> Option<Arc<WarcCapture>> {
+        if !self.enabled {
+            return None;
+        }
+        let dir = data_dir.join("warc").join(source_id);
+        let max_bytes = self
+            .max_file_mb
+            .map(|mb| mb * 1024 * 1024)
+            .unwrap_or(DEFAULT_WARC_FILE_BYTES);
+        tracing::info!("Capturing HTTP exchanges to {}", dir.display());
+        Some(Arc::new(WarcCapture::new(dir, source_id, max_bytes)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,6 +689,41 @@ mod tests {
         assert!(config.browser.as_ref().unwrap().enabled);
     }
 
+    #[test]
+    fn test_level_browser_steps_deserialization() {
+        let json = r##"{
+            "levels": [
+                {"link_selectors": ["a.listing"]},
+                {
+                    "link_selectors": ["a.doc"],
+                    "browser_steps": [
+                        {"action": "click", "selector": "#accept"},
+                        {"action": "extract_links", "selector": "#results a", "attribute": "data-href"}
+                    ]
+                }
+            ]
+        }"##;
+        let config: DiscoveryConfig = serde_json::from_str(json).unwrap();
+        assert!(config.levels[0].browser_steps.is_empty());
+        assert_eq!(
+            config.levels[1].browser_steps,
+            vec![
+                BrowserStep::Click {
+                    selector: "#accept".to_string(),
+                    navigate: false,
+                    optional: false,
+                },
+                BrowserStep::ExtractLinks {
+                    selector: "#results a".to_string(),
+                    attribute: "data-href".to_string(),
+                },
+            ]
+        );
+        assert!(!serde_json::to_string(&config.levels[0])
+            .unwrap()
+            .contains("browser_steps"));
+    }
+
     #[test]
     fn test_discovery_config_defaults() {
         let config: DiscoveryConfig = serde_json::from_str("{}").unwrap();
@@ -453,6 +733,65 @@ mod tests {
         assert!(!config.use_browser);
     }
 
+    #[test]
+    fn test_form_search_config_deserialization() {
+        let json = r#"{
+            "type": "form_search",
+            "search_queries": ["budget"],
+            "form": {
+                "form_url": "/Search.aspx",
+                "query_field": "ctl00$Main$txtKeyword",
+                "fields": {"ctl00$Main$btnSearch": "Search"},
+                "date_windows": {
+                    "from_field": "ctl00$Main$txtFrom",
+                    "to_field": "ctl00$Main$txtTo",
+                    "start": "2020-01-01"
+                },
+                "next_selector": "a.next"
+            }
+        }"#;
+        let config: DiscoveryConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.discovery_type, "form_search");
+        let form = config.form.unwrap();
+        assert_eq!(form.form_url, "/Search.aspx");
+        assert_eq!(form.fields["ctl00$Main$btnSearch"], "Search");
+        let windows = form.date_windows.unwrap();
+        assert_eq!(windows.window_days, 30);
+        assert_eq!(windows.date_format, "%Y-%m-%d");
+        assert!(windows.end.is_none());
+        assert!(form.max_pages.is_none());
+    }
+
+    #[test]
+    fn test_sitemap_config_deserialization() {
+        let json = r#"{"type": "sitemap", "sitemap": {"urls": ["/sitemap_index.xml"]}}"#;
+        let config: DiscoveryConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.discovery_type, "sitemap");
+        let sitemap = config.sitemap.unwrap();
+        assert_eq!(sitemap.urls, vec!["/sitemap_index.xml"]);
+        assert!(!sitemap.include_images);
+        assert!(sitemap.max_sitemaps.is_none());
+    }
+
+    #[test]
+    fn test_frontier_and_budget_deserialization() {
+        let json = r#"{
+            "discovery": {
+                "type": "html_crawl",
+                "frontier": {"url_weights": {"/foia/": 25, "/news/": -40}}
+            },
+            "budget": {"max_requests": 500, "max_bytes": 1073741824}
+        }"#;
+        let config: ScraperConfig = serde_json::from_str(json).unwrap();
+        let frontier = config.discovery.frontier.unwrap();
+        assert_eq!(frontier.url_weights["/news/"], -40);
+        assert!(frontier.depth_weight.is_none());
+        let budget = config.budget.unwrap();
+        assert_eq!(budget.max_requests, Some(500));
+        assert_eq!(budget.max_bytes, Some(1 << 30));
+        assert!(budget.max_new_documents.is_none());
+    }
+
     #[test]
     fn test_api_pagination_defaults() {
         let config: ApiPaginationConfig = serde_json::from_str("{}").unwrap();
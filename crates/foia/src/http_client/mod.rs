@@ -14,18 +14,22 @@
 
 mod response;
 mod user_agent;
+mod warc_capture;
 
 #[allow(unused_imports)]
 pub use response::{parse_content_disposition_filename, HeadResponse, HttpResponse};
 #[allow(unused_imports)]
 pub use user_agent::{resolve_user_agent, IMPERSONATE_USER_AGENTS, USER_AGENT};
+pub use warc_capture::{WarcCapture, DEFAULT_MAX_FILE_BYTES as DEFAULT_WARC_FILE_BYTES};
 
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
-use reqwest::{Client, Proxy, Response, StatusCode};
+use reqwest::cookie::Jar;
+use reqwest::{Client, Proxy, RequestBuilder, Response, StatusCode};
 #[cfg(feature = "browser")]
 use tracing::debug;
 
@@ -34,6 +38,7 @@ use crate::models::{CrawlRequest, CrawlUrl, UrlStatus};
 use crate::privacy::{PrivacyConfig, PrivacyMode};
 use crate::rate_limit::{InMemoryRateLimitBackend, RateLimiter};
 use crate::repository::DieselCrawlRepository;
+use warc_capture::CapturedRequest;
 
 #[cfg(feature = "browser")]
 use crate::browser::{BrowserPool, BrowserPoolConfig};
@@ -53,9 +58,15 @@ use crate::browser::{BrowserPool, BrowserPoolConfig};
 /// - When `via_mappings` is configured, URLs matching a key prefix are rewritten
 ///   to fetch through a caching proxy (e.g., CloudFront, Cloudflare)
 /// - The original URL is preserved in metadata for accurate record-keeping
+///
+/// WARC capture:
+/// - When a `WarcCapture` is attached, every request/response pair is also
+///   written to rotating WARC files
 #[derive(Clone)]
 pub struct HttpClient {
     client: Client,
+    /// Resolved User-Agent, recorded in captured requests.
+    user_agent: String,
     crawl_repo: Option<Arc<DieselCrawlRepository>>,
     source_id: String,
     request_delay: Duration,
@@ -67,6 +78,10 @@ pub struct HttpClient {
     via_mappings: Arc<HashMap<String, String>>,
     /// Via mode controlling when via mappings are used for requests.
     via_mode: ViaMode,
+    /// Sink for raw HTTP exchanges, shared by all clones.
+    warc_capture: Option<Arc<WarcCapture>>,
+    /// Cookie jar, when the client keeps cookies between requests.
+    cookie_jar: Option<Arc<Jar>>,
     #[cfg(feature = "browser")]
     browser_pool: Option<Arc<BrowserPool>>,
 }
@@ -84,6 +99,28 @@ fn extract_response_headers(response: &Response) -> HashMap<String, String> {
         .collect()
 }
 
+/// Headers that carry session secrets and are masked in request logs.
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+/// Copy headers for the request log with session secrets masked.
+fn redact_headers(headers: &HashMap<String, String>) -> HashMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            if REDACTED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                (name.clone(), "[redacted]".to_string())
+            } else {
+                (name.clone(), value.clone())
+            }
+        })
+        .collect()
+}
+
 /// Builder for constructing `HttpClient` with optional configuration.
 ///
 /// Required parameters (source_id, timeout, request_delay) are provided
@@ -100,6 +137,8 @@ pub struct HttpClientBuilder {
     via_mode: Option<ViaMode>,
     crawl_repo: Option<Arc<DieselCrawlRepository>>,
     referer: Option<String>,
+    warc_capture: Option<Arc<WarcCapture>>,
+    cookie_jar: Option<Arc<Jar>>,
 }
 
 impl HttpClientBuilder {
@@ -145,6 +184,21 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Tee every request/response pair into a WARC capture.
+    pub fn warc_capture(mut self, capture: Arc<WarcCapture>) -> Self {
+        self.warc_capture = Some(capture);
+        self
+    }
+
+    /// Keep cookies set by responses and send them on later requests.
+    /// Needed for stateful sites such as ASP.NET WebForms search pages.
+    ///
+    /// GETs then skip the `BROWSER_URL` pool, which can't share the jar.
+    pub fn cookie_store(mut self, enabled: bool) -> Self {
+        self.cookie_jar = enabled.then(|| Arc::new(Jar::default()));
+        self
+    }
+
     /// Build the `HttpClient`.
     ///
     /// # Errors
@@ -157,8 +211,12 @@ impl HttpClientBuilder {
             .privacy
             .unwrap_or_else(|| PrivacyConfig::default().with_env_overrides());
 
-        let (client, privacy_mode) =
-            HttpClient::build_client(&user_agent, self.timeout, Some(&privacy_config))?;
+        let (client, privacy_mode) = HttpClient::build_client(
+            &user_agent,
+            self.timeout,
+            Some(&privacy_config),
+            self.cookie_jar.clone(),
+        )?;
 
         let rate_limiter = self.rate_limiter.unwrap_or_else(|| {
             let backend = Arc::new(InMemoryRateLimitBackend::new(
@@ -183,6 +241,7 @@ impl HttpClientBuilder {
 
         Ok(HttpClient {
             client,
+            user_agent,
             crawl_repo: self.crawl_repo,
             source_id: self.source_id,
             request_delay: self.request_delay,
@@ -191,8 +250,14 @@ impl HttpClientBuilder {
             privacy_mode,
             via_mappings: Arc::new(via_mappings),
             via_mode,
+            warc_capture: self.warc_capture,
             #[cfg(feature = "browser")]
-            browser_pool: HttpClient::create_browser_pool(),
+            browser_pool: match self.cookie_jar {
+                // Pooled browsers can't see this client's cookies
+                Some(_) => None,
+                None => HttpClient::create_browser_pool(),
+            },
+            cookie_jar: self.cookie_jar,
         })
     }
 }
@@ -220,6 +285,8 @@ impl HttpClient {
             via_mode: None,
             crawl_repo: None,
             referer: None,
+            warc_capture: None,
+            cookie_jar: None,
         }
     }
 
@@ -262,12 +329,16 @@ impl HttpClient {
         user_agent: &str,
         timeout: Duration,
         privacy_config: Option<&PrivacyConfig>,
+        cookie_jar: Option<Arc<Jar>>,
     ) -> Result<(Client, PrivacyMode), String> {
         let mut builder = Client::builder()
             .user_agent(user_agent)
             .timeout(timeout)
             .gzip(true)
             .brotli(true);
+        if let Some(jar) = cookie_jar {
+            builder = builder.cookie_provider(jar);
+        }
 
         let mode = privacy_config
             .map(|c| c.mode())
@@ -381,6 +452,12 @@ impl HttpClient {
         self
     }
 
+    /// Tee every request/response pair into a WARC capture.
+    pub fn with_warc_capture(mut self, capture: Arc<WarcCapture>) -> Self {
+        self.warc_capture = Some(capture);
+        self
+    }
+
     /// Get the rate limiter for this client.
     pub fn rate_limiter(&self) -> &RateLimiter {
         &self.rate_limiter
@@ -402,6 +479,57 @@ impl HttpClient {
         &self.via_mappings
     }
 
+    /// Get the cookie jar, if this client keeps cookies.
+    pub fn cookie_jar(&self) -> Option<&Arc<Jar>> {
+        self.cookie_jar.as_ref()
+    }
+
+    /// Send a request, snapshotting it first when WARC capture is enabled.
+    async fn send(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<(Response, Option<CapturedRequest>), reqwest::Error> {
+        let request = request.build()?;
+        let captured = self
+            .warc_capture
+            .as_ref()
+            .map(|_| CapturedRequest::new(&request, &self.user_agent));
+        let response = self.client.execute(request).await?;
+        Ok((response, captured))
+    }
+
+    /// Wrap a response for the caller.
+    ///
+    /// With WARC capture enabled the body is read here so the exchange can be
+    /// recorded, and the response is returned already buffered.
+    async fn finish_response(
+        &self,
+        response: Response,
+        headers: HashMap<String, String>,
+        captured: Option<CapturedRequest>,
+        original_url: &str,
+    ) -> Result<HttpResponse, reqwest::Error> {
+        let status = response.status();
+        let (Some(capture), Some(captured)) = (&self.warc_capture, captured) else {
+            return Ok(HttpResponse::from_reqwest(status, headers, response));
+        };
+
+        let ip = self.remote_ip(&response);
+        let raw_headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+        capture.record_exchange(&captured, original_url, status, &raw_headers, &body, ip);
+        Ok(HttpResponse::from_bytes(status, headers, body))
+    }
+
+    /// Peer address to record in captures.
+    /// Omitted when proxied, since it would only identify the proxy.
+    fn remote_ip(&self, response: &Response) -> Option<IpAddr> {
+        if self.is_proxied() {
+            return None;
+        }
+        response.remote_addr().map(|addr| addr.ip())
+    }
+
     async fn finalize_request(
         &self,
         request_log: &mut CrawlRequest,
@@ -414,7 +542,8 @@ impl HttpClient {
         request_log.response_at = Some(Utc::now());
         request_log.duration_ms = Some(duration.as_millis() as u64);
         request_log.response_status = Some(status_code);
-        request_log.response_headers = response_headers.clone();
+        request_log.request_headers = redact_headers(&request_log.request_headers);
+        request_log.response_headers = redact_headers(response_headers);
 
         if let Some(repo) = &self.crawl_repo {
             let _ = repo.log_request(request_log).await;
@@ -530,6 +659,16 @@ impl HttpClient {
             Ok(browser_response) => {
                 let status_code = browser_response.status;
 
+                if let Some(capture) = &self.warc_capture {
+                    capture.record_resource(
+                        fetch_url,
+                        original_url,
+                        Utc::now(),
+                        &browser_response.content_type,
+                        browser_response.content.as_bytes(),
+                    );
+                }
+
                 let mut request_log = CrawlRequest::new(
                     self.source_id.clone(),
                     original_url.to_string(),
@@ -661,7 +800,7 @@ impl HttpClient {
         request_log.was_conditional = was_conditional;
 
         let start = Instant::now();
-        let response = request.send().await?;
+        let (response, captured) = self.send(request).await?;
         let duration = start.elapsed();
 
         let status_code = response.status().as_u16();
@@ -678,11 +817,8 @@ impl HttpClient {
         )
         .await;
 
-        Ok(HttpResponse::from_reqwest(
-            response.status(),
-            response_headers,
-            response,
-        ))
+        self.finish_response(response, response_headers, captured, original_url)
+            .await
     }
 
     /// Get page content as text.
@@ -714,7 +850,7 @@ impl HttpClient {
         request_log.request_headers = headers.clone();
 
         let start = Instant::now();
-        let response = request.send().await?;
+        let (response, captured) = self.send(request).await?;
         let duration = start.elapsed();
 
         let status_code = response.status().as_u16();
@@ -730,11 +866,8 @@ impl HttpClient {
         )
         .await;
 
-        Ok(HttpResponse::from_reqwest(
-            response.status(),
-            response_headers,
-            response,
-        ))
+        self.finish_response(response, response_headers, captured, url)
+            .await
     }
 
     /// Make a POST request with form data.
@@ -748,6 +881,46 @@ impl HttpClient {
         self.post_via_reqwest(url, form).await
     }
 
+    /// POST a login form.
+    ///
+    /// Unlike [`post`](Self::post), the exchange is never written to WARC
+    /// capture, so the credentials in the body stay out of archives. The
+    /// request log records only the URL, method and status.
+    pub async fn post_credentials<T: serde::Serialize + ?Sized>(
+        &self,
+        url: &str,
+        form: &T,
+    ) -> Result<HttpResponse, reqwest::Error> {
+        let (fetch_url, _via_rewritten) = self.apply_via_rewrite(url);
+        let domain = self.rate_limiter.acquire(url).await;
+
+        let request = self.client.post(&fetch_url).form(form).build()?;
+        let mut request_log =
+            CrawlRequest::new(self.source_id.clone(), url.to_string(), "POST".to_string());
+
+        let start = Instant::now();
+        let response = self.client.execute(request).await?;
+        let duration = start.elapsed();
+
+        let status = response.status();
+        let response_headers = extract_response_headers(&response);
+        self.finalize_request(
+            &mut request_log,
+            url,
+            &domain,
+            status.as_u16(),
+            &response_headers,
+            duration,
+        )
+        .await;
+
+        Ok(HttpResponse::from_reqwest(
+            status,
+            response_headers,
+            response,
+        ))
+    }
+
     /// Make a POST request with JSON body.
     ///
     /// Note: Browser pool is not used for POST requests - they always go through reqwest.
@@ -783,7 +956,7 @@ impl HttpClient {
         request_log.request_headers = headers.clone();
 
         let start = Instant::now();
-        let response = request.send().await?;
+        let (response, captured) = self.send(request).await?;
         let duration = start.elapsed();
 
         let status_code = response.status().as_u16();
@@ -799,11 +972,8 @@ impl HttpClient {
         )
         .await;
 
-        Ok(HttpResponse::from_reqwest(
-            response.status(),
-            response_headers,
-            response,
-        ))
+        self.finish_response(response, response_headers, captured, url)
+            .await
     }
 
     /// POST via reqwest (direct HTTP).
@@ -825,7 +995,7 @@ impl HttpClient {
             CrawlRequest::new(self.source_id.clone(), url.to_string(), "POST".to_string());
 
         let start = Instant::now();
-        let response = request.send().await?;
+        let (response, captured) = self.send(request).await?;
         let duration = start.elapsed();
 
         let status_code = response.status().as_u16();
@@ -841,11 +1011,8 @@ impl HttpClient {
         )
         .await;
 
-        Ok(HttpResponse::from_reqwest(
-            response.status(),
-            response_headers,
-            response,
-        ))
+        self.finish_response(response, response_headers, captured, url)
+            .await
     }
 
     /// POST JSON via reqwest (direct HTTP).
@@ -867,7 +1034,7 @@ impl HttpClient {
             CrawlRequest::new(self.source_id.clone(), url.to_string(), "POST".to_string());
 
         let start = Instant::now();
-        let response = request.send().await?;
+        let (response, captured) = self.send(request).await?;
         let duration = start.elapsed();
 
         let status_code = response.status().as_u16();
@@ -883,11 +1050,8 @@ impl HttpClient {
         )
         .await;
 
-        Ok(HttpResponse::from_reqwest(
-            response.status(),
-            response_headers,
-            response,
-        ))
+        self.finish_response(response, response_headers, captured, url)
+            .await
     }
 
     /// Make a HEAD request to check headers without downloading content.
@@ -927,7 +1091,7 @@ impl HttpClient {
         request_log.was_conditional = was_conditional;
 
         let start = Instant::now();
-        let response = request.send().await?;
+        let (response, captured) = self.send(request).await?;
         let duration = start.elapsed();
 
         let status_code = response.status().as_u16();
@@ -944,6 +1108,18 @@ impl HttpClient {
         )
         .await;
 
+        if let (Some(capture), Some(captured)) = (&self.warc_capture, captured) {
+            let ip = self.remote_ip(&response);
+            capture.record_exchange(
+                &captured,
+                url,
+                response.status(),
+                response.headers(),
+                &[],
+                ip,
+            );
+        }
+
         Ok(HeadResponse {
             status: response.status(),
             headers: response_headers,
@@ -1040,6 +1216,19 @@ mod tests {
         Duration::from_secs(5)
     }
 
+    #[test]
+    fn test_redact_headers_masks_session_secrets() {
+        let headers = HashMap::from([
+            ("Set-Cookie".to_string(), "session=abc123".to_string()),
+            ("authorization".to_string(), "Bearer xyz".to_string()),
+            ("content-type".to_string(), "text/html".to_string()),
+        ]);
+        let redacted = redact_headers(&headers);
+        assert_eq!(redacted["Set-Cookie"], "[redacted]");
+        assert_eq!(redacted["authorization"], "[redacted]");
+        assert_eq!(redacted["content-type"], "text/html");
+    }
+
     fn tor_direct_config() -> PrivacyConfig {
         let mut config = PrivacyConfig::default();
         config.direct = false;
@@ -1071,7 +1260,7 @@ mod tests {
         let config = tor_direct_config();
         assert_eq!(config.mode(), PrivacyMode::TorDirect);
 
-        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config));
+        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config), None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
@@ -1086,7 +1275,7 @@ mod tests {
         let config = tor_obfuscated_config();
         assert!(matches!(config.mode(), PrivacyMode::TorObfuscated(_)));
 
-        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config));
+        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config), None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
@@ -1100,7 +1289,7 @@ mod tests {
     fn test_build_client_external_proxy_fails_without_url() {
         let config = external_proxy_no_url_config();
 
-        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config));
+        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config), None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
@@ -1115,7 +1304,7 @@ mod tests {
         let config = direct_config();
         assert_eq!(config.mode(), PrivacyMode::Direct);
 
-        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config));
+        let result = HttpClient::build_client("test-agent", test_timeout(), Some(&config), None);
         assert!(result.is_ok());
         let (_, mode) = result.unwrap();
         assert_eq!(mode, PrivacyMode::Direct);
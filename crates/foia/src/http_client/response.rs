@@ -91,6 +91,29 @@ impl HttpResponse {
             .and_then(|h| parse_content_disposition_filename(h))
     }
 
+    /// Read the body into memory so it can be inspected before it is consumed.
+    pub async fn buffered(self) -> Result<Self, reqwest::Error> {
+        let body = match self.body {
+            ResponseBody::Pending(response) => {
+                ResponseBody::Ready(response.bytes().await?.to_vec())
+            }
+            ready => ready,
+        };
+        Ok(Self {
+            status: self.status,
+            headers: self.headers,
+            body,
+        })
+    }
+
+    /// Body of a [`buffered`](Self::buffered) response.
+    pub fn buffered_body(&self) -> Option<&[u8]> {
+        match &self.body {
+            ResponseBody::Ready(bytes) => Some(bytes),
+            ResponseBody::Pending(_) => None,
+        }
+    }
+
     /// Get response body as bytes.
     pub async fn bytes(self) -> Result<Vec<u8>, reqwest::Error> {
         match self.body {
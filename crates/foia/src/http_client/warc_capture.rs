@@ -0,0 +1,349 @@
+//! WARC capture of raw HTTP exchanges.
+//!
+//! When a client has a [`WarcCapture`] attached, every request it sends and
+//! the response it gets back are written as a `request`/`response` record
+//! pair. reqwest decodes `Content-Encoding` and chunked transfer before the
+//! body is seen, so response records hold the decoded payload and omit the
+//! `Transfer-Encoding` header. Browser fetches have no raw exchange to
+//! record; the rendered content is stored as a `resource` record instead.
+//!
+//! Records are handed to a dedicated writer thread, so file writes and
+//! flushes never block the async request path.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, TRANSFER_ENCODING};
+use reqwest::{Request, StatusCode};
+
+use crate::models::DocumentVersion;
+use crate::storage::warc::{http_response_block, new_record_id, warc_date, RotatingWarcWriter};
+
+/// Default size at which capture files are rotated (1 GB).
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Shared sink for a source's captured HTTP exchanges.
+///
+/// Wrap in an `Arc` and attach to each client for the source; writes are
+/// serialized so records from concurrent workers never interleave. Dropping
+/// the last reference waits for queued records to reach disk.
+pub struct WarcCapture {
+    sender: Option<Sender<CaptureJob>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+/// WARC headers and block for one record.
+type OwnedRecord = (Vec<(&'static str, String)>, Vec<u8>);
+
+/// A group of related records queued for the writer thread.
+struct CaptureJob {
+    url: String,
+    records: Vec<OwnedRecord>,
+}
+
+/// Snapshot of an outgoing request, taken before it is sent.
+pub(crate) struct CapturedRequest {
+    date: DateTime<Utc>,
+    url: String,
+    block: Vec<u8>,
+}
+
+impl CapturedRequest {
+    /// Serialize a request as an HTTP/1.1 message.
+    ///
+    /// reqwest adds `User-Agent`, `Accept` and `Accept-Encoding` when the
+    /// connection is made, so those are reconstructed here from the client
+    /// configuration.
+    pub(crate) fn new(request: &Request, user_agent: &str) -> Self {
+        let url = request.url();
+        let mut target = url.path().to_string();
+        if let Some(query) = url.query() {
+            target.push('?');
+            target.push_str(query);
+        }
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .unwrap_or_default();
+
+        let headers = request.headers();
+        let mut lines = vec![
+            format!("{} {} HTTP/1.1", request.method(), target),
+            format!("host: {}", host),
+            format!("user-agent: {}", user_agent),
+        ];
+        if !headers.contains_key("accept") {
+            lines.push("accept: */*".to_string());
+        }
+        if !headers.contains_key("accept-encoding") {
+            lines.push("accept-encoding: gzip, br".to_string());
+        }
+        lines.extend(header_lines(headers));
+        if !body.is_empty() {
+            lines.push(format!("content-length: {}", body.len()));
+        }
+
+        let mut block = lines.join("\r\n").into_bytes();
+        block.extend_from_slice(b"\r\n\r\n");
+        block.extend_from_slice(body);
+
+        Self {
+            date: Utc::now(),
+            url: url.to_string(),
+            block,
+        }
+    }
+}
+
+/// Render headers as `name: value` lines, dropping any that aren't text.
+fn header_lines(headers: &HeaderMap) -> impl Iterator<Item = String> + '_ {
+    headers.iter().filter_map(|(name, value)| {
+        value
+            .to_str()
+            .ok()
+            .map(|value| format!("{}: {}", name, value))
+    })
+}
+
+/// Compare URLs after normalization, so `https://a.gov` matches `https://a.gov/`.
+fn same_url(fetched: &str, original: &str) -> bool {
+    fetched == original || reqwest::Url::parse(original).is_ok_and(|url| url.as_str() == fetched)
+}
+
+impl WarcCapture {
+    /// Capture into `dir`, rotating files once they reach `max_file_bytes`.
+    pub fn new(dir: PathBuf, source_id: &str, max_file_bytes: u64) -> Self {
+        let mut writer = RotatingWarcWriter::new(dir, source_id, max_file_bytes)
+            .with_info("isPartOf", source_id);
+        let (sender, receiver) = mpsc::channel::<CaptureJob>();
+
+        let handle = std::thread::Builder::new()
+            .name(format!("warc-{}", source_id))
+            .spawn(move || {
+                for job in receiver {
+                    let records: Vec<(&[(&str, String)], &[u8])> = job
+                        .records
+                        .iter()
+                        .map(|(headers, block)| (headers.as_slice(), block.as_slice()))
+                        .collect();
+                    if let Err(e) = writer.write_records(&records) {
+                        tracing::warn!("Failed to write WARC capture for {}: {}", job.url, e);
+                    }
+                }
+                if let Err(e) = writer.close() {
+                    tracing::warn!("Failed to close WARC capture file: {}", e);
+                }
+            });
+
+        match handle {
+            Ok(handle) => Self {
+                sender: Some(sender),
+                writer: Some(handle),
+            },
+            Err(e) => {
+                tracing::warn!("Failed to start WARC capture writer: {}", e);
+                Self {
+                    sender: None,
+                    writer: None,
+                }
+            }
+        }
+    }
+
+    /// Write a request and the response it received.
+    ///
+    /// `original_url` is the URL before via rewriting; it is recorded when it
+    /// differs from the URL actually fetched. Failures are logged rather than
+    /// returned so a full disk never aborts a crawl.
+    pub(crate) fn record_exchange(
+        &self,
+        request: &CapturedRequest,
+        original_url: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+        ip: Option<IpAddr>,
+    ) {
+        let response_headers: Vec<(&str, String)> = headers
+            .iter()
+            .filter(|(name, _)| **name != TRANSFER_ENCODING)
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.as_str(), value.to_string()))
+            })
+            .collect();
+        let response_block = http_response_block(status.as_u16(), &response_headers, body);
+
+        let request_id = new_record_id();
+        let response_id = new_record_id();
+        let date = warc_date(request.date);
+
+        let mut request_warc = vec![
+            ("WARC-Type", "request".to_string()),
+            ("WARC-Record-ID", request_id.clone()),
+            ("WARC-Date", date.clone()),
+            ("WARC-Target-URI", request.url.clone()),
+            ("WARC-Concurrent-To", response_id.clone()),
+            (
+                "Content-Type",
+                "application/http; msgtype=request".to_string(),
+            ),
+        ];
+        let mut response_warc = vec![
+            ("WARC-Type", "response".to_string()),
+            ("WARC-Record-ID", response_id),
+            ("WARC-Date", date),
+            ("WARC-Target-URI", request.url.clone()),
+            ("WARC-Concurrent-To", request_id),
+            (
+                "WARC-Payload-Digest",
+                format!("sha256:{}", DocumentVersion::compute_hash(body)),
+            ),
+            (
+                "Content-Type",
+                "application/http; msgtype=response".to_string(),
+            ),
+        ];
+        if let Some(ip) = ip {
+            response_warc.push(("WARC-IP-Address", ip.to_string()));
+        }
+        if !same_url(&request.url, original_url) {
+            request_warc.push(("Foia-Original-URI", original_url.to_string()));
+            response_warc.push(("Foia-Original-URI", original_url.to_string()));
+        }
+
+        self.write(
+            &request.url,
+            vec![
+                (request_warc, request.block.clone()),
+                (response_warc, response_block),
+            ],
+        );
+    }
+
+    /// Write content obtained through the browser as a `resource` record.
+    pub(crate) fn record_resource(
+        &self,
+        url: &str,
+        original_url: &str,
+        date: DateTime<Utc>,
+        content_type: &str,
+        content: &[u8],
+    ) {
+        let mut headers = vec![
+            ("WARC-Type", "resource".to_string()),
+            ("WARC-Record-ID", new_record_id()),
+            ("WARC-Date", warc_date(date)),
+            ("WARC-Target-URI", url.to_string()),
+            (
+                "WARC-Payload-Digest",
+                format!("sha256:{}", DocumentVersion::compute_hash(content)),
+            ),
+            ("Content-Type", content_type.to_string()),
+        ];
+        if !same_url(url, original_url) {
+            headers.push(("Foia-Original-URI", original_url.to_string()));
+        }
+
+        self.write(url, vec![(headers, content.to_vec())]);
+    }
+
+    /// Queue records for the writer thread.
+    fn write(&self, url: &str, records: Vec<OwnedRecord>) {
+        let job = CaptureJob {
+            url: url.to_string(),
+            records,
+        };
+        let sent = self
+            .sender
+            .as_ref()
+            .is_some_and(|sender| sender.send(job).is_ok());
+        if !sent {
+            tracing::warn!("WARC capture writer is not running; dropped {}", url);
+        }
+    }
+}
+
+impl Drop for WarcCapture {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer drain the queue and exit
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_captured_request_block() {
+        let client = reqwest::Client::new();
+        let request = client
+            .get("https://example.gov:8443/foia/list?page=2")
+            .header("If-None-Match", "\"abc\"")
+            .build()
+            .unwrap();
+
+        let captured = CapturedRequest::new(&request, "foia-test");
+        let block = String::from_utf8(captured.block).unwrap();
+        assert!(block.starts_with("GET /foia/list?page=2 HTTP/1.1\r\nhost: example.gov:8443\r\n"));
+        assert!(block.contains("user-agent: foia-test\r\n"));
+        assert!(block.contains("if-none-match: \"abc\"\r\n"));
+        assert!(block.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_record_exchange() {
+        let dir = tempfile::tempdir().unwrap();
+        let capture = WarcCapture::new(dir.path().to_path_buf(), "agency", DEFAULT_MAX_FILE_BYTES);
+
+        let client = reqwest::Client::new();
+        let request = client
+            .get("https://cache.example.net/reading-room")
+            .build()
+            .unwrap();
+        let captured = CapturedRequest::new(&request, "foia-test");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "text/html".parse().unwrap());
+        headers.insert("transfer-encoding", "chunked".parse().unwrap());
+        capture.record_exchange(
+            &captured,
+            "https://agency.gov/reading-room",
+            StatusCode::OK,
+            &headers,
+            b"<html></html>",
+            None,
+        );
+
+        // Dropping the capture waits for the writer thread to finish
+        drop(capture);
+
+        let files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        let file_name = files[0].file_name().unwrap().to_string_lossy();
+        assert!(file_name.starts_with("agency-"));
+        let warc = String::from_utf8(std::fs::read(&files[0]).unwrap()).unwrap();
+        assert!(warc.contains("isPartOf: agency\r\n"));
+        assert!(warc.contains("WARC-Type: request\r\n"));
+        assert!(warc.contains("WARC-Type: response\r\n"));
+        assert!(warc.contains("Foia-Original-URI: https://agency.gov/reading-room\r\n"));
+        assert!(warc.contains("HTTP/1.1 200 OK\r\ncontent-type: text/html\r\n\r\n<html></html>"));
+        assert!(!warc.contains("transfer-encoding"));
+    }
+}
@@ -0,0 +1,575 @@
+//! FOIA request letters rendered from templates.
+//!
+//! A template is Markdown with `{field}` placeholders and optional TOML
+//! front matter between `+++` lines holding a description and default field
+//! values. Fields are filled, lowest precedence first, from built-in
+//! defaults, the template's front matter, the shared `[fields]` and the
+//! selected `[agencies.<key>]` table of `letters.toml`, and finally the
+//! caller (tracked request, command line).
+//!
+//! Besides plain fields, three placeholders expand to blocks that vanish
+//! when unused: `{fee_waiver}`, `{expedited}` and `{cited_documents}`.
+//! Lines whose placeholders all come out empty are dropped, so optional
+//! address lines don't leave gaps and `Ref: {tracking_number}` disappears
+//! when there is no tracking number.
+
+pub mod pdf;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// Built-in templates, by name.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("federal", include_str!("templates/federal.md")),
+    ("follow-up", include_str!("templates/follow_up.md")),
+];
+
+/// Name of the profiles file in the templates directory.
+pub const PROFILES_FILE: &str = "letters.toml";
+
+const FEE_WAIVER_TEXT: &str = "I request a waiver of all fees for this request under \
+5 U.S.C. § 552(a)(4)(A)(iii). Disclosure of the requested information is in the public \
+interest because it is likely to contribute significantly to public understanding of the \
+operations or activities of the government, and it is not primarily in my commercial \
+interest. If a waiver is denied, please notify me before incurring fees above $25.";
+
+const EXPEDITED_TEXT: &str = "I request expedited processing under 5 U.S.C. § 552(a)(6)(E).";
+
+const EXPEDITED_CERTIFICATION: &str = "I certify that the foregoing statements are true and \
+correct to the best of my knowledge and belief.";
+
+const CITED_INTRO: &str = "For reference, this request relates to the following records, \
+which I already hold:";
+
+/// Fields every template can use without defining them.
+fn default_fields() -> BTreeMap<String, String> {
+    [
+        ("salutation", "FOIA Officer"),
+        ("foia_officer", "FOIA Officer"),
+        ("agency_address", ""),
+        ("requester_address", ""),
+        ("requester_email", ""),
+        ("requester_phone", ""),
+        ("tracking_number", ""),
+        ("fee_waiver_text", FEE_WAIVER_TEXT),
+        ("expedited_text", EXPEDITED_TEXT),
+        ("cited_intro", CITED_INTRO),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{([a-z][a-z0-9_]*)\}").expect("valid regex"));
+
+/// Errors from loading or rendering a letter.
+#[derive(Debug, thiserror::Error)]
+pub enum LetterError {
+    #[error("Template not found: {0}")]
+    TemplateNotFound(String),
+    #[error("Invalid front matter in {0}: {1}")]
+    FrontMatter(String, String),
+    #[error("Invalid {file}: {message}")]
+    Profiles { file: PathBuf, message: String },
+    #[error("Missing letter fields: {}", .0.join(", "))]
+    MissingFields(Vec<String>),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Output format for a rendered letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterFormat {
+    Markdown,
+    Text,
+    Pdf,
+}
+
+impl LetterFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "markdown" | "md" => Some(Self::Markdown),
+            "text" | "txt" => Some(Self::Text),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+
+    /// Render Markdown letter text in this format.
+    pub fn render(&self, markdown: &str) -> Vec<u8> {
+        match self {
+            Self::Markdown => markdown.as_bytes().to_vec(),
+            Self::Text => markdown_to_text(markdown).into_bytes(),
+            Self::Pdf => pdf::text_to_pdf(&markdown_to_text(markdown)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FrontMatter {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    fields: BTreeMap<String, String>,
+}
+
+/// A letter template.
+#[derive(Debug, Clone)]
+pub struct LetterTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    /// Default field values from the front matter.
+    pub fields: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl LetterTemplate {
+    /// Parse a template, splitting off any `+++` front matter.
+    pub fn parse(name: &str, source: &str) -> Result<Self, LetterError> {
+        let source = source.replace("\r\n", "\n");
+        let (front, body) = match source.strip_prefix("+++\n") {
+            Some(rest) => match rest.split_once("\n+++\n") {
+                Some((front, body)) => (Some(front.to_string()), body.to_string()),
+                None => {
+                    return Err(LetterError::FrontMatter(
+                        name.to_string(),
+                        "missing closing +++".to_string(),
+                    ))
+                }
+            },
+            None => (None, source),
+        };
+        let front: FrontMatter = match front {
+            Some(f) => toml::from_str(&f)
+                .map_err(|e| LetterError::FrontMatter(name.to_string(), e.to_string()))?,
+            None => FrontMatter::default(),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            description: front.description,
+            fields: front.fields,
+            body: body.trim_start_matches('\n').to_string(),
+        })
+    }
+
+    /// A built-in template by name.
+    pub fn builtin(name: &str) -> Option<Self> {
+        BUILTIN_TEMPLATES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(n, src)| Self::parse(n, src).expect("built-in templates parse"))
+    }
+
+    /// Load a template by path, by name from `dir` (`{dir}/{name}.md`), or
+    /// by built-in name, in that order.
+    pub fn load(dir: &Path, name_or_path: &str) -> Result<Self, LetterError> {
+        let as_path = Path::new(name_or_path);
+        let path = if as_path.is_file() {
+            Some(as_path.to_path_buf())
+        } else {
+            let in_dir = dir.join(format!("{}.md", name_or_path));
+            in_dir.is_file().then_some(in_dir)
+        };
+        if let Some(path) = path {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| name_or_path.to_string());
+            return Self::parse(&name, &std::fs::read_to_string(&path)?);
+        }
+        Self::builtin(name_or_path)
+            .ok_or_else(|| LetterError::TemplateNotFound(name_or_path.to_string()))
+    }
+
+    /// Templates available in `dir` plus the built-ins, by name. A file
+    /// named like a built-in replaces it.
+    pub fn list(dir: &Path) -> Result<Vec<Self>, LetterError> {
+        let mut templates: BTreeMap<String, Self> = BUILTIN_TEMPLATES
+            .iter()
+            .filter_map(|(name, _)| Self::builtin(name))
+            .map(|t| (t.name.clone(), t))
+            .collect();
+        if dir.is_dir() {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "md") {
+                    let name = path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let template = Self::parse(&name, &std::fs::read_to_string(&path)?)?;
+                    templates.insert(name, template);
+                }
+            }
+        }
+        Ok(templates.into_values().collect())
+    }
+
+    /// Fill the template's placeholders.
+    ///
+    /// Fails listing every placeholder that has no value, so a letter is
+    /// never produced with `{address}` left in it.
+    pub fn render(&self, ctx: &LetterContext) -> Result<String, LetterError> {
+        let mut fields = default_fields();
+        fields.extend(self.fields.clone());
+        fields.extend(ctx.fields.clone());
+
+        let mut blocks = BTreeMap::new();
+        blocks.insert(
+            "fee_waiver",
+            if ctx.fee_waiver {
+                fields["fee_waiver_text"].clone()
+            } else {
+                String::new()
+            },
+        );
+        let expedited = if ctx.expedited {
+            match fields.get("expedited_justification") {
+                Some(why) if !why.trim().is_empty() => format!(
+                    "{} {}\n\n{}",
+                    fields["expedited_text"],
+                    why.trim(),
+                    EXPEDITED_CERTIFICATION
+                ),
+                _ => {
+                    return Err(LetterError::MissingFields(vec![
+                        "expedited_justification".to_string()
+                    ]))
+                }
+            }
+        } else {
+            String::new()
+        };
+        blocks.insert("expedited", expedited);
+        blocks.insert("cited_list", cited_list(&ctx.cited));
+        blocks.insert(
+            "cited_documents",
+            if ctx.cited.is_empty() {
+                String::new()
+            } else {
+                format!("{}\n\n{}", fields["cited_intro"], cited_list(&ctx.cited))
+            },
+        );
+
+        let mut missing = Vec::new();
+        let mut lines = Vec::new();
+        for line in self.body.lines() {
+            let has_placeholder = PLACEHOLDER.is_match(line);
+            let mut filled = false;
+            let rendered = PLACEHOLDER.replace_all(line, |caps: &regex::Captures| {
+                let key = &caps[1];
+                let value = blocks.get(key).or_else(|| fields.get(key));
+                if let Some(value) = value {
+                    let value = value.trim_end();
+                    filled |= !value.trim().is_empty();
+                    value.to_string()
+                } else {
+                    filled = true;
+                    if !missing.iter().any(|m| m == key) {
+                        missing.push(key.to_string());
+                    }
+                    caps[0].to_string()
+                }
+            });
+            // Drop lines whose fields all turned out empty, labels included
+            if has_placeholder && !filled {
+                continue;
+            }
+            lines.push(rendered.into_owned());
+        }
+        if !missing.is_empty() {
+            return Err(LetterError::MissingFields(missing));
+        }
+
+        Ok(collapse_blank_lines(&lines.join("\n")))
+    }
+}
+
+/// A document cited in a letter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CitedDocument {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+fn cited_list(cited: &[CitedDocument]) -> String {
+    cited
+        .iter()
+        .map(|d| format!("- *{}* (ID {}): <{}>", d.title, d.id, d.url))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Values for a letter beyond the template's own defaults.
+#[derive(Debug, Clone, Default)]
+pub struct LetterContext {
+    pub fields: BTreeMap<String, String>,
+    pub cited: Vec<CitedDocument>,
+    pub fee_waiver: bool,
+    pub expedited: bool,
+}
+
+impl LetterContext {
+    /// Seed fields from the shared profile and, if given, an agency profile.
+    pub fn new(profiles: &LetterProfiles, agency: Option<&BTreeMap<String, String>>) -> Self {
+        let mut fields = profiles.fields.clone();
+        if let Some(agency) = agency {
+            fields.extend(agency.clone());
+        }
+        Self {
+            fields,
+            ..Default::default()
+        }
+    }
+
+    /// Set a field unless it already has a value.
+    pub fn set_default(&mut self, key: &str, value: impl Into<String>) {
+        self.fields
+            .entry(key.to_string())
+            .or_insert_with(|| value.into());
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        self.fields.insert(key.to_string(), value.into());
+    }
+}
+
+/// Requester details and agency profiles from `letters.toml`.
+///
+/// ```toml
+/// [fields]
+/// requester_name = "Jane Doe"
+/// requester_email = "jane@example.org"
+///
+/// [agencies.fbi]
+/// agency = "Federal Bureau of Investigation"
+/// agency_address = "170 Marcel Drive\nWinchester, VA 22602"
+/// regulation = "28 C.F.R. Part 16"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LetterProfiles {
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default)]
+    pub agencies: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl LetterProfiles {
+    /// Load `letters.toml` from `dir`, or empty profiles if there is none.
+    pub fn load(dir: &Path) -> Result<Self, LetterError> {
+        let file = dir.join(PROFILES_FILE);
+        if !file.is_file() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&file)?;
+        toml::from_str(&contents).map_err(|e| LetterError::Profiles {
+            file,
+            message: e.to_string(),
+        })
+    }
+
+    /// An agency profile by key, or by its `agency` name (case-insensitive).
+    pub fn agency(&self, key_or_name: &str) -> Option<&BTreeMap<String, String>> {
+        self.agencies.get(key_or_name).or_else(|| {
+            self.agencies.values().find(|fields| {
+                fields
+                    .get("agency")
+                    .is_some_and(|name| name.eq_ignore_ascii_case(key_or_name))
+            })
+        })
+    }
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.trim().lines() {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").expect("valid regex"));
+static AUTOLINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(https?://[^>]+)>").expect("valid regex"));
+static STRONG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*\*([^*]+)\*\*").expect("valid regex"));
+static EMPHASIS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*([^*\s][^*]*)\*").expect("valid regex"));
+
+/// Convert letter Markdown to plain text wrapped at 72 columns.
+///
+/// Handles the subset letters use: headings, emphasis, links, inline code
+/// and list items. Line breaks within paragraphs are kept, since address
+/// blocks rely on them.
+pub fn markdown_to_text(markdown: &str) -> String {
+    let plain: Vec<String> = markdown
+        .lines()
+        .map(|line| {
+            let line = line.trim_start_matches('#').trim_start();
+            let line = LINK.replace_all(line, |caps: &regex::Captures| {
+                if caps[1] == caps[2] {
+                    caps[2].to_string()
+                } else {
+                    format!("{} ({})", &caps[1], &caps[2])
+                }
+            });
+            let line = AUTOLINK.replace_all(&line, "$1");
+            let line = STRONG.replace_all(&line, "$1");
+            let line = EMPHASIS.replace_all(&line, "$1");
+            line.replace('`', "")
+        })
+        .collect();
+    wrap_text(&plain.join("\n"), 72)
+}
+
+/// Wrap each line at `width` characters, indenting continuations of list
+/// items under their text.
+pub fn wrap_text(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    for line in text.lines() {
+        if line.chars().count() <= width {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+        let leading = &line[..line.len() - line.trim_start().len()];
+        let indent = if line.starts_with("- ") || line.starts_with("* ") {
+            2
+        } else {
+            leading.len()
+        };
+        let mut current = leading.to_string();
+        let mut line_start = true;
+        for word in line.split_whitespace() {
+            if !line_start {
+                if current.chars().count() + 1 + word.chars().count() > width {
+                    out.push_str(&current);
+                    out.push('\n');
+                    current = " ".repeat(indent);
+                } else {
+                    current.push(' ');
+                }
+            }
+            current.push_str(word);
+            line_start = false;
+        }
+        out.push_str(&current);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> LetterContext {
+        let mut ctx = LetterContext::default();
+        ctx.set("requester_name", "Jane Doe");
+        ctx.set("agency", "Federal Bureau of Investigation");
+        ctx.set("subject", "Field office memos");
+        ctx.set("description", "All memos about X.");
+        ctx.set("today", "2024-11-22");
+        ctx
+    }
+
+    #[test]
+    fn test_render_builtin() {
+        let template = LetterTemplate::builtin("federal").unwrap();
+        let letter = template.render(&context()).unwrap();
+        assert!(letter.contains("Federal Bureau of Investigation"));
+        assert!(letter.contains("All memos about X."));
+        assert!(!letter.contains("552(a)(4)(A)(iii)"));
+        assert!(!letter.contains('{'));
+        assert!(!letter.contains("\n\n\n"));
+
+        let mut ctx = context();
+        ctx.fee_waiver = true;
+        ctx.cited.push(CitedDocument {
+            id: "doc-1".to_string(),
+            title: "Release 1".to_string(),
+            url: "https://vault.fbi.gov/release-1".to_string(),
+        });
+        let letter = template.render(&ctx).unwrap();
+        assert!(letter.contains("552(a)(4)(A)(iii)"));
+        assert!(letter.contains("- *Release 1* (ID doc-1): <https://vault.fbi.gov/release-1>"));
+
+        ctx.expedited = true;
+        assert!(matches!(
+            template.render(&ctx),
+            Err(LetterError::MissingFields(f)) if f == ["expedited_justification"]
+        ));
+        ctx.set(
+            "expedited_justification",
+            "There is an urgency to inform the public.",
+        );
+        assert!(template.render(&ctx).unwrap().contains("552(a)(6)(E)"));
+    }
+
+    #[test]
+    fn test_front_matter_and_missing_fields() {
+        let source = "+++\ndescription = \"Test\"\n[fields]\nregulation = \"28 C.F.R. 16\"\n+++\n\
+                      To {agency}\n{agency_address}\nUnder {regulation}, {records}.\n";
+        let template = LetterTemplate::parse("test", source).unwrap();
+        assert_eq!(template.description.as_deref(), Some("Test"));
+
+        let mut ctx = LetterContext::default();
+        ctx.set("agency", "FBI");
+        assert!(matches!(
+            template.render(&ctx),
+            Err(LetterError::MissingFields(f)) if f == ["records"]
+        ));
+        ctx.set("records", "all files");
+        // The empty address line is dropped
+        assert_eq!(
+            template.render(&ctx).unwrap(),
+            "To FBI\nUnder 28 C.F.R. 16, all files.\n"
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let profiles: LetterProfiles = toml::from_str(
+            "[fields]\nrequester_name = \"Jane\"\n\n[agencies.fbi]\nagency = \"Federal Bureau of Investigation\"\n",
+        )
+        .unwrap();
+        assert!(profiles.agency("fbi").is_some());
+        let agency = profiles.agency("federal bureau of investigation");
+        assert!(agency.is_some());
+        let ctx = LetterContext::new(&profiles, agency);
+        assert_eq!(ctx.fields["requester_name"], "Jane");
+        assert_eq!(ctx.fields["agency"], "Federal Bureau of Investigation");
+    }
+
+    #[test]
+    fn test_markdown_to_text() {
+        let text = markdown_to_text(
+            "**Re: Request**\n\nSee [the memo](https://example.gov/a) and <https://example.gov/b>.\n- *Title* (ID `x`)",
+        );
+        assert_eq!(
+            text,
+            "Re: Request\n\nSee the memo (https://example.gov/a) and https://example.gov/b.\n- Title (ID x)\n"
+        );
+
+        let wrapped = wrap_text(&format!("- {}", "word ".repeat(20)), 30);
+        assert!(wrapped.lines().all(|l| l.chars().count() <= 30));
+        assert!(wrapped.lines().skip(1).all(|l| l.starts_with("  word")));
+    }
+}
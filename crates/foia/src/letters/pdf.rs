@@ -0,0 +1,171 @@
+//! Minimal PDF output for plain-text letters.
+//!
+//! Sets text in the standard Courier font on US Letter pages, so no fonts
+//! need embedding. Characters outside WinAnsi print as `?`.
+
+use super::wrap_text;
+
+const PAGE_WIDTH: u32 = 612;
+const PAGE_HEIGHT: u32 = 792;
+const MARGIN: u32 = 72;
+const FONT_SIZE: u32 = 11;
+const LEADING: u32 = 14;
+/// Courier advances 0.6em, so 11pt gives 70 characters in 6.5 inches.
+const LINE_WIDTH: usize = 70;
+const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+
+/// Lay out `text` as a PDF document.
+pub fn text_to_pdf(text: &str) -> Vec<u8> {
+    let wrapped = wrap_text(text, LINE_WIDTH);
+    let lines: Vec<&str> = wrapped.lines().collect();
+    let pages: Vec<&[&str]> = if lines.is_empty() {
+        vec![&[]]
+    } else {
+        lines.chunks(LINES_PER_PAGE).collect()
+    };
+
+    // Objects 1-3 are the catalog, page tree and font; each page then takes
+    // a page object and a content stream.
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + 2 * i).collect();
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (page, id) in pages.iter().zip(&page_ids) {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                id + 1
+            )
+            .into_bytes(),
+        );
+        let content = page_content(page);
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend_from_slice(&content);
+        stream.extend_from_slice(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        out.extend_from_slice(object);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = out.len();
+    out.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    out
+}
+
+fn page_content(lines: &[&str]) -> Vec<u8> {
+    let mut content = format!(
+        "BT\n/F1 {} Tf\n{} TL\n{} {} Td\n",
+        FONT_SIZE,
+        LEADING,
+        MARGIN,
+        PAGE_HEIGHT - MARGIN - FONT_SIZE
+    );
+    for line in lines {
+        content.push('(');
+        content.push_str(&escape(line));
+        content.push_str(") Tj T*\n");
+    }
+    content.push_str("ET");
+    content.into_bytes()
+}
+
+/// Encode a line as a PDF string body, octal-escaping non-ASCII bytes so
+/// the content stream stays 7-bit.
+fn escape(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\t' => out.push_str("    "),
+            _ => out.push_str(&format!("\\{:03o}", win_ansi(c))),
+        }
+    }
+    out
+}
+
+fn win_ansi(c: char) -> u8 {
+    match c {
+        '\u{a0}'..='\u{ff}' => c as u8,
+        '€' => 0x80,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        _ => b'?',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_to_pdf() {
+        let text = "Re: Request (§ 552) — \\ test\n".repeat(LINES_PER_PAGE + 1);
+        let pdf = text_to_pdf(&text);
+        let body = String::from_utf8(pdf).expect("PDF is ASCII");
+        assert!(body.starts_with("%PDF-1.4"));
+        assert!(body.contains("/Count 2"));
+        assert!(body.contains(r"(Re: Request \(\247 552\) \227 \\ test) Tj T*"));
+
+        // The xref offsets point at their objects
+        let startxref: usize = body
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|s| s.lines().next())
+            .and_then(|s| s.parse().ok())
+            .unwrap();
+        assert!(body[startxref..].starts_with("xref"));
+        let entries: Vec<usize> = body[startxref..]
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .map(|l| l[..10].parse().unwrap())
+            .collect();
+        assert_eq!(entries.len(), 7);
+        for (i, offset) in entries.iter().enumerate() {
+            assert!(body[*offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+}
@@ -12,6 +12,7 @@ pub mod config;
 #[cfg(feature = "gis")]
 pub mod gis_data;
 pub mod http_client;
+pub mod letters;
 pub mod llm;
 pub mod migrations;
 pub mod models;
@@ -0,0 +1,54 @@
+use cetane::prelude::*;
+
+pub fn migration() -> Migration {
+    Migration::new("0015_tombstones_legal_holds")
+        .depends_on(&["0014_search_indexes"])
+        // Tombstones record deleted documents so scrapers and imports don't
+        // bring them back. Deliberately no FK: the document row is gone.
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"CREATE TABLE IF NOT EXISTS document_tombstones (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id TEXT NOT NULL,
+    source_id TEXT NOT NULL,
+    source_url TEXT NOT NULL,
+    content_hash TEXT,
+    reason TEXT,
+    deleted_at TEXT NOT NULL
+)"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"CREATE TABLE IF NOT EXISTS document_tombstones (
+    id SERIAL PRIMARY KEY,
+    document_id TEXT NOT NULL,
+    source_id TEXT NOT NULL,
+    source_url TEXT NOT NULL,
+    content_hash TEXT,
+    reason TEXT,
+    deleted_at TEXT NOT NULL
+)"#,
+                ),
+        )
+        .operation(AddIndex::new(
+            "document_tombstones",
+            Index::new("idx_tombstones_source_url").column("source_url"),
+        ))
+        .operation(AddIndex::new(
+            "document_tombstones",
+            Index::new("idx_tombstones_content_hash").column("content_hash"),
+        ))
+        .operation(AddIndex::new(
+            "document_tombstones",
+            Index::new("idx_tombstones_document_id").column("document_id"),
+        ))
+        // Legal holds block deletion and dedup merges of a document.
+        .operation(
+            CreateTable::new("document_legal_holds")
+                .add_field(Field::new("document_id", FieldType::Text).primary_key())
+                .add_field(Field::new("reason", FieldType::Text))
+                .add_field(Field::new("placed_at", FieldType::Text).not_null()),
+        )
+}
@@ -0,0 +1,51 @@
+use cetane::prelude::*;
+
+pub fn migration() -> Migration {
+    Migration::new("0016_pii_spans")
+        .depends_on(&["0015_tombstones_legal_holds"])
+        // Detected PII spans, stored per page. Offsets are byte offsets into the
+        // page's best text (final, OCR, then PDF text).
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"CREATE TABLE IF NOT EXISTS document_pii_spans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id TEXT NOT NULL REFERENCES documents(id),
+    page_id INTEGER NOT NULL,
+    page_number INTEGER NOT NULL,
+    pii_type TEXT NOT NULL,
+    start_offset INTEGER NOT NULL,
+    end_offset INTEGER NOT NULL,
+    matched_text TEXT NOT NULL,
+    created_at TEXT NOT NULL
+)"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"CREATE TABLE IF NOT EXISTS document_pii_spans (
+    id SERIAL PRIMARY KEY,
+    document_id TEXT NOT NULL REFERENCES documents(id),
+    page_id INTEGER NOT NULL,
+    page_number INTEGER NOT NULL,
+    pii_type TEXT NOT NULL,
+    start_offset INTEGER NOT NULL,
+    end_offset INTEGER NOT NULL,
+    matched_text TEXT NOT NULL,
+    created_at TEXT NOT NULL
+)"#,
+                ),
+        )
+        .operation(AddIndex::new(
+            "document_pii_spans",
+            Index::new("idx_pii_spans_document").column("document_id"),
+        ))
+        .operation(AddIndex::new(
+            "document_pii_spans",
+            Index::new("idx_pii_spans_page").column("page_id"),
+        ))
+        .operation(AddIndex::new(
+            "document_pii_spans",
+            Index::new("idx_pii_spans_type").column("pii_type"),
+        ))
+}
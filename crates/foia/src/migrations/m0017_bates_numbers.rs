@@ -0,0 +1,98 @@
+use cetane::prelude::*;
+
+pub fn migration() -> Migration {
+    Migration::new("0017_bates_numbers")
+        .depends_on(&["0016_pii_spans"])
+        // Bates ranges on documents and the stamp on each page
+        .operation(AddField::new(
+            "documents",
+            Field::new("bates_begin", FieldType::Text),
+        ))
+        .operation(AddField::new(
+            "documents",
+            Field::new("bates_end", FieldType::Text),
+        ))
+        .operation(AddField::new(
+            "document_pages",
+            Field::new("bates_number", FieldType::Text),
+        ))
+        .operation(AddIndex::new(
+            "documents",
+            Index::new("idx_documents_bates_begin").column("bates_begin"),
+        ))
+        .operation(AddIndex::new(
+            "document_pages",
+            Index::new("idx_pages_bates_number").column("bates_number"),
+        ))
+        // Backfill ranges recorded in metadata by earlier Concordance imports,
+        // normalized the way `BatesNumber` prints them so lookups match
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"UPDATE documents
+SET bates_begin = UPPER(TRIM(json_extract(metadata, '$.begin_bates'))),
+    bates_end = UPPER(TRIM(COALESCE(json_extract(metadata, '$.end_bates'), json_extract(metadata, '$.begin_bates'))))
+WHERE metadata LIKE '%"import_source":"concordance"%'
+  AND json_extract(metadata, '$.begin_bates') IS NOT NULL"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"UPDATE documents
+SET bates_begin = UPPER(TRIM(metadata::jsonb ->> 'begin_bates')),
+    bates_end = UPPER(TRIM(COALESCE(metadata::jsonb ->> 'end_bates', metadata::jsonb ->> 'begin_bates')))
+WHERE metadata LIKE '%"import_source":"concordance"%'
+  AND metadata::jsonb ->> 'begin_bates' IS NOT NULL"#,
+                ),
+        )
+        // Stamp existing pages: page N carries the Nth number of the range,
+        // keeping the counter's zero padding; pages past the end stay blank
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"UPDATE document_pages
+SET bates_number = b.prefix || CASE
+        WHEN length(b.first + document_pages.page_number - 1) >= b.width
+            THEN CAST(b.first + document_pages.page_number - 1 AS TEXT)
+        ELSE substr('00000000000000000000' || (b.first + document_pages.page_number - 1), -b.width)
+    END
+FROM (
+    SELECT id,
+           UPPER(RTRIM(TRIM(bates_begin), '0123456789')) AS prefix,
+           length(TRIM(bates_begin)) - length(RTRIM(TRIM(bates_begin), '0123456789')) AS width,
+           CAST(substr(TRIM(bates_begin), length(RTRIM(TRIM(bates_begin), '0123456789')) + 1) AS INTEGER) AS first,
+           CAST(substr(TRIM(bates_end), length(RTRIM(TRIM(bates_end), '0123456789')) + 1) AS INTEGER) AS last
+    FROM documents
+    WHERE bates_begin IS NOT NULL AND bates_end IS NOT NULL
+) AS b
+WHERE document_pages.document_id = b.id
+  AND document_pages.bates_number IS NULL
+  AND document_pages.page_number >= 1
+  AND b.width > 0
+  AND b.first + document_pages.page_number - 1 <= b.last"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"UPDATE document_pages AS p
+SET bates_number = b.prefix || CASE
+        WHEN length((b.first + p.page_number - 1)::text) >= b.width
+            THEN (b.first + p.page_number - 1)::text
+        ELSE lpad((b.first + p.page_number - 1)::text, b.width, '0')
+    END
+FROM (
+    SELECT id,
+           upper(regexp_replace(trim(bates_begin), '[0-9]+$', '')) AS prefix,
+           length(substring(trim(bates_begin) from '[0-9]+$')) AS width,
+           substring(trim(bates_begin) from '[0-9]+$')::bigint AS first,
+           substring(trim(bates_end) from '[0-9]+$')::bigint AS last
+    FROM documents
+    WHERE trim(bates_begin) ~ '[0-9]$' AND trim(bates_end) ~ '[0-9]$'
+) AS b
+WHERE p.document_id = b.id
+  AND p.bates_number IS NULL
+  AND p.page_number >= 1
+  AND b.first + p.page_number - 1 <= b.last"#,
+                ),
+        )
+}
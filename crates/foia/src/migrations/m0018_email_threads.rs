@@ -0,0 +1,83 @@
+use cetane::prelude::*;
+
+pub fn migration() -> Migration {
+    Migration::new("0018_email_threads")
+        .depends_on(&["0017_bates_numbers"])
+        // Reconstructed conversation threads, one row per email document.
+        // Rebuilt wholesale by `annotate threads`.
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"CREATE TABLE IF NOT EXISTS email_messages (
+    document_id TEXT PRIMARY KEY NOT NULL REFERENCES documents(id),
+    thread_id TEXT NOT NULL,
+    parent_document_id TEXT,
+    message_id TEXT,
+    subject TEXT,
+    sender TEXT,
+    sent_at TEXT,
+    depth INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL
+)"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"CREATE TABLE IF NOT EXISTS email_messages (
+    document_id TEXT PRIMARY KEY NOT NULL REFERENCES documents(id),
+    thread_id TEXT NOT NULL,
+    parent_document_id TEXT,
+    message_id TEXT,
+    subject TEXT,
+    sender TEXT,
+    sent_at TEXT,
+    depth INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL
+)"#,
+                ),
+        )
+        // Sender -> recipient edges, one per recipient of each message
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"CREATE TABLE IF NOT EXISTS email_correspondence (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id TEXT NOT NULL REFERENCES documents(id),
+    sender TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    sent_at TEXT
+)"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"CREATE TABLE IF NOT EXISTS email_correspondence (
+    id SERIAL PRIMARY KEY,
+    document_id TEXT NOT NULL REFERENCES documents(id),
+    sender TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    sent_at TEXT
+)"#,
+                ),
+        )
+        .operation(AddIndex::new(
+            "email_messages",
+            Index::new("idx_email_messages_thread").column("thread_id"),
+        ))
+        .operation(AddIndex::new(
+            "email_messages",
+            Index::new("idx_email_messages_message_id").column("message_id"),
+        ))
+        .operation(AddIndex::new(
+            "email_correspondence",
+            Index::new("idx_email_correspondence_document").column("document_id"),
+        ))
+        .operation(AddIndex::new(
+            "email_correspondence",
+            Index::new("idx_email_correspondence_pair")
+                .column("sender")
+                .column("recipient"),
+        ))
+}
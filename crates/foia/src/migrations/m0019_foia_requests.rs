@@ -0,0 +1,90 @@
+use cetane::prelude::*;
+
+const FOIA_REQUESTS: &str = r#"CREATE TABLE IF NOT EXISTS foia_requests (
+    id TEXT PRIMARY KEY NOT NULL,
+    agency TEXT NOT NULL,
+    tracking_number TEXT,
+    subject TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL,
+    filed_at TEXT,
+    deadline_rule TEXT NOT NULL DEFAULT 'business',
+    response_days INTEGER NOT NULL DEFAULT 20,
+    extension_days INTEGER NOT NULL DEFAULT 0,
+    closed_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+)"#;
+
+const FOIA_REQUEST_DOCUMENTS: &str = r#"CREATE TABLE IF NOT EXISTS foia_request_documents (
+    request_id TEXT NOT NULL REFERENCES foia_requests(id),
+    document_id TEXT NOT NULL REFERENCES documents(id),
+    linked_at TEXT NOT NULL,
+    PRIMARY KEY (request_id, document_id)
+)"#;
+
+pub fn migration() -> Migration {
+    Migration::new("0019_foia_requests")
+        .depends_on(&["0018_email_threads"])
+        // Requests we filed ourselves, with their statutory deadline inputs.
+        // Dates are YYYY-MM-DD; the due date is computed, not stored.
+        .operation(
+            RunSql::portable()
+                .for_backend("sqlite", FOIA_REQUESTS)
+                .for_backend("postgres", FOIA_REQUESTS),
+        )
+        // Status history, correspondence and notes for each request
+        .operation(
+            RunSql::portable()
+                .for_backend(
+                    "sqlite",
+                    r#"CREATE TABLE IF NOT EXISTS foia_request_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    request_id TEXT NOT NULL REFERENCES foia_requests(id),
+    kind TEXT NOT NULL,
+    status TEXT,
+    summary TEXT,
+    body TEXT,
+    document_id TEXT,
+    occurred_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+)"#,
+                )
+                .for_backend(
+                    "postgres",
+                    r#"CREATE TABLE IF NOT EXISTS foia_request_events (
+    id SERIAL PRIMARY KEY,
+    request_id TEXT NOT NULL REFERENCES foia_requests(id),
+    kind TEXT NOT NULL,
+    status TEXT,
+    summary TEXT,
+    body TEXT,
+    document_id TEXT,
+    occurred_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+)"#,
+                ),
+        )
+        // Documents released in response to a request
+        .operation(
+            RunSql::portable()
+                .for_backend("sqlite", FOIA_REQUEST_DOCUMENTS)
+                .for_backend("postgres", FOIA_REQUEST_DOCUMENTS),
+        )
+        .operation(AddIndex::new(
+            "foia_requests",
+            Index::new("idx_foia_requests_status").column("status"),
+        ))
+        .operation(AddIndex::new(
+            "foia_requests",
+            Index::new("idx_foia_requests_agency").column("agency"),
+        ))
+        .operation(AddIndex::new(
+            "foia_request_events",
+            Index::new("idx_foia_request_events_request").column("request_id"),
+        ))
+        .operation(AddIndex::new(
+            "foia_request_documents",
+            Index::new("idx_foia_request_documents_document").column("document_id"),
+        ))
+}
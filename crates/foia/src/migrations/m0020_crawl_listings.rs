@@ -0,0 +1,24 @@
+use cetane::prelude::*;
+
+const CRAWL_LISTINGS: &str = r#"CREATE TABLE IF NOT EXISTS crawl_listings (
+    source_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    link_count INTEGER NOT NULL DEFAULT 0,
+    checked_at TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    walked_at TEXT NOT NULL,
+    PRIMARY KEY (source_id, url)
+)"#;
+
+pub fn migration() -> Migration {
+    Migration::new("0020_crawl_listings")
+        .depends_on(&["0019_foia_requests"])
+        // Fingerprints of listing pages, so incremental crawls can stop
+        // paginating once they reach pages that haven't changed.
+        .operation(
+            RunSql::portable()
+                .for_backend("sqlite", CRAWL_LISTINGS)
+                .for_backend("postgres", CRAWL_LISTINGS),
+        )
+}
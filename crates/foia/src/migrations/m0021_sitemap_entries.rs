@@ -0,0 +1,23 @@
+use cetane::prelude::*;
+
+const CRAWL_SITEMAP_ENTRIES: &str = r#"CREATE TABLE IF NOT EXISTS crawl_sitemap_entries (
+    source_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    sitemap_url TEXT NOT NULL,
+    lastmod TEXT,
+    first_seen_at TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    PRIMARY KEY (source_id, url)
+)"#;
+
+pub fn migration() -> Migration {
+    Migration::new("0021_sitemap_entries")
+        .depends_on(&["0020_crawl_listings"])
+        // Last seen <lastmod> of every sitemap entry, including child
+        // sitemaps, so sitemap discovery only enqueues new or changed URLs.
+        .operation(
+            RunSql::portable()
+                .for_backend("sqlite", CRAWL_SITEMAP_ENTRIES)
+                .for_backend("postgres", CRAWL_SITEMAP_ENTRIES),
+        )
+}
@@ -0,0 +1,20 @@
+use cetane::prelude::*;
+
+pub fn migration() -> Migration {
+    Migration::new("0022_crawl_priority")
+        .depends_on(&["0021_sitemap_entries"])
+        // Frontier score; pending URLs are claimed highest priority first
+        .operation(AddField::new(
+            "crawl_urls",
+            Field::new("priority", FieldType::Integer)
+                .not_null()
+                .default("0"),
+        ))
+        .operation(AddIndex::new(
+            "crawl_urls",
+            Index::new("idx_crawl_urls_frontier")
+                .column("source_id")
+                .column("status")
+                .column("priority"),
+        ))
+}
@@ -0,0 +1,21 @@
+use cetane::prelude::*;
+
+pub fn migration() -> Migration {
+    Migration::new("0023_url_leases")
+        .depends_on(&["0022_crawl_priority"])
+        // Worker holding a `fetching` URL, and when its claim lapses
+        .operation(AddField::new(
+            "crawl_urls",
+            Field::new("claimed_by", FieldType::Text),
+        ))
+        .operation(AddField::new(
+            "crawl_urls",
+            Field::new("lease_expires_at", FieldType::Text),
+        ))
+        .operation(AddIndex::new(
+            "crawl_urls",
+            Index::new("idx_crawl_urls_lease")
+                .column("status")
+                .column("lease_expires_at"),
+        ))
+}
@@ -12,6 +12,15 @@ mod m0011_constraints;
 mod m0012_scraper_configs;
 mod m0013_analysis_lookup_index;
 mod m0014_search_indexes;
+mod m0015_tombstones_legal_holds;
+mod m0016_pii_spans;
+mod m0017_bates_numbers;
+mod m0018_email_threads;
+mod m0019_foia_requests;
+mod m0020_crawl_listings;
+mod m0021_sitemap_entries;
+mod m0022_crawl_priority;
+mod m0023_url_leases;
 
 use cetane::prelude::MigrationRegistry;
 
@@ -31,5 +40,14 @@ pub fn registry() -> MigrationRegistry {
     reg.register(m0012_scraper_configs::migration());
     reg.register(m0013_analysis_lookup_index::migration());
     reg.register(m0014_search_indexes::migration());
+    reg.register(m0015_tombstones_legal_holds::migration());
+    reg.register(m0016_pii_spans::migration());
+    reg.register(m0017_bates_numbers::migration());
+    reg.register(m0018_email_threads::migration());
+    reg.register(m0019_foia_requests::migration());
+    reg.register(m0020_crawl_listings::migration());
+    reg.register(m0021_sitemap_entries::migration());
+    reg.register(m0022_crawl_priority::migration());
+    reg.register(m0023_url_leases::migration());
     reg
 }
@@ -0,0 +1,184 @@
+//! Bates numbers and ranges.
+//!
+//! Litigation productions stamp every page with a Bates number: an optional
+//! alphanumeric prefix followed by a zero-padded counter (e.g. `ABC000123`).
+//! A produced document spans a contiguous range of those numbers, so the
+//! page a number falls on is its offset from the start of the range.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A single Bates number split into prefix and counter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BatesNumber {
+    /// Everything before the trailing digits, including any separator.
+    /// Prefixes are case-insensitive and normalized to upper case.
+    pub prefix: String,
+    /// Numeric counter.
+    pub number: u64,
+    /// Zero-padded width of the counter.
+    pub width: usize,
+}
+
+impl BatesNumber {
+    /// Parse a Bates number. Returns `None` unless it ends in digits.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let digits_start = s
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_ascii_digit())
+            .last()
+            .map(|(i, _)| i)?;
+        let digits = &s[digits_start..];
+        Some(Self {
+            prefix: s[..digits_start].to_ascii_uppercase(),
+            number: digits.parse().ok()?,
+            width: digits.len(),
+        })
+    }
+
+    /// Whether two numbers belong to the same sequence.
+    fn same_sequence(&self, other: &Self) -> bool {
+        self.prefix == other.prefix && self.width == other.width
+    }
+}
+
+impl fmt::Display for BatesNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{:0width$}",
+            self.prefix,
+            self.number,
+            width = self.width
+        )
+    }
+}
+
+/// A document's inclusive Bates range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatesRange {
+    pub begin: BatesNumber,
+    pub end: BatesNumber,
+}
+
+impl BatesRange {
+    /// Parse a range from its begin and end numbers.
+    ///
+    /// Returns `None` if either end fails to parse, the ends belong to
+    /// different sequences, or the range runs backwards.
+    pub fn parse(begin: &str, end: &str) -> Option<Self> {
+        let begin = BatesNumber::parse(begin)?;
+        let end = BatesNumber::parse(end)?;
+        if !begin.same_sequence(&end) || end.number < begin.number {
+            return None;
+        }
+        Some(Self { begin, end })
+    }
+
+    /// Number of pages covered by the range.
+    pub fn page_count(&self) -> u64 {
+        self.end.number - self.begin.number + 1
+    }
+
+    /// The 1-indexed page a Bates number falls on, if it is in range.
+    pub fn page_of(&self, bates: &BatesNumber) -> Option<u32> {
+        if !self.begin.same_sequence(bates)
+            || bates.number < self.begin.number
+            || bates.number > self.end.number
+        {
+            return None;
+        }
+        u32::try_from(bates.number - self.begin.number + 1).ok()
+    }
+
+    /// The Bates number stamped on a 1-indexed page, if it is in range.
+    pub fn page_number(&self, page: u32) -> Option<BatesNumber> {
+        let number = self.begin.number + u64::from(page.checked_sub(1)?);
+        (number <= self.end.number).then(|| BatesNumber {
+            number,
+            ..self.begin.clone()
+        })
+    }
+}
+
+impl fmt::Display for BatesRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.begin == self.end {
+            write!(f, "{}", self.begin)
+        } else {
+            write!(f, "{} - {}", self.begin, self.end)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bates_number() {
+        let b = BatesNumber::parse(" ABC000123 ").unwrap();
+        assert_eq!(b.prefix, "ABC");
+        assert_eq!(b.number, 123);
+        assert_eq!(b.width, 6);
+        assert_eq!(b.to_string(), "ABC000123");
+
+        let b = BatesNumber::parse("DOJ-OGR-0042").unwrap();
+        assert_eq!(b.prefix, "DOJ-OGR-");
+        assert_eq!(b.to_string(), "DOJ-OGR-0042");
+
+        let b = BatesNumber::parse("efta00000001").unwrap();
+        assert_eq!(b.to_string(), "EFTA00000001");
+
+        assert!(BatesNumber::parse("ABC").is_none());
+        assert!(BatesNumber::parse("").is_none());
+    }
+
+    #[test]
+    fn test_range_page_lookup() {
+        let range = BatesRange::parse("ABC000100", "ABC000104").unwrap();
+        assert_eq!(range.page_count(), 5);
+        assert_eq!(
+            range.page_of(&BatesNumber::parse("ABC000100").unwrap()),
+            Some(1)
+        );
+        assert_eq!(
+            range.page_of(&BatesNumber::parse("abc000103").unwrap()),
+            Some(4)
+        );
+        assert_eq!(
+            range.page_of(&BatesNumber::parse("ABC000105").unwrap()),
+            None
+        );
+        assert_eq!(
+            range.page_of(&BatesNumber::parse("XYZ000101").unwrap()),
+            None
+        );
+        assert_eq!(
+            range.page_of(&BatesNumber::parse("ABC00101").unwrap()),
+            None
+        );
+
+        assert_eq!(range.page_number(2).unwrap().to_string(), "ABC000101");
+        assert!(range.page_number(0).is_none());
+        assert!(range.page_number(6).is_none());
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(BatesRange::parse("ABC000200", "ABC000100").is_none());
+        assert!(BatesRange::parse("ABC000100", "XYZ000200").is_none());
+        assert!(BatesRange::parse("ABC000100", "ABC").is_none());
+    }
+
+    #[test]
+    fn test_range_display() {
+        let single = BatesRange::parse("ABC0001", "ABC0001").unwrap();
+        assert_eq!(single.to_string(), "ABC0001");
+        let range = BatesRange::parse("ABC0001", "ABC0009").unwrap();
+        assert_eq!(range.to_string(), "ABC0001 - ABC0009");
+    }
+}
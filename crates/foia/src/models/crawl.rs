@@ -7,6 +7,7 @@
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
 /// Status of a discovered URL in the crawl.
@@ -86,6 +87,8 @@ pub enum DiscoveryMethod {
     Manual,
     /// Imported from Concordance DAT/OPT load files, queued for verification.
     ConcordanceImport,
+    /// Found in the results of a submitted search form.
+    FormSearch,
 }
 
 impl DiscoveryMethod {
@@ -106,6 +109,7 @@ impl DiscoveryMethod {
             Self::CommonPath => "common_path",
             Self::Manual => "manual",
             Self::ConcordanceImport => "concordance_import",
+            Self::FormSearch => "form_search",
         }
     }
 
@@ -126,6 +130,7 @@ impl DiscoveryMethod {
             "common_path" => Some(Self::CommonPath),
             "manual" => Some(Self::Manual),
             "concordance_import" => Some(Self::ConcordanceImport),
+            "form_search" => Some(Self::FormSearch),
             _ => None,
         }
     }
@@ -169,8 +174,20 @@ pub struct CrawlUrl {
     pub content_hash: Option<String>,
     /// Link to Document if this is a document URL.
     pub document_id: Option<String>,
+
+    /// Frontier score; higher-priority URLs are fetched first.
+    pub priority: i32,
+
+    // Lease held while `Fetching`
+    /// Worker that claimed this URL.
+    pub claimed_by: Option<String>,
+    /// When the claim lapses and another worker may take the URL.
+    pub lease_expires_at: Option<DateTime<Utc>>,
 }
 
+/// `discovery_context` key for metadata carried over from discovery.
+const DOCUMENT_METADATA_KEY: &str = "document_metadata";
+
 impl CrawlUrl {
     /// Create a new discovered URL.
     pub fn new(
@@ -197,9 +214,33 @@ impl CrawlUrl {
             last_modified: None,
             content_hash: None,
             document_id: None,
+            priority: 0,
+            claimed_by: None,
+            lease_expires_at: None,
         }
     }
 
+    /// Set the frontier score.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Attach metadata to store on the document fetched from this URL.
+    pub fn with_document_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.discovery_context
+            .insert(DOCUMENT_METADATA_KEY.to_string(), metadata);
+        self
+    }
+
+    /// Metadata to store on the document fetched from this URL.
+    pub fn document_metadata(&self) -> serde_json::Value {
+        self.discovery_context
+            .get(DOCUMENT_METADATA_KEY)
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({}))
+    }
+
     /// Mark URL as currently being fetched.
     pub fn mark_fetching(&mut self) {
         self.status = UrlStatus::Fetching;
@@ -302,6 +343,53 @@ impl CrawlRequest {
     }
 }
 
+/// Fingerprint of the links on a listing page.
+///
+/// Incremental crawls compare a page's current fingerprint against the
+/// stored one to tell whether anything was added since the last visit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListingPage {
+    pub source_id: String,
+    pub url: String,
+    /// SHA-256 over the page's links, in page order.
+    pub fingerprint: String,
+    pub link_count: u32,
+    /// Last time the page was fetched.
+    pub checked_at: DateTime<Utc>,
+    /// Last time the fingerprint differed from the stored one.
+    pub changed_at: DateTime<Utc>,
+    /// Last time the crawl continued past this page.
+    pub walked_at: DateTime<Utc>,
+}
+
+impl ListingPage {
+    /// Fingerprint a page's links. Order matters: on a newest-first listing
+    /// a new item shifts every link down, which changes the fingerprint.
+    pub fn fingerprint<S: AsRef<str>>(links: &[S]) -> String {
+        let mut hasher = Sha256::new();
+        for link in links {
+            hasher.update(link.as_ref().as_bytes());
+            hasher.update(b"\n");
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Whether the page holds nothing new since the crawl last walked past
+    /// it: the fingerprint matches, or the page only shifted and none of its
+    /// links are new. Pages not walked past within `max_age` never count as
+    /// unchanged, so the full listing is revisited periodically.
+    pub fn is_unchanged(
+        &self,
+        fingerprint: &str,
+        has_new_links: bool,
+        max_age: Option<Duration>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        (self.fingerprint == fingerprint || !has_new_links)
+            && max_age.is_none_or(|age| now - self.walked_at < age)
+    }
+}
+
 /// Aggregate state of a crawl for a source.
 ///
 /// Used to determine whether a crawl needs to resume and what
@@ -391,6 +479,7 @@ mod tests {
             DiscoveryMethod::CommonPath,
             DiscoveryMethod::Manual,
             DiscoveryMethod::ConcordanceImport,
+            DiscoveryMethod::FormSearch,
         ];
 
         for method in methods {
@@ -551,4 +640,32 @@ mod tests {
         assert!(!req.was_conditional);
         assert!(!req.was_not_modified);
     }
+
+    #[test]
+    fn test_listing_page_unchanged() {
+        let links = ["https://example.com/b.pdf", "https://example.com/a.pdf"];
+        let fingerprint = ListingPage::fingerprint(&links);
+        assert_ne!(
+            fingerprint,
+            ListingPage::fingerprint(&["https://example.com/a.pdf", "https://example.com/b.pdf"])
+        );
+
+        let now = Utc::now();
+        let page = ListingPage {
+            source_id: "source1".to_string(),
+            url: "https://example.com/list".to_string(),
+            fingerprint: fingerprint.clone(),
+            link_count: 2,
+            checked_at: now,
+            changed_at: now - Duration::days(30),
+            walked_at: now - Duration::days(3),
+        };
+        assert!(page.is_unchanged(&fingerprint, false, None, now));
+        assert!(page.is_unchanged(&fingerprint, false, Some(Duration::days(7)), now));
+        // Walked too long ago: treat as changed so the crawl goes past it
+        assert!(!page.is_unchanged(&fingerprint, false, Some(Duration::days(2)), now));
+        // Shifted by items added on an earlier page, but nothing new here
+        assert!(page.is_unchanged("other", false, None, now));
+        assert!(!page.is_unchanged("other", true, None, now));
+    }
 }
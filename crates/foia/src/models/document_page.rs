@@ -67,6 +67,8 @@ pub struct DocumentPage {
     pub created_at: DateTime<Utc>,
     /// When this page was last updated.
     pub updated_at: DateTime<Utc>,
+    /// Bates number stamped on this page, derived from the document's range.
+    pub bates_number: Option<String>,
 }
 
 impl DocumentPage {
@@ -84,6 +86,7 @@ impl DocumentPage {
             ocr_status: PageOcrStatus::Pending,
             created_at: now,
             updated_at: now,
+            bates_number: None, // Set by stamp_bates_pages
         }
     }
 
@@ -0,0 +1,285 @@
+//! FOIA requests we file ourselves.
+//!
+//! Unlike documents, which come from what agencies publish, these track our
+//! own requests from draft to final response: filing date, statutory
+//! deadline, status history, correspondence and the documents released.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::business_days::{add_business_days, business_days_between};
+
+/// Federal FOIA response window (5 U.S.C. 552(a)(6)(A)).
+pub const DEFAULT_RESPONSE_DAYS: u32 = 20;
+
+/// Where a request stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestStatus {
+    /// Written but not yet sent.
+    Draft,
+    Filed,
+    Acknowledged,
+    Processing,
+    /// Some records released, more to come.
+    Partial,
+    Fulfilled,
+    NoRecords,
+    Denied,
+    Appealed,
+    Withdrawn,
+}
+
+impl RequestStatus {
+    pub const ALL: [RequestStatus; 10] = [
+        Self::Draft,
+        Self::Filed,
+        Self::Acknowledged,
+        Self::Processing,
+        Self::Partial,
+        Self::Fulfilled,
+        Self::NoRecords,
+        Self::Denied,
+        Self::Appealed,
+        Self::Withdrawn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Filed => "filed",
+            Self::Acknowledged => "acknowledged",
+            Self::Processing => "processing",
+            Self::Partial => "partial",
+            Self::Fulfilled => "fulfilled",
+            Self::NoRecords => "no_records",
+            Self::Denied => "denied",
+            Self::Appealed => "appealed",
+            Self::Withdrawn => "withdrawn",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == s)
+    }
+
+    /// Whether the agency still owes a response.
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self,
+            Self::Filed | Self::Acknowledged | Self::Processing | Self::Partial | Self::Appealed
+        )
+    }
+
+    /// Whether the request is finished.
+    pub fn is_closed(&self) -> bool {
+        !self.is_open() && *self != Self::Draft
+    }
+}
+
+/// How the response window is counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadlineRule {
+    /// Working days, skipping weekends and federal holidays (federal FOIA).
+    #[default]
+    Business,
+    /// Calendar days, as in many state public records laws.
+    Calendar,
+}
+
+impl DeadlineRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Business => "business",
+            Self::Calendar => "calendar",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "business" => Some(Self::Business),
+            "calendar" => Some(Self::Calendar),
+            _ => None,
+        }
+    }
+
+    /// The date `days` after `start` under this rule.
+    pub fn add_days(&self, start: NaiveDate, days: u32) -> NaiveDate {
+        match self {
+            Self::Business => add_business_days(start, days),
+            Self::Calendar => start + Duration::days(i64::from(days)),
+        }
+    }
+
+    /// Days after `start` up to `end` under this rule (negative if earlier).
+    pub fn days_between(&self, start: NaiveDate, end: NaiveDate) -> i64 {
+        match self {
+            Self::Business => business_days_between(start, end),
+            Self::Calendar => (end - start).num_days(),
+        }
+    }
+}
+
+/// A FOIA request we filed (or are about to).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoiaRequest {
+    pub id: String,
+    pub agency: String,
+    /// Number assigned by the agency.
+    pub tracking_number: Option<String>,
+    pub subject: String,
+    /// The records requested, or any other notes.
+    pub description: Option<String>,
+    pub status: RequestStatus,
+    /// When the agency received the request.
+    pub filed_at: Option<NaiveDate>,
+    pub deadline_rule: DeadlineRule,
+    pub response_days: u32,
+    /// Extra days claimed by the agency (e.g. 10 for unusual circumstances).
+    pub extension_days: u32,
+    pub closed_at: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FoiaRequest {
+    /// Create a draft request with the federal 20-business-day deadline.
+    pub fn new(agency: &str, subject: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            agency: agency.to_string(),
+            tracking_number: None,
+            subject: subject.to_string(),
+            description: None,
+            status: RequestStatus::Draft,
+            filed_at: None,
+            deadline_rule: DeadlineRule::Business,
+            response_days: DEFAULT_RESPONSE_DAYS,
+            extension_days: 0,
+            closed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Statutory response deadline, including any extension.
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        let filed = self.filed_at?;
+        Some(
+            self.deadline_rule
+                .add_days(filed, self.response_days + self.extension_days),
+        )
+    }
+
+    /// Days left until the deadline (negative once overdue), counted under
+    /// the request's rule. `None` for drafts and closed requests.
+    pub fn days_remaining(&self, today: NaiveDate) -> Option<i64> {
+        if !self.status.is_open() {
+            return None;
+        }
+        let due = self.due_date()?;
+        Some(self.deadline_rule.days_between(today, due))
+    }
+
+    /// Whether an open request is past its deadline.
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.days_remaining(today).is_some_and(|days| days < 0)
+    }
+}
+
+/// Kind of entry in a request's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestEventKind {
+    /// Status change.
+    Status,
+    /// Correspondence we sent.
+    Sent,
+    /// Correspondence from the agency.
+    Received,
+    Note,
+}
+
+impl RequestEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Sent => "sent",
+            Self::Received => "received",
+            Self::Note => "note",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "status" => Some(Self::Status),
+            "sent" => Some(Self::Sent),
+            "received" => Some(Self::Received),
+            "note" => Some(Self::Note),
+            _ => None,
+        }
+    }
+}
+
+/// An entry in a request's history: status change, correspondence or note.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestEvent {
+    pub id: i64,
+    pub request_id: String,
+    pub kind: RequestEventKind,
+    /// New status, for status changes.
+    pub status: Option<RequestStatus>,
+    pub summary: Option<String>,
+    /// Full text of a letter or email.
+    pub body: Option<String>,
+    /// Stored copy of the correspondence, if any.
+    pub document_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_status_roundtrip() {
+        for status in RequestStatus::ALL {
+            assert_eq!(RequestStatus::from_str(status.as_str()), Some(status));
+        }
+        assert_eq!(RequestStatus::from_str("bogus"), None);
+        assert!(RequestStatus::Appealed.is_open());
+        assert!(RequestStatus::Denied.is_closed());
+        assert!(!RequestStatus::Draft.is_open() && !RequestStatus::Draft.is_closed());
+    }
+
+    #[test]
+    fn test_due_date_and_overdue() {
+        let mut request = FoiaRequest::new("FBI", "Field office records");
+        assert_eq!(request.due_date(), None);
+
+        request.status = RequestStatus::Filed;
+        request.filed_at = Some(date("2024-11-22"));
+        request.response_days = 5;
+        assert_eq!(request.due_date(), Some(date("2024-12-02")));
+        assert_eq!(request.days_remaining(date("2024-11-29")), Some(1));
+        assert!(!request.is_overdue(date("2024-12-02")));
+        assert!(request.is_overdue(date("2024-12-03")));
+
+        request.extension_days = 10;
+        assert_eq!(request.due_date(), Some(date("2024-12-16")));
+
+        request.deadline_rule = DeadlineRule::Calendar;
+        assert_eq!(request.due_date(), Some(date("2024-12-07")));
+        assert_eq!(request.days_remaining(date("2024-12-10")), Some(-3));
+
+        request.status = RequestStatus::Fulfilled;
+        assert_eq!(request.days_remaining(date("2024-12-10")), None);
+        assert!(!request.is_overdue(date("2024-12-10")));
+    }
+}
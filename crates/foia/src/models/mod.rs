@@ -1,17 +1,25 @@
 //! Data models for foia.
 
 mod archive;
+mod bates;
 mod crawl;
 mod document;
 mod document_page;
+mod foia_request;
+mod pii;
 mod service_status;
 mod source;
 mod virtual_file;
 
 pub use archive::ArchiveService;
-pub use crawl::{CrawlRequest, CrawlUrl, DiscoveryMethod, UrlStatus};
-pub use document::{Document, DocumentStatus, DocumentVersion};
+pub use bates::{BatesNumber, BatesRange};
+pub use crawl::{CrawlRequest, CrawlUrl, DiscoveryMethod, ListingPage, UrlStatus};
+pub use document::{ContentHashes, Document, DocumentStatus, DocumentVersion};
 pub use document_page::{DocumentPage, PageOcrStatus};
+pub use foia_request::{
+    DeadlineRule, FoiaRequest, RequestEvent, RequestEventKind, RequestStatus, DEFAULT_RESPONSE_DAYS,
+};
+pub use pii::{pii_scanned_version, PiiMask, PiiSpan, PiiType, PII_ANNOTATION_TYPE};
 pub use service_status::{ScraperStats, ServiceState, ServiceStatus, ServiceType};
 pub use source::{Source, SourceType};
 pub use virtual_file::{VirtualFile, VirtualFileStatus};
@@ -0,0 +1,211 @@
+//! Personally identifiable information (PII) spans and masking.
+//!
+//! Detection lives in `foia-annotate`; this module holds the shared types and
+//! the masking applied when publishing text. Masking happens on output only,
+//! so stored text is never altered and disabling public mode reverses it.
+
+use serde::{Deserialize, Serialize};
+
+/// Annotation type recorded once a document has been scanned for PII.
+pub const PII_ANNOTATION_TYPE: &str = "pii_detection";
+
+/// ID of the document version covered by its recorded PII scan.
+///
+/// `None` when there is no successful scan, or when the scan predates
+/// version tracking.
+pub fn pii_scanned_version(metadata: &serde_json::Value) -> Option<i64> {
+    let annotation = metadata.get("annotations")?.get(PII_ANNOTATION_TYPE)?;
+    if annotation.get("error").is_some_and(|e| !e.is_null()) {
+        return None;
+    }
+    let data: serde_json::Value = serde_json::from_str(annotation.get("data")?.as_str()?).ok()?;
+    data.get("version_id")?.as_i64()
+}
+
+/// Category of detected PII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiType {
+    /// US Social Security number.
+    Ssn,
+    /// Phone number.
+    Phone,
+    /// Email address.
+    Email,
+    /// Date of birth (a date introduced by a DOB label).
+    DateOfBirth,
+    /// USCIS alien registration number.
+    ANumber,
+    /// Street address.
+    StreetAddress,
+}
+
+impl PiiType {
+    pub const ALL: [PiiType; 6] = [
+        Self::Ssn,
+        Self::Phone,
+        Self::Email,
+        Self::DateOfBirth,
+        Self::ANumber,
+        Self::StreetAddress,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ssn => "ssn",
+            Self::Phone => "phone",
+            Self::Email => "email",
+            Self::DateOfBirth => "date_of_birth",
+            Self::ANumber => "a_number",
+            Self::StreetAddress => "street_address",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "ssn" => Some(Self::Ssn),
+            "phone" => Some(Self::Phone),
+            "email" => Some(Self::Email),
+            "date_of_birth" => Some(Self::DateOfBirth),
+            "a_number" => Some(Self::ANumber),
+            "street_address" => Some(Self::StreetAddress),
+            _ => None,
+        }
+    }
+
+    /// Placeholder substituted for masked values.
+    pub fn mask_label(&self) -> &'static str {
+        match self {
+            Self::Ssn => "[REDACTED SSN]",
+            Self::Phone => "[REDACTED PHONE]",
+            Self::Email => "[REDACTED EMAIL]",
+            Self::DateOfBirth => "[REDACTED DOB]",
+            Self::ANumber => "[REDACTED A-NUMBER]",
+            Self::StreetAddress => "[REDACTED ADDRESS]",
+        }
+    }
+}
+
+/// A detected PII span within a page's text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PiiSpan {
+    pub pii_type: PiiType,
+    /// Byte offset of the span start.
+    pub start: usize,
+    /// Byte offset one past the span end.
+    pub end: usize,
+    /// The matched text.
+    pub text: String,
+}
+
+/// Masks known PII values in text.
+///
+/// Values are matched literally rather than by offset, so the same mask
+/// applies to every text variant of a page (PDF, OCR, final), to combined
+/// document text and to search snippets.
+#[derive(Debug, Clone, Default)]
+pub struct PiiMask {
+    values: Vec<(String, PiiType)>,
+}
+
+impl PiiMask {
+    /// Build a mask from detected spans.
+    pub fn new<'a>(spans: impl IntoIterator<Item = (PiiType, &'a str)>) -> Self {
+        let mut values: Vec<(String, PiiType)> = spans
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(t, text)| (text.to_string(), t))
+            .collect();
+        // Longest first so a value containing another is replaced whole
+        values.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        values.dedup_by(|a, b| a.0 == b.0);
+        Self { values }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Replace every occurrence of a known value with its type's placeholder.
+    pub fn mask(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for (value, pii_type) in &self.values {
+            if out.contains(value.as_str()) {
+                out = out.replace(value.as_str(), pii_type.mask_label());
+            }
+        }
+        out
+    }
+
+    /// Mask optional text.
+    pub fn mask_opt(&self, text: Option<String>) -> Option<String> {
+        text.map(|t| self.mask(&t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pii_type_roundtrip() {
+        for t in PiiType::ALL {
+            assert_eq!(PiiType::from_str(t.as_str()), Some(t));
+        }
+        assert_eq!(PiiType::from_str("bogus"), None);
+    }
+
+    #[test]
+    fn test_mask_replaces_all_occurrences() {
+        let mask = PiiMask::new([
+            (PiiType::Ssn, "123-45-6789"),
+            (PiiType::Email, "jdoe@example.com"),
+            (PiiType::Ssn, "123-45-6789"),
+        ]);
+        let masked = mask.mask("SSN 123-45-6789, mail jdoe@example.com, again 123-45-6789");
+        assert_eq!(
+            masked,
+            "SSN [REDACTED SSN], mail [REDACTED EMAIL], again [REDACTED SSN]"
+        );
+    }
+
+    #[test]
+    fn test_mask_prefers_longest_value() {
+        let mask = PiiMask::new([
+            (PiiType::Phone, "555-0100"),
+            (PiiType::StreetAddress, "12 Main Street, 555-0100"),
+        ]);
+        assert_eq!(
+            mask.mask("at 12 Main Street, 555-0100 today"),
+            "at [REDACTED ADDRESS] today"
+        );
+    }
+
+    #[test]
+    fn test_empty_mask_is_identity() {
+        let mask = PiiMask::default();
+        assert!(mask.is_empty());
+        assert_eq!(mask.mask("nothing here"), "nothing here");
+    }
+
+    #[test]
+    fn test_pii_scanned_version() {
+        let scanned = |data: &str, error: Option<&str>| {
+            serde_json::json!({
+                "annotations": {
+                    PII_ANNOTATION_TYPE: { "version": 2, "data": data, "error": error }
+                }
+            })
+        };
+        assert_eq!(
+            pii_scanned_version(&scanned(r#"{"version_id":7,"counts":{}}"#, None)),
+            Some(7)
+        );
+        assert_eq!(pii_scanned_version(&scanned(r#"{"ssn":1}"#, None)), None);
+        assert_eq!(
+            pii_scanned_version(&scanned(r#"{"version_id":7}"#, Some("failed"))),
+            None
+        );
+        assert_eq!(pii_scanned_version(&serde_json::json!({})), None);
+    }
+}
@@ -10,6 +10,7 @@ pub enum ServiceType {
     Scraper,
     Ocr,
     Server,
+    Worker,
 }
 
 impl ServiceType {
@@ -18,6 +19,7 @@ impl ServiceType {
             Self::Scraper => "scraper",
             Self::Ocr => "ocr",
             Self::Server => "server",
+            Self::Worker => "worker",
         }
     }
 
@@ -26,6 +28,7 @@ impl ServiceType {
             "scraper" => Some(Self::Scraper),
             "ocr" => Some(Self::Ocr),
             "server" => Some(Self::Server),
+            "worker" => Some(Self::Worker),
             _ => None,
         }
     }
@@ -123,6 +126,26 @@ impl ServiceStatus {
         )
     }
 
+    /// Create a new download worker status.
+    ///
+    /// `worker_id` is the ID its URL leases are held under.
+    pub fn new_worker(worker_id: &str, source_id: Option<&str>) -> Self {
+        Self::new_service(
+            format!("worker:{}", worker_id),
+            ServiceType::Worker,
+            source_id.map(|s| s.to_string()),
+        )
+    }
+
+    /// Default download worker ID: hostname and process ID.
+    pub fn default_worker_id() -> String {
+        format!(
+            "{}-{}",
+            get_hostname().unwrap_or_else(|| "localhost".to_string()),
+            std::process::id()
+        )
+    }
+
     /// Create a new server status.
     pub fn new_server() -> Self {
         Self::new_service("server:main".to_string(), ServiceType::Server, None)
@@ -203,7 +226,12 @@ mod tests {
 
     #[test]
     fn test_service_type_roundtrip() {
-        for stype in [ServiceType::Scraper, ServiceType::Ocr, ServiceType::Server] {
+        for stype in [
+            ServiceType::Scraper,
+            ServiceType::Ocr,
+            ServiceType::Server,
+            ServiceType::Worker,
+        ] {
             let s = stype.as_str();
             let parsed = ServiceType::from_str(s);
             assert_eq!(parsed, Some(stype));
@@ -257,6 +285,14 @@ mod tests {
         assert_eq!(status.status, ServiceState::Starting);
     }
 
+    #[test]
+    fn test_new_worker() {
+        let status = ServiceStatus::new_worker("tor-exit-1", Some("doj"));
+        assert_eq!(status.id, "worker:tor-exit-1");
+        assert_eq!(status.service_type, ServiceType::Worker);
+        assert_eq!(status.source_id, Some("doj".to_string()));
+    }
+
     #[test]
     fn test_set_running() {
         let mut status = ServiceStatus::new_scraper("test");
@@ -104,4 +104,12 @@ impl VirtualFile {
             updated_at: now,
         }
     }
+
+    /// Archive path for the `index`th named attachment of an email.
+    ///
+    /// Attachments can share a filename, so the part index keeps each one
+    /// distinct.
+    pub fn email_attachment_path(index: usize, filename: &str) -> String {
+        format!("{}/{}", index, filename)
+    }
 }
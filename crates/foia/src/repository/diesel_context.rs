@@ -8,6 +8,7 @@ use std::path::Path;
 use super::diesel_config_history::DieselConfigHistoryRepository;
 use super::diesel_crawl::DieselCrawlRepository;
 use super::diesel_document::DieselDocumentRepository;
+use super::diesel_foia_request::DieselFoiaRequestRepository;
 use super::diesel_scraper_config::DieselScraperConfigRepository;
 use super::diesel_service_status::DieselServiceStatusRepository;
 use super::diesel_source::DieselSourceRepository;
@@ -103,6 +104,11 @@ impl DieselDbContext {
         DieselServiceStatusRepository::new(self.pool.clone())
     }
 
+    /// Get a FOIA request tracking repository.
+    pub fn foia_requests(&self) -> DieselFoiaRequestRepository {
+        DieselFoiaRequestRepository::new(self.pool.clone())
+    }
+
     /// Test that the database connection works.
     ///
     /// For PostgreSQL, this validates credentials and network connectivity.
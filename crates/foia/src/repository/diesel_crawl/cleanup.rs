@@ -5,7 +5,9 @@ use diesel_async::RunQueryDsl;
 
 use super::DieselCrawlRepository;
 use crate::repository::pool::DieselError;
-use crate::schema::{crawl_config, crawl_requests, crawl_urls};
+use crate::schema::{
+    crawl_config, crawl_listings, crawl_requests, crawl_sitemap_entries, crawl_urls,
+};
 use crate::with_conn;
 
 impl DieselCrawlRepository {
@@ -49,6 +51,16 @@ impl DieselCrawlRepository {
                 .execute(&mut conn)
                 .await?;
 
+            diesel::delete(crawl_listings::table.filter(crawl_listings::source_id.eq(source_id)))
+                .execute(&mut conn)
+                .await?;
+
+            diesel::delete(
+                crawl_sitemap_entries::table.filter(crawl_sitemap_entries::source_id.eq(source_id)),
+            )
+            .execute(&mut conn)
+            .await?;
+
             Ok(())
         })
     }
@@ -0,0 +1,100 @@
+//! Listing page fingerprints for the crawl repository.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselCrawlRepository;
+use crate::models::ListingPage;
+use crate::repository::models::CrawlListingRecord;
+use crate::repository::pool::DieselError;
+use crate::schema::crawl_listings;
+use crate::with_conn;
+
+impl DieselCrawlRepository {
+    /// Get the stored fingerprint for a listing page.
+    pub async fn get_listing(
+        &self,
+        source_id: &str,
+        url: &str,
+    ) -> Result<Option<ListingPage>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let record: Option<CrawlListingRecord> = crawl_listings::table
+                .filter(crawl_listings::source_id.eq(source_id))
+                .filter(crawl_listings::url.eq(url))
+                .first(&mut conn)
+                .await
+                .optional()?;
+            Ok(record.map(ListingPage::from))
+        })
+    }
+
+    /// Store a listing page's fingerprint.
+    ///
+    /// `walked` records that the crawl continued past the page; pages where
+    /// the crawl stopped keep their previous `walked_at`.
+    pub async fn record_listing(
+        &self,
+        source_id: &str,
+        url: &str,
+        fingerprint: &str,
+        link_count: u32,
+        walked: bool,
+    ) -> Result<(), DieselError> {
+        let now = Utc::now().to_rfc3339();
+
+        with_conn!(self.pool, conn, {
+            let existing: Option<CrawlListingRecord> = crawl_listings::table
+                .filter(crawl_listings::source_id.eq(source_id))
+                .filter(crawl_listings::url.eq(url))
+                .first(&mut conn)
+                .await
+                .optional()?;
+
+            match existing {
+                Some(existing) => {
+                    let record = CrawlListingRecord {
+                        changed_at: if existing.fingerprint == fingerprint {
+                            existing.changed_at
+                        } else {
+                            now.clone()
+                        },
+                        walked_at: if walked {
+                            now.clone()
+                        } else {
+                            existing.walked_at
+                        },
+                        fingerprint: fingerprint.to_string(),
+                        link_count: link_count as i32,
+                        checked_at: now,
+                        ..existing
+                    };
+                    diesel::update(
+                        crawl_listings::table
+                            .filter(crawl_listings::source_id.eq(source_id))
+                            .filter(crawl_listings::url.eq(url)),
+                    )
+                    .set(&record)
+                    .execute(&mut conn)
+                    .await?;
+                }
+                None => {
+                    diesel::insert_into(crawl_listings::table)
+                        .values(CrawlListingRecord {
+                            source_id: source_id.to_string(),
+                            url: url.to_string(),
+                            fingerprint: fingerprint.to_string(),
+                            link_count: link_count as i32,
+                            checked_at: now.clone(),
+                            changed_at: now.clone(),
+                            walked_at: now,
+                        })
+                        .execute(&mut conn)
+                        .await?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -9,12 +9,16 @@
 //! - `requests.rs`: Request logging
 //! - `stats.rs`: Statistics and analytics
 //! - `config.rs`: Config hash management
+//! - `listings.rs`: Listing page fingerprints
+//! - `sitemaps.rs`: Sitemap entry lastmod tracking
 //! - `cleanup.rs`: Cleanup operations
 
 mod cleanup;
 mod config;
+mod listings;
 mod queue;
 mod requests;
+mod sitemaps;
 mod stats;
 mod urls;
 
@@ -23,10 +27,11 @@ use std::collections::HashMap;
 
 use diesel::prelude::*;
 
-use super::models::{CrawlRequestRecord, CrawlUrlRecord};
+use super::models::{CrawlListingRecord, CrawlRequestRecord, CrawlUrlRecord};
 use super::pool::DbPool;
 use super::{parse_datetime, parse_datetime_opt};
-use crate::models::{CrawlRequest, CrawlUrl, DiscoveryMethod, UrlStatus};
+use crate::config::BudgetConfig;
+use crate::models::{CrawlRequest, CrawlUrl, DiscoveryMethod, ListingPage, UrlStatus};
 
 /// Common fields for crawl URL database records.
 trait CrawlUrlFields {
@@ -46,6 +51,9 @@ trait CrawlUrlFields {
     fn last_modified(&self) -> Option<&str>;
     fn content_hash(&self) -> Option<&str>;
     fn document_id(&self) -> Option<&str>;
+    fn priority(&self) -> i32;
+    fn claimed_by(&self) -> Option<&str>;
+    fn lease_expires_at(&self) -> Option<&str>;
 }
 
 /// Convert any crawl URL record to a CrawlUrl model.
@@ -72,6 +80,9 @@ fn crawl_url_from_record<T: CrawlUrlFields>(record: &T) -> Result<CrawlUrl, dies
         last_modified: record.last_modified().map(ToString::to_string),
         content_hash: record.content_hash().map(ToString::to_string),
         document_id: record.document_id().map(ToString::to_string),
+        priority: record.priority(),
+        claimed_by: record.claimed_by().map(ToString::to_string),
+        lease_expires_at: record.lease_expires_at().map(parse_datetime),
     })
 }
 
@@ -124,6 +135,15 @@ impl CrawlUrlFields for CrawlUrlRecord {
     fn document_id(&self) -> Option<&str> {
         self.document_id.as_deref()
     }
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+    fn claimed_by(&self) -> Option<&str> {
+        self.claimed_by.as_deref()
+    }
+    fn lease_expires_at(&self) -> Option<&str> {
+        self.lease_expires_at.as_deref()
+    }
 }
 
 /// Convert a database record to a domain model.
@@ -163,6 +183,20 @@ impl TryFrom<CrawlRequestRecord> for CrawlRequest {
     }
 }
 
+impl From<CrawlListingRecord> for ListingPage {
+    fn from(record: CrawlListingRecord) -> Self {
+        ListingPage {
+            source_id: record.source_id,
+            url: record.url,
+            fingerprint: record.fingerprint,
+            link_count: record.link_count as u32,
+            checked_at: parse_datetime(&record.checked_at),
+            changed_at: parse_datetime(&record.changed_at),
+            walked_at: parse_datetime(&record.walked_at),
+        }
+    }
+}
+
 /// Diesel-based crawl repository with compile-time query checking.
 #[derive(Clone)]
 pub struct DieselCrawlRepository {
@@ -211,6 +245,35 @@ pub struct RequestStats {
     pub total_requests: u64,
 }
 
+/// A worker's claim on the URLs it fetches.
+///
+/// Leased URLs stay `fetching` until the lease expires; after that any
+/// worker may claim them again, so a crashed host doesn't strand its URLs.
+#[derive(Debug, Clone)]
+pub struct UrlLease {
+    pub worker_id: String,
+    pub duration: chrono::Duration,
+}
+
+/// What a source has fetched during the current run, for budget checks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub requests: u64,
+    pub bytes: u64,
+    pub new_documents: u64,
+}
+
+impl BudgetUsage {
+    /// Whether any of the budget's limits has been reached.
+    pub fn exhausts(&self, budget: &BudgetConfig) -> bool {
+        budget.max_requests.is_some_and(|max| self.requests >= max)
+            || budget.max_bytes.is_some_and(|max| self.bytes >= max)
+            || budget
+                .max_new_documents
+                .is_some_and(|max| self.new_documents >= max)
+    }
+}
+
 /// Combined crawl statistics.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CrawlStats {
@@ -284,6 +347,12 @@ pub(crate) struct CrawlUrlRecordRaw {
     pub content_hash: Option<String>,
     #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
     pub document_id: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub priority: i32,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub claimed_by: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub lease_expires_at: Option<String>,
 }
 
 impl CrawlUrlFields for CrawlUrlRecordRaw {
@@ -335,6 +404,15 @@ impl CrawlUrlFields for CrawlUrlRecordRaw {
     fn document_id(&self) -> Option<&str> {
         self.document_id.as_deref()
     }
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+    fn claimed_by(&self) -> Option<&str> {
+        self.claimed_by.as_deref()
+    }
+    fn lease_expires_at(&self) -> Option<&str> {
+        self.lease_expires_at.as_deref()
+    }
 }
 
 impl TryFrom<CrawlUrlRecordRaw> for CrawlUrl {
@@ -379,6 +457,9 @@ mod tests {
                 last_modified TEXT,
                 content_hash TEXT,
                 document_id TEXT,
+                priority INTEGER NOT NULL DEFAULT 0,
+                claimed_by TEXT,
+                lease_expires_at TEXT,
                 UNIQUE(source_id, url)
             );
 
@@ -404,6 +485,37 @@ mod tests {
                 config_hash TEXT NOT NULL,
                 updated_at TEXT NOT NULL
             );
+
+            CREATE TABLE IF NOT EXISTS crawl_listings (
+                source_id TEXT NOT NULL,
+                url TEXT NOT NULL,
+                fingerprint TEXT NOT NULL,
+                link_count INTEGER NOT NULL DEFAULT 0,
+                checked_at TEXT NOT NULL,
+                changed_at TEXT NOT NULL,
+                walked_at TEXT NOT NULL,
+                PRIMARY KEY (source_id, url)
+            );
+
+            CREATE TABLE IF NOT EXISTS crawl_sitemap_entries (
+                source_id TEXT NOT NULL,
+                url TEXT NOT NULL,
+                sitemap_url TEXT NOT NULL,
+                lastmod TEXT,
+                first_seen_at TEXT NOT NULL,
+                changed_at TEXT NOT NULL,
+                PRIMARY KEY (source_id, url)
+            );
+
+            CREATE TABLE IF NOT EXISTS document_tombstones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                source_url TEXT NOT NULL,
+                content_hash TEXT,
+                reason TEXT,
+                deleted_at TEXT NOT NULL
+            );
             "#,
         )
         .await
@@ -458,6 +570,34 @@ mod tests {
         assert_eq!(*counts.get("discovered").unwrap_or(&0), 1);
     }
 
+    #[tokio::test]
+    async fn test_add_url_skips_tombstoned() {
+        let (pool, _dir) = setup_test_db().await;
+        if let DbPool::Sqlite(ref sqlite_pool) = pool {
+            let mut conn = sqlite_pool.get().await.unwrap();
+            conn.batch_execute(
+                "INSERT INTO document_tombstones (document_id, source_id, source_url, deleted_at) \
+                 VALUES ('doc-1', 'test-source', 'https://example.com/removed.pdf', '2024-01-01T00:00:00Z')",
+            )
+            .await
+            .unwrap();
+        }
+        let repo = DieselCrawlRepository::new(pool);
+
+        let crawl_url = CrawlUrl::new(
+            "https://example.com/removed.pdf".to_string(),
+            "test-source".to_string(),
+            DiscoveryMethod::Seed,
+            None,
+            0,
+        );
+        assert!(!repo.add_url(&crawl_url).await.unwrap());
+        assert!(!repo
+            .url_exists("test-source", "https://example.com/removed.pdf")
+            .await
+            .unwrap());
+    }
+
     #[tokio::test]
     async fn test_claim_pending_url() {
         let (pool, _dir) = setup_test_db().await;
@@ -487,6 +627,165 @@ mod tests {
         assert!(pending.is_none());
     }
 
+    #[tokio::test]
+    async fn test_claim_by_priority_within_budget() {
+        let (pool, _dir) = setup_test_db().await;
+        if let DbPool::Sqlite(ref sqlite_pool) = pool {
+            let mut conn = sqlite_pool.get().await.unwrap();
+            conn.batch_execute(
+                "CREATE TABLE documents (id TEXT PRIMARY KEY, source_id TEXT NOT NULL, created_at TEXT NOT NULL)",
+            )
+            .await
+            .unwrap();
+        }
+        let repo = DieselCrawlRepository::new(pool);
+        let since = chrono::Utc::now() - chrono::Duration::minutes(1);
+
+        for (url, depth, priority) in [
+            ("https://example.com/shallow", 1, 0),
+            ("https://example.com/deep/report.pdf", 3, 40),
+            ("https://example.com/news", 1, -20),
+        ] {
+            let crawl_url = CrawlUrl::new(
+                url.to_string(),
+                "test-source".to_string(),
+                DiscoveryMethod::HtmlLink,
+                None,
+                depth,
+            )
+            .with_priority(priority);
+            repo.add_url(&crawl_url).await.unwrap();
+        }
+
+        let budgets = HashMap::from([(
+            "test-source".to_string(),
+            BudgetConfig {
+                max_requests: Some(1),
+                ..Default::default()
+            },
+        )]);
+        let claimed = repo
+            .claim_pending_url_within(Some("test-source"), &budgets, since, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.url, "https://example.com/deep/report.pdf");
+        assert_eq!(claimed.priority, 40);
+
+        let mut request = CrawlRequest::new(
+            "test-source".to_string(),
+            claimed.url.clone(),
+            "GET".to_string(),
+        );
+        request.response_size = Some(2048);
+        repo.log_request(&request).await.unwrap();
+
+        let usage = repo.get_budget_usage("test-source", since).await.unwrap();
+        assert_eq!(usage.requests, 1);
+        assert_eq!(usage.bytes, 2048);
+        assert_eq!(usage.new_documents, 0);
+
+        // Over budget: nothing more is claimed for the source
+        assert!(repo
+            .claim_pending_url_within(Some("test-source"), &budgets, since, None)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repo
+            .claim_pending_url_within(None, &budgets, since, None)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Without a budget, the next best URL is claimed
+        let next = repo
+            .claim_pending_url(Some("test-source"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.url, "https://example.com/shallow");
+    }
+
+    #[tokio::test]
+    async fn test_leased_claims() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselCrawlRepository::new(pool);
+        let budgets = HashMap::new();
+        let since = chrono::Utc::now();
+        let lease = |worker_id: &str, secs| UrlLease {
+            worker_id: worker_id.to_string(),
+            duration: chrono::Duration::seconds(secs),
+        };
+
+        let crawl_url = CrawlUrl::new(
+            "https://example.com/leased".to_string(),
+            "test-source".to_string(),
+            DiscoveryMethod::Seed,
+            None,
+            0,
+        );
+        repo.add_url(&crawl_url).await.unwrap();
+
+        // Host A's lease has already lapsed, as if it had crashed
+        let a = lease("host-a", -1);
+        let claimed = repo
+            .claim_pending_url_within(None, &budgets, since, Some(&a))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.claimed_by.as_deref(), Some("host-a"));
+
+        // Host B takes the URL over and A can no longer renew it
+        let b = lease("host-b", 60);
+        let taken = repo
+            .claim_pending_url_within(None, &budgets, since, Some(&b))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(taken.url, claimed.url);
+        assert!(!repo.renew_lease(&claimed, &a).await.unwrap());
+        assert!(repo.renew_lease(&taken, &b).await.unwrap());
+
+        // A live lease is never handed out twice
+        assert!(repo
+            .claim_pending_url_within(None, &budgets, since, Some(&a))
+            .await
+            .unwrap()
+            .is_none());
+
+        // Unleased claims are only recovered on request
+        let crawl_url = CrawlUrl::new(
+            "https://example.com/unleased".to_string(),
+            "test-source".to_string(),
+            DiscoveryMethod::Seed,
+            None,
+            0,
+        );
+        repo.add_url(&crawl_url).await.unwrap();
+        repo.claim_pending_url(None).await.unwrap().unwrap();
+        assert_eq!(repo.recover_stale_urls(None, false).await.unwrap(), 0);
+        assert_eq!(repo.recover_stale_urls(None, true).await.unwrap(), 1);
+
+        let recovered = repo
+            .get_url("test-source", "https://example.com/unleased")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovered.status, UrlStatus::Discovered);
+
+        // Finishing a URL ends its lease
+        let mut fetched = taken.clone();
+        fetched.status = UrlStatus::Fetched;
+        repo.update_url(&fetched).await.unwrap();
+        let stored = repo
+            .get_url("test-source", &taken.url)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.claimed_by, None);
+        assert_eq!(stored.lease_expires_at, None);
+    }
+
     #[tokio::test]
     async fn test_config_hash() {
         let (pool, _dir) = setup_test_db().await;
@@ -519,6 +818,100 @@ mod tests {
         assert!(changed);
     }
 
+    #[tokio::test]
+    async fn test_listing_fingerprints() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselCrawlRepository::new(pool);
+        let url = "https://example.com/reading-room?page=1";
+
+        assert!(repo
+            .get_listing("test-source", url)
+            .await
+            .unwrap()
+            .is_none());
+
+        repo.record_listing("test-source", url, "fp1", 20, true)
+            .await
+            .unwrap();
+        let first = repo.get_listing("test-source", url).await.unwrap().unwrap();
+        assert_eq!(first.fingerprint, "fp1");
+        assert_eq!(first.link_count, 20);
+
+        // Same fingerprint, crawl stopped here: nothing moves but checked_at
+        repo.record_listing("test-source", url, "fp1", 20, false)
+            .await
+            .unwrap();
+        let second = repo.get_listing("test-source", url).await.unwrap().unwrap();
+        assert_eq!(second.changed_at, first.changed_at);
+        assert_eq!(second.walked_at, first.walked_at);
+        assert!(second.checked_at >= first.checked_at);
+
+        // New fingerprint
+        repo.record_listing("test-source", url, "fp2", 21, true)
+            .await
+            .unwrap();
+        let third = repo.get_listing("test-source", url).await.unwrap().unwrap();
+        assert_eq!(third.fingerprint, "fp2");
+        assert_eq!(third.link_count, 21);
+        assert!(third.changed_at >= first.changed_at);
+
+        repo.clear_source_all("test-source").await.unwrap();
+        assert!(repo
+            .get_listing("test-source", url)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sitemap_lastmods() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselCrawlRepository::new(pool);
+        let sitemap = "https://example.com/sitemap.xml";
+
+        assert!(repo
+            .get_sitemap_lastmods("test-source")
+            .await
+            .unwrap()
+            .is_empty());
+
+        repo.record_sitemap_entry("test-source", "https://example.com/a.pdf", sitemap, None)
+            .await
+            .unwrap();
+        repo.record_sitemap_entry(
+            "test-source",
+            "https://example.com/b.pdf",
+            sitemap,
+            Some("2024-06-01"),
+        )
+        .await
+        .unwrap();
+        repo.record_sitemap_entry(
+            "test-source",
+            "https://example.com/b.pdf",
+            sitemap,
+            Some("2024-06-02"),
+        )
+        .await
+        .unwrap();
+
+        let lastmods = repo.get_sitemap_lastmods("test-source").await.unwrap();
+        assert_eq!(lastmods.len(), 2);
+        assert_eq!(lastmods["https://example.com/a.pdf"], None);
+        assert_eq!(
+            lastmods["https://example.com/b.pdf"].as_deref(),
+            Some("2024-06-02")
+        );
+        assert!(repo.get_sitemap_lastmods("other").await.unwrap().is_empty());
+
+        repo.clear_source_all("test-source").await.unwrap();
+        assert!(repo
+            .get_sitemap_lastmods("test-source")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     async fn insert_raw_crawl(pool: &DbPool, sql: &str) {
         match pool {
             DbPool::Sqlite(ref sqlite_pool) => {
@@ -577,4 +970,36 @@ mod tests {
             Some("test"),
         );
     }
+
+    #[tokio::test]
+    async fn test_get_requests_for_url() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselCrawlRepository::new(pool);
+
+        let url = "https://example.com/doc.pdf";
+        let mut first = CrawlRequest::new(
+            "test-source".to_string(),
+            url.to_string(),
+            "GET".to_string(),
+        );
+        first.response_status = Some(200);
+        let mut second = first.clone();
+        second.request_at = first.request_at + chrono::Duration::hours(1);
+        second.response_status = Some(304);
+        second.was_not_modified = true;
+        let other = CrawlRequest::new(
+            "test-source".to_string(),
+            "https://example.com/other".to_string(),
+            "GET".to_string(),
+        );
+
+        for request in [&second, &first, &other] {
+            repo.log_request(request).await.unwrap();
+        }
+
+        let requests = repo.get_requests_for_url("test-source", url).await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].response_status, Some(200));
+        assert!(requests[1].was_not_modified);
+    }
 }
@@ -1,10 +1,13 @@
 //! Queue and claiming operations for the crawl repository.
 
-use chrono::Utc;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
-use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::RunQueryDsl;
 
-use super::DieselCrawlRepository;
+use super::{BudgetUsage, DieselCrawlRepository, UrlLease};
+use crate::config::BudgetConfig;
 use crate::models::{CrawlUrl, UrlStatus};
 use crate::repository::models::CrawlUrlRecord;
 use crate::repository::pool::DieselError;
@@ -27,7 +30,11 @@ impl DieselCrawlRepository {
                         .eq("discovered")
                         .or(crawl_urls::status.eq("fetching")),
                 )
-                .order((crawl_urls::depth.asc(), crawl_urls::discovered_at.asc()))
+                .order((
+                    crawl_urls::priority.desc(),
+                    crawl_urls::depth.asc(),
+                    crawl_urls::discovered_at.asc(),
+                ))
                 .limit(limit)
                 .load::<CrawlUrlRecord>(&mut conn)
                 .await
@@ -35,48 +42,226 @@ impl DieselCrawlRepository {
         })
     }
 
-    /// Atomically claim a pending URL for processing.
+    /// Atomically claim the highest-priority pending URL for processing.
     pub async fn claim_pending_url(
         &self,
         source_id: Option<&str>,
     ) -> Result<Option<CrawlUrl>, DieselError> {
-        let source_id = source_id.map(|s| s.to_string());
+        self.claim_next_url(source_id, &[], None).await
+    }
+
+    /// Claim the highest-priority pending URL from a source that hasn't used
+    /// up its budget since `since`.
+    ///
+    /// With a `lease`, the URL is held for `lease.worker_id` until the lease
+    /// expires, after which any worker may claim it again. Usage is checked
+    /// before claiming, so concurrent workers can overshoot a limit by one
+    /// URL each.
+    pub async fn claim_pending_url_within(
+        &self,
+        source_id: Option<&str>,
+        budgets: &HashMap<String, BudgetConfig>,
+        since: DateTime<Utc>,
+        lease: Option<&UrlLease>,
+    ) -> Result<Option<CrawlUrl>, DieselError> {
+        let mut exhausted = Vec::new();
+        for (budget_source, budget) in budgets {
+            if source_id.is_some_and(|sid| sid != budget_source) {
+                continue;
+            }
+            if self
+                .get_budget_usage(budget_source, since)
+                .await?
+                .exhausts(budget)
+            {
+                exhausted.push(budget_source.clone());
+            }
+        }
+
+        if source_id.is_some() && !exhausted.is_empty() {
+            return Ok(None);
+        }
+        self.claim_next_url(source_id, &exhausted, lease).await
+    }
+
+    /// Extend a worker's lease on a URL it is fetching.
+    ///
+    /// Returns false if the lease was lost, i.e. it expired and another
+    /// worker claimed the URL, or the URL is no longer being fetched.
+    pub async fn renew_lease(
+        &self,
+        crawl_url: &CrawlUrl,
+        lease: &UrlLease,
+    ) -> Result<bool, DieselError> {
+        let expires_at = (Utc::now() + lease.duration).to_rfc3339();
+
+        with_conn!(self.pool, conn, {
+            let rows = diesel::update(
+                crawl_urls::table
+                    .filter(crawl_urls::source_id.eq(&crawl_url.source_id))
+                    .filter(crawl_urls::url.eq(&crawl_url.url))
+                    .filter(crawl_urls::status.eq("fetching"))
+                    .filter(crawl_urls::claimed_by.eq(&lease.worker_id)),
+            )
+            .set(crawl_urls::lease_expires_at.eq(&expires_at))
+            .execute(&mut conn)
+            .await?;
+            Ok(rows > 0)
+        })
+    }
+
+    /// Return stale `fetching` URLs to the queue.
+    ///
+    /// Expired leases are always released. URLs claimed without a lease carry
+    /// no expiry, so they are only released with `include_unleased`, which is
+    /// safe once nothing else is fetching from the source.
+    pub async fn recover_stale_urls(
+        &self,
+        source_id: Option<&str>,
+        include_unleased: bool,
+    ) -> Result<u64, DieselError> {
+        let now = Utc::now().to_rfc3339();
+
+        with_conn!(self.pool, conn, {
+            let mut query =
+                diesel::update(crawl_urls::table.filter(crawl_urls::status.eq("fetching")))
+                    .set((
+                        crawl_urls::status.eq("discovered"),
+                        crawl_urls::claimed_by.eq::<Option<String>>(None),
+                        crawl_urls::lease_expires_at.eq::<Option<String>>(None),
+                    ))
+                    .into_boxed();
+
+            if let Some(sid) = source_id {
+                query = query.filter(crawl_urls::source_id.eq(sid));
+            }
+            query = if include_unleased {
+                query.filter(
+                    crawl_urls::lease_expires_at
+                        .is_null()
+                        .or(crawl_urls::lease_expires_at.le(&now)),
+                )
+            } else {
+                query.filter(crawl_urls::lease_expires_at.le(&now))
+            };
+
+            query.execute(&mut conn).await.map(|n| n as u64)
+        })
+    }
 
+    /// What a source has fetched since `since`: requests and response bytes
+    /// from `crawl_requests`, and documents added.
+    pub async fn get_budget_usage(
+        &self,
+        source_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<BudgetUsage, DieselError> {
+        #[derive(QueryableByName)]
+        struct UsageRow {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            requests: i64,
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            bytes: i64,
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            new_documents: i64,
+        }
+
+        let since = since.to_rfc3339();
         with_conn!(self.pool, conn, {
-            conn.transaction(|conn| {
-                let source_id = source_id.clone();
-                Box::pin(async move {
-                    let mut query = crawl_urls::table
-                        .filter(crawl_urls::status.eq("discovered"))
-                        .order((crawl_urls::depth.asc(), crawl_urls::discovered_at.asc()))
-                        .limit(1)
-                        .into_boxed();
-
-                    if let Some(ref sid) = source_id {
-                        query = query.filter(crawl_urls::source_id.eq(sid));
-                    }
-
-                    let record: Option<CrawlUrlRecord> = query.first(conn).await.optional()?;
-
-                    if let Some(record) = record {
-                        diesel::update(
-                            crawl_urls::table
-                                .filter(crawl_urls::source_id.eq(&record.source_id))
-                                .filter(crawl_urls::url.eq(&record.url)),
-                        )
-                        .set(crawl_urls::status.eq("fetching"))
-                        .execute(conn)
-                        .await?;
-
-                        let mut crawl_url = CrawlUrl::try_from(record)?;
-                        crawl_url.status = UrlStatus::Fetching;
-                        Ok(Some(crawl_url))
-                    } else {
-                        Ok(None)
-                    }
-                })
+            let row: UsageRow = diesel::sql_query(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM crawl_requests
+                        WHERE source_id = $1 AND request_at >= $2) as requests,
+                    (SELECT COALESCE(SUM(response_size), 0) FROM crawl_requests
+                        WHERE source_id = $1 AND request_at >= $2) as bytes,
+                    (SELECT COUNT(*) FROM documents
+                        WHERE source_id = $1 AND created_at >= $2) as new_documents
+                "#,
+            )
+            .bind::<diesel::sql_types::Text, _>(source_id)
+            .bind::<diesel::sql_types::Text, _>(&since)
+            .get_result(&mut conn)
+            .await?;
+
+            Ok(BudgetUsage {
+                requests: row.requests as u64,
+                bytes: row.bytes as u64,
+                new_documents: row.new_documents as u64,
             })
-            .await
+        })
+    }
+
+    /// Claim a pending URL, skipping the excluded sources.
+    ///
+    /// URLs whose lease has expired are claimable again. The claim is a
+    /// conditional update that only succeeds while the URL is still
+    /// claimable, so workers on different hosts never get the same URL; a
+    /// worker that loses the race moves on to the next candidate.
+    async fn claim_next_url(
+        &self,
+        source_id: Option<&str>,
+        exclude: &[String],
+        lease: Option<&UrlLease>,
+    ) -> Result<Option<CrawlUrl>, DieselError> {
+        let exclude = exclude.to_vec();
+
+        with_conn!(self.pool, conn, {
+            loop {
+                let now = Utc::now();
+                let now_str = now.to_rfc3339();
+                let claimable = crawl_urls::status.eq("discovered").or(crawl_urls::status
+                    .eq("fetching")
+                    .and(crawl_urls::lease_expires_at.le(now_str.clone())));
+
+                let mut query = crawl_urls::table
+                    .filter(claimable.clone())
+                    .order((
+                        crawl_urls::priority.desc(),
+                        crawl_urls::depth.asc(),
+                        crawl_urls::discovered_at.asc(),
+                    ))
+                    .limit(1)
+                    .into_boxed();
+
+                if let Some(sid) = source_id {
+                    query = query.filter(crawl_urls::source_id.eq(sid));
+                }
+                if !exclude.is_empty() {
+                    query = query.filter(crawl_urls::source_id.ne_all(exclude.clone()));
+                }
+
+                let Some(record) = query.first::<CrawlUrlRecord>(&mut conn).await.optional()?
+                else {
+                    return Ok(None);
+                };
+
+                let claimed_by = lease.map(|l| l.worker_id.clone());
+                let lease_expires_at = lease.map(|l| now + l.duration);
+                let rows = diesel::update(
+                    crawl_urls::table
+                        .filter(crawl_urls::id.eq(record.id))
+                        .filter(claimable),
+                )
+                .set((
+                    crawl_urls::status.eq("fetching"),
+                    crawl_urls::claimed_by.eq(&claimed_by),
+                    crawl_urls::lease_expires_at.eq(lease_expires_at.map(|dt| dt.to_rfc3339())),
+                ))
+                .execute(&mut conn)
+                .await?;
+
+                if rows == 0 {
+                    // Another worker claimed it first
+                    continue;
+                }
+
+                let mut crawl_url = CrawlUrl::try_from(record)?;
+                crawl_url.status = UrlStatus::Fetching;
+                crawl_url.claimed_by = claimed_by;
+                crawl_url.lease_expires_at = lease_expires_at;
+                return Ok(Some(crawl_url));
+            }
         })
     }
 
@@ -106,7 +291,11 @@ impl DieselCrawlRepository {
                 .filter(crawl_urls::source_id.eq(source_id))
                 .filter(crawl_urls::status.eq("discovered"))
                 .filter(crawl_urls::retry_count.gt(0))
-                .order((crawl_urls::depth.asc(), crawl_urls::discovered_at.asc()))
+                .order((
+                    crawl_urls::priority.desc(),
+                    crawl_urls::depth.asc(),
+                    crawl_urls::discovered_at.asc(),
+                ))
                 .limit(limit)
                 .load::<CrawlUrlRecord>(&mut conn)
                 .await
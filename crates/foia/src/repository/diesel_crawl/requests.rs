@@ -7,6 +7,7 @@ use diesel_async::RunQueryDsl;
 use super::LastInsertId;
 use super::{DieselCrawlRepository, LastInsertRowId};
 use crate::models::CrawlRequest;
+use crate::repository::models::CrawlRequestRecord;
 use crate::repository::pool::{DbPool, DieselError};
 use crate::schema::crawl_requests;
 use crate::with_conn;
@@ -66,4 +67,22 @@ impl DieselCrawlRepository {
             Ok(id)
         })
     }
+
+    /// Get every request logged for a URL, oldest first.
+    pub async fn get_requests_for_url(
+        &self,
+        source_id: &str,
+        url: &str,
+    ) -> Result<Vec<CrawlRequest>, DieselError> {
+        let records: Vec<CrawlRequestRecord> = with_conn!(self.pool, conn, {
+            crawl_requests::table
+                .filter(crawl_requests::source_id.eq(source_id))
+                .filter(crawl_requests::url.eq(url))
+                .order(crawl_requests::request_at.asc())
+                .load(&mut conn)
+                .await
+        })?;
+
+        records.into_iter().map(CrawlRequest::try_from).collect()
+    }
 }
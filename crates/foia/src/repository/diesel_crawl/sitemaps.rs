@@ -0,0 +1,73 @@
+//! Sitemap entry lastmod tracking for the crawl repository.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselCrawlRepository;
+use crate::repository::pool::DieselError;
+use crate::schema::crawl_sitemap_entries;
+use crate::with_conn;
+
+impl DieselCrawlRepository {
+    /// Last seen `<lastmod>` of every sitemap entry for a source, by URL.
+    pub async fn get_sitemap_lastmods(
+        &self,
+        source_id: &str,
+    ) -> Result<HashMap<String, Option<String>>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let rows: Vec<(String, Option<String>)> = crawl_sitemap_entries::table
+                .filter(crawl_sitemap_entries::source_id.eq(source_id))
+                .select((crawl_sitemap_entries::url, crawl_sitemap_entries::lastmod))
+                .load(&mut conn)
+                .await?;
+            Ok(rows.into_iter().collect())
+        })
+    }
+
+    /// Store a new or changed sitemap entry.
+    pub async fn record_sitemap_entry(
+        &self,
+        source_id: &str,
+        url: &str,
+        sitemap_url: &str,
+        lastmod: Option<&str>,
+    ) -> Result<(), DieselError> {
+        let now = Utc::now().to_rfc3339();
+
+        with_conn!(self.pool, conn, {
+            // Try to update first
+            let updated = diesel::update(
+                crawl_sitemap_entries::table
+                    .filter(crawl_sitemap_entries::source_id.eq(source_id))
+                    .filter(crawl_sitemap_entries::url.eq(url)),
+            )
+            .set((
+                crawl_sitemap_entries::sitemap_url.eq(sitemap_url),
+                crawl_sitemap_entries::lastmod.eq(lastmod),
+                crawl_sitemap_entries::changed_at.eq(&now),
+            ))
+            .execute(&mut conn)
+            .await?;
+
+            // If no row was updated, insert
+            if updated == 0 {
+                diesel::insert_into(crawl_sitemap_entries::table)
+                    .values((
+                        crawl_sitemap_entries::source_id.eq(source_id),
+                        crawl_sitemap_entries::url.eq(url),
+                        crawl_sitemap_entries::sitemap_url.eq(sitemap_url),
+                        crawl_sitemap_entries::lastmod.eq(lastmod),
+                        crawl_sitemap_entries::first_seen_at.eq(&now),
+                        crawl_sitemap_entries::changed_at.eq(&now),
+                    ))
+                    .execute(&mut conn)
+                    .await?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -4,14 +4,16 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 
 use super::DieselCrawlRepository;
-use crate::models::CrawlUrl;
+use crate::models::{CrawlUrl, UrlStatus};
 use crate::repository::models::CrawlUrlRecord;
 use crate::repository::pool::DieselError;
-use crate::schema::crawl_urls;
+use crate::schema::{crawl_urls, document_tombstones};
 use crate::with_conn;
 
 impl DieselCrawlRepository {
     /// Add a discovered URL if not already known.
+    ///
+    /// URLs of deleted documents (see `document_tombstones`) are never re-added.
     pub async fn add_url(&self, crawl_url: &CrawlUrl) -> Result<bool, DieselError> {
         let status = crawl_url.status.as_str().to_string();
         let discovery_method = crawl_url.discovery_method.as_str().to_string();
@@ -36,6 +38,16 @@ impl DieselCrawlRepository {
                 return Ok(false);
             }
 
+            let tombstoned: i64 = document_tombstones::table
+                .filter(document_tombstones::source_url.eq(&crawl_url.url))
+                .select(count_star())
+                .first(&mut conn)
+                .await?;
+
+            if tombstoned > 0 {
+                return Ok(false);
+            }
+
             diesel::insert_into(crawl_urls::table)
                 .values((
                     crawl_urls::url.eq(&crawl_url.url),
@@ -54,6 +66,7 @@ impl DieselCrawlRepository {
                     crawl_urls::last_modified.eq(&crawl_url.last_modified),
                     crawl_urls::content_hash.eq(&crawl_url.content_hash),
                     crawl_urls::document_id.eq(&crawl_url.document_id),
+                    crawl_urls::priority.eq(crawl_url.priority),
                 ))
                 .execute(&mut conn)
                 .await?;
@@ -100,6 +113,15 @@ impl DieselCrawlRepository {
         let fetched_at = crawl_url.fetched_at.map(|dt| dt.to_rfc3339());
         let next_retry_at = crawl_url.next_retry_at.map(|dt| dt.to_rfc3339());
         let retry_count = crawl_url.retry_count as i32;
+        // A lease ends once the URL leaves `fetching`
+        let (claimed_by, lease_expires_at) = if crawl_url.status == UrlStatus::Fetching {
+            (
+                crawl_url.claimed_by.clone(),
+                crawl_url.lease_expires_at.map(|dt| dt.to_rfc3339()),
+            )
+        } else {
+            (None, None)
+        };
 
         with_conn!(self.pool, conn, {
             diesel::update(
@@ -117,6 +139,8 @@ impl DieselCrawlRepository {
                 crawl_urls::last_modified.eq(&crawl_url.last_modified),
                 crawl_urls::content_hash.eq(&crawl_url.content_hash),
                 crawl_urls::document_id.eq(&crawl_url.document_id),
+                crawl_urls::claimed_by.eq(&claimed_by),
+                crawl_urls::lease_expires_at.eq(&lease_expires_at),
             ))
             .execute(&mut conn)
             .await?;
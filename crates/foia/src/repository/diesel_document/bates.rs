@@ -0,0 +1,226 @@
+//! Bates range storage and lookup.
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselDocumentRepository;
+use crate::models::{BatesNumber, BatesRange};
+use crate::repository::pool::DieselError;
+use crate::schema::{document_pages, documents};
+use crate::with_conn;
+
+/// A document page resolved from a Bates number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatesMatch {
+    pub document_id: String,
+    /// 1-indexed page the number is stamped on.
+    pub page_number: u32,
+    /// The document's full Bates range.
+    pub range: BatesRange,
+}
+
+impl DieselDocumentRepository {
+    /// Set a document's Bates range and stamp any pages already extracted.
+    ///
+    /// Returns the number of pages stamped.
+    pub async fn set_bates_range(
+        &self,
+        document_id: &str,
+        range: &BatesRange,
+    ) -> Result<usize, DieselError> {
+        let begin = range.begin.to_string();
+        let end = range.end.to_string();
+
+        with_conn!(self.pool, conn, {
+            diesel::update(documents::table.find(document_id))
+                .set((
+                    documents::bates_begin.eq(&begin),
+                    documents::bates_end.eq(&end),
+                ))
+                .execute(&mut conn)
+                .await
+        })?;
+
+        self.stamp_bates_pages(document_id).await
+    }
+
+    /// Get a document's Bates range, if it has one.
+    pub async fn get_bates_range(
+        &self,
+        document_id: &str,
+    ) -> Result<Option<BatesRange>, DieselError> {
+        let row: Option<(Option<String>, Option<String>)> = with_conn!(self.pool, conn, {
+            documents::table
+                .find(document_id)
+                .select((documents::bates_begin, documents::bates_end))
+                .first(&mut conn)
+                .await
+                .optional()
+        })?;
+
+        Ok(match row {
+            Some((Some(begin), Some(end))) => BatesRange::parse(&begin, &end),
+            _ => None,
+        })
+    }
+
+    /// Write the Bates number onto each of a document's pages.
+    ///
+    /// Page N carries the Nth number of the document's range; pages past the
+    /// end of the range are left unstamped. Returns the number of pages
+    /// stamped.
+    pub async fn stamp_bates_pages(&self, document_id: &str) -> Result<usize, DieselError> {
+        use diesel_async::AsyncConnection;
+
+        let Some(range) = self.get_bates_range(document_id).await? else {
+            return Ok(0);
+        };
+
+        let pages: Vec<(i32, i32)> = with_conn!(self.pool, conn, {
+            document_pages::table
+                .filter(document_pages::document_id.eq(document_id))
+                .select((document_pages::id, document_pages::page_number))
+                .load(&mut conn)
+                .await
+        })?;
+
+        let stamps: Vec<(i32, String)> = pages
+            .into_iter()
+            .filter_map(|(id, page_number)| {
+                let bates = range.page_number(u32::try_from(page_number).ok()?)?;
+                Some((id, bates.to_string()))
+            })
+            .collect();
+
+        with_conn!(self.pool, conn, {
+            conn.transaction(|conn| {
+                Box::pin(async move {
+                    for (id, bates) in &stamps {
+                        diesel::update(document_pages::table.find(id))
+                            .set(document_pages::bates_number.eq(bates))
+                            .execute(conn)
+                            .await?;
+                    }
+                    Ok::<_, DieselError>(stamps.len())
+                })
+            })
+            .await
+        })
+    }
+
+    /// Resolve a Bates number to the document and page it is stamped on.
+    ///
+    /// Numbers within one sequence share a prefix and width, so comparing
+    /// them as strings orders them numerically; the range filter narrows
+    /// candidates in SQL and each is then checked exactly.
+    pub async fn find_by_bates(&self, bates: &BatesNumber) -> Result<Vec<BatesMatch>, DieselError> {
+        let needle = bates.to_string();
+
+        let rows: Vec<(String, Option<String>, Option<String>)> = with_conn!(self.pool, conn, {
+            documents::table
+                .filter(documents::bates_begin.le(&needle))
+                .filter(documents::bates_end.ge(&needle))
+                .select((documents::id, documents::bates_begin, documents::bates_end))
+                .load(&mut conn)
+                .await
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(document_id, begin, end)| {
+                let range = BatesRange::parse(&begin?, &end?)?;
+                let page_number = range.page_of(bates)?;
+                Some(BatesMatch {
+                    document_id,
+                    page_number,
+                    range,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Document, DocumentPage, DocumentVersion};
+    use crate::repository::diesel_document::tests::setup_test_db;
+
+    async fn save_doc(repo: &DieselDocumentRepository, id: &str, pages: u32) {
+        let url = format!("https://example.com/{}.pdf", id);
+        let version = DocumentVersion::new(
+            id.as_bytes(),
+            "application/pdf".to_string(),
+            Some(url.clone()),
+        );
+        let doc = Document::new(
+            id.to_string(),
+            "test-source".to_string(),
+            id.to_string(),
+            url,
+            version,
+            serde_json::json!({}),
+        );
+        repo.save_with_versions(&doc).await.unwrap();
+        let version_id = repo.get(id).await.unwrap().unwrap().versions[0].id;
+        for n in 1..=pages {
+            repo.save_page(&DocumentPage::new(id.to_string(), version_id, n))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_range_and_lookup() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        save_doc(&repo, "doc-a", 3).await;
+        save_doc(&repo, "doc-b", 2).await;
+
+        let stamped = repo
+            .set_bates_range(
+                "doc-a",
+                &BatesRange::parse("ABC000100", "ABC000102").unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stamped, 3);
+        repo.set_bates_range(
+            "doc-b",
+            &BatesRange::parse("ABC000103", "ABC000104").unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let matches = repo
+            .find_by_bates(&BatesNumber::parse("ABC000102").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].document_id, "doc-a");
+        assert_eq!(matches[0].page_number, 3);
+
+        let matches = repo
+            .find_by_bates(&BatesNumber::parse("ABC000103").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(matches[0].document_id, "doc-b");
+        assert_eq!(matches[0].page_number, 1);
+
+        // Same digits under another prefix or width do not match
+        assert!(repo
+            .find_by_bates(&BatesNumber::parse("ABC00101").unwrap())
+            .await
+            .unwrap()
+            .is_empty());
+
+        let version_id = repo.get("doc-a").await.unwrap().unwrap().versions[0].id;
+        let pages = repo.get_pages("doc-a", version_id as i32).await.unwrap();
+        let stamps: Vec<_> = pages.iter().map(|p| p.bates_number.as_deref()).collect();
+        assert_eq!(
+            stamps,
+            vec![Some("ABC000100"), Some("ABC000101"), Some("ABC000102")]
+        );
+    }
+}
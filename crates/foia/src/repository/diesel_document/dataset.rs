@@ -0,0 +1,186 @@
+//! Raw table reads for dataset exports.
+//!
+//! Each reader returns one page of rows in primary key order, starting after
+//! the given key, so exports can walk a table of any size in fixed memory.
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselDocumentRepository;
+use crate::repository::models::{
+    DocumentAnalysisResultRecord, DocumentEntityRecord, DocumentPageRecord, DocumentRecord,
+    DocumentVersionRecord,
+};
+use crate::repository::pool::DieselError;
+use crate::schema::{
+    document_analysis_results, document_entities, document_pages, document_versions, documents,
+};
+use crate::with_conn;
+
+impl DieselDocumentRepository {
+    /// Get document rows with IDs after `after_id`, in ID order.
+    pub async fn get_document_records_after(
+        &self,
+        after_id: &str,
+        limit: usize,
+        source_id: Option<&str>,
+    ) -> Result<Vec<DocumentRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let mut query = documents::table
+                .filter(documents::id.gt(after_id))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query
+                .order(documents::id.asc())
+                .limit(limit as i64)
+                .load::<DocumentRecord>(&mut conn)
+                .await
+        })
+    }
+
+    /// Get version rows with IDs after `after_id`, in ID order.
+    pub async fn get_version_records_after(
+        &self,
+        after_id: i32,
+        limit: usize,
+        source_id: Option<&str>,
+    ) -> Result<Vec<DocumentVersionRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let mut query = document_versions::table
+                .inner_join(documents::table)
+                .filter(document_versions::id.gt(after_id))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query
+                .order(document_versions::id.asc())
+                .limit(limit as i64)
+                .select(DocumentVersionRecord::as_select())
+                .load(&mut conn)
+                .await
+        })
+    }
+
+    /// Get page rows with IDs after `after_id`, in ID order.
+    pub async fn get_page_records_after(
+        &self,
+        after_id: i32,
+        limit: usize,
+        source_id: Option<&str>,
+    ) -> Result<Vec<DocumentPageRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let mut query = document_pages::table
+                .inner_join(documents::table)
+                .filter(document_pages::id.gt(after_id))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query
+                .order(document_pages::id.asc())
+                .limit(limit as i64)
+                .select(DocumentPageRecord::as_select())
+                .load(&mut conn)
+                .await
+        })
+    }
+
+    /// Get entity rows with IDs after `after_id`, in ID order.
+    pub async fn get_entity_records_after(
+        &self,
+        after_id: i32,
+        limit: usize,
+        source_id: Option<&str>,
+    ) -> Result<Vec<DocumentEntityRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let mut query = document_entities::table
+                .inner_join(documents::table)
+                .filter(document_entities::id.gt(after_id))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query
+                .order(document_entities::id.asc())
+                .limit(limit as i64)
+                .select(DocumentEntityRecord::as_select())
+                .load(&mut conn)
+                .await
+        })
+    }
+
+    /// Get analysis result rows with IDs after `after_id`, in ID order.
+    pub async fn get_analysis_records_after(
+        &self,
+        after_id: i32,
+        limit: usize,
+        source_id: Option<&str>,
+    ) -> Result<Vec<DocumentAnalysisResultRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let mut query = document_analysis_results::table
+                .inner_join(documents::table)
+                .filter(document_analysis_results::id.gt(after_id))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query
+                .order(document_analysis_results::id.asc())
+                .limit(limit as i64)
+                .select(DocumentAnalysisResultRecord::as_select())
+                .load(&mut conn)
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Document, DocumentVersion};
+    use crate::repository::diesel_document::tests::setup_test_db;
+
+    #[tokio::test]
+    async fn test_record_readers_paginate_by_key() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        for (i, source) in ["src-a", "src-b", "src-a"].iter().enumerate() {
+            let content = format!("content {}", i);
+            let doc = Document::new(
+                format!("doc-{}", i),
+                source.to_string(),
+                format!("Doc {}", i),
+                format!("https://example.com/{}.pdf", i),
+                DocumentVersion::new(content.as_bytes(), "application/pdf".to_string(), None),
+                serde_json::json!({}),
+            );
+            repo.save_with_versions(&doc).await.unwrap();
+        }
+
+        let first = repo.get_document_records_after("", 2, None).await.unwrap();
+        assert_eq!(first.len(), 2);
+        let rest = repo
+            .get_document_records_after(&first[1].id, 2, None)
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].id, "doc-2");
+
+        let versions = repo
+            .get_version_records_after(0, 10, Some("src-a"))
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions.windows(2).all(|w| w[0].id < w[1].id));
+        let after = repo
+            .get_version_records_after(versions[0].id, 10, Some("src-a"))
+            .await
+            .unwrap();
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].document_id, "doc-2");
+    }
+}
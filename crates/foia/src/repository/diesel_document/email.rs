@@ -0,0 +1,321 @@
+//! Email thread and correspondent graph storage.
+
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselDocumentRepository;
+use crate::repository::models::{EmailMessageRecord, NewEmailCorrespondence};
+use crate::repository::pool::DieselError;
+use crate::schema::{documents, email_correspondence, email_messages};
+use crate::with_conn;
+
+/// Aggregated messages from one address to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrespondentEdge {
+    pub sender: String,
+    pub recipient: String,
+    pub count: u64,
+}
+
+impl DieselDocumentRepository {
+    /// Get `(document_id, metadata)` for documents carrying email headers.
+    pub async fn get_email_metadata(
+        &self,
+        source_id: Option<&str>,
+    ) -> Result<Vec<(String, String)>, DieselError> {
+        with_conn!(self.pool, conn, {
+            let mut query = documents::table
+                .filter(documents::metadata.like("%\"message_id\"%"))
+                .select((documents::id, documents::metadata))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query.load(&mut conn).await
+        })
+    }
+
+    /// Replace thread rows and correspondent edges.
+    ///
+    /// With a source, only that source's emails are replaced; otherwise all
+    /// existing rows are cleared first.
+    pub async fn replace_email_threads(
+        &self,
+        source_id: Option<&str>,
+        messages: &[EmailMessageRecord],
+        edges: &[NewEmailCorrespondence],
+    ) -> Result<(), DieselError> {
+        use diesel_async::AsyncConnection;
+
+        with_conn!(self.pool, conn, {
+            conn.transaction(|conn| {
+                Box::pin(async move {
+                    if let Some(sid) = source_id {
+                        let ids = documents::table
+                            .filter(documents::source_id.eq(sid))
+                            .select(documents::id);
+                        diesel::delete(
+                            email_messages::table.filter(email_messages::document_id.eq_any(ids)),
+                        )
+                        .execute(conn)
+                        .await?;
+                        let ids = documents::table
+                            .filter(documents::source_id.eq(sid))
+                            .select(documents::id);
+                        diesel::delete(
+                            email_correspondence::table
+                                .filter(email_correspondence::document_id.eq_any(ids)),
+                        )
+                        .execute(conn)
+                        .await?;
+                    } else {
+                        diesel::delete(email_messages::table).execute(conn).await?;
+                        diesel::delete(email_correspondence::table)
+                            .execute(conn)
+                            .await?;
+                    }
+
+                    for chunk in messages.chunks(100) {
+                        diesel::insert_into(email_messages::table)
+                            .values(chunk)
+                            .execute(conn)
+                            .await?;
+                    }
+                    for chunk in edges.chunks(100) {
+                        diesel::insert_into(email_correspondence::table)
+                            .values(chunk)
+                            .execute(conn)
+                            .await?;
+                    }
+                    Ok::<_, DieselError>(())
+                })
+            })
+            .await
+        })
+    }
+
+    /// Get the thread row for an email document.
+    pub async fn get_email_message(
+        &self,
+        document_id: &str,
+    ) -> Result<Option<EmailMessageRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            email_messages::table
+                .find(document_id)
+                .first(&mut conn)
+                .await
+                .optional()
+        })
+    }
+
+    /// Get every message in a thread, oldest first.
+    pub async fn get_email_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<Vec<EmailMessageRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            email_messages::table
+                .filter(email_messages::thread_id.eq(thread_id))
+                .order((
+                    email_messages::sent_at.asc(),
+                    email_messages::document_id.asc(),
+                ))
+                .load(&mut conn)
+                .await
+        })
+    }
+
+    /// Who emailed whom and how often, busiest pairs first.
+    pub async fn get_correspondent_graph(
+        &self,
+        source_id: Option<&str>,
+        address: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CorrespondentEdge>, DieselError> {
+        let address = address.map(|a| a.to_lowercase());
+
+        let rows: Vec<(String, String, i64)> = with_conn!(self.pool, conn, {
+            let mut query = email_correspondence::table
+                .inner_join(documents::table)
+                .group_by((
+                    email_correspondence::sender,
+                    email_correspondence::recipient,
+                ))
+                .select((
+                    email_correspondence::sender,
+                    email_correspondence::recipient,
+                    count_star(),
+                ))
+                .order((count_star().desc(), email_correspondence::sender.asc()))
+                .limit(limit as i64)
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            if let Some(addr) = &address {
+                query = query.filter(
+                    email_correspondence::sender
+                        .eq(addr)
+                        .or(email_correspondence::recipient.eq(addr)),
+                );
+            }
+            query.load(&mut conn).await
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(sender, recipient, count)| CorrespondentEdge {
+                sender,
+                recipient,
+                count: count as u64,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Document, DocumentVersion};
+    use crate::repository::diesel_document::tests::setup_test_db;
+    use crate::repository::pool::DbPool;
+    use diesel_async::SimpleAsyncConnection;
+
+    async fn create_email_tables(pool: &DbPool) {
+        let DbPool::Sqlite(sqlite) = pool else {
+            unreachable!()
+        };
+        let mut conn = sqlite.get().await.unwrap();
+        conn.batch_execute(
+            r#"
+            CREATE TABLE email_messages (
+                document_id TEXT PRIMARY KEY NOT NULL,
+                thread_id TEXT NOT NULL,
+                parent_document_id TEXT,
+                message_id TEXT,
+                subject TEXT,
+                sender TEXT,
+                sent_at TEXT,
+                depth INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL
+            );
+            CREATE TABLE email_correspondence (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id TEXT NOT NULL,
+                sender TEXT NOT NULL,
+                recipient TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                sent_at TEXT
+            );
+            "#,
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn save_doc(repo: &DieselDocumentRepository, id: &str, metadata: serde_json::Value) {
+        let url = format!("mid:{}", id);
+        let version = DocumentVersion::new(
+            id.as_bytes(),
+            "message/rfc822".to_string(),
+            Some(url.clone()),
+        );
+        let doc = Document::new(
+            id.to_string(),
+            "test-source".to_string(),
+            id.to_string(),
+            url,
+            version,
+            metadata,
+        );
+        repo.save_with_versions(&doc).await.unwrap();
+    }
+
+    fn record(id: &str, thread_id: &str, sent_at: &str) -> EmailMessageRecord {
+        EmailMessageRecord {
+            document_id: id.to_string(),
+            thread_id: thread_id.to_string(),
+            parent_document_id: None,
+            message_id: Some(format!("{}@x", id)),
+            subject: Some("Budget".to_string()),
+            sender: Some("alice@example.gov".to_string()),
+            sent_at: Some(sent_at.to_string()),
+            depth: 0,
+            updated_at: sent_at.to_string(),
+        }
+    }
+
+    fn edge(id: &str, recipient: &str) -> NewEmailCorrespondence {
+        NewEmailCorrespondence {
+            document_id: id.to_string(),
+            sender: "alice@example.gov".to_string(),
+            recipient: recipient.to_string(),
+            kind: "to".to_string(),
+            sent_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_threads_and_graph() {
+        let (pool, _dir) = setup_test_db().await;
+        create_email_tables(&pool).await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        save_doc(&repo, "a", serde_json::json!({"message_id": "a@x"})).await;
+        save_doc(&repo, "b", serde_json::json!({"message_id": "b@x"})).await;
+        save_doc(&repo, "c", serde_json::json!({"title": "not an email"})).await;
+
+        let metadata = repo.get_email_metadata(None).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+
+        repo.replace_email_threads(
+            None,
+            &[
+                record("b", "a", "2024-01-02T00:00:00+00:00"),
+                record("a", "a", "2024-01-01T00:00:00+00:00"),
+            ],
+            &[
+                edge("a", "bob@example.gov"),
+                edge("b", "bob@example.gov"),
+                edge("b", "carol@example.gov"),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let thread = repo.get_email_thread("a").await.unwrap();
+        let ids: Vec<_> = thread.iter().map(|m| m.document_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(
+            repo.get_email_message("b")
+                .await
+                .unwrap()
+                .unwrap()
+                .thread_id,
+            "a"
+        );
+
+        let graph = repo.get_correspondent_graph(None, None, 10).await.unwrap();
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph[0].recipient, "bob@example.gov");
+        assert_eq!(graph[0].count, 2);
+
+        let carol = repo
+            .get_correspondent_graph(Some("test-source"), Some("Carol@example.gov"), 10)
+            .await
+            .unwrap();
+        assert_eq!(carol.len(), 1);
+
+        // Rebuilding replaces rather than appends
+        repo.replace_email_threads(None, &[record("a", "a", "2024-01-01T00:00:00+00:00")], &[])
+            .await
+            .unwrap();
+        assert_eq!(repo.get_email_thread("a").await.unwrap().len(), 1);
+        assert!(repo
+            .get_correspondent_graph(None, None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
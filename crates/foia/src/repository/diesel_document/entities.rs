@@ -10,12 +10,20 @@ use crate::repository::pool::DieselError;
 use crate::schema::document_entities;
 use crate::{with_conn, with_conn_split};
 
+/// Entity type for email addresses taken from message headers.
+///
+/// These come from structured data rather than NER, so they survive
+/// re-extraction.
+pub const EMAIL_ENTITY_TYPE: &str = "email";
+
 /// Filter for entity-based document search.
 #[derive(Debug, Clone)]
 pub struct EntityFilter {
     pub entity_type: Option<String>,
     pub text: String,
     pub exact: bool,
+    /// Entity type the filter must never match, even when `entity_type` is unset.
+    pub exclude_type: Option<String>,
 }
 
 /// Entity type + count pair for statistics.
@@ -72,11 +80,28 @@ impl DieselDocumentRepository {
         Ok(())
     }
 
-    /// Delete all entities for a document (before re-extraction).
+    /// Delete extracted entities for a document (before re-extraction).
+    /// Header-derived email addresses are kept.
     pub async fn delete_document_entities(&self, doc_id: &str) -> Result<usize, DieselError> {
         with_conn!(self.pool, conn, {
             diesel::delete(
-                document_entities::table.filter(document_entities::document_id.eq(doc_id)),
+                document_entities::table
+                    .filter(document_entities::document_id.eq(doc_id))
+                    .filter(document_entities::entity_type.ne(EMAIL_ENTITY_TYPE)),
+            )
+            .execute(&mut conn)
+            .await
+        })
+    }
+
+    /// Delete a document's header-derived email addresses (before saving
+    /// those of a new version).
+    pub async fn delete_email_entities(&self, doc_id: &str) -> Result<usize, DieselError> {
+        with_conn!(self.pool, conn, {
+            diesel::delete(
+                document_entities::table
+                    .filter(document_entities::document_id.eq(doc_id))
+                    .filter(document_entities::entity_type.eq(EMAIL_ENTITY_TYPE)),
             )
             .execute(&mut conn)
             .await
@@ -203,6 +228,10 @@ impl DieselDocumentRepository {
                 query = query.filter(document_entities::entity_type.eq(entity_type));
             }
 
+            if let Some(ref excluded) = filter.exclude_type {
+                query = query.filter(document_entities::entity_type.ne(excluded));
+            }
+
             if let Some(sid) = source_id {
                 use crate::schema::documents;
                 let source_doc_ids = documents::table
@@ -533,6 +562,47 @@ mod tests {
 
         let empty = repo.get_document_entities("doc-entity-1").await.unwrap();
         assert!(empty.is_empty());
+
+        // Header-derived addresses survive re-extraction
+        repo.save_document_entities(&[NewDocumentEntity {
+            document_id: "doc-entity-1",
+            entity_type: EMAIL_ENTITY_TYPE,
+            entity_text: "foia@cia.gov",
+            normalized_text: "foia@cia.gov",
+            latitude: None,
+            longitude: None,
+            created_at: &now,
+        }])
+        .await
+        .unwrap();
+        assert_eq!(
+            repo.delete_document_entities("doc-entity-1").await.unwrap(),
+            0
+        );
+
+        // Excluding the email type hides the address from untyped searches
+        let mut filter = EntityFilter {
+            entity_type: None,
+            text: "foia@cia.gov".to_string(),
+            exact: true,
+            exclude_type: None,
+        };
+        assert_eq!(
+            repo.count_by_entities(&[filter.clone()], None)
+                .await
+                .unwrap(),
+            1
+        );
+        filter.exclude_type = Some(EMAIL_ENTITY_TYPE.to_string());
+        assert_eq!(repo.count_by_entities(&[filter], None).await.unwrap(), 0);
+
+        // A new mailbox version replaces the addresses
+        assert_eq!(repo.delete_email_entities("doc-entity-1").await.unwrap(), 1);
+        assert!(repo
+            .get_document_entities("doc-entity-1")
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[tokio::test]
@@ -598,6 +668,7 @@ mod tests {
             entity_type: Some("organization".to_string()),
             text: "cia".to_string(),
             exact: true,
+            exclude_type: None,
         }];
         let results = repo
             .search_by_entities(&filters, None, 100, 0)
@@ -611,11 +682,13 @@ mod tests {
                 entity_type: Some("organization".to_string()),
                 text: "cia".to_string(),
                 exact: true,
+                exclude_type: None,
             },
             EntityFilter {
                 entity_type: Some("person".to_string()),
                 text: "john smith".to_string(),
                 exact: true,
+                exclude_type: None,
             },
         ];
         let results = repo
@@ -716,6 +789,7 @@ mod tests {
             entity_type: Some("'; DROP TABLE documents; --".to_string()),
             text: "' OR '1'='1".to_string(),
             exact: false,
+            exclude_type: None,
         }];
         let result = repo.search_by_entities(&filters, None, 100, 0).await;
         assert!(result.is_ok());
@@ -8,14 +8,26 @@
 //! - `pages.rs`: Document page and OCR operations
 //! - `queries.rs`: Complex queries, browsing, statistics
 //! - `analysis.rs`: Analysis result operations
+//! - `pii.rs`: Detected PII spans and masks
+//! - `bates.rs`: Bates ranges and page stamps
+//! - `dataset.rs`: Keyset-paginated raw table reads for dataset exports
 
 mod analysis;
+mod bates;
+mod dataset;
+mod email;
 pub mod entities;
 mod pages;
+mod pii;
 mod queries;
+mod removal;
 mod versions;
 
+pub use bates::BatesMatch;
+pub use email::CorrespondentEdge;
 pub use queries::BrowseParams;
+pub use removal::{DeleteOutcome, LegalHold, Tombstone};
+pub use versions::StoredVersion;
 
 use std::path::PathBuf;
 
@@ -232,7 +244,21 @@ impl DieselDocumentRepository {
     /// Use this instead of `save()` when creating a new document or adding
     /// versions, so the version rows are actually written to document_versions.
     /// Use `save()` alone when only updating document metadata/status.
-    pub async fn save_with_versions(&self, doc: &Document) -> Result<(), DieselError> {
+    ///
+    /// Refuses to save anything, returning `false`, when the document's URL
+    /// or a new version's content hash matches a tombstone, so no import or
+    /// scrape path can bring a deleted document back.
+    pub async fn save_with_versions(&self, doc: &Document) -> Result<bool, DieselError> {
+        for version in doc.versions.iter().filter(|v| v.id == 0) {
+            if self
+                .find_tombstone(Some(&doc.source_url), Some(&version.content_hash))
+                .await?
+                .is_some()
+            {
+                return Ok(false);
+            }
+        }
+
         self.save(doc).await?;
 
         for version in &doc.versions {
@@ -241,7 +267,7 @@ impl DieselDocumentRepository {
             }
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Delete a document.
@@ -372,6 +398,28 @@ impl DieselDocumentRepository {
         })
     }
 
+    /// Record the extraction result for an existing virtual file.
+    pub async fn update_virtual_file_text(
+        &self,
+        id: &str,
+        extracted_text: Option<&str>,
+        status: VirtualFileStatus,
+    ) -> Result<(), DieselError> {
+        let now = Utc::now().to_rfc3339();
+
+        with_conn!(self.pool, conn, {
+            diesel::update(virtual_files::table.find(id))
+                .set((
+                    virtual_files::extracted_text.eq(extracted_text),
+                    virtual_files::status.eq(status.as_str()),
+                    virtual_files::updated_at.eq(&now),
+                ))
+                .execute(&mut conn)
+                .await?;
+            Ok(())
+        })
+    }
+
     /// Get virtual files.
     pub async fn get_virtual_files(
         &self,
@@ -789,7 +837,9 @@ mod tests {
                 date_source TEXT,
                 manual_date TEXT,
                 discovery_method TEXT NOT NULL DEFAULT 'import',
-                category_id TEXT
+                category_id TEXT,
+                bates_begin TEXT,
+                bates_end TEXT
             );
 
             CREATE TABLE IF NOT EXISTS document_versions (
@@ -821,6 +871,7 @@ mod tests {
                 ocr_status TEXT NOT NULL DEFAULT 'pending',
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
+                bates_number TEXT,
                 UNIQUE(document_id, version_id, page_number)
             );
 
@@ -839,6 +890,16 @@ mod tests {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL
             );
+
+            CREATE TABLE IF NOT EXISTS document_tombstones (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                source_url TEXT NOT NULL,
+                content_hash TEXT,
+                reason TEXT,
+                deleted_at TEXT NOT NULL
+            );
             "#,
         )
         .await
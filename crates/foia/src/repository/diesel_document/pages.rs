@@ -51,6 +51,7 @@ impl From<DocumentPageRecord> for DocumentPage {
             ocr_status: PageOcrStatus::from_str(&r.ocr_status).unwrap_or(PageOcrStatus::Pending),
             created_at: parse_datetime(&r.created_at),
             updated_at: parse_datetime(&r.updated_at),
+            bates_number: r.bates_number,
         }
     }
 }
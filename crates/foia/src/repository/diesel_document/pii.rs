@@ -0,0 +1,307 @@
+//! Detected PII span storage and mask construction.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselDocumentRepository;
+use crate::models::{pii_scanned_version, PiiMask, PiiType, PII_ANNOTATION_TYPE};
+use crate::repository::models::{DocumentPiiSpanRecord, NewDocumentPiiSpan};
+use crate::repository::pool::DieselError;
+use crate::schema::{document_pii_spans, documents};
+use crate::with_conn;
+
+impl DieselDocumentRepository {
+    /// Replace all PII spans for a document with a fresh detection result.
+    pub async fn replace_pii_spans(
+        &self,
+        document_id: &str,
+        spans: &[NewDocumentPiiSpan<'_>],
+    ) -> Result<usize, DieselError> {
+        use diesel_async::AsyncConnection;
+
+        with_conn!(self.pool, conn, {
+            conn.transaction(|conn| {
+                Box::pin(async move {
+                    diesel::delete(
+                        document_pii_spans::table
+                            .filter(document_pii_spans::document_id.eq(document_id)),
+                    )
+                    .execute(conn)
+                    .await?;
+
+                    let mut inserted = 0;
+                    for chunk in spans.chunks(100) {
+                        inserted += diesel::insert_into(document_pii_spans::table)
+                            .values(chunk)
+                            .execute(conn)
+                            .await?;
+                    }
+                    Ok::<_, DieselError>(inserted)
+                })
+            })
+            .await
+        })
+    }
+
+    /// Get all PII spans for a document, in page order.
+    pub async fn get_pii_spans(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentPiiSpanRecord>, DieselError> {
+        with_conn!(self.pool, conn, {
+            document_pii_spans::table
+                .filter(document_pii_spans::document_id.eq(document_id))
+                .order((
+                    document_pii_spans::page_number.asc(),
+                    document_pii_spans::start_offset.asc(),
+                ))
+                .load(&mut conn)
+                .await
+        })
+    }
+
+    /// Build the mask for a document's detected PII.
+    pub async fn get_pii_mask(&self, document_id: &str) -> Result<PiiMask, DieselError> {
+        let spans = self.get_pii_spans(document_id).await?;
+        Ok(mask_from_records(&spans))
+    }
+
+    /// Build masks for multiple documents in a single query.
+    ///
+    /// Documents without detected PII are absent from the map.
+    pub async fn get_pii_masks_batch(
+        &self,
+        document_ids: &[String],
+    ) -> Result<HashMap<String, PiiMask>, DieselError> {
+        if document_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let records: Vec<DocumentPiiSpanRecord> = with_conn!(self.pool, conn, {
+            document_pii_spans::table
+                .filter(document_pii_spans::document_id.eq_any(document_ids))
+                .load(&mut conn)
+                .await
+        })?;
+
+        let mut by_doc: HashMap<String, Vec<DocumentPiiSpanRecord>> = HashMap::new();
+        for record in records {
+            by_doc
+                .entry(record.document_id.clone())
+                .or_default()
+                .push(record);
+        }
+        Ok(by_doc
+            .into_iter()
+            .map(|(id, spans)| (id, mask_from_records(&spans)))
+            .collect())
+    }
+
+    /// Count detected PII spans by type across all documents.
+    pub async fn count_pii_by_type(&self) -> Result<Vec<(String, i64)>, DieselError> {
+        with_conn!(self.pool, conn, {
+            document_pii_spans::table
+                .group_by(document_pii_spans::pii_type)
+                .select((document_pii_spans::pii_type, count_star()))
+                .order(document_pii_spans::pii_type.asc())
+                .load(&mut conn)
+                .await
+        })
+    }
+
+    /// Clear PII scan records that don't cover the document's current
+    /// version, so the next `detect-pii` run scans it again.
+    ///
+    /// Returns the number of documents cleared.
+    pub async fn reset_stale_pii_scans(&self, source_id: Option<&str>) -> Result<u64, DieselError> {
+        let pattern = format!("%\"{}\"%", PII_ANNOTATION_TYPE);
+        let rows: Vec<(String, String)> = with_conn!(self.pool, conn, {
+            let mut query = documents::table
+                .filter(documents::metadata.like(&pattern))
+                .select((documents::id, documents::metadata))
+                .into_boxed();
+            if let Some(sid) = source_id {
+                query = query.filter(documents::source_id.eq(sid));
+            }
+            query.load(&mut conn).await
+        })?;
+
+        let mut reset = 0;
+        for (id, metadata) in rows {
+            let Ok(mut metadata) = serde_json::from_str::<serde_json::Value>(&metadata) else {
+                continue;
+            };
+            let scanned = pii_scanned_version(&metadata);
+            if scanned.is_some() && scanned == self.get_current_version_id(&id).await? {
+                continue;
+            }
+            let removed = metadata
+                .get_mut("annotations")
+                .and_then(|a| a.as_object_mut())
+                .and_then(|a| a.remove(PII_ANNOTATION_TYPE));
+            if removed.is_none() {
+                continue;
+            }
+
+            let now = Utc::now().to_rfc3339();
+            with_conn!(self.pool, conn, {
+                diesel::update(documents::table.find(id.as_str()))
+                    .set((
+                        documents::metadata.eq(metadata.to_string()),
+                        documents::updated_at.eq(&now),
+                    ))
+                    .execute(&mut conn)
+                    .await
+            })?;
+            reset += 1;
+        }
+        Ok(reset)
+    }
+}
+
+fn mask_from_records(records: &[DocumentPiiSpanRecord]) -> PiiMask {
+    PiiMask::new(
+        records
+            .iter()
+            .filter_map(|r| PiiType::from_str(&r.pii_type).map(|t| (t, r.matched_text.as_str()))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::diesel_document::tests::setup_test_db;
+    use crate::repository::pool::DbPool;
+    use diesel_async::SimpleAsyncConnection;
+
+    async fn create_pii_table(pool: &DbPool) {
+        let DbPool::Sqlite(sqlite) = pool else {
+            unreachable!()
+        };
+        let mut conn = sqlite.get().await.unwrap();
+        conn.batch_execute(
+            r#"
+            CREATE TABLE document_pii_spans (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                document_id TEXT NOT NULL,
+                page_id INTEGER NOT NULL,
+                page_number INTEGER NOT NULL,
+                pii_type TEXT NOT NULL,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                matched_text TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            "#,
+        )
+        .await
+        .unwrap();
+    }
+
+    fn span<'a>(
+        doc: &'a str,
+        page: i32,
+        pii_type: &'a str,
+        text: &'a str,
+    ) -> NewDocumentPiiSpan<'a> {
+        NewDocumentPiiSpan {
+            document_id: doc,
+            page_id: page,
+            page_number: page,
+            pii_type,
+            start_offset: 0,
+            end_offset: text.len() as i32,
+            matched_text: text,
+            created_at: "2024-01-01T00:00:00Z",
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_and_mask() {
+        let (pool, _dir) = setup_test_db().await;
+        create_pii_table(&pool).await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        repo.replace_pii_spans("doc-1", &[span("doc-1", 1, "ssn", "123-45-6789")])
+            .await
+            .unwrap();
+        let inserted = repo
+            .replace_pii_spans(
+                "doc-1",
+                &[
+                    span("doc-1", 2, "email", "jdoe@example.com"),
+                    span("doc-1", 1, "phone", "(202) 555-0143"),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(inserted, 2);
+
+        let spans = repo.get_pii_spans("doc-1").await.unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].page_number, 1);
+
+        let mask = repo.get_pii_mask("doc-1").await.unwrap();
+        assert_eq!(
+            mask.mask("call (202) 555-0143 or 123-45-6789"),
+            "call [REDACTED PHONE] or 123-45-6789"
+        );
+
+        let masks = repo
+            .get_pii_masks_batch(&["doc-1".to_string(), "doc-2".to_string()])
+            .await
+            .unwrap();
+        assert!(masks.contains_key("doc-1"));
+        assert!(!masks.contains_key("doc-2"));
+
+        let counts = repo.count_pii_by_type().await.unwrap();
+        assert_eq!(
+            counts,
+            vec![("email".to_string(), 1), ("phone".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reset_stale_pii_scans() {
+        use crate::models::{Document, DocumentVersion};
+
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        let doc = Document::new(
+            "doc-1".to_string(),
+            "test-source".to_string(),
+            "Letter".to_string(),
+            "https://example.com/letter.pdf".to_string(),
+            DocumentVersion::new(b"v1", "application/pdf".to_string(), None),
+            serde_json::json!({}),
+        );
+        repo.save_with_versions(&doc).await.unwrap();
+        let first = repo.get_current_version_id("doc-1").await.unwrap().unwrap();
+        let data = serde_json::json!({ "version_id": first, "counts": {} }).to_string();
+        repo.record_annotation("doc-1", PII_ANNOTATION_TYPE, 2, Some(&data), None)
+            .await
+            .unwrap();
+
+        assert_eq!(repo.reset_stale_pii_scans(None).await.unwrap(), 0);
+
+        let mut doc = repo.get("doc-1").await.unwrap().unwrap();
+        doc.add_version(DocumentVersion::new(
+            b"v2",
+            "application/pdf".to_string(),
+            None,
+        ));
+        repo.save_with_versions(&doc).await.unwrap();
+        assert_eq!(repo.reset_stale_pii_scans(None).await.unwrap(), 1);
+
+        let doc = repo.get("doc-1").await.unwrap().unwrap();
+        assert!(doc.metadata["annotations"]
+            .get(PII_ANNOTATION_TYPE)
+            .is_none());
+        assert_eq!(repo.reset_stale_pii_scans(None).await.unwrap(), 0);
+    }
+}
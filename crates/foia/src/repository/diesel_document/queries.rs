@@ -39,6 +39,10 @@ pub struct BrowseParams<'a> {
     pub sort_order: Option<&'a str>,
     pub limit: u32,
     pub offset: u32,
+    /// Keyset cursor: only return documents with IDs after this one, in ID
+    /// order. Overrides `sort_field`/`sort_order`; use an empty string to
+    /// start from the beginning.
+    pub after_id: Option<&'a str>,
 }
 
 impl DieselDocumentRepository {
@@ -626,6 +630,7 @@ impl DieselDocumentRepository {
         let search_query = params.search_query;
         let sort_field = params.sort_field;
         let sort_order = params.sort_order;
+        let after_id = params.after_id;
 
         let records: Vec<DocumentRecord> = with_conn!(self.pool, conn, {
             // Build query with filters first, then order and paginate
@@ -659,30 +664,36 @@ impl DieselDocumentRepository {
             }
 
             // Apply sorting
-            let is_desc = sort_order
-                .map(|o| o.eq_ignore_ascii_case("desc"))
-                .unwrap_or(true);
-            match sort_field {
-                Some("created_at") => {
-                    if is_desc {
-                        query = query.order(documents::created_at.desc());
-                    } else {
-                        query = query.order(documents::created_at.asc());
+            if let Some(after) = after_id {
+                query = query
+                    .filter(documents::id.gt(after))
+                    .order(documents::id.asc());
+            } else {
+                let is_desc = sort_order
+                    .map(|o| o.eq_ignore_ascii_case("desc"))
+                    .unwrap_or(true);
+                match sort_field {
+                    Some("created_at") => {
+                        if is_desc {
+                            query = query.order(documents::created_at.desc());
+                        } else {
+                            query = query.order(documents::created_at.asc());
+                        }
                     }
-                }
-                Some("title") => {
-                    if is_desc {
-                        query = query.order(documents::title.desc());
-                    } else {
-                        query = query.order(documents::title.asc());
+                    Some("title") => {
+                        if is_desc {
+                            query = query.order(documents::title.desc());
+                        } else {
+                            query = query.order(documents::title.asc());
+                        }
                     }
-                }
-                _ => {
-                    // Default: updated_at desc
-                    if is_desc {
-                        query = query.order(documents::updated_at.desc());
-                    } else {
-                        query = query.order(documents::updated_at.asc());
+                    _ => {
+                        // Default: updated_at desc
+                        if is_desc {
+                            query = query.order(documents::updated_at.desc());
+                        } else {
+                            query = query.order(documents::updated_at.asc());
+                        }
                     }
                 }
             }
@@ -1663,4 +1674,58 @@ mod tests {
             .await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_browse_after_id_walks_in_id_order() {
+        use crate::models::{Document, DocumentVersion};
+
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        for (id, source) in [("doc-c", "src-a"), ("doc-a", "src-a"), ("doc-b", "src-b")] {
+            let doc = Document::new(
+                id.to_string(),
+                source.to_string(),
+                id.to_string(),
+                format!("https://example.com/{}.pdf", id),
+                DocumentVersion::new(id.as_bytes(), "application/pdf".to_string(), None),
+                serde_json::json!({}),
+            );
+            repo.save_with_versions(&doc).await.unwrap();
+        }
+
+        let first = repo
+            .browse(BrowseParams {
+                limit: 2,
+                after_id: Some(""),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let ids: Vec<_> = first.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["doc-a", "doc-b"]);
+
+        let rest = repo
+            .browse(BrowseParams {
+                limit: 2,
+                after_id: Some("doc-b"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].id, "doc-c");
+
+        let source_a = repo
+            .browse(BrowseParams {
+                source_id: Some("src-a"),
+                limit: 10,
+                after_id: Some("doc-a"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(source_a.len(), 1);
+        assert_eq!(source_a[0].id, "doc-c");
+    }
 }
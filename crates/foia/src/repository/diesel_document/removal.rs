@@ -0,0 +1,534 @@
+//! Document deletion, tombstones and legal holds.
+//!
+//! Deleting a document removes every row that hangs off it and leaves a
+//! tombstone behind for each URL and content hash it was stored under, so
+//! scrapers and imports can refuse to bring it back. A legal hold is the
+//! opposite: it pins a document so it can be neither deleted nor merged
+//! away by deduplication.
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::DieselDocumentRepository;
+use crate::models::DocumentVersion;
+use crate::repository::models::{
+    DocumentLegalHoldRecord, DocumentTombstoneRecord, DocumentVersionRecord, NewDocumentTombstone,
+};
+use crate::repository::parse_datetime;
+use crate::repository::pool::DieselError;
+use crate::schema::{
+    crawl_urls, document_analysis_results, document_entities, document_legal_holds, document_pages,
+    document_pii_spans, document_tombstones, document_versions, documents, email_correspondence,
+    email_messages, foia_request_documents, foia_request_events, page_ocr_results, virtual_files,
+};
+use crate::with_conn;
+
+// Inline schema for document_annotations (not in main schema.rs)
+diesel::table! {
+    document_annotations (id) {
+        id -> Integer,
+        document_id -> Text,
+    }
+}
+
+/// A record left behind when a document is deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tombstone {
+    pub id: i32,
+    pub document_id: String,
+    pub source_id: String,
+    pub source_url: String,
+    pub content_hash: Option<String>,
+    pub reason: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl From<DocumentTombstoneRecord> for Tombstone {
+    fn from(r: DocumentTombstoneRecord) -> Self {
+        Self {
+            id: r.id,
+            document_id: r.document_id,
+            source_id: r.source_id,
+            source_url: r.source_url,
+            content_hash: r.content_hash,
+            reason: r.reason,
+            deleted_at: parse_datetime(&r.deleted_at),
+        }
+    }
+}
+
+/// A legal hold placed on a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegalHold {
+    pub document_id: String,
+    pub reason: Option<String>,
+    pub placed_at: DateTime<Utc>,
+}
+
+impl From<DocumentLegalHoldRecord> for LegalHold {
+    fn from(r: DocumentLegalHoldRecord) -> Self {
+        Self {
+            document_id: r.document_id,
+            reason: r.reason,
+            placed_at: parse_datetime(&r.placed_at),
+        }
+    }
+}
+
+/// Result of a document deletion request.
+#[derive(Debug, Clone)]
+pub enum DeleteOutcome {
+    /// The document and its dependent rows were deleted.
+    ///
+    /// Carries the deleted versions so the caller can remove their files,
+    /// along with the document's URL and title needed to resolve paths.
+    Deleted {
+        source_url: String,
+        title: String,
+        versions: Vec<DocumentVersion>,
+    },
+    /// No document with that ID exists.
+    NotFound,
+    /// The document is under legal hold and was left untouched.
+    OnHold(LegalHold),
+}
+
+impl DieselDocumentRepository {
+    /// Delete a document and everything that references it, leaving tombstones.
+    ///
+    /// Removes OCR results, analysis results, PII spans, pages, entities,
+    /// annotations, virtual files and versions, unlinks it from tracked FOIA
+    /// requests, detaches crawl URLs (marking them skipped so they are not
+    /// retried), and records a tombstone for each distinct URL/content-hash
+    /// pair. Files on disk are not touched.
+    pub async fn delete_with_tombstone(
+        &self,
+        id: &str,
+        reason: Option<&str>,
+    ) -> Result<DeleteOutcome, DieselError> {
+        use diesel_async::AsyncConnection;
+
+        let id = id.to_string();
+        let reason = reason.map(|r| r.to_string());
+        let deleted_at = Utc::now().to_rfc3339();
+
+        with_conn!(self.pool, conn, {
+            conn.transaction(|conn| {
+                let id = id.clone();
+                let reason = reason.clone();
+                let deleted_at = deleted_at.clone();
+                Box::pin(async move {
+                    let hold: Option<DocumentLegalHoldRecord> = document_legal_holds::table
+                        .find(&id)
+                        .first(conn)
+                        .await
+                        .optional()?;
+                    if let Some(hold) = hold {
+                        return Ok(DeleteOutcome::OnHold(hold.into()));
+                    }
+
+                    let doc: Option<(String, String, String)> = documents::table
+                        .find(&id)
+                        .select((
+                            documents::source_id,
+                            documents::source_url,
+                            documents::title,
+                        ))
+                        .first(conn)
+                        .await
+                        .optional()?;
+                    let Some((source_id, source_url, title)) = doc else {
+                        return Ok(DeleteOutcome::NotFound);
+                    };
+
+                    let versions: Vec<DocumentVersion> = document_versions::table
+                        .filter(document_versions::document_id.eq(&id))
+                        .order(document_versions::id.asc())
+                        .load::<DocumentVersionRecord>(conn)
+                        .await?
+                        .into_iter()
+                        .map(Self::version_record_to_model)
+                        .collect();
+
+                    let page_ids = document_pages::table
+                        .filter(document_pages::document_id.eq(&id))
+                        .select(document_pages::id);
+                    diesel::delete(
+                        page_ocr_results::table.filter(page_ocr_results::page_id.eq_any(page_ids)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        document_analysis_results::table
+                            .filter(document_analysis_results::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        document_pii_spans::table.filter(document_pii_spans::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        email_messages::table.filter(email_messages::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        email_correspondence::table
+                            .filter(email_correspondence::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        foia_request_documents::table
+                            .filter(foia_request_documents::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::update(
+                        foia_request_events::table.filter(foia_request_events::document_id.eq(&id)),
+                    )
+                    .set(foia_request_events::document_id.eq(None::<String>))
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        document_pages::table.filter(document_pages::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        document_entities::table.filter(document_entities::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        document_annotations::table
+                            .filter(document_annotations::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(virtual_files::table.filter(virtual_files::document_id.eq(&id)))
+                        .execute(conn)
+                        .await?;
+                    diesel::delete(
+                        document_versions::table.filter(document_versions::document_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::update(crawl_urls::table.filter(crawl_urls::document_id.eq(&id)))
+                        .set((
+                            crawl_urls::document_id.eq(None::<String>),
+                            crawl_urls::status.eq("skipped"),
+                        ))
+                        .execute(conn)
+                        .await?;
+                    diesel::delete(documents::table.find(&id))
+                        .execute(conn)
+                        .await?;
+
+                    let mut pairs: Vec<(&str, Option<&str>)> = Vec::new();
+                    for version in &versions {
+                        let urls = std::iter::once(source_url.as_str())
+                            .chain(version.source_url.as_deref());
+                        for url in urls {
+                            let pair = (url, Some(version.content_hash.as_str()));
+                            if !pairs.contains(&pair) {
+                                pairs.push(pair);
+                            }
+                        }
+                    }
+                    if pairs.is_empty() {
+                        pairs.push((source_url.as_str(), None));
+                    }
+
+                    let rows: Vec<NewDocumentTombstone> = pairs
+                        .into_iter()
+                        .map(|(url, hash)| NewDocumentTombstone {
+                            document_id: &id,
+                            source_id: &source_id,
+                            source_url: url,
+                            content_hash: hash,
+                            reason: reason.as_deref(),
+                            deleted_at: &deleted_at,
+                        })
+                        .collect();
+                    for row in &rows {
+                        diesel::insert_into(document_tombstones::table)
+                            .values(row)
+                            .execute(conn)
+                            .await?;
+                    }
+
+                    Ok(DeleteOutcome::Deleted {
+                        source_url,
+                        title,
+                        versions,
+                    })
+                })
+            })
+            .await
+        })
+    }
+
+    /// Find a tombstone matching a URL or content hash.
+    pub async fn find_tombstone(
+        &self,
+        url: Option<&str>,
+        content_hash: Option<&str>,
+    ) -> Result<Option<Tombstone>, DieselError> {
+        if url.is_none() && content_hash.is_none() {
+            return Ok(None);
+        }
+
+        with_conn!(self.pool, conn, {
+            let mut query = document_tombstones::table.into_boxed();
+            if let Some(url) = url {
+                query = query.or_filter(document_tombstones::source_url.eq(url));
+            }
+            if let Some(hash) = content_hash {
+                query = query.or_filter(document_tombstones::content_hash.eq(hash));
+            }
+            query
+                .first::<DocumentTombstoneRecord>(&mut conn)
+                .await
+                .optional()
+                .map(|r| r.map(Tombstone::from))
+        })
+    }
+
+    /// List tombstones, newest first.
+    pub async fn list_tombstones(&self, limit: usize) -> Result<Vec<Tombstone>, DieselError> {
+        let limit = limit as i64;
+        with_conn!(self.pool, conn, {
+            document_tombstones::table
+                .order(document_tombstones::id.desc())
+                .limit(limit)
+                .load::<DocumentTombstoneRecord>(&mut conn)
+                .await
+                .map(|rows| rows.into_iter().map(Tombstone::from).collect())
+        })
+    }
+
+    /// Remove all tombstones for a document, allowing it to be re-acquired.
+    pub async fn clear_tombstones(&self, document_id: &str) -> Result<usize, DieselError> {
+        with_conn!(self.pool, conn, {
+            diesel::delete(
+                document_tombstones::table.filter(document_tombstones::document_id.eq(document_id)),
+            )
+            .execute(&mut conn)
+            .await
+        })
+    }
+
+    /// Count documents (other than `exclude_id`) that share a content hash.
+    pub async fn count_documents_with_hash(
+        &self,
+        content_hash: &str,
+        exclude_id: &str,
+    ) -> Result<u64, DieselError> {
+        with_conn!(self.pool, conn, {
+            document_versions::table
+                .filter(document_versions::content_hash.eq(content_hash))
+                .filter(document_versions::document_id.ne(exclude_id))
+                .select(diesel::dsl::count_distinct(document_versions::document_id))
+                .first::<i64>(&mut conn)
+                .await
+                .map(|c| c as u64)
+        })
+    }
+
+    /// Place a legal hold on a document, replacing any existing hold.
+    ///
+    /// Returns `false` if the document does not exist.
+    pub async fn place_legal_hold(
+        &self,
+        document_id: &str,
+        reason: Option<&str>,
+    ) -> Result<bool, DieselError> {
+        if !self.exists(document_id).await? {
+            return Ok(false);
+        }
+
+        let record = DocumentLegalHoldRecord {
+            document_id: document_id.to_string(),
+            reason: reason.map(|r| r.to_string()),
+            placed_at: Utc::now().to_rfc3339(),
+        };
+
+        with_conn!(self.pool, conn, {
+            diesel::delete(document_legal_holds::table.find(document_id))
+                .execute(&mut conn)
+                .await?;
+            diesel::insert_into(document_legal_holds::table)
+                .values(&record)
+                .execute(&mut conn)
+                .await?;
+            Ok(true)
+        })
+    }
+
+    /// Release a legal hold. Returns `false` if the document was not held.
+    pub async fn release_legal_hold(&self, document_id: &str) -> Result<bool, DieselError> {
+        with_conn!(self.pool, conn, {
+            diesel::delete(document_legal_holds::table.find(document_id))
+                .execute(&mut conn)
+                .await
+                .map(|rows| rows > 0)
+        })
+    }
+
+    /// Get the legal hold on a document, if any.
+    pub async fn get_legal_hold(
+        &self,
+        document_id: &str,
+    ) -> Result<Option<LegalHold>, DieselError> {
+        with_conn!(self.pool, conn, {
+            document_legal_holds::table
+                .find(document_id)
+                .first::<DocumentLegalHoldRecord>(&mut conn)
+                .await
+                .optional()
+                .map(|r| r.map(LegalHold::from))
+        })
+    }
+
+    /// List all legal holds, oldest first.
+    pub async fn list_legal_holds(&self) -> Result<Vec<LegalHold>, DieselError> {
+        with_conn!(self.pool, conn, {
+            document_legal_holds::table
+                .order(document_legal_holds::placed_at.asc())
+                .load::<DocumentLegalHoldRecord>(&mut conn)
+                .await
+                .map(|rows| rows.into_iter().map(LegalHold::from).collect())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Document;
+    use crate::repository::diesel_document::tests::setup_test_db;
+    use crate::repository::pool::DbPool;
+    use diesel_async::SimpleAsyncConnection;
+
+    async fn create_dependent_tables(pool: &DbPool) {
+        let DbPool::Sqlite(sqlite) = pool else {
+            unreachable!()
+        };
+        let mut conn = sqlite.get().await.unwrap();
+        conn.batch_execute(
+            r#"
+            CREATE TABLE page_ocr_results (id INTEGER PRIMARY KEY, page_id INTEGER NOT NULL);
+            CREATE TABLE document_analysis_results (id INTEGER PRIMARY KEY, document_id TEXT NOT NULL);
+            CREATE TABLE document_entities (id INTEGER PRIMARY KEY, document_id TEXT NOT NULL);
+            CREATE TABLE document_pii_spans (id INTEGER PRIMARY KEY, document_id TEXT NOT NULL);
+            CREATE TABLE email_messages (document_id TEXT PRIMARY KEY NOT NULL);
+            CREATE TABLE email_correspondence (id INTEGER PRIMARY KEY, document_id TEXT NOT NULL);
+            CREATE TABLE foia_request_documents (request_id TEXT NOT NULL, document_id TEXT NOT NULL);
+            CREATE TABLE foia_request_events (id INTEGER PRIMARY KEY, document_id TEXT);
+            CREATE TABLE document_annotations (id INTEGER PRIMARY KEY, document_id TEXT NOT NULL);
+            CREATE TABLE crawl_urls (id INTEGER PRIMARY KEY, url TEXT, status TEXT, document_id TEXT);
+            CREATE TABLE document_legal_holds (
+                document_id TEXT PRIMARY KEY,
+                reason TEXT,
+                placed_at TEXT NOT NULL
+            );
+            "#,
+        )
+        .await
+        .unwrap();
+    }
+
+    fn sample_document(id: &str, url: &str, content: &[u8]) -> Document {
+        Document::new(
+            id.to_string(),
+            "test-source".to_string(),
+            "Sample".to_string(),
+            url.to_string(),
+            DocumentVersion::new(
+                content,
+                "application/pdf".to_string(),
+                Some(url.to_string()),
+            ),
+            serde_json::json!({}),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_delete_leaves_tombstone() {
+        let (pool, _dir) = setup_test_db().await;
+        create_dependent_tables(&pool).await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        let doc = sample_document("doc-1", "https://example.com/a.pdf", b"pii");
+        assert!(repo.save_with_versions(&doc).await.unwrap());
+
+        let outcome = repo
+            .delete_with_tombstone("doc-1", Some("takedown"))
+            .await
+            .unwrap();
+        let DeleteOutcome::Deleted { versions, .. } = outcome else {
+            panic!("expected deletion");
+        };
+        assert_eq!(versions.len(), 1);
+        assert!(!repo.exists("doc-1").await.unwrap());
+
+        let by_url = repo
+            .find_tombstone(Some("https://example.com/a.pdf"), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(by_url.reason.as_deref(), Some("takedown"));
+
+        let hash = DocumentVersion::compute_hash(b"pii");
+        let by_hash = repo.find_tombstone(None, Some(&hash)).await.unwrap();
+        assert!(by_hash.is_some());
+
+        assert!(!repo.save_with_versions(&doc).await.unwrap());
+        let copy = sample_document("doc-3", "https://example.com/copy.pdf", b"pii");
+        assert!(!repo.save_with_versions(&copy).await.unwrap());
+        assert!(!repo.exists("doc-3").await.unwrap());
+
+        assert_eq!(repo.clear_tombstones("doc-1").await.unwrap(), 1);
+        assert!(repo
+            .find_tombstone(Some("https://example.com/a.pdf"), Some(&hash))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repo.save_with_versions(&doc).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_legal_hold_blocks_delete() {
+        let (pool, _dir) = setup_test_db().await;
+        create_dependent_tables(&pool).await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        let doc = sample_document("doc-2", "https://example.com/b.pdf", b"evidence");
+        repo.save_with_versions(&doc).await.unwrap();
+
+        assert!(repo
+            .place_legal_hold("doc-2", Some("litigation"))
+            .await
+            .unwrap());
+        assert!(!repo.place_legal_hold("missing", None).await.unwrap());
+
+        let outcome = repo.delete_with_tombstone("doc-2", None).await.unwrap();
+        assert!(matches!(
+            outcome,
+            DeleteOutcome::OnHold(ref h) if h.reason.as_deref() == Some("litigation")
+        ));
+        assert!(repo.exists("doc-2").await.unwrap());
+
+        assert!(repo.release_legal_hold("doc-2").await.unwrap());
+        let outcome = repo.delete_with_tombstone("doc-2", None).await.unwrap();
+        assert!(matches!(outcome, DeleteOutcome::Deleted { .. }));
+        assert!(matches!(
+            repo.delete_with_tombstone("doc-2", None).await.unwrap(),
+            DeleteOutcome::NotFound
+        ));
+    }
+}
@@ -10,6 +10,16 @@ use crate::repository::pool::DieselError;
 use crate::schema::document_versions;
 use crate::with_conn;
 
+/// A version together with the document fields needed to resolve its path.
+#[derive(Debug, Clone)]
+pub struct StoredVersion {
+    pub document_id: String,
+    pub source_id: String,
+    pub source_url: String,
+    pub title: String,
+    pub version: DocumentVersion,
+}
+
 impl DieselDocumentRepository {
     /// Load versions for a document.
     pub(crate) async fn load_versions(
@@ -322,6 +332,93 @@ impl DieselDocumentRepository {
             .collect())
     }
 
+    /// Get versions in ascending id order for storage verification.
+    ///
+    /// Uses cursor pagination on version id so long-running passes can be
+    /// checkpointed and resumed.
+    pub async fn get_stored_versions_after(
+        &self,
+        after_id: i64,
+        limit: usize,
+        source_id: Option<&str>,
+    ) -> Result<Vec<StoredVersion>, DieselError> {
+        use crate::schema::documents;
+
+        let records: Vec<(DocumentVersionRecord, String, String, String)> =
+            with_conn!(self.pool, conn, {
+                let mut query = document_versions::table
+                    .inner_join(documents::table)
+                    .filter(document_versions::id.gt(after_id as i32))
+                    .into_boxed();
+                if let Some(sid) = source_id {
+                    query = query.filter(documents::source_id.eq(sid));
+                }
+                query
+                    .order(document_versions::id.asc())
+                    .limit(limit as i64)
+                    .select((
+                        DocumentVersionRecord::as_select(),
+                        documents::source_id,
+                        documents::source_url,
+                        documents::title,
+                    ))
+                    .load(&mut conn)
+                    .await
+            })?;
+
+        Ok(records
+            .into_iter()
+            .map(|(rec, source_id, source_url, title)| StoredVersion {
+                document_id: rec.document_id.clone(),
+                source_id,
+                source_url,
+                title,
+                version: Self::version_record_to_model(rec),
+            })
+            .collect())
+    }
+
+    /// Get archive URLs that may hold a copy of a version's content.
+    ///
+    /// The snapshot the version was fetched from (if any) comes first,
+    /// followed by other known snapshots of the same URL, newest first.
+    pub async fn get_archive_urls(
+        &self,
+        version: &DocumentVersion,
+        source_url: &str,
+    ) -> Result<Vec<String>, DieselError> {
+        use crate::schema::archive_snapshots;
+
+        let original_url = version.source_url.as_deref().unwrap_or(source_url);
+        let snapshots: Vec<(i32, String)> = with_conn!(self.pool, conn, {
+            archive_snapshots::table
+                .filter(archive_snapshots::original_url.eq(original_url))
+                .order(archive_snapshots::captured_at.desc())
+                .select((archive_snapshots::id, archive_snapshots::archive_url))
+                .load(&mut conn)
+                .await
+        })?;
+
+        let mut urls = Vec::with_capacity(snapshots.len() + 1);
+        if let Some(snapshot_id) = version.archive_snapshot_id {
+            let own: Option<String> = with_conn!(self.pool, conn, {
+                archive_snapshots::table
+                    .find(snapshot_id)
+                    .select(archive_snapshots::archive_url)
+                    .first(&mut conn)
+                    .await
+                    .optional()
+            })?;
+            urls.extend(own);
+        }
+        for (_, url) in snapshots {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        Ok(urls)
+    }
+
     /// Get all content hashes for duplicate detection.
     /// Returns (doc_id, source_id, content_hash, title) tuples
     pub async fn get_content_hashes(
@@ -362,6 +459,25 @@ impl DieselDocumentRepository {
             .collect())
     }
 
+    /// Find IDs of documents with a version whose content hash starts with `prefix`.
+    ///
+    /// Stored filenames embed the first 8 hash characters, which is enough to
+    /// map a file back to the documents that reference it.
+    pub async fn find_document_ids_by_hash_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<String>, DieselError> {
+        let pattern = format!("{}%", prefix);
+        with_conn!(self.pool, conn, {
+            document_versions::table
+                .filter(document_versions::content_hash.like(&pattern))
+                .select(document_versions::document_id)
+                .distinct()
+                .load(&mut conn)
+                .await
+        })
+    }
+
     /// Find documents by content hash.
     /// Returns (source_id, document_id, title) tuples
     pub async fn find_sources_by_hash(
@@ -432,4 +548,43 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn test_get_stored_versions_after_paginates_by_id() {
+        use crate::models::Document;
+
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselDocumentRepository::new(pool);
+
+        for (i, source) in ["src-a", "src-b", "src-a"].iter().enumerate() {
+            let content = format!("content {}", i);
+            let doc = Document::new(
+                format!("doc-{}", i),
+                source.to_string(),
+                format!("Doc {}", i),
+                format!("https://example.com/{}.pdf", i),
+                DocumentVersion::new(content.as_bytes(), "application/pdf".to_string(), None),
+                serde_json::json!({}),
+            );
+            repo.save_with_versions(&doc).await.unwrap();
+        }
+
+        let first = repo.get_stored_versions_after(0, 2, None).await.unwrap();
+        assert_eq!(first.len(), 2);
+        let last_id = first.last().unwrap().version.id;
+
+        let rest = repo
+            .get_stored_versions_after(last_id, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].document_id, "doc-2");
+
+        let only_a = repo
+            .get_stored_versions_after(0, 10, Some("src-a"))
+            .await
+            .unwrap();
+        assert_eq!(only_a.len(), 2);
+        assert!(only_a.iter().all(|v| v.source_id == "src-a"));
+    }
 }
@@ -0,0 +1,687 @@
+//! Diesel-based repository for tracked FOIA requests.
+//!
+//! Stores requests we filed in `foia_requests`, their status history and
+//! correspondence in `foia_request_events`, and the documents released in
+//! response in `foia_request_documents`.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+
+use super::models::{
+    FoiaRequestEventRecord, FoiaRequestRecord, NewFoiaRequestDocument, NewFoiaRequestEvent,
+};
+use super::parse_datetime;
+use super::pool::{DbPool, DieselError};
+use crate::models::{DeadlineRule, FoiaRequest, RequestEvent, RequestEventKind, RequestStatus};
+use crate::schema::{documents, foia_request_documents, foia_request_events, foia_requests};
+use crate::{with_conn, with_conn_split};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn invalid(what: &str, value: &str) -> DieselError {
+    DieselError::DeserializationError(format!("Invalid {}: '{}'", what, value).into())
+}
+
+fn parse_date(s: Option<String>) -> Option<NaiveDate> {
+    s.and_then(|s| NaiveDate::parse_from_str(&s, DATE_FORMAT).ok())
+}
+
+fn format_date(date: Option<NaiveDate>) -> Option<String> {
+    date.map(|d| d.format(DATE_FORMAT).to_string())
+}
+
+/// Convert a database record to a domain model.
+impl TryFrom<FoiaRequestRecord> for FoiaRequest {
+    type Error = DieselError;
+
+    fn try_from(record: FoiaRequestRecord) -> Result<Self, Self::Error> {
+        Ok(FoiaRequest {
+            status: RequestStatus::from_str(&record.status)
+                .ok_or_else(|| invalid("request status", &record.status))?,
+            deadline_rule: DeadlineRule::from_str(&record.deadline_rule)
+                .ok_or_else(|| invalid("deadline rule", &record.deadline_rule))?,
+            id: record.id,
+            agency: record.agency,
+            tracking_number: record.tracking_number,
+            subject: record.subject,
+            description: record.description,
+            filed_at: parse_date(record.filed_at),
+            response_days: record.response_days.max(0) as u32,
+            extension_days: record.extension_days.max(0) as u32,
+            closed_at: parse_date(record.closed_at),
+            created_at: parse_datetime(&record.created_at),
+            updated_at: parse_datetime(&record.updated_at),
+        })
+    }
+}
+
+impl From<&FoiaRequest> for FoiaRequestRecord {
+    fn from(request: &FoiaRequest) -> Self {
+        Self {
+            id: request.id.clone(),
+            agency: request.agency.clone(),
+            tracking_number: request.tracking_number.clone(),
+            subject: request.subject.clone(),
+            description: request.description.clone(),
+            status: request.status.as_str().to_string(),
+            filed_at: format_date(request.filed_at),
+            deadline_rule: request.deadline_rule.as_str().to_string(),
+            response_days: request.response_days as i32,
+            extension_days: request.extension_days as i32,
+            closed_at: format_date(request.closed_at),
+            created_at: request.created_at.to_rfc3339(),
+            updated_at: request.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Convert a database record to a domain model.
+impl TryFrom<FoiaRequestEventRecord> for RequestEvent {
+    type Error = DieselError;
+
+    fn try_from(record: FoiaRequestEventRecord) -> Result<Self, Self::Error> {
+        let status = record
+            .status
+            .as_deref()
+            .map(|s| RequestStatus::from_str(s).ok_or_else(|| invalid("request status", s)))
+            .transpose()?;
+        Ok(RequestEvent {
+            id: i64::from(record.id),
+            kind: RequestEventKind::from_str(&record.kind)
+                .ok_or_else(|| invalid("event kind", &record.kind))?,
+            request_id: record.request_id,
+            status,
+            summary: record.summary,
+            body: record.body,
+            document_id: record.document_id,
+            occurred_at: parse_datetime(&record.occurred_at),
+        })
+    }
+}
+
+/// Filters for listing requests.
+#[derive(Debug, Clone, Default)]
+pub struct FoiaRequestFilter {
+    pub status: Option<RequestStatus>,
+    /// Case-insensitive substring of the agency name.
+    pub agency: Option<String>,
+    /// Only requests still awaiting a response.
+    pub open_only: bool,
+}
+
+/// A document linked to a request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedDocument {
+    pub document_id: String,
+    pub title: String,
+    pub source_id: String,
+    pub linked_at: DateTime<Utc>,
+}
+
+/// Correspondence or note to add to a request's history.
+#[derive(Debug, Clone, Default)]
+pub struct NewRequestEvent<'a> {
+    pub summary: Option<&'a str>,
+    pub body: Option<&'a str>,
+    pub document_id: Option<&'a str>,
+    /// Defaults to now.
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+/// Diesel-based FOIA request repository.
+#[derive(Clone)]
+pub struct DieselFoiaRequestRepository {
+    pool: DbPool,
+}
+
+impl DieselFoiaRequestRepository {
+    /// Create a new repository with an existing pool.
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert a new request.
+    pub async fn create(&self, request: &FoiaRequest) -> Result<(), DieselError> {
+        let record = FoiaRequestRecord::from(request);
+        with_conn!(self.pool, conn, {
+            diesel::insert_into(foia_requests::table)
+                .values(&record)
+                .execute(&mut conn)
+                .await?;
+            Ok(())
+        })
+    }
+
+    /// Get a request by ID.
+    pub async fn get(&self, id: &str) -> Result<Option<FoiaRequest>, DieselError> {
+        with_conn!(self.pool, conn, {
+            foia_requests::table
+                .find(id)
+                .first::<FoiaRequestRecord>(&mut conn)
+                .await
+                .optional()
+                .and_then(|opt| opt.map(FoiaRequest::try_from).transpose())
+        })
+    }
+
+    /// Look up a request by ID or, failing that, by agency tracking number.
+    pub async fn find(&self, id_or_tracking: &str) -> Result<Option<FoiaRequest>, DieselError> {
+        if let Some(request) = self.get(id_or_tracking).await? {
+            return Ok(Some(request));
+        }
+        with_conn!(self.pool, conn, {
+            foia_requests::table
+                .filter(foia_requests::tracking_number.eq(id_or_tracking))
+                .order(foia_requests::created_at.desc())
+                .first::<FoiaRequestRecord>(&mut conn)
+                .await
+                .optional()
+                .and_then(|opt| opt.map(FoiaRequest::try_from).transpose())
+        })
+    }
+
+    /// List requests, most recently filed first (drafts last).
+    pub async fn list(&self, filter: &FoiaRequestFilter) -> Result<Vec<FoiaRequest>, DieselError> {
+        let mut requests: Vec<FoiaRequest> = with_conn!(self.pool, conn, {
+            let mut query = foia_requests::table.into_boxed();
+            if let Some(status) = filter.status {
+                query = query.filter(foia_requests::status.eq(status.as_str()));
+            }
+            if filter.open_only {
+                let open: Vec<&str> = RequestStatus::ALL
+                    .iter()
+                    .filter(|s| s.is_open())
+                    .map(|s| s.as_str())
+                    .collect();
+                query = query.filter(foia_requests::status.eq_any(open));
+            }
+            query
+                .order(foia_requests::created_at.desc())
+                .load::<FoiaRequestRecord>(&mut conn)
+                .await
+                .and_then(|records| records.into_iter().map(FoiaRequest::try_from).collect())
+        })?;
+
+        if let Some(agency) = &filter.agency {
+            let agency = agency.to_lowercase();
+            requests.retain(|r| r.agency.to_lowercase().contains(&agency));
+        }
+        requests.sort_by(|a, b| b.filed_at.cmp(&a.filed_at));
+        Ok(requests)
+    }
+
+    /// Open requests due within `within_days` of `today` (or already
+    /// overdue), soonest first, with the days remaining for each.
+    pub async fn due_within(
+        &self,
+        today: NaiveDate,
+        within_days: i64,
+    ) -> Result<Vec<(FoiaRequest, i64)>, DieselError> {
+        let open = self
+            .list(&FoiaRequestFilter {
+                open_only: true,
+                ..Default::default()
+            })
+            .await?;
+        let mut due: Vec<(FoiaRequest, i64)> = open
+            .into_iter()
+            .filter_map(|r| r.days_remaining(today).map(|days| (r, days)))
+            .filter(|(_, days)| *days <= within_days)
+            .collect();
+        due.sort_by_key(|(_, days)| *days);
+        Ok(due)
+    }
+
+    /// Save changes to a request's fields. Returns false if it doesn't exist.
+    ///
+    /// Use [`Self::set_status`] to change status so the history is recorded.
+    pub async fn update(&self, request: &FoiaRequest) -> Result<bool, DieselError> {
+        let mut record = FoiaRequestRecord::from(request);
+        record.updated_at = Utc::now().to_rfc3339();
+        let rows = with_conn!(self.pool, conn, {
+            diesel::update(foia_requests::table.find(&request.id))
+                .set(&record)
+                .execute(&mut conn)
+                .await?
+        });
+        Ok(rows > 0)
+    }
+
+    /// Delete a request with its history and document links.
+    ///
+    /// Linked documents themselves are kept.
+    pub async fn delete(&self, id: &str) -> Result<bool, DieselError> {
+        use diesel_async::AsyncConnection;
+
+        let id = id.to_string();
+        with_conn!(self.pool, conn, {
+            conn.transaction(|conn| {
+                let id = id.clone();
+                Box::pin(async move {
+                    diesel::delete(
+                        foia_request_events::table.filter(foia_request_events::request_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::delete(
+                        foia_request_documents::table
+                            .filter(foia_request_documents::request_id.eq(&id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    let rows = diesel::delete(foia_requests::table.find(&id))
+                        .execute(conn)
+                        .await?;
+                    Ok(rows > 0)
+                })
+            })
+            .await
+        })
+    }
+
+    /// Change a request's status and record it in the history.
+    ///
+    /// Moving to `filed` sets the filing date if it isn't set yet, which
+    /// starts the deadline clock. Closing sets `closed_at`; reopening (for
+    /// example on appeal) clears it. Returns the updated request, or `None`
+    /// if it doesn't exist.
+    pub async fn set_status(
+        &self,
+        id: &str,
+        status: RequestStatus,
+        at: DateTime<Utc>,
+        note: Option<&str>,
+    ) -> Result<Option<FoiaRequest>, DieselError> {
+        use diesel_async::AsyncConnection;
+
+        let id = id.to_string();
+        let note = note.map(|n| n.to_string());
+        let now = Utc::now().to_rfc3339();
+        let occurred_at = at.to_rfc3339();
+        let date = at.date_naive();
+
+        with_conn!(self.pool, conn, {
+            conn.transaction(|conn| {
+                let id = id.clone();
+                let note = note.clone();
+                let now = now.clone();
+                let occurred_at = occurred_at.clone();
+                Box::pin(async move {
+                    let record: Option<FoiaRequestRecord> = foia_requests::table
+                        .find(&id)
+                        .first(conn)
+                        .await
+                        .optional()?;
+                    let Some(record) = record else {
+                        return Ok(None);
+                    };
+                    let mut request = FoiaRequest::try_from(record)?;
+
+                    request.status = status;
+                    if status == RequestStatus::Filed && request.filed_at.is_none() {
+                        request.filed_at = Some(date);
+                    }
+                    request.closed_at = status.is_closed().then_some(date);
+
+                    let mut updated = FoiaRequestRecord::from(&request);
+                    updated.updated_at = now.clone();
+                    diesel::update(foia_requests::table.find(&id))
+                        .set(&updated)
+                        .execute(conn)
+                        .await?;
+                    diesel::insert_into(foia_request_events::table)
+                        .values(&NewFoiaRequestEvent {
+                            request_id: &id,
+                            kind: RequestEventKind::Status.as_str(),
+                            status: Some(status.as_str()),
+                            summary: note.as_deref(),
+                            body: None,
+                            document_id: None,
+                            occurred_at: &occurred_at,
+                            created_at: &now,
+                        })
+                        .execute(conn)
+                        .await?;
+
+                    Ok(Some(FoiaRequest::try_from(updated)?))
+                })
+            })
+            .await
+        })
+    }
+
+    /// Add correspondence or a note to a request's history.
+    pub async fn add_event(
+        &self,
+        request_id: &str,
+        kind: RequestEventKind,
+        event: &NewRequestEvent<'_>,
+    ) -> Result<(), DieselError> {
+        let now = Utc::now().to_rfc3339();
+        let occurred_at = event
+            .occurred_at
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_else(|| now.clone());
+        with_conn!(self.pool, conn, {
+            diesel::insert_into(foia_request_events::table)
+                .values(&NewFoiaRequestEvent {
+                    request_id,
+                    kind: kind.as_str(),
+                    status: None,
+                    summary: event.summary,
+                    body: event.body,
+                    document_id: event.document_id,
+                    occurred_at: &occurred_at,
+                    created_at: &now,
+                })
+                .execute(&mut conn)
+                .await?;
+            Ok(())
+        })
+    }
+
+    /// A request's history, oldest first.
+    pub async fn events(&self, request_id: &str) -> Result<Vec<RequestEvent>, DieselError> {
+        with_conn!(self.pool, conn, {
+            foia_request_events::table
+                .filter(foia_request_events::request_id.eq(request_id))
+                .order((
+                    foia_request_events::occurred_at.asc(),
+                    foia_request_events::id.asc(),
+                ))
+                .load::<FoiaRequestEventRecord>(&mut conn)
+                .await
+                .and_then(|records| records.into_iter().map(RequestEvent::try_from).collect())
+        })
+    }
+
+    /// Link a released document to a request.
+    ///
+    /// Returns false if it was already linked, and `NotFound` if the
+    /// document doesn't exist.
+    pub async fn link_document(
+        &self,
+        request_id: &str,
+        document_id: &str,
+    ) -> Result<bool, DieselError> {
+        let now = Utc::now().to_rfc3339();
+        let link = NewFoiaRequestDocument {
+            request_id,
+            document_id,
+            linked_at: &now,
+        };
+
+        let exists: i64 = with_conn!(self.pool, conn, {
+            documents::table
+                .filter(documents::id.eq(document_id))
+                .count()
+                .get_result(&mut conn)
+                .await?
+        });
+        if exists == 0 {
+            return Err(DieselError::NotFound);
+        }
+
+        let rows = with_conn_split!(self.pool,
+            sqlite: conn => {
+                diesel::insert_or_ignore_into(foia_request_documents::table)
+                    .values(&link)
+                    .execute(&mut conn)
+                    .await?
+            },
+            postgres: conn => {
+                diesel::insert_into(foia_request_documents::table)
+                    .values(&link)
+                    .on_conflict_do_nothing()
+                    .execute(&mut conn)
+                    .await?
+            }
+        );
+        Ok(rows > 0)
+    }
+
+    /// Remove a document link. Returns false if it wasn't linked.
+    pub async fn unlink_document(
+        &self,
+        request_id: &str,
+        document_id: &str,
+    ) -> Result<bool, DieselError> {
+        let rows = with_conn!(self.pool, conn, {
+            diesel::delete(
+                foia_request_documents::table
+                    .filter(foia_request_documents::request_id.eq(request_id))
+                    .filter(foia_request_documents::document_id.eq(document_id)),
+            )
+            .execute(&mut conn)
+            .await?
+        });
+        Ok(rows > 0)
+    }
+
+    /// Documents linked to a request, in the order they were linked.
+    pub async fn documents(&self, request_id: &str) -> Result<Vec<LinkedDocument>, DieselError> {
+        let rows: Vec<(String, String, String, String)> = with_conn!(self.pool, conn, {
+            foia_request_documents::table
+                .inner_join(documents::table)
+                .filter(foia_request_documents::request_id.eq(request_id))
+                .order(foia_request_documents::linked_at.asc())
+                .select((
+                    foia_request_documents::document_id,
+                    documents::title,
+                    documents::source_id,
+                    foia_request_documents::linked_at,
+                ))
+                .load(&mut conn)
+                .await?
+        });
+        Ok(rows
+            .into_iter()
+            .map(
+                |(document_id, title, source_id, linked_at)| LinkedDocument {
+                    document_id,
+                    title,
+                    source_id,
+                    linked_at: parse_datetime(&linked_at),
+                },
+            )
+            .collect())
+    }
+
+    /// Requests a document is linked to.
+    pub async fn requests_for_document(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<FoiaRequest>, DieselError> {
+        with_conn!(self.pool, conn, {
+            foia_requests::table
+                .inner_join(foia_request_documents::table)
+                .filter(foia_request_documents::document_id.eq(document_id))
+                .select(FoiaRequestRecord::as_select())
+                .load::<FoiaRequestRecord>(&mut conn)
+                .await
+                .and_then(|records| records.into_iter().map(FoiaRequest::try_from).collect())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::pool::SqlitePool;
+    use diesel_async::SimpleAsyncConnection;
+    use tempfile::tempdir;
+
+    async fn setup_test_db() -> (DbPool, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+
+        let sqlite_pool = SqlitePool::from_path(&db_path);
+        let mut conn = sqlite_pool.get().await.unwrap();
+
+        conn.batch_execute(
+            r#"
+            CREATE TABLE documents (
+                id TEXT PRIMARY KEY NOT NULL,
+                source_id TEXT NOT NULL,
+                title TEXT NOT NULL
+            );
+            CREATE TABLE foia_requests (
+                id TEXT PRIMARY KEY NOT NULL,
+                agency TEXT NOT NULL,
+                tracking_number TEXT,
+                subject TEXT NOT NULL,
+                description TEXT,
+                status TEXT NOT NULL,
+                filed_at TEXT,
+                deadline_rule TEXT NOT NULL DEFAULT 'business',
+                response_days INTEGER NOT NULL DEFAULT 20,
+                extension_days INTEGER NOT NULL DEFAULT 0,
+                closed_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            CREATE TABLE foia_request_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                request_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                status TEXT,
+                summary TEXT,
+                body TEXT,
+                document_id TEXT,
+                occurred_at TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE TABLE foia_request_documents (
+                request_id TEXT NOT NULL,
+                document_id TEXT NOT NULL,
+                linked_at TEXT NOT NULL,
+                PRIMARY KEY (request_id, document_id)
+            );
+            INSERT INTO documents (id, source_id, title) VALUES ('doc-1', 'fbi', 'Release 1');
+            "#,
+        )
+        .await
+        .unwrap();
+
+        (DbPool::Sqlite(sqlite_pool), dir)
+    }
+
+    fn at(date: &str) -> DateTime<Utc> {
+        NaiveDate::parse_from_str(date, DATE_FORMAT)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[tokio::test]
+    async fn test_request_lifecycle() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselFoiaRequestRepository::new(pool);
+
+        let mut request = FoiaRequest::new("Federal Bureau of Investigation", "Field office memos");
+        request.tracking_number = Some("1234567-000".to_string());
+        repo.create(&request).await.unwrap();
+
+        let found = repo.find("1234567-000").await.unwrap().unwrap();
+        assert_eq!(found.id, request.id);
+        assert_eq!(found.status, RequestStatus::Draft);
+
+        let filed = repo
+            .set_status(&request.id, RequestStatus::Filed, at("2024-11-22"), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(filed.filed_at, NaiveDate::from_ymd_opt(2024, 11, 22));
+        assert!(filed.due_date().is_some());
+
+        repo.add_event(
+            &request.id,
+            RequestEventKind::Received,
+            &NewRequestEvent {
+                summary: Some("Acknowledgement letter"),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let closed = repo
+            .set_status(
+                &request.id,
+                RequestStatus::Fulfilled,
+                at("2025-01-10"),
+                Some("Final response"),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(closed.closed_at, NaiveDate::from_ymd_opt(2025, 1, 10));
+        // Filing date is kept once set
+        assert_eq!(closed.filed_at, NaiveDate::from_ymd_opt(2024, 11, 22));
+
+        let events = repo.events(&request.id).await.unwrap();
+        let kinds: Vec<_> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RequestEventKind::Status,
+                RequestEventKind::Status,
+                RequestEventKind::Received
+            ]
+        );
+        assert_eq!(events[1].status, Some(RequestStatus::Fulfilled));
+
+        let open = repo
+            .list(&FoiaRequestFilter {
+                open_only: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(open.is_empty());
+
+        assert!(repo.link_document(&request.id, "doc-1").await.unwrap());
+        assert!(!repo.link_document(&request.id, "doc-1").await.unwrap());
+        assert!(matches!(
+            repo.link_document(&request.id, "missing").await,
+            Err(DieselError::NotFound)
+        ));
+        let docs = repo.documents(&request.id).await.unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].title, "Release 1");
+
+        assert!(repo.delete(&request.id).await.unwrap());
+        assert!(repo.get(&request.id).await.unwrap().is_none());
+        assert!(repo.events(&request.id).await.unwrap().is_empty());
+        assert!(repo.documents(&request.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_due_within() {
+        let (pool, _dir) = setup_test_db().await;
+        let repo = DieselFoiaRequestRepository::new(pool);
+
+        for (agency, filed) in [("DOJ", "2024-10-01"), ("DHS", "2024-12-02")] {
+            let request = FoiaRequest::new(agency, "Records");
+            repo.create(&request).await.unwrap();
+            repo.set_status(&request.id, RequestStatus::Filed, at(filed), None)
+                .await
+                .unwrap();
+        }
+        repo.create(&FoiaRequest::new("EPA", "Draft"))
+            .await
+            .unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2024, 12, 4).unwrap();
+        let overdue = repo.due_within(today, -1).await.unwrap();
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].0.agency, "DOJ");
+        assert!(overdue[0].1 < 0);
+
+        let upcoming = repo.due_within(today, 30).await.unwrap();
+        let agencies: Vec<_> = upcoming.iter().map(|(r, _)| r.agency.as_str()).collect();
+        assert_eq!(agencies, vec!["DOJ", "DHS"]);
+    }
+}
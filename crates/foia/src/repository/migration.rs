@@ -49,6 +49,8 @@ pub struct PortableDocument {
     pub manual_date: Option<String>,
     pub discovery_method: String,
     pub category_id: Option<String>,
+    pub bates_begin: Option<String>,
+    pub bates_end: Option<String>,
 }
 
 /// Portable document version record for migration.
@@ -81,6 +83,7 @@ pub struct PortableDocumentPage {
     pub ocr_status: String,
     pub created_at: String,
     pub updated_at: String,
+    pub bates_number: Option<String>,
 }
 
 /// Portable virtual file record for migration.
@@ -121,6 +124,8 @@ pub struct PortableCrawlUrl {
     pub last_modified: Option<String>,
     pub content_hash: Option<String>,
     pub document_id: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
 }
 
 /// Portable crawl request record for migration.
@@ -323,6 +328,8 @@ impl From<super::models::DocumentRecord> for PortableDocument {
             manual_date: r.manual_date,
             discovery_method: r.discovery_method,
             category_id: r.category_id,
+            bates_begin: r.bates_begin,
+            bates_end: r.bates_end,
         }
     }
 }
@@ -359,6 +366,7 @@ impl From<super::models::DocumentPageRecord> for PortableDocumentPage {
             ocr_status: r.ocr_status,
             created_at: r.created_at,
             updated_at: r.updated_at,
+            bates_number: r.bates_number,
         }
     }
 }
@@ -403,6 +411,7 @@ impl From<super::models::CrawlUrlRecord> for PortableCrawlUrl {
             last_modified: r.last_modified,
             content_hash: r.content_hash,
             document_id: r.document_id,
+            priority: r.priority,
         }
     }
 }
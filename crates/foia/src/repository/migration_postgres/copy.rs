@@ -76,14 +76,14 @@ impl PostgresMigrator {
         self.copy_batched(
             "COPY documents (id, source_id, title, source_url, extracted_text, status, metadata,
                 created_at, updated_at, synopsis, tags, estimated_date, date_confidence, date_source,
-                manual_date, discovery_method, category_id)
+                manual_date, discovery_method, category_id, bates_begin, bates_end)
              FROM STDIN WITH (FORMAT text)",
             documents,
             1000,
             500,
             |d| {
                 format!(
-                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
                     Self::escape_copy_value(Some(&d.id)),
                     Self::escape_copy_value(Some(&d.source_id)),
                     Self::escape_copy_value(Some(&d.title)),
@@ -101,6 +101,8 @@ impl PostgresMigrator {
                     Self::escape_copy_value(d.manual_date.as_deref()),
                     Self::escape_copy_value(Some(&d.discovery_method)),
                     Self::escape_copy_value(d.category_id.as_deref()),
+                    Self::escape_copy_value(d.bates_begin.as_deref()),
+                    Self::escape_copy_value(d.bates_end.as_deref()),
                 )
             },
             progress,
@@ -183,14 +185,14 @@ impl PostgresMigrator {
     ) -> Result<usize, DieselError> {
         self.copy_batched(
             "COPY document_pages (id, document_id, version_id, page_number, pdf_text,
-                ocr_text, final_text, ocr_status, created_at, updated_at)
+                ocr_text, final_text, ocr_status, created_at, updated_at, bates_number)
              FROM STDIN WITH (FORMAT text)",
             pages,
             1000,
             500,
             |p| {
                 format!(
-                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
                     p.id,
                     Self::escape_copy_value(Some(&p.document_id)),
                     p.version_id,
@@ -201,6 +203,7 @@ impl PostgresMigrator {
                     Self::escape_copy_value(Some(&p.ocr_status)),
                     Self::escape_copy_value(Some(&p.created_at)),
                     Self::escape_copy_value(Some(&p.updated_at)),
+                    Self::escape_copy_value(p.bates_number.as_deref()),
                 )
             },
             progress,
@@ -217,14 +220,14 @@ impl PostgresMigrator {
         self.copy_batched(
             "COPY crawl_urls (id, url, source_id, status, discovery_method, parent_url,
                 discovery_context, depth, discovered_at, fetched_at, retry_count, last_error,
-                next_retry_at, etag, last_modified, content_hash, document_id)
+                next_retry_at, etag, last_modified, content_hash, document_id, priority)
              FROM STDIN WITH (FORMAT text)",
             urls,
             1000,
             300,
             |u| {
                 format!(
-                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
                     u.id,
                     Self::escape_copy_value(Some(&u.url)),
                     Self::escape_copy_value(Some(&u.source_id)),
@@ -242,6 +245,7 @@ impl PostgresMigrator {
                     Self::escape_copy_value(u.last_modified.as_deref()),
                     Self::escape_copy_value(u.content_hash.as_deref()),
                     Self::escape_copy_value(u.document_id.as_deref()),
+                    u.priority,
                 )
             },
             progress,
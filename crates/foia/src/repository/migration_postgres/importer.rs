@@ -103,8 +103,8 @@ impl DatabaseImporter for PostgresMigrator {
             diesel::sql_query(
                 "INSERT INTO documents (id, source_id, title, source_url, extracted_text, status, metadata,
                     created_at, updated_at, synopsis, tags, estimated_date, date_confidence, date_source,
-                    manual_date, discovery_method, category_id)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                    manual_date, discovery_method, category_id, bates_begin, bates_end)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
                  ON CONFLICT (id) DO UPDATE SET
                     source_id = EXCLUDED.source_id,
                     title = EXCLUDED.title,
@@ -121,7 +121,9 @@ impl DatabaseImporter for PostgresMigrator {
                     date_source = EXCLUDED.date_source,
                     manual_date = EXCLUDED.manual_date,
                     discovery_method = EXCLUDED.discovery_method,
-                    category_id = EXCLUDED.category_id"
+                    category_id = EXCLUDED.category_id,
+                    bates_begin = EXCLUDED.bates_begin,
+                    bates_end = EXCLUDED.bates_end"
             )
             .bind::<diesel::sql_types::Text, _>(&d.id)
             .bind::<diesel::sql_types::Text, _>(&d.source_id)
@@ -140,6 +142,8 @@ impl DatabaseImporter for PostgresMigrator {
             .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&d.manual_date)
             .bind::<diesel::sql_types::Text, _>(&d.discovery_method)
             .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&d.category_id)
+            .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&d.bates_begin)
+            .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&d.bates_end)
             .execute(&mut conn)
             .await?;
             count += 1;
@@ -229,9 +233,9 @@ impl DatabaseImporter for PostgresMigrator {
         for p in pages {
             diesel::sql_query(
                 "INSERT INTO document_pages (id, document_id, version_id, page_number, pdf_text,
-                    ocr_text, final_text, ocr_status, created_at, updated_at)
+                    ocr_text, final_text, ocr_status, created_at, updated_at, bates_number)
                  OVERRIDING SYSTEM VALUE
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
                  ON CONFLICT (id) DO UPDATE SET
                     document_id = EXCLUDED.document_id,
                     version_id = EXCLUDED.version_id,
@@ -241,7 +245,8 @@ impl DatabaseImporter for PostgresMigrator {
                     final_text = EXCLUDED.final_text,
                     ocr_status = EXCLUDED.ocr_status,
                     created_at = EXCLUDED.created_at,
-                    updated_at = EXCLUDED.updated_at",
+                    updated_at = EXCLUDED.updated_at,
+                    bates_number = EXCLUDED.bates_number",
             )
             .bind::<diesel::sql_types::Integer, _>(p.id)
             .bind::<diesel::sql_types::Text, _>(&p.document_id)
@@ -253,6 +258,7 @@ impl DatabaseImporter for PostgresMigrator {
             .bind::<diesel::sql_types::Text, _>(&p.ocr_status)
             .bind::<diesel::sql_types::Text, _>(&p.created_at)
             .bind::<diesel::sql_types::Text, _>(&p.updated_at)
+            .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&p.bates_number)
             .execute(&mut conn)
             .await?;
             count += 1;
@@ -333,9 +339,9 @@ impl DatabaseImporter for PostgresMigrator {
             diesel::sql_query(
                 "INSERT INTO crawl_urls (id, url, source_id, status, discovery_method, parent_url,
                     discovery_context, depth, discovered_at, fetched_at, retry_count, last_error,
-                    next_retry_at, etag, last_modified, content_hash, document_id)
+                    next_retry_at, etag, last_modified, content_hash, document_id, priority)
                  OVERRIDING SYSTEM VALUE
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
                  ON CONFLICT (id) DO UPDATE SET
                     url = EXCLUDED.url,
                     source_id = EXCLUDED.source_id,
@@ -352,7 +358,8 @@ impl DatabaseImporter for PostgresMigrator {
                     etag = EXCLUDED.etag,
                     last_modified = EXCLUDED.last_modified,
                     content_hash = EXCLUDED.content_hash,
-                    document_id = EXCLUDED.document_id",
+                    document_id = EXCLUDED.document_id,
+                    priority = EXCLUDED.priority",
             )
             .bind::<diesel::sql_types::Integer, _>(u.id)
             .bind::<diesel::sql_types::Text, _>(&u.url)
@@ -371,6 +378,7 @@ impl DatabaseImporter for PostgresMigrator {
             .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&u.last_modified)
             .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&u.content_hash)
             .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(&u.document_id)
+            .bind::<diesel::sql_types::Integer, _>(u.priority)
             .execute(&mut conn)
             .await?;
             count += 1;
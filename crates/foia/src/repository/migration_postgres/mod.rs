@@ -258,7 +258,9 @@ impl PostgresMigrator {
                 date_source TEXT,
                 manual_date TEXT,
                 discovery_method TEXT NOT NULL DEFAULT 'seed',
-                category_id TEXT
+                category_id TEXT,
+                bates_begin TEXT,
+                bates_end TEXT
             )"#,
             r#"CREATE TABLE IF NOT EXISTS document_versions (
                 id SERIAL PRIMARY KEY,
@@ -284,7 +286,8 @@ impl PostgresMigrator {
                 final_text TEXT,
                 ocr_status TEXT NOT NULL DEFAULT 'pending',
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                bates_number TEXT
             )"#,
             r#"CREATE TABLE IF NOT EXISTS virtual_files (
                 id TEXT PRIMARY KEY,
@@ -319,6 +322,9 @@ impl PostgresMigrator {
                 last_modified TEXT,
                 content_hash TEXT,
                 document_id TEXT,
+                priority INTEGER NOT NULL DEFAULT 0,
+                claimed_by TEXT,
+                lease_expires_at TEXT,
                 UNIQUE(source_id, url)
             )"#,
             r#"CREATE TABLE IF NOT EXISTS crawl_requests (
@@ -376,6 +382,8 @@ impl PostgresMigrator {
             "CREATE INDEX IF NOT EXISTS idx_documents_url ON documents(source_url)",
             "CREATE INDEX IF NOT EXISTS idx_document_versions_doc ON document_versions(document_id)",
             "CREATE INDEX IF NOT EXISTS idx_crawl_urls_source_status ON crawl_urls(source_id, status)",
+            "CREATE INDEX IF NOT EXISTS idx_crawl_urls_frontier ON crawl_urls(source_id, status, priority)",
+            "CREATE INDEX IF NOT EXISTS idx_crawl_urls_lease ON crawl_urls(status, lease_expires_at)",
             "CREATE INDEX IF NOT EXISTS idx_crawl_requests_source ON crawl_requests(source_id, request_at)",
             "CREATE INDEX IF NOT EXISTS idx_page_ocr_results_page ON page_ocr_results(page_id)",
             "CREATE INDEX IF NOT EXISTS idx_page_ocr_results_backend ON page_ocr_results(backend)",
@@ -197,6 +197,8 @@ impl DatabaseImporter for SqliteMigrator {
                     documents::manual_date.eq(&d.manual_date),
                     documents::discovery_method.eq(&d.discovery_method),
                     documents::category_id.eq(&d.category_id),
+                    documents::bates_begin.eq(&d.bates_begin),
+                    documents::bates_end.eq(&d.bates_end),
                 ))
                 .execute(&mut conn)
                 .await?;
@@ -265,6 +267,7 @@ impl DatabaseImporter for SqliteMigrator {
                     document_pages::ocr_status.eq(&p.ocr_status),
                     document_pages::created_at.eq(&p.created_at),
                     document_pages::updated_at.eq(&p.updated_at),
+                    document_pages::bates_number.eq(&p.bates_number),
                 ))
                 .execute(&mut conn)
                 .await?;
@@ -341,6 +344,7 @@ impl DatabaseImporter for SqliteMigrator {
                     crawl_urls::last_modified.eq(&u.last_modified),
                     crawl_urls::content_hash.eq(&u.content_hash),
                     crawl_urls::document_id.eq(&u.document_id),
+                    crawl_urls::priority.eq(u.priority),
                 ))
                 .execute(&mut conn)
                 .await?;
@@ -16,6 +16,7 @@ pub mod source;
 pub mod diesel_config_history;
 pub mod diesel_crawl;
 pub mod diesel_document;
+pub mod diesel_foia_request;
 pub mod diesel_scraper_config;
 
 // Keep these until fully migrated
@@ -53,6 +54,7 @@ pub use source::SourceRepository;
 pub use diesel_config_history::DieselConfigHistoryRepository;
 pub use diesel_crawl::DieselCrawlRepository;
 pub use diesel_document::DieselDocumentRepository;
+pub use diesel_foia_request::DieselFoiaRequestRepository;
 pub use diesel_scraper_config::DieselScraperConfigRepository;
 #[allow(unused_imports)]
 pub use diesel_service_status::DieselServiceStatusRepository;
@@ -89,6 +91,7 @@ pub struct Repositories {
     pub config_history: DieselConfigHistoryRepository,
     pub scraper_configs: DieselScraperConfigRepository,
     pub service_status: DieselServiceStatusRepository,
+    pub foia_requests: DieselFoiaRequestRepository,
     pool: DbPool,
 }
 
@@ -101,6 +104,7 @@ impl Repositories {
             config_history: ctx.config_history(),
             scraper_configs: ctx.scraper_configs(),
             service_status: ctx.service_status(),
+            foia_requests: ctx.foia_requests(),
             pool: ctx.pool().clone(),
         }
     }
@@ -61,6 +61,9 @@ pub struct CrawlUrlRecord {
     pub last_modified: Option<String>,
     pub content_hash: Option<String>,
     pub document_id: Option<String>,
+    pub priority: i32,
+    pub claimed_by: Option<String>,
+    pub lease_expires_at: Option<String>,
 }
 
 /// New crawl URL for insertion.
@@ -83,6 +86,9 @@ pub struct NewCrawlUrl<'a> {
     pub last_modified: Option<&'a str>,
     pub content_hash: Option<&'a str>,
     pub document_id: Option<&'a str>,
+    pub priority: i32,
+    pub claimed_by: Option<&'a str>,
+    pub lease_expires_at: Option<&'a str>,
 }
 
 // =============================================================================
@@ -142,6 +148,24 @@ pub struct CrawlConfigRecord {
     pub updated_at: String,
 }
 
+// =============================================================================
+// Crawl Listings
+// =============================================================================
+
+/// Listing page fingerprint record from the database.
+#[derive(Queryable, Selectable, Identifiable, Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = schema::crawl_listings)]
+#[diesel(primary_key(source_id, url))]
+pub struct CrawlListingRecord {
+    pub source_id: String,
+    pub url: String,
+    pub fingerprint: String,
+    pub link_count: i32,
+    pub checked_at: String,
+    pub changed_at: String,
+    pub walked_at: String,
+}
+
 // =============================================================================
 // Documents
 // =============================================================================
@@ -167,6 +191,8 @@ pub struct DocumentRecord {
     pub manual_date: Option<String>,
     pub discovery_method: String,
     pub category_id: Option<String>,
+    pub bates_begin: Option<String>,
+    pub bates_end: Option<String>,
 }
 
 /// New document for insertion.
@@ -190,6 +216,8 @@ pub struct NewDocument<'a> {
     pub manual_date: Option<&'a str>,
     pub discovery_method: &'a str,
     pub category_id: Option<&'a str>,
+    pub bates_begin: Option<&'a str>,
+    pub bates_end: Option<&'a str>,
 }
 
 // =============================================================================
@@ -255,6 +283,7 @@ pub struct DocumentPageRecord {
     pub ocr_status: String,
     pub created_at: String,
     pub updated_at: String,
+    pub bates_number: Option<String>,
 }
 
 /// New document page for insertion.
@@ -270,6 +299,7 @@ pub struct NewDocumentPage<'a> {
     pub ocr_status: &'a str,
     pub created_at: &'a str,
     pub updated_at: &'a str,
+    pub bates_number: Option<&'a str>,
 }
 
 // =============================================================================
@@ -533,3 +563,168 @@ pub struct NewDocumentAnalysisResult<'a> {
     pub metadata: Option<&'a str>,
     pub model: Option<&'a str>,
 }
+
+// =============================================================================
+// Tombstones and Legal Holds
+// =============================================================================
+
+/// Tombstone record for a deleted document.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone)]
+#[diesel(table_name = schema::document_tombstones)]
+pub struct DocumentTombstoneRecord {
+    pub id: i32,
+    pub document_id: String,
+    pub source_id: String,
+    pub source_url: String,
+    pub content_hash: Option<String>,
+    pub reason: Option<String>,
+    pub deleted_at: String,
+}
+
+/// New tombstone for insertion.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = schema::document_tombstones)]
+pub struct NewDocumentTombstone<'a> {
+    pub document_id: &'a str,
+    pub source_id: &'a str,
+    pub source_url: &'a str,
+    pub content_hash: Option<&'a str>,
+    pub reason: Option<&'a str>,
+    pub deleted_at: &'a str,
+}
+
+/// Legal hold record from the database.
+#[derive(Queryable, Selectable, Identifiable, Insertable, Debug, Clone)]
+#[diesel(table_name = schema::document_legal_holds)]
+#[diesel(primary_key(document_id))]
+pub struct DocumentLegalHoldRecord {
+    pub document_id: String,
+    pub reason: Option<String>,
+    pub placed_at: String,
+}
+
+// =============================================================================
+// PII Spans
+// =============================================================================
+
+/// Detected PII span record from the database.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone)]
+#[diesel(table_name = schema::document_pii_spans)]
+pub struct DocumentPiiSpanRecord {
+    pub id: i32,
+    pub document_id: String,
+    pub page_id: i32,
+    pub page_number: i32,
+    pub pii_type: String,
+    pub start_offset: i32,
+    pub end_offset: i32,
+    pub matched_text: String,
+    pub created_at: String,
+}
+
+/// New PII span for insertion.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = schema::document_pii_spans)]
+pub struct NewDocumentPiiSpan<'a> {
+    pub document_id: &'a str,
+    pub page_id: i32,
+    pub page_number: i32,
+    pub pii_type: &'a str,
+    pub start_offset: i32,
+    pub end_offset: i32,
+    pub matched_text: &'a str,
+    pub created_at: &'a str,
+}
+
+// =============================================================================
+// Email Threads
+// =============================================================================
+
+/// Threaded email message record from the database.
+#[derive(Queryable, Selectable, Identifiable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = schema::email_messages)]
+#[diesel(primary_key(document_id))]
+pub struct EmailMessageRecord {
+    pub document_id: String,
+    pub thread_id: String,
+    pub parent_document_id: Option<String>,
+    pub message_id: Option<String>,
+    pub subject: Option<String>,
+    pub sender: Option<String>,
+    pub sent_at: Option<String>,
+    pub depth: i32,
+    pub updated_at: String,
+}
+
+/// New sender -> recipient edge for insertion.
+#[derive(Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = schema::email_correspondence)]
+pub struct NewEmailCorrespondence {
+    pub document_id: String,
+    pub sender: String,
+    pub recipient: String,
+    pub kind: String,
+    pub sent_at: Option<String>,
+}
+
+// =============================================================================
+// FOIA Requests
+// =============================================================================
+
+/// Tracked FOIA request record from the database.
+#[derive(Queryable, Selectable, Identifiable, Insertable, AsChangeset, Debug, Clone, PartialEq)]
+#[diesel(table_name = schema::foia_requests)]
+#[diesel(treat_none_as_null = true)]
+pub struct FoiaRequestRecord {
+    pub id: String,
+    pub agency: String,
+    pub tracking_number: Option<String>,
+    pub subject: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub filed_at: Option<String>,
+    pub deadline_rule: String,
+    pub response_days: i32,
+    pub extension_days: i32,
+    pub closed_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request history entry from the database.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq)]
+#[diesel(table_name = schema::foia_request_events)]
+pub struct FoiaRequestEventRecord {
+    pub id: i32,
+    pub request_id: String,
+    pub kind: String,
+    pub status: Option<String>,
+    pub summary: Option<String>,
+    pub body: Option<String>,
+    pub document_id: Option<String>,
+    pub occurred_at: String,
+    pub created_at: String,
+}
+
+/// New request history entry for insertion.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = schema::foia_request_events)]
+pub struct NewFoiaRequestEvent<'a> {
+    pub request_id: &'a str,
+    pub kind: &'a str,
+    pub status: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub body: Option<&'a str>,
+    pub document_id: Option<&'a str>,
+    pub occurred_at: &'a str,
+    pub created_at: &'a str,
+}
+
+/// Link between a request and a document released in response to it.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = schema::foia_request_documents)]
+pub struct NewFoiaRequestDocument<'a> {
+    pub request_id: &'a str,
+    pub document_id: &'a str,
+    pub linked_at: &'a str,
+}
@@ -19,6 +19,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    crawl_listings (source_id, url) {
+        source_id -> Text,
+        url -> Text,
+        fingerprint -> Text,
+        link_count -> Integer,
+        checked_at -> Text,
+        changed_at -> Text,
+        walked_at -> Text,
+    }
+}
+
 diesel::table! {
     crawl_requests (id) {
         id -> Integer,
@@ -38,6 +50,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    crawl_sitemap_entries (source_id, url) {
+        source_id -> Text,
+        url -> Text,
+        sitemap_url -> Text,
+        lastmod -> Nullable<Text>,
+        first_seen_at -> Text,
+        changed_at -> Text,
+    }
+}
+
 diesel::table! {
     crawl_urls (id) {
         id -> Integer,
@@ -57,6 +80,9 @@ diesel::table! {
         last_modified -> Nullable<Text>,
         content_hash -> Nullable<Text>,
         document_id -> Nullable<Text>,
+        priority -> Integer,
+        claimed_by -> Nullable<Text>,
+        lease_expires_at -> Nullable<Text>,
     }
 }
 
@@ -104,6 +130,7 @@ diesel::table! {
         ocr_status -> Text,
         created_at -> Text,
         updated_at -> Text,
+        bates_number -> Nullable<Text>,
     }
 }
 
@@ -194,6 +221,107 @@ diesel::table! {
         manual_date -> Nullable<Text>,
         discovery_method -> Text,
         category_id -> Nullable<Text>,
+        bates_begin -> Nullable<Text>,
+        bates_end -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    document_tombstones (id) {
+        id -> Integer,
+        document_id -> Text,
+        source_id -> Text,
+        source_url -> Text,
+        content_hash -> Nullable<Text>,
+        reason -> Nullable<Text>,
+        deleted_at -> Text,
+    }
+}
+
+diesel::table! {
+    document_legal_holds (document_id) {
+        document_id -> Text,
+        reason -> Nullable<Text>,
+        placed_at -> Text,
+    }
+}
+
+diesel::table! {
+    document_pii_spans (id) {
+        id -> Integer,
+        document_id -> Text,
+        page_id -> Integer,
+        page_number -> Integer,
+        pii_type -> Text,
+        start_offset -> Integer,
+        end_offset -> Integer,
+        matched_text -> Text,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    email_correspondence (id) {
+        id -> Integer,
+        document_id -> Text,
+        sender -> Text,
+        recipient -> Text,
+        kind -> Text,
+        sent_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    email_messages (document_id) {
+        document_id -> Text,
+        thread_id -> Text,
+        parent_document_id -> Nullable<Text>,
+        message_id -> Nullable<Text>,
+        subject -> Nullable<Text>,
+        sender -> Nullable<Text>,
+        sent_at -> Nullable<Text>,
+        depth -> Integer,
+        updated_at -> Text,
+    }
+}
+
+diesel::table! {
+    foia_request_documents (request_id, document_id) {
+        request_id -> Text,
+        document_id -> Text,
+        linked_at -> Text,
+    }
+}
+
+diesel::table! {
+    foia_request_events (id) {
+        id -> Integer,
+        request_id -> Text,
+        kind -> Text,
+        status -> Nullable<Text>,
+        summary -> Nullable<Text>,
+        body -> Nullable<Text>,
+        document_id -> Nullable<Text>,
+        occurred_at -> Text,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    foia_requests (id) {
+        id -> Text,
+        agency -> Text,
+        tracking_number -> Nullable<Text>,
+        subject -> Text,
+        description -> Nullable<Text>,
+        status -> Text,
+        filed_at -> Nullable<Text>,
+        deadline_rule -> Text,
+        response_days -> Integer,
+        extension_days -> Integer,
+        closed_at -> Nullable<Text>,
+        created_at -> Text,
+        updated_at -> Text,
     }
 }
 
@@ -268,9 +396,15 @@ diesel::table! {
 
 diesel::joinable!(document_entities -> documents (document_id));
 diesel::joinable!(document_pages -> documents (document_id));
+diesel::joinable!(document_pii_spans -> documents (document_id));
 diesel::joinable!(document_versions -> documents (document_id));
 diesel::joinable!(document_versions -> archive_snapshots (archive_snapshot_id));
 diesel::joinable!(documents -> sources (source_id));
+diesel::joinable!(email_correspondence -> documents (document_id));
+diesel::joinable!(email_messages -> documents (document_id));
+diesel::joinable!(foia_request_documents -> documents (document_id));
+diesel::joinable!(foia_request_documents -> foia_requests (request_id));
+diesel::joinable!(foia_request_events -> foia_requests (request_id));
 diesel::joinable!(virtual_files -> documents (document_id));
 diesel::joinable!(page_ocr_results -> document_pages (page_id));
 
@@ -285,13 +419,23 @@ diesel::allow_tables_to_appear_in_same_query!(
     archive_snapshots,
     configuration_history,
     crawl_config,
+    crawl_listings,
     crawl_requests,
+    crawl_sitemap_entries,
     crawl_urls,
     document_analysis_results,
     document_entities,
+    document_legal_holds,
     document_pages,
+    document_pii_spans,
+    document_tombstones,
     document_versions,
     documents,
+    email_correspondence,
+    email_messages,
+    foia_request_documents,
+    foia_request_events,
+    foia_requests,
     page_ocr_results,
     rate_limit_state,
     scraper_configs,
@@ -0,0 +1,311 @@
+//! BagIt packaging (RFC 8493).
+//!
+//! Bags are written either as a plain directory or, when the output path ends
+//! in `.zip`, as a zip holding a single top-level directory named after the
+//! file, the serialization the spec recommends for transfer. Payload files are
+//! listed in `manifest-sha256.txt`; every tag file, including that manifest,
+//! is listed in `tagmanifest-sha256.txt`, so signing the tag manifest covers
+//! the whole bag.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Name of the tag manifest, the file that gets signed.
+pub const TAG_MANIFEST: &str = "tagmanifest-sha256.txt";
+
+/// How the tag manifest is signed when a bag is finished.
+pub enum BagSignature<'a> {
+    /// Leave the bag unsigned.
+    None,
+    /// Detached, ASCII-armored `gpg` signature, using the default key unless
+    /// one is named.
+    Gpg { key: Option<&'a str> },
+}
+
+/// Totals for a finished bag.
+#[derive(Debug, Clone)]
+pub struct BagSummary {
+    /// Number of payload files.
+    pub files: usize,
+    /// Total payload bytes.
+    pub bytes: u64,
+    /// Name of the signature file, if the bag was signed.
+    pub signature: Option<String>,
+}
+
+enum BagTarget {
+    Dir(PathBuf),
+    Zip { zip: ZipWriter<File>, root: String },
+}
+
+/// Writes a BagIt bag file by file.
+pub struct BagWriter {
+    target: BagTarget,
+    manifest: Vec<(String, String)>,
+    tag_manifest: Vec<(String, String)>,
+    bytes: u64,
+}
+
+impl BagWriter {
+    /// Start a bag at `path`.
+    ///
+    /// A directory bag refuses to write into an existing non-empty directory.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let is_zip = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+
+        let target = if is_zip {
+            let root = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("bag")
+                .to_string();
+            BagTarget::Zip {
+                zip: ZipWriter::new(File::create(path)?),
+                root,
+            }
+        } else {
+            if path.exists() && fs::read_dir(path)?.next().is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} already exists and is not empty", path.display()),
+                ));
+            }
+            fs::create_dir_all(path)?;
+            BagTarget::Dir(path.to_path_buf())
+        };
+
+        Ok(Self {
+            target,
+            manifest: Vec::new(),
+            tag_manifest: Vec::new(),
+            bytes: 0,
+        })
+    }
+
+    /// Add a payload file at `data/{path}`, returning its SHA-256.
+    pub fn add_payload(&mut self, path: &str, content: &[u8]) -> io::Result<String> {
+        let path = format!("data/{}", path);
+        let digest = sha256_hex(content);
+        self.write_file(&path, content)?;
+        self.manifest.push((digest.clone(), path));
+        self.bytes += content.len() as u64;
+        Ok(digest)
+    }
+
+    /// Add a tag file at the top level of the bag.
+    pub fn add_tag_file(&mut self, name: &str, content: &[u8]) -> io::Result<()> {
+        self.write_file(name, content)?;
+        self.tag_manifest
+            .push((sha256_hex(content), name.to_string()));
+        Ok(())
+    }
+
+    /// Write the declaration, `bag-info.txt` and manifests, then sign.
+    ///
+    /// `Payload-Oxum` is appended to `bag_info` automatically.
+    pub fn finish(
+        mut self,
+        bag_info: &[(&str, String)],
+        signature: BagSignature<'_>,
+    ) -> io::Result<BagSummary> {
+        let files = self.manifest.len();
+
+        self.add_tag_file(
+            "bagit.txt",
+            b"BagIt-Version: 1.0\nTag-File-Character-Encoding: UTF-8\n",
+        )?;
+
+        let mut info = String::new();
+        for (name, value) in bag_info {
+            info.push_str(&format!("{}: {}\n", name, value));
+        }
+        info.push_str(&format!("Payload-Oxum: {}.{}\n", self.bytes, files));
+        self.add_tag_file("bag-info.txt", info.as_bytes())?;
+
+        let manifest = manifest_text(&self.manifest);
+        self.add_tag_file("manifest-sha256.txt", manifest.as_bytes())?;
+
+        let tag_manifest = manifest_text(&self.tag_manifest);
+        self.write_file(TAG_MANIFEST, tag_manifest.as_bytes())?;
+
+        let signature = match signature {
+            BagSignature::None => None,
+            BagSignature::Gpg { key } => {
+                let name = format!("{}.asc", TAG_MANIFEST);
+                let signed = gpg_detach_sign(tag_manifest.as_bytes(), key)?;
+                self.write_file(&name, &signed)?;
+                Some(name)
+            }
+        };
+
+        if let BagTarget::Zip { zip, .. } = self.target {
+            zip.finish()?;
+        }
+
+        Ok(BagSummary {
+            files,
+            bytes: self.bytes,
+            signature,
+        })
+    }
+
+    fn write_file(&mut self, path: &str, content: &[u8]) -> io::Result<()> {
+        match &mut self.target {
+            BagTarget::Dir(root) => {
+                let dest = root.join(path);
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(dest, content)
+            }
+            BagTarget::Zip { zip, root } => {
+                let options = SimpleFileOptions::default()
+                    .large_file(content.len() as u64 >= u32::MAX as u64);
+                zip.start_file(format!("{}/{}", root, path), options)?;
+                zip.write_all(content)
+            }
+        }
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Render manifest lines, escaping characters BagIt reserves in paths.
+fn manifest_text(entries: &[(String, String)]) -> String {
+    entries
+        .iter()
+        .map(|(digest, path)| {
+            let path = path
+                .replace('%', "%25")
+                .replace('\r', "%0D")
+                .replace('\n', "%0A");
+            format!("{}  {}\n", digest, path)
+        })
+        .collect()
+}
+
+/// Check whether `gpg` can be run.
+pub fn gpg_available() -> bool {
+    Command::new("gpg")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Produce a detached, ASCII-armored signature of `data` with `gpg`.
+pub fn gpg_detach_sign(data: &[u8], key: Option<&str>) -> io::Result<Vec<u8>> {
+    let mut cmd = Command::new("gpg");
+    cmd.args(["--batch", "--armor", "--detach-sign"]);
+    if let Some(key) = key {
+        cmd.args(["--local-user", key]);
+    }
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(data)?;
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "gpg signing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn test_directory_bag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bag");
+
+        let mut bag = BagWriter::create(&path).unwrap();
+        let digest = bag.add_payload("doc1/v1/report.pdf", b"%PDF-1.4").unwrap();
+        bag.add_payload("doc1/v1/extracted.txt", b"text").unwrap();
+        bag.add_tag_file("provenance.json", b"{}").unwrap();
+        let summary = bag
+            .finish(
+                &[("Bag-Software-Agent", "foia".to_string())],
+                BagSignature::None,
+            )
+            .unwrap();
+
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.bytes, 12);
+        assert!(summary.signature.is_none());
+        assert_eq!(digest, sha256_hex(b"%PDF-1.4"));
+
+        assert_eq!(
+            fs::read(path.join("data/doc1/v1/report.pdf")).unwrap(),
+            b"%PDF-1.4"
+        );
+        let info = fs::read_to_string(path.join("bag-info.txt")).unwrap();
+        assert!(info.contains("Payload-Oxum: 12.2\n"));
+        let manifest = fs::read_to_string(path.join("manifest-sha256.txt")).unwrap();
+        assert!(manifest.contains(&format!("{}  data/doc1/v1/report.pdf\n", digest)));
+
+        let tag_manifest = fs::read_to_string(path.join(TAG_MANIFEST)).unwrap();
+        for name in [
+            "provenance.json",
+            "bagit.txt",
+            "bag-info.txt",
+            "manifest-sha256.txt",
+        ] {
+            let content = fs::read(path.join(name)).unwrap();
+            assert!(tag_manifest.contains(&format!("{}  {}\n", sha256_hex(&content), name)));
+        }
+
+        // Refuses to write over an existing bag
+        assert!(BagWriter::create(&path).is_err());
+    }
+
+    #[test]
+    fn test_zip_bag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evidence.zip");
+
+        let mut bag = BagWriter::create(&path).unwrap();
+        bag.add_payload("a.txt", b"hello").unwrap();
+        bag.finish(&[], BagSignature::None).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("evidence/data/a.txt")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "hello");
+        assert!(archive.by_name("evidence/tagmanifest-sha256.txt").is_ok());
+    }
+
+    #[test]
+    fn test_manifest_escapes_paths() {
+        let text = manifest_text(&[("abc".to_string(), "data/100%\nfile".to_string())]);
+        assert_eq!(text, "abc  data/100%25%0Afile\n");
+    }
+}
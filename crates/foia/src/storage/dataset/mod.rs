@@ -0,0 +1,414 @@
+//! Flat dataset exports for analysis tools.
+//!
+//! Copies documents, versions, pages, entities and analysis results into
+//! either one Parquet file per table or a standalone SQLite database. Rows
+//! are written in batches as they are read, so exports of any size run in
+//! constant memory. Columns mirror the database tables; timestamps stay as
+//! the RFC 3339 strings they are stored as.
+
+mod parquet_file;
+mod sqlite_file;
+
+pub use parquet_file::ParquetDatasetWriter;
+pub use sqlite_file::SqliteDatasetWriter;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::repository::models::{
+    DocumentAnalysisResultRecord, DocumentEntityRecord, DocumentPageRecord, DocumentRecord,
+    DocumentVersionRecord,
+};
+
+/// Type of a dataset column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Real,
+}
+
+/// A column in an exported table.
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub name: &'static str,
+    pub kind: ColumnType,
+    pub nullable: bool,
+}
+
+const fn column(name: &'static str, kind: ColumnType, nullable: bool) -> Column {
+    Column {
+        name,
+        kind,
+        nullable,
+    }
+}
+
+use ColumnType::{Integer, Real, Text};
+
+const DOCUMENT_COLUMNS: &[Column] = &[
+    column("id", Text, false),
+    column("source_id", Text, false),
+    column("title", Text, false),
+    column("source_url", Text, false),
+    column("extracted_text", Text, true),
+    column("status", Text, false),
+    column("metadata", Text, false),
+    column("created_at", Text, false),
+    column("updated_at", Text, false),
+    column("synopsis", Text, true),
+    column("tags", Text, true),
+    column("estimated_date", Text, true),
+    column("date_confidence", Text, true),
+    column("date_source", Text, true),
+    column("manual_date", Text, true),
+    column("discovery_method", Text, false),
+    column("category_id", Text, true),
+    column("bates_begin", Text, true),
+    column("bates_end", Text, true),
+];
+
+const VERSION_COLUMNS: &[Column] = &[
+    column("id", Integer, false),
+    column("document_id", Text, false),
+    column("content_hash", Text, false),
+    column("content_hash_blake3", Text, true),
+    column("file_path", Text, true),
+    column("file_size", Integer, false),
+    column("mime_type", Text, false),
+    column("acquired_at", Text, false),
+    column("source_url", Text, true),
+    column("original_filename", Text, true),
+    column("server_date", Text, true),
+    column("page_count", Integer, true),
+    column("archive_snapshot_id", Integer, true),
+    column("earliest_archived_at", Text, true),
+    column("dedup_index", Integer, true),
+];
+
+const PAGE_COLUMNS: &[Column] = &[
+    column("id", Integer, false),
+    column("document_id", Text, false),
+    column("version_id", Integer, false),
+    column("page_number", Integer, false),
+    column("pdf_text", Text, true),
+    column("ocr_text", Text, true),
+    column("final_text", Text, true),
+    column("ocr_status", Text, false),
+    column("created_at", Text, false),
+    column("updated_at", Text, false),
+    column("bates_number", Text, true),
+];
+
+const ENTITY_COLUMNS: &[Column] = &[
+    column("id", Integer, false),
+    column("document_id", Text, false),
+    column("entity_type", Text, false),
+    column("entity_text", Text, false),
+    column("normalized_text", Text, false),
+    column("latitude", Real, true),
+    column("longitude", Real, true),
+    column("created_at", Text, false),
+];
+
+const ANALYSIS_COLUMNS: &[Column] = &[
+    column("id", Integer, false),
+    column("page_id", Integer, true),
+    column("document_id", Text, false),
+    column("version_id", Integer, false),
+    column("analysis_type", Text, false),
+    column("backend", Text, false),
+    column("result_text", Text, true),
+    column("confidence", Real, true),
+    column("processing_time_ms", Integer, true),
+    column("error", Text, true),
+    column("status", Text, false),
+    column("created_at", Text, false),
+    column("metadata", Text, true),
+    column("model", Text, true),
+];
+
+/// A table that can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetTable {
+    Documents,
+    Versions,
+    Pages,
+    Entities,
+    AnalysisResults,
+}
+
+impl DatasetTable {
+    /// All tables, in export order.
+    pub const ALL: [DatasetTable; 5] = [
+        DatasetTable::Documents,
+        DatasetTable::Versions,
+        DatasetTable::Pages,
+        DatasetTable::Entities,
+        DatasetTable::AnalysisResults,
+    ];
+
+    /// Table name in the exported dataset.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DatasetTable::Documents => "documents",
+            DatasetTable::Versions => "versions",
+            DatasetTable::Pages => "pages",
+            DatasetTable::Entities => "entities",
+            DatasetTable::AnalysisResults => "analysis_results",
+        }
+    }
+
+    /// Columns of the table, primary key first.
+    pub fn columns(&self) -> &'static [Column] {
+        match self {
+            DatasetTable::Documents => DOCUMENT_COLUMNS,
+            DatasetTable::Versions => VERSION_COLUMNS,
+            DatasetTable::Pages => PAGE_COLUMNS,
+            DatasetTable::Entities => ENTITY_COLUMNS,
+            DatasetTable::AnalysisResults => ANALYSIS_COLUMNS,
+        }
+    }
+}
+
+impl fmt::Display for DatasetTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DatasetTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DatasetTable::ALL
+            .into_iter()
+            .find(|table| table.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = DatasetTable::ALL.iter().map(|t| t.name()).collect();
+                format!("unknown table '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+/// A single cell value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Text(String),
+    Integer(i64),
+    Real(f64),
+}
+
+/// A row of values, in the order of [`DatasetTable::columns`].
+pub type Row = Vec<Value>;
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Integer(value.into())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Real(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Real(value.into())
+    }
+}
+
+/// Drop bulk text when the export excludes it.
+fn text(value: Option<String>, include_text: bool) -> Value {
+    if include_text {
+        value.into()
+    } else {
+        Value::Null
+    }
+}
+
+/// Convert a document record to a row.
+///
+/// Without `include_text`, `extracted_text` is left null.
+pub fn document_row(rec: DocumentRecord, include_text: bool) -> Row {
+    vec![
+        rec.id.into(),
+        rec.source_id.into(),
+        rec.title.into(),
+        rec.source_url.into(),
+        text(rec.extracted_text, include_text),
+        rec.status.into(),
+        rec.metadata.into(),
+        rec.created_at.into(),
+        rec.updated_at.into(),
+        rec.synopsis.into(),
+        rec.tags.into(),
+        rec.estimated_date.into(),
+        rec.date_confidence.into(),
+        rec.date_source.into(),
+        rec.manual_date.into(),
+        rec.discovery_method.into(),
+        rec.category_id.into(),
+        rec.bates_begin.into(),
+        rec.bates_end.into(),
+    ]
+}
+
+/// Convert a version record to a row.
+pub fn version_row(rec: DocumentVersionRecord) -> Row {
+    vec![
+        rec.id.into(),
+        rec.document_id.into(),
+        rec.content_hash.into(),
+        rec.content_hash_blake3.into(),
+        rec.file_path.into(),
+        rec.file_size.into(),
+        rec.mime_type.into(),
+        rec.acquired_at.into(),
+        rec.source_url.into(),
+        rec.original_filename.into(),
+        rec.server_date.into(),
+        rec.page_count.into(),
+        rec.archive_snapshot_id.into(),
+        rec.earliest_archived_at.into(),
+        rec.dedup_index.into(),
+    ]
+}
+
+/// Convert a page record to a row.
+///
+/// Without `include_text`, the three text columns are left null.
+pub fn page_row(rec: DocumentPageRecord, include_text: bool) -> Row {
+    vec![
+        rec.id.into(),
+        rec.document_id.into(),
+        rec.version_id.into(),
+        rec.page_number.into(),
+        text(rec.pdf_text, include_text),
+        text(rec.ocr_text, include_text),
+        text(rec.final_text, include_text),
+        rec.ocr_status.into(),
+        rec.created_at.into(),
+        rec.updated_at.into(),
+        rec.bates_number.into(),
+    ]
+}
+
+/// Convert an entity record to a row.
+pub fn entity_row(rec: DocumentEntityRecord) -> Row {
+    vec![
+        rec.id.into(),
+        rec.document_id.into(),
+        rec.entity_type.into(),
+        rec.entity_text.into(),
+        rec.normalized_text.into(),
+        rec.latitude.into(),
+        rec.longitude.into(),
+        rec.created_at.into(),
+    ]
+}
+
+/// Convert an analysis result record to a row.
+///
+/// Without `include_text`, `result_text` is left null.
+pub fn analysis_row(rec: DocumentAnalysisResultRecord, include_text: bool) -> Row {
+    vec![
+        rec.id.into(),
+        rec.page_id.into(),
+        rec.document_id.into(),
+        rec.version_id.into(),
+        rec.analysis_type.into(),
+        rec.backend.into(),
+        text(rec.result_text, include_text),
+        rec.confidence.into(),
+        rec.processing_time_ms.into(),
+        rec.error.into(),
+        rec.status.into(),
+        rec.created_at.into(),
+        rec.metadata.into(),
+        rec.model.into(),
+    ]
+}
+
+/// Destination for exported tables.
+///
+/// Tables are written one at a time: [`start_table`], any number of
+/// [`write_rows`] calls, then the next table or [`finish`].
+///
+/// [`start_table`]: DatasetWriter::start_table
+/// [`write_rows`]: DatasetWriter::write_rows
+/// [`finish`]: DatasetWriter::finish
+pub trait DatasetWriter {
+    /// Begin writing `table`, closing the previous one.
+    fn start_table(&mut self, table: DatasetTable) -> anyhow::Result<()>;
+
+    /// Append rows to the current table.
+    fn write_rows(&mut self, rows: &[Row]) -> anyhow::Result<()>;
+
+    /// Close the last table and finalize the output.
+    fn finish(self: Box<Self>) -> anyhow::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_names_round_trip() {
+        for table in DatasetTable::ALL {
+            assert_eq!(table.name().parse::<DatasetTable>().unwrap(), table);
+        }
+        assert!("nope".parse::<DatasetTable>().is_err());
+    }
+
+    #[test]
+    fn test_rows_match_columns() {
+        let page = DocumentPageRecord {
+            id: 1,
+            document_id: "doc".to_string(),
+            version_id: 2,
+            page_number: 1,
+            pdf_text: Some("pdf".to_string()),
+            ocr_text: None,
+            final_text: Some("final".to_string()),
+            ocr_status: "complete".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            bates_number: None,
+        };
+        let row = page_row(page.clone(), true);
+        assert_eq!(row.len(), DatasetTable::Pages.columns().len());
+        assert_eq!(row[6], Value::Text("final".to_string()));
+        assert_eq!(page_row(page, false)[6], Value::Null);
+
+        let entity = DocumentEntityRecord {
+            id: 1,
+            document_id: "doc".to_string(),
+            entity_type: "place".to_string(),
+            entity_text: "Boston".to_string(),
+            normalized_text: "boston".to_string(),
+            latitude: Some(42.36),
+            longitude: Some(-71.06),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        assert_eq!(
+            entity_row(entity).len(),
+            DatasetTable::Entities.columns().len()
+        );
+    }
+}
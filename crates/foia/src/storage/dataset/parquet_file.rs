@@ -0,0 +1,182 @@
+//! One Parquet file per table.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::builder::{Float64Builder, Int64Builder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use super::{Column, ColumnType, DatasetTable, DatasetWriter, Row, Value};
+
+/// Writes `{table}.parquet` files into a directory.
+pub struct ParquetDatasetWriter {
+    dir: PathBuf,
+    current: Option<(&'static [Column], SchemaRef, ArrowWriter<File>)>,
+}
+
+impl ParquetDatasetWriter {
+    /// Write into `dir`, creating it if needed.
+    ///
+    /// Existing files for the exported tables are replaced.
+    pub fn create(dir: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            current: None,
+        })
+    }
+
+    fn close_table(&mut self) -> anyhow::Result<()> {
+        if let Some((_, _, writer)) = self.current.take() {
+            writer.close()?;
+        }
+        Ok(())
+    }
+}
+
+fn arrow_schema(columns: &[Column]) -> SchemaRef {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|col| {
+            let data_type = match col.kind {
+                ColumnType::Text => DataType::Utf8,
+                ColumnType::Integer => DataType::Int64,
+                ColumnType::Real => DataType::Float64,
+            };
+            Field::new(col.name, data_type, col.nullable)
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Build one Arrow array per column from row-oriented values.
+fn record_batch(
+    columns: &[Column],
+    schema: SchemaRef,
+    rows: &[Row],
+) -> anyhow::Result<RecordBatch> {
+    let arrays: Vec<ArrayRef> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| -> ArrayRef {
+            let cells = rows.iter().map(|row| &row[i]);
+            match col.kind {
+                ColumnType::Text => {
+                    let mut builder = StringBuilder::new();
+                    for cell in cells {
+                        match cell {
+                            Value::Text(s) => builder.append_value(s),
+                            _ => builder.append_null(),
+                        }
+                    }
+                    Arc::new(builder.finish())
+                }
+                ColumnType::Integer => {
+                    let mut builder = Int64Builder::with_capacity(rows.len());
+                    for cell in cells {
+                        match cell {
+                            Value::Integer(n) => builder.append_value(*n),
+                            _ => builder.append_null(),
+                        }
+                    }
+                    Arc::new(builder.finish())
+                }
+                ColumnType::Real => {
+                    let mut builder = Float64Builder::with_capacity(rows.len());
+                    for cell in cells {
+                        match cell {
+                            Value::Real(n) => builder.append_value(*n),
+                            _ => builder.append_null(),
+                        }
+                    }
+                    Arc::new(builder.finish())
+                }
+            }
+        })
+        .collect();
+    Ok(RecordBatch::try_new(schema, arrays)?)
+}
+
+impl DatasetWriter for ParquetDatasetWriter {
+    fn start_table(&mut self, table: DatasetTable) -> anyhow::Result<()> {
+        self.close_table()?;
+
+        let columns = table.columns();
+        let schema = arrow_schema(columns);
+        let file = File::create(self.dir.join(format!("{}.parquet", table.name())))?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(props))?;
+        self.current = Some((columns, schema, writer));
+        Ok(())
+    }
+
+    fn write_rows(&mut self, rows: &[Row]) -> anyhow::Result<()> {
+        let Some((columns, schema, writer)) = self.current.as_mut() else {
+            anyhow::bail!("write_rows called before start_table");
+        };
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = record_batch(columns, schema.clone(), rows)?;
+        writer.write(&batch)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> anyhow::Result<()> {
+        self.close_table()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    use super::*;
+
+    #[test]
+    fn test_writes_parquet_per_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer: Box<dyn DatasetWriter> =
+            Box::new(ParquetDatasetWriter::create(dir.path()).unwrap());
+
+        writer.start_table(DatasetTable::Entities).unwrap();
+        let row = |id: i64, lat: Option<f64>| -> Row {
+            vec![
+                Value::Integer(id),
+                Value::Text("doc".to_string()),
+                Value::Text("place".to_string()),
+                Value::Text("Boston".to_string()),
+                Value::Text("boston".to_string()),
+                lat.into(),
+                Value::Null,
+                Value::Text("2024-01-01T00:00:00Z".to_string()),
+            ]
+        };
+        writer.write_rows(&[row(1, Some(42.36))]).unwrap();
+        writer.write_rows(&[row(2, None)]).unwrap();
+        writer.start_table(DatasetTable::Pages).unwrap();
+        writer.finish().unwrap();
+
+        let reader =
+            SerializedFileReader::new(File::open(dir.path().join("entities.parquet")).unwrap())
+                .unwrap();
+        let meta = reader.metadata();
+        assert_eq!(meta.file_metadata().num_rows(), 2);
+        assert_eq!(
+            meta.file_metadata().schema_descr().column(5).name(),
+            "latitude"
+        );
+
+        let pages =
+            SerializedFileReader::new(File::open(dir.path().join("pages.parquet")).unwrap())
+                .unwrap();
+        assert_eq!(pages.metadata().file_metadata().num_rows(), 0);
+    }
+}
@@ -0,0 +1,176 @@
+//! Standalone SQLite snapshot.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use rusqlite::{params_from_iter, Connection, ToSql};
+
+use super::{ColumnType, DatasetTable, DatasetWriter, Row, Value};
+
+/// Writes every table into a single new SQLite database.
+///
+/// Each batch is inserted in its own transaction. On finish, indexes are
+/// built on `document_id` columns, the file is compacted and then marked
+/// read-only so the snapshot isn't modified by accident.
+pub struct SqliteDatasetWriter {
+    path: PathBuf,
+    conn: Connection,
+    current: Option<DatasetTable>,
+    tables: Vec<DatasetTable>,
+}
+
+impl ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(match self {
+            Value::Null => ValueRef::Null,
+            Value::Text(s) => ValueRef::Text(s.as_bytes()),
+            Value::Integer(n) => ValueRef::Integer(*n),
+            Value::Real(n) => ValueRef::Real(*n),
+        }))
+    }
+}
+
+impl SqliteDatasetWriter {
+    /// Create a new database at `path`, which must not already exist.
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            anyhow::bail!("{} already exists", path.display());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        // The snapshot is rebuilt from scratch on failure, so skip the journal.
+        conn.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            conn,
+            current: None,
+            tables: Vec::new(),
+        })
+    }
+}
+
+impl DatasetWriter for SqliteDatasetWriter {
+    fn start_table(&mut self, table: DatasetTable) -> anyhow::Result<()> {
+        let columns: Vec<String> = table
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                let kind = match col.kind {
+                    ColumnType::Text => "TEXT",
+                    ColumnType::Integer => "INTEGER",
+                    ColumnType::Real => "REAL",
+                };
+                let mut def = format!("{} {}", col.name, kind);
+                if i == 0 {
+                    def.push_str(" PRIMARY KEY");
+                } else if !col.nullable {
+                    def.push_str(" NOT NULL");
+                }
+                def
+            })
+            .collect();
+        self.conn.execute_batch(&format!(
+            "CREATE TABLE {} ({});",
+            table.name(),
+            columns.join(", ")
+        ))?;
+        self.current = Some(table);
+        self.tables.push(table);
+        Ok(())
+    }
+
+    fn write_rows(&mut self, rows: &[Row]) -> anyhow::Result<()> {
+        let Some(table) = self.current else {
+            anyhow::bail!("write_rows called before start_table");
+        };
+        let columns = table.columns();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table.name(),
+            columns
+                .iter()
+                .map(|c| c.name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(&sql)?;
+            for row in rows {
+                stmt.execute(params_from_iter(row.iter()))?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> anyhow::Result<()> {
+        let Self {
+            path, conn, tables, ..
+        } = *self;
+        for table in &tables {
+            if table.columns().iter().any(|c| c.name == "document_id") {
+                conn.execute_batch(&format!(
+                    "CREATE INDEX idx_{0}_document_id ON {0} (document_id);",
+                    table.name()
+                ))?;
+            }
+        }
+        conn.execute_batch("VACUUM;")?;
+        conn.close().map_err(|(_, e)| e)?;
+
+        let mut permissions = fs::metadata(&path)?.permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&path, permissions)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_readonly_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.db");
+        let mut writer: Box<dyn DatasetWriter> =
+            Box::new(SqliteDatasetWriter::create(&path).unwrap());
+
+        writer.start_table(DatasetTable::Entities).unwrap();
+        writer
+            .write_rows(&[vec![
+                Value::Integer(7),
+                Value::Text("doc".to_string()),
+                Value::Text("place".to_string()),
+                Value::Text("Boston".to_string()),
+                Value::Text("boston".to_string()),
+                Value::Real(42.36),
+                Value::Null,
+                Value::Text("2024-01-01T00:00:00Z".to_string()),
+            ]])
+            .unwrap();
+        writer.finish().unwrap();
+
+        assert!(fs::metadata(&path).unwrap().permissions().readonly());
+        assert!(SqliteDatasetWriter::create(&path).is_err());
+
+        let conn = Connection::open(&path).unwrap();
+        let (text, lat, lon): (String, f64, Option<f64>) = conn
+            .query_row(
+                "SELECT entity_text, latitude, longitude FROM entities WHERE id = 7",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(text, "Boston");
+        assert_eq!(lat, 42.36);
+        assert_eq!(lon, None);
+    }
+}
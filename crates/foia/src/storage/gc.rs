@@ -0,0 +1,363 @@
+//! Garbage collection for unreferenced content files.
+//!
+//! Deduplication, category remapping and document deletion can leave files
+//! under `documents_dir` that no `document_versions` row points at. Rather
+//! than deleting them outright, GC moves them into a hidden quarantine area,
+//! one timestamped batch directory per run, and only purges a batch once it
+//! has sat there for the configured grace period. A file that becomes
+//! referenced again while quarantined can be moved back into place. Only
+//! files in the content-addressed layout are ever collected.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+/// Quarantine directory name inside `documents_dir`.
+///
+/// Dot-prefixed so [`super::verify::find_orphans`] never reports it.
+pub const QUARANTINE_DIR: &str = ".quarantine";
+
+/// Timestamp format used for quarantine batch directory names.
+const BATCH_NAME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// A quarantine batch directory and its contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantineBatch {
+    /// Absolute path of the batch directory.
+    pub path: PathBuf,
+    /// When the batch was quarantined.
+    pub quarantined_at: DateTime<Utc>,
+    /// Number of files in the batch.
+    pub files: usize,
+    /// Total size of the files in bytes.
+    pub bytes: u64,
+}
+
+/// Path of the quarantine area for a documents directory.
+pub fn quarantine_root(documents_dir: &Path) -> PathBuf {
+    documents_dir.join(QUARANTINE_DIR)
+}
+
+/// Whether `path` is laid out like stored content.
+///
+/// Content lives one level down, in a directory named by the first two to
+/// seven hex digits of its hash, as `{hash[0..8]}.{ext}` or
+/// `{name}-{hash[0..8]}.{ext}`. Anything else under `documents_dir` was
+/// not written by the content store and is left alone.
+pub fn is_content_path(documents_dir: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(documents_dir) else {
+        return false;
+    };
+    let parts: Option<Vec<&str>> = relative.iter().map(|c| c.to_str()).collect();
+    let Some(&[dir, file]) = parts.as_deref() else {
+        return false;
+    };
+    let stem = Path::new(file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file);
+    let is_hex = |s: &str| s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    let short_hash = stem.rsplit('-').next().unwrap_or(stem);
+    (2..8).contains(&dir.len())
+        && is_hex(dir)
+        && short_hash.len() == 8
+        && is_hex(short_hash)
+        && short_hash.starts_with(dir)
+}
+
+/// When a file last changed on disk.
+///
+/// On Unix this is the later of the modification and inode change times.
+/// Hardlinking existing content into place only updates the change time,
+/// so a file linked by an import that is still running counts as new.
+fn last_changed(metadata: &std::fs::Metadata) -> Option<SystemTime> {
+    let modified = metadata.modified().ok();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let changed = u64::try_from(metadata.ctime()).ok().map(|secs| {
+            SystemTime::UNIX_EPOCH + std::time::Duration::new(secs, metadata.ctime_nsec() as u32)
+        });
+        modified.max(changed)
+    }
+    #[cfg(not(unix))]
+    modified
+}
+
+/// Keep only orphans that have not changed on disk for `min_age`.
+///
+/// Downloads and imports write or link content before the version row is
+/// committed, so a recently changed unreferenced file may simply be in
+/// flight.
+pub fn older_than(
+    orphans: Vec<(PathBuf, u64)>,
+    min_age: std::time::Duration,
+    now: SystemTime,
+) -> Vec<(PathBuf, u64)> {
+    orphans
+        .into_iter()
+        .filter(|(path, _)| {
+            std::fs::metadata(path)
+                .ok()
+                .as_ref()
+                .and_then(last_changed)
+                .and_then(|changed| now.duration_since(changed).ok())
+                .is_some_and(|age| age >= min_age)
+        })
+        .collect()
+}
+
+/// Move files into a new quarantine batch, preserving their relative paths.
+///
+/// Returns the batch that was created. Files outside `documents_dir` are
+/// skipped.
+pub fn quarantine_files(
+    documents_dir: &Path,
+    files: &[(PathBuf, u64)],
+    now: DateTime<Utc>,
+) -> std::io::Result<QuarantineBatch> {
+    let batch_path = quarantine_root(documents_dir).join(now.format(BATCH_NAME_FORMAT).to_string());
+    let mut batch = QuarantineBatch {
+        path: batch_path,
+        quarantined_at: now,
+        files: 0,
+        bytes: 0,
+    };
+
+    for (path, size) in files {
+        let Ok(relative) = path.strip_prefix(documents_dir) else {
+            continue;
+        };
+        let dest = batch.path.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(path, &dest)?;
+        batch.files += 1;
+        batch.bytes += size;
+    }
+
+    Ok(batch)
+}
+
+/// List quarantine batches, oldest first.
+///
+/// Directories whose names are not batch timestamps are ignored.
+pub fn list_batches(documents_dir: &Path) -> std::io::Result<Vec<QuarantineBatch>> {
+    let root = quarantine_root(documents_dir);
+    let entries = match std::fs::read_dir(&root) {
+        Ok(e) => e,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut batches = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(naive) = NaiveDateTime::parse_from_str(&name, BATCH_NAME_FORMAT) else {
+            continue;
+        };
+        let (files, bytes) = dir_usage(&entry.path())?;
+        batches.push(QuarantineBatch {
+            path: entry.path(),
+            quarantined_at: Utc.from_utc_datetime(&naive),
+            files,
+            bytes,
+        });
+    }
+
+    batches.sort_by_key(|b| b.quarantined_at);
+    Ok(batches)
+}
+
+/// Return batches that have been quarantined for at least `grace`.
+pub fn expired_batches(
+    documents_dir: &Path,
+    grace: chrono::Duration,
+    now: DateTime<Utc>,
+) -> std::io::Result<Vec<QuarantineBatch>> {
+    Ok(list_batches(documents_dir)?
+        .into_iter()
+        .filter(|b| now - b.quarantined_at >= grace)
+        .collect())
+}
+
+/// Permanently delete a quarantine batch.
+pub fn purge_batch(batch: &QuarantineBatch) -> std::io::Result<()> {
+    std::fs::remove_dir_all(&batch.path)
+}
+
+/// Move quarantined files back if they are referenced again.
+///
+/// A file is restored when its original path is in `referenced` and nothing
+/// currently exists there. Returns the restored paths.
+pub fn restore_referenced(
+    documents_dir: &Path,
+    referenced: &HashSet<PathBuf>,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut restored = Vec::new();
+
+    for batch in list_batches(documents_dir)? {
+        let mut stack = vec![batch.path.clone()];
+        while let Some(dir) = stack.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if entry.file_type()?.is_dir() {
+                    stack.push(path);
+                    continue;
+                }
+                let Ok(relative) = path.strip_prefix(&batch.path) else {
+                    continue;
+                };
+                let original = documents_dir.join(relative);
+                if referenced.contains(&original) && !original.exists() {
+                    if let Some(parent) = original.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::rename(&path, &original)?;
+                    restored.push(original);
+                }
+            }
+        }
+    }
+
+    restored.sort();
+    Ok(restored)
+}
+
+/// Count files and bytes under a directory.
+fn dir_usage(dir: &Path) -> std::io::Result<(usize, u64)> {
+    let mut files = 0;
+    let mut bytes = 0;
+    let mut stack = vec![dir.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                stack.push(entry.path());
+            } else if file_type.is_file() {
+                files += 1;
+                bytes += entry.metadata()?.len();
+            }
+        }
+    }
+
+    Ok((files, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(path: &Path, content: &[u8]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_quarantine_and_expire() {
+        let dir = tempdir().unwrap();
+        let orphan = dir.path().join("ab").join("orphan-abcdef12.pdf");
+        write(&orphan, b"orphaned");
+
+        let quarantined_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let batch = quarantine_files(dir.path(), &[(orphan.clone(), 8)], quarantined_at).unwrap();
+        assert_eq!(batch.files, 1);
+        assert!(!orphan.exists());
+        assert!(batch.path.join("ab").join("orphan-abcdef12.pdf").exists());
+
+        let listed = list_batches(dir.path()).unwrap();
+        assert_eq!(listed, vec![batch.clone()]);
+
+        let grace = chrono::Duration::days(7);
+        let early = quarantined_at + chrono::Duration::days(3);
+        assert!(expired_batches(dir.path(), grace, early)
+            .unwrap()
+            .is_empty());
+
+        let late = quarantined_at + chrono::Duration::days(8);
+        let expired = expired_batches(dir.path(), grace, late).unwrap();
+        assert_eq!(expired.len(), 1);
+        purge_batch(&expired[0]).unwrap();
+        assert!(list_batches(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_referenced() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cd").join("report-cdef1234.pdf");
+        write(&path, b"report");
+
+        quarantine_files(dir.path(), &[(path.clone(), 6)], Utc::now()).unwrap();
+        assert!(!path.exists());
+
+        let referenced: HashSet<PathBuf> = [path.clone()].into_iter().collect();
+        let restored = restore_referenced(dir.path(), &referenced).unwrap();
+        assert_eq!(restored, vec![path.clone()]);
+        assert_eq!(std::fs::read(&path).unwrap(), b"report");
+    }
+
+    #[test]
+    fn test_older_than_skips_recent_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ef").join("fresh-ef012345.pdf");
+        write(&path, b"fresh");
+
+        let orphans = vec![(path.clone(), 5)];
+        let hour = std::time::Duration::from_secs(3600);
+        assert!(older_than(orphans.clone(), hour, SystemTime::now()).is_empty());
+        assert_eq!(
+            older_than(orphans, hour, SystemTime::now() + hour * 2),
+            vec![(path, 5)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_older_than_uses_change_time() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ab").join("linked-abcdef12.pdf");
+        write(&path, b"linked");
+
+        // An old file hardlinked into place keeps its mtime, but its inode
+        // change time is now
+        let day = std::time::Duration::from_secs(86400);
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - day * 30).unwrap();
+
+        let hour = std::time::Duration::from_secs(3600);
+        assert!(older_than(vec![(path, 6)], hour, SystemTime::now()).is_empty());
+    }
+
+    #[test]
+    fn test_is_content_path() {
+        let root = Path::new("/docs");
+        assert!(is_content_path(root, &root.join("ab/report-abcdef12.pdf")));
+        assert!(is_content_path(root, &root.join("ab/abcdef12.pdf")));
+        assert!(is_content_path(
+            root,
+            &root.join("abc/my-file-abcdef12.pdf")
+        ));
+
+        assert!(!is_content_path(root, &root.join("report.pdf")));
+        assert!(!is_content_path(root, &root.join("ab/report.pdf")));
+        assert!(!is_content_path(root, &root.join("cd/report-abcdef12.pdf")));
+        assert!(!is_content_path(
+            root,
+            &root.join("ab/x/report-abcdef12.pdf")
+        ));
+        assert!(!is_content_path(root, &root.join("notes/abcdef12.pdf")));
+        assert!(!is_content_path(root, Path::new("/other/ab/abcdef12.pdf")));
+    }
+}
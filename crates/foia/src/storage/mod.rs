@@ -1,5 +1,13 @@
 //! Storage helpers for document content on disk.
 
+pub mod bagit;
+#[cfg(feature = "dataset")]
+pub mod dataset;
+pub mod gc;
+pub mod verify;
+pub mod wacz;
+pub mod warc;
+
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
@@ -123,19 +131,39 @@ pub fn compute_storage_path_with_dedup(
     (relative, Some(content_hash.len() as u32 - 2))
 }
 
+/// What [`save_document_async`] did with a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveOutcome {
+    /// A new document was created.
+    Created,
+    /// The content was added to (or already stored on) an existing document.
+    Updated,
+    /// The URL or content matches a deleted document, so nothing was saved.
+    Deleted,
+}
+
 /// Save document content to disk and database.
 ///
 /// Uses `DocumentInput` so callers don't need to depend on `ScraperResult`.
 /// New records store `file_path: None` (paths are deterministic).
+/// Content whose URL or hash matches a tombstone is not written at all.
 pub async fn save_document_async(
     doc_repo: &DieselDocumentRepository,
     content: &[u8],
     input: &DocumentInput,
     source_id: &str,
     documents_dir: &Path,
-) -> anyhow::Result<bool> {
+) -> anyhow::Result<SaveOutcome> {
     let content_hash = DocumentVersion::compute_hash(content);
 
+    if doc_repo
+        .find_tombstone(Some(&input.url), Some(&content_hash))
+        .await?
+        .is_some()
+    {
+        return Ok(SaveOutcome::Deleted);
+    }
+
     let (basename, extension) = extract_filename_parts(&input.url, &input.title, &input.mime_type);
 
     // Compute path with collision detection
@@ -165,10 +193,10 @@ pub async fn save_document_async(
     let existing = doc_repo.get_by_url(&input.url).await?;
 
     if let Some(mut doc) = existing.into_iter().next() {
-        if doc.add_version(version) {
-            doc_repo.save_with_versions(&doc).await?;
+        if doc.add_version(version) && !doc_repo.save_with_versions(&doc).await? {
+            return Ok(SaveOutcome::Deleted);
         }
-        Ok(false) // Updated existing
+        Ok(SaveOutcome::Updated)
     } else {
         let doc = Document::new(
             uuid::Uuid::new_v4().to_string(),
@@ -178,8 +206,10 @@ pub async fn save_document_async(
             version,
             input.metadata.clone(),
         );
-        doc_repo.save_with_versions(&doc).await?;
-        Ok(true) // Created new
+        if !doc_repo.save_with_versions(&doc).await? {
+            return Ok(SaveOutcome::Deleted);
+        }
+        Ok(SaveOutcome::Created)
     }
 }
 
@@ -225,6 +255,50 @@ pub fn save_version_content(
     Ok(content_path)
 }
 
+/// Outcome of removing a deleted document's files from disk.
+#[derive(Debug, Default)]
+pub struct RemovedFiles {
+    /// Files deleted from disk.
+    pub removed: Vec<PathBuf>,
+    /// Files kept because other documents still reference the same content,
+    /// with the number of such documents.
+    pub shared: Vec<(PathBuf, u64)>,
+}
+
+/// Remove the stored files of a document that has already been deleted.
+///
+/// Content deduplication means several documents can point at one file, so
+/// a file is only removed when no remaining version has the same hash.
+/// Missing files are ignored.
+pub async fn remove_version_files(
+    doc_repo: &DieselDocumentRepository,
+    documents_dir: &Path,
+    document_id: &str,
+    source_url: &str,
+    title: &str,
+    versions: &[DocumentVersion],
+) -> anyhow::Result<RemovedFiles> {
+    let mut result = RemovedFiles::default();
+
+    for version in versions {
+        let path = version.resolve_path(documents_dir, source_url, title);
+        let shared = doc_repo
+            .count_documents_with_hash(&version.content_hash, document_id)
+            .await?;
+        if shared > 0 {
+            result.shared.push((path, shared));
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => result.removed.push(path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,376 @@
+//! Content store integrity verification.
+//!
+//! Files under `documents_dir` are addressed by their SHA-256 hash, with a
+//! BLAKE3 hash recorded alongside for collision resistance. Nothing re-checks
+//! them after they are written, so bit rot, partial copies and stray deletes
+//! go unnoticed. This module re-hashes stored files against the hashes in
+//! `document_versions`, restores content fetched from elsewhere, and finds
+//! files on disk that no version references.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::{ContentHashes, DocumentVersion};
+
+/// Read buffer size used when hashing files.
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Default checkpoint filename, stored in the data directory.
+pub const VERIFY_CHECKPOINT_FILENAME: &str = "storage_verify.progress";
+
+/// Result of checking a single stored file against its version record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    /// File exists and both hashes match.
+    Ok,
+    /// File does not exist at its resolved path.
+    Missing,
+    /// File exists but its size differs from the recorded size.
+    SizeMismatch { expected: u64, actual: u64 },
+    /// File exists but its content hash differs from the recorded hash.
+    Corrupted {
+        expected_sha256: String,
+        actual_sha256: String,
+    },
+}
+
+impl VerifyOutcome {
+    /// Whether the file is present and intact.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+
+    /// Short label used in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Missing => "missing",
+            Self::SizeMismatch { .. } => "size_mismatch",
+            Self::Corrupted { .. } => "corrupted",
+        }
+    }
+}
+
+/// Compute SHA-256 and BLAKE3 hashes of a file without loading it into memory.
+///
+/// Returns the hashes and the number of bytes read.
+pub fn hash_file(path: &Path) -> std::io::Result<(ContentHashes, u64)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut sha256 = Sha256::new();
+    let mut blake3 = blake3::Hasher::new();
+    let mut buf = vec![0u8; HASH_BUFFER_SIZE];
+    let mut total = 0u64;
+
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        sha256.update(&buf[..n]);
+        blake3.update(&buf[..n]);
+        total += n as u64;
+    }
+
+    Ok((
+        ContentHashes {
+            sha256: hex::encode(sha256.finalize()),
+            blake3: hex::encode(blake3.finalize().as_bytes()),
+        },
+        total,
+    ))
+}
+
+/// Verify a stored file against the hashes recorded for its version.
+///
+/// The size check runs first so truncated files are reported without
+/// hashing. BLAKE3 is only compared when the version has one recorded.
+pub fn verify_file(path: &Path, version: &DocumentVersion) -> std::io::Result<VerifyOutcome> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VerifyOutcome::Missing),
+        Err(e) => return Err(e),
+    };
+
+    if metadata.len() != version.file_size {
+        return Ok(VerifyOutcome::SizeMismatch {
+            expected: version.file_size,
+            actual: metadata.len(),
+        });
+    }
+
+    let (hashes, _) = hash_file(path)?;
+    if !version_matches(version, &hashes) {
+        return Ok(VerifyOutcome::Corrupted {
+            expected_sha256: version.content_hash.clone(),
+            actual_sha256: hashes.sha256,
+        });
+    }
+
+    Ok(VerifyOutcome::Ok)
+}
+
+/// Check whether computed hashes match a version's recorded hashes.
+pub fn version_matches(version: &DocumentVersion, hashes: &ContentHashes) -> bool {
+    if hashes.sha256 != version.content_hash {
+        return false;
+    }
+    match &version.content_hash_blake3 {
+        Some(expected) => *expected == hashes.blake3,
+        None => true,
+    }
+}
+
+/// Write recovered content to a version's storage path.
+///
+/// The content is only written if it hashes to the version's recorded
+/// hashes, so a changed upstream file can never overwrite the original.
+/// Writes go to a temporary sibling first and are renamed into place.
+///
+/// Returns `false` if the content does not match.
+pub fn restore_content(
+    path: &Path,
+    version: &DocumentVersion,
+    content: &[u8],
+) -> std::io::Result<bool> {
+    let hashes = DocumentVersion::compute_dual_hashes(content);
+    if content.len() as u64 != version.file_size || !version_matches(version, &hashes) {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("restore.tmp");
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(true)
+}
+
+/// Walk `documents_dir` and return files not present in `referenced`.
+///
+/// Hidden files and directories (names starting with `.`) are skipped, which
+/// keeps checkpoint files and quarantine areas out of the results. Returns
+/// `(path, size)` pairs sorted by path.
+pub fn find_orphans(
+    documents_dir: &Path,
+    referenced: &HashSet<PathBuf>,
+) -> std::io::Result<Vec<(PathBuf, u64)>> {
+    let mut orphans = Vec::new();
+    let mut stack = vec![documents_dir.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(e) => e,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if file_type.is_file() && !referenced.contains(&path) {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                orphans.push((path, size));
+            }
+        }
+    }
+
+    orphans.sort();
+    Ok(orphans)
+}
+
+/// Resumable position and running totals for a verification pass.
+///
+/// Versions are verified in ascending id order, so the last checked id is
+/// enough to resume an interrupted pass over a large store.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyCheckpoint {
+    /// Highest `document_versions.id` that has been verified.
+    pub last_version_id: i64,
+    /// Files checked so far in this pass.
+    pub checked: u64,
+    /// Files found missing so far.
+    pub missing: u64,
+    /// Files found corrupted (hash or size mismatch) so far.
+    pub corrupted: u64,
+    /// Files restored from their source or an archive so far.
+    pub repaired: u64,
+    /// When this pass started.
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the pass finished, if it did.
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl VerifyCheckpoint {
+    /// Checkpoint file for a pass over `source_id`, or over every source.
+    ///
+    /// Each source filter gets its own file, so a pass restricted to one
+    /// source never resumes from another's position.
+    pub fn path(data_dir: &Path, source_id: Option<&str>) -> PathBuf {
+        match source_id {
+            None => data_dir.join(VERIFY_CHECKPOINT_FILENAME),
+            Some(source_id) => {
+                let safe: String = source_id
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect();
+                data_dir.join(format!("storage_verify.{}.progress", safe))
+            }
+        }
+    }
+
+    /// Load a checkpoint, returning `None` if absent or unreadable.
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save the checkpoint.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, content)
+    }
+
+    /// Whether this checkpoint describes a finished pass.
+    pub fn is_complete(&self) -> bool {
+        self.completed_at.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn version_for(content: &[u8]) -> DocumentVersion {
+        DocumentVersion::new(content, "application/pdf".to_string(), None)
+    }
+
+    #[test]
+    fn test_hash_file_matches_in_memory_hashes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("doc.pdf");
+        let content = vec![7u8; HASH_BUFFER_SIZE * 2 + 13];
+        std::fs::write(&path, &content).unwrap();
+
+        let (hashes, size) = hash_file(&path).unwrap();
+        assert_eq!(hashes, DocumentVersion::compute_dual_hashes(&content));
+        assert_eq!(size, content.len() as u64);
+    }
+
+    #[test]
+    fn test_verify_file_ok() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("doc.pdf");
+        std::fs::write(&path, b"intact content").unwrap();
+
+        let outcome = verify_file(&path, &version_for(b"intact content")).unwrap();
+        assert_eq!(outcome, VerifyOutcome::Ok);
+    }
+
+    #[test]
+    fn test_verify_file_missing() {
+        let dir = tempdir().unwrap();
+        let outcome = verify_file(&dir.path().join("gone.pdf"), &version_for(b"x")).unwrap();
+        assert_eq!(outcome, VerifyOutcome::Missing);
+    }
+
+    #[test]
+    fn test_verify_file_size_mismatch() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("doc.pdf");
+        std::fs::write(&path, b"trunc").unwrap();
+
+        let outcome = verify_file(&path, &version_for(b"truncated content")).unwrap();
+        assert!(matches!(outcome, VerifyOutcome::SizeMismatch { .. }));
+    }
+
+    #[test]
+    fn test_verify_file_corrupted() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("doc.pdf");
+        std::fs::write(&path, b"flipped bitz").unwrap();
+
+        let outcome = verify_file(&path, &version_for(b"flipped bits")).unwrap();
+        assert_eq!(outcome.as_str(), "corrupted");
+    }
+
+    #[test]
+    fn test_restore_content_rejects_mismatched_content() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ab").join("doc.pdf");
+        let version = version_for(b"original");
+
+        assert!(!restore_content(&path, &version, b"modified").unwrap());
+        assert!(!path.exists());
+
+        assert!(restore_content(&path, &version, b"original").unwrap());
+        assert_eq!(std::fs::read(&path).unwrap(), b"original");
+    }
+
+    #[test]
+    fn test_find_orphans_skips_referenced_and_hidden() {
+        let dir = tempdir().unwrap();
+        let kept = dir.path().join("ab").join("kept-abcdef12.pdf");
+        let orphan = dir.path().join("cd").join("orphan-cdef1234.pdf");
+        let hidden = dir.path().join(".quarantine").join("old.pdf");
+        for p in [&kept, &orphan, &hidden] {
+            std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+            std::fs::write(p, b"data").unwrap();
+        }
+
+        let referenced: HashSet<PathBuf> = [kept.clone()].into_iter().collect();
+        let orphans = find_orphans(dir.path(), &referenced).unwrap();
+        assert_eq!(orphans, vec![(orphan, 4)]);
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(VERIFY_CHECKPOINT_FILENAME);
+        let checkpoint = VerifyCheckpoint {
+            last_version_id: 42,
+            checked: 40,
+            missing: 1,
+            ..Default::default()
+        };
+        checkpoint.save(&path).unwrap();
+
+        let loaded = VerifyCheckpoint::load(&path).unwrap();
+        assert_eq!(loaded.last_version_id, 42);
+        assert_eq!(loaded.missing, 1);
+        assert!(!loaded.is_complete());
+    }
+
+    #[test]
+    fn test_checkpoint_path_per_source() {
+        let dir = Path::new("/data");
+        let all = VerifyCheckpoint::path(dir, None);
+        let a = VerifyCheckpoint::path(dir, Some("fbi-vault"));
+        let b = VerifyCheckpoint::path(dir, Some("cia-reading-room"));
+        assert_eq!(all, dir.join(VERIFY_CHECKPOINT_FILENAME));
+        assert_ne!(a, all);
+        assert_ne!(a, b);
+        assert_eq!(
+            VerifyCheckpoint::path(dir, Some("../x")),
+            dir.join("storage_verify.___x.progress")
+        );
+    }
+}
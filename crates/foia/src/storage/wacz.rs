@@ -0,0 +1,496 @@
+//! WACZ package writing.
+//!
+//! A WACZ is a zip holding WARC data under `archive/`, a CDXJ index under
+//! `indexes/`, the captured pages in `pages/pages.jsonl` and a
+//! `datapackage.json` manifest with the SHA-256 of every file. Replay tools
+//! such as ReplayWeb.page use the index to read records straight out of the
+//! WARC entry, which is why it is stored uncompressed.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::warc::{new_record_id, warc_date, WarcRecord, WarcWriter};
+
+/// WACZ specification version written to `datapackage.json`.
+const WACZ_VERSION: &str = "1.1.1";
+
+/// Name of the WARC file inside `archive/`.
+pub const WACZ_WARC_NAME: &str = "data.warc";
+
+/// Compute the SURT key used to sort CDXJ index lines.
+///
+/// `https://www.Example.com/a/b?x=1` becomes `com,example)/a/b?x=1`.
+pub fn surt(url: &str) -> String {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return url.to_lowercase();
+    };
+
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let mut key = host.split('.').rev().collect::<Vec<_>>().join(",");
+    if let Some(port) = parsed.port() {
+        key.push_str(&format!(":{}", port));
+    }
+    key.push(')');
+    key.push_str(&parsed.path().to_lowercase());
+    if let Some(query) = parsed.query() {
+        key.push('?');
+        key.push_str(&query.to_lowercase());
+    }
+    key
+}
+
+/// Writer that hashes and counts the bytes passing through it.
+struct DigestWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A fetched document to add to a WACZ package.
+pub struct WaczCapture<'a> {
+    /// URL the content was fetched from.
+    pub url: &'a str,
+    /// When the content was fetched.
+    pub date: DateTime<Utc>,
+    /// MIME type of the content.
+    pub mime_type: &'a str,
+    /// Title listed in `pages.jsonl`.
+    pub title: &'a str,
+    /// Content bytes.
+    pub content: &'a [u8],
+    /// Hex SHA-256 of the content.
+    pub content_hash: &'a str,
+    /// The `response` record captured when the content was fetched, if
+    /// HTTP capture was enabled for the source.
+    pub response: Option<&'a WarcRecord>,
+    /// Provenance written as a `metadata` record alongside the capture.
+    pub provenance: serde_json::Value,
+}
+
+/// Counts from a finished package.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WaczSummary {
+    /// Number of captures written.
+    pub captures: usize,
+    /// Size of the WARC data in bytes.
+    pub warc_bytes: u64,
+}
+
+/// One CDXJ index line before sorting.
+struct CdxjLine {
+    key: String,
+    timestamp: String,
+    json: String,
+}
+
+/// A file entry in `datapackage.json`.
+#[derive(Serialize)]
+struct DataPackageResource {
+    name: String,
+    path: String,
+    hash: String,
+    bytes: u64,
+}
+
+impl DataPackageResource {
+    fn new(path: &str, hash: String, bytes: u64) -> Self {
+        Self {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.to_string(),
+            hash: format!("sha256:{}", hash),
+            bytes,
+        }
+    }
+}
+
+/// Streams captures into a WACZ package.
+///
+/// Content is written to the WARC entry as it is added; the index, page
+/// list and manifest are written by [`WaczWriter::finish`].
+pub struct WaczWriter {
+    warc: WarcWriter<DigestWriter<ZipWriter<File>>>,
+    title: String,
+    created: DateTime<Utc>,
+    index: Vec<CdxjLine>,
+    pages: Vec<String>,
+}
+
+impl WaczWriter {
+    /// Create a package at `path`, replacing any existing file.
+    pub fn create(path: &Path, title: &str) -> anyhow::Result<Self> {
+        let mut zip = ZipWriter::new(File::create(path)?);
+        zip.start_file(
+            format!("archive/{}", WACZ_WARC_NAME),
+            SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .large_file(true),
+        )?;
+
+        let mut warc = WarcWriter::new(DigestWriter {
+            inner: zip,
+            hasher: Sha256::new(),
+            bytes: 0,
+        });
+        warc.write_warcinfo(
+            WACZ_WARC_NAME,
+            &[
+                ("software", software()),
+                ("format", "WARC File Format 1.1".to_string()),
+                ("title", title.to_string()),
+            ],
+        )?;
+
+        Ok(Self {
+            warc,
+            title: title.to_string(),
+            created: Utc::now(),
+            index: Vec::new(),
+            pages: Vec::new(),
+        })
+    }
+
+    /// Add a capture followed by a `metadata` record holding its provenance.
+    ///
+    /// A captured `response` record is copied unchanged. Otherwise the
+    /// stored content is written as a `resource` record, since no HTTP
+    /// headers were kept for it.
+    pub fn add_capture(&mut self, capture: &WaczCapture) -> anyhow::Result<()> {
+        let digest = format!("sha256:{}", capture.content_hash);
+        let (record_id, captured_at, status, location) = match capture.response {
+            Some(response) => {
+                let record_id = response
+                    .header("WARC-Record-ID")
+                    .map(str::to_string)
+                    .unwrap_or_else(new_record_id);
+                let captured_at = response
+                    .header("WARC-Date")
+                    .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                    .map_or(capture.date, |d| d.with_timezone(&Utc));
+                // "HTTP/1.1 200 OK"
+                let status = response
+                    .block
+                    .split(|&b| b == b' ')
+                    .nth(1)
+                    .and_then(|s| std::str::from_utf8(s).ok())
+                    .map(str::to_string);
+                let headers: Vec<(&str, String)> = response
+                    .headers
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.clone()))
+                    .collect();
+                let location = self.warc.write_record(&headers, &response.block)?;
+                (record_id, captured_at, status, location)
+            }
+            None => {
+                let record_id = new_record_id();
+                let location = self.warc.write_record(
+                    &[
+                        ("WARC-Type", "resource".to_string()),
+                        ("WARC-Record-ID", record_id.clone()),
+                        ("WARC-Date", warc_date(capture.date)),
+                        ("WARC-Target-URI", capture.url.to_string()),
+                        ("WARC-Payload-Digest", digest.clone()),
+                        ("Content-Type", capture.mime_type.to_string()),
+                    ],
+                    capture.content,
+                )?;
+                (record_id, capture.date, None, location)
+            }
+        };
+        let date = warc_date(captured_at);
+
+        let provenance = serde_json::to_vec_pretty(&capture.provenance)?;
+        self.warc.write_record(
+            &[
+                ("WARC-Type", "metadata".to_string()),
+                ("WARC-Record-ID", new_record_id()),
+                ("WARC-Date", date.clone()),
+                ("WARC-Target-URI", capture.url.to_string()),
+                ("WARC-Concurrent-To", record_id),
+                ("Content-Type", "application/json".to_string()),
+            ],
+            &provenance,
+        )?;
+
+        let mut entry = serde_json::json!({
+            "url": capture.url,
+            "mime": capture.mime_type,
+            "digest": digest,
+            "length": location.length.to_string(),
+            "offset": location.offset.to_string(),
+            "filename": WACZ_WARC_NAME,
+        });
+        if let Some(status) = status {
+            entry["status"] = serde_json::Value::String(status);
+        }
+        self.index.push(CdxjLine {
+            key: surt(capture.url),
+            timestamp: captured_at.format("%Y%m%d%H%M%S").to_string(),
+            json: entry.to_string(),
+        });
+        self.pages.push(
+            serde_json::json!({
+                "id": uuid::Uuid::new_v4().to_string(),
+                "url": capture.url,
+                "ts": date,
+                "title": capture.title,
+            })
+            .to_string(),
+        );
+
+        Ok(())
+    }
+
+    /// Write the index, page list and manifest, and close the package.
+    pub fn finish(mut self) -> anyhow::Result<WaczSummary> {
+        let summary = WaczSummary {
+            captures: self.pages.len(),
+            warc_bytes: self.warc.position(),
+        };
+
+        self.warc.flush()?;
+        let DigestWriter {
+            inner: mut zip,
+            hasher,
+            bytes,
+        } = self.warc.into_inner();
+        let mut resources = vec![DataPackageResource::new(
+            &format!("archive/{}", WACZ_WARC_NAME),
+            hex::encode(hasher.finalize()),
+            bytes,
+        )];
+
+        self.index
+            .sort_by(|a, b| (&a.key, &a.timestamp).cmp(&(&b.key, &b.timestamp)));
+        let mut index = String::new();
+        for line in &self.index {
+            index.push_str(&format!("{} {} {}\n", line.key, line.timestamp, line.json));
+        }
+
+        let mut pages = serde_json::json!({
+            "format": "json-pages-1.0",
+            "id": "pages",
+            "title": self.title,
+        })
+        .to_string();
+        pages.push('\n');
+        for page in &self.pages {
+            pages.push_str(page);
+            pages.push('\n');
+        }
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (path, data) in [("indexes/index.cdxj", index), ("pages/pages.jsonl", pages)] {
+            zip.start_file(path, options)?;
+            zip.write_all(data.as_bytes())?;
+            resources.push(DataPackageResource::new(
+                path,
+                sha256_hex(data.as_bytes()),
+                data.len() as u64,
+            ));
+        }
+
+        let datapackage = serde_json::to_vec_pretty(&serde_json::json!({
+            "profile": "data-package",
+            "wacz_version": WACZ_VERSION,
+            "title": self.title,
+            "created": warc_date(self.created),
+            "software": software(),
+            "resources": resources,
+        }))?;
+        zip.start_file("datapackage.json", options)?;
+        zip.write_all(&datapackage)?;
+
+        let digest = serde_json::to_vec_pretty(&serde_json::json!({
+            "path": "datapackage.json",
+            "hash": format!("sha256:{}", sha256_hex(&datapackage)),
+        }))?;
+        zip.start_file("datapackage-digest.json", options)?;
+        zip.write_all(&digest)?;
+
+        zip.finish()?;
+        Ok(summary)
+    }
+}
+
+/// Software identifier recorded in the package.
+fn software() -> String {
+    format!("foia {}", env!("CARGO_PKG_VERSION"))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Seek, SeekFrom};
+
+    use super::*;
+    use crate::storage::warc::http_response_block;
+
+    #[test]
+    fn test_surt() {
+        assert_eq!(
+            surt("https://www.Example.com/Reports/a.pdf?Year=2024"),
+            "com,example)/reports/a.pdf?year=2024"
+        );
+        assert_eq!(
+            surt("http://foia.agency.gov:8080/"),
+            "gov,agency,foia:8080)/"
+        );
+    }
+
+    #[test]
+    fn test_write_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.wacz");
+
+        let content = b"%PDF-1.4 test";
+        let mut writer = WaczWriter::create(&path, "Test capture").unwrap();
+        writer
+            .add_capture(&WaczCapture {
+                url: "https://agency.gov/reports/a.pdf",
+                date: Utc::now(),
+                mime_type: "application/pdf",
+                title: "a.pdf",
+                content,
+                content_hash: &sha256_hex(content),
+                response: None,
+                provenance: serde_json::json!({ "document_id": "doc-1" }),
+            })
+            .unwrap();
+        let summary = writer.finish().unwrap();
+        assert_eq!(summary.captures, 1);
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut index = String::new();
+        archive
+            .by_name("indexes/index.cdxj")
+            .unwrap()
+            .read_to_string(&mut index)
+            .unwrap();
+        let (key, rest) = index.trim().split_once(' ').unwrap();
+        assert_eq!(key, "gov,agency)/reports/a.pdf");
+        let json: serde_json::Value =
+            serde_json::from_str(rest.split_once(' ').unwrap().1).unwrap();
+        let offset: u64 = json["offset"].as_str().unwrap().parse().unwrap();
+        let length: u64 = json["length"].as_str().unwrap().parse().unwrap();
+
+        // The indexed offset points at the response record in the stored WARC
+        let warc = archive.by_name("archive/data.warc").unwrap();
+        assert_eq!(warc.compression(), CompressionMethod::Stored);
+        let data_start = warc.data_start();
+        drop(warc);
+
+        let mut file = File::open(&path).unwrap();
+        file.seek(SeekFrom::Start(data_start + offset)).unwrap();
+        let mut record = vec![0; length as usize];
+        file.read_exact(&mut record).unwrap();
+        let record = String::from_utf8_lossy(&record);
+        assert!(record.starts_with("WARC/1.1\r\nWARC-Type: resource\r\n"));
+        assert!(record.contains("WARC-Target-URI: https://agency.gov/reports/a.pdf\r\n"));
+        assert!(record.contains("Content-Type: application/pdf\r\n"));
+        assert!(record.ends_with("\r\n\r\n%PDF-1.4 test\r\n\r\n"));
+        assert!(json.get("status").is_none());
+
+        for name in [
+            "pages/pages.jsonl",
+            "datapackage.json",
+            "datapackage-digest.json",
+        ] {
+            assert!(archive.by_name(name).is_ok(), "missing {}", name);
+        }
+    }
+
+    #[test]
+    fn test_reuse_captured_response() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.wacz");
+
+        let content = b"<html>reading room</html>";
+        let block = http_response_block(
+            200,
+            &[
+                ("Content-Type", "text/html".to_string()),
+                ("Server", "agency".to_string()),
+            ],
+            content,
+        );
+        let response = WarcRecord {
+            headers: vec![
+                ("WARC-Type".to_string(), "response".to_string()),
+                (
+                    "WARC-Record-ID".to_string(),
+                    "<urn:uuid:captured>".to_string(),
+                ),
+                ("WARC-Date".to_string(), "2024-01-05T12:00:00Z".to_string()),
+                (
+                    "WARC-Target-URI".to_string(),
+                    "https://agency.gov/reading-room".to_string(),
+                ),
+            ],
+            block: block.clone(),
+        };
+
+        let mut writer = WaczWriter::create(&path, "Test capture").unwrap();
+        writer
+            .add_capture(&WaczCapture {
+                url: "https://agency.gov/reading-room",
+                date: Utc::now(),
+                mime_type: "text/html",
+                title: "Reading room",
+                content,
+                content_hash: &sha256_hex(content),
+                response: Some(&response),
+                provenance: serde_json::json!({ "document_id": "doc-1" }),
+            })
+            .unwrap();
+        writer.finish().unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut index = String::new();
+        archive
+            .by_name("indexes/index.cdxj")
+            .unwrap()
+            .read_to_string(&mut index)
+            .unwrap();
+        let mut fields = index.trim().splitn(3, ' ');
+        assert_eq!(fields.next(), Some("gov,agency)/reading-room"));
+        assert_eq!(fields.next(), Some("20240105120000"));
+        let json: serde_json::Value = serde_json::from_str(fields.next().unwrap()).unwrap();
+        assert_eq!(json["status"], "200");
+
+        let mut warc = Vec::new();
+        archive
+            .by_name("archive/data.warc")
+            .unwrap()
+            .read_to_end(&mut warc)
+            .unwrap();
+        let warc = String::from_utf8_lossy(&warc);
+        assert!(warc.contains("WARC-Record-ID: <urn:uuid:captured>\r\n"));
+        assert!(warc.contains("WARC-Concurrent-To: <urn:uuid:captured>\r\n"));
+        assert!(warc.contains(&*String::from_utf8_lossy(&block)));
+    }
+}
@@ -0,0 +1,509 @@
+//! WARC record writing.
+//!
+//! Writes uncompressed WARC/1.1 records so byte offsets stay meaningful for
+//! CDXJ indexes. [`ResponseIndex`] reads back the files written here so
+//! exports can reuse captured responses; other WARCs are read by the `warc`
+//! crate in the importers.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// WARC version line written at the start of every record.
+const WARC_VERSION: &str = "WARC/1.1";
+
+/// Generate a new `WARC-Record-ID` value.
+pub fn new_record_id() -> String {
+    format!("<urn:uuid:{}>", uuid::Uuid::new_v4())
+}
+
+/// Format a timestamp for the `WARC-Date` header.
+pub fn warc_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Build an HTTP/1.1 response message for the block of a `response` record.
+pub fn http_response_block(status: u16, headers: &[(&str, String)], body: &[u8]) -> Vec<u8> {
+    let reason = reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+    let mut block = format!("HTTP/1.1 {} {}\r\n", status, reason).into_bytes();
+    for (name, value) in headers {
+        block.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+    }
+    block.extend_from_slice(b"\r\n");
+    block.extend_from_slice(body);
+    block
+}
+
+/// Position of a written record within its WARC file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarcRecordLocation {
+    /// Byte offset of the record's version line.
+    pub offset: u64,
+    /// Total bytes of the record, including the trailing separator.
+    pub length: u64,
+}
+
+/// Writes WARC records to an underlying writer, tracking offsets.
+pub struct WarcWriter<W: Write> {
+    inner: W,
+    position: u64,
+}
+
+impl<W: Write> WarcWriter<W> {
+    /// Wrap a writer positioned at the start of a WARC file.
+    pub fn new(inner: W) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    /// Bytes written so far.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Write one record.
+    ///
+    /// `headers` should include `WARC-Type`, `WARC-Record-ID` and `WARC-Date`;
+    /// `Content-Length` is added from the block.
+    pub fn write_record(
+        &mut self,
+        headers: &[(&str, String)],
+        block: &[u8],
+    ) -> io::Result<WarcRecordLocation> {
+        let mut head = format!("{}\r\n", WARC_VERSION);
+        for (name, value) in headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", block.len()));
+
+        self.inner.write_all(head.as_bytes())?;
+        self.inner.write_all(block)?;
+        self.inner.write_all(b"\r\n\r\n")?;
+
+        let location = WarcRecordLocation {
+            offset: self.position,
+            length: (head.len() + block.len() + 4) as u64,
+        };
+        self.position += location.length;
+        Ok(location)
+    }
+
+    /// Write a `warcinfo` record describing the file.
+    pub fn write_warcinfo(
+        &mut self,
+        filename: &str,
+        fields: &[(&str, String)],
+    ) -> io::Result<WarcRecordLocation> {
+        let mut block = String::new();
+        for (name, value) in fields {
+            block.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        self.write_record(
+            &[
+                ("WARC-Type", "warcinfo".to_string()),
+                ("WARC-Record-ID", new_record_id()),
+                ("WARC-Date", warc_date(Utc::now())),
+                ("WARC-Filename", filename.to_string()),
+                ("Content-Type", "application/warc-fields".to_string()),
+            ],
+            block.as_bytes(),
+        )
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Writes WARC records across size-capped files in one directory.
+///
+/// Files are named `{prefix}-{timestamp}-{serial}.warc` and each starts with
+/// a `warcinfo` record. A new file is started once the current one reaches
+/// `max_bytes`; records passed to a single [`write_records`] call always land
+/// in the same file so a request stays next to its response.
+///
+/// [`write_records`]: RotatingWarcWriter::write_records
+pub struct RotatingWarcWriter {
+    dir: PathBuf,
+    prefix: String,
+    max_bytes: u64,
+    info: Vec<(&'static str, String)>,
+    serial: u32,
+    current: Option<(String, WarcWriter<BufWriter<File>>)>,
+}
+
+impl RotatingWarcWriter {
+    /// Create a writer; no file is opened until the first record is written.
+    pub fn new(dir: impl Into<PathBuf>, prefix: &str, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: prefix.to_string(),
+            max_bytes,
+            info: vec![
+                ("software", format!("foia/{}", env!("CARGO_PKG_VERSION"))),
+                ("format", "WARC File Format 1.1".to_string()),
+            ],
+            serial: 0,
+            current: None,
+        }
+    }
+
+    /// Add a field to the `warcinfo` record at the start of each file.
+    pub fn with_info(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.info.push((name, value.into()));
+        self
+    }
+
+    /// Name of the file currently being written, if any.
+    pub fn current_file(&self) -> Option<&str> {
+        self.current.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    /// Write a group of related records and flush them to disk.
+    ///
+    /// Returns the name of the file the records were written to.
+    pub fn write_records(&mut self, records: &[(&[(&str, String)], &[u8])]) -> io::Result<String> {
+        if let Some((_, writer)) = &self.current {
+            if writer.position() >= self.max_bytes {
+                self.close()?;
+            }
+        }
+        if self.current.is_none() {
+            self.open()?;
+        }
+
+        let (name, writer) = self.current.as_mut().expect("file opened above");
+        for (headers, block) in records {
+            writer.write_record(headers, block)?;
+        }
+        writer.flush()?;
+        Ok(name.clone())
+    }
+
+    /// Flush and close the current file.
+    pub fn close(&mut self) -> io::Result<()> {
+        if let Some((_, mut writer)) = self.current.take() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let timestamp = Utc::now().format("%Y%m%d%H%M%S");
+        loop {
+            self.serial += 1;
+            let name = format!("{}-{}-{:05}.warc", self.prefix, timestamp, self.serial);
+            let file = match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.dir.join(&name))
+            {
+                Ok(file) => file,
+                // Another process started a file in the same second
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            };
+
+            let mut writer = WarcWriter::new(BufWriter::new(file));
+            writer.write_warcinfo(&name, &self.info)?;
+            self.current = Some((name, writer));
+            return Ok(());
+        }
+    }
+}
+
+/// A record read back from an uncompressed WARC file.
+#[derive(Debug, Clone)]
+pub struct WarcRecord {
+    /// WARC headers in file order, without `Content-Length`.
+    pub headers: Vec<(String, String)>,
+    /// The record block.
+    pub block: Vec<u8>,
+}
+
+impl WarcRecord {
+    /// Look up a header by name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Read the next record's headers and block length, skipping the blank
+/// lines that separate records.
+fn read_record_head<R: BufRead>(
+    reader: &mut R,
+) -> io::Result<Option<(Vec<(String, String)>, u64)>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim_end().is_empty() {
+            break;
+        }
+    }
+    if !line.starts_with("WARC/") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("expected a WARC version line, found {:?}", line.trim_end()),
+        ));
+    }
+
+    let mut headers = Vec::new();
+    let mut length = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("Content-Length") {
+            length = value.parse::<u64>().ok();
+        } else {
+            headers.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "record has no Content-Length")
+    })?;
+    Ok(Some((headers, length)))
+}
+
+/// Read the next record from an uncompressed WARC file.
+pub fn read_record<R: BufRead>(reader: &mut R) -> io::Result<Option<WarcRecord>> {
+    let Some((headers, length)) = read_record_head(reader)? else {
+        return Ok(None);
+    };
+    let mut block = vec![0; length as usize];
+    reader.read_exact(&mut block)?;
+    Ok(Some(WarcRecord { headers, block }))
+}
+
+/// Locates captured `response` records by URL and payload digest.
+///
+/// Built by scanning the `.warc` files a [`RotatingWarcWriter`] produced.
+/// Only offsets are kept; blocks are read when a record is requested.
+#[derive(Debug, Default)]
+pub struct ResponseIndex {
+    records: HashMap<(String, String), (PathBuf, u64)>,
+}
+
+impl ResponseIndex {
+    /// Index every `response` record in the `.warc` files under `dir`.
+    ///
+    /// A missing directory gives an empty index. Records are keyed by their
+    /// target URI and, when the fetch was rewritten, by the original URI too.
+    pub fn scan(dir: &Path) -> io::Result<Self> {
+        let mut index = Self::default();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(index),
+            Err(e) => return Err(e),
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "warc"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let mut reader = BufReader::new(File::open(&path)?);
+            loop {
+                let offset = reader.stream_position()?;
+                let Some((headers, length)) = read_record_head(&mut reader)? else {
+                    break;
+                };
+                reader.seek_relative(length as i64)?;
+
+                let header = |name: &str| {
+                    headers
+                        .iter()
+                        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                        .map(|(_, v)| v.clone())
+                };
+                if header("WARC-Type").as_deref() != Some("response") {
+                    continue;
+                }
+                let Some(digest) = header("WARC-Payload-Digest") else {
+                    continue;
+                };
+                for url in [header("WARC-Target-URI"), header("Foia-Original-URI")]
+                    .into_iter()
+                    .flatten()
+                {
+                    // Later captures of the same content replace earlier ones
+                    index
+                        .records
+                        .insert((url, digest.clone()), (path.clone(), offset));
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Number of indexed URL and digest pairs.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether no responses were found.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Read the response captured for `url` with the given
+    /// `sha256:<hex>` payload digest, if there is one.
+    pub fn get(&self, url: &str, payload_digest: &str) -> io::Result<Option<WarcRecord>> {
+        let Some((path, offset)) = self
+            .records
+            .get(&(url.to_string(), payload_digest.to_string()))
+        else {
+            return Ok(None);
+        };
+        let mut reader = BufReader::new(File::open(path)?);
+        reader.seek(SeekFrom::Start(*offset))?;
+        read_record(&mut reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_record_offsets() {
+        let mut writer = WarcWriter::new(Vec::new());
+        let first = writer
+            .write_record(
+                &[
+                    ("WARC-Type", "resource".to_string()),
+                    ("WARC-Record-ID", new_record_id()),
+                ],
+                b"hello",
+            )
+            .unwrap();
+        let second = writer
+            .write_record(&[("WARC-Type", "resource".to_string())], b"")
+            .unwrap();
+
+        assert_eq!(first.offset, 0);
+        assert_eq!(second.offset, first.length);
+        assert_eq!(writer.position(), first.length + second.length);
+
+        let bytes = writer.into_inner();
+        let record = &bytes[..first.length as usize];
+        assert!(record.starts_with(b"WARC/1.1\r\nWARC-Type: resource\r\n"));
+        assert!(record.ends_with(b"Content-Length: 5\r\n\r\nhello\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_http_response_block() {
+        let block =
+            http_response_block(200, &[("Content-Type", "text/plain".to_string())], b"body");
+        assert_eq!(
+            block,
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\nbody".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_response_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RotatingWarcWriter::new(dir.path(), "test", 1024 * 1024);
+
+        let body = b"%PDF-1.4 captured";
+        let digest = "sha256:abc".to_string();
+        let block = http_response_block(200, &[("Content-Type", "application/pdf".into())], body);
+        let response = [
+            ("WARC-Type", "response".to_string()),
+            ("WARC-Record-ID", new_record_id()),
+            (
+                "WARC-Target-URI",
+                "https://cache.example.net/a.pdf".to_string(),
+            ),
+            ("WARC-Payload-Digest", digest.clone()),
+            ("Foia-Original-URI", "https://agency.gov/a.pdf".to_string()),
+        ];
+        let request = [("WARC-Type", "request".to_string())];
+        writer
+            .write_records(&[
+                (&request, b"GET /a.pdf HTTP/1.1\r\n\r\n"),
+                (&response, &block),
+            ])
+            .unwrap();
+        writer.close().unwrap();
+
+        let index = ResponseIndex::scan(dir.path()).unwrap();
+        assert_eq!(index.len(), 2);
+        let record = index
+            .get("https://agency.gov/a.pdf", &digest)
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.header("warc-type"), Some("response"));
+        assert_eq!(record.block, block);
+        assert!(index
+            .get("https://agency.gov/a.pdf", "sha256:other")
+            .unwrap()
+            .is_none());
+
+        let missing = ResponseIndex::scan(&dir.path().join("missing")).unwrap();
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_rotating_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RotatingWarcWriter::new(dir.path(), "test", 200);
+
+        let headers = [("WARC-Type", "resource".to_string())];
+        let block = vec![b'x'; 150];
+        let first = writer
+            .write_records(&[(&headers, &block), (&headers, &block)])
+            .unwrap();
+        let second = writer.write_records(&[(&headers, &block)]).unwrap();
+        writer.close().unwrap();
+
+        assert_ne!(first, second);
+        let mut files: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec![first.clone(), second]);
+
+        let bytes = fs::read(dir.path().join(&first)).unwrap();
+        assert!(bytes.starts_with(b"WARC/1.1\r\nWARC-Type: warcinfo\r\n"));
+        assert_eq!(
+            bytes
+                .windows(b"WARC-Type: resource".len())
+                .filter(|w| *w == b"WARC-Type: resource")
+                .count(),
+            2
+        );
+    }
+}
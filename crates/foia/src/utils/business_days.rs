@@ -0,0 +1,161 @@
+//! Business-day arithmetic for statutory deadlines.
+//!
+//! Federal FOIA deadlines count working days, skipping weekends and the
+//! federal holidays of 5 U.S.C. 6103. A holiday on a Saturday is observed
+//! the Friday before and one on a Sunday the Monday after.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// The `n`th (1-based) `weekday` of a month.
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u32) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+        .expect("every month has at least four of each weekday")
+}
+
+/// The last `weekday` of a month.
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5)
+        .unwrap_or_else(|| nth_weekday(year, month, weekday, 4))
+}
+
+/// Move a fixed-date holiday off the weekend.
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+/// Observed federal holidays for a year, in date order.
+///
+/// New Year's Day falling on a Saturday is observed on December 31 of the
+/// previous year, so that date appears in the previous year's list.
+pub fn federal_holidays(year: i32) -> Vec<NaiveDate> {
+    let fixed = |month, day| NaiveDate::from_ymd_opt(year, month, day).expect("valid date");
+
+    let mut holidays = vec![
+        observed(fixed(1, 1)),
+        nth_weekday(year, 1, Weekday::Mon, 3),
+        nth_weekday(year, 2, Weekday::Mon, 3),
+        last_weekday(year, 5, Weekday::Mon),
+        observed(fixed(7, 4)),
+        nth_weekday(year, 9, Weekday::Mon, 1),
+        nth_weekday(year, 10, Weekday::Mon, 2),
+        observed(fixed(11, 11)),
+        nth_weekday(year, 11, Weekday::Thu, 4),
+        observed(fixed(12, 25)),
+    ];
+    if year >= 2021 {
+        holidays.push(observed(fixed(6, 19)));
+    }
+    // Next New Year's Day, when observed on this year's December 31
+    let next_new_year = observed(NaiveDate::from_ymd_opt(year + 1, 1, 1).expect("valid date"));
+    if next_new_year.year() == year {
+        holidays.push(next_new_year);
+    }
+    holidays.retain(|d| d.year() == year);
+    holidays.sort();
+    holidays
+}
+
+/// Whether federal offices are open on `date`.
+pub fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+        && !federal_holidays(date.year()).contains(&date)
+}
+
+/// The date `days` business days after `start`.
+///
+/// `start` itself is not counted, matching how the FOIA clock starts the
+/// day after a request is received.
+pub fn add_business_days(start: NaiveDate, days: u32) -> NaiveDate {
+    let mut date = start;
+    let mut remaining = days;
+    while remaining > 0 {
+        date += Duration::days(1);
+        if is_business_day(date) {
+            remaining -= 1;
+        }
+    }
+    date
+}
+
+/// Business days after `start` up to and including `end`.
+///
+/// Negative when `end` is before `start`.
+pub fn business_days_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    if end < start {
+        return -business_days_between(end, start);
+    }
+    start
+        .iter_days()
+        .skip(1)
+        .take_while(|d| *d <= end)
+        .filter(|d| is_business_day(*d))
+        .count() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_federal_holidays() {
+        let holidays = federal_holidays(2024);
+        let expected = [
+            "2024-01-01",
+            "2024-01-15",
+            "2024-02-19",
+            "2024-05-27",
+            "2024-06-19",
+            "2024-07-04",
+            "2024-09-02",
+            "2024-10-14",
+            "2024-11-11",
+            "2024-11-28",
+            "2024-12-25",
+        ];
+        assert_eq!(holidays, expected.map(date).to_vec());
+
+        // 2022-01-01 was a Saturday, observed 2021-12-31
+        assert!(federal_holidays(2021).contains(&date("2021-12-31")));
+        assert!(!federal_holidays(2022).contains(&date("2021-12-31")));
+        // 2026-07-04 is a Saturday, observed Friday 2026-07-03
+        assert!(federal_holidays(2026).contains(&date("2026-07-03")));
+        // No Juneteenth before 2021
+        assert!(!federal_holidays(2020).contains(&date("2020-06-19")));
+    }
+
+    #[test]
+    fn test_add_business_days() {
+        // Filed Friday 2024-11-22: skips two weekends and Thanksgiving
+        assert_eq!(add_business_days(date("2024-11-22"), 5), date("2024-12-02"));
+        // 20 business days from 2024-12-16 skips Christmas and New Year's Day
+        assert_eq!(
+            add_business_days(date("2024-12-16"), 20),
+            date("2025-01-15")
+        );
+        assert_eq!(add_business_days(date("2024-03-01"), 0), date("2024-03-01"));
+    }
+
+    #[test]
+    fn test_business_days_between() {
+        assert_eq!(
+            business_days_between(date("2024-11-22"), date("2024-12-02")),
+            5
+        );
+        assert_eq!(
+            business_days_between(date("2024-12-02"), date("2024-11-22")),
+            -5
+        );
+        assert_eq!(
+            business_days_between(date("2024-06-03"), date("2024-06-03")),
+            0
+        );
+    }
+}
@@ -4,7 +4,9 @@
 //! - `html`: HTML escaping for safe rendering
 //! - `format`: Human-readable formatting (sizes, etc.)
 //! - `mime`: MIME type categorization and icons
+//! - `business_days`: Federal business-day deadline arithmetic
 
+pub mod business_days;
 mod format;
 mod mime;
 pub mod url_finder;